@@ -0,0 +1,97 @@
+/*
+ * C API for the `jwt-compact` library.
+ *
+ * Ownership rules:
+ *
+ * - Strings passed to the library are borrowed for the duration of the call and must be
+ *   nul-terminated UTF-8.
+ * - Strings returned via `char **out` parameters are owned by the caller and must be freed
+ *   with `jwt_string_free()`.
+ * - Key handles returned by `jwt_key_from_jwk()` must be freed with `jwt_key_free()`.
+ *   Handles are immutable and can be shared among threads.
+ * - The string returned by `jwt_last_error()` is owned by the library and is valid until
+ *   the next API call on the same thread.
+ *
+ * On failure, out parameters are not modified.
+ */
+
+#ifndef JWT_COMPACT_H
+#define JWT_COMPACT_H
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef enum jwt_status {
+    JWT_OK = 0,
+    JWT_INVALID_KEY = 1,
+    JWT_INVALID_INPUT = 2,
+    JWT_UNSUPPORTED_ALGORITHM = 3,
+    JWT_MALFORMED_TOKEN = 4,
+    JWT_INVALID_SIGNATURE = 5,
+    JWT_EXPIRED = 6,
+    JWT_NOT_MATURE = 7,
+    JWT_MISSING_CLAIM = 8,
+    JWT_CLAIM_MISMATCH = 9,
+    JWT_CREATION = 10,
+    JWT_KEY_NOT_FOUND = 11,
+    JWT_NULL_ARGUMENT = -1,
+    JWT_INVALID_UTF8 = -2,
+    JWT_PANIC = -3,
+} jwt_status;
+
+typedef struct jwt_key jwt_key;
+
+typedef struct jwt_creation_options {
+    /* Key ID (`kid`) to put into the header. May be NULL. */
+    const char *key_id;
+    /* Token type (`typ`) to put into the header. May be NULL. */
+    const char *token_type;
+    /* Token lifetime in seconds. If positive, `exp` and `iat` claims are set. */
+    int64_t expires_in;
+} jwt_creation_options;
+
+typedef struct jwt_validation_options {
+    /* Leeway for time-based checks in seconds. */
+    int64_t leeway;
+    /* If non-zero, the `exp` claim is required. */
+    int require_expiration;
+    /* Expected issuer (`iss`). May be NULL. */
+    const char *issuer;
+    /* Expected audience (`aud`). May be NULL. */
+    const char *audience;
+    /* Comma-separated list of allowed algorithms. May be NULL. */
+    const char *algorithms;
+} jwt_validation_options;
+
+const char *jwt_last_error(void);
+void jwt_string_free(char *s);
+
+jwt_status jwt_key_from_jwk(const char *jwk, jwt_key **out);
+void jwt_key_free(jwt_key *key);
+jwt_status jwt_key_to_public_jwk(const jwt_key *key, char **out);
+jwt_status jwt_key_thumbprint(const jwt_key *key, char **out);
+
+jwt_status jwt_create(
+    const jwt_key *key,
+    const char *alg,
+    const char *claims,
+    const jwt_creation_options *options,
+    char **out
+);
+
+void jwt_validation_options_init(jwt_validation_options *options);
+jwt_status jwt_validate(
+    const jwt_key *key,
+    const char *token,
+    const jwt_validation_options *options,
+    char **out
+);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* JWT_COMPACT_H */
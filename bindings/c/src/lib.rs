@@ -0,0 +1,385 @@
+//! C API for `jwt-compact`. See `include/jwt_compact.h` for the C declarations
+//! and the ownership rules.
+//!
+//! All functions return a [`JwtStatus`]; on failure, a human-readable error message
+//! can be retrieved with [`jwt_last_error()`]. Strings returned via out parameters
+//! are owned by the caller and must be freed with [`jwt_string_free()`]; keys must be freed
+//! with [`jwt_key_free()`]. Panics never cross the FFI boundary; they are reported
+//! as [`JwtStatus::Panic`].
+
+#![warn(missing_debug_implementations, missing_docs, bare_trait_objects)]
+#![warn(clippy::all, clippy::pedantic)]
+#![allow(clippy::must_use_candidate)]
+
+use std::{
+    cell::RefCell,
+    ffi::{c_char, c_int, CStr, CString},
+    panic::{self, AssertUnwindSafe},
+    ptr,
+};
+
+use jwt_compact_bindings::{
+    create_token, validate_token, CreationOptions, Error, ErrorKind, Key, ValidationOptions,
+};
+
+/// Status code returned by the API functions. Positive codes correspond to token / key errors,
+/// negative ones to API misuse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(C)]
+pub enum JwtStatus {
+    /// Operation has succeeded.
+    Ok = 0,
+    /// Key cannot be parsed, or cannot be used for the requested operation.
+    InvalidKey = 1,
+    /// Claims or options are malformed.
+    InvalidInput = 2,
+    /// Algorithm is unsupported, disallowed or doesn't match the key.
+    UnsupportedAlgorithm = 3,
+    /// Token cannot be parsed.
+    MalformedToken = 4,
+    /// Token signature is invalid.
+    InvalidSignature = 5,
+    /// Token has expired.
+    Expired = 6,
+    /// Token is not yet valid.
+    NotMature = 7,
+    /// Required claim is missing.
+    MissingClaim = 8,
+    /// Claim value doesn't match the validation options.
+    ClaimMismatch = 9,
+    /// Token cannot be created.
+    Creation = 10,
+    /// Key set contains no key matching the token.
+    KeyNotFound = 11,
+    /// Required pointer argument is null.
+    NullArgument = -1,
+    /// String argument is not valid UTF-8.
+    InvalidUtf8 = -2,
+    /// Internal error (a panic in the library code).
+    Panic = -3,
+}
+
+impl From<ErrorKind> for JwtStatus {
+    fn from(kind: ErrorKind) -> Self {
+        match kind {
+            ErrorKind::InvalidKey => Self::InvalidKey,
+            ErrorKind::InvalidInput => Self::InvalidInput,
+            ErrorKind::UnsupportedAlgorithm => Self::UnsupportedAlgorithm,
+            ErrorKind::MalformedToken => Self::MalformedToken,
+            ErrorKind::InvalidSignature => Self::InvalidSignature,
+            ErrorKind::Expired => Self::Expired,
+            ErrorKind::NotMature => Self::NotMature,
+            ErrorKind::MissingClaim => Self::MissingClaim,
+            ErrorKind::ClaimMismatch => Self::ClaimMismatch,
+            ErrorKind::Creation => Self::Creation,
+            ErrorKind::KeyNotFound => Self::KeyNotFound,
+        }
+    }
+}
+
+/// Opaque key handle.
+#[derive(Debug)]
+pub struct JwtKey(Key);
+
+/// Options for [`jwt_create()`].
+#[derive(Debug)]
+#[repr(C)]
+pub struct JwtCreationOptions {
+    /// Key ID (`kid`) to put into the header. May be null.
+    pub key_id: *const c_char,
+    /// Token type (`typ`) to put into the header. May be null.
+    pub token_type: *const c_char,
+    /// Token lifetime in seconds. If positive, `exp` and `iat` claims are set.
+    pub expires_in: i64,
+}
+
+/// Options for [`jwt_validate()`]. Should be initialized with [`jwt_validation_options_init()`].
+#[derive(Debug)]
+#[repr(C)]
+pub struct JwtValidationOptions {
+    /// Leeway for time-based checks in seconds.
+    pub leeway: i64,
+    /// If non-zero, the `exp` claim is required.
+    pub require_expiration: c_int,
+    /// Expected issuer (`iss`). May be null.
+    pub issuer: *const c_char,
+    /// Expected audience (`aud`). May be null.
+    pub audience: *const c_char,
+    /// Comma-separated list of allowed algorithms. If null, all algorithms compatible
+    /// with the key are allowed.
+    pub algorithms: *const c_char,
+}
+
+/// Internal error type combining API misuse and library errors.
+enum FfiError {
+    Status(JwtStatus, &'static str),
+    Library(Error),
+}
+
+impl From<Error> for FfiError {
+    fn from(err: Error) -> Self {
+        Self::Library(err)
+    }
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: &str) {
+    let message = CString::new(message.replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|last_error| *last_error.borrow_mut() = Some(message));
+}
+
+/// Runs `action` converting errors and panics into status codes.
+fn ffi_call(action: impl FnOnce() -> Result<(), FfiError>) -> JwtStatus {
+    LAST_ERROR.with(|last_error| *last_error.borrow_mut() = None);
+    match panic::catch_unwind(AssertUnwindSafe(action)) {
+        Ok(Ok(())) => JwtStatus::Ok,
+        Ok(Err(FfiError::Status(status, message))) => {
+            set_last_error(message);
+            status
+        }
+        Ok(Err(FfiError::Library(err))) => {
+            set_last_error(err.message());
+            err.kind().into()
+        }
+        Err(_) => {
+            set_last_error("internal error");
+            JwtStatus::Panic
+        }
+    }
+}
+
+/// Reads an optional C string.
+///
+/// # Safety
+///
+/// `ptr` must be null or point to a valid nul-terminated string.
+unsafe fn read_str<'a>(ptr: *const c_char) -> Result<Option<&'a str>, FfiError> {
+    if ptr.is_null() {
+        return Ok(None);
+    }
+    let s = CStr::from_ptr(ptr).to_str();
+    s.map(Some)
+        .map_err(|_| FfiError::Status(JwtStatus::InvalidUtf8, "string is not valid UTF-8"))
+}
+
+/// Reads a required C string.
+///
+/// # Safety
+///
+/// See [`read_str()`].
+unsafe fn read_required_str<'a>(ptr: *const c_char) -> Result<&'a str, FfiError> {
+    read_str(ptr)?.ok_or(FfiError::Status(
+        JwtStatus::NullArgument,
+        "required string is null",
+    ))
+}
+
+fn null_argument() -> FfiError {
+    FfiError::Status(JwtStatus::NullArgument, "required argument is null")
+}
+
+/// Writes an owned string to the `out` pointer.
+///
+/// # Safety
+///
+/// `out` must be a valid pointer.
+unsafe fn write_string(out: *mut *mut c_char, value: String) -> Result<(), FfiError> {
+    let value = CString::new(value)
+        .map_err(|_| FfiError::Status(JwtStatus::InvalidInput, "output contains nul byte"))?;
+    *out = value.into_raw();
+    Ok(())
+}
+
+/// Returns the message for the last error that occurred on the calling thread, or null
+/// if the last call has succeeded. The returned string is owned by the library and is valid
+/// until the next API call on the same thread.
+#[no_mangle]
+pub extern "C" fn jwt_last_error() -> *const c_char {
+    LAST_ERROR.with(|last_error| {
+        last_error
+            .borrow()
+            .as_ref()
+            .map_or(ptr::null(), |message| message.as_ptr())
+    })
+}
+
+/// Frees a string returned by the library. Does nothing if `s` is null.
+///
+/// # Safety
+///
+/// `s` must be null or a string returned by the library that was not freed before.
+#[no_mangle]
+pub unsafe extern "C" fn jwt_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+/// Parses a key from a JWK (`jwk`) and writes the key handle to `out`. The handle must be
+/// freed with [`jwt_key_free()`].
+///
+/// # Safety
+///
+/// `jwk` must be a valid nul-terminated string; `out` must be a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn jwt_key_from_jwk(jwk: *const c_char, out: *mut *mut JwtKey) -> JwtStatus {
+    ffi_call(|| {
+        if out.is_null() {
+            return Err(null_argument());
+        }
+        let key = Key::from_jwk(read_required_str(jwk)?)?;
+        *out = Box::into_raw(Box::new(JwtKey(key)));
+        Ok(())
+    })
+}
+
+/// Frees a key handle. Does nothing if `key` is null.
+///
+/// # Safety
+///
+/// `key` must be null or a handle returned by [`jwt_key_from_jwk()`] that was not freed before.
+#[no_mangle]
+pub unsafe extern "C" fn jwt_key_free(key: *mut JwtKey) {
+    if !key.is_null() {
+        drop(Box::from_raw(key));
+    }
+}
+
+/// Writes the JWK with the public parts of `key` to `out`.
+///
+/// # Safety
+///
+/// `key` must be a valid key handle; `out` must be a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn jwt_key_to_public_jwk(
+    key: *const JwtKey,
+    out: *mut *mut c_char,
+) -> JwtStatus {
+    ffi_call(|| {
+        let key = key.as_ref().ok_or_else(null_argument)?;
+        if out.is_null() {
+            return Err(null_argument());
+        }
+        write_string(out, key.0.to_public_jwk())
+    })
+}
+
+/// Writes the base64url-encoded SHA-256 thumbprint (RFC 7638) of `key` to `out`.
+///
+/// # Safety
+///
+/// `key` must be a valid key handle; `out` must be a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn jwt_key_thumbprint(
+    key: *const JwtKey,
+    out: *mut *mut c_char,
+) -> JwtStatus {
+    ffi_call(|| {
+        let key = key.as_ref().ok_or_else(null_argument)?;
+        if out.is_null() {
+            return Err(null_argument());
+        }
+        write_string(out, key.0.thumbprint())
+    })
+}
+
+/// Creates a token with the specified claims (a JSON object) signed by `key` with the algorithm
+/// named `alg` (e.g., `ES256`), and writes it to `out`.
+///
+/// # Safety
+///
+/// `key` must be a valid key handle; `alg` and `claims` must be valid nul-terminated strings;
+/// `options` must be null or point to valid options; `out` must be a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn jwt_create(
+    key: *const JwtKey,
+    alg: *const c_char,
+    claims: *const c_char,
+    options: *const JwtCreationOptions,
+    out: *mut *mut c_char,
+) -> JwtStatus {
+    ffi_call(|| {
+        let key = key.as_ref().ok_or_else(null_argument)?;
+        if out.is_null() {
+            return Err(null_argument());
+        }
+        let alg = read_required_str(alg)?;
+        let claims = serde_json::from_str(read_required_str(claims)?)
+            .map_err(|_| FfiError::Status(JwtStatus::InvalidInput, "claims are not valid JSON"))?;
+
+        let options = if let Some(options) = options.as_ref() {
+            CreationOptions {
+                key_id: read_str(options.key_id)?.map(str::to_owned),
+                token_type: read_str(options.token_type)?.map(str::to_owned),
+                expires_in: (options.expires_in > 0).then_some(options.expires_in),
+            }
+        } else {
+            CreationOptions::default()
+        };
+        let token = create_token(&key.0, alg, claims, &options)?;
+        write_string(out, token)
+    })
+}
+
+/// Initializes validation options with the default values: 60 seconds leeway, required `exp`
+/// claim, no issuer / audience checks, and all algorithms compatible with the key allowed.
+///
+/// # Safety
+///
+/// `options` must be a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn jwt_validation_options_init(options: *mut JwtValidationOptions) {
+    let defaults = ValidationOptions::default();
+    if let Some(options) = options.as_mut() {
+        *options = JwtValidationOptions {
+            leeway: defaults.leeway,
+            require_expiration: c_int::from(defaults.require_expiration),
+            issuer: ptr::null(),
+            audience: ptr::null(),
+            algorithms: ptr::null(),
+        };
+    }
+}
+
+/// Validates `token` using `key` and writes the token claims (as a JSON object) to `out`.
+/// If `options` is null, the default options are used.
+///
+/// # Safety
+///
+/// `key` must be a valid key handle; `token` must be a valid nul-terminated string;
+/// `options` must be null or point to valid options; `out` must be a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn jwt_validate(
+    key: *const JwtKey,
+    token: *const c_char,
+    options: *const JwtValidationOptions,
+    out: *mut *mut c_char,
+) -> JwtStatus {
+    ffi_call(|| {
+        let key = key.as_ref().ok_or_else(null_argument)?;
+        if out.is_null() {
+            return Err(null_argument());
+        }
+        let token = read_required_str(token)?;
+
+        let options = if let Some(options) = options.as_ref() {
+            let algorithms = read_str(options.algorithms)?.map_or_else(Vec::new, |algs| {
+                algs.split(',').map(|alg| alg.trim().to_owned()).collect()
+            });
+            ValidationOptions {
+                leeway: options.leeway,
+                require_expiration: options.require_expiration != 0,
+                issuer: read_str(options.issuer)?.map(str::to_owned),
+                audience: read_str(options.audience)?.map(str::to_owned),
+                algorithms,
+            }
+        } else {
+            ValidationOptions::default()
+        };
+        let claims = validate_token(&key.0, token, &options)?;
+        write_string(out, claims.to_string())
+    })
+}
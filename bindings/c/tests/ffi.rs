@@ -0,0 +1,138 @@
+//! Tests for the C API exercised via Rust FFI calls.
+
+use serde_json::Value;
+
+use std::{
+    ffi::{c_char, CStr, CString},
+    ptr,
+};
+
+use jwt_compact_capi::*;
+
+const HMAC_JWK: &str = r#"{"kty":"oct","k":"c2VjcmV0LXNlY3JldC1zZWNyZXQtc2VjcmV0LXNlY3JldA"}"#;
+
+fn c_string(s: &str) -> CString {
+    CString::new(s).unwrap()
+}
+
+unsafe fn take_string(ptr: *mut c_char) -> String {
+    let s = CStr::from_ptr(ptr).to_str().unwrap().to_owned();
+    jwt_string_free(ptr);
+    s
+}
+
+unsafe fn last_error() -> String {
+    CStr::from_ptr(jwt_last_error())
+        .to_str()
+        .unwrap()
+        .to_owned()
+}
+
+unsafe fn hmac_key() -> *mut JwtKey {
+    let jwk = c_string(HMAC_JWK);
+    let mut key = ptr::null_mut();
+    assert_eq!(jwt_key_from_jwk(jwk.as_ptr(), &mut key), JwtStatus::Ok);
+    assert!(jwt_last_error().is_null());
+    key
+}
+
+#[test]
+fn token_roundtrip() {
+    unsafe {
+        let key = hmac_key();
+        let alg = c_string("HS256");
+        let claims = c_string(r#"{"sub":"alice","aud":"api"}"#);
+        let kid = c_string("hmac");
+        let options = JwtCreationOptions {
+            key_id: kid.as_ptr(),
+            token_type: ptr::null(),
+            expires_in: 300,
+        };
+        let mut token = ptr::null_mut();
+        let status = jwt_create(key, alg.as_ptr(), claims.as_ptr(), &options, &mut token);
+        assert_eq!(status, JwtStatus::Ok);
+        let token = c_string(&take_string(token));
+
+        let mut options = std::mem::zeroed::<JwtValidationOptions>();
+        jwt_validation_options_init(&mut options);
+        assert_eq!(options.leeway, 60);
+        assert_eq!(options.require_expiration, 1);
+        let audience = c_string("api");
+        let algorithms = c_string("HS256, HS512");
+        options.audience = audience.as_ptr();
+        options.algorithms = algorithms.as_ptr();
+
+        let mut claims = ptr::null_mut();
+        let status = jwt_validate(key, token.as_ptr(), &options, &mut claims);
+        assert_eq!(status, JwtStatus::Ok);
+        let claims: Value = serde_json::from_str(&take_string(claims)).unwrap();
+        assert_eq!(claims["sub"], "alice");
+        assert!(claims["exp"].is_i64());
+
+        let wrong_audience = c_string("web");
+        options.audience = wrong_audience.as_ptr();
+        let mut claims = ptr::null_mut();
+        let status = jwt_validate(key, token.as_ptr(), &options, &mut claims);
+        assert_eq!(status, JwtStatus::ClaimMismatch);
+        assert!(claims.is_null());
+        assert!(last_error().contains("audience"));
+
+        jwt_key_free(key);
+    }
+}
+
+#[test]
+fn validation_errors() {
+    unsafe {
+        let key = hmac_key();
+        let mut claims = ptr::null_mut();
+        let token = c_string("not.a.token");
+        let status = jwt_validate(key, token.as_ptr(), ptr::null(), &mut claims);
+        assert_eq!(status, JwtStatus::MalformedToken);
+
+        let alg = c_string("HS256");
+        let token_claims = c_string(r#"{"exp":1000}"#);
+        let mut token = ptr::null_mut();
+        let status = jwt_create(
+            key,
+            alg.as_ptr(),
+            token_claims.as_ptr(),
+            ptr::null(),
+            &mut token,
+        );
+        assert_eq!(status, JwtStatus::Ok);
+        let token = c_string(&take_string(token));
+        let status = jwt_validate(key, token.as_ptr(), ptr::null(), &mut claims);
+        assert_eq!(status, JwtStatus::Expired);
+
+        let status = jwt_validate(key, ptr::null(), ptr::null(), &mut claims);
+        assert_eq!(status, JwtStatus::NullArgument);
+        jwt_key_free(key);
+    }
+}
+
+#[test]
+fn key_operations() {
+    unsafe {
+        let key = hmac_key();
+        let mut thumbprint = ptr::null_mut();
+        assert_eq!(jwt_key_thumbprint(key, &mut thumbprint), JwtStatus::Ok);
+        assert_eq!(take_string(thumbprint).len(), 43);
+
+        let mut jwk = ptr::null_mut();
+        assert_eq!(jwt_key_to_public_jwk(key, &mut jwk), JwtStatus::Ok);
+        let jwk: Value = serde_json::from_str(&take_string(jwk)).unwrap();
+        assert_eq!(jwk["kty"], "oct");
+        jwt_key_free(key);
+
+        let invalid_jwk = c_string(r#"{"kty":"RSA"}"#);
+        let mut key = ptr::null_mut();
+        let status = jwt_key_from_jwk(invalid_jwk.as_ptr(), &mut key);
+        assert_eq!(status, JwtStatus::InvalidKey);
+        assert!(key.is_null());
+
+        let invalid_utf8 = [0xff_u8, 0];
+        let status = jwt_key_from_jwk(invalid_utf8.as_ptr().cast(), &mut key);
+        assert_eq!(status, JwtStatus::InvalidUtf8);
+    }
+}
@@ -0,0 +1,823 @@
+//! Shared logic for `jwt-compact` bindings to other languages (C, Python, Node.js).
+//!
+//! Bindings operate on JSON claims and select the signing / verification algorithm dynamically
+//! based on the key type and the algorithm name. Validation rules (leeway, required claims,
+//! issuer and audience checks) are defined in this crate once, so that all bindings behave
+//! identically.
+
+#![no_std]
+#![warn(missing_debug_implementations, missing_docs, bare_trait_objects)]
+#![warn(clippy::all, clippy::pedantic)]
+#![allow(
+    clippy::missing_errors_doc,
+    clippy::missing_panics_doc,
+    clippy::must_use_candidate
+)]
+
+extern crate alloc;
+
+use base64ct::{Base64UrlUnpadded, Encoding};
+use chrono::Duration;
+use serde_json::{Map, Value};
+use sha2::Sha256;
+
+use alloc::{
+    borrow::ToOwned,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt;
+
+#[cfg(feature = "pem")]
+mod pem;
+
+use jwt_compact::{
+    alg::{Ed25519, Es256, Es256k, Hs256, Hs256Key, Hs384, Hs384Key, Hs512, Hs512Key, Rsa},
+    jwk::{JsonWebKey, KeyType},
+    Algorithm, AlgorithmExt, Claim, Claims, Header, TimeOptions, Token, UntrustedToken,
+    ValidationError,
+};
+
+type Es256SigningKey = <Es256 as Algorithm>::SigningKey;
+type Es256VerifyingKey = <Es256 as Algorithm>::VerifyingKey;
+type Es256kSigningKey = <Es256k as Algorithm>::SigningKey;
+type Es256kVerifyingKey = <Es256k as Algorithm>::VerifyingKey;
+type Ed25519SigningKey = <Ed25519 as Algorithm>::SigningKey;
+type Ed25519VerifyingKey = <Ed25519 as Algorithm>::VerifyingKey;
+type RsaSigningKey = <Rsa as Algorithm>::SigningKey;
+type RsaVerifyingKey = <Rsa as Algorithm>::VerifyingKey;
+
+/// Names of the supported algorithms.
+pub const ALGORITHMS: &[&str] = &[
+    "HS256", "HS384", "HS512", "RS256", "RS384", "RS512", "PS256", "PS384", "PS512", "ES256",
+    "ES256K", "EdDSA",
+];
+
+/// Kind of [`Error`]. Bindings map kinds to language-specific error types or codes,
+/// so the kinds must not be renumbered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(i32)]
+pub enum ErrorKind {
+    /// Key cannot be parsed, or cannot be used for the requested operation.
+    InvalidKey = 1,
+    /// Input claims or options are malformed.
+    InvalidInput = 2,
+    /// Algorithm is not supported, is not allowed by the validation options, or doesn't
+    /// match the key type.
+    UnsupportedAlgorithm = 3,
+    /// Token cannot be parsed.
+    MalformedToken = 4,
+    /// Token signature is invalid.
+    InvalidSignature = 5,
+    /// Token has expired.
+    Expired = 6,
+    /// Token is not yet valid as per the `nbf` claim.
+    NotMature = 7,
+    /// Claim required by the validation options is missing.
+    MissingClaim = 8,
+    /// Claim has a value not matching the validation options.
+    ClaimMismatch = 9,
+    /// Token cannot be created.
+    Creation = 10,
+    /// Key set contains no key matching the token.
+    KeyNotFound = 11,
+}
+
+impl ErrorKind {
+    /// Returns the stable string identifier of this kind, e.g. `invalid_signature`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::InvalidKey => "invalid_key",
+            Self::InvalidInput => "invalid_input",
+            Self::UnsupportedAlgorithm => "unsupported_algorithm",
+            Self::MalformedToken => "malformed_token",
+            Self::InvalidSignature => "invalid_signature",
+            Self::Expired => "expired",
+            Self::NotMature => "not_mature",
+            Self::MissingClaim => "missing_claim",
+            Self::ClaimMismatch => "claim_mismatch",
+            Self::Creation => "creation",
+            Self::KeyNotFound => "key_not_found",
+        }
+    }
+}
+
+/// Error produced by binding operations.
+#[derive(Debug, Clone)]
+pub struct Error {
+    kind: ErrorKind,
+    message: String,
+    claim: Option<&'static str>,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(&self.message)
+    }
+}
+
+impl Error {
+    fn new(kind: ErrorKind, message: impl fmt::Display) -> Self {
+        Self {
+            kind,
+            message: message.to_string(),
+            claim: None,
+        }
+    }
+
+    fn with_claim(mut self, claim: &'static str) -> Self {
+        self.claim = Some(claim);
+        self
+    }
+
+    /// Returns the error kind.
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    /// Returns the human-readable error message.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Returns the name of the claim that has caused this error (e.g., `exp` for expired tokens),
+    /// if applicable.
+    pub fn claim(&self) -> Option<&'static str> {
+        self.claim
+    }
+}
+
+impl From<ValidationError> for Error {
+    fn from(err: ValidationError) -> Self {
+        let (kind, claim) = match &err {
+            ValidationError::AlgorithmMismatch { .. } => (ErrorKind::UnsupportedAlgorithm, None),
+            ValidationError::MalformedClaims(_) => (ErrorKind::MalformedToken, None),
+            ValidationError::NoClaim(claim) => (ErrorKind::MissingClaim, claim_name(claim)),
+            ValidationError::Expired => (ErrorKind::Expired, Some("exp")),
+            ValidationError::NotMature => (ErrorKind::NotMature, Some("nbf")),
+            ValidationError::UnknownKey => (ErrorKind::KeyNotFound, None),
+            _ => (ErrorKind::InvalidSignature, None),
+        };
+        let err = Self::new(kind, err);
+        match claim {
+            Some(claim) => err.with_claim(claim),
+            None => err,
+        }
+    }
+}
+
+fn claim_name(claim: &Claim) -> Option<&'static str> {
+    match claim {
+        Claim::Expiration => Some("exp"),
+        Claim::NotBefore => Some("nbf"),
+        _ => None,
+    }
+}
+
+#[derive(Clone)]
+enum KeyInner {
+    Symmetric(Vec<u8>),
+    Rsa(Option<RsaSigningKey>, RsaVerifyingKey),
+    Es256(Option<Es256SigningKey>, Es256VerifyingKey),
+    Es256k(Option<Es256kSigningKey>, Es256kVerifyingKey),
+    Ed25519(Option<Ed25519SigningKey>, Ed25519VerifyingKey),
+}
+
+/// Signing or verifying key parsed from a JWK.
+#[derive(Clone)]
+pub struct Key {
+    inner: KeyInner,
+    jwk: String,
+}
+
+impl fmt::Debug for Key {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("Key")
+            .field("algorithms", &self.algorithms())
+            .field("is_signing_key", &self.is_signing_key())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Key {
+    /// Parses a key from its JWK presentation.
+    pub fn from_jwk(jwk: &str) -> Result<Self, Error> {
+        let parsed: JsonWebKey<'_> =
+            serde_json::from_str(jwk).map_err(|err| Error::new(ErrorKind::InvalidKey, err))?;
+        Self::from_parsed_jwk(&parsed)
+    }
+
+    fn from_parsed_jwk(jwk: &JsonWebKey<'_>) -> Result<Self, Error> {
+        let invalid_key = |err| Error::new(ErrorKind::InvalidKey, err);
+        let inner = match jwk {
+            JsonWebKey::Symmetric { secret } => KeyInner::Symmetric(secret.to_vec()),
+            JsonWebKey::Rsa { .. } => {
+                if jwk.is_signing_key() {
+                    let signing_key = RsaSigningKey::try_from(jwk).map_err(invalid_key)?;
+                    let verifying_key = signing_key.to_public_key();
+                    KeyInner::Rsa(Some(signing_key), verifying_key)
+                } else {
+                    KeyInner::Rsa(None, RsaVerifyingKey::try_from(jwk).map_err(invalid_key)?)
+                }
+            }
+            JsonWebKey::EllipticCurve { curve, .. } if curve == "P-256" => {
+                if jwk.is_signing_key() {
+                    let signing_key = Es256SigningKey::try_from(jwk).map_err(invalid_key)?;
+                    let verifying_key = *signing_key.verifying_key();
+                    KeyInner::Es256(Some(signing_key), verifying_key)
+                } else {
+                    KeyInner::Es256(None, Es256VerifyingKey::try_from(jwk).map_err(invalid_key)?)
+                }
+            }
+            JsonWebKey::EllipticCurve { curve, .. } if curve == "secp256k1" => {
+                if jwk.is_signing_key() {
+                    let signing_key = Es256kSigningKey::try_from(jwk).map_err(invalid_key)?;
+                    let verifying_key = *signing_key.verifying_key();
+                    KeyInner::Es256k(Some(signing_key), verifying_key)
+                } else {
+                    let verifying_key = Es256kVerifyingKey::try_from(jwk).map_err(invalid_key)?;
+                    KeyInner::Es256k(None, verifying_key)
+                }
+            }
+            JsonWebKey::KeyPair { curve, .. } if curve == "Ed25519" => {
+                if jwk.is_signing_key() {
+                    let signing_key = Ed25519SigningKey::try_from(jwk).map_err(invalid_key)?;
+                    let verifying_key = signing_key.public_key();
+                    KeyInner::Ed25519(Some(signing_key), verifying_key)
+                } else {
+                    let verifying_key = Ed25519VerifyingKey::try_from(jwk).map_err(invalid_key)?;
+                    KeyInner::Ed25519(None, verifying_key)
+                }
+            }
+            JsonWebKey::EllipticCurve { curve, .. } | JsonWebKey::KeyPair { curve, .. } => {
+                let message = format!("unsupported curve: {curve}");
+                return Err(Error::new(ErrorKind::InvalidKey, message));
+            }
+            _ => return Err(Error::new(ErrorKind::InvalidKey, "unsupported key type")),
+        };
+
+        let jwk =
+            serde_json::to_string(jwk).map_err(|err| Error::new(ErrorKind::InvalidKey, err))?;
+        Ok(Self { inner, jwk })
+    }
+
+    /// Returns names of algorithms that can be used with this key.
+    pub fn algorithms(&self) -> &'static [&'static str] {
+        match &self.inner {
+            KeyInner::Symmetric(_) => &ALGORITHMS[..3],
+            KeyInner::Rsa(..) => &ALGORITHMS[3..9],
+            KeyInner::Es256(..) => &ALGORITHMS[9..10],
+            KeyInner::Es256k(..) => &ALGORITHMS[10..11],
+            KeyInner::Ed25519(..) => &ALGORITHMS[11..],
+        }
+    }
+
+    /// Checks whether this key can be used for signing.
+    pub fn is_signing_key(&self) -> bool {
+        match &self.inner {
+            KeyInner::Symmetric(_) => true,
+            KeyInner::Rsa(signing_key, _) => signing_key.is_some(),
+            KeyInner::Es256(signing_key, _) => signing_key.is_some(),
+            KeyInner::Es256k(signing_key, _) => signing_key.is_some(),
+            KeyInner::Ed25519(signing_key, _) => signing_key.is_some(),
+        }
+    }
+
+    fn parsed_jwk(&self) -> JsonWebKey<'_> {
+        serde_json::from_str(&self.jwk).expect("JWK was serialized by this crate")
+    }
+
+    /// Returns the JWK presentation of the key with private parts removed. For symmetric keys,
+    /// the key itself is returned.
+    pub fn to_public_jwk(&self) -> String {
+        let jwk = self.parsed_jwk().to_verifying_key();
+        serde_json::to_string(&jwk).expect("cannot serialize JWK")
+    }
+
+    /// Returns the base64url-encoded SHA-256 [thumbprint](https://www.rfc-editor.org/rfc/rfc7638)
+    /// of the key.
+    pub fn thumbprint(&self) -> String {
+        Base64UrlUnpadded::encode_string(&self.parsed_jwk().thumbprint::<Sha256>())
+    }
+
+    /// Returns the type of the key.
+    pub fn key_type(&self) -> KeyType {
+        match &self.inner {
+            KeyInner::Symmetric(_) => KeyType::Symmetric,
+            KeyInner::Rsa(..) => KeyType::Rsa,
+            KeyInner::Es256(..) | KeyInner::Es256k(..) => KeyType::EllipticCurve,
+            KeyInner::Ed25519(..) => KeyType::KeyPair,
+        }
+    }
+}
+
+/// Options for token creation.
+#[derive(Debug, Clone, Default)]
+pub struct CreationOptions {
+    /// Key ID (`kid`) to put into the token header.
+    pub key_id: Option<String>,
+    /// Token type (`typ`) to put into the token header.
+    pub token_type: Option<String>,
+    /// Token lifetime in seconds. If set, the `exp` and `iat` claims are set accordingly,
+    /// overriding the values in the claims.
+    pub expires_in: Option<i64>,
+}
+
+/// Creates a token signed with `key` using the algorithm with the specified name.
+/// `claims` must be a JSON object; registered time claims (`exp`, `nbf`, `iat`)
+/// must be represented as UNIX timestamps.
+pub fn create_token(
+    key: &Key,
+    algorithm: &str,
+    claims: Value,
+    options: &CreationOptions,
+) -> Result<String, Error> {
+    let (header, claims) = prepare_token(claims, options)?;
+    let no_signing_key = || Error::new(ErrorKind::InvalidKey, "key cannot be used for signing");
+    match (&key.inner, algorithm) {
+        (KeyInner::Symmetric(secret), "HS256") => {
+            sign(&Hs256, &header, &claims, &Hs256Key::new(secret))
+        }
+        (KeyInner::Symmetric(secret), "HS384") => {
+            sign(&Hs384, &header, &claims, &Hs384Key::new(secret))
+        }
+        (KeyInner::Symmetric(secret), "HS512") => {
+            sign(&Hs512, &header, &claims, &Hs512Key::new(secret))
+        }
+        (KeyInner::Rsa(signing_key, _), _) if key.algorithms().contains(&algorithm) => {
+            let signing_key = signing_key.as_ref().ok_or_else(no_signing_key)?;
+            sign(&rsa(algorithm), &header, &claims, signing_key)
+        }
+        (KeyInner::Es256(signing_key, _), "ES256") => {
+            let signing_key = signing_key.as_ref().ok_or_else(no_signing_key)?;
+            sign(&Es256, &header, &claims, signing_key)
+        }
+        (KeyInner::Es256k(signing_key, _), "ES256K") => {
+            let signing_key = signing_key.as_ref().ok_or_else(no_signing_key)?;
+            sign(&<Es256k>::default(), &header, &claims, signing_key)
+        }
+        (KeyInner::Ed25519(signing_key, _), "EdDSA") => {
+            let signing_key = signing_key.as_ref().ok_or_else(no_signing_key)?;
+            sign(&Ed25519, &header, &claims, signing_key)
+        }
+        _ => Err(unsupported_algorithm(key, algorithm)),
+    }
+}
+
+/// Returns the signing input (base64url-encoded header and claims separated by a `.` char)
+/// of a token with the specified claims, for a signature produced outside this crate
+/// (e.g., by the Web Crypto API). The signature must then be attached using
+/// [`attach_signature()`]. Arguments have the same meaning as for [`create_token()`].
+pub fn signing_input(
+    algorithm: &str,
+    claims: Value,
+    options: &CreationOptions,
+) -> Result<String, Error> {
+    let (header, claims) = prepare_token(claims, options)?;
+    let signing_input = match algorithm {
+        "HS256" => unsigned(&Hs256, &header, &claims),
+        "HS384" => unsigned(&Hs384, &header, &claims),
+        "HS512" => unsigned(&Hs512, &header, &claims),
+        "RS256" | "RS384" | "RS512" | "PS256" | "PS384" | "PS512" => {
+            unsigned(&rsa(algorithm), &header, &claims)
+        }
+        "ES256" => unsigned(&Es256, &header, &claims),
+        "ES256K" => unsigned(&<Es256k>::default(), &header, &claims),
+        "EdDSA" => unsigned(&Ed25519, &header, &claims),
+        _ => {
+            let message = format!("unknown algorithm `{algorithm}`");
+            return Err(Error::new(ErrorKind::UnsupportedAlgorithm, message));
+        }
+    }?;
+    // Signing input consists of base64url chars and a `.`, so it is always valid UTF-8.
+    Ok(String::from_utf8(signing_input).expect("signing input is not UTF-8"))
+}
+
+/// Attaches a `signature` over the [signing input](signing_input()) to a token.
+/// The signature is not checked; a token with an invalid signature will fail validation.
+pub fn attach_signature(signing_input: &str, signature: &[u8]) -> String {
+    let signature = Base64UrlUnpadded::encode_string(signature);
+    format!("{signing_input}.{signature}")
+}
+
+fn prepare_token(
+    claims: Value,
+    options: &CreationOptions,
+) -> Result<(Header, Claims<Map<String, Value>>), Error> {
+    if !matches!(claims, Value::Object(_)) {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "claims must be an object",
+        ));
+    }
+    let mut claims: Claims<Map<String, Value>> =
+        serde_json::from_value(claims).map_err(|err| Error::new(ErrorKind::InvalidInput, err))?;
+    if let Some(expires_in) = options.expires_in {
+        let duration = Duration::try_seconds(expires_in)
+            .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "token lifetime is too large"))?;
+        claims = claims.set_duration_and_issuance(&TimeOptions::default(), duration);
+    }
+
+    let mut header = Header::empty();
+    if let Some(key_id) = &options.key_id {
+        header = header.with_key_id(key_id.clone());
+    }
+    if let Some(token_type) = &options.token_type {
+        header = header.with_token_type(token_type.clone());
+    }
+    Ok((header, claims))
+}
+
+fn rsa(algorithm: &str) -> Rsa {
+    algorithm
+        .parse()
+        .expect("algorithm is checked by the caller")
+}
+
+fn unsupported_algorithm(key: &Key, algorithm: &str) -> Error {
+    let message = format!(
+        "algorithm `{algorithm}` cannot be used with the key (supported: {})",
+        key.algorithms().join(", ")
+    );
+    Error::new(ErrorKind::UnsupportedAlgorithm, message)
+}
+
+fn sign<A: Algorithm>(
+    algorithm: &A,
+    header: &Header,
+    claims: &Claims<Map<String, Value>>,
+    signing_key: &A::SigningKey,
+) -> Result<String, Error> {
+    algorithm
+        .token(header, claims, signing_key)
+        .map(String::from)
+        .map_err(|err| Error::new(ErrorKind::Creation, err))
+}
+
+fn unsigned<A: Algorithm>(
+    algorithm: &A,
+    header: &Header,
+    claims: &Claims<Map<String, Value>>,
+) -> Result<Vec<u8>, Error> {
+    algorithm
+        .unsigned_token(header, claims)
+        .map(|token| token.signing_input().to_vec())
+        .map_err(|err| Error::new(ErrorKind::Creation, err))
+}
+
+/// Options for token validation.
+#[derive(Debug, Clone)]
+pub struct ValidationOptions {
+    /// Leeway for time-based checks in seconds. The default value is 60.
+    pub leeway: i64,
+    /// Require the `exp` claim to be present. Enabled by default.
+    pub require_expiration: bool,
+    /// Expected issuer (`iss` claim).
+    pub issuer: Option<String>,
+    /// Expected audience; the `aud` claim must be equal to this value or, if it's an array,
+    /// contain it.
+    pub audience: Option<String>,
+    /// Allowed algorithms. If empty, all algorithms compatible with the key are allowed.
+    pub algorithms: Vec<String>,
+}
+
+impl Default for ValidationOptions {
+    fn default() -> Self {
+        Self {
+            leeway: 60,
+            require_expiration: true,
+            issuer: None,
+            audience: None,
+            algorithms: Vec::new(),
+        }
+    }
+}
+
+/// Validates `token` with `key` and returns its claims as a JSON object.
+pub fn validate_token(key: &Key, token: &str, options: &ValidationOptions) -> Result<Value, Error> {
+    let token = parse_token(token, options)?;
+    validate_parsed_token(key, &token, options)
+}
+
+/// Validates `token` with a key from `key_set` selected as described in [`KeySet::resolve()`],
+/// and returns token claims as a JSON object.
+pub fn validate_token_with_key_set(
+    key_set: &KeySet,
+    token: &str,
+    options: &ValidationOptions,
+) -> Result<Value, Error> {
+    let token = parse_token(token, options)?;
+    let key = key_set.resolve_for(&token)?;
+    validate_parsed_token(key, &token, options)
+}
+
+fn parse_token<'a>(
+    token: &'a str,
+    options: &ValidationOptions,
+) -> Result<UntrustedToken<'a>, Error> {
+    let token =
+        UntrustedToken::new(token).map_err(|err| Error::new(ErrorKind::MalformedToken, err))?;
+    let algorithm = token.algorithm();
+    if !options.algorithms.is_empty() && !options.algorithms.iter().any(|alg| alg == algorithm) {
+        let message = format!("algorithm `{algorithm}` is not allowed");
+        return Err(Error::new(ErrorKind::UnsupportedAlgorithm, message));
+    }
+    Ok(token)
+}
+
+fn validate_parsed_token(
+    key: &Key,
+    token: &UntrustedToken<'_>,
+    options: &ValidationOptions,
+) -> Result<Value, Error> {
+    let algorithm = token.algorithm();
+    let token = match (&key.inner, algorithm) {
+        (KeyInner::Symmetric(secret), "HS256") => verify(&Hs256, token, &Hs256Key::new(secret)),
+        (KeyInner::Symmetric(secret), "HS384") => verify(&Hs384, token, &Hs384Key::new(secret)),
+        (KeyInner::Symmetric(secret), "HS512") => verify(&Hs512, token, &Hs512Key::new(secret)),
+        (KeyInner::Rsa(_, verifying_key), _) if key.algorithms().contains(&algorithm) => {
+            verify(&rsa(algorithm), token, verifying_key)
+        }
+        (KeyInner::Es256(_, verifying_key), "ES256") => verify(&Es256, token, verifying_key),
+        (KeyInner::Es256k(_, verifying_key), "ES256K") => {
+            verify(&<Es256k>::default(), token, verifying_key)
+        }
+        (KeyInner::Ed25519(_, verifying_key), "EdDSA") => verify(&Ed25519, token, verifying_key),
+        _ => Err(unsupported_algorithm(key, algorithm)),
+    }?;
+
+    validate_claims(token.claims(), options)
+}
+
+fn validate_claims(
+    claims: &Claims<Map<String, Value>>,
+    options: &ValidationOptions,
+) -> Result<Value, Error> {
+    let leeway = Duration::try_seconds(options.leeway)
+        .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "leeway is too large"))?;
+    let time_options = TimeOptions::from_leeway(leeway);
+    if options.require_expiration || claims.expiration.is_some() {
+        claims.validate_expiration(&time_options)?;
+    }
+    if claims.not_before.is_some() {
+        claims.validate_maturity(&time_options)?;
+    }
+    if let Some(issuer) = &options.issuer {
+        match claims.custom.get("iss") {
+            None => {
+                let err = Error::new(ErrorKind::MissingClaim, "missing `iss` claim");
+                return Err(err.with_claim("iss"));
+            }
+            Some(Value::String(iss)) if iss == issuer => { /* OK */ }
+            Some(_) => {
+                let err = Error::new(ErrorKind::ClaimMismatch, "unexpected issuer");
+                return Err(err.with_claim("iss"));
+            }
+        }
+    }
+    if let Some(audience) = &options.audience {
+        let matches = match claims.custom.get("aud") {
+            None => {
+                let err = Error::new(ErrorKind::MissingClaim, "missing `aud` claim");
+                return Err(err.with_claim("aud"));
+            }
+            Some(Value::String(aud)) => aud == audience,
+            Some(Value::Array(aud)) => aud.iter().any(|aud| aud.as_str() == Some(audience)),
+            Some(_) => false,
+        };
+        if !matches {
+            let err = Error::new(ErrorKind::ClaimMismatch, "unexpected audience");
+            return Err(err.with_claim("aud"));
+        }
+    }
+
+    serde_json::to_value(claims).map_err(|err| Error::new(ErrorKind::MalformedToken, err))
+}
+
+/// Token parsed by [`parse_for_external_verification()`], the signature of which is verified
+/// outside this crate (e.g., by the Web Crypto API).
+#[derive(Debug)]
+pub struct ExternalVerification {
+    token: UntrustedToken<'static>,
+    signed_data: String,
+}
+
+/// Parses `token` so that its signature can be verified outside this crate. Allowed algorithms
+/// are checked according to `options`.
+pub fn parse_for_external_verification(
+    token: &str,
+    options: &ValidationOptions,
+) -> Result<ExternalVerification, Error> {
+    let parsed = parse_token(token, options)?.into_owned();
+    if let Some(extension) = parsed.critical_extensions().first() {
+        return Err(ValidationError::UnknownCriticalExtension(extension.clone()).into());
+    }
+    // The token is successfully parsed, so it necessarily contains a `.` char.
+    let (signed_data, _) = token.rsplit_once('.').unwrap_or_default();
+    Ok(ExternalVerification {
+        token: parsed,
+        signed_data: signed_data.to_owned(),
+    })
+}
+
+impl ExternalVerification {
+    /// Returns the algorithm specified in the token header.
+    pub fn algorithm(&self) -> &str {
+        self.token.algorithm()
+    }
+
+    /// Returns the data covered by the token signature.
+    pub fn signed_data(&self) -> &[u8] {
+        self.signed_data.as_bytes()
+    }
+
+    /// Returns the token signature.
+    pub fn signature(&self) -> &[u8] {
+        self.token.signature_bytes()
+    }
+
+    /// Completes token validation given the result of the signature verification, and returns
+    /// token claims as a JSON object.
+    pub fn finish(self, is_valid: bool, options: &ValidationOptions) -> Result<Value, Error> {
+        if !is_valid {
+            return Err(ValidationError::InvalidSignature.into());
+        }
+        let claims = self
+            .token
+            .deserialize_claims_unverified::<Map<String, Value>>()?;
+        validate_claims(&claims, options)
+    }
+}
+
+fn verify<A: Algorithm>(
+    algorithm: &A,
+    token: &UntrustedToken<'_>,
+    verifying_key: &A::VerifyingKey,
+) -> Result<Token<Map<String, Value>>, Error> {
+    Ok(algorithm.validator(verifying_key).validate(token)?)
+}
+
+fn is_supported(jwk: &JsonWebKey<'_>) -> bool {
+    match jwk {
+        JsonWebKey::Symmetric { .. } | JsonWebKey::Rsa { .. } => true,
+        JsonWebKey::EllipticCurve { curve, .. } => curve == "P-256" || curve == "secp256k1",
+        JsonWebKey::KeyPair { curve, .. } => curve == "Ed25519",
+        _ => false,
+    }
+}
+
+/// Entry of a [`KeySet`].
+#[derive(Debug, Clone)]
+struct KeySetEntry {
+    key: Key,
+    key_id: Option<String>,
+    algorithm: Option<String>,
+}
+
+impl KeySetEntry {
+    fn supports(&self, algorithm: &str) -> bool {
+        self.key.algorithms().contains(&algorithm)
+            && self
+                .algorithm
+                .as_deref()
+                .map_or(true, |alg| alg == algorithm)
+    }
+}
+
+/// Set of verifying keys parsed from a [JWK set](https://www.rfc-editor.org/rfc/rfc7517#section-5),
+/// e.g., one published by an identity provider.
+#[derive(Debug, Clone)]
+pub struct KeySet {
+    entries: Vec<KeySetEntry>,
+}
+
+impl KeySet {
+    /// Parses a key set from its JSON presentation (an object with the `keys` array).
+    ///
+    /// As per RFC 7517, keys with unsupported types or curves are ignored, as well as keys
+    /// intended for encryption (`"use": "enc"`). Keys with a supported type that cannot
+    /// be parsed result in an error.
+    pub fn from_jwks(jwks: &str) -> Result<Self, Error> {
+        let invalid_key = |err| Error::new(ErrorKind::InvalidKey, err);
+        let jwks: Value = serde_json::from_str(jwks).map_err(invalid_key)?;
+        let Some(Value::Array(keys)) = jwks.get("keys") else {
+            return Err(Error::new(ErrorKind::InvalidKey, "missing `keys` array"));
+        };
+
+        let mut entries = Vec::with_capacity(keys.len());
+        for (i, key) in keys.iter().enumerate() {
+            if key.get("use").and_then(Value::as_str) == Some("enc") {
+                continue;
+            }
+            let key_type = key.get("kty").and_then(Value::as_str);
+            if !matches!(key_type, Some("oct" | "RSA" | "EC" | "OKP")) {
+                continue;
+            }
+            let parsed = serde_json::from_value::<JsonWebKey<'static>>(key.clone())
+                .map_err(|err| Error::new(ErrorKind::InvalidKey, err))
+                .and_then(|jwk| {
+                    if is_supported(&jwk) {
+                        Key::from_parsed_jwk(&jwk).map(Some)
+                    } else {
+                        Ok(None)
+                    }
+                });
+            let parsed = parsed.map_err(|err| {
+                let message = format!("cannot parse key #{i}: {err}");
+                Error::new(ErrorKind::InvalidKey, message)
+            })?;
+            if let Some(parsed) = parsed {
+                entries.push(KeySetEntry {
+                    key: parsed,
+                    key_id: key.get("kid").and_then(Value::as_str).map(str::to_owned),
+                    algorithm: key.get("alg").and_then(Value::as_str).map(str::to_owned),
+                });
+            }
+        }
+        Ok(Self { entries })
+    }
+
+    /// Returns the number of keys in this set.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Checks whether this set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterates over IDs of keys in this set. Keys without an ID are skipped.
+    pub fn key_ids(&self) -> impl Iterator<Item = &str> + '_ {
+        self.entries
+            .iter()
+            .filter_map(|entry| entry.key_id.as_deref())
+    }
+
+    /// Returns the key with the specified ID.
+    pub fn get(&self, key_id: &str) -> Option<&Key> {
+        self.entries
+            .iter()
+            .find(|entry| entry.key_id.as_deref() == Some(key_id))
+            .map(|entry| &entry.key)
+    }
+
+    /// Selects a key for verifying `token` without verifying the token.
+    ///
+    /// If the token header contains a key ID (`kid`), the key with this ID is selected.
+    /// Otherwise, the set must contain a single key compatible with the token algorithm.
+    /// In both cases, the key must support the token algorithm, and the `alg` field of the key
+    /// (if present) must match it.
+    pub fn resolve(&self, token: &str) -> Result<&Key, Error> {
+        let token =
+            UntrustedToken::new(token).map_err(|err| Error::new(ErrorKind::MalformedToken, err))?;
+        self.resolve_for(&token)
+    }
+
+    fn resolve_for(&self, token: &UntrustedToken<'_>) -> Result<&Key, Error> {
+        let algorithm = token.algorithm();
+        let mut candidates = self
+            .entries
+            .iter()
+            .filter(|entry| entry.supports(algorithm));
+        let entry = if let Some(key_id) = &token.header().key_id {
+            candidates
+                .find(|entry| entry.key_id.as_ref() == Some(key_id))
+                .ok_or_else(|| {
+                    let message = format!("no key with ID `{key_id}` supporting `{algorithm}`");
+                    Error::new(ErrorKind::KeyNotFound, message)
+                })?
+        } else {
+            let entry = candidates.next().ok_or_else(|| {
+                let message = format!("no key supporting `{algorithm}`");
+                Error::new(ErrorKind::KeyNotFound, message)
+            })?;
+            if candidates.next().is_some() {
+                let message = "token has no key ID, and multiple keys match its algorithm";
+                return Err(Error::new(ErrorKind::KeyNotFound, message));
+            }
+            entry
+        };
+        Ok(&entry.key)
+    }
+}
+
+/// Parses a token without validating it, returning its header and claims as JSON objects.
+/// This is useful to select a key based on the token header (e.g., `kid`).
+pub fn decode_unverified(token: &str) -> Result<(Value, Value), Error> {
+    let token =
+        UntrustedToken::new(token).map_err(|err| Error::new(ErrorKind::MalformedToken, err))?;
+    let mut header = serde_json::to_value(token.header())
+        .map_err(|err| Error::new(ErrorKind::MalformedToken, err))?;
+    if let Value::Object(header) = &mut header {
+        header.insert("alg".to_owned(), token.algorithm().into());
+    }
+    let claims = token
+        .deserialize_claims_unverified::<Map<String, Value>>()
+        .map_err(|err| Error::new(ErrorKind::MalformedToken, err))?;
+    let claims =
+        serde_json::to_value(claims).map_err(|err| Error::new(ErrorKind::MalformedToken, err))?;
+    Ok((header, claims))
+}
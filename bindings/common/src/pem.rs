@@ -0,0 +1,94 @@
+//! Parsing keys from PEM.
+
+use jwt_compact::jwk::JsonWebKey;
+use rsa::{
+    pkcs1::{DecodeRsaPrivateKey, DecodeRsaPublicKey},
+    pkcs8::{DecodePrivateKey, DecodePublicKey},
+    RsaPrivateKey, RsaPublicKey,
+};
+
+use alloc::format;
+
+use crate::{Error, ErrorKind, Key};
+
+impl Key {
+    /// Parses a key from PEM. The following formats are supported:
+    ///
+    /// - PKCS#8 private keys (`PRIVATE KEY`) and SPKI public keys (`PUBLIC KEY`)
+    ///   for RSA, P-256, secp256k1 and Ed25519 keys
+    /// - PKCS#1 RSA keys (`RSA PRIVATE KEY`, `RSA PUBLIC KEY`)
+    /// - SEC1 elliptic curve keys (`EC PRIVATE KEY`) for P-256 and secp256k1
+    pub fn from_pem(pem: &str) -> Result<Self, Error> {
+        let label = pem
+            .trim_start()
+            .strip_prefix("-----BEGIN ")
+            .and_then(|rest| rest.split_once("-----"))
+            .map(|(label, _)| label)
+            .ok_or_else(|| Error::new(ErrorKind::InvalidKey, "input is not PEM-encoded"))?;
+
+        let key = match label {
+            "PRIVATE KEY" => parse_pkcs8(pem),
+            "PUBLIC KEY" => parse_spki(pem),
+            "RSA PRIVATE KEY" => RsaPrivateKey::from_pkcs1_pem(pem)
+                .ok()
+                .map(|key| to_key(&key)),
+            "RSA PUBLIC KEY" => RsaPublicKey::from_pkcs1_pem(pem)
+                .ok()
+                .map(|key| to_key(&key)),
+            "EC PRIVATE KEY" => parse_sec1(pem),
+            _ => {
+                let message = format!("unsupported PEM label: {label}");
+                return Err(Error::new(ErrorKind::InvalidKey, message));
+            }
+        };
+        key.unwrap_or_else(|| {
+            let message = format!("cannot parse `{label}` PEM as a supported key");
+            Err(Error::new(ErrorKind::InvalidKey, message))
+        })
+    }
+}
+
+fn to_key<'a, K>(key: &'a K) -> Result<Key, Error>
+where
+    JsonWebKey<'a>: From<&'a K>,
+{
+    Key::from_parsed_jwk(&JsonWebKey::from(key))
+}
+
+fn parse_pkcs8(pem: &str) -> Option<Result<Key, Error>> {
+    if let Ok(key) = RsaPrivateKey::from_pkcs8_pem(pem) {
+        Some(to_key(&key))
+    } else if let Ok(key) = p256::ecdsa::SigningKey::from_pkcs8_pem(pem) {
+        Some(to_key(&key))
+    } else if let Ok(key) = k256::ecdsa::SigningKey::from_pkcs8_pem(pem) {
+        Some(to_key(&key))
+    } else if let Ok(key) = ed25519_compact::SecretKey::from_pem(pem) {
+        Some(to_key(&key))
+    } else {
+        None
+    }
+}
+
+fn parse_spki(pem: &str) -> Option<Result<Key, Error>> {
+    if let Ok(key) = RsaPublicKey::from_public_key_pem(pem) {
+        Some(to_key(&key))
+    } else if let Ok(key) = p256::ecdsa::VerifyingKey::from_public_key_pem(pem) {
+        Some(to_key(&key))
+    } else if let Ok(key) = k256::ecdsa::VerifyingKey::from_public_key_pem(pem) {
+        Some(to_key(&key))
+    } else if let Ok(key) = ed25519_compact::PublicKey::from_pem(pem) {
+        Some(to_key(&key))
+    } else {
+        None
+    }
+}
+
+fn parse_sec1(pem: &str) -> Option<Result<Key, Error>> {
+    if let Ok(key) = p256::SecretKey::from_sec1_pem(pem) {
+        Some(to_key(&p256::ecdsa::SigningKey::from(key)))
+    } else if let Ok(key) = k256::SecretKey::from_sec1_pem(pem) {
+        Some(to_key(&k256::ecdsa::SigningKey::from(key)))
+    } else {
+        None
+    }
+}
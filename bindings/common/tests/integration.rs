@@ -0,0 +1,274 @@
+//! Integration tests for shared bindings logic.
+
+use assert_matches::assert_matches;
+use rand::thread_rng;
+use serde_json::{json, Value};
+
+use jwt_compact::jwk::JsonWebKey;
+use jwt_compact_bindings::{
+    attach_signature, create_token, decode_unverified, parse_for_external_verification,
+    signing_input, validate_token, validate_token_with_key_set, CreationOptions, ErrorKind, Key,
+    KeySet, ValidationOptions,
+};
+
+const HMAC_JWK: &str = r#"{"kty":"oct","k":"c2VjcmV0LXNlY3JldC1zZWNyZXQtc2VjcmV0LXNlY3JldA"}"#;
+
+fn es256_keys() -> (Key, Key) {
+    let signing_key = p256::ecdsa::SigningKey::random(&mut thread_rng());
+    let jwk = serde_json::to_string(&JsonWebKey::from(&signing_key)).unwrap();
+    let signing_key = Key::from_jwk(&jwk).unwrap();
+    let verifying_key = Key::from_jwk(&signing_key.to_public_jwk()).unwrap();
+    (signing_key, verifying_key)
+}
+
+fn expiring() -> CreationOptions {
+    CreationOptions {
+        expires_in: Some(300),
+        ..CreationOptions::default()
+    }
+}
+
+#[test]
+fn hmac_token_roundtrip() {
+    let key = Key::from_jwk(HMAC_JWK).unwrap();
+    assert_eq!(key.algorithms(), ["HS256", "HS384", "HS512"]);
+    let options = CreationOptions {
+        key_id: Some("hmac".to_owned()),
+        ..expiring()
+    };
+    let token = create_token(&key, "HS384", json!({ "sub": "alice" }), &options).unwrap();
+
+    let claims = validate_token(&key, &token, &ValidationOptions::default()).unwrap();
+    assert_eq!(claims["sub"], "alice");
+    assert!(claims["exp"].is_i64());
+    assert!(claims["iat"].is_i64());
+
+    let (header, _) = decode_unverified(&token).unwrap();
+    assert_eq!(header["alg"], "HS384");
+    assert_eq!(header["kid"], "hmac");
+}
+
+#[test]
+fn es256_token_roundtrip() {
+    let (signing_key, verifying_key) = es256_keys();
+    assert!(signing_key.is_signing_key());
+    assert!(!verifying_key.is_signing_key());
+    assert_eq!(signing_key.thumbprint(), verifying_key.thumbprint());
+
+    let token = create_token(&signing_key, "ES256", json!({}), &expiring()).unwrap();
+    validate_token(&verifying_key, &token, &ValidationOptions::default()).unwrap();
+
+    let err = create_token(&verifying_key, "ES256", json!({}), &expiring()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidKey);
+    let err = create_token(&signing_key, "HS256", json!({}), &expiring()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnsupportedAlgorithm);
+}
+
+#[test]
+fn time_claims_are_validated() {
+    let key = Key::from_jwk(HMAC_JWK).unwrap();
+    let token = create_token(
+        &key,
+        "HS256",
+        json!({ "sub": "alice" }),
+        &Default::default(),
+    );
+    let err = validate_token(&key, &token.unwrap(), &ValidationOptions::default()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::MissingClaim);
+    assert_eq!(err.claim(), Some("exp"));
+
+    let claims = json!({ "exp": 1_000_000 });
+    let token = create_token(&key, "HS256", claims, &Default::default()).unwrap();
+    let err = validate_token(&key, &token, &ValidationOptions::default()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Expired);
+    assert_eq!(err.claim(), Some("exp"));
+
+    let options = ValidationOptions {
+        leeway: i64::from(u32::MAX),
+        ..ValidationOptions::default()
+    };
+    validate_token(&key, &token, &options).unwrap();
+}
+
+#[test]
+fn issuer_and_audience_are_validated() {
+    let key = Key::from_jwk(HMAC_JWK).unwrap();
+    let claims = json!({ "iss": "https://issuer.example", "aud": ["api", "web"] });
+    let token = create_token(&key, "HS256", claims, &expiring()).unwrap();
+
+    let options = ValidationOptions {
+        issuer: Some("https://issuer.example".to_owned()),
+        audience: Some("web".to_owned()),
+        ..ValidationOptions::default()
+    };
+    let claims = validate_token(&key, &token, &options).unwrap();
+    assert_eq!(claims["aud"], json!(["api", "web"]));
+
+    let options = ValidationOptions {
+        audience: Some("mobile".to_owned()),
+        ..ValidationOptions::default()
+    };
+    let err = validate_token(&key, &token, &options).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::ClaimMismatch);
+    assert_eq!(err.claim(), Some("aud"));
+
+    let options = ValidationOptions {
+        issuer: Some("https://other.example".to_owned()),
+        ..ValidationOptions::default()
+    };
+    let err = validate_token(&key, &token, &options).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::ClaimMismatch);
+    assert_eq!(err.claim(), Some("iss"));
+}
+
+#[test]
+fn algorithms_can_be_restricted() {
+    let key = Key::from_jwk(HMAC_JWK).unwrap();
+    let token = create_token(&key, "HS512", json!({}), &expiring()).unwrap();
+    let options = ValidationOptions {
+        algorithms: vec!["HS256".to_owned()],
+        ..ValidationOptions::default()
+    };
+    let err = validate_token(&key, &token, &options).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnsupportedAlgorithm);
+
+    let (_, es256_key) = es256_keys();
+    let err = validate_token(&es256_key, &token, &ValidationOptions::default()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnsupportedAlgorithm);
+}
+
+#[test]
+fn invalid_inputs() {
+    let err = Key::from_jwk(r#"{"kty":"EC","crv":"P-384","x":"AA","y":"AA"}"#).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidKey);
+    assert_matches!(err.message(), msg if msg.contains("P-384"));
+
+    let key = Key::from_jwk(HMAC_JWK).unwrap();
+    let err = create_token(&key, "HS256", Value::from(1), &expiring()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    let err = validate_token(&key, "not.a.token", &ValidationOptions::default()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::MalformedToken);
+}
+
+#[test]
+fn key_set_resolution() {
+    let (first_signing_key, first_key) = es256_keys();
+    let (second_signing_key, second_key) = es256_keys();
+    let mut first_jwk: Value = serde_json::from_str(&first_key.to_public_jwk()).unwrap();
+    first_jwk["kid"] = "first".into();
+    first_jwk["use"] = "sig".into();
+    let mut second_jwk: Value = serde_json::from_str(&second_key.to_public_jwk()).unwrap();
+    second_jwk["kid"] = "second".into();
+    second_jwk["alg"] = "ES256".into();
+    let jwks = json!({
+        "keys": [
+            first_jwk,
+            second_jwk,
+            { "kty": "EC", "crv": "P-384", "x": "AA", "y": "AA", "kid": "unsupported" },
+            { "kty": "oct", "k": "c2VjcmV0", "use": "enc" },
+            { "kty": "unknown" },
+        ],
+    });
+    let key_set = KeySet::from_jwks(&jwks.to_string()).unwrap();
+    assert_eq!(key_set.len(), 2);
+    assert_eq!(key_set.key_ids().collect::<Vec<_>>(), ["first", "second"]);
+    assert!(key_set.get("unsupported").is_none());
+
+    let options = CreationOptions {
+        key_id: Some("second".to_owned()),
+        ..expiring()
+    };
+    let token = create_token(&second_signing_key, "ES256", json!({}), &options).unwrap();
+    let resolved = key_set.resolve(&token).unwrap();
+    assert_eq!(resolved.thumbprint(), second_key.thumbprint());
+    validate_token_with_key_set(&key_set, &token, &ValidationOptions::default()).unwrap();
+
+    // A token signed by a key with a mismatching ID.
+    let token = create_token(&first_signing_key, "ES256", json!({}), &options).unwrap();
+    let err =
+        validate_token_with_key_set(&key_set, &token, &ValidationOptions::default()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidSignature);
+
+    let options = CreationOptions {
+        key_id: Some("third".to_owned()),
+        ..expiring()
+    };
+    let token = create_token(&first_signing_key, "ES256", json!({}), &options).unwrap();
+    let err = key_set.resolve(&token).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::KeyNotFound);
+
+    // Without `kid`, the key is ambiguous.
+    let token = create_token(&first_signing_key, "ES256", json!({}), &expiring()).unwrap();
+    let err = key_set.resolve(&token).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::KeyNotFound);
+    assert_matches!(err.message(), msg if msg.contains("multiple keys"));
+
+    let hmac_key = Key::from_jwk(HMAC_JWK).unwrap();
+    let token = create_token(&hmac_key, "HS256", json!({}), &expiring()).unwrap();
+    let err = key_set.resolve(&token).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::KeyNotFound);
+}
+
+#[test]
+fn single_key_set_without_key_ids() {
+    let (signing_key, verifying_key) = es256_keys();
+    let jwks = format!(r#"{{"keys":[{}]}}"#, verifying_key.to_public_jwk());
+    let key_set = KeySet::from_jwks(&jwks).unwrap();
+    let token = create_token(
+        &signing_key,
+        "ES256",
+        json!({ "sub": "alice" }),
+        &expiring(),
+    )
+    .unwrap();
+    let claims =
+        validate_token_with_key_set(&key_set, &token, &ValidationOptions::default()).unwrap();
+    assert_eq!(claims["sub"], "alice");
+
+    let err = KeySet::from_jwks(r#"{"keys":[{"kty":"EC","crv":"P-256"}]}"#).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidKey);
+    let err = KeySet::from_jwks("{}").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidKey);
+}
+
+#[test]
+fn external_signing_and_verification() {
+    use p256::ecdsa::{signature::Signer, signature::Verifier, Signature};
+
+    let signing_key = p256::ecdsa::SigningKey::random(&mut thread_rng());
+    let jwk = serde_json::to_string(&JsonWebKey::from(signing_key.verifying_key())).unwrap();
+    let verifying_key = Key::from_jwk(&jwk).unwrap();
+
+    let claims = json!({ "sub": "alice" });
+    let input = signing_input("ES256", claims, &expiring()).unwrap();
+    let signature: Signature = signing_key.sign(input.as_bytes());
+    let token = attach_signature(&input, &signature.to_bytes());
+    let claims = validate_token(&verifying_key, &token, &ValidationOptions::default()).unwrap();
+    assert_eq!(claims["sub"], "alice");
+
+    let options = ValidationOptions::default();
+    let verification = parse_for_external_verification(&token, &options).unwrap();
+    assert_eq!(verification.algorithm(), "ES256");
+    assert_eq!(verification.signed_data(), input.as_bytes());
+    let signature = Signature::from_slice(verification.signature()).unwrap();
+    let is_valid = signing_key
+        .verifying_key()
+        .verify(verification.signed_data(), &signature)
+        .is_ok();
+    let claims = verification.finish(is_valid, &options).unwrap();
+    assert_eq!(claims["sub"], "alice");
+
+    let verification = parse_for_external_verification(&token, &options).unwrap();
+    let err = verification.finish(false, &options).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidSignature);
+
+    let options = ValidationOptions {
+        algorithms: vec!["EdDSA".to_owned()],
+        ..ValidationOptions::default()
+    };
+    let err = parse_for_external_verification(&token, &options).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnsupportedAlgorithm);
+
+    let err = signing_input("ES384", json!({}), &CreationOptions::default()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnsupportedAlgorithm);
+}
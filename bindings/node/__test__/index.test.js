@@ -0,0 +1,86 @@
+// Tests for the Node.js bindings. Require the native module to be built (`npm run build:debug`).
+
+const assert = require('node:assert/strict');
+const { test } = require('node:test');
+
+const {
+  Key, KeySet, createToken, decodeUnverified, verifyToken, verifyTokenWithKeySet,
+} = require('..');
+
+const HMAC_JWK = { kty: 'oct', k: 'c2VjcmV0LXNlY3JldC1zZWNyZXQtc2VjcmV0LXNlY3JldA' };
+// P-256 key from RFC 7515, Appendix A.3.
+const ES256_JWK = {
+  kty: 'EC',
+  crv: 'P-256',
+  x: 'f83OJ3D2xF1Bg8vub9tLe1gHMzV76e8Tus9uPHvRVEU',
+  y: 'x_FEzRu9m36HLN_tue659LNpXW6pCyStikYjKIWI5a0',
+  d: 'jpsQnnGQmL-YBIffH1136cspYG6-0iY7X1fCE9-E9LI',
+};
+
+test('HMAC token roundtrip', () => {
+  const key = Key.fromJwk(JSON.stringify(HMAC_JWK));
+  assert.deepEqual(key.algorithms, ['HS256', 'HS384', 'HS512']);
+  assert.ok(key.isSigningKey);
+
+  const token = createToken(key, 'HS256', { sub: 'alice', aud: ['api', 'web'] }, {
+    keyId: 'hmac',
+    expiresIn: 300,
+  });
+  const { header, claims } = decodeUnverified(token);
+  assert.equal(header.kid, 'hmac');
+  assert.equal(claims.sub, 'alice');
+
+  const verified = verifyToken(key, token, { audience: 'api', algorithms: ['HS256'] });
+  assert.equal(verified.sub, 'alice');
+  assert.equal(typeof verified.exp, 'number');
+
+  assert.throws(
+    () => verifyToken(key, token, { audience: 'mobile' }),
+    { code: 'claim_mismatch', message: /audience/ },
+  );
+});
+
+test('verification errors', () => {
+  const key = Key.fromJwk(HMAC_JWK);
+  assert.throws(() => verifyToken(key, 'not.a.token'), { code: 'malformed_token' });
+
+  const expired = createToken(key, 'HS256', { exp: 1000 });
+  assert.throws(() => verifyToken(key, expired), { code: 'expired' });
+  assert.throws(() => verifyToken(key, expired, { algorithms: ['ES256'] }), {
+    code: 'unsupported_algorithm',
+  });
+
+  const eternal = createToken(key, 'HS256', { sub: 'bob' });
+  assert.throws(() => verifyToken(key, eternal), { code: 'missing_claim' });
+  assert.deepEqual(verifyToken(key, eternal, { requireExpiration: false }), { sub: 'bob' });
+
+  assert.throws(() => Key.fromJwk({ kty: 'RSA' }), { code: 'invalid_key' });
+});
+
+test('key set resolution', () => {
+  const signingKey = Key.fromJwk(ES256_JWK);
+  const publicJwk = signingKey.toPublicJwk();
+  assert.equal(publicJwk.d, undefined);
+
+  const keySet = KeySet.fromJwks({
+    keys: [
+      { ...publicJwk, kid: 'es256', use: 'sig' },
+      { ...HMAC_JWK, kid: 'hmac' },
+      { kty: 'EC', crv: 'P-384', x: 'AA', y: 'AA', kid: 'unsupported' },
+    ],
+  });
+  assert.equal(keySet.size, 2);
+  assert.deepEqual(keySet.keyIds(), ['es256', 'hmac']);
+  assert.equal(keySet.get('es256').thumbprint(), signingKey.thumbprint());
+  assert.equal(keySet.get('unsupported'), null);
+
+  const token = createToken(signingKey, 'ES256', { sub: 'alice' }, {
+    keyId: 'es256',
+    expiresIn: 60,
+  });
+  assert.equal(keySet.resolve(token).thumbprint(), signingKey.thumbprint());
+  assert.equal(verifyTokenWithKeySet(keySet, token).sub, 'alice');
+
+  const unknown = createToken(signingKey, 'ES256', {}, { keyId: 'other', expiresIn: 60 });
+  assert.throws(() => verifyTokenWithKeySet(keySet, unknown), { code: 'key_not_found' });
+});
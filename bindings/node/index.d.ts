@@ -0,0 +1,98 @@
+/* Type definitions for the native module; regenerated by `napi build` from `src/lib.rs`. */
+
+/** Signing or verifying key parsed from a JWK. */
+export declare class Key {
+  /** Parses a key from a JWK, which can be supplied either as a string or as an object. */
+  static fromJwk(jwk: string | object): Key
+  /** Names of algorithms that can be used with this key. */
+  get algorithms(): Array<string>
+  /** Whether this key can be used for signing. */
+  get isSigningKey(): boolean
+  /** Returns the RFC 7638 thumbprint of the key (base64url-encoded SHA-256 digest). */
+  thumbprint(): string
+  /** Returns the JWK with the public parts of the key. */
+  toPublicJwk(): Record<string, unknown>
+}
+
+/** Set of verifying keys parsed from a JWK set, e.g., one published by an identity provider. */
+export declare class KeySet {
+  /**
+   * Parses a JWK set, which can be supplied either as a string or as an object.
+   * Keys with unsupported types and encryption keys are ignored.
+   */
+  static fromJwks(jwks: string | object): KeySet
+  /** Number of keys in this set. */
+  get size(): number
+  /** Returns IDs of keys in this set. Keys without an ID are skipped. */
+  keyIds(): Array<string>
+  /** Returns the key with the specified ID, or `null` if there is no such key. */
+  get(keyId: string): Key | null
+  /**
+   * Selects a key for verifying `token` based on its `kid` and `alg` header fields.
+   * The token is not verified.
+   */
+  resolve(token: string): Key
+}
+
+/** Options for token creation. */
+export interface CreationOptions {
+  /** Key ID (`kid`) to put into the token header. */
+  keyId?: string
+  /** Token type (`typ`) to put into the token header. */
+  tokenType?: string
+  /** Token lifetime in seconds. If set, the `exp` and `iat` claims are set accordingly. */
+  expiresIn?: number
+}
+
+/** Options for token verification. */
+export interface VerificationOptions {
+  /** Leeway for time-based checks in seconds. The default value is 60. */
+  leeway?: number
+  /** Require the `exp` claim to be present. Enabled by default. */
+  requireExpiration?: boolean
+  /** Expected issuer (`iss` claim). */
+  issuer?: string
+  /**
+   * Expected audience; the `aud` claim must be equal to this value or, if it's an array,
+   * contain it.
+   */
+  audience?: string
+  /** Allowed algorithms. If not specified, all algorithms compatible with the key are allowed. */
+  algorithms?: Array<string>
+}
+
+/** Header and claims of a token decoded without verification. */
+export interface DecodedToken {
+  /** Token header. */
+  header: Record<string, unknown>
+  /** Token claims. */
+  claims: Record<string, unknown>
+}
+
+/** Creates a token with the specified claims signed by `key`. */
+export declare function createToken(
+  key: Key,
+  algorithm: string,
+  claims: Record<string, unknown>,
+  options?: CreationOptions,
+): string
+
+/** Verifies `token` with `key` and returns its claims. */
+export declare function verifyToken(
+  key: Key,
+  token: string,
+  options?: VerificationOptions | undefined | null,
+): Record<string, unknown>
+
+/**
+ * Verifies `token` with a key from `keySet` selected based on the token header
+ * (see `KeySet.resolve()`), and returns token claims.
+ */
+export declare function verifyTokenWithKeySet(
+  keySet: KeySet,
+  token: string,
+  options?: VerificationOptions | undefined | null,
+): Record<string, unknown>
+
+/** Decodes `token` *without* verifying it. This is useful to inspect the header (e.g., `kid`). */
+export declare function decodeUnverified(token: string): DecodedToken
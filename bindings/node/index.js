@@ -0,0 +1,2 @@
+// Loads the native module built by `napi build` (see `package.json`).
+module.exports = require('./jwt-compact.node');
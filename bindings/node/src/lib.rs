@@ -0,0 +1,240 @@
+//! Node.js bindings for `jwt-compact` built with [napi-rs](https://napi.rs/).
+//!
+//! The bindings expose token creation / verification, JWK handling and key resolution
+//! from JWK sets. Validation follows the policy defined in `jwt-compact-bindings`, so it behaves
+//! identically to other bindings (e.g., regarding the leeway and `aud` handling).
+
+// `missing_docs` is not enabled since `#[napi]` generates undocumented public items
+#![warn(missing_debug_implementations, bare_trait_objects)]
+#![warn(clippy::all, clippy::pedantic)]
+// False positives for code generated by `#[napi]`
+#![allow(
+    clippy::missing_errors_doc,
+    clippy::missing_panics_doc,
+    clippy::must_use_candidate,
+    clippy::needless_pass_by_value
+)]
+
+use napi::{Error as NapiError, Result};
+use napi_derive::napi;
+use serde_json::Value;
+
+use jwt_compact_bindings as bindings;
+
+/// Error code exposed as the `code` property of JS errors (e.g., `"expired"`).
+#[derive(Debug, Clone, Copy)]
+pub struct ErrorCode(&'static str);
+
+impl AsRef<str> for ErrorCode {
+    fn as_ref(&self) -> &str {
+        self.0
+    }
+}
+
+fn to_napi_error(err: bindings::Error) -> NapiError<ErrorCode> {
+    NapiError::new(ErrorCode(err.kind().as_str()), err.message())
+}
+
+fn json_input(value: Value) -> String {
+    match value {
+        Value::String(s) => s,
+        other => other.to_string(),
+    }
+}
+
+/// Signing or verifying key parsed from a JWK.
+#[napi]
+#[derive(Debug)]
+pub struct Key(bindings::Key);
+
+#[napi]
+impl Key {
+    /// Parses a key from a JWK, which can be supplied either as a string or as an object.
+    #[napi(factory, ts_args_type = "jwk: string | object")]
+    pub fn from_jwk(jwk: Value) -> Result<Self, ErrorCode> {
+        bindings::Key::from_jwk(&json_input(jwk))
+            .map(Self)
+            .map_err(to_napi_error)
+    }
+
+    /// Names of algorithms that can be used with this key.
+    #[napi(getter)]
+    pub fn algorithms(&self) -> Vec<&'static str> {
+        self.0.algorithms().to_vec()
+    }
+
+    /// Whether this key can be used for signing.
+    #[napi(getter)]
+    pub fn is_signing_key(&self) -> bool {
+        self.0.is_signing_key()
+    }
+
+    /// Returns the RFC 7638 thumbprint of the key (base64url-encoded SHA-256 digest).
+    #[napi]
+    pub fn thumbprint(&self) -> String {
+        self.0.thumbprint()
+    }
+
+    /// Returns the JWK with the public parts of the key.
+    #[napi(ts_return_type = "Record<string, unknown>")]
+    pub fn to_public_jwk(&self) -> Value {
+        serde_json::from_str(&self.0.to_public_jwk()).expect("JWK is serialized by bindings")
+    }
+}
+
+/// Set of verifying keys parsed from a JWK set, e.g., one published by an identity provider.
+#[napi]
+#[derive(Debug)]
+pub struct KeySet(bindings::KeySet);
+
+#[napi]
+impl KeySet {
+    /// Parses a JWK set, which can be supplied either as a string or as an object.
+    /// Keys with unsupported types and encryption keys are ignored.
+    #[napi(factory, ts_args_type = "jwks: string | object")]
+    pub fn from_jwks(jwks: Value) -> Result<Self, ErrorCode> {
+        bindings::KeySet::from_jwks(&json_input(jwks))
+            .map(Self)
+            .map_err(to_napi_error)
+    }
+
+    /// Number of keys in this set.
+    #[napi(getter)]
+    pub fn size(&self) -> u32 {
+        u32::try_from(self.0.len()).unwrap_or(u32::MAX)
+    }
+
+    /// Returns IDs of keys in this set. Keys without an ID are skipped.
+    #[napi]
+    pub fn key_ids(&self) -> Vec<String> {
+        self.0.key_ids().map(str::to_owned).collect()
+    }
+
+    /// Returns the key with the specified ID, or `null` if there is no such key.
+    #[napi]
+    pub fn get(&self, key_id: String) -> Option<Key> {
+        self.0.get(&key_id).cloned().map(Key)
+    }
+
+    /// Selects a key for verifying `token` based on its `kid` and `alg` header fields.
+    /// The token is not verified.
+    #[napi]
+    pub fn resolve(&self, token: String) -> Result<Key, ErrorCode> {
+        self.0
+            .resolve(&token)
+            .cloned()
+            .map(Key)
+            .map_err(to_napi_error)
+    }
+}
+
+/// Options for token creation.
+#[napi(object)]
+#[derive(Debug, Default)]
+pub struct CreationOptions {
+    /// Key ID (`kid`) to put into the token header.
+    pub key_id: Option<String>,
+    /// Token type (`typ`) to put into the token header.
+    pub token_type: Option<String>,
+    /// Token lifetime in seconds. If set, the `exp` and `iat` claims are set accordingly.
+    pub expires_in: Option<i64>,
+}
+
+impl From<CreationOptions> for bindings::CreationOptions {
+    fn from(options: CreationOptions) -> Self {
+        Self {
+            key_id: options.key_id,
+            token_type: options.token_type,
+            expires_in: options.expires_in,
+        }
+    }
+}
+
+/// Options for token verification.
+#[napi(object)]
+#[derive(Debug, Default)]
+pub struct VerificationOptions {
+    /// Leeway for time-based checks in seconds. The default value is 60.
+    pub leeway: Option<i64>,
+    /// Require the `exp` claim to be present. Enabled by default.
+    pub require_expiration: Option<bool>,
+    /// Expected issuer (`iss` claim).
+    pub issuer: Option<String>,
+    /// Expected audience; the `aud` claim must be equal to this value or, if it's an array,
+    /// contain it.
+    pub audience: Option<String>,
+    /// Allowed algorithms. If not specified, all algorithms compatible with the key are allowed.
+    pub algorithms: Option<Vec<String>>,
+}
+
+impl From<VerificationOptions> for bindings::ValidationOptions {
+    fn from(options: VerificationOptions) -> Self {
+        let defaults = Self::default();
+        Self {
+            leeway: options.leeway.unwrap_or(defaults.leeway),
+            require_expiration: options
+                .require_expiration
+                .unwrap_or(defaults.require_expiration),
+            issuer: options.issuer,
+            audience: options.audience,
+            algorithms: options.algorithms.unwrap_or_default(),
+        }
+    }
+}
+
+/// Creates a token with the specified claims signed by `key`.
+#[napi(
+    ts_args_type = "key: Key, algorithm: string, claims: Record<string, unknown>, \
+                       options?: CreationOptions"
+)]
+pub fn create_token(
+    key: &Key,
+    algorithm: String,
+    claims: Value,
+    options: Option<CreationOptions>,
+) -> Result<String, ErrorCode> {
+    let options = options.unwrap_or_default().into();
+    bindings::create_token(&key.0, &algorithm, claims, &options).map_err(to_napi_error)
+}
+
+/// Verifies `token` with `key` and returns its claims.
+#[napi(ts_return_type = "Record<string, unknown>")]
+pub fn verify_token(
+    key: &Key,
+    token: String,
+    options: Option<VerificationOptions>,
+) -> Result<Value, ErrorCode> {
+    let options = options.unwrap_or_default().into();
+    bindings::validate_token(&key.0, &token, &options).map_err(to_napi_error)
+}
+
+/// Verifies `token` with a key from `keySet` selected based on the token header
+/// (see `KeySet.resolve()`), and returns token claims.
+#[napi(ts_return_type = "Record<string, unknown>")]
+pub fn verify_token_with_key_set(
+    key_set: &KeySet,
+    token: String,
+    options: Option<VerificationOptions>,
+) -> Result<Value, ErrorCode> {
+    let options = options.unwrap_or_default().into();
+    bindings::validate_token_with_key_set(&key_set.0, &token, &options).map_err(to_napi_error)
+}
+
+/// Header and claims of a token decoded without verification.
+#[napi(object)]
+#[derive(Debug)]
+pub struct DecodedToken {
+    /// Token header.
+    #[napi(ts_type = "Record<string, unknown>")]
+    pub header: Value,
+    /// Token claims.
+    #[napi(ts_type = "Record<string, unknown>")]
+    pub claims: Value,
+}
+
+/// Decodes `token` *without* verifying it. This is useful to inspect the header (e.g., `kid`).
+#[napi]
+pub fn decode_unverified(token: String) -> Result<DecodedToken, ErrorCode> {
+    let (header, claims) = bindings::decode_unverified(&token).map_err(to_napi_error)?;
+    Ok(DecodedToken { header, claims })
+}
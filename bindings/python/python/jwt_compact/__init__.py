@@ -0,0 +1,27 @@
+"""Python bindings for the `jwt-compact` Rust library."""
+
+from ._native import (
+    ClaimsError,
+    ExpiredTokenError,
+    ImmatureTokenError,
+    InvalidKeyError,
+    InvalidTokenError,
+    JwtError,
+    Key,
+    create_token,
+    decode_unverified,
+    validate_token,
+)
+
+__all__ = [
+    "ClaimsError",
+    "ExpiredTokenError",
+    "ImmatureTokenError",
+    "InvalidKeyError",
+    "InvalidTokenError",
+    "JwtError",
+    "Key",
+    "create_token",
+    "decode_unverified",
+    "validate_token",
+]
@@ -0,0 +1,231 @@
+//! Python bindings for `jwt-compact` built with [PyO3](https://pyo3.rs/).
+//!
+//! The bindings expose token creation / validation and JWK handling. Validation follows
+//! the policy defined in `jwt-compact-bindings`, so it behaves identically to other bindings
+//! (e.g., regarding the leeway and `aud` handling). Claims are converted to and from Python
+//! objects via JSON.
+
+#![warn(missing_debug_implementations, missing_docs, bare_trait_objects)]
+#![warn(clippy::all, clippy::pedantic)]
+// False positives for code generated by `#[pyfunction]` / `#[pymethods]`
+#![allow(clippy::needless_pass_by_value, clippy::useless_conversion)]
+
+use pyo3::{create_exception, exceptions::PyException, prelude::*, types::PyString};
+use serde_json::Value;
+
+use jwt_compact_bindings::{
+    create_token as create_token_inner, decode_unverified as decode_unverified_inner,
+    validate_token as validate_token_inner, CreationOptions, Error, ErrorKind, Key as KeyInner,
+    ValidationOptions,
+};
+
+create_exception!(
+    _native,
+    JwtError,
+    PyException,
+    "Base class for errors raised by this module."
+);
+create_exception!(
+    _native,
+    InvalidKeyError,
+    JwtError,
+    "Key cannot be parsed or used for the requested operation."
+);
+create_exception!(
+    _native,
+    InvalidTokenError,
+    JwtError,
+    "Token is malformed, has an invalid signature or uses a disallowed algorithm."
+);
+create_exception!(
+    _native,
+    ExpiredTokenError,
+    InvalidTokenError,
+    "Token has expired."
+);
+create_exception!(
+    _native,
+    ImmatureTokenError,
+    InvalidTokenError,
+    "Token is not yet valid as per the `nbf` claim."
+);
+create_exception!(
+    _native,
+    ClaimsError,
+    InvalidTokenError,
+    "Claim required by the validation options is missing or has an unexpected value."
+);
+
+fn to_py_err(err: Error) -> PyErr {
+    let message = (err.message().to_owned(), err.kind().as_str());
+    match err.kind() {
+        ErrorKind::InvalidKey | ErrorKind::KeyNotFound => InvalidKeyError::new_err(message),
+        ErrorKind::UnsupportedAlgorithm
+        | ErrorKind::MalformedToken
+        | ErrorKind::InvalidSignature => InvalidTokenError::new_err(message),
+        ErrorKind::Expired => ExpiredTokenError::new_err(message),
+        ErrorKind::NotMature => ImmatureTokenError::new_err(message),
+        ErrorKind::MissingClaim | ErrorKind::ClaimMismatch => ClaimsError::new_err(message),
+        ErrorKind::InvalidInput | ErrorKind::Creation => JwtError::new_err(message),
+    }
+}
+
+/// Converts a Python object to JSON using the `json` module.
+fn to_json(object: &Bound<'_, PyAny>) -> PyResult<Value> {
+    let json = object.py().import_bound("json")?;
+    let json_string: String = json.call_method1("dumps", (object,))?.extract()?;
+    serde_json::from_str(&json_string).map_err(|err| JwtError::new_err(err.to_string()))
+}
+
+/// Converts JSON to a Python object using the `json` module.
+fn from_json(py: Python<'_>, value: &Value) -> PyResult<PyObject> {
+    let json = py.import_bound("json")?;
+    Ok(json.call_method1("loads", (value.to_string(),))?.unbind())
+}
+
+/// Signing or verifying key parsed from a JWK.
+#[pyclass(frozen, module = "jwt_compact")]
+#[derive(Debug)]
+pub struct Key(KeyInner);
+
+#[pymethods]
+impl Key {
+    /// Parses a key from a JWK, which can be supplied either as a string or as a `dict`.
+    #[staticmethod]
+    fn from_jwk(jwk: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let key = if let Ok(jwk) = jwk.downcast::<PyString>() {
+            KeyInner::from_jwk(jwk.to_str()?)
+        } else {
+            KeyInner::from_jwk(&to_json(jwk)?.to_string())
+        };
+        key.map(Self).map_err(to_py_err)
+    }
+
+    /// Names of algorithms that can be used with this key.
+    #[getter]
+    fn algorithms(&self) -> Vec<&'static str> {
+        self.0.algorithms().to_vec()
+    }
+
+    /// Whether this key can be used for signing.
+    #[getter]
+    fn is_signing_key(&self) -> bool {
+        self.0.is_signing_key()
+    }
+
+    /// Returns the RFC 7638 thumbprint of the key (base64url-encoded SHA-256 digest).
+    fn thumbprint(&self) -> String {
+        self.0.thumbprint()
+    }
+
+    /// Returns the JWK with the public parts of the key as a `dict`.
+    fn to_public_jwk(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let jwk: Value = serde_json::from_str(&self.0.to_public_jwk())
+            .map_err(|err| JwtError::new_err(err.to_string()))?;
+        from_json(py, &jwk)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Key(algorithms={:?}, is_signing_key={})",
+            self.0.algorithms(),
+            self.0.is_signing_key()
+        )
+    }
+}
+
+/// Creates a token with the specified claims (a `dict`) signed by `key`.
+///
+/// If `expires_in` (seconds) is specified, `exp` and `iat` claims are set.
+#[pyfunction]
+#[pyo3(signature = (key, algorithm, claims, *, key_id = None, token_type = None, expires_in = None))]
+fn create_token(
+    key: &Key,
+    algorithm: &str,
+    claims: &Bound<'_, PyAny>,
+    key_id: Option<String>,
+    token_type: Option<String>,
+    expires_in: Option<i64>,
+) -> PyResult<String> {
+    let options = CreationOptions {
+        key_id,
+        token_type,
+        expires_in,
+    };
+    create_token_inner(&key.0, algorithm, to_json(claims)?, &options).map_err(to_py_err)
+}
+
+/// Validates `token` with `key` and returns its claims as a `dict`.
+///
+/// The `exp` claim is required unless `require_expiration` is false. If `audience`
+/// is specified, the `aud` claim must be equal to it or, if it's a list, contain it.
+#[pyfunction]
+#[pyo3(signature = (
+    key,
+    token,
+    *,
+    leeway = 60,
+    require_expiration = true,
+    issuer = None,
+    audience = None,
+    algorithms = None,
+))]
+#[allow(clippy::too_many_arguments)]
+fn validate_token(
+    py: Python<'_>,
+    key: &Key,
+    token: &str,
+    leeway: i64,
+    require_expiration: bool,
+    issuer: Option<String>,
+    audience: Option<String>,
+    algorithms: Option<Vec<String>>,
+) -> PyResult<PyObject> {
+    let options = ValidationOptions {
+        leeway,
+        require_expiration,
+        issuer,
+        audience,
+        algorithms: algorithms.unwrap_or_default(),
+    };
+    let claims = validate_token_inner(&key.0, token, &options).map_err(to_py_err)?;
+    from_json(py, &claims)
+}
+
+/// Decodes `token` *without* validating it, returning a `(header, claims)` tuple of `dict`s.
+/// This is useful to select a validation key based on the header (e.g., `kid`).
+#[pyfunction]
+fn decode_unverified(py: Python<'_>, token: &str) -> PyResult<(PyObject, PyObject)> {
+    let (header, claims) = decode_unverified_inner(token).map_err(to_py_err)?;
+    Ok((from_json(py, &header)?, from_json(py, &claims)?))
+}
+
+/// Native module; re-exported by the `jwt_compact` Python package.
+///
+/// # Errors
+///
+/// Propagates errors adding items to the module.
+#[pymodule]
+pub fn _native(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = module.py();
+    module.add_class::<Key>()?;
+    module.add_function(wrap_pyfunction!(create_token, module)?)?;
+    module.add_function(wrap_pyfunction!(validate_token, module)?)?;
+    module.add_function(wrap_pyfunction!(decode_unverified, module)?)?;
+    module.add("JwtError", py.get_type_bound::<JwtError>())?;
+    module.add("InvalidKeyError", py.get_type_bound::<InvalidKeyError>())?;
+    module.add(
+        "InvalidTokenError",
+        py.get_type_bound::<InvalidTokenError>(),
+    )?;
+    module.add(
+        "ExpiredTokenError",
+        py.get_type_bound::<ExpiredTokenError>(),
+    )?;
+    module.add(
+        "ImmatureTokenError",
+        py.get_type_bound::<ImmatureTokenError>(),
+    )?;
+    module.add("ClaimsError", py.get_type_bound::<ClaimsError>())?;
+    Ok(())
+}
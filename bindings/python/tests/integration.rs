@@ -0,0 +1,110 @@
+//! Tests for the Python bindings exercised via an embedded Python interpreter.
+
+use pyo3::{prelude::*, types::PyDict};
+
+use std::sync::Once;
+
+use jwt_compact_python::_native;
+
+const HMAC_JWK: &str = r#"{"kty":"oct","k":"c2VjcmV0LXNlY3JldC1zZWNyZXQtc2VjcmV0LXNlY3JldA"}"#;
+
+fn run_script(script: &str) {
+    static INIT: Once = Once::new();
+
+    INIT.call_once(|| {
+        pyo3::append_to_inittab!(_native);
+        pyo3::prepare_freethreaded_python();
+    });
+    Python::with_gil(|py| {
+        let globals = PyDict::new_bound(py);
+        globals.set_item("HMAC_JWK", HMAC_JWK).unwrap();
+        py.run_bound("import _native as jwt", Some(&globals), None)
+            .unwrap();
+        if let Err(err) = py.run_bound(script, Some(&globals), None) {
+            err.print(py);
+            panic!("Python script failed: {err}");
+        }
+    });
+}
+
+#[test]
+fn token_roundtrip() {
+    run_script(
+        r#"
+key = jwt.Key.from_jwk(HMAC_JWK)
+assert key.is_signing_key
+assert "HS256" in key.algorithms
+token = jwt.create_token(key, "HS256", {"sub": "alice", "aud": ["api", "web"]}, key_id="hmac", expires_in=300)
+
+header, claims = jwt.decode_unverified(token)
+assert header["kid"] == "hmac", header
+assert claims["sub"] == "alice"
+
+claims = jwt.validate_token(key, token, audience="api", algorithms=["HS256"])
+assert claims["sub"] == "alice"
+assert isinstance(claims["exp"], int)
+
+try:
+    jwt.validate_token(key, token, audience="mobile")
+    raise AssertionError("audience mismatch was not detected")
+except jwt.ClaimsError as err:
+    assert "audience" in str(err), err
+"#,
+    );
+}
+
+#[test]
+fn validation_errors() {
+    run_script(
+        r#"
+key = jwt.Key.from_jwk(HMAC_JWK)
+try:
+    jwt.validate_token(key, "not.a.token")
+    raise AssertionError("malformed token was accepted")
+except jwt.InvalidTokenError:
+    pass
+
+token = jwt.create_token(key, "HS256", {"exp": 1000})
+try:
+    jwt.validate_token(key, token)
+    raise AssertionError("expired token was accepted")
+except jwt.ExpiredTokenError as err:
+    assert isinstance(err, jwt.InvalidTokenError)
+    assert isinstance(err, jwt.JwtError)
+
+try:
+    jwt.validate_token(key, token, algorithms=["ES256"])
+    raise AssertionError("disallowed algorithm was accepted")
+except jwt.InvalidTokenError:
+    pass
+
+token = jwt.create_token(key, "HS256", {"sub": "bob"})
+try:
+    jwt.validate_token(key, token)
+    raise AssertionError("token without expiration was accepted")
+except jwt.ClaimsError:
+    pass
+claims = jwt.validate_token(key, token, require_expiration=False)
+assert claims == {"sub": "bob"}, claims
+"#,
+    );
+}
+
+#[test]
+fn key_operations() {
+    run_script(
+        r#"
+import json
+
+key = jwt.Key.from_jwk(json.loads(HMAC_JWK))
+assert len(key.thumbprint()) == 43
+assert key.to_public_jwk()["kty"] == "oct"
+
+try:
+    jwt.Key.from_jwk({"kty": "RSA"})
+    raise AssertionError("invalid key was accepted")
+except jwt.InvalidKeyError:
+    pass
+"#,
+    );
+}
@@ -0,0 +1,510 @@
+//! WASM bindings for `jwt-compact`.
+//!
+//! The bindings expose token creation / verification for all algorithms supported by
+//! `jwt-compact-bindings` with arbitrary JSON claims. Keys can be supplied as JWKs or in PEM.
+//! Alternatively, tokens can be signed and verified with Web Crypto API keys (`CryptoKey`s),
+//! which allows using non-extractable keys; in this case, cryptographic operations
+//! are delegated to `SubtleCrypto`.
+//! TypeScript definitions for the exported items are generated by `wasm-bindgen`.
+
+#![no_std]
+#![warn(missing_debug_implementations, missing_docs, bare_trait_objects)]
+#![warn(clippy::all, clippy::pedantic)]
+#![allow(clippy::missing_errors_doc, clippy::must_use_candidate)]
+// Triggered by the `wasm_bindgen` macro / required by its argument conventions
+#![allow(clippy::needless_pass_by_value, clippy::unused_unit)]
+
+extern crate alloc;
+
+use js_sys::{futures::future_to_promise, Reflect, Uint8Array};
+use serde_json::Value;
+use wasm_bindgen::{prelude::*, JsCast};
+
+use alloc::{
+    borrow::ToOwned,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use jwt_compact_bindings as bindings;
+
+#[wasm_bindgen(typescript_custom_section)]
+const TS_TYPES: &str = r#"
+/** Options for token creation. */
+export interface CreationOptions {
+  /** Key ID (`kid`) to put into the token header. */
+  keyId?: string;
+  /** Token type (`typ`) to put into the token header. */
+  tokenType?: string;
+  /** Token lifetime in seconds. If set, the `exp` and `iat` claims are set accordingly. */
+  expiresIn?: number;
+}
+
+/** Options for token verification. */
+export interface VerificationOptions {
+  /** Leeway for time-based checks in seconds. The default value is 60. */
+  leeway?: number;
+  /** Require the `exp` claim to be present. Enabled by default. */
+  requireExpiration?: boolean;
+  /** Expected issuer (`iss` claim). */
+  issuer?: string;
+  /** Expected audience; the `aud` claim must be equal to this value or, if it's an array, contain it. */
+  audience?: string;
+  /** Allowed algorithms. If not specified, all algorithms compatible with the key are allowed. */
+  algorithms?: string[];
+}
+
+/** Stable code of an error thrown by the library. */
+export type ErrorCode =
+  | 'ERR_INVALID_KEY'
+  | 'ERR_INVALID_INPUT'
+  | 'ERR_UNSUPPORTED_ALG'
+  | 'ERR_MALFORMED_TOKEN'
+  | 'ERR_BAD_SIGNATURE'
+  | 'ERR_EXPIRED'
+  | 'ERR_NOT_MATURE'
+  | 'ERR_MISSING_CLAIM'
+  | 'ERR_CLAIM_MISMATCH'
+  | 'ERR_CREATION'
+  | 'ERR_KEY_NOT_FOUND';
+
+/** Error thrown by the library. */
+export interface JwtError extends Error {
+  /** Stable error code that can be used to branch on the failure reason. */
+  code: ErrorCode;
+  /** Name of the offending claim (e.g., `exp` for expired tokens), if applicable. */
+  claim?: string;
+}
+
+/** Header and claims of a token decoded without verification. */
+export interface DecodedToken {
+  header: Record<string, unknown>;
+  claims: Record<string, unknown>;
+}
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    type Error;
+
+    #[wasm_bindgen(constructor)]
+    fn new(message: &str) -> Error;
+
+    #[wasm_bindgen(method, setter)]
+    fn set_code(this: &Error, code: &str);
+
+    #[wasm_bindgen(method, setter)]
+    fn set_claim(this: &Error, claim: &str);
+
+    // For some undecipherable reason, if both `wasm-bindgen/serde-serialize` and
+    // `rand_core/getrandom` features are on, type inference breaks in the `der` crate
+    // (specifically for the WASM target!). The simplest way to fix this is to manually wrap
+    // `JSON` methods instead of using `serde-serialize`.
+    #[wasm_bindgen(js_name = parse, js_namespace = JSON)]
+    fn json_parse(s: &str) -> JsValue;
+
+    #[wasm_bindgen(js_name = stringify, js_namespace = JSON)]
+    fn json_stringify(value: &JsValue) -> String;
+
+    type SubtleCrypto;
+
+    #[wasm_bindgen(method, catch)]
+    fn sign(
+        this: &SubtleCrypto,
+        algorithm: &JsValue,
+        key: &CryptoKey,
+        data: &[u8],
+    ) -> Result<js_sys::Promise, JsValue>;
+
+    #[wasm_bindgen(method, catch)]
+    fn verify(
+        this: &SubtleCrypto,
+        algorithm: &JsValue,
+        key: &CryptoKey,
+        signature: &[u8],
+        data: &[u8],
+    ) -> Result<js_sys::Promise, JsValue>;
+
+    /// Web Crypto API key. The key may be non-extractable.
+    #[wasm_bindgen(typescript_type = "CryptoKey")]
+    #[derive(Debug, Clone)]
+    pub type CryptoKey;
+
+    #[wasm_bindgen(method, getter)]
+    fn algorithm(this: &CryptoKey) -> JsValue;
+
+    /// JSON object, e.g. token claims.
+    #[wasm_bindgen(typescript_type = "Record<string, unknown>")]
+    pub type JsonObject;
+
+    /// JWK supplied either as a string or as an object.
+    #[wasm_bindgen(typescript_type = "string | Record<string, unknown>")]
+    pub type JwkInput;
+
+    /// Token creation options.
+    #[wasm_bindgen(typescript_type = "CreationOptions")]
+    pub type CreationOptionsInput;
+
+    /// Token verification options.
+    #[wasm_bindgen(typescript_type = "VerificationOptions")]
+    pub type VerificationOptionsInput;
+
+    /// Output of [`decode_unverified()`].
+    #[wasm_bindgen(typescript_type = "DecodedToken")]
+    pub type DecodedToken;
+
+    /// Output of [`create_token_with_crypto_key()`].
+    #[wasm_bindgen(typescript_type = "Promise<string>")]
+    pub type TokenPromise;
+
+    /// Output of [`verify_token_with_crypto_key()`].
+    #[wasm_bindgen(typescript_type = "Promise<Record<string, unknown>>")]
+    pub type ClaimsPromise;
+}
+
+/// Returns the stable JS error code for the specified error kind. The codes are a part
+/// of the public interface (see `ErrorCode` in the TypeScript definitions); they must not change.
+fn error_code(kind: bindings::ErrorKind) -> &'static str {
+    use bindings::ErrorKind;
+
+    match kind {
+        ErrorKind::InvalidKey => "ERR_INVALID_KEY",
+        ErrorKind::InvalidInput => "ERR_INVALID_INPUT",
+        ErrorKind::UnsupportedAlgorithm => "ERR_UNSUPPORTED_ALG",
+        ErrorKind::MalformedToken => "ERR_MALFORMED_TOKEN",
+        ErrorKind::InvalidSignature => "ERR_BAD_SIGNATURE",
+        ErrorKind::Expired => "ERR_EXPIRED",
+        ErrorKind::NotMature => "ERR_NOT_MATURE",
+        ErrorKind::MissingClaim => "ERR_MISSING_CLAIM",
+        ErrorKind::ClaimMismatch => "ERR_CLAIM_MISMATCH",
+        ErrorKind::Creation => "ERR_CREATION",
+        ErrorKind::KeyNotFound => "ERR_KEY_NOT_FOUND",
+    }
+}
+
+/// Converts a bindings error to a JS `Error` with the `code` property (e.g., `"ERR_EXPIRED"`)
+/// and, if applicable, the `claim` property with the name of the offending claim.
+fn to_js_error(err: &bindings::Error) -> JsValue {
+    let js_err = Error::new(err.message());
+    js_err.set_code(error_code(err.kind()));
+    if let Some(claim) = err.claim() {
+        js_err.set_claim(claim);
+    }
+    js_err.into()
+}
+
+fn js_error(kind: bindings::ErrorKind, message: &str) -> JsValue {
+    let js_err = Error::new(message);
+    js_err.set_code(error_code(kind));
+    js_err.into()
+}
+
+fn invalid_input(message: &str) -> JsValue {
+    js_error(bindings::ErrorKind::InvalidInput, message)
+}
+
+fn to_json(value: &JsValue) -> Result<Value, JsValue> {
+    let json_string = json_stringify(value);
+    serde_json::from_str(&json_string).map_err(|err| invalid_input(&err.to_string()))
+}
+
+fn from_json(value: &Value) -> JsValue {
+    json_parse(&value.to_string())
+}
+
+/// Converts a string or an object to a JSON string.
+fn json_input(value: &JsValue) -> Result<String, JsValue> {
+    if let Some(s) = value.as_string() {
+        Ok(s)
+    } else {
+        Ok(to_json(value)?.to_string())
+    }
+}
+
+/// Parses an options object; `undefined` and `null` are treated as an empty object.
+fn options_input(value: Option<&JsValue>) -> Result<Value, JsValue> {
+    let Some(value) = value.filter(|value| !value.is_null()) else {
+        return Ok(Value::Null);
+    };
+    let value = to_json(value)?;
+    if value.is_object() {
+        Ok(value)
+    } else {
+        Err(invalid_input("options must be an object"))
+    }
+}
+
+fn string_option(options: &Value, name: &str) -> Option<String> {
+    options.get(name).and_then(Value::as_str).map(str::to_owned)
+}
+
+fn creation_options(options: Option<&JsValue>) -> Result<bindings::CreationOptions, JsValue> {
+    let options = options_input(options)?;
+    Ok(bindings::CreationOptions {
+        key_id: string_option(&options, "keyId"),
+        token_type: string_option(&options, "tokenType"),
+        expires_in: options.get("expiresIn").and_then(Value::as_i64),
+    })
+}
+
+fn verification_options(options: Option<&JsValue>) -> Result<bindings::ValidationOptions, JsValue> {
+    let options = options_input(options)?;
+    let defaults = bindings::ValidationOptions::default();
+    let algorithms = options.get("algorithms").and_then(Value::as_array);
+    let algorithms = algorithms.map_or_else(Vec::new, |algorithms| {
+        algorithms
+            .iter()
+            .filter_map(|alg| alg.as_str().map(str::to_owned))
+            .collect()
+    });
+
+    Ok(bindings::ValidationOptions {
+        leeway: options
+            .get("leeway")
+            .and_then(Value::as_i64)
+            .unwrap_or(defaults.leeway),
+        require_expiration: options
+            .get("requireExpiration")
+            .and_then(Value::as_bool)
+            .unwrap_or(defaults.require_expiration),
+        issuer: string_option(&options, "issuer"),
+        audience: string_option(&options, "audience"),
+        algorithms,
+    })
+}
+
+/// Signing or verifying key.
+#[wasm_bindgen]
+#[derive(Debug)]
+pub struct Key(bindings::Key);
+
+#[wasm_bindgen]
+impl Key {
+    /// Parses a key from a JWK, which can be supplied either as a string or as an object.
+    #[wasm_bindgen(js_name = fromJwk)]
+    pub fn from_jwk(jwk: &JwkInput) -> Result<Key, JsValue> {
+        let jwk = json_input(jwk)?;
+        bindings::Key::from_jwk(&jwk)
+            .map(Self)
+            .map_err(|err| to_js_error(&err))
+    }
+
+    /// Parses a key from PEM. PKCS#8 / SPKI keys are supported for all asymmetric algorithms;
+    /// PKCS#1 and SEC1 keys are supported for RSA and elliptic curve keys, respectively.
+    #[wasm_bindgen(js_name = fromPem)]
+    pub fn from_pem(pem: &str) -> Result<Key, JsValue> {
+        bindings::Key::from_pem(pem)
+            .map(Self)
+            .map_err(|err| to_js_error(&err))
+    }
+
+    /// Names of algorithms that can be used with this key.
+    #[wasm_bindgen(getter)]
+    pub fn algorithms(&self) -> Vec<String> {
+        self.0
+            .algorithms()
+            .iter()
+            .map(|&alg| alg.to_owned())
+            .collect()
+    }
+
+    /// Whether this key can be used for signing.
+    #[wasm_bindgen(getter, js_name = isSigningKey)]
+    pub fn is_signing_key(&self) -> bool {
+        self.0.is_signing_key()
+    }
+
+    /// Returns the RFC 7638 thumbprint of the key (base64url-encoded SHA-256 digest).
+    pub fn thumbprint(&self) -> String {
+        self.0.thumbprint()
+    }
+
+    /// Returns the JWK with the public parts of the key.
+    #[wasm_bindgen(js_name = toPublicJwk)]
+    pub fn to_public_jwk(&self) -> JsonObject {
+        json_parse(&self.0.to_public_jwk()).unchecked_into()
+    }
+}
+
+/// Creates a token with the specified claims signed by `key`.
+#[wasm_bindgen(js_name = createToken)]
+pub fn create_token(
+    key: &Key,
+    algorithm: &str,
+    claims: &JsonObject,
+    options: Option<CreationOptionsInput>,
+) -> Result<String, JsValue> {
+    let claims = to_json(claims)?;
+    let options = creation_options(options.as_deref())?;
+    bindings::create_token(&key.0, algorithm, claims, &options).map_err(|err| to_js_error(&err))
+}
+
+/// Verifies `token` with `key` and returns its claims.
+#[wasm_bindgen(js_name = verifyToken)]
+pub fn verify_token(
+    key: &Key,
+    token: &str,
+    options: Option<VerificationOptionsInput>,
+) -> Result<JsonObject, JsValue> {
+    let options = verification_options(options.as_deref())?;
+    let claims =
+        bindings::validate_token(&key.0, token, &options).map_err(|err| to_js_error(&err))?;
+    Ok(from_json(&claims).unchecked_into())
+}
+
+/// Decodes `token` *without* verifying it. This is useful to inspect the header (e.g., `kid`).
+#[wasm_bindgen(js_name = decodeUnverified)]
+pub fn decode_unverified(token: &str) -> Result<DecodedToken, JsValue> {
+    let (header, claims) = bindings::decode_unverified(token).map_err(|err| to_js_error(&err))?;
+    let decoded = serde_json::json!({ "header": header, "claims": claims });
+    Ok(from_json(&decoded).unchecked_into())
+}
+
+fn subtle_crypto() -> Result<SubtleCrypto, JsValue> {
+    let crypto = Reflect::get(&js_sys::global(), &"crypto".into())?;
+    let subtle = if crypto.is_object() {
+        Reflect::get(&crypto, &"subtle".into())?
+    } else {
+        JsValue::UNDEFINED
+    };
+    if subtle.is_object() {
+        Ok(subtle.unchecked_into())
+    } else {
+        let message = "Web Crypto API is not available";
+        Err(js_error(bindings::ErrorKind::InvalidKey, message))
+    }
+}
+
+fn string_property(object: &JsValue, name: &str) -> Option<String> {
+    Reflect::get(object, &name.into()).ok()?.as_string()
+}
+
+/// Returns Web Crypto API parameters for signing / verification with the specified algorithm,
+/// checking that the key is compatible with it.
+fn web_crypto_params(key: &CryptoKey, algorithm: &str) -> Result<JsValue, JsValue> {
+    let key_algorithm = key.algorithm();
+    let name = string_property(&key_algorithm, "name").unwrap_or_default();
+    let hash = Reflect::get(&key_algorithm, &"hash".into())
+        .ok()
+        .and_then(|hash| string_property(&hash, "name"));
+    let curve = string_property(&key_algorithm, "namedCurve");
+    // Hash function used by the `HS*`, `RS*` and `PS*` algorithms, e.g. `SHA-256`.
+    let expected_hash = algorithm.get(2..).map(|bits| format!("SHA-{bits}"));
+    let hash_matches = hash.is_some() && hash == expected_hash;
+
+    let params = match (algorithm, name.as_str()) {
+        ("HS256" | "HS384" | "HS512", "HMAC") if hash_matches => serde_json::json!({
+            "name": "HMAC",
+        }),
+        ("RS256" | "RS384" | "RS512", "RSASSA-PKCS1-v1_5") if hash_matches => serde_json::json!({
+            "name": "RSASSA-PKCS1-v1_5",
+        }),
+        ("PS256" | "PS384" | "PS512", "RSA-PSS") if hash_matches => {
+            // RFC 7518 requires the salt length to be equal to the hash digest length.
+            let salt_len = match algorithm {
+                "PS256" => 32,
+                "PS384" => 48,
+                _ => 64,
+            };
+            serde_json::json!({ "name": "RSA-PSS", "saltLength": salt_len })
+        }
+        ("ES256", "ECDSA") if curve.as_deref() == Some("P-256") => serde_json::json!({
+            "name": "ECDSA",
+            "hash": "SHA-256",
+        }),
+        ("EdDSA", "Ed25519") => serde_json::json!({ "name": "Ed25519" }),
+        _ => {
+            let message = format!("algorithm `{algorithm}` cannot be used with the `{name}` key");
+            return Err(js_error(
+                bindings::ErrorKind::UnsupportedAlgorithm,
+                &message,
+            ));
+        }
+    };
+    Ok(from_json(&params))
+}
+
+/// Converts an error thrown by `SubtleCrypto` (e.g., if the key usages do not allow
+/// the operation) to our error type.
+fn web_crypto_error(err: &JsValue) -> JsValue {
+    let message = string_property(err, "message").unwrap_or_else(|| "unknown error".to_owned());
+    let message = format!("Web Crypto API error: {message}");
+    js_error(bindings::ErrorKind::InvalidKey, &message)
+}
+
+/// Creates a token with the specified claims signed by a Web Crypto API `key`.
+/// The key may be non-extractable; signing is performed by `SubtleCrypto`.
+///
+/// Supported algorithms are `HS*`, `RS*`, `PS*`, `ES256` and `EdDSA`; the algorithm must
+/// correspond to the key (e.g., `HS256` requires an `HMAC` key with the `SHA-256` hash).
+/// Returns a promise resolving to the token.
+#[wasm_bindgen(js_name = createTokenWithCryptoKey)]
+pub fn create_token_with_crypto_key(
+    key: &CryptoKey,
+    algorithm: &str,
+    claims: &JsonObject,
+    options: Option<CreationOptionsInput>,
+) -> TokenPromise {
+    let key = key.clone();
+    let algorithm = algorithm.to_owned();
+    let claims: JsValue = claims.into();
+    let options: Option<JsValue> = options.map(Into::into);
+
+    let promise = future_to_promise(async move {
+        let params = web_crypto_params(&key, &algorithm)?;
+        let claims = to_json(&claims)?;
+        let options = creation_options(options.as_ref())?;
+        let signing_input = bindings::signing_input(&algorithm, claims, &options)
+            .map_err(|err| to_js_error(&err))?;
+
+        let signature = subtle_crypto()?
+            .sign(&params, &key, signing_input.as_bytes())
+            .map_err(|err| web_crypto_error(&err))?
+            .await
+            .map_err(|err| web_crypto_error(&err))?;
+        let signature = Uint8Array::new(&signature).to_vec();
+        let token = bindings::attach_signature(&signing_input, &signature);
+        Ok(token.into())
+    });
+    promise.unchecked_into()
+}
+
+/// Verifies `token` with a Web Crypto API `key` and returns its claims. The key may be
+/// non-extractable; signature verification is performed by `SubtleCrypto`.
+///
+/// See [`create_token_with_crypto_key()`] for the supported algorithms.
+/// Returns a promise resolving to the token claims.
+#[wasm_bindgen(js_name = verifyTokenWithCryptoKey)]
+pub fn verify_token_with_crypto_key(
+    key: &CryptoKey,
+    token: &str,
+    options: Option<VerificationOptionsInput>,
+) -> ClaimsPromise {
+    let key = key.clone();
+    let token = token.to_owned();
+    let options: Option<JsValue> = options.map(Into::into);
+
+    let promise = future_to_promise(async move {
+        let options = verification_options(options.as_ref())?;
+        let verification = bindings::parse_for_external_verification(&token, &options)
+            .map_err(|err| to_js_error(&err))?;
+        let params = web_crypto_params(&key, verification.algorithm())?;
+
+        let is_valid = subtle_crypto()?
+            .verify(
+                &params,
+                &key,
+                verification.signature(),
+                verification.signed_data(),
+            )
+            .map_err(|err| web_crypto_error(&err))?
+            .await
+            .map_err(|err| web_crypto_error(&err))?;
+        let claims = verification
+            .finish(is_valid.as_bool() == Some(true), &options)
+            .map_err(|err| to_js_error(&err))?;
+        Ok(from_json(&claims))
+    });
+    promise.unchecked_into()
+}
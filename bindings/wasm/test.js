@@ -0,0 +1,183 @@
+#!/usr/bin/env node
+
+const { strict: assert } = require('assert');
+const { webcrypto: { subtle } } = require('crypto');
+const {
+  SignJWT, jwtVerify, generateKeyPair, generateSecret, exportJWK, exportPKCS8, exportSPKI,
+} = require('jose');
+
+const {
+  Key, createToken, verifyToken, decodeUnverified, createTokenWithCryptoKey,
+  verifyTokenWithCryptoKey,
+} = require('jwt-compact-wasm');
+
+// Claims have an arbitrary shape.
+const payload = {
+  name: 'John Doe',
+  admin: false,
+  roles: ['reader', 'writer'],
+  profile: { locale: 'en-US', age: 42 },
+};
+
+async function assertRoundTrip({ algorithm, keyGenerator, keyFormats }) {
+  const { privateKey, publicKey } = await keyGenerator();
+
+  for (const format of keyFormats) {
+    let signingKey;
+    let verifyingKey;
+    if (format === 'jwk') {
+      signingKey = Key.fromJwk(await exportJWK(privateKey));
+      verifyingKey = Key.fromJwk(JSON.stringify(await exportJWK(publicKey)));
+    } else {
+      signingKey = Key.fromPem(await exportPKCS8(privateKey));
+      verifyingKey = Key.fromPem(await exportSPKI(publicKey));
+    }
+    assert.ok(signingKey.algorithms.includes(algorithm));
+
+    console.log(`Verifying ${algorithm} with ${format} keys (JS -> WASM)...`);
+    const token = await new SignJWT(payload)
+      .setProtectedHeader({ alg: algorithm, kid: 'test' })
+      .setExpirationTime('1h')
+      .setSubject('john.doe@example.com')
+      .sign(privateKey);
+
+    const claims = verifyToken(verifyingKey, token, { algorithms: [algorithm] });
+    assert.equal(typeof claims.exp, 'number');
+    delete claims.exp;
+    assert.deepEqual(claims, { sub: 'john.doe@example.com', ...payload });
+    assert.equal(decodeUnverified(token).header.kid, 'test');
+
+    console.log(`Verifying ${algorithm} with ${format} keys (WASM -> JS)...`);
+    const wasmToken = createToken(signingKey, algorithm, claims, { expiresIn: 3600 });
+    const { payload: wasmClaims } = await jwtVerify(wasmToken, publicKey);
+    assert.equal(typeof wasmClaims.exp, 'number');
+    delete wasmClaims.exp;
+    delete wasmClaims.iat;
+    assert.deepEqual(wasmClaims, claims);
+  }
+}
+
+function assertErrors() {
+  console.log('Checking errors...');
+  const key = Key.fromJwk({ kty: 'oct', k: 'c2VjcmV0LXNlY3JldC1zZWNyZXQtc2VjcmV0LXNlY3JldA' });
+  const expired = createToken(key, 'HS256', { exp: 1000 });
+  assert.throws(() => verifyToken(key, expired), { code: 'ERR_EXPIRED', claim: 'exp' });
+  assert.throws(() => verifyToken(key, expired, { algorithms: ['HS512'] }), {
+    code: 'ERR_UNSUPPORTED_ALG',
+  });
+
+  const token = createToken(key, 'HS256', { aud: ['api', 'web'] }, { expiresIn: 60 });
+  verifyToken(key, token, { audience: 'web' });
+  assert.throws(() => verifyToken(key, token, { audience: 'mobile' }), {
+    code: 'ERR_CLAIM_MISMATCH',
+    claim: 'aud',
+  });
+  assert.throws(() => verifyToken(key, token, { issuer: 'auth' }), {
+    code: 'ERR_MISSING_CLAIM',
+    claim: 'iss',
+  });
+  const otherKey = Key.fromJwk({ kty: 'oct', k: 'b3RoZXItc2VjcmV0LW90aGVyLXNlY3JldC1vdGhlcg' });
+  assert.throws(() => verifyToken(otherKey, token), { code: 'ERR_BAD_SIGNATURE' });
+  assert.throws(() => Key.fromPem('not a key'), { code: 'ERR_INVALID_KEY' });
+}
+
+async function assertCryptoKeys() {
+  const rsaParams = { modulusLength: 2048, publicExponent: new Uint8Array([1, 0, 1]) };
+  const cases = [
+    ['HS256', { name: 'HMAC', hash: 'SHA-256' }],
+    ['RS256', { name: 'RSASSA-PKCS1-v1_5', hash: 'SHA-256', ...rsaParams }],
+    ['PS384', { name: 'RSA-PSS', hash: 'SHA-384', ...rsaParams }],
+    ['ES256', { name: 'ECDSA', namedCurve: 'P-256' }],
+    ['EdDSA', { name: 'Ed25519' }],
+  ];
+
+  for (const [algorithm, params] of cases) {
+    console.log(`Checking ${algorithm} with non-extractable CryptoKeys...`);
+    const keys = await subtle.generateKey(params, false, ['sign', 'verify']);
+    const { privateKey, publicKey } = keys.privateKey ? keys : { privateKey: keys, publicKey: keys };
+    assert.equal(privateKey.extractable, false);
+
+    const token = await createTokenWithCryptoKey(privateKey, algorithm, payload, { expiresIn: 60 });
+    const claims = await verifyTokenWithCryptoKey(publicKey, token, { algorithms: [algorithm] });
+    assert.equal(typeof claims.exp, 'number');
+    delete claims.exp;
+    delete claims.iat;
+    assert.deepEqual(claims, payload);
+
+    const tampered = `${token.slice(0, -2)}${token.endsWith('AA') ? 'BB' : 'AA'}`;
+    await assert.rejects(verifyTokenWithCryptoKey(publicKey, tampered), {
+      code: 'ERR_BAD_SIGNATURE',
+    });
+    const expired = await createTokenWithCryptoKey(privateKey, algorithm, { exp: 1000 });
+    await assert.rejects(verifyTokenWithCryptoKey(publicKey, expired), {
+      code: 'ERR_EXPIRED',
+      claim: 'exp',
+    });
+  }
+
+  const hmacKey = await subtle.generateKey({ name: 'HMAC', hash: 'SHA-256' }, false, ['sign']);
+  await assert.rejects(createTokenWithCryptoKey(hmacKey, 'HS512', payload), {
+    code: 'ERR_UNSUPPORTED_ALG',
+  });
+  const token = await createTokenWithCryptoKey(hmacKey, 'HS256', payload, { expiresIn: 60 });
+  // The key usages do not allow verification.
+  await assert.rejects(verifyTokenWithCryptoKey(hmacKey, token), { code: 'ERR_INVALID_KEY' });
+}
+
+async function iteration() {
+  // RSA algorithms.
+  for (const algorithm of ['RS256', 'RS384', 'RS512', 'PS256', 'PS384', 'PS512']) {
+    await assertRoundTrip({
+      algorithm,
+      keyGenerator: () => generateKeyPair(algorithm, { modulusLength: 2048, extractable: true }),
+      keyFormats: ['jwk', 'pem'],
+    });
+  }
+
+  // HMAC-based algorithms.
+  for (const algorithm of ['HS256', 'HS384', 'HS512']) {
+    await assertRoundTrip({
+      algorithm,
+      keyGenerator: async () => {
+        const secret = await generateSecret(algorithm, { extractable: true });
+        return { privateKey: secret, publicKey: secret };
+      },
+      keyFormats: ['jwk'],
+    });
+  }
+
+  // EdDSA algorithm on the Ed25519 curve.
+  await assertRoundTrip({
+    algorithm: 'EdDSA',
+    keyGenerator: () => generateKeyPair('EdDSA', { crv: 'Ed25519', extractable: true }),
+    keyFormats: ['jwk', 'pem'],
+  });
+
+  // ES256K algorithm.
+  await assertRoundTrip({
+    algorithm: 'ES256K',
+    keyGenerator: () => generateKeyPair('ES256K', { extractable: true }),
+    keyFormats: ['jwk', 'pem'],
+  });
+
+  // ES256 algorithm.
+  await assertRoundTrip({
+    algorithm: 'ES256',
+    keyGenerator: () => generateKeyPair('ES256', { extractable: true }),
+    keyFormats: ['jwk', 'pem'],
+  });
+}
+
+async function main(iterations = 5) {
+  assertErrors();
+  await assertCryptoKeys();
+  for (let i = 1; i <= iterations; i++) {
+    console.log(`Iteration ${i}/${iterations}`);
+    await iteration();
+  }
+}
+
+main().catch((err) => {
+  console.error(err);
+  process.exit(1);
+});
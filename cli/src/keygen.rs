@@ -0,0 +1,100 @@
+//! Key generation.
+
+use anyhow::{anyhow, bail};
+use rand::thread_rng;
+
+use jwt_compact::{
+    alg::{
+        Ed25519, Es256, Es256k, GenerateKey, Hs256, Hs256Key, Hs384, Hs384Key, Hs512, Hs512Key,
+        ModulusBits, Rsa,
+    },
+    jwk::JsonWebKey,
+    pem::PemKey,
+    Algorithm,
+};
+
+/// Algorithms for which keys can be generated.
+pub(crate) const ALGORITHMS: &[&str] = &[
+    "HS256", "HS384", "HS512", "RS256", "RS384", "RS512", "PS256", "PS384", "PS512", "ES256",
+    "ES256K", "EdDSA",
+];
+
+/// Output format for generated keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Format {
+    Jwk,
+    Pem,
+}
+
+/// Signing and verifying keys encoded in the requested format.
+#[derive(Debug)]
+pub(crate) struct GeneratedKeys {
+    pub signing_key: String,
+    pub verifying_key: String,
+}
+
+fn encode<K: PemKey>(key: &K, format: Format) -> anyhow::Result<String>
+where
+    for<'a> JsonWebKey<'a>: From<&'a K>,
+{
+    Ok(match format {
+        Format::Jwk => JsonWebKey::from(key).to_string(),
+        Format::Pem => key.to_pem()?.as_str().to_owned(),
+    })
+}
+
+fn generate_pair<A>(
+    params: <A::SigningKey as GenerateKey<A>>::Params,
+    format: Format,
+) -> anyhow::Result<GeneratedKeys>
+where
+    A: Algorithm,
+    A::SigningKey: GenerateKey<A> + PemKey,
+    A::VerifyingKey: PemKey,
+    for<'a> JsonWebKey<'a>: From<&'a A::SigningKey> + From<&'a A::VerifyingKey>,
+{
+    let (signing_key, verifying_key) = A::SigningKey::generate_pair_with(&mut thread_rng(), params);
+    Ok(GeneratedKeys {
+        signing_key: encode(&signing_key, format)?,
+        verifying_key: encode(&verifying_key, format)?,
+    })
+}
+
+fn generate_symmetric<A, K>(format: Format) -> anyhow::Result<GeneratedKeys>
+where
+    A: Algorithm<SigningKey = K>,
+    K: GenerateKey<A>,
+    for<'a> JsonWebKey<'a>: From<&'a K>,
+{
+    if format == Format::Pem {
+        bail!("PEM format is not supported for symmetric keys");
+    }
+    let key = K::generate(&mut thread_rng());
+    let key = JsonWebKey::from(&key).to_string();
+    Ok(GeneratedKeys {
+        signing_key: key.clone(),
+        verifying_key: key,
+    })
+}
+
+/// Generates a key pair for the specified algorithm.
+pub(crate) fn generate(
+    algorithm: &str,
+    format: Format,
+    rsa_bits: usize,
+) -> anyhow::Result<GeneratedKeys> {
+    match algorithm {
+        "HS256" => generate_symmetric::<Hs256, Hs256Key>(format),
+        "HS384" => generate_symmetric::<Hs384, Hs384Key>(format),
+        "HS512" => generate_symmetric::<Hs512, Hs512Key>(format),
+        "ES256" => generate_pair::<Es256>((), format),
+        "ES256K" => generate_pair::<Es256k>((), format),
+        "EdDSA" => generate_pair::<Ed25519>((), format),
+        _ if algorithm.parse::<Rsa>().is_ok() => {
+            let bits = ModulusBits::try_from(rsa_bits)
+                .map_err(|_| anyhow!("unsupported RSA modulus length: {rsa_bits}"))?;
+            generate_pair::<Rsa>(bits, format)
+        }
+        _ => bail!("unsupported algorithm: {algorithm}"),
+    }
+}
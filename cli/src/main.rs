@@ -0,0 +1,320 @@
+//! Command-line tool to decode, verify and sign JWTs, and to generate keys for them.
+//!
+//! Keys are read from files in the JWK, JWK set or PEM format. Signing and validation logic
+//! is shared with the bindings to other languages, so that the tool behaves identically
+//! to them.
+
+#![warn(missing_debug_implementations, missing_docs, bare_trait_objects)]
+#![warn(clippy::all, clippy::pedantic)]
+
+use anyhow::{anyhow, bail, Context as _};
+use clap::{value_parser, Arg, ArgAction, ArgMatches, Command};
+use serde_json::{json, Value};
+
+use std::{
+    fs,
+    io::{self, Read},
+};
+
+use jwt_compact::UntrustedToken;
+use jwt_compact_bindings::{
+    create_token, decode_unverified, validate_token, validate_token_with_key_set, CreationOptions,
+    Key, KeySet, ValidationOptions,
+};
+
+mod keygen;
+
+/// Converts a binding error into an `anyhow` error (binding errors are `no_std`-compatible
+/// and do not implement the standard `Error` trait).
+#[allow(clippy::needless_pass_by_value)] // used in `map_err()`
+fn binding_error(err: jwt_compact_bindings::Error) -> anyhow::Error {
+    anyhow!("{} ({})", err.message(), err.kind().as_str())
+}
+
+/// Reads the contents of the file at `path`, or of stdin if `path` is `-` or not specified.
+fn read_input(path: Option<&String>) -> anyhow::Result<String> {
+    match path.map(String::as_str) {
+        None | Some("-") => {
+            let mut input = String::new();
+            io::stdin()
+                .read_to_string(&mut input)
+                .context("cannot read stdin")?;
+            Ok(input)
+        }
+        Some(path) => fs::read_to_string(path).with_context(|| format!("cannot read `{path}`")),
+    }
+}
+
+/// Returns the token passed as a command-line arg, or reads it from stdin if the arg
+/// is `-` or not specified.
+fn read_token(arg: Option<&String>) -> anyhow::Result<String> {
+    match arg.map(String::as_str) {
+        None | Some("-") => read_input(None),
+        Some(token) => Ok(token.to_owned()),
+    }
+}
+
+/// Key(s) read from a file.
+#[allow(clippy::large_enum_variant)] // the value is short-lived
+enum VerifyingKeys {
+    Single(Key),
+    Set(KeySet),
+}
+
+fn read_key(path: &str) -> anyhow::Result<VerifyingKeys> {
+    let contents = fs::read_to_string(path).with_context(|| format!("cannot read `{path}`"))?;
+    if contents.trim_start().starts_with("-----BEGIN ") {
+        return Key::from_pem(&contents)
+            .map(VerifyingKeys::Single)
+            .map_err(binding_error);
+    }
+
+    let json: Value = serde_json::from_str(&contents)
+        .with_context(|| format!("`{path}` is neither PEM nor JSON"))?;
+    if json.get("keys").is_some() {
+        KeySet::from_jwks(&contents)
+            .map(VerifyingKeys::Set)
+            .map_err(binding_error)
+    } else {
+        Key::from_jwk(&contents)
+            .map(VerifyingKeys::Single)
+            .map_err(binding_error)
+    }
+}
+
+fn token_arg() -> Arg {
+    Arg::new("token")
+        .value_name("TOKEN")
+        .help("Token to process; if omitted or `-`, the token is read from stdin")
+}
+
+fn key_arg() -> Arg {
+    Arg::new("key")
+        .long("key")
+        .short('k')
+        .value_name("FILE")
+        .required(true)
+}
+
+fn verify_command() -> Command {
+    Command::new("verify")
+        .about("Verifies a token and outputs its claims")
+        .arg(key_arg().help("Verifying key as JWK, JWK set or PEM (SPKI / PKCS#1 / PKCS#8 / SEC1)"))
+        .arg(
+            Arg::new("alg")
+                .long("alg")
+                .value_name("ALG")
+                .action(ArgAction::Append)
+                .help("Allowed algorithm; may be specified multiple times"),
+        )
+        .arg(
+            Arg::new("leeway")
+                .long("leeway")
+                .value_name("SECONDS")
+                .value_parser(value_parser!(i64))
+                .default_value("60")
+                .help("Leeway for time-based checks"),
+        )
+        .arg(
+            Arg::new("allow-no-exp")
+                .long("allow-no-exp")
+                .action(ArgAction::SetTrue)
+                .help("Do not require the `exp` claim"),
+        )
+        .arg(
+            Arg::new("iss")
+                .long("iss")
+                .value_name("ISSUER")
+                .help("Expected issuer"),
+        )
+        .arg(
+            Arg::new("aud")
+                .long("aud")
+                .value_name("AUDIENCE")
+                .help("Expected audience"),
+        )
+        .arg(token_arg())
+}
+
+fn sign_command() -> Command {
+    Command::new("sign")
+        .about("Signs claims (a JSON object) and outputs the token")
+        .arg(key_arg().help("Signing key as JWK or PEM (PKCS#1 / PKCS#8 / SEC1)"))
+        .arg(
+            Arg::new("alg")
+                .long("alg")
+                .value_name("ALG")
+                .help("Signing algorithm; defaults to the first algorithm supported by the key"),
+        )
+        .arg(
+            Arg::new("kid")
+                .long("kid")
+                .value_name("KEY_ID")
+                .help("Key ID to put into the token header"),
+        )
+        .arg(
+            Arg::new("typ")
+                .long("typ")
+                .value_name("TYPE")
+                .help("Token type to put into the token header"),
+        )
+        .arg(
+            Arg::new("expires-in")
+                .long("expires-in")
+                .value_name("SECONDS")
+                .value_parser(value_parser!(i64))
+                .help("Token lifetime; sets the `exp` and `iat` claims"),
+        )
+        .arg(
+            Arg::new("claims")
+                .value_name("FILE")
+                .help("File with claims; if omitted or `-`, claims are read from stdin"),
+        )
+}
+
+fn keygen_command() -> Command {
+    Command::new("keygen")
+        .about("Generates a signing key and outputs it")
+        .arg(
+            Arg::new("alg")
+                .long("alg")
+                .value_name("ALG")
+                .required(true)
+                .value_parser(keygen::ALGORITHMS.to_vec())
+                .help("Algorithm the key is generated for"),
+        )
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .value_name("FORMAT")
+                .value_parser(["jwk", "pem"])
+                .default_value("jwk")
+                .help("Output format; PEM is not supported for HMAC keys"),
+        )
+        .arg(
+            Arg::new("rsa-bits")
+                .long("rsa-bits")
+                .value_name("BITS")
+                .value_parser(value_parser!(usize))
+                .default_value("2048")
+                .help("Modulus length for RSA keys (2048, 3072 or 4096)"),
+        )
+        .arg(
+            Arg::new("public-out")
+                .long("public-out")
+                .value_name("FILE")
+                .help("File to write the verifying key to"),
+        )
+}
+
+fn command() -> Command {
+    let decode = Command::new("decode")
+        .about("Decodes a token without verifying it")
+        .arg(
+            Arg::new("describe")
+                .long("describe")
+                .action(ArgAction::SetTrue)
+                .help("Output a human-readable description instead of JSON"),
+        )
+        .arg(token_arg());
+    Command::new("jwt-compact-cli")
+        .about("Decodes, verifies and signs JWTs, and generates keys for them")
+        .subcommand_required(true)
+        .subcommands([decode, verify_command(), sign_command(), keygen_command()])
+}
+
+fn decode(args: &ArgMatches) -> anyhow::Result<String> {
+    let token = read_token(args.get_one("token"))?;
+    if args.get_flag("describe") {
+        let token = UntrustedToken::new(token.trim())?;
+        // `describe()` output ends with a newline
+        return Ok(token.describe().to_string().trim_end().to_owned());
+    }
+    let (header, claims) = decode_unverified(token.trim()).map_err(binding_error)?;
+    Ok(format!(
+        "{:#}",
+        json!({ "header": header, "claims": claims })
+    ))
+}
+
+fn verify(args: &ArgMatches) -> anyhow::Result<Value> {
+    let key = read_key(args.get_one::<String>("key").unwrap())?;
+    let token = read_token(args.get_one("token"))?;
+    let options = ValidationOptions {
+        leeway: *args.get_one("leeway").unwrap(),
+        require_expiration: !args.get_flag("allow-no-exp"),
+        issuer: args.get_one("iss").cloned(),
+        audience: args.get_one("aud").cloned(),
+        algorithms: args
+            .get_many::<String>("alg")
+            .unwrap_or_default()
+            .cloned()
+            .collect(),
+    };
+
+    match &key {
+        VerifyingKeys::Single(key) => validate_token(key, token.trim(), &options),
+        VerifyingKeys::Set(key_set) => validate_token_with_key_set(key_set, token.trim(), &options),
+    }
+    .map_err(binding_error)
+}
+
+fn sign(args: &ArgMatches) -> anyhow::Result<String> {
+    let VerifyingKeys::Single(key) = read_key(args.get_one::<String>("key").unwrap())? else {
+        bail!("JWK sets cannot be used for signing");
+    };
+    if !key.is_signing_key() {
+        bail!("key cannot be used for signing");
+    }
+    let algorithm = match args.get_one::<String>("alg") {
+        Some(algorithm) => algorithm.as_str(),
+        None => key.algorithms()[0],
+    };
+    let claims = read_input(args.get_one("claims"))?;
+    let claims: Value = serde_json::from_str(&claims).context("claims are not valid JSON")?;
+    let options = CreationOptions {
+        key_id: args.get_one("kid").cloned(),
+        token_type: args.get_one("typ").cloned(),
+        expires_in: args.get_one("expires-in").copied(),
+    };
+    create_token(&key, algorithm, claims, &options).map_err(binding_error)
+}
+
+fn main() -> anyhow::Result<()> {
+    let matches = command().get_matches();
+    let (name, args) = matches.subcommand().expect("subcommand is required");
+    match name {
+        "decode" => println!("{}", decode(args)?),
+        "verify" => println!("{:#}", verify(args)?),
+        "sign" => println!("{}", sign(args)?),
+        "keygen" => {
+            let format = if args.get_one::<String>("format").unwrap() == "pem" {
+                keygen::Format::Pem
+            } else {
+                keygen::Format::Jwk
+            };
+            let keys = keygen::generate(
+                args.get_one::<String>("alg").unwrap(),
+                format,
+                *args.get_one("rsa-bits").unwrap(),
+            )?;
+            if let Some(path) = args.get_one::<String>("public-out") {
+                fs::write(path, &keys.verifying_key)
+                    .with_context(|| format!("cannot write `{path}`"))?;
+            }
+            println!("{}", keys.signing_key.trim_end());
+        }
+        _ => unreachable!("all subcommands are covered"),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn command_is_valid() {
+        command().debug_assert();
+    }
+}
@@ -0,0 +1,169 @@
+//! Tests for the CLI binary.
+
+use serde_json::{json, Value};
+
+use std::{
+    fs,
+    io::Write,
+    path::PathBuf,
+    process::{Command, Output, Stdio},
+};
+
+fn run(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_jwt-compact-cli"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn run_ok(args: &[&str], stdin: &str) -> String {
+    let output = run(args, stdin);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{args:?} failed: {stderr}");
+    String::from_utf8(output.stdout).unwrap()
+}
+
+/// Temporary directory removed on drop.
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new(name: &str) -> Self {
+        let path =
+            std::env::temp_dir().join(format!("jwt-compact-cli-{name}-{}", std::process::id()));
+        fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+
+    fn path(&self, name: &str) -> String {
+        self.0.join(name).to_str().unwrap().to_owned()
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        fs::remove_dir_all(&self.0).ok();
+    }
+}
+
+fn sign_and_verify(dir: &TempDir, alg: &str, format: &str) {
+    let signing_key = dir.path("key");
+    let verifying_key = dir.path("key.pub");
+    let key = run_ok(
+        &[
+            "keygen",
+            "--alg",
+            alg,
+            "--format",
+            format,
+            "--public-out",
+            &verifying_key,
+        ],
+        "",
+    );
+    fs::write(&signing_key, key).unwrap();
+
+    let claims = json!({ "sub": "alice", "admin": true }).to_string();
+    let token = run_ok(
+        &[
+            "sign",
+            "--key",
+            &signing_key,
+            "--alg",
+            alg,
+            "--kid",
+            "test",
+            "--expires-in",
+            "300",
+        ],
+        &claims,
+    );
+
+    let decoded: Value = serde_json::from_str(&run_ok(&["decode"], &token)).unwrap();
+    assert_eq!(decoded["header"]["alg"], alg);
+    assert_eq!(decoded["header"]["kid"], "test");
+    assert_eq!(decoded["claims"]["sub"], "alice");
+    assert!(decoded["claims"]["exp"].is_i64());
+    let description = run_ok(&["decode", "--describe", token.trim()], "");
+    assert!(description.starts_with(&format!("Header:\n  alg: \"{alg}\"\n")));
+    assert!(description.contains("  sub: \"alice\"\n"), "{description}");
+
+    let claims: Value = serde_json::from_str(&run_ok(
+        &["verify", "--key", &verifying_key, token.trim()],
+        "",
+    ))
+    .unwrap();
+    assert_eq!(claims["sub"], "alice");
+    assert_eq!(claims["admin"], true);
+
+    if !alg.starts_with("HS") {
+        // Verifying keys cannot be used for signing.
+        let output = run(&["sign", "--key", &verifying_key], "{}");
+        assert!(!output.status.success());
+    }
+}
+
+#[test]
+fn hs256_roundtrip() {
+    sign_and_verify(&TempDir::new("hs256"), "HS256", "jwk");
+}
+
+#[test]
+fn es256_roundtrip_with_pem_keys() {
+    sign_and_verify(&TempDir::new("es256"), "ES256", "pem");
+}
+
+#[test]
+fn eddsa_roundtrip_with_jwk_keys() {
+    sign_and_verify(&TempDir::new("eddsa"), "EdDSA", "jwk");
+}
+
+#[test]
+fn verification_errors() {
+    let dir = TempDir::new("errors");
+    let key_path = dir.path("key.json");
+    fs::write(&key_path, run_ok(&["keygen", "--alg", "HS256"], "")).unwrap();
+    let token = run_ok(&["sign", "--key", &key_path], r#"{"sub":"alice"}"#);
+
+    // No `exp` claim
+    let output = run(&["verify", "--key", &key_path], &token);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("missing_claim"), "{stderr}");
+    run_ok(&["verify", "--key", &key_path, "--allow-no-exp"], &token);
+
+    // Disallowed algorithm
+    let output = run(
+        &[
+            "verify",
+            "--key",
+            &key_path,
+            "--allow-no-exp",
+            "--alg",
+            "HS512",
+        ],
+        &token,
+    );
+    assert!(!output.status.success());
+
+    // Key set
+    let jwk: Value = serde_json::from_str(&fs::read_to_string(&key_path).unwrap()).unwrap();
+    let jwks_path = dir.path("jwks.json");
+    fs::write(&jwks_path, json!({ "keys": [jwk] }).to_string()).unwrap();
+    run_ok(&["verify", "--key", &jwks_path, "--allow-no-exp"], &token);
+    let output = run(&["sign", "--key", &jwks_path], "{}");
+    assert!(!output.status.success());
+
+    // Malformed token
+    let output = run(&["decode", "not-a-token"], "");
+    assert!(!output.status.success());
+}
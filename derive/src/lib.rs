@@ -0,0 +1,297 @@
+//! Derive macro for custom claims in [`jwt-compact`].
+//!
+//! This crate should not be used directly; the macro is re-exported by `jwt-compact`
+//! with the `derive` crate feature. See the [`Claims`](macro@Claims) docs in `jwt-compact`
+//! for the supported attributes.
+//!
+//! [`jwt-compact`]: https://docs.rs/jwt-compact/
+
+#![warn(missing_debug_implementations, missing_docs, bare_trait_objects)]
+#![warn(clippy::all, clippy::pedantic)]
+#![allow(clippy::must_use_candidate, clippy::module_name_repetitions)]
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, Data, DeriveInput, Field, Fields, GenericArgument, Ident, LitStr,
+    PathArguments, Type,
+};
+
+/// Derives `serde` (de)serialization and a `validate()` method for custom claims.
+#[proc_macro_derive(Claims, attributes(claim))]
+pub fn derive_claims(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match ClaimsStruct::new(&input) {
+        Ok(claims) => claims.derive(),
+        Err(err) => err.to_compile_error(),
+    }
+    .into()
+}
+
+/// Field of a struct with custom claims.
+struct ClaimField {
+    ident: Ident,
+    ty: Type,
+    /// Claim name in the serialized form.
+    name: LitStr,
+    /// Type wrapped in `Option`, if any.
+    optional: bool,
+    required: bool,
+    audience: Option<LitStr>,
+    issuer: Option<LitStr>,
+}
+
+impl ClaimField {
+    fn new(field: &Field) -> syn::Result<Self> {
+        let ident = field.ident.clone().ok_or_else(|| {
+            syn::Error::new_spanned(field, "claims must be declared as named fields")
+        })?;
+        let ident_str = ident.to_string();
+        let default_name = ident_str.strip_prefix("r#").unwrap_or(&ident_str);
+
+        let mut this = Self {
+            name: LitStr::new(default_name, ident.span()),
+            ident,
+            ty: field.ty.clone(),
+            optional: is_option(&field.ty),
+            required: false,
+            audience: None,
+            issuer: None,
+        };
+        for attr in &field.attrs {
+            if !attr.path().is_ident("claim") {
+                continue;
+            }
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("required") {
+                    this.required = true;
+                } else if meta.path.is_ident("rename") {
+                    this.name = meta.value()?.parse()?;
+                } else if meta.path.is_ident("audience") {
+                    this.audience = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("issuer") {
+                    this.issuer = Some(meta.value()?.parse()?);
+                } else {
+                    return Err(meta.error(
+                        "unsupported claim attribute; expected one of `required`, `rename`, \
+                         `audience` or `issuer`",
+                    ));
+                }
+                Ok(())
+            })?;
+        }
+
+        if this.required && !this.optional {
+            let message = "`required` only applies to `Option` fields; \
+                           other fields are always required";
+            return Err(syn::Error::new_spanned(&field.ty, message));
+        }
+        Ok(this)
+    }
+
+    fn serde_attrs(&self, is_serialization: bool) -> TokenStream2 {
+        let name = &self.name;
+        match (self.optional, is_serialization) {
+            (false, _) => quote!(#[serde(rename = #name)]),
+            (true, true) => quote! {
+                #[serde(rename = #name, skip_serializing_if = "::jwt_compact::__private::is_none")]
+            },
+            (true, false) => quote!(#[serde(rename = #name, default)]),
+        }
+    }
+
+    fn validation(&self) -> TokenStream2 {
+        let ident = &self.ident;
+        let name = &self.name;
+        let private = quote!(::jwt_compact::__private);
+
+        let mut checks = vec![];
+        if let Some(issuer) = &self.issuer {
+            checks.push(quote! {
+                if #private::ClaimStr::claim_str(value) != #issuer {
+                    return Err(::jwt_compact::presets::PresetError::ClaimMismatch(#name));
+                }
+            });
+        }
+        if let Some(audience) = &self.audience {
+            checks.push(quote! {
+                if !#private::ClaimStrs::contains_str(value, #audience) {
+                    return Err(::jwt_compact::presets::PresetError::ClaimMismatch(#name));
+                }
+            });
+        }
+
+        if self.required {
+            quote! {
+                let value = self.#ident.as_ref().ok_or(
+                    ::jwt_compact::presets::PresetError::MissingClaim(#name),
+                )?;
+                #(#checks)*
+            }
+        } else if checks.is_empty() {
+            quote!()
+        } else if self.optional {
+            quote! {
+                if let Some(value) = &self.#ident {
+                    #(#checks)*
+                }
+            }
+        } else {
+            quote! {
+                let value = &self.#ident;
+                #(#checks)*
+            }
+        }
+    }
+}
+
+fn is_option(ty: &Type) -> bool {
+    let Type::Path(path) = ty else {
+        return false;
+    };
+    let Some(last_segment) = path.path.segments.last() else {
+        return false;
+    };
+    if last_segment.ident != "Option" {
+        return false;
+    }
+    match &last_segment.arguments {
+        PathArguments::AngleBracketed(args) => {
+            matches!(args.args.first(), Some(GenericArgument::Type(_))) && args.args.len() == 1
+        }
+        _ => false,
+    }
+}
+
+/// Parsed struct with custom claims.
+struct ClaimsStruct {
+    ident: Ident,
+    fields: Vec<ClaimField>,
+}
+
+impl ClaimsStruct {
+    fn new(input: &DeriveInput) -> syn::Result<Self> {
+        if !input.generics.params.is_empty() {
+            let message = "`Claims` cannot be derived for generic structs";
+            return Err(syn::Error::new_spanned(&input.generics, message));
+        }
+        let Data::Struct(data) = &input.data else {
+            let message = "`Claims` can only be derived for structs";
+            return Err(syn::Error::new_spanned(&input.ident, message));
+        };
+        let fields = match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            Fields::Unit => {
+                let message = "`Claims` cannot be derived for unit structs; use `Empty` instead";
+                return Err(syn::Error::new_spanned(&input.ident, message));
+            }
+            Fields::Unnamed(_) => {
+                let message = "`Claims` can only be derived for structs with named fields";
+                return Err(syn::Error::new_spanned(&input.ident, message));
+            }
+        };
+        let fields = fields
+            .iter()
+            .map(ClaimField::new)
+            .collect::<syn::Result<_>>()?;
+        Ok(Self {
+            ident: input.ident.clone(),
+            fields,
+        })
+    }
+
+    fn derive(&self) -> TokenStream2 {
+        let serialize = self.derive_serialize();
+        let deserialize = self.derive_deserialize();
+        let validate = self.derive_validate();
+        quote!(#serialize #deserialize #validate)
+    }
+
+    fn derive_serialize(&self) -> TokenStream2 {
+        let ident = &self.ident;
+        let serde = quote!(::jwt_compact::__private::serde);
+        let helper = format_ident!("__{}Ref", ident, span = Span::call_site());
+        let field_idents: Vec<_> = self.fields.iter().map(|field| &field.ident).collect();
+        let fields = self.fields.iter().map(|field| {
+            let ident = &field.ident;
+            let ty = &field.ty;
+            let attrs = field.serde_attrs(true);
+            quote!(#attrs #ident: &'__a #ty)
+        });
+
+        quote! {
+            impl #serde::Serialize for #ident {
+                fn serialize<__S: #serde::Serializer>(
+                    &self,
+                    serializer: __S,
+                ) -> ::core::result::Result<__S::Ok, __S::Error> {
+                    #[derive(#serde::Serialize)]
+                    #[serde(crate = "::jwt_compact::__private::serde")]
+                    struct #helper<'__a> {
+                        #(#fields,)*
+                    }
+
+                    #serde::Serialize::serialize(
+                        &#helper { #(#field_idents: &self.#field_idents,)* },
+                        serializer,
+                    )
+                }
+            }
+        }
+    }
+
+    fn derive_deserialize(&self) -> TokenStream2 {
+        let ident = &self.ident;
+        let serde = quote!(::jwt_compact::__private::serde);
+        let helper = format_ident!("__{}Owned", ident, span = Span::call_site());
+        let field_idents: Vec<_> = self.fields.iter().map(|field| &field.ident).collect();
+        let fields = self.fields.iter().map(|field| {
+            let ident = &field.ident;
+            let ty = &field.ty;
+            let attrs = field.serde_attrs(false);
+            quote!(#attrs #ident: #ty)
+        });
+
+        quote! {
+            impl<'de> #serde::Deserialize<'de> for #ident {
+                fn deserialize<__D: #serde::Deserializer<'de>>(
+                    deserializer: __D,
+                ) -> ::core::result::Result<Self, __D::Error> {
+                    #[derive(#serde::Deserialize)]
+                    #[serde(crate = "::jwt_compact::__private::serde")]
+                    struct #helper {
+                        #(#fields,)*
+                    }
+
+                    let claims = <#helper as #serde::Deserialize<'de>>::deserialize(deserializer)?;
+                    ::core::result::Result::Ok(Self {
+                        #(#field_idents: claims.#field_idents,)*
+                    })
+                }
+            }
+        }
+    }
+
+    fn derive_validate(&self) -> TokenStream2 {
+        let ident = &self.ident;
+        let validations = self.fields.iter().map(ClaimField::validation);
+
+        quote! {
+            impl #ident {
+                /// Validates these claims according to `#[claim(..)]` attributes on the fields.
+                ///
+                /// # Errors
+                ///
+                /// Returns an error if a required claim is missing or a claim has
+                /// an unexpected value.
+                pub fn validate(
+                    &self,
+                ) -> ::core::result::Result<(), ::jwt_compact::presets::PresetError> {
+                    #({ #validations })*
+                    ::core::result::Result::Ok(())
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,106 @@
+//! Tests for the `Claims` derive macro.
+
+use assert_matches::assert_matches;
+use jwt_compact::{
+    alg::{Hs256, Hs256Key},
+    prelude::*,
+    presets::PresetError,
+    Audience,
+};
+use serde_json::json;
+
+#[derive(Debug, Clone, PartialEq, jwt_compact::Claims)]
+struct ApiClaims {
+    #[claim(rename = "sub")]
+    subject: String,
+    #[claim(rename = "iss", issuer = "https://auth.example.com/")]
+    issuer: String,
+    #[claim(rename = "aud", required, audience = "api://orders")]
+    audience: Option<Audience>,
+    #[claim(rename = "azp", audience = "client")]
+    authorized_party: Option<String>,
+    scope: Option<String>,
+    r#type: u8,
+}
+
+fn claims() -> ApiClaims {
+    ApiClaims {
+        subject: "alice".to_owned(),
+        issuer: "https://auth.example.com/".to_owned(),
+        audience: Some(Audience::Many(vec![
+            "api://users".to_owned(),
+            "api://orders".to_owned(),
+        ])),
+        authorized_party: None,
+        scope: None,
+        r#type: 1,
+    }
+}
+
+#[test]
+fn serializing_claims() {
+    let claims = claims();
+    let json = serde_json::to_value(&claims).unwrap();
+    assert_eq!(
+        json,
+        json!({
+            "sub": "alice",
+            "iss": "https://auth.example.com/",
+            "aud": ["api://users", "api://orders"],
+            "type": 1,
+        })
+    );
+
+    let restored: ApiClaims = serde_json::from_value(json).unwrap();
+    assert_eq!(restored, claims);
+}
+
+#[test]
+fn deserializing_claims_with_missing_fields() {
+    let json = json!({ "sub": "alice", "iss": "https://auth.example.com/", "type": 0 });
+    let claims: ApiClaims = serde_json::from_value(json).unwrap();
+    assert_eq!(claims.audience, None);
+    assert_eq!(claims.scope, None);
+
+    let json = json!({ "iss": "https://auth.example.com/", "type": 0 });
+    let err = serde_json::from_value::<ApiClaims>(json).unwrap_err();
+    assert!(err.to_string().contains("missing field `sub`"), "{err}");
+}
+
+#[test]
+fn validating_claims() {
+    claims().validate().unwrap();
+
+    let mut claims = claims();
+    claims.authorized_party = Some("client".to_owned());
+    claims.validate().unwrap();
+
+    claims.authorized_party = Some("other-client".to_owned());
+    let err = claims.validate().unwrap_err();
+    assert_matches!(err, PresetError::ClaimMismatch("azp"));
+
+    claims.authorized_party = None;
+    claims.audience = Some("api://users".into());
+    let err = claims.validate().unwrap_err();
+    assert_matches!(err, PresetError::ClaimMismatch("aud"));
+
+    claims.audience = None;
+    let err = claims.validate().unwrap_err();
+    assert_matches!(err, PresetError::MissingClaim("aud"));
+
+    claims.audience = Some("api://orders".into());
+    claims.issuer = "https://evil.example.com/".to_owned();
+    let err = claims.validate().unwrap_err();
+    assert_matches!(err, PresetError::ClaimMismatch("iss"));
+}
+
+#[test]
+fn using_derived_claims_in_token() {
+    let key = Hs256Key::new(b"super_secret_key_donut_steel");
+    let claims = Claims::new(claims());
+    let token = Hs256.token(&Header::empty(), &claims, &key).unwrap();
+    let token = UntrustedToken::new(&token).unwrap();
+    let token = Hs256.validator::<ApiClaims>(&key).validate(&token).unwrap();
+    assert_eq!(token.claims().custom, claims.custom);
+    token.claims().custom.validate().unwrap();
+}
@@ -6,7 +6,7 @@
 extern crate alloc;
 
 use anyhow::anyhow;
-use chrono::{DateTime, Duration, TimeZone, Utc};
+use chrono::Duration;
 use cortex_m_rt::entry;
 use cortex_m_semihosting::{debug, hprintln, syscall};
 use embedded_alloc::Heap;
@@ -22,7 +22,7 @@ use jwt_compact::alg::Rsa;
 use jwt_compact::{
     alg::{Hs256, Hs384, Hs512, SigningKey, VerifyingKey},
     prelude::*,
-    Algorithm,
+    Algorithm, Timestamp,
 };
 
 #[global_allocator]
@@ -56,9 +56,9 @@ mod rsa_helpers {
 }
 
 /// Gets current time via a semihosting syscall.
-fn now() -> DateTime<Utc> {
+fn now() -> Timestamp {
     let epoch_seconds = unsafe { syscall!(TIME) };
-    Utc.timestamp_opt(epoch_seconds as i64, 0).unwrap()
+    Timestamp::from_secs(epoch_seconds as i64)
 }
 
 /// Sample token claims.
@@ -89,8 +89,7 @@ impl TokenChecker {
     ) -> anyhow::Result<&'a SampleClaims> {
         Ok(&token
             .claims()
-            .validate_expiration(&self.time_options)
-            .map_err(|err| anyhow!(err))?
+            .validate_expiration(&self.time_options)?
             .custom)
     }
 
@@ -100,11 +99,10 @@ impl TokenChecker {
         token: &str,
         verifying_key: &T::VerifyingKey,
     ) -> anyhow::Result<SampleClaims> {
-        let token = UntrustedToken::new(token).map_err(|err| anyhow!(err))?;
+        let token = UntrustedToken::new(token)?;
         let token = alg
             .validator::<SampleClaims>(verifying_key)
-            .validate(&token)
-            .map_err(|err| anyhow!(err))?;
+            .validate(&token)?;
         let claims = self.extract_claims(&token)?;
         Ok(claims.to_owned())
     }
@@ -118,10 +116,8 @@ impl TokenChecker {
         let claims = Claims::new(claims)
             .set_duration_and_issuance(&self.time_options, Duration::try_minutes(10).unwrap());
 
-        let token = alg
-            .token(&Header::empty(), &claims, signing_key)
-            .map_err(|err| anyhow!(err))?;
-        Ok(token)
+        let token = alg.token(&Header::empty(), &claims, signing_key)?;
+        Ok(token.into())
     }
 
     fn roundtrip_alg<T>(&self, signing_key: &[u8], verifying_key: &[u8]) -> anyhow::Result<()>
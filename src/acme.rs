@@ -0,0 +1,315 @@
+//! JWS helpers for the [ACME protocol][RFC 8555].
+//!
+//! ACME requests are JWS objects in the flattened JSON serialization with `nonce` and `url`
+//! fields in the protected header. The key is identified either by the `jwk` field
+//! (for requests made before the account is created, such as `newAccount`) or by the `kid`
+//! field containing the account URL (for all other requests). POST-as-GET requests
+//! have an empty payload.
+//!
+//! [RFC 8555]: https://www.rfc-editor.org/rfc/rfc8555.html
+//!
+//! # Examples
+//!
+//! ```
+//! # use jwt_compact::alg::{Hs256, Hs256Key};
+//! use jwt_compact::acme::AcmeSigner;
+//! # use serde_json::json;
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! # // ACME forbids MAC algorithms; `HS256` is only used here to keep the example short.
+//! # let key = Hs256Key::new(b"super_secret_key_donut_steel");
+//! let signer = AcmeSigner::new(&Hs256, &key)
+//!     .with_account_url("https://example.com/acme/acct/evOfKhNU60wg");
+//! let request = signer.sign(
+//!     "https://example.com/acme/new-order",
+//!     "5XJ1L3lEkMG7tR6pA00clA",
+//!     &json!({ "identifiers": [{ "type": "dns", "value": "www.example.org" }] }),
+//! )?;
+//! let body = serde_json::to_string(&request)?;
+//! // `body` can be sent with the `application/jose+json` content type.
+//!
+//! let post_as_get = signer.post_as_get(
+//!     "https://example.com/acme/order/TOlocE8rfgo",
+//!     "IXVHDyxIRGcTE0VSblhPzw",
+//! )?;
+//! assert_eq!(post_as_get.payload, "");
+//! # Ok(())
+//! # }
+//! ```
+
+use base64ct::{Base64UrlUnpadded, Encoding};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+use core::fmt;
+
+use crate::{
+    alg::SigningKey,
+    alloc::{Cow, String, ToOwned, Vec},
+    jwk::JsonWebKey,
+    Algorithm, AlgorithmSignature,
+};
+
+/// Content type of ACME requests.
+pub const CONTENT_TYPE: &str = "application/jose+json";
+
+/// Errors that can occur when creating ACME requests.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum AcmeError {
+    /// Protected header or payload cannot be serialized.
+    Serialization(serde_json::Error),
+    /// The `jwk` header field was requested for a symmetric key. ACME only allows
+    /// asymmetric signature algorithms.
+    SymmetricKey,
+}
+
+impl fmt::Display for AcmeError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Serialization(err) => write!(formatter, "cannot serialize JWS part: {err}"),
+            Self::SymmetricKey => formatter.write_str("symmetric keys cannot be used in ACME"),
+        }
+    }
+}
+
+impl core::error::Error for AcmeError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::Serialization(err) => Some(err),
+            Self::SymmetricKey => None,
+        }
+    }
+}
+
+/// JWS in the flattened JSON serialization, as used in ACME request bodies.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FlattenedJws {
+    /// Base64url-encoded protected header.
+    pub protected: String,
+    /// Base64url-encoded payload. Empty for POST-as-GET requests.
+    pub payload: String,
+    /// Base64url-encoded signature.
+    pub signature: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ProtectedHeader<'a> {
+    alg: Cow<'static, str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    jwk: Option<&'a JsonWebKey<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    kid: Option<&'a str>,
+    nonce: &'a str,
+    url: &'a str,
+}
+
+/// Signer of ACME requests.
+///
+/// By default, the signer identifies the key using the `jwk` header field. After the account
+/// is created, [`Self::with_account_url()`] should be used to switch to the `kid` field.
+#[derive(Debug)]
+pub struct AcmeSigner<'a, A: Algorithm> {
+    algorithm: &'a A,
+    signing_key: &'a A::SigningKey,
+    account_url: Option<String>,
+}
+
+impl<'a, A: Algorithm> AcmeSigner<'a, A> {
+    /// Creates a signer with the specified algorithm and signing key.
+    pub fn new(algorithm: &'a A, signing_key: &'a A::SigningKey) -> Self {
+        Self {
+            algorithm,
+            signing_key,
+            account_url: None,
+        }
+    }
+
+    /// Sets the account URL, which will be used as the `kid` header field.
+    #[must_use]
+    pub fn with_account_url(mut self, account_url: impl Into<String>) -> Self {
+        self.account_url = Some(account_url.into());
+        self
+    }
+
+    /// Returns the account URL, if it was set.
+    pub fn account_url(&self) -> Option<&str> {
+        self.account_url.as_deref()
+    }
+}
+
+impl<A: Algorithm> AcmeSigner<'_, A>
+where
+    A::SigningKey: SigningKey<A>,
+    for<'k> JsonWebKey<'k>: From<&'k A::VerifyingKey>,
+{
+    /// Returns the public JWK corresponding to the signing key.
+    pub fn jwk(&self) -> Result<JsonWebKey<'static>, AcmeError> {
+        let verifying_key = self.signing_key.to_verifying_key();
+        let jwk = JsonWebKey::from(&verifying_key);
+        if matches!(jwk, JsonWebKey::Symmetric { .. }) {
+            return Err(AcmeError::SymmetricKey);
+        }
+        // Convert the JWK to an owned form by roundtripping it through JSON.
+        let jwk = serde_json::to_value(&jwk).map_err(AcmeError::Serialization)?;
+        serde_json::from_value(jwk).map_err(AcmeError::Serialization)
+    }
+
+    /// Computes the key authorization for a challenge `token` as per [RFC 8555, Section 8.1].
+    ///
+    /// [RFC 8555, Section 8.1]: https://www.rfc-editor.org/rfc/rfc8555.html#section-8.1
+    pub fn key_authorization(&self, token: &str) -> Result<String, AcmeError> {
+        let thumbprint = self.jwk()?.thumbprint::<Sha256>();
+        let mut authorization = token.to_owned();
+        authorization.push('.');
+        authorization.push_str(&Base64UrlUnpadded::encode_string(&thumbprint));
+        Ok(authorization)
+    }
+
+    /// Signs a request with the specified JSON `payload` to the `url` using the `nonce`
+    /// obtained from the ACME server.
+    pub fn sign(
+        &self,
+        url: &str,
+        nonce: &str,
+        payload: &impl Serialize,
+    ) -> Result<FlattenedJws, AcmeError> {
+        let payload = serde_json::to_vec(payload).map_err(AcmeError::Serialization)?;
+        self.sign_bytes(url, nonce, &payload)
+    }
+
+    /// Signs a POST-as-GET request (i.e., a request with an empty payload) to the `url`.
+    pub fn post_as_get(&self, url: &str, nonce: &str) -> Result<FlattenedJws, AcmeError> {
+        self.sign_bytes(url, nonce, &[])
+    }
+
+    fn sign_bytes(
+        &self,
+        url: &str,
+        nonce: &str,
+        payload: &[u8],
+    ) -> Result<FlattenedJws, AcmeError> {
+        let jwk = if self.account_url.is_none() {
+            Some(self.jwk()?)
+        } else {
+            None
+        };
+        let header = ProtectedHeader {
+            alg: self.algorithm.name(),
+            jwk: jwk.as_ref(),
+            kid: self.account_url.as_deref(),
+            nonce,
+            url,
+        };
+        let header = serde_json::to_vec(&header).map_err(AcmeError::Serialization)?;
+
+        let protected = Base64UrlUnpadded::encode_string(&header);
+        let payload = if payload.is_empty() {
+            String::new()
+        } else {
+            Base64UrlUnpadded::encode_string(payload)
+        };
+        let mut signing_input = Vec::with_capacity(protected.len() + 1 + payload.len());
+        signing_input.extend_from_slice(protected.as_bytes());
+        signing_input.push(b'.');
+        signing_input.extend_from_slice(payload.as_bytes());
+
+        let signature = self.algorithm.sign(self.signing_key, &signing_input);
+        Ok(FlattenedJws {
+            protected,
+            payload,
+            signature: Base64UrlUnpadded::encode_string(&signature.as_bytes()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::{
+        alg::{Hs256, Hs256Key},
+        alloc::format,
+    };
+
+    fn decode_json(encoded: &str) -> Value {
+        let bytes = Base64UrlUnpadded::decode_vec(encoded).unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[test]
+    fn signing_request_with_account_url() {
+        let key = Hs256Key::new(b"super_secret_key_donut_steel");
+        let signer = AcmeSigner::new(&Hs256, &key).with_account_url("https://example.com/acct/1");
+        let jws = signer
+            .sign(
+                "https://example.com/new-order",
+                "nonce",
+                &json!({ "test": 1 }),
+            )
+            .unwrap();
+
+        assert_eq!(
+            decode_json(&jws.protected),
+            json!({
+                "alg": "HS256",
+                "kid": "https://example.com/acct/1",
+                "nonce": "nonce",
+                "url": "https://example.com/new-order",
+            })
+        );
+        assert_eq!(decode_json(&jws.payload), json!({ "test": 1 }));
+
+        let signing_input = format!("{}.{}", jws.protected, jws.payload);
+        let signature = Base64UrlUnpadded::decode_vec(&jws.signature).unwrap();
+        let signature = <Hs256 as Algorithm>::Signature::try_from_slice(&signature).unwrap();
+        assert!(Hs256.verify_signature(&signature, &key, signing_input.as_bytes()));
+
+        let post_as_get = signer
+            .post_as_get("https://example.com/order/1", "nonce")
+            .unwrap();
+        assert_eq!(post_as_get.payload, "");
+        let serialized = serde_json::to_value(&post_as_get).unwrap();
+        assert_eq!(serialized["payload"], "");
+    }
+
+    #[test]
+    fn jwk_is_rejected_for_symmetric_keys() {
+        let key = Hs256Key::new(b"super_secret_key_donut_steel");
+        let signer = AcmeSigner::new(&Hs256, &key);
+        let err = signer
+            .sign("https://example.com/new-account", "nonce", &json!({}))
+            .unwrap_err();
+        assert_matches!(err, AcmeError::SymmetricKey);
+    }
+
+    #[cfg(feature = "es256k")]
+    #[test]
+    fn signing_request_with_jwk() {
+        use crate::alg::Es256k;
+        use secp256k1::SecretKey;
+
+        let signing_key = SecretKey::from_slice(&[1; 32]).unwrap();
+        let es256k = Es256k::default();
+        let signer = AcmeSigner::new(&es256k, &signing_key);
+        let jws = signer
+            .sign(
+                "https://example.com/new-account",
+                "nonce",
+                &json!({ "termsOfServiceAgreed": true }),
+            )
+            .unwrap();
+
+        let header = decode_json(&jws.protected);
+        assert_eq!(header["alg"], "ES256K");
+        assert_eq!(header["jwk"]["kty"], "EC");
+        assert!(header["jwk"].get("d").is_none());
+        assert!(header.get("kid").is_none());
+
+        let key_authorization = signer.key_authorization("token").unwrap();
+        assert!(key_authorization.starts_with("token."));
+        assert_eq!(key_authorization.len(), "token.".len() + 43);
+    }
+}
@@ -1,12 +1,98 @@
 //! Implementations of JWT signing / verification algorithms. Also contains generic traits
 //! for signing and verifying keys.
+//!
+//! # Zeroization
+//!
+//! Secret key material is zeroized when it is dropped:
+//!
+//! - HMAC keys ([`Hs256Key`] etc.) and owned [`SecretBytes`] are zeroized by this crate.
+//!   Temporary buffers with secret bytes produced during key conversions (e.g., to JWK or PEM)
+//!   are zeroized as well.
+//! - RSA, ECDSA (`p256` / `k256` backends) and Ed25519 (`ed25519-compact` and `exonum-crypto`
+//!   backends) signing keys are zeroized by the corresponding crypto backends.
+//! - `ed25519-dalek` signing keys are zeroized if the `zeroize` crate feature is enabled
+//!   (it is enabled by default).
+//! - Signing keys in the `secp256k1` backend (the `es256k` feature) are **not** zeroized,
+//!   since they are `Copy` types.
 
 use core::fmt;
 
-use crate::{alloc::Cow, Algorithm};
+use crate::{
+    alloc::{Cow, Vec},
+    Algorithm, PrepareVerifyingKey,
+};
+
+/// Implements [`PrepareVerifyingKey`] for an algorithm which verifying keys are already stored
+/// in a form ready for verification, so that preparing a key amounts to cloning it.
+#[cfg(any(
+    feature = "exonum-crypto",
+    feature = "ed25519-dalek",
+    feature = "ed25519-compact",
+    feature = "es256k",
+    feature = "k256",
+    feature = "p256",
+    feature = "rsa"
+))]
+macro_rules! impl_prepare_verifying_key {
+    (impl<$($param:ident),*> $alg:ty where $($bounds:tt)*) => {
+        impl<$($param),*> $crate::PrepareVerifyingKey for $alg
+        where
+            $($bounds)*
+        {
+            type PreparedKey = <Self as $crate::Algorithm>::VerifyingKey;
 
+            fn prepare_verifying_key(
+                &self,
+                verifying_key: &Self::VerifyingKey,
+            ) -> Self::PreparedKey {
+                verifying_key.clone()
+            }
+
+            fn verify_prepared(
+                &self,
+                signature: &Self::Signature,
+                prepared_key: &Self::PreparedKey,
+                message: &[u8],
+            ) -> bool {
+                $crate::Algorithm::verify_signature(self, signature, prepared_key, message)
+            }
+        }
+    };
+    ($alg:ty) => {
+        impl_prepare_verifying_key!(impl<> $alg where);
+    };
+}
+
+mod ecdsa;
 mod generic;
+#[cfg(feature = "hs")]
 mod hmacs;
+#[cfg(feature = "sign")]
+mod remote;
+// Remote signing backends.
+#[cfg(all(
+    feature = "apple-platforms",
+    any(target_os = "macos", target_os = "ios")
+))]
+mod apple_keychain;
+#[cfg(feature = "aws-kms")]
+mod aws_kms;
+#[cfg(feature = "azure-key-vault")]
+mod azure_key_vault;
+#[cfg(feature = "gcp-kms")]
+mod gcp_kms;
+#[cfg(feature = "keyring")]
+mod os_keyring;
+#[cfg(feature = "pkcs11")]
+mod pkcs11;
+#[cfg(feature = "ssh-agent")]
+mod ssh_agent;
+#[cfg(feature = "tpm")]
+mod tpm;
+#[cfg(feature = "vault-transit")]
+mod vault_transit;
+#[cfg(feature = "yubikey")]
+mod yubikey_piv;
 // Alternative ES256K implementations.
 #[cfg(feature = "secp256k1")]
 mod es256k;
@@ -26,6 +112,23 @@ mod p256;
 #[cfg(feature = "rsa")]
 mod rsa;
 
+#[cfg(all(
+    feature = "apple-platforms",
+    any(target_os = "macos", target_os = "ios")
+))]
+#[cfg_attr(docsrs, doc(cfg(feature = "apple-platforms")))]
+pub use self::apple_keychain::{AppleKeychain, AppleKeychainError, AppleKeychainKey};
+#[cfg(feature = "aws-kms")]
+#[cfg_attr(docsrs, doc(cfg(feature = "aws-kms")))]
+pub use self::aws_kms::{AwsKms, AwsKmsError, AwsKmsKey};
+#[cfg(feature = "azure-key-vault")]
+#[cfg_attr(docsrs, doc(cfg(feature = "azure-key-vault")))]
+pub use self::azure_key_vault::{
+    AzureKeyVault, AzureKeyVaultError, AzureKeyVaultKey, ManagedIdentity,
+};
+pub use self::ecdsa::{
+    ecdsa_signature_from_der, ecdsa_signature_to_der, EcdsaCurve, EcdsaSignatureError,
+};
 #[cfg(feature = "ed25519-compact")]
 pub use self::eddsa_compact::*;
 #[cfg(feature = "ed25519-dalek")]
@@ -34,18 +137,44 @@ pub use self::eddsa_dalek::Ed25519;
 pub use self::eddsa_sodium::Ed25519;
 #[cfg(feature = "es256k")]
 pub use self::es256k::Es256k;
-pub use self::generic::{SecretBytes, SigningKey, VerifyingKey};
+#[cfg(feature = "gcp-kms")]
+#[cfg_attr(docsrs, doc(cfg(feature = "gcp-kms")))]
+pub use self::gcp_kms::{GcpKms, GcpKmsError, GcpKmsKey};
+pub use self::generic::{GenerateKey, SecretBytes, SigningKey, VerifyingKey};
+#[cfg(feature = "hs")]
+#[cfg_attr(docsrs, doc(cfg(feature = "hs")))]
 pub use self::hmacs::*;
 #[cfg(feature = "k256")]
 pub use self::k256::Es256k;
+#[cfg(feature = "keyring")]
+#[cfg_attr(docsrs, doc(cfg(feature = "keyring")))]
+pub use self::os_keyring::{KeyringError, KeyringSecret, KeyringStore};
 #[cfg(feature = "p256")]
 pub use self::p256::Es256;
+#[cfg(feature = "pkcs11")]
+#[cfg_attr(docsrs, doc(cfg(feature = "pkcs11")))]
+pub use self::pkcs11::{Pkcs11, Pkcs11Error, Pkcs11Key, Pkcs11Sessions, SessionAction};
+#[cfg(feature = "sign")]
+#[cfg_attr(docsrs, doc(cfg(feature = "sign")))]
+pub use self::remote::{RawSignature, Remote, RemoteSigner};
 #[cfg(feature = "rsa")]
 #[cfg_attr(docsrs, doc(cfg(feature = "rsa")))]
 pub use self::rsa::{
     ModulusBits, ModulusBitsError, Rsa, RsaError, RsaParseError, RsaPrivateKey, RsaPublicKey,
     RsaSignature,
 };
+#[cfg(feature = "ssh-agent")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ssh-agent")))]
+pub use self::ssh_agent::{AgentConnection, SshAgent, SshAgentClient, SshAgentError, SshAgentKey};
+#[cfg(feature = "tpm")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tpm")))]
+pub use self::tpm::{ContextAction, Tpm, TpmContexts, TpmError, TpmKey};
+#[cfg(feature = "vault-transit")]
+#[cfg_attr(docsrs, doc(cfg(feature = "vault-transit")))]
+pub use self::vault_transit::{VaultTransit, VaultTransitError, VaultTransitKey};
+#[cfg(feature = "yubikey")]
+#[cfg_attr(docsrs, doc(cfg(feature = "yubikey")))]
+pub use self::yubikey_piv::{YubiKeyError, YubiKeyPiv, YubiKeyPivKey};
 
 /// Wrapper around keys allowing to enforce key strength requirements.
 ///
@@ -94,8 +223,7 @@ impl<T> fmt::Display for WeakKeyError<T> {
     }
 }
 
-#[cfg(feature = "std")]
-impl<T: fmt::Debug + 'static> std::error::Error for WeakKeyError<T> {}
+impl<T: fmt::Debug + 'static> core::error::Error for WeakKeyError<T> {}
 
 /// Wrapper around a JWT algorithm signalling that it supports only [`StrongKey`]s.
 ///
@@ -137,6 +265,7 @@ where
         self.0.name()
     }
 
+    #[cfg(feature = "sign")]
     fn sign(&self, signing_key: &Self::SigningKey, message: &[u8]) -> Self::Signature {
         self.0.sign(&signing_key.0, message)
     }
@@ -150,4 +279,34 @@ where
         self.0
             .verify_signature(signature, &verifying_key.0, message)
     }
+
+    fn verify_signatures(
+        &self,
+        verifying_key: &Self::VerifyingKey,
+        signatures: &[(Self::Signature, &[u8])],
+    ) -> Vec<bool> {
+        self.0.verify_signatures(&verifying_key.0, signatures)
+    }
+}
+
+#[allow(clippy::trait_duplication_in_bounds)] // false positive
+impl<T: PrepareVerifyingKey> PrepareVerifyingKey for StrongAlg<T>
+where
+    StrongKey<T::SigningKey>: TryFrom<T::SigningKey>,
+    StrongKey<T::VerifyingKey>: TryFrom<T::VerifyingKey>,
+{
+    type PreparedKey = T::PreparedKey;
+
+    fn prepare_verifying_key(&self, verifying_key: &Self::VerifyingKey) -> Self::PreparedKey {
+        self.0.prepare_verifying_key(&verifying_key.0)
+    }
+
+    fn verify_prepared(
+        &self,
+        signature: &Self::Signature,
+        prepared_key: &Self::PreparedKey,
+        message: &[u8],
+    ) -> bool {
+        self.0.verify_prepared(signature, prepared_key, message)
+    }
 }
@@ -0,0 +1,246 @@
+//! `ES256` signing using `SecKey` handles on Apple platforms (including keys held
+//! by the Secure Enclave) via the [`security_framework`] crate.
+
+use security_framework::{
+    item::{ItemClass, ItemSearchOptions, KeyClass, Reference, SearchResult},
+    key::{Algorithm as SecKeyAlgorithm, SecKey},
+};
+
+use core::fmt;
+
+use super::{ecdsa_signature_from_der, EcdsaCurve, RawSignature};
+use crate::{
+    alloc::{Box, Cow, String, ToOwned, ToString, Vec},
+    AsyncAlgorithm, Header, SignFuture,
+};
+
+/// `errSecItemNotFound` status code.
+const ITEM_NOT_FOUND: i32 = -25_300;
+
+/// Errors that can occur when signing with keys on Apple platforms.
+#[derive(Debug)]
+#[non_exhaustive]
+#[cfg_attr(docsrs, doc(cfg(feature = "apple-platforms")))]
+pub enum AppleKeychainError {
+    /// Error returned by a keychain operation.
+    Keychain(security_framework::base::Error),
+    /// Private key with the specified label was not found in the keychain.
+    KeyNotFound(String),
+    /// Error returned by `SecKeyCreateSignature` (e.g., if the key is not a P-256 private key,
+    /// or if the user has canceled authentication required by the key access control).
+    Signing {
+        /// Error code.
+        code: isize,
+        /// Human-readable error description.
+        description: String,
+    },
+    /// Security framework has returned a malformed signature.
+    MalformedSignature,
+}
+
+impl fmt::Display for AppleKeychainError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Keychain(err) => write!(formatter, "keychain error: {err}"),
+            Self::KeyNotFound(label) => {
+                write!(formatter, "private key with label `{label}` not found")
+            }
+            Self::Signing { code, description } => {
+                write!(formatter, "signing failed (code {code}): {description}")
+            }
+            Self::MalformedSignature => formatter.write_str("malformed ECDSA signature"),
+        }
+    }
+}
+
+impl std::error::Error for AppleKeychainError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Keychain(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<security_framework::base::Error> for AppleKeychainError {
+    fn from(err: security_framework::base::Error) -> Self {
+        Self::Keychain(err)
+    }
+}
+
+/// P-256 private key on an Apple platform. Used as a signing key for the [`AppleKeychain`]
+/// algorithm.
+///
+/// The key can be obtained from the keychain by its label, or wrapped from a [`SecKey`] handle
+/// (e.g., one generated in the Secure Enclave with [`GenerateKeyOptions`], or passed from Swift
+/// code via FFI). Secure Enclave keys never leave the enclave; signing operations may require
+/// user presence depending on the key access control.
+///
+/// [`GenerateKeyOptions`]: security_framework::key::GenerateKeyOptions
+#[derive(Clone)]
+#[cfg_attr(docsrs, doc(cfg(feature = "apple-platforms")))]
+pub struct AppleKeychainKey {
+    key: SecKey,
+    key_id: Option<String>,
+}
+
+impl fmt::Debug for AppleKeychainKey {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("AppleKeychainKey")
+            .field("key_id", &self.key_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl From<SecKey> for AppleKeychainKey {
+    fn from(key: SecKey) -> Self {
+        Self { key, key_id: None }
+    }
+}
+
+impl AppleKeychainKey {
+    /// Finds a private key with the specified label in the keychain.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the keychain search fails, or if the key is not found.
+    pub fn find_by_label(label: &str) -> Result<Self, AppleKeychainError> {
+        let results = ItemSearchOptions::new()
+            .class(ItemClass::key())
+            .key_class(KeyClass::private())
+            .label(label)
+            .load_refs(true)
+            .search()
+            .map_err(|err| {
+                if err.code() == ITEM_NOT_FOUND {
+                    AppleKeychainError::KeyNotFound(label.to_owned())
+                } else {
+                    err.into()
+                }
+            })?;
+
+        results
+            .into_iter()
+            .find_map(|result| match result {
+                SearchResult::Ref(Reference::Key(key)) => Some(Self::from(key)),
+                _ => None,
+            })
+            .ok_or_else(|| AppleKeychainError::KeyNotFound(label.to_owned()))
+    }
+
+    /// Sets the key ID (`kid`) for tokens signed with this key.
+    #[must_use]
+    pub fn with_key_id(mut self, key_id: impl Into<String>) -> Self {
+        self.key_id = Some(key_id.into());
+        self
+    }
+
+    /// Returns the wrapped key handle.
+    pub fn as_sec_key(&self) -> &SecKey {
+        &self.key
+    }
+
+    /// Returns the public key as an uncompressed SEC1-encoded point, which can be converted
+    /// to a verifying key for [`Es256`](crate::alg::Es256). Returns `None` if the public key
+    /// cannot be derived or exported.
+    pub fn public_key(&self) -> Option<Vec<u8>> {
+        let public_key = self.key.public_key()?;
+        Some(public_key.external_representation()?.to_vec())
+    }
+
+    /// Returns the key ID, if set.
+    pub fn key_id(&self) -> Option<&str> {
+        self.key_id.as_deref()
+    }
+
+    /// Returns a header with the [key ID](Self::key_id()) set (if any).
+    pub fn header(&self) -> Header {
+        let header = Header::empty();
+        match &self.key_id {
+            Some(key_id) => header.with_key_id(key_id.clone()),
+            None => header,
+        }
+    }
+}
+
+/// `ES256` signing algorithm using `SecKey` handles on Apple platforms (macOS and iOS).
+///
+/// Signing is performed by the Security framework, so it works with keys held
+/// by the Secure Enclave. DER-encoded signatures are converted to the JWS format.
+///
+/// This algorithm implements [`AsyncAlgorithm`] since signing may fail (e.g., if user presence
+/// is required and not confirmed). Note that `SecKeyCreateSignature` is blocking and is called
+/// when the signing future is polled; [`AppleKeychain::sign_blocking()`] can be used
+/// in synchronous contexts (e.g., in functions exported via FFI).
+///
+/// # Examples
+///
+/// ```no_run
+/// # use chrono::Duration;
+/// use jwt_compact::{
+///     alg::{AppleKeychain, AppleKeychainKey},
+///     prelude::*,
+///     AsyncAlgorithmExt,
+/// };
+///
+/// # async fn test_wrapper() -> anyhow::Result<()> {
+/// let key = AppleKeychainKey::find_by_label("com.example.device-key")?
+///     .with_key_id("device-key");
+/// let claims = Claims::new(serde_json::json!({ "sub": "device" }))
+///     .set_duration_and_issuance(&TimeOptions::default(), Duration::try_minutes(5).unwrap());
+/// let token = AppleKeychain::es256()
+///     .token_async(&key.header(), &claims, &key)
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(docsrs, doc(cfg(feature = "apple-platforms")))]
+pub struct AppleKeychain(());
+
+impl AppleKeychain {
+    /// `ES256` algorithm (ECDSA on the P-256 curve with SHA-256).
+    pub const fn es256() -> Self {
+        Self(())
+    }
+
+    /// Signs `message` with the key synchronously.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the Security framework returns an error or a malformed signature.
+    pub fn sign_blocking(
+        &self,
+        key: &AppleKeychainKey,
+        message: &[u8],
+    ) -> Result<RawSignature, AppleKeychainError> {
+        let der = key
+            .key
+            .create_signature(SecKeyAlgorithm::ECDSASignatureMessageX962SHA256, message)
+            .map_err(|err| AppleKeychainError::Signing {
+                code: err.code(),
+                description: err.description().to_string(),
+            })?;
+        let raw = ecdsa_signature_from_der(&der, EcdsaCurve::P256)
+            .map_err(|_| AppleKeychainError::MalformedSignature)?;
+        Ok(RawSignature::new(raw))
+    }
+}
+
+impl AsyncAlgorithm for AppleKeychain {
+    type SigningKey = AppleKeychainKey;
+    type Signature = RawSignature;
+
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Borrowed("ES256")
+    }
+
+    fn sign_async<'a>(
+        &'a self,
+        signing_key: &'a Self::SigningKey,
+        message: &'a [u8],
+    ) -> SignFuture<'a, Self::Signature> {
+        Box::pin(async move { Ok(self.sign_blocking(signing_key, message)?) })
+    }
+}
@@ -0,0 +1,308 @@
+//! `RS*` and `ES*` signing using asymmetric keys held by [AWS KMS](https://aws.amazon.com/kms/).
+
+use aws_sdk_kms::{
+    primitives::Blob,
+    types::{MessageType, SigningAlgorithmSpec},
+    Client,
+};
+use sha2::{Digest, Sha256, Sha384, Sha512};
+
+use core::fmt;
+
+use super::{
+    ecdsa_signature_from_der,
+    remote::{self, RawSignature},
+    EcdsaCurve,
+};
+use crate::{
+    alloc::{Box, Cow, Vec},
+    AsyncAlgorithm, SignFuture,
+};
+
+/// Errors that can occur when interacting with AWS KMS.
+#[derive(Debug)]
+#[non_exhaustive]
+#[cfg_attr(docsrs, doc(cfg(feature = "aws-kms")))]
+pub enum AwsKmsError {
+    /// Error calling the KMS API.
+    Kms(Box<aws_sdk_kms::Error>),
+    /// KMS response does not contain an expected field.
+    MissingField(&'static str),
+    /// Signature returned by KMS is malformed.
+    MalformedSignature,
+    /// Public key returned by KMS cannot be converted to the requested key type.
+    PublicKey(anyhow::Error),
+}
+
+impl fmt::Display for AwsKmsError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Kms(err) => write!(formatter, "error calling AWS KMS: {err}"),
+            Self::MissingField(field) => {
+                write!(
+                    formatter,
+                    "field `{field}` is missing in the AWS KMS response"
+                )
+            }
+            Self::MalformedSignature => formatter.write_str("AWS KMS returned malformed signature"),
+            Self::PublicKey(err) => write!(formatter, "cannot parse AWS KMS public key: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for AwsKmsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Kms(err) => Some(err.as_ref()),
+            Self::PublicKey(err) => Some(err.as_ref()),
+            Self::MissingField(_) | Self::MalformedSignature => None,
+        }
+    }
+}
+
+impl AwsKmsError {
+    fn kms(err: impl Into<aws_sdk_kms::Error>) -> Self {
+        Self::Kms(Box::new(err.into()))
+    }
+}
+
+/// Handle to an asymmetric AWS KMS key with the `SIGN_VERIFY` usage. Used as a signing key
+/// for the [`AwsKms`] algorithm; the private key never leaves KMS.
+///
+/// The KMS client should be configured by the caller, e.g., using the `aws-config` crate.
+#[derive(Debug, Clone)]
+#[cfg_attr(docsrs, doc(cfg(feature = "aws-kms")))]
+pub struct AwsKmsKey {
+    client: Client,
+    key_id: String,
+}
+
+impl AwsKmsKey {
+    /// Creates a key handle. `key_id` may be specified as a key ID, a key ARN, an alias name
+    /// or an alias ARN.
+    pub fn new(client: Client, key_id: impl Into<String>) -> Self {
+        Self {
+            client,
+            key_id: key_id.into(),
+        }
+    }
+
+    /// Returns the key ID as specified on creation.
+    pub fn key_id(&self) -> &str {
+        &self.key_id
+    }
+
+    /// Fetches the public key from KMS as a DER-encoded `SubjectPublicKeyInfo`.
+    ///
+    /// The public key can be cached indefinitely: KMS keys are immutable, and key rotation
+    /// is not supported for asymmetric keys.
+    pub async fn public_key_der(&self) -> Result<Vec<u8>, AwsKmsError> {
+        let output = self
+            .client
+            .get_public_key()
+            .key_id(&self.key_id)
+            .send()
+            .await
+            .map_err(AwsKmsError::kms)?;
+        let public_key = output
+            .public_key
+            .ok_or(AwsKmsError::MissingField("PublicKey"))?;
+        Ok(public_key.into_inner())
+    }
+
+    /// Fetches the RSA public key from KMS. The key can be used to verify tokens
+    /// with the [`Rsa`](crate::alg::Rsa) algorithm.
+    #[cfg(feature = "rsa")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "rsa")))]
+    pub async fn rsa_public_key(&self) -> Result<rsa::RsaPublicKey, AwsKmsError> {
+        let der = self.public_key_der().await?;
+        remote::rsa_public_key_from_der(&der).map_err(AwsKmsError::PublicKey)
+    }
+
+    /// Fetches the P-256 public key from KMS. The key can be used to verify tokens
+    /// with the [`Es256`](crate::alg::Es256) algorithm.
+    #[cfg(feature = "p256")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "p256")))]
+    pub async fn es256_public_key(&self) -> Result<p256::ecdsa::VerifyingKey, AwsKmsError> {
+        let der = self.public_key_der().await?;
+        remote::es256_public_key_from_der(&der).map_err(AwsKmsError::PublicKey)
+    }
+}
+
+/// Signing scheme supported by [`AwsKms`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Scheme {
+    Rs256,
+    Rs384,
+    Rs512,
+    Es256,
+    Es384,
+    Es512,
+}
+
+impl Scheme {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Rs256 => "RS256",
+            Self::Rs384 => "RS384",
+            Self::Rs512 => "RS512",
+            Self::Es256 => "ES256",
+            Self::Es384 => "ES384",
+            Self::Es512 => "ES512",
+        }
+    }
+
+    fn kms_algorithm(self) -> SigningAlgorithmSpec {
+        match self {
+            Self::Rs256 => SigningAlgorithmSpec::RsassaPkcs1V15Sha256,
+            Self::Rs384 => SigningAlgorithmSpec::RsassaPkcs1V15Sha384,
+            Self::Rs512 => SigningAlgorithmSpec::RsassaPkcs1V15Sha512,
+            Self::Es256 => SigningAlgorithmSpec::EcdsaSha256,
+            Self::Es384 => SigningAlgorithmSpec::EcdsaSha384,
+            Self::Es512 => SigningAlgorithmSpec::EcdsaSha512,
+        }
+    }
+
+    fn digest(self, message: &[u8]) -> Vec<u8> {
+        match self {
+            Self::Rs256 | Self::Es256 => Sha256::digest(message).to_vec(),
+            Self::Rs384 | Self::Es384 => Sha384::digest(message).to_vec(),
+            Self::Rs512 | Self::Es512 => Sha512::digest(message).to_vec(),
+        }
+    }
+
+    /// Returns the elliptic curve for ECDSA schemes.
+    fn ecdsa_curve(self) -> Option<EcdsaCurve> {
+        match self {
+            Self::Rs256 | Self::Rs384 | Self::Rs512 => None,
+            Self::Es256 => Some(EcdsaCurve::P256),
+            Self::Es384 => Some(EcdsaCurve::P384),
+            Self::Es512 => Some(EcdsaCurve::P521),
+        }
+    }
+}
+
+/// Signing algorithm using asymmetric AWS KMS keys (RSASSA-PKCS1-v1_5 and ECDSA).
+///
+/// The message digest is computed locally, so that tokens of any size can be signed.
+/// ECDSA signatures returned by KMS are converted from DER to the JWS format.
+///
+/// Since signing requires a network call, this algorithm implements [`AsyncAlgorithm`]
+/// rather than [`Algorithm`](crate::Algorithm). Tokens are verified with the corresponding
+/// local algorithm (e.g., [`Rsa`](crate::alg::Rsa) or [`Es256`](crate::alg::Es256)) using
+/// the public key fetched from KMS via [`AwsKmsKey`] methods, or obtained from a JWK set.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use chrono::Duration;
+/// use jwt_compact::{alg::{AwsKms, AwsKmsKey, Es256}, prelude::*, AsyncAlgorithmExt};
+/// # use serde_json::Value;
+///
+/// # async fn test_wrapper(client: aws_sdk_kms::Client) -> anyhow::Result<()> {
+/// // `client` is configured using the `aws-config` crate.
+/// let key = AwsKmsKey::new(client, "alias/token-signing");
+/// let claims = Claims::new(serde_json::json!({ "sub": "alice" }))
+///     .set_duration_and_issuance(&TimeOptions::default(), Duration::try_minutes(10).unwrap());
+/// let header = Header::empty().with_key_id("token-signing");
+/// let token = AwsKms::es256().token_async(&header, &claims, &key).await?;
+///
+/// // Tokens are verified locally.
+/// let verifying_key = key.es256_public_key().await?;
+/// let token = UntrustedToken::new(&token)?;
+/// let token = Es256.validator::<Value>(&verifying_key).validate(&token)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(docsrs, doc(cfg(feature = "aws-kms")))]
+pub struct AwsKms {
+    scheme: Scheme,
+}
+
+impl AwsKms {
+    /// `RS256` algorithm (RSASSA-PKCS1-v1_5 with SHA-256).
+    pub const fn rs256() -> Self {
+        Self {
+            scheme: Scheme::Rs256,
+        }
+    }
+
+    /// `RS384` algorithm (RSASSA-PKCS1-v1_5 with SHA-384).
+    pub const fn rs384() -> Self {
+        Self {
+            scheme: Scheme::Rs384,
+        }
+    }
+
+    /// `RS512` algorithm (RSASSA-PKCS1-v1_5 with SHA-512).
+    pub const fn rs512() -> Self {
+        Self {
+            scheme: Scheme::Rs512,
+        }
+    }
+
+    /// `ES256` algorithm (ECDSA on the P-256 curve with SHA-256).
+    pub const fn es256() -> Self {
+        Self {
+            scheme: Scheme::Es256,
+        }
+    }
+
+    /// `ES384` algorithm (ECDSA on the P-384 curve with SHA-384).
+    pub const fn es384() -> Self {
+        Self {
+            scheme: Scheme::Es384,
+        }
+    }
+
+    /// `ES512` algorithm (ECDSA on the P-521 curve with SHA-512).
+    pub const fn es512() -> Self {
+        Self {
+            scheme: Scheme::Es512,
+        }
+    }
+
+    async fn sign(&self, key: &AwsKmsKey, digest: Vec<u8>) -> Result<RawSignature, AwsKmsError> {
+        let output = key
+            .client
+            .sign()
+            .key_id(&key.key_id)
+            .message(Blob::new(digest))
+            .message_type(MessageType::Digest)
+            .signing_algorithm(self.scheme.kms_algorithm())
+            .send()
+            .await
+            .map_err(AwsKmsError::kms)?;
+        let signature = output
+            .signature
+            .ok_or(AwsKmsError::MissingField("Signature"))?
+            .into_inner();
+
+        let signature = if let Some(curve) = self.scheme.ecdsa_curve() {
+            ecdsa_signature_from_der(&signature, curve)
+                .map_err(|_| AwsKmsError::MalformedSignature)?
+        } else {
+            signature
+        };
+        Ok(RawSignature::new(signature))
+    }
+}
+
+impl AsyncAlgorithm for AwsKms {
+    type SigningKey = AwsKmsKey;
+    type Signature = RawSignature;
+
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Borrowed(self.scheme.name())
+    }
+
+    fn sign_async<'a>(
+        &'a self,
+        signing_key: &'a Self::SigningKey,
+        message: &'a [u8],
+    ) -> SignFuture<'a, Self::Signature> {
+        let digest = self.scheme.digest(message);
+        Box::pin(async move { Ok(self.sign(signing_key, digest).await?) })
+    }
+}
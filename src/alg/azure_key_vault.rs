@@ -0,0 +1,461 @@
+//! `RS256`, `PS256` and `ES256` signing using keys held by
+//! [Azure Key Vault](https://learn.microsoft.com/en-us/azure/key-vault/).
+
+use base64ct::{Base64UrlUnpadded, Encoding};
+use reqwest::{Client, Method, StatusCode};
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+
+use core::{fmt, future::Future};
+use std::{
+    sync::{Arc, Mutex, PoisonError},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use super::remote::{self, AccessTokenFn, RawSignature};
+use crate::{
+    alloc::{Box, Cow, String, ToOwned, Vec},
+    jwk::{JsonWebKey, JwkError},
+    AsyncAlgorithm, Header, SignFuture,
+};
+
+/// Version of the Key Vault REST API used by the backend.
+const API_VERSION: &str = "7.4";
+/// Resource (audience) of access tokens for Key Vault.
+const VAULT_RESOURCE: &str = "https://vault.azure.net";
+/// Default endpoint of the Azure Instance Metadata Service (IMDS) issuing managed identity tokens.
+const IMDS_ENDPOINT: &str = "http://169.254.169.254/metadata/identity/oauth2/token";
+/// Margin before access token expiration after which [`ManagedIdentity`] requests a new token.
+const REFRESH_MARGIN: Duration = Duration::from_secs(300);
+
+/// Errors that can occur when interacting with Azure Key Vault.
+#[derive(Debug)]
+#[non_exhaustive]
+#[cfg_attr(docsrs, doc(cfg(feature = "azure-key-vault")))]
+pub enum AzureKeyVaultError {
+    /// Error obtaining an access token.
+    AccessToken(anyhow::Error),
+    /// HTTP error calling the Key Vault API.
+    Http(reqwest::Error),
+    /// Key Vault API has returned an error response.
+    Api {
+        /// HTTP status of the response.
+        status: u16,
+        /// Error message from the response.
+        message: String,
+    },
+    /// Key Vault response does not contain an expected field, or the field is malformed.
+    InvalidResponse(&'static str),
+    /// Public key returned by Key Vault cannot be converted to the requested key type.
+    PublicKey(JwkError),
+}
+
+impl fmt::Display for AzureKeyVaultError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AccessToken(err) => write!(formatter, "cannot obtain access token: {err}"),
+            Self::Http(err) => write!(formatter, "error calling Azure Key Vault: {err}"),
+            Self::Api { status, message } => write!(
+                formatter,
+                "Azure Key Vault returned error (HTTP {status}): {message}"
+            ),
+            Self::InvalidResponse(field) => write!(
+                formatter,
+                "field `{field}` is missing or invalid in the Azure Key Vault response"
+            ),
+            Self::PublicKey(err) => {
+                write!(
+                    formatter,
+                    "cannot convert Azure Key Vault public key: {err}"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for AzureKeyVaultError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::AccessToken(err) => Some(err.as_ref()),
+            Self::Http(err) => Some(err),
+            Self::PublicKey(err) => Some(err),
+            Self::Api { .. } | Self::InvalidResponse(_) => None,
+        }
+    }
+}
+
+/// Sends a request with an optional JSON body and parses the JSON response, converting
+/// non-successful responses to errors.
+async fn call(
+    request: reqwest::RequestBuilder,
+    body: Option<&Value>,
+) -> Result<Value, AzureKeyVaultError> {
+    let request = if let Some(body) = body {
+        request
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body.to_string())
+    } else {
+        request
+    };
+    let response = request.send().await.map_err(AzureKeyVaultError::Http)?;
+    let status = response.status();
+    let body = response.bytes().await.map_err(AzureKeyVaultError::Http)?;
+
+    if status != StatusCode::OK {
+        let message = serde_json::from_slice::<Value>(&body)
+            .ok()
+            .and_then(|body| {
+                let error = &body["error"];
+                let message = error["message"].as_str().or(error.as_str())?;
+                Some(message.to_owned())
+            })
+            .unwrap_or_else(|| String::from_utf8_lossy(&body).into_owned());
+        return Err(AzureKeyVaultError::Api {
+            status: status.as_u16(),
+            message,
+        });
+    }
+    serde_json::from_slice(&body).map_err(|_| AzureKeyVaultError::InvalidResponse("body"))
+}
+
+#[derive(Debug)]
+struct CachedAccessToken {
+    token: String,
+    expires_on: SystemTime,
+}
+
+/// Access token provider for Key Vault using an [Azure managed identity] via the Instance
+/// Metadata Service (IMDS). Tokens are cached until they are within 5 minutes
+/// of their expiration; clones of the provider share the cache.
+///
+/// [Azure managed identity]: https://learn.microsoft.com/en-us/entra/identity/managed-identities-azure-resources/overview
+#[derive(Debug, Clone)]
+#[cfg_attr(docsrs, doc(cfg(feature = "azure-key-vault")))]
+pub struct ManagedIdentity {
+    client: Client,
+    endpoint: String,
+    client_id: Option<String>,
+    cached: Arc<Mutex<Option<CachedAccessToken>>>,
+}
+
+impl ManagedIdentity {
+    /// Creates a provider using the system-assigned managed identity.
+    pub fn new(client: Client) -> Self {
+        Self {
+            client,
+            endpoint: IMDS_ENDPOINT.to_owned(),
+            client_id: None,
+            cached: Arc::default(),
+        }
+    }
+
+    /// Uses the user-assigned managed identity with the specified client ID.
+    #[must_use]
+    pub fn with_client_id(mut self, client_id: impl Into<String>) -> Self {
+        self.client_id = Some(client_id.into());
+        self
+    }
+
+    /// Sets the token endpoint. By default, the IMDS endpoint is used.
+    #[must_use]
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = endpoint.into();
+        self
+    }
+
+    fn cached_token(&self, now: SystemTime) -> Option<String> {
+        let cached = self.cached.lock().unwrap_or_else(PoisonError::into_inner);
+        let cached = cached.as_ref()?;
+        (now + REFRESH_MARGIN < cached.expires_on).then(|| cached.token.clone())
+    }
+
+    /// Returns an access token for Key Vault, requesting a new one if necessary.
+    pub async fn access_token(&self) -> Result<String, AzureKeyVaultError> {
+        if let Some(token) = self.cached_token(SystemTime::now()) {
+            return Ok(token);
+        }
+
+        let mut query = vec![("api-version", "2018-02-01"), ("resource", VAULT_RESOURCE)];
+        if let Some(client_id) = &self.client_id {
+            query.push(("client_id", client_id));
+        }
+        let request = self
+            .client
+            .get(&self.endpoint)
+            .query(&query)
+            .header("Metadata", "true");
+        let response = call(request, None).await?;
+
+        let token = response["access_token"]
+            .as_str()
+            .ok_or(AzureKeyVaultError::InvalidResponse("access_token"))?;
+        // `expires_on` is a string with a Unix timestamp.
+        let expires_on = match &response["expires_on"] {
+            Value::String(timestamp) => timestamp.parse::<u64>().ok(),
+            value => value.as_u64(),
+        };
+        let expires_on = expires_on.ok_or(AzureKeyVaultError::InvalidResponse("expires_on"))?;
+
+        *self.cached.lock().unwrap_or_else(PoisonError::into_inner) = Some(CachedAccessToken {
+            token: token.to_owned(),
+            expires_on: UNIX_EPOCH + Duration::from_secs(expires_on),
+        });
+        Ok(token.to_owned())
+    }
+}
+
+/// Handle to a key in Azure Key Vault (including Managed HSM). Used as a signing key
+/// for the [`AzureKeyVault`] algorithm; the private key never leaves the vault.
+///
+/// Requests are authenticated with access tokens supplied by a caller-provided hook,
+/// or by a [`ManagedIdentity`]. The public key is fetched from the vault
+/// as a JWK once and then cached. The `reqwest` client must have a TLS backend enabled.
+#[derive(Clone)]
+#[cfg_attr(docsrs, doc(cfg(feature = "azure-key-vault")))]
+pub struct AzureKeyVaultKey {
+    client: Client,
+    key_url: String,
+    access_token: Arc<AccessTokenFn>,
+    public_key: Arc<Mutex<Option<JsonWebKey<'static>>>>,
+}
+
+impl fmt::Debug for AzureKeyVaultKey {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("AzureKeyVaultKey")
+            .field("key_url", &self.key_url)
+            .finish_non_exhaustive()
+    }
+}
+
+impl AzureKeyVaultKey {
+    /// Creates a key handle. `key_url` is the key identifier, i.e.,
+    /// `https://{vault}.vault.azure.net/keys/{name}/{version}`. The version should be specified;
+    /// otherwise, the latest key version will be used for signing, and the cached public key
+    /// may become stale after key rotation.
+    pub fn new<F, Fut>(client: Client, key_url: impl Into<String>, access_token: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = anyhow::Result<String>> + Send + 'static,
+    {
+        let mut key_url = key_url.into();
+        if key_url.ends_with('/') {
+            key_url.pop();
+        }
+        Self {
+            client,
+            key_url,
+            access_token: remote::access_token_fn(access_token),
+            public_key: Arc::default(),
+        }
+    }
+
+    /// Creates a key handle authenticated using the provided managed identity.
+    pub fn with_managed_identity(
+        client: Client,
+        key_url: impl Into<String>,
+        identity: ManagedIdentity,
+    ) -> Self {
+        Self::new(client, key_url, move || {
+            let identity = identity.clone();
+            async move { Ok(identity.access_token().await?) }
+        })
+    }
+
+    /// Returns the key identifier, which is used as the key ID (`kid`) of signed tokens.
+    pub fn key_id(&self) -> &str {
+        &self.key_url
+    }
+
+    /// Returns a header with the [key ID](Self::key_id()) set.
+    pub fn header(&self) -> Header {
+        Header::empty().with_key_id(self.key_url.clone())
+    }
+
+    async fn call(
+        &self,
+        method: Method,
+        url: String,
+        body: Option<Value>,
+    ) -> Result<Value, AzureKeyVaultError> {
+        let access_token = (self.access_token)()
+            .await
+            .map_err(AzureKeyVaultError::AccessToken)?;
+        let request = self
+            .client
+            .request(method, url)
+            .query(&[("api-version", API_VERSION)])
+            .bearer_auth(access_token);
+        call(request, body.as_ref()).await
+    }
+
+    /// Returns the public key as a JWK. The key is fetched from the vault on the first call
+    /// and cached afterwards. Vault-specific JWK fields (e.g., `key_ops`) are stripped,
+    /// and HSM key types (`RSA-HSM`, `EC-HSM`) are mapped to the standard ones.
+    pub async fn public_jwk(&self) -> Result<JsonWebKey<'static>, AzureKeyVaultError> {
+        let cached = self
+            .public_key
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+        if let Some(jwk) = cached {
+            return Ok(jwk);
+        }
+
+        let response = self.call(Method::GET, self.key_url.clone(), None).await?;
+        let jwk = Self::parse_jwk(&response["key"])?;
+        *self
+            .public_key
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = Some(jwk.clone());
+        Ok(jwk)
+    }
+
+    fn parse_jwk(key: &Value) -> Result<JsonWebKey<'static>, AzureKeyVaultError> {
+        const FIELDS: &[&str] = &["n", "e", "crv", "x", "y"];
+
+        let key_type = match key["kty"].as_str() {
+            Some("RSA" | "RSA-HSM") => "RSA",
+            Some("EC" | "EC-HSM") => "EC",
+            _ => return Err(AzureKeyVaultError::InvalidResponse("key.kty")),
+        };
+        let mut jwk: Map<String, Value> = FIELDS
+            .iter()
+            .filter_map(|&field| Some((field.to_owned(), key.get(field)?.clone())))
+            .collect();
+        jwk.insert("kty".to_owned(), key_type.into());
+        serde_json::from_value(Value::Object(jwk))
+            .map_err(|_| AzureKeyVaultError::InvalidResponse("key"))
+    }
+
+    /// Returns the verifying key (e.g., an [`RsaPublicKey`](crate::alg::RsaPublicKey)
+    /// or a P-256 `VerifyingKey`) converted from the [public JWK](Self::public_jwk()).
+    pub async fn verifying_key<K>(&self) -> Result<K, AzureKeyVaultError>
+    where
+        K: for<'a> TryFrom<&'a JsonWebKey<'a>, Error = JwkError>,
+    {
+        let jwk = self.public_jwk().await?;
+        K::try_from(&jwk).map_err(AzureKeyVaultError::PublicKey)
+    }
+}
+
+/// Signing scheme supported by [`AzureKeyVault`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Scheme {
+    Rs256,
+    Ps256,
+    Es256,
+}
+
+impl Scheme {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Rs256 => "RS256",
+            Self::Ps256 => "PS256",
+            Self::Es256 => "ES256",
+        }
+    }
+}
+
+/// Signing algorithm using keys held by Azure Key Vault. Supports `RS256`, `PS256`
+/// (RSA keys) and `ES256` (P-256 keys).
+///
+/// The message digest is computed locally. Since signing requires a network call,
+/// this algorithm implements [`AsyncAlgorithm`] rather than [`Algorithm`](crate::Algorithm).
+/// Tokens are verified locally with the corresponding algorithm (e.g., [`Rsa`](crate::alg::Rsa)
+/// or [`Es256`](crate::alg::Es256)) using the public key cached by [`AzureKeyVaultKey`].
+///
+/// # Examples
+///
+/// ```no_run
+/// # use chrono::Duration;
+/// use jwt_compact::{
+///     alg::{AzureKeyVault, AzureKeyVaultKey, Es256, ManagedIdentity},
+///     prelude::*,
+///     AsyncAlgorithmExt,
+/// };
+/// # use serde_json::Value;
+///
+/// # async fn test_wrapper() -> anyhow::Result<()> {
+/// let client = reqwest::Client::new();
+/// let key = AzureKeyVaultKey::with_managed_identity(
+///     client.clone(),
+///     "https://my-vault.vault.azure.net/keys/token-signing/0123456789abcdef",
+///     ManagedIdentity::new(client),
+/// );
+/// let claims = Claims::new(serde_json::json!({ "sub": "service" }))
+///     .set_duration_and_issuance(&TimeOptions::default(), Duration::try_minutes(10).unwrap());
+/// let token = AzureKeyVault::es256().token_async(&key.header(), &claims, &key).await?;
+///
+/// // Tokens are verified locally using the cached public key.
+/// let verifying_key: p256::ecdsa::VerifyingKey = key.verifying_key().await?;
+/// let token = UntrustedToken::new(&token)?;
+/// let token = Es256.validator::<Value>(&verifying_key).validate(&token)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(docsrs, doc(cfg(feature = "azure-key-vault")))]
+pub struct AzureKeyVault {
+    scheme: Scheme,
+}
+
+impl AzureKeyVault {
+    /// `RS256` algorithm (RSASSA-PKCS1-v1_5 with SHA-256).
+    pub const fn rs256() -> Self {
+        Self {
+            scheme: Scheme::Rs256,
+        }
+    }
+
+    /// `PS256` algorithm (RSASSA-PSS with SHA-256).
+    pub const fn ps256() -> Self {
+        Self {
+            scheme: Scheme::Ps256,
+        }
+    }
+
+    /// `ES256` algorithm (ECDSA on the P-256 curve with SHA-256).
+    pub const fn es256() -> Self {
+        Self {
+            scheme: Scheme::Es256,
+        }
+    }
+
+    async fn sign(
+        &self,
+        key: &AzureKeyVaultKey,
+        digest: Vec<u8>,
+    ) -> Result<RawSignature, AzureKeyVaultError> {
+        let url = format!("{}/sign", key.key_url);
+        let body = json!({
+            "alg": self.scheme.name(),
+            "value": Base64UrlUnpadded::encode_string(&digest),
+        });
+        let response = key.call(Method::POST, url, Some(body)).await?;
+        // Key Vault returns ECDSA signatures in the JWS format, so no conversion is necessary.
+        let signature = response["value"]
+            .as_str()
+            .and_then(|signature| Base64UrlUnpadded::decode_vec(signature).ok())
+            .ok_or(AzureKeyVaultError::InvalidResponse("value"))?;
+        Ok(RawSignature::new(signature))
+    }
+}
+
+impl AsyncAlgorithm for AzureKeyVault {
+    type SigningKey = AzureKeyVaultKey;
+    type Signature = RawSignature;
+
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Borrowed(self.scheme.name())
+    }
+
+    fn sign_async<'a>(
+        &'a self,
+        signing_key: &'a Self::SigningKey,
+        message: &'a [u8],
+    ) -> SignFuture<'a, Self::Signature> {
+        let digest = Sha256::digest(message).to_vec();
+        Box::pin(async move { Ok(self.sign(signing_key, digest).await?) })
+    }
+}
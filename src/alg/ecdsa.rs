@@ -0,0 +1,279 @@
+//! Conversions between DER-encoded and raw ECDSA signatures.
+
+use core::fmt;
+
+use crate::alloc::Vec;
+
+/// Elliptic curve used by an ECDSA-based JWS algorithm. Determines the size of the signature
+/// scalars in the raw `r || s` signature form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum EcdsaCurve {
+    /// NIST P-256 curve (used by `ES256`).
+    P256,
+    /// NIST P-384 curve (used by `ES384`).
+    P384,
+    /// NIST P-521 curve (used by `ES512`).
+    P521,
+    /// secp256k1 curve (used by `ES256K`).
+    Secp256k1,
+}
+
+impl EcdsaCurve {
+    /// Returns the curve used by the specified JWS algorithm, or `None` if the algorithm
+    /// is not ECDSA-based.
+    pub fn for_algorithm(alg: &str) -> Option<Self> {
+        Some(match alg {
+            "ES256" => Self::P256,
+            "ES384" => Self::P384,
+            "ES512" => Self::P521,
+            "ES256K" => Self::Secp256k1,
+            _ => return None,
+        })
+    }
+
+    /// Returns the byte length of the curve order, i.e., the length of each of the `r` and `s`
+    /// scalars in the raw signature form.
+    pub const fn scalar_len(self) -> usize {
+        match self {
+            Self::P256 | Self::Secp256k1 => 32,
+            Self::P384 => 48,
+            Self::P521 => 66,
+        }
+    }
+}
+
+/// Errors that can occur when converting ECDSA signatures between DER and raw forms.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum EcdsaSignatureError {
+    /// DER-encoded signature is malformed or has out-of-range scalars for the curve.
+    MalformedDer,
+    /// Raw signature has an unexpected length.
+    InvalidLength {
+        /// Expected signature length in bytes.
+        expected: usize,
+        /// Actual signature length in bytes.
+        actual: usize,
+    },
+}
+
+impl fmt::Display for EcdsaSignatureError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MalformedDer => formatter.write_str("malformed DER-encoded ECDSA signature"),
+            Self::InvalidLength { expected, actual } => write!(
+                formatter,
+                "invalid raw ECDSA signature length: expected {expected} bytes, got {actual}"
+            ),
+        }
+    }
+}
+
+impl core::error::Error for EcdsaSignatureError {}
+
+/// Converts a DER-encoded ECDSA signature (an ASN.1 `SEQUENCE` of two `INTEGER`s, as produced
+/// by OpenSSL and most KMSs / HSMs) into the fixed-size `r || s` form used by JWS.
+///
+/// # Errors
+///
+/// Returns an error if the signature is malformed or its scalars do not fit the `curve`.
+///
+/// # Examples
+///
+/// ```
+/// # use jwt_compact::alg::{ecdsa_signature_from_der, ecdsa_signature_to_der, EcdsaCurve};
+/// let raw = [[0x80; 32], [0x01; 32]].concat();
+/// let der = ecdsa_signature_to_der(&raw, EcdsaCurve::P256)?;
+/// // `r` gets a leading zero byte since its high bit is set.
+/// assert_eq!(der[..5], [0x30, 0x45, 0x02, 0x21, 0x00]);
+/// assert_eq!(ecdsa_signature_from_der(&der, EcdsaCurve::P256)?, raw);
+/// # Ok::<_, jwt_compact::alg::EcdsaSignatureError>(())
+/// ```
+pub fn ecdsa_signature_from_der(
+    der: &[u8],
+    curve: EcdsaCurve,
+) -> Result<Vec<u8>, EcdsaSignatureError> {
+    let scalar_len = curve.scalar_len();
+    let (tag, sequence, rest) = read_der_element(der).ok_or(EcdsaSignatureError::MalformedDer)?;
+    if tag != SEQUENCE_TAG || !rest.is_empty() {
+        return Err(EcdsaSignatureError::MalformedDer);
+    }
+
+    let mut raw = Vec::with_capacity(2 * scalar_len);
+    let mut remaining = sequence;
+    for _ in 0..2 {
+        let (tag, mut integer, rest) =
+            read_der_element(remaining).ok_or(EcdsaSignatureError::MalformedDer)?;
+        if tag != INTEGER_TAG {
+            return Err(EcdsaSignatureError::MalformedDer);
+        }
+        // Strip the leading zero byte ensuring that the integer is positive.
+        while integer.len() > scalar_len && integer.first() == Some(&0) {
+            integer = &integer[1..];
+        }
+        if integer.len() > scalar_len {
+            return Err(EcdsaSignatureError::MalformedDer);
+        }
+        raw.resize(raw.len() + scalar_len - integer.len(), 0);
+        raw.extend_from_slice(integer);
+        remaining = rest;
+    }
+
+    if remaining.is_empty() {
+        Ok(raw)
+    } else {
+        Err(EcdsaSignatureError::MalformedDer)
+    }
+}
+
+/// Converts an ECDSA signature in the `r || s` form used by JWS into the DER encoding
+/// (an ASN.1 `SEQUENCE` of two `INTEGER`s), e.g., to verify it with an external library.
+///
+/// # Errors
+///
+/// Returns an error if the signature length does not correspond to the `curve`.
+pub fn ecdsa_signature_to_der(
+    raw: &[u8],
+    curve: EcdsaCurve,
+) -> Result<Vec<u8>, EcdsaSignatureError> {
+    let scalar_len = curve.scalar_len();
+    if raw.len() != 2 * scalar_len {
+        return Err(EcdsaSignatureError::InvalidLength {
+            expected: 2 * scalar_len,
+            actual: raw.len(),
+        });
+    }
+
+    let mut contents = Vec::with_capacity(2 * scalar_len + 6);
+    for scalar in raw.chunks_exact(scalar_len) {
+        // Minimal encoding: strip leading zeros, but keep at least one byte.
+        let first_nonzero = scalar.iter().position(|&byte| byte != 0);
+        let scalar = &scalar[first_nonzero.unwrap_or(scalar_len - 1)..];
+        // Prepend a zero byte if the high bit is set, so that the integer is positive.
+        let needs_padding = scalar[0] & 0x80 != 0;
+        let len = scalar.len() + usize::from(needs_padding);
+        write_der_header(&mut contents, INTEGER_TAG, len);
+        if needs_padding {
+            contents.push(0);
+        }
+        contents.extend_from_slice(scalar);
+    }
+
+    let mut der = Vec::with_capacity(contents.len() + 3);
+    write_der_header(&mut der, SEQUENCE_TAG, contents.len());
+    der.extend_from_slice(&contents);
+    Ok(der)
+}
+
+const SEQUENCE_TAG: u8 = 0x30;
+const INTEGER_TAG: u8 = 0x02;
+
+/// Reads a single DER element, returning its tag, contents and the remaining bytes.
+fn read_der_element(bytes: &[u8]) -> Option<(u8, &[u8], &[u8])> {
+    let (&tag, bytes) = bytes.split_first()?;
+    let (&len_byte, mut bytes) = bytes.split_first()?;
+    let len = if len_byte < 0x80 {
+        usize::from(len_byte)
+    } else {
+        // Long form; ECDSA signatures never need more than 2 length bytes.
+        let len_len = usize::from(len_byte & 0x7f);
+        if len_len == 0 || len_len > 2 || bytes.len() < len_len {
+            return None;
+        }
+        let (len_bytes, rest) = bytes.split_at(len_len);
+        bytes = rest;
+        len_bytes
+            .iter()
+            .fold(0_usize, |acc, &byte| (acc << 8) | usize::from(byte))
+    };
+
+    if bytes.len() < len {
+        return None;
+    }
+    let (contents, rest) = bytes.split_at(len);
+    Some((tag, contents, rest))
+}
+
+/// Writes the tag and length of a DER element. Lengths of ECDSA signature elements
+/// are always less than 256 bytes.
+#[allow(clippy::cast_possible_truncation)] // lengths are checked
+fn write_der_header(buffer: &mut Vec<u8>, tag: u8, len: usize) {
+    debug_assert!(len < 256);
+    buffer.push(tag);
+    if len >= 0x80 {
+        buffer.push(0x81);
+    }
+    buffer.push(len as u8);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converting_der_signatures() {
+        // `r` with the leading zero byte, short `s`.
+        let der = [0x30, 0x08, 0x02, 0x03, 0x00, 0x80, 0x01, 0x02, 0x01, 0x05];
+        let raw = ecdsa_signature_from_der(&der, EcdsaCurve::P256).unwrap();
+        let mut expected = [0_u8; 64];
+        expected[30..32].copy_from_slice(&[0x80, 0x01]);
+        expected[63] = 5;
+        assert_eq!(raw, expected);
+
+        // Trailing data
+        let mut invalid = der.to_vec();
+        invalid.push(0);
+        let err = ecdsa_signature_from_der(&invalid, EcdsaCurve::P256).unwrap_err();
+        assert_eq!(err, EcdsaSignatureError::MalformedDer);
+        // Integer too long
+        let mut long = [0x30, 0x46, 0x02, 0x21, 0x01].to_vec();
+        long.extend_from_slice(&[0; 32]);
+        long.extend_from_slice(&[0x02, 0x21, 0x00]);
+        long.extend_from_slice(&[0; 32]);
+        let err = ecdsa_signature_from_der(&long, EcdsaCurve::P256).unwrap_err();
+        assert_eq!(err, EcdsaSignatureError::MalformedDer);
+        ecdsa_signature_from_der(&long, EcdsaCurve::P384).unwrap();
+        // Truncated data
+        let err = ecdsa_signature_from_der(&der[..5], EcdsaCurve::P256).unwrap_err();
+        assert_eq!(err, EcdsaSignatureError::MalformedDer);
+    }
+
+    #[test]
+    fn der_signature_roundtrip() {
+        for curve in [EcdsaCurve::P256, EcdsaCurve::P384, EcdsaCurve::P521] {
+            let scalar_len = curve.scalar_len();
+            let samples = [
+                [[0xff; 66], [0x7f; 66]].concat(),
+                [[0; 66], [0; 66]].concat(),
+                [[0x01; 66], [0x80; 66]].concat(),
+            ];
+            for sample in samples {
+                let raw = [&sample[..scalar_len], &sample[66..66 + scalar_len]].concat();
+                let der = ecdsa_signature_to_der(&raw, curve).unwrap();
+                assert_eq!(ecdsa_signature_from_der(&der, curve).unwrap(), raw);
+            }
+        }
+
+        let err = ecdsa_signature_to_der(&[0; 63], EcdsaCurve::Secp256k1).unwrap_err();
+        assert_eq!(
+            err,
+            EcdsaSignatureError::InvalidLength {
+                expected: 64,
+                actual: 63
+            }
+        );
+    }
+
+    #[cfg(feature = "p256")]
+    #[test]
+    fn der_signatures_are_compatible_with_p256() {
+        use p256::ecdsa::Signature;
+
+        let raw = [[0x80; 32], [0x01; 32]].concat();
+        let der = ecdsa_signature_to_der(&raw, EcdsaCurve::P256).unwrap();
+        let signature = Signature::from_der(&der).unwrap();
+        assert_eq!(signature.to_bytes().as_slice(), raw);
+        assert_eq!(signature.to_der().as_bytes(), der);
+    }
+}
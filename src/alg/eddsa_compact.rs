@@ -1,16 +1,25 @@
 //! `EdDSA` algorithm implementation using the `ed25519-compact` crate.
 
-use ed25519_compact::{KeyPair, Noise, PublicKey, SecretKey, Seed, Signature};
+#[cfg(feature = "sign")]
+use ed25519_compact::Noise;
+use ed25519_compact::{KeyPair, PublicKey, SecretKey, Seed, Signature};
 use rand_core::{CryptoRng, RngCore};
 
 use core::num::NonZeroUsize;
 
 use crate::{
-    alg::{SecretBytes, SigningKey, VerifyingKey},
+    alg::{GenerateKey, SecretBytes, SigningKey, VerifyingKey},
     alloc::Cow,
     jwk::{JsonWebKey, JwkError, KeyType},
     Algorithm, AlgorithmSignature, Renamed,
 };
+#[cfg(feature = "pem")]
+use crate::{
+    alloc::Vec,
+    pem::{self, asn1, PemError, PemFormat, PemKey},
+};
+#[cfg(feature = "pem")]
+use zeroize::Zeroizing;
 
 impl AlgorithmSignature for Signature {
     const LENGTH: Option<NonZeroUsize> = NonZeroUsize::new(Signature::BYTES);
@@ -60,6 +69,7 @@ impl Algorithm for Ed25519 {
         Cow::Borrowed("EdDSA")
     }
 
+    #[cfg(feature = "sign")]
     fn sign(&self, signing_key: &Self::SigningKey, message: &[u8]) -> Self::Signature {
         signing_key.sign(message, Some(Noise::default()))
     }
@@ -74,6 +84,8 @@ impl Algorithm for Ed25519 {
     }
 }
 
+impl_prepare_verifying_key!(Ed25519);
+
 impl VerifyingKey<Ed25519> for PublicKey {
     fn from_slice(raw: &[u8]) -> anyhow::Result<Self> {
         Self::from_slice(raw).map_err(|err| anyhow::anyhow!(err))
@@ -98,6 +110,14 @@ impl SigningKey<Ed25519> for SecretKey {
     }
 }
 
+impl GenerateKey<Ed25519> for SecretKey {
+    type Params = ();
+
+    fn generate_pair_with<R: CryptoRng + RngCore>(rng: &mut R, (): ()) -> (Self, PublicKey) {
+        Ed25519::generate(rng)
+    }
+}
+
 impl<'a> From<&'a PublicKey> for JsonWebKey<'a> {
     fn from(key: &'a PublicKey) -> JsonWebKey<'a> {
         JsonWebKey::KeyPair {
@@ -148,3 +168,35 @@ impl TryFrom<&JsonWebKey<'_>> for SecretKey {
         jwk.ensure_key_match(secret_key)
     }
 }
+
+#[cfg(feature = "pem")]
+impl PemKey for SecretKey {
+    const DEFAULT_FORMAT: PemFormat = PemFormat::Pkcs8;
+
+    fn decode_der(format: PemFormat, der: &[u8]) -> Result<Self, PemError> {
+        pem::expect_format(format, PemFormat::Pkcs8)?;
+        let seed = asn1::decode_ed25519_pkcs8(der)?;
+        Ok(KeyPair::from_seed(Seed::new(*seed)).sk)
+    }
+
+    fn encode_der(&self, format: PemFormat) -> Result<Zeroizing<Vec<u8>>, PemError> {
+        pem::expect_format(format, PemFormat::Pkcs8)?;
+        asn1::encode_ed25519_pkcs8(self.seed().as_ref())
+    }
+}
+
+#[cfg(feature = "pem")]
+impl PemKey for PublicKey {
+    const DEFAULT_FORMAT: PemFormat = PemFormat::Spki;
+
+    fn decode_der(format: PemFormat, der: &[u8]) -> Result<Self, PemError> {
+        pem::expect_format(format, PemFormat::Spki)?;
+        let public_key = asn1::decode_spki(der, &asn1::ED25519)?;
+        Self::from_slice(public_key).map_err(PemError::malformed)
+    }
+
+    fn encode_der(&self, format: PemFormat) -> Result<Zeroizing<Vec<u8>>, PemError> {
+        pem::expect_format(format, PemFormat::Spki)?;
+        asn1::encode_spki(asn1::ED25519, self.as_ref())
+    }
+}
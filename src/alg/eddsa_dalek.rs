@@ -1,18 +1,29 @@
 //! `EdDSA` algorithm implementation using the `ed25519-dalek` crate.
 
+#[cfg(feature = "sign")]
+use ed25519_dalek::Signer;
 use ed25519_dalek::{
-    SecretKey, Signature, Signer, Verifier, KEYPAIR_LENGTH, PUBLIC_KEY_LENGTH, SECRET_KEY_LENGTH,
+    SecretKey, Signature, Verifier, KEYPAIR_LENGTH, PUBLIC_KEY_LENGTH, SECRET_KEY_LENGTH,
     SIGNATURE_LENGTH,
 };
+use rand_core::{CryptoRng, RngCore};
+use zeroize::Zeroizing;
 
 use core::num::NonZeroUsize;
 
 use crate::{
-    alg::{SecretBytes, SigningKey, VerifyingKey},
+    alg::{GenerateKey, SecretBytes, SigningKey, VerifyingKey},
     alloc::Cow,
     jwk::{JsonWebKey, JwkError, KeyType},
     Algorithm, AlgorithmSignature, Renamed,
 };
+#[cfg(feature = "pem")]
+use crate::{
+    alloc::Vec,
+    pem::{self, PemError, PemFormat, PemKey},
+};
+#[cfg(feature = "pem")]
+use ed25519_dalek::pkcs8::{DecodePrivateKey, DecodePublicKey, EncodePrivateKey, EncodePublicKey};
 
 impl AlgorithmSignature for Signature {
     const LENGTH: Option<NonZeroUsize> = NonZeroUsize::new(SIGNATURE_LENGTH);
@@ -52,6 +63,7 @@ impl Algorithm for Ed25519 {
         Cow::Borrowed("EdDSA")
     }
 
+    #[cfg(feature = "sign")]
     fn sign(&self, signing_key: &Self::SigningKey, message: &[u8]) -> Self::Signature {
         signing_key.sign(message)
     }
@@ -66,6 +78,8 @@ impl Algorithm for Ed25519 {
     }
 }
 
+impl_prepare_verifying_key!(Ed25519);
+
 impl VerifyingKey<Ed25519> for ed25519_dalek::VerifyingKey {
     fn from_slice(raw: &[u8]) -> anyhow::Result<Self> {
         let raw = <&[u8; PUBLIC_KEY_LENGTH]>::try_from(raw).map_err(|err| {
@@ -96,7 +110,22 @@ impl SigningKey<Ed25519> for ed25519_dalek::SigningKey {
 
     fn as_bytes(&self) -> SecretBytes<'_> {
         // We return the expanded key for compatibility with other implementations
-        SecretBytes::owned(self.to_keypair_bytes().to_vec())
+        SecretBytes::copied_from(self.to_keypair_bytes())
+    }
+}
+
+impl GenerateKey<Ed25519> for ed25519_dalek::SigningKey {
+    type Params = ();
+
+    fn generate_pair_with<R: CryptoRng + RngCore>(
+        rng: &mut R,
+        (): (),
+    ) -> (Self, ed25519_dalek::VerifyingKey) {
+        let mut secret = Zeroizing::new(SecretKey::default());
+        rng.fill_bytes(secret.as_mut());
+        let signing_key = Self::from_bytes(&secret);
+        let verifying_key = signing_key.verifying_key();
+        (signing_key, verifying_key)
     }
 }
 
@@ -129,7 +158,7 @@ impl<'a> From<&'a ed25519_dalek::SigningKey> for JsonWebKey<'a> {
         JsonWebKey::KeyPair {
             curve: Cow::Borrowed("Ed25519"),
             x: Cow::Borrowed(signing_key.as_ref().as_bytes()),
-            secret: Some(SecretBytes::owned(signing_key.to_bytes().to_vec())),
+            secret: Some(SecretBytes::copied_from(signing_key.to_bytes())),
         }
     }
 }
@@ -150,3 +179,35 @@ impl TryFrom<&JsonWebKey<'_>> for ed25519_dalek::SigningKey {
         jwk.ensure_key_match(signing_key)
     }
 }
+
+#[cfg(feature = "pem")]
+impl PemKey for ed25519_dalek::SigningKey {
+    const DEFAULT_FORMAT: PemFormat = PemFormat::Pkcs8;
+
+    fn decode_der(format: PemFormat, der: &[u8]) -> Result<Self, PemError> {
+        pem::expect_format(format, PemFormat::Pkcs8)?;
+        <Self as DecodePrivateKey>::from_pkcs8_der(der).map_err(PemError::malformed)
+    }
+
+    fn encode_der(&self, format: PemFormat) -> Result<Zeroizing<Vec<u8>>, PemError> {
+        pem::expect_format(format, PemFormat::Pkcs8)?;
+        let document = EncodePrivateKey::to_pkcs8_der(self).map_err(PemError::malformed)?;
+        Ok(Zeroizing::new(document.as_bytes().to_vec()))
+    }
+}
+
+#[cfg(feature = "pem")]
+impl PemKey for ed25519_dalek::VerifyingKey {
+    const DEFAULT_FORMAT: PemFormat = PemFormat::Spki;
+
+    fn decode_der(format: PemFormat, der: &[u8]) -> Result<Self, PemError> {
+        pem::expect_format(format, PemFormat::Spki)?;
+        Self::from_public_key_der(der).map_err(PemError::malformed)
+    }
+
+    fn encode_der(&self, format: PemFormat) -> Result<Zeroizing<Vec<u8>>, PemError> {
+        pem::expect_format(format, PemFormat::Spki)?;
+        let document = self.to_public_key_der().map_err(PemError::malformed)?;
+        Ok(Zeroizing::new(document.as_bytes().to_vec()))
+    }
+}
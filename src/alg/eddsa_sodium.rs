@@ -1,19 +1,30 @@
 //! `EdDSA` algorithm implementation using the `exonum-crypto` crate.
 
 use anyhow::format_err;
+#[cfg(feature = "sign")]
+use exonum_crypto::sign;
 use exonum_crypto::{
-    gen_keypair_from_seed, sign, verify, PublicKey, SecretKey, Seed, Signature, PUBLIC_KEY_LENGTH,
+    gen_keypair_from_seed, verify, PublicKey, SecretKey, Seed, Signature, PUBLIC_KEY_LENGTH,
     SEED_LENGTH, SIGNATURE_LENGTH,
 };
+use rand_core::{CryptoRng, RngCore};
+use zeroize::Zeroize;
 
 use core::num::NonZeroUsize;
 
 use crate::{
-    alg::{SecretBytes, SigningKey, VerifyingKey},
+    alg::{GenerateKey, SecretBytes, SigningKey, VerifyingKey},
     alloc::Cow,
     jwk::{JsonWebKey, JwkError, KeyType},
     Algorithm, AlgorithmSignature, Renamed,
 };
+#[cfg(feature = "pem")]
+use crate::{
+    alloc::Vec,
+    pem::{self, asn1, PemError, PemFormat, PemKey},
+};
+#[cfg(feature = "pem")]
+use zeroize::Zeroizing;
 
 impl AlgorithmSignature for Signature {
     const LENGTH: Option<NonZeroUsize> = NonZeroUsize::new(SIGNATURE_LENGTH);
@@ -63,6 +74,7 @@ impl Algorithm for Ed25519 {
         Cow::Borrowed("EdDSA")
     }
 
+    #[cfg(feature = "sign")]
     fn sign(&self, signing_key: &Self::SigningKey, message: &[u8]) -> Self::Signature {
         sign(message, signing_key)
     }
@@ -77,6 +89,8 @@ impl Algorithm for Ed25519 {
     }
 }
 
+impl_prepare_verifying_key!(Ed25519);
+
 impl VerifyingKey<Ed25519> for PublicKey {
     fn from_slice(raw: &[u8]) -> anyhow::Result<Self> {
         Self::from_slice(raw).ok_or_else(|| format_err!("Invalid public key length"))
@@ -104,6 +118,18 @@ impl SigningKey<Ed25519> for SecretKey {
     }
 }
 
+impl GenerateKey<Ed25519> for SecretKey {
+    type Params = ();
+
+    fn generate_pair_with<R: CryptoRng + RngCore>(rng: &mut R, (): ()) -> (Self, PublicKey) {
+        let mut seed = [0_u8; SEED_LENGTH];
+        rng.fill_bytes(&mut seed);
+        let (verifying_key, signing_key) = gen_keypair_from_seed(&Seed::new(seed));
+        seed.zeroize();
+        (signing_key, verifying_key)
+    }
+}
+
 impl<'a> From<&'a PublicKey> for JsonWebKey<'a> {
     fn from(key: &'a PublicKey) -> JsonWebKey<'a> {
         JsonWebKey::KeyPair {
@@ -155,3 +181,36 @@ impl TryFrom<&JsonWebKey<'_>> for SecretKey {
         jwk.ensure_key_match(sk)
     }
 }
+
+#[cfg(feature = "pem")]
+impl PemKey for SecretKey {
+    const DEFAULT_FORMAT: PemFormat = PemFormat::Pkcs8;
+
+    fn decode_der(format: PemFormat, der: &[u8]) -> Result<Self, PemError> {
+        pem::expect_format(format, PemFormat::Pkcs8)?;
+        let seed = asn1::decode_ed25519_pkcs8(der)?;
+        Ok(gen_keypair_from_seed(&Seed::new(*seed)).1)
+    }
+
+    fn encode_der(&self, format: PemFormat) -> Result<Zeroizing<Vec<u8>>, PemError> {
+        pem::expect_format(format, PemFormat::Pkcs8)?;
+        asn1::encode_ed25519_pkcs8(&self[..SEED_LENGTH])
+    }
+}
+
+#[cfg(feature = "pem")]
+impl PemKey for PublicKey {
+    const DEFAULT_FORMAT: PemFormat = PemFormat::Spki;
+
+    fn decode_der(format: PemFormat, der: &[u8]) -> Result<Self, PemError> {
+        pem::expect_format(format, PemFormat::Spki)?;
+        let public_key = asn1::decode_spki(der, &asn1::ED25519)?;
+        Self::from_slice(public_key)
+            .ok_or_else(|| PemError::malformed("invalid Ed25519 public key length"))
+    }
+
+    fn encode_der(&self, format: PemFormat) -> Result<Zeroizing<Vec<u8>>, PemError> {
+        pem::expect_format(format, PemFormat::Spki)?;
+        asn1::encode_spki(asn1::ED25519, self.as_ref())
+    }
+}
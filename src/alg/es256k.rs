@@ -1,6 +1,7 @@
 //! `ES256K` algorithm implementation using the `secp256k1` crate.
 
 use lazy_static::lazy_static;
+use rand_core::{CryptoRng, RngCore};
 use secp256k1::{
     constants::{
         COMPACT_SIGNATURE_SIZE, FIELD_SIZE, SECRET_KEY_SIZE, UNCOMPRESSED_PUBLIC_KEY_SIZE,
@@ -14,15 +15,25 @@ use sha2::{
     },
     Digest, Sha256,
 };
+use zeroize::Zeroizing;
 
 use core::{marker::PhantomData, num::NonZeroUsize};
 
 use crate::{
-    alg::{SecretBytes, SigningKey, VerifyingKey},
+    alg::{GenerateKey, SecretBytes, SigningKey, VerifyingKey},
     alloc::Cow,
     jwk::{JsonWebKey, JwkError, KeyType},
     Algorithm, AlgorithmSignature,
 };
+#[cfg(feature = "pem")]
+use crate::{
+    alloc::Vec,
+    pem::{self, asn1, PemError, PemFormat, PemKey},
+};
+#[cfg(feature = "pem")]
+use pkcs8::{der::Encode, ObjectIdentifier};
+#[cfg(feature = "pem")]
+use sec1::{EcParameters, EcPrivateKey};
 
 /// Byte size of a serialized EC coordinate.
 const COORDINATE_SIZE: usize = FIELD_SIZE.len();
@@ -92,6 +103,7 @@ where
         Cow::Borrowed("ES256K")
     }
 
+    #[cfg(feature = "sign")]
     fn sign(&self, signing_key: &Self::SigningKey, message: &[u8]) -> Self::Signature {
         let mut digest = D::default();
         digest.update(message);
@@ -123,6 +135,12 @@ where
     }
 }
 
+impl_prepare_verifying_key!(
+    impl<D> Es256k<D>
+    where
+    D: FixedOutputReset<OutputSize = U32> + BlockSizeUser + Clone + Default + HashMarker,
+);
+
 /// This implementation initializes a `libsecp256k1` context once on the first call to
 /// `to_verifying_key` if it was not initialized previously.
 impl SigningKey<Es256k> for SecretKey {
@@ -130,6 +148,8 @@ impl SigningKey<Es256k> for SecretKey {
         Self::from_slice(raw).map_err(From::from)
     }
 
+    // `LazyLock` is unavailable in the no-std mode.
+    #[allow(clippy::non_std_lazy_statics)]
     fn to_verifying_key(&self) -> PublicKey {
         lazy_static! {
             static ref CONTEXT: Secp256k1<All> = Secp256k1::new();
@@ -142,6 +162,24 @@ impl SigningKey<Es256k> for SecretKey {
     }
 }
 
+impl GenerateKey<Es256k> for SecretKey {
+    type Params = ();
+
+    fn generate_pair_with<R: CryptoRng + RngCore>(rng: &mut R, (): ()) -> (Self, PublicKey) {
+        let mut bytes = Zeroizing::new([0_u8; SECRET_KEY_SIZE]);
+        let signing_key = loop {
+            // The probability of generating an invalid scalar is negligible (~2^-128),
+            // but we handle it for completeness.
+            rng.fill_bytes(bytes.as_mut());
+            if let Ok(key) = Self::from_slice(bytes.as_ref()) {
+                break key;
+            }
+        };
+        let verifying_key = signing_key.to_verifying_key();
+        (signing_key, verifying_key)
+    }
+}
+
 impl VerifyingKey<Es256k> for PublicKey {
     fn from_slice(raw: &[u8]) -> anyhow::Result<Self> {
         Self::from_slice(raw).map_err(From::from)
@@ -209,3 +247,79 @@ impl TryFrom<&JsonWebKey<'_>> for SecretKey {
         jwk.ensure_key_match(sk)
     }
 }
+
+#[cfg(feature = "pem")]
+const SECP256K1_OID: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.132.0.10");
+
+/// Decodes a SEC1 `ECPrivateKey`.
+#[cfg(feature = "pem")]
+fn decode_ec_private_key(der: &[u8]) -> Result<SecretKey, PemError> {
+    let ec_key = EcPrivateKey::try_from(der).map_err(PemError::malformed)?;
+    if let Some(EcParameters::NamedCurve(curve)) = ec_key.parameters {
+        if curve != SECP256K1_OID {
+            return Err(PemError::malformed("unexpected elliptic curve"));
+        }
+    }
+    SecretKey::from_slice(ec_key.private_key).map_err(PemError::malformed)
+}
+
+/// Encodes `key` as a SEC1 `ECPrivateKey`, optionally specifying the curve.
+#[cfg(feature = "pem")]
+fn encode_ec_private_key(
+    key: &SecretKey,
+    with_curve: bool,
+) -> Result<Zeroizing<Vec<u8>>, PemError> {
+    let public_key = key.to_verifying_key().serialize_uncompressed();
+    let ec_key = EcPrivateKey {
+        private_key: &key[..],
+        parameters: with_curve.then_some(EcParameters::NamedCurve(SECP256K1_OID)),
+        public_key: Some(&public_key),
+    };
+    Ok(Zeroizing::new(ec_key.to_der()?))
+}
+
+#[cfg(feature = "pem")]
+impl PemKey for SecretKey {
+    const DEFAULT_FORMAT: PemFormat = PemFormat::Pkcs8;
+
+    fn decode_der(format: PemFormat, der: &[u8]) -> Result<Self, PemError> {
+        match format {
+            PemFormat::Pkcs8 => {
+                let algorithm = asn1::ec_algorithm(&SECP256K1_OID);
+                decode_ec_private_key(asn1::decode_pkcs8(der, &algorithm)?)
+            }
+            PemFormat::Sec1 => decode_ec_private_key(der),
+            _ => Err(PemError::UnsupportedFormat(format)),
+        }
+    }
+
+    fn encode_der(&self, format: PemFormat) -> Result<Zeroizing<Vec<u8>>, PemError> {
+        match format {
+            PemFormat::Pkcs8 => {
+                // The curve is specified in the algorithm identifier, so it's omitted
+                // from the inner SEC1 key.
+                let ec_key = encode_ec_private_key(self, false)?;
+                asn1::encode_pkcs8(asn1::ec_algorithm(&SECP256K1_OID), &ec_key)
+            }
+            PemFormat::Sec1 => encode_ec_private_key(self, true),
+            _ => Err(PemError::UnsupportedFormat(format)),
+        }
+    }
+}
+
+#[cfg(feature = "pem")]
+impl PemKey for PublicKey {
+    const DEFAULT_FORMAT: PemFormat = PemFormat::Spki;
+
+    fn decode_der(format: PemFormat, der: &[u8]) -> Result<Self, PemError> {
+        pem::expect_format(format, PemFormat::Spki)?;
+        let public_key = asn1::decode_spki(der, &asn1::ec_algorithm(&SECP256K1_OID))?;
+        Self::from_slice(public_key).map_err(PemError::malformed)
+    }
+
+    fn encode_der(&self, format: PemFormat) -> Result<Zeroizing<Vec<u8>>, PemError> {
+        pem::expect_format(format, PemFormat::Spki)?;
+        let algorithm = asn1::ec_algorithm(&SECP256K1_OID);
+        asn1::encode_spki(algorithm, &self.serialize_uncompressed())
+    }
+}
@@ -0,0 +1,324 @@
+//! `RS256`, `PS256` and `ES256` signing using asymmetric keys held by
+//! [Google Cloud KMS](https://cloud.google.com/kms).
+
+use base64ct::{Base64, Encoding};
+use reqwest::{Client, Method, StatusCode};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use core::{fmt, future::Future};
+use std::sync::Arc;
+
+use super::{
+    ecdsa_signature_from_der,
+    remote::{self, AccessTokenFn, RawSignature},
+    EcdsaCurve,
+};
+use crate::{
+    alloc::{Box, Cow, String, Vec},
+    AsyncAlgorithm, Header, SignFuture,
+};
+
+/// Default base URL of the Cloud KMS REST API.
+const DEFAULT_ENDPOINT: &str = "https://cloudkms.googleapis.com/v1/";
+
+/// Errors that can occur when interacting with Google Cloud KMS.
+#[derive(Debug)]
+#[non_exhaustive]
+#[cfg_attr(docsrs, doc(cfg(feature = "gcp-kms")))]
+pub enum GcpKmsError {
+    /// Error obtaining an access token.
+    AccessToken(anyhow::Error),
+    /// HTTP error calling the KMS API.
+    Http(reqwest::Error),
+    /// KMS API has returned an error response.
+    Api {
+        /// HTTP status of the response.
+        status: u16,
+        /// Error message from the response.
+        message: String,
+    },
+    /// KMS response does not contain an expected field, or the field is malformed.
+    InvalidResponse(&'static str),
+    /// Signature returned by KMS is malformed.
+    MalformedSignature,
+    /// Public key returned by KMS cannot be converted to the requested key type.
+    PublicKey(anyhow::Error),
+}
+
+impl fmt::Display for GcpKmsError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AccessToken(err) => write!(formatter, "cannot obtain access token: {err}"),
+            Self::Http(err) => write!(formatter, "error calling Cloud KMS: {err}"),
+            Self::Api { status, message } => {
+                write!(
+                    formatter,
+                    "Cloud KMS returned error (HTTP {status}): {message}"
+                )
+            }
+            Self::InvalidResponse(field) => {
+                write!(
+                    formatter,
+                    "field `{field}` is missing or invalid in the Cloud KMS response"
+                )
+            }
+            Self::MalformedSignature => {
+                formatter.write_str("Cloud KMS returned malformed signature")
+            }
+            Self::PublicKey(err) => write!(formatter, "cannot parse Cloud KMS public key: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for GcpKmsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::AccessToken(err) | Self::PublicKey(err) => Some(err.as_ref()),
+            Self::Http(err) => Some(err),
+            Self::Api { .. } | Self::InvalidResponse(_) | Self::MalformedSignature => None,
+        }
+    }
+}
+
+/// Handle to an asymmetric signing key version in Google Cloud KMS. Used as a signing key
+/// for the [`GcpKms`] algorithm; the private key never leaves KMS.
+///
+/// Requests are authenticated with OAuth 2.0 access tokens supplied by a caller-provided hook
+/// (e.g., backed by the `gcp_auth` crate or the metadata server). The hook is called
+/// for each request, so it should cache tokens if necessary. The `reqwest` client must have
+/// a TLS backend enabled to reach the default endpoint.
+#[derive(Clone)]
+#[cfg_attr(docsrs, doc(cfg(feature = "gcp-kms")))]
+pub struct GcpKmsKey {
+    client: Client,
+    name: String,
+    endpoint: String,
+    access_token: Arc<AccessTokenFn>,
+}
+
+impl fmt::Debug for GcpKmsKey {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("GcpKmsKey")
+            .field("name", &self.name)
+            .field("endpoint", &self.endpoint)
+            .finish_non_exhaustive()
+    }
+}
+
+impl GcpKmsKey {
+    /// Creates a key handle. `name` is the resource name of the key version, i.e.,
+    /// `projects/{project}/locations/{location}/keyRings/{ring}/cryptoKeys/{key}/cryptoKeyVersions/{version}`.
+    pub fn new<F, Fut>(client: Client, name: impl Into<String>, access_token: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = anyhow::Result<String>> + Send + 'static,
+    {
+        Self {
+            client,
+            name: name.into(),
+            endpoint: DEFAULT_ENDPOINT.to_owned(),
+            access_token: remote::access_token_fn(access_token),
+        }
+    }
+
+    /// Sets the base URL of the KMS REST API (e.g., for a private service endpoint).
+    /// The URL should end with a slash; by default, `https://cloudkms.googleapis.com/v1/`
+    /// is used.
+    #[must_use]
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = endpoint.into();
+        self
+    }
+
+    /// Returns the resource name of the key version.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the key ID (`kid`) for tokens signed with this key. The key ID is derived
+    /// from the key version resource name as `{key}/{version}` (e.g., `token-signing/3`),
+    /// so that it changes on key rotation.
+    pub fn key_id(&self) -> String {
+        let mut segments = self.name.rsplit('/');
+        match (segments.next(), segments.next(), segments.next()) {
+            (Some(version), Some("cryptoKeyVersions"), Some(key)) => format!("{key}/{version}"),
+            _ => self.name.clone(),
+        }
+    }
+
+    /// Returns a header with the [key ID](Self::key_id()) set.
+    pub fn header(&self) -> Header {
+        Header::empty().with_key_id(self.key_id())
+    }
+
+    async fn call(
+        &self,
+        method: Method,
+        url: String,
+        body: Option<Value>,
+    ) -> Result<Value, GcpKmsError> {
+        let access_token = (self.access_token)()
+            .await
+            .map_err(GcpKmsError::AccessToken)?;
+        let mut request = self.client.request(method, url).bearer_auth(access_token);
+        if let Some(body) = body {
+            request = request
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(body.to_string());
+        }
+        let response = request.send().await.map_err(GcpKmsError::Http)?;
+        let status = response.status();
+        let body = response.bytes().await.map_err(GcpKmsError::Http)?;
+
+        if status != StatusCode::OK {
+            let message = serde_json::from_slice::<Value>(&body)
+                .ok()
+                .and_then(|body| body["error"]["message"].as_str().map(str::to_owned))
+                .unwrap_or_else(|| String::from_utf8_lossy(&body).into_owned());
+            return Err(GcpKmsError::Api {
+                status: status.as_u16(),
+                message,
+            });
+        }
+        serde_json::from_slice(&body).map_err(|_| GcpKmsError::InvalidResponse("body"))
+    }
+
+    /// Fetches the public key from KMS as a DER-encoded `SubjectPublicKeyInfo`.
+    /// The public key of a key version never changes, so it can be cached indefinitely.
+    pub async fn public_key_der(&self) -> Result<Vec<u8>, GcpKmsError> {
+        let url = format!("{}{}/publicKey", self.endpoint, self.name);
+        let response = self.call(Method::GET, url, None).await?;
+        let pem = response["pem"]
+            .as_str()
+            .ok_or(GcpKmsError::InvalidResponse("pem"))?;
+        remote::pem_to_der(pem).ok_or(GcpKmsError::InvalidResponse("pem"))
+    }
+
+    /// Fetches the RSA public key from KMS. The key can be used to verify tokens
+    /// with the [`Rsa`](crate::alg::Rsa) algorithm.
+    #[cfg(feature = "rsa")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "rsa")))]
+    pub async fn rsa_public_key(&self) -> Result<rsa::RsaPublicKey, GcpKmsError> {
+        let der = self.public_key_der().await?;
+        remote::rsa_public_key_from_der(&der).map_err(GcpKmsError::PublicKey)
+    }
+
+    /// Fetches the P-256 public key from KMS. The key can be used to verify tokens
+    /// with the [`Es256`](crate::alg::Es256) algorithm.
+    #[cfg(feature = "p256")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "p256")))]
+    pub async fn es256_public_key(&self) -> Result<p256::ecdsa::VerifyingKey, GcpKmsError> {
+        let der = self.public_key_der().await?;
+        remote::es256_public_key_from_der(&der).map_err(GcpKmsError::PublicKey)
+    }
+}
+
+/// Signing scheme supported by [`GcpKms`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Scheme {
+    Rs256,
+    Ps256,
+    Es256,
+}
+
+/// Signing algorithm using asymmetric Google Cloud KMS keys. Supports `RS256`
+/// (`RSA_SIGN_PKCS1_*_SHA256` keys), `PS256` (`RSA_SIGN_PSS_*_SHA256` keys)
+/// and `ES256` (`EC_SIGN_P256_SHA256` keys).
+///
+/// The message digest is computed locally. ECDSA signatures returned by KMS
+/// are converted from DER to the JWS format.
+///
+/// Since signing requires a network call, this algorithm implements [`AsyncAlgorithm`]
+/// rather than [`Algorithm`](crate::Algorithm). Tokens are verified with the corresponding
+/// local algorithm (e.g., [`Rsa`](crate::alg::Rsa) or [`Es256`](crate::alg::Es256)) using
+/// the public key fetched via [`GcpKmsKey`] methods, or obtained from a JWK set.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use chrono::Duration;
+/// use jwt_compact::{alg::{GcpKms, GcpKmsKey}, prelude::*, AsyncAlgorithmExt};
+///
+/// # async fn test_wrapper() -> anyhow::Result<()> {
+/// let key = GcpKmsKey::new(
+///     reqwest::Client::new(),
+///     "projects/my-project/locations/global/keyRings/tokens/cryptoKeys/signing/cryptoKeyVersions/1",
+///     || async { Ok("access token".to_owned()) },
+/// );
+/// let claims = Claims::new(serde_json::json!({ "sub": "service" }))
+///     .set_duration_and_issuance(&TimeOptions::default(), Duration::try_minutes(10).unwrap());
+/// // The header contains `"kid": "signing/1"`.
+/// let token = GcpKms::es256().token_async(&key.header(), &claims, &key).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(docsrs, doc(cfg(feature = "gcp-kms")))]
+pub struct GcpKms {
+    scheme: Scheme,
+}
+
+impl GcpKms {
+    /// `RS256` algorithm (RSASSA-PKCS1-v1_5 with SHA-256).
+    pub const fn rs256() -> Self {
+        Self {
+            scheme: Scheme::Rs256,
+        }
+    }
+
+    /// `PS256` algorithm (RSASSA-PSS with SHA-256).
+    pub const fn ps256() -> Self {
+        Self {
+            scheme: Scheme::Ps256,
+        }
+    }
+
+    /// `ES256` algorithm (ECDSA on the P-256 curve with SHA-256).
+    pub const fn es256() -> Self {
+        Self {
+            scheme: Scheme::Es256,
+        }
+    }
+
+    async fn sign(&self, key: &GcpKmsKey, digest: Vec<u8>) -> Result<RawSignature, GcpKmsError> {
+        let url = format!("{}{}:asymmetricSign", key.endpoint, key.name);
+        let body = json!({ "digest": { "sha256": Base64::encode_string(&digest) } });
+        let response = key.call(Method::POST, url, Some(body)).await?;
+        let signature = response["signature"]
+            .as_str()
+            .and_then(|signature| Base64::decode_vec(signature).ok())
+            .ok_or(GcpKmsError::InvalidResponse("signature"))?;
+
+        let signature = if self.scheme == Scheme::Es256 {
+            ecdsa_signature_from_der(&signature, EcdsaCurve::P256)
+                .map_err(|_| GcpKmsError::MalformedSignature)?
+        } else {
+            signature
+        };
+        Ok(RawSignature::new(signature))
+    }
+}
+
+impl AsyncAlgorithm for GcpKms {
+    type SigningKey = GcpKmsKey;
+    type Signature = RawSignature;
+
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Borrowed(match self.scheme {
+            Scheme::Rs256 => "RS256",
+            Scheme::Ps256 => "PS256",
+            Scheme::Es256 => "ES256",
+        })
+    }
+
+    fn sign_async<'a>(
+        &'a self,
+        signing_key: &'a Self::SigningKey,
+        message: &'a [u8],
+    ) -> SignFuture<'a, Self::Signature> {
+        let digest = Sha256::digest(message).to_vec();
+        Box::pin(async move { Ok(self.sign(signing_key, digest).await?) })
+    }
+}
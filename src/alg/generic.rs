@@ -1,6 +1,7 @@
 //! Generic traits providing uniform interfaces for a certain cryptosystem
 //! across different backends.
 
+use rand_core::{CryptoRng, RngCore};
 use zeroize::Zeroize;
 
 use core::{fmt, ops};
@@ -53,6 +54,52 @@ where
     fn as_bytes(&self) -> SecretBytes<'_>;
 }
 
+/// Signing key that can be randomly generated, together with the matching verifying key.
+///
+/// This trait provides a uniform way to provision keys for all supported algorithms,
+/// without needing to know the key generation APIs of the underlying crypto backends.
+///
+/// # Examples
+///
+/// ```
+/// use jwt_compact::{prelude::*, alg::{GenerateKey, Hs384, Hs384Key}};
+/// # use chrono::Duration;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let (signing_key, verifying_key) = Hs384Key::generate_pair(&mut rand::thread_rng());
+/// let claims = Claims::empty()
+///     .set_duration(&TimeOptions::default(), Duration::try_minutes(10).unwrap());
+/// let token = Hs384.token(&Header::empty(), &claims, &signing_key)?;
+/// let token = UntrustedToken::new(&token)?;
+/// Hs384.validator::<()>(&verifying_key).validate(&token)?;
+/// # Ok(())
+/// # }
+/// ```
+pub trait GenerateKey<T>: Sized
+where
+    T: Algorithm<SigningKey = Self>,
+{
+    /// Parameters of key generation, such as the modulus length for RSA keys.
+    /// For most algorithms, there are no parameters, and this type is set to `()`.
+    type Params: Default;
+
+    /// Generates a key pair with the specified parameters using a cryptographically secure RNG.
+    fn generate_pair_with<R: CryptoRng + RngCore>(
+        rng: &mut R,
+        params: Self::Params,
+    ) -> (Self, T::VerifyingKey);
+
+    /// Generates a key pair with the default parameters using a cryptographically secure RNG.
+    fn generate_pair<R: CryptoRng + RngCore>(rng: &mut R) -> (Self, T::VerifyingKey) {
+        Self::generate_pair_with(rng, Self::Params::default())
+    }
+
+    /// Generates a signing key with the default parameters using a cryptographically secure RNG.
+    fn generate<R: CryptoRng + RngCore>(rng: &mut R) -> Self {
+        Self::generate_pair(rng).0
+    }
+}
+
 /// Generic container for secret bytes, which can be either owned or borrowed.
 /// If owned, bytes are zeroized on drop.
 ///
@@ -81,6 +128,15 @@ impl<'a> SecretBytes<'a> {
     pub fn owned(bytes: Vec<u8>) -> Self {
         Self(Cow::Owned(bytes))
     }
+
+    /// Copies bytes from a temporary `buffer` (e.g., one returned by a crypto backend
+    /// when serializing a key), which is zeroized afterwards.
+    #[cfg(any(feature = "p256", feature = "k256", feature = "ed25519-dalek"))]
+    pub(crate) fn copied_from(mut buffer: impl AsMut<[u8]>) -> Self {
+        let bytes = buffer.as_mut().to_vec();
+        buffer.as_mut().zeroize();
+        Self::owned(bytes)
+    }
 }
 
 impl fmt::Debug for SecretBytes<'_> {
@@ -8,15 +8,15 @@ use sha2::{
     Sha256, Sha384, Sha512,
 };
 use smallvec::{smallvec, SmallVec};
-use zeroize::Zeroize;
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
 use core::{fmt, num::NonZeroUsize};
 
 use crate::{
-    alg::{SecretBytes, SigningKey, StrongKey, VerifyingKey, WeakKeyError},
-    alloc::Cow,
+    alg::{GenerateKey, SecretBytes, SigningKey, StrongKey, VerifyingKey, WeakKeyError},
+    alloc::{Cow, Vec},
     jwk::{JsonWebKey, JwkError, KeyType},
-    Algorithm, AlgorithmSignature,
+    Algorithm, AlgorithmSignature, PrepareVerifyingKey,
 };
 
 macro_rules! define_hmac_signature {
@@ -69,13 +69,16 @@ macro_rules! define_hmac_key {
         struct $name:ident<$digest:ident>([u8; $buffer_size:expr]);
     ) => {
         $(#[$($attr)+])*
-        #[derive(Clone, Zeroize)]
-        #[zeroize(drop)]
+        #[derive(Clone, Zeroize, ZeroizeOnDrop)]
         pub struct $name(pub(crate) SmallVec<[u8; $buffer_size]>);
 
+        /// Outputs only the key length, never the key bytes.
         impl fmt::Debug for $name {
             fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
-                formatter.debug_tuple(stringify!($name)).field(&"_").finish()
+                formatter
+                    .debug_struct(stringify!($name))
+                    .field("len", &self.0.len())
+                    .finish()
             }
         }
 
@@ -94,11 +97,16 @@ macro_rules! define_hmac_key {
 
             /// Computes HMAC with this key and the specified `message`.
             fn hmac(&self, message: impl AsRef<[u8]>) -> CtOutput<Hmac<$digest>> {
-                let mut hmac = Hmac::<$digest>::new_from_slice(&self.0)
-                    .expect("HMACs work with any key size");
+                let mut hmac = self.prepare_hmac();
                 hmac.update(message.as_ref());
                 hmac.finalize()
             }
+
+            /// Initializes HMAC state with this key. The state can be cloned to compute HMACs
+            /// for multiple messages without repeating key preparation.
+            fn prepare_hmac(&self) -> Hmac<$digest> {
+                Hmac::<$digest>::new_from_slice(&self.0).expect("HMACs work with any key size")
+            }
         }
 
         impl From<&[u8]> for $name {
@@ -163,6 +171,7 @@ impl Algorithm for Hs256 {
         Cow::Borrowed("HS256")
     }
 
+    #[cfg(feature = "sign")]
     fn sign(&self, signing_key: &Self::SigningKey, message: &[u8]) -> Self::Signature {
         Hs256Signature(signing_key.hmac(message))
     }
@@ -175,6 +184,39 @@ impl Algorithm for Hs256 {
     ) -> bool {
         verifying_key.hmac(message) == signature.0
     }
+
+    fn verify_signatures(
+        &self,
+        verifying_key: &Self::VerifyingKey,
+        signatures: &[(Self::Signature, &[u8])],
+    ) -> Vec<bool> {
+        let prepared_key = self.prepare_verifying_key(verifying_key);
+        signatures
+            .iter()
+            .map(|(signature, message)| self.verify_prepared(signature, &prepared_key, message))
+            .collect()
+    }
+}
+
+/// The prepared key is the HMAC state initialized with the key. Unlike HMAC keys,
+/// this state is **not** zeroized on drop.
+impl PrepareVerifyingKey for Hs256 {
+    type PreparedKey = Hmac<Sha256>;
+
+    fn prepare_verifying_key(&self, verifying_key: &Self::VerifyingKey) -> Self::PreparedKey {
+        verifying_key.prepare_hmac()
+    }
+
+    fn verify_prepared(
+        &self,
+        signature: &Self::Signature,
+        prepared_key: &Self::PreparedKey,
+        message: &[u8],
+    ) -> bool {
+        let mut hmac = prepared_key.clone();
+        hmac.update(message);
+        hmac.finalize() == signature.0
+    }
 }
 
 /// `HS384` signing algorithm.
@@ -194,6 +236,7 @@ impl Algorithm for Hs384 {
         Cow::Borrowed("HS384")
     }
 
+    #[cfg(feature = "sign")]
     fn sign(&self, signing_key: &Self::SigningKey, message: &[u8]) -> Self::Signature {
         Hs384Signature(signing_key.hmac(message))
     }
@@ -206,6 +249,39 @@ impl Algorithm for Hs384 {
     ) -> bool {
         verifying_key.hmac(message) == signature.0
     }
+
+    fn verify_signatures(
+        &self,
+        verifying_key: &Self::VerifyingKey,
+        signatures: &[(Self::Signature, &[u8])],
+    ) -> Vec<bool> {
+        let prepared_key = self.prepare_verifying_key(verifying_key);
+        signatures
+            .iter()
+            .map(|(signature, message)| self.verify_prepared(signature, &prepared_key, message))
+            .collect()
+    }
+}
+
+/// The prepared key is the HMAC state initialized with the key. Unlike HMAC keys,
+/// this state is **not** zeroized on drop.
+impl PrepareVerifyingKey for Hs384 {
+    type PreparedKey = Hmac<Sha384>;
+
+    fn prepare_verifying_key(&self, verifying_key: &Self::VerifyingKey) -> Self::PreparedKey {
+        verifying_key.prepare_hmac()
+    }
+
+    fn verify_prepared(
+        &self,
+        signature: &Self::Signature,
+        prepared_key: &Self::PreparedKey,
+        message: &[u8],
+    ) -> bool {
+        let mut hmac = prepared_key.clone();
+        hmac.update(message);
+        hmac.finalize() == signature.0
+    }
 }
 
 /// `HS512` signing algorithm.
@@ -225,6 +301,7 @@ impl Algorithm for Hs512 {
         Cow::Borrowed("HS512")
     }
 
+    #[cfg(feature = "sign")]
     fn sign(&self, signing_key: &Self::SigningKey, message: &[u8]) -> Self::Signature {
         Hs512Signature(signing_key.hmac(message))
     }
@@ -237,6 +314,39 @@ impl Algorithm for Hs512 {
     ) -> bool {
         verifying_key.hmac(message) == signature.0
     }
+
+    fn verify_signatures(
+        &self,
+        verifying_key: &Self::VerifyingKey,
+        signatures: &[(Self::Signature, &[u8])],
+    ) -> Vec<bool> {
+        let prepared_key = self.prepare_verifying_key(verifying_key);
+        signatures
+            .iter()
+            .map(|(signature, message)| self.verify_prepared(signature, &prepared_key, message))
+            .collect()
+    }
+}
+
+/// The prepared key is the HMAC state initialized with the key. Unlike HMAC keys,
+/// this state is **not** zeroized on drop.
+impl PrepareVerifyingKey for Hs512 {
+    type PreparedKey = Hmac<Sha512>;
+
+    fn prepare_verifying_key(&self, verifying_key: &Self::VerifyingKey) -> Self::PreparedKey {
+        verifying_key.prepare_hmac()
+    }
+
+    fn verify_prepared(
+        &self,
+        signature: &Self::Signature,
+        prepared_key: &Self::PreparedKey,
+        message: &[u8],
+    ) -> bool {
+        let mut hmac = prepared_key.clone();
+        hmac.update(message);
+        hmac.finalize() == signature.0
+    }
 }
 
 macro_rules! impl_key_traits {
@@ -255,6 +365,15 @@ macro_rules! impl_key_traits {
             }
         }
 
+        impl GenerateKey<$alg> for $key {
+            type Params = ();
+
+            fn generate_pair_with<R: CryptoRng + RngCore>(rng: &mut R, (): ()) -> (Self, Self) {
+                let key = Self::generate(rng).into_inner();
+                (key.clone(), key)
+            }
+        }
+
         impl VerifyingKey<$alg> for $key {
             fn from_slice(raw: &[u8]) -> anyhow::Result<Self> {
                 Ok(Self::from(raw))
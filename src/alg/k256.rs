@@ -8,16 +8,26 @@ use k256::{
     elliptic_curve::FieldBytesSize,
     Secp256k1,
 };
+use rand_core::{CryptoRng, RngCore};
 use sha2::{digest::typenum::Unsigned, Digest, Sha256};
 
 use core::{marker::PhantomData, num::NonZeroUsize, ops::Add};
 
 use crate::{
-    alg::{self, SecretBytes},
+    alg::{self, GenerateKey, SecretBytes},
     alloc::Cow,
     jwk::{JsonWebKey, JwkError, KeyType},
     Algorithm, AlgorithmSignature,
 };
+#[cfg(feature = "pem")]
+use crate::{
+    alloc::Vec,
+    pem::{self, PemError, PemFormat, PemKey},
+};
+#[cfg(feature = "pem")]
+use k256::pkcs8::{DecodePrivateKey, DecodePublicKey, EncodePrivateKey, EncodePublicKey};
+#[cfg(feature = "pem")]
+use zeroize::Zeroizing;
 
 impl AlgorithmSignature for Signature {
     const LENGTH: Option<NonZeroUsize> =
@@ -71,6 +81,7 @@ where
         Cow::Borrowed("ES256K")
     }
 
+    #[cfg(feature = "sign")]
     fn sign(&self, signing_key: &Self::SigningKey, message: &[u8]) -> Self::Signature {
         let mut digest = D::default();
         digest.update(message);
@@ -101,6 +112,14 @@ where
     }
 }
 
+impl_prepare_verifying_key!(
+    impl<D> Es256k<D>
+    where
+    D: Default + Digest,
+    SigningKey: DigestSigner<D, Signature>,
+    VerifyingKey: DigestVerifier<D, Signature>,
+);
+
 impl alg::SigningKey<Es256k> for SigningKey {
     fn from_slice(raw: &[u8]) -> anyhow::Result<Self> {
         Self::from_slice(raw).map_err(|err| anyhow::anyhow!(err))
@@ -111,7 +130,17 @@ impl alg::SigningKey<Es256k> for SigningKey {
     }
 
     fn as_bytes(&self) -> SecretBytes<'_> {
-        SecretBytes::owned(self.to_bytes().to_vec())
+        SecretBytes::copied_from(self.to_bytes())
+    }
+}
+
+impl GenerateKey<Es256k> for SigningKey {
+    type Params = ();
+
+    fn generate_pair_with<R: CryptoRng + RngCore>(rng: &mut R, (): ()) -> (Self, VerifyingKey) {
+        let signing_key = Self::random(rng);
+        let verifying_key = *signing_key.verifying_key();
+        (signing_key, verifying_key)
     }
 }
 
@@ -133,7 +162,7 @@ fn create_jwk<'a>(pk: &VerifyingKey, sk: Option<&'a SigningKey>) -> JsonWebKey<'
         curve: "secp256k1".into(),
         x: Cow::Owned(uncompressed.x().expect("x coord").to_vec()),
         y: Cow::Owned(uncompressed.y().expect("y coord").to_vec()),
-        secret: sk.map(|sk| SecretBytes::owned(sk.to_bytes().to_vec())),
+        secret: sk.map(|sk| SecretBytes::copied_from(sk.to_bytes())),
     }
 }
 
@@ -187,3 +216,52 @@ impl TryFrom<&JsonWebKey<'_>> for SigningKey {
         jwk.ensure_key_match(sk)
     }
 }
+
+#[cfg(feature = "pem")]
+impl PemKey for SigningKey {
+    const DEFAULT_FORMAT: PemFormat = PemFormat::Pkcs8;
+
+    fn decode_der(format: PemFormat, der: &[u8]) -> Result<Self, PemError> {
+        match format {
+            PemFormat::Pkcs8 => {
+                <Self as DecodePrivateKey>::from_pkcs8_der(der).map_err(PemError::malformed)
+            }
+            PemFormat::Sec1 => {
+                let secret_key =
+                    k256::SecretKey::from_sec1_der(der).map_err(PemError::malformed)?;
+                Ok(Self::from(secret_key))
+            }
+            _ => Err(PemError::UnsupportedFormat(format)),
+        }
+    }
+
+    fn encode_der(&self, format: PemFormat) -> Result<Zeroizing<Vec<u8>>, PemError> {
+        match format {
+            PemFormat::Pkcs8 => {
+                let document = EncodePrivateKey::to_pkcs8_der(self).map_err(PemError::malformed)?;
+                Ok(Zeroizing::new(document.as_bytes().to_vec()))
+            }
+            PemFormat::Sec1 => {
+                let secret_key = k256::SecretKey::from(self);
+                secret_key.to_sec1_der().map_err(PemError::malformed)
+            }
+            _ => Err(PemError::UnsupportedFormat(format)),
+        }
+    }
+}
+
+#[cfg(feature = "pem")]
+impl PemKey for VerifyingKey {
+    const DEFAULT_FORMAT: PemFormat = PemFormat::Spki;
+
+    fn decode_der(format: PemFormat, der: &[u8]) -> Result<Self, PemError> {
+        pem::expect_format(format, PemFormat::Spki)?;
+        Self::from_public_key_der(der).map_err(PemError::malformed)
+    }
+
+    fn encode_der(&self, format: PemFormat) -> Result<Zeroizing<Vec<u8>>, PemError> {
+        pem::expect_format(format, PemFormat::Spki)?;
+        let document = self.to_public_key_der().map_err(PemError::malformed)?;
+        Ok(Zeroizing::new(document.as_bytes().to_vec()))
+    }
+}
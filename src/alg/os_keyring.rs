@@ -0,0 +1,176 @@
+//! Storage of symmetric secrets in the OS credential store.
+
+use rand_core::{CryptoRng, RngCore};
+use zeroize::Zeroizing;
+
+use core::fmt;
+
+use super::{Hs256Key, Hs384Key, Hs512Key};
+use crate::alloc::String;
+
+/// Errors that can occur when accessing secrets in the OS credential store.
+#[derive(Debug)]
+#[non_exhaustive]
+#[cfg_attr(docsrs, doc(cfg(feature = "keyring")))]
+pub enum KeyringError {
+    /// Secret with the specified name is not present in the store.
+    NotFound(String),
+    /// Error accessing the credential store.
+    Keyring(keyring::Error),
+}
+
+impl fmt::Display for KeyringError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound(name) => {
+                write!(formatter, "secret `{name}` is not present in OS keyring")
+            }
+            Self::Keyring(err) => write!(formatter, "cannot access OS keyring: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for KeyringError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Keyring(err) => Some(err),
+            Self::NotFound(_) => None,
+        }
+    }
+}
+
+impl From<keyring::Error> for KeyringError {
+    fn from(err: keyring::Error) -> Self {
+        Self::Keyring(err)
+    }
+}
+
+/// Symmetric key that can be persisted in a [`KeyringStore`].
+#[cfg_attr(docsrs, doc(cfg(feature = "keyring")))]
+pub trait KeyringSecret: AsRef<[u8]> + for<'a> From<&'a [u8]> {
+    /// Generates a random key using a cryptographically secure RNG.
+    fn generate_secret<R: CryptoRng + RngCore>(rng: &mut R) -> Self;
+}
+
+macro_rules! impl_keyring_secret {
+    ($($key:ident),+) => {
+        $(
+        impl KeyringSecret for $key {
+            fn generate_secret<R: CryptoRng + RngCore>(rng: &mut R) -> Self {
+                Self::generate(rng).into_inner()
+            }
+        }
+        )+
+    };
+}
+
+impl_keyring_secret!(Hs256Key, Hs384Key, Hs512Key);
+
+/// Store of symmetric signing secrets (e.g., [`Hs256Key`]s) in the OS credential store:
+/// Keychain on macOS / iOS, Credential Manager on Windows, and the kernel keyring on Linux.
+///
+/// Secrets are identified by a name within the service specified when creating the store.
+/// This allows local tools and desktop apps to avoid keeping secrets in config files.
+///
+/// The platform store used by default is determined by the features of the [`keyring`] crate.
+/// For example, to use the Secret Service on Linux, enable the `keyring/sync-secret-service`
+/// feature in the application. Alternatively, a store can be set with
+/// [`keyring::set_default_credential_builder()`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use jwt_compact::{prelude::*, alg::{Hs256, Hs256Key, KeyringStore}};
+/// # use chrono::Duration;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let store = KeyringStore::new("com.example.cli");
+/// let key: Hs256Key = store.load_or_generate("session-tokens", &mut rand::thread_rng())?;
+/// let claims = Claims::new("alice")
+///     .set_duration(&TimeOptions::default(), Duration::try_hours(1).unwrap());
+/// let token = Hs256.token(&Header::empty(), &claims, &key)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(docsrs, doc(cfg(feature = "keyring")))]
+pub struct KeyringStore {
+    service: String,
+}
+
+impl KeyringStore {
+    /// Creates a store for the specified service name (e.g., `com.example.app`).
+    pub fn new(service: impl Into<String>) -> Self {
+        Self {
+            service: service.into(),
+        }
+    }
+
+    /// Returns the service name of this store.
+    pub fn service(&self) -> &str {
+        &self.service
+    }
+
+    fn entry(&self, name: &str) -> Result<keyring::Entry, KeyringError> {
+        Ok(keyring::Entry::new(&self.service, name)?)
+    }
+
+    /// Loads the secret with the specified name.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the secret is not present or the store cannot be accessed.
+    pub fn load<K: KeyringSecret>(&self, name: &str) -> Result<K, KeyringError> {
+        let secret = match self.entry(name)?.get_secret() {
+            Ok(secret) => Zeroizing::new(secret),
+            Err(keyring::Error::NoEntry) => return Err(KeyringError::NotFound(name.into())),
+            Err(err) => return Err(err.into()),
+        };
+        Ok(K::from(secret.as_slice()))
+    }
+
+    /// Stores `key` under the specified name, overwriting the existing secret if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store cannot be accessed.
+    pub fn store<K: KeyringSecret>(&self, name: &str, key: &K) -> Result<(), KeyringError> {
+        self.entry(name)?.set_secret(key.as_ref())?;
+        Ok(())
+    }
+
+    /// Loads the secret with the specified name, or generates and stores a new secret
+    /// if it is not present.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store cannot be accessed.
+    pub fn load_or_generate<K, R>(&self, name: &str, rng: &mut R) -> Result<K, KeyringError>
+    where
+        K: KeyringSecret,
+        R: CryptoRng + RngCore,
+    {
+        match self.load(name) {
+            Err(KeyringError::NotFound(_)) => {
+                let key = K::generate_secret(rng);
+                self.store(name, &key)?;
+                Ok(key)
+            }
+            other => other,
+        }
+    }
+
+    /// Deletes the secret with the specified name. Returns `false` if the secret
+    /// was not present.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store cannot be accessed.
+    pub fn delete(&self, name: &str) -> Result<bool, KeyringError> {
+        match self.entry(name)?.delete_credential() {
+            Ok(()) => Ok(true),
+            Err(keyring::Error::NoEntry) => Ok(false),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
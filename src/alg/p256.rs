@@ -1,19 +1,28 @@
 //! `ES256` algorithm implementation using the `p256` crate.
 
-use p256::ecdsa::{
-    signature::{DigestSigner, DigestVerifier},
-    Signature, SigningKey, VerifyingKey,
-};
+#[cfg(feature = "sign")]
+use p256::ecdsa::signature::DigestSigner;
+use p256::ecdsa::{signature::DigestVerifier, Signature, SigningKey, VerifyingKey};
+use rand_core::{CryptoRng, RngCore};
 use sha2::{Digest, Sha256};
 
 use core::num::NonZeroUsize;
 
 use crate::{
-    alg::{self, SecretBytes},
+    alg::{self, GenerateKey, SecretBytes},
     alloc::Cow,
     jwk::{JsonWebKey, JwkError, KeyType},
     Algorithm, AlgorithmSignature,
 };
+#[cfg(feature = "pem")]
+use crate::{
+    alloc::Vec,
+    pem::{self, PemError, PemFormat, PemKey},
+};
+#[cfg(feature = "pem")]
+use p256::pkcs8::{DecodePrivateKey, DecodePublicKey, EncodePrivateKey, EncodePublicKey};
+#[cfg(feature = "pem")]
+use zeroize::Zeroizing;
 
 impl AlgorithmSignature for Signature {
     const LENGTH: Option<NonZeroUsize> = NonZeroUsize::new(64);
@@ -42,6 +51,7 @@ impl Algorithm for Es256 {
         Cow::Borrowed("ES256")
     }
 
+    #[cfg(feature = "sign")]
     fn sign(&self, signing_key: &Self::SigningKey, message: &[u8]) -> Self::Signature {
         let mut digest = Sha256::default();
         digest.update(message);
@@ -61,6 +71,8 @@ impl Algorithm for Es256 {
     }
 }
 
+impl_prepare_verifying_key!(Es256);
+
 impl alg::SigningKey<Es256> for SigningKey {
     fn from_slice(raw: &[u8]) -> anyhow::Result<Self> {
         Self::from_slice(raw).map_err(|err| anyhow::anyhow!(err))
@@ -71,7 +83,17 @@ impl alg::SigningKey<Es256> for SigningKey {
     }
 
     fn as_bytes(&self) -> SecretBytes<'_> {
-        SecretBytes::owned(self.to_bytes().to_vec())
+        SecretBytes::copied_from(self.to_bytes())
+    }
+}
+
+impl GenerateKey<Es256> for SigningKey {
+    type Params = ();
+
+    fn generate_pair_with<R: CryptoRng + RngCore>(rng: &mut R, (): ()) -> (Self, VerifyingKey) {
+        let signing_key = Self::random(rng);
+        let verifying_key = *signing_key.verifying_key();
+        (signing_key, verifying_key)
     }
 }
 
@@ -93,7 +115,7 @@ fn create_jwk<'a>(pk: &VerifyingKey, sk: Option<&'a SigningKey>) -> JsonWebKey<'
         curve: "P-256".into(),
         x: Cow::Owned(uncompressed.x().expect("x coord").to_vec()),
         y: Cow::Owned(uncompressed.y().expect("y coord").to_vec()),
-        secret: sk.map(|sk| SecretBytes::owned(sk.to_bytes().to_vec())),
+        secret: sk.map(|sk| SecretBytes::copied_from(sk.to_bytes())),
     }
 }
 
@@ -147,3 +169,52 @@ impl TryFrom<&JsonWebKey<'_>> for SigningKey {
         jwk.ensure_key_match(sk)
     }
 }
+
+#[cfg(feature = "pem")]
+impl PemKey for SigningKey {
+    const DEFAULT_FORMAT: PemFormat = PemFormat::Pkcs8;
+
+    fn decode_der(format: PemFormat, der: &[u8]) -> Result<Self, PemError> {
+        match format {
+            PemFormat::Pkcs8 => {
+                <Self as DecodePrivateKey>::from_pkcs8_der(der).map_err(PemError::malformed)
+            }
+            PemFormat::Sec1 => {
+                let secret_key =
+                    p256::SecretKey::from_sec1_der(der).map_err(PemError::malformed)?;
+                Ok(Self::from(secret_key))
+            }
+            _ => Err(PemError::UnsupportedFormat(format)),
+        }
+    }
+
+    fn encode_der(&self, format: PemFormat) -> Result<Zeroizing<Vec<u8>>, PemError> {
+        match format {
+            PemFormat::Pkcs8 => {
+                let document = EncodePrivateKey::to_pkcs8_der(self).map_err(PemError::malformed)?;
+                Ok(Zeroizing::new(document.as_bytes().to_vec()))
+            }
+            PemFormat::Sec1 => {
+                let secret_key = p256::SecretKey::from(self);
+                secret_key.to_sec1_der().map_err(PemError::malformed)
+            }
+            _ => Err(PemError::UnsupportedFormat(format)),
+        }
+    }
+}
+
+#[cfg(feature = "pem")]
+impl PemKey for VerifyingKey {
+    const DEFAULT_FORMAT: PemFormat = PemFormat::Spki;
+
+    fn decode_der(format: PemFormat, der: &[u8]) -> Result<Self, PemError> {
+        pem::expect_format(format, PemFormat::Spki)?;
+        Self::from_public_key_der(der).map_err(PemError::malformed)
+    }
+
+    fn encode_der(&self, format: PemFormat) -> Result<Zeroizing<Vec<u8>>, PemError> {
+        pem::expect_format(format, PemFormat::Spki)?;
+        let document = self.to_public_key_der().map_err(PemError::malformed)?;
+        Ok(Zeroizing::new(document.as_bytes().to_vec()))
+    }
+}
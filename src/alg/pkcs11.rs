@@ -0,0 +1,369 @@
+//! `RS*`, `PS256`, `ES256`, `ES384` and `EdDSA` signing using private keys held by PKCS#11 tokens
+//! (HSMs and smartcards) via the [`cryptoki`] crate.
+
+use cryptoki::{
+    mechanism::{
+        rsa::{PkcsMgfType, PkcsPssParams},
+        Mechanism, MechanismType,
+    },
+    object::{Attribute, ObjectClass, ObjectHandle},
+    session::Session,
+};
+use sha2::{Digest, Sha256, Sha384};
+
+use core::fmt;
+use std::sync::{Arc, Mutex};
+
+use super::remote::RawSignature;
+use crate::{
+    alloc::{Box, Cow, String, Vec},
+    AsyncAlgorithm, Header, SignFuture,
+};
+
+/// Errors that can occur when signing with PKCS#11 keys.
+#[derive(Debug)]
+#[non_exhaustive]
+#[cfg_attr(docsrs, doc(cfg(feature = "pkcs11")))]
+pub enum Pkcs11Error {
+    /// Error returned by the PKCS#11 module.
+    Cryptoki(cryptoki::error::Error),
+    /// Session provider cannot provide a session.
+    Session(anyhow::Error),
+    /// Private key with the specified label was not found on the token.
+    KeyNotFound(String),
+}
+
+impl fmt::Display for Pkcs11Error {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cryptoki(err) => write!(formatter, "PKCS#11 error: {err}"),
+            Self::Session(err) => write!(formatter, "cannot obtain PKCS#11 session: {err}"),
+            Self::KeyNotFound(label) => {
+                write!(formatter, "private key with label `{label}` not found")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Pkcs11Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Cryptoki(err) => Some(err),
+            Self::Session(err) => Some(err.as_ref()),
+            Self::KeyNotFound(_) => None,
+        }
+    }
+}
+
+impl From<cryptoki::error::Error> for Pkcs11Error {
+    fn from(err: cryptoki::error::Error) -> Self {
+        Self::Cryptoki(err)
+    }
+}
+
+/// Action performed by [`Pkcs11Sessions`] within a session.
+pub type SessionAction<'a> = dyn FnMut(&Session) -> cryptoki::error::Result<()> + 'a;
+
+/// Provider of PKCS#11 sessions used by [`Pkcs11Key`]s.
+///
+/// This crate never opens sessions or logs into tokens itself; session lifecycle, pooling
+/// and PIN management are the responsibility of the provider. The provider must supply
+/// sessions in which the private key is usable (i.e., logged in as a normal user if required
+/// by the token).
+///
+/// The trait is implemented for `Mutex<Session>`, which serializes signing operations
+/// on a single session.
+#[cfg_attr(docsrs, doc(cfg(feature = "pkcs11")))]
+pub trait Pkcs11Sessions: Send + Sync {
+    /// Runs `action` within a session.
+    ///
+    /// # Errors
+    ///
+    /// Should return an error if a session cannot be obtained, or if `action` fails.
+    fn with_session(&self, action: &mut SessionAction<'_>) -> Result<(), Pkcs11Error>;
+}
+
+impl Pkcs11Sessions for Mutex<Session> {
+    fn with_session(&self, action: &mut SessionAction<'_>) -> Result<(), Pkcs11Error> {
+        let session = self
+            .lock()
+            .map_err(|_| Pkcs11Error::Session(anyhow::anyhow!("session mutex is poisoned")))?;
+        action(&session).map_err(Pkcs11Error::Cryptoki)
+    }
+}
+
+/// Handle to a private key on a PKCS#11 token. Used as a signing key
+/// for the [`Pkcs11`] algorithm; the private key never leaves the token.
+#[derive(Clone)]
+#[cfg_attr(docsrs, doc(cfg(feature = "pkcs11")))]
+pub struct Pkcs11Key {
+    sessions: Arc<dyn Pkcs11Sessions>,
+    handle: ObjectHandle,
+    key_id: Option<String>,
+}
+
+impl fmt::Debug for Pkcs11Key {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("Pkcs11Key")
+            .field("handle", &self.handle)
+            .field("key_id", &self.key_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Pkcs11Key {
+    /// Creates a key from the object handle of a private key.
+    pub fn new(sessions: Arc<dyn Pkcs11Sessions>, handle: ObjectHandle) -> Self {
+        Self {
+            sessions,
+            handle,
+            key_id: None,
+        }
+    }
+
+    /// Finds a private key with the specified label (`CKA_LABEL`) on the token. The label
+    /// is used as the key ID; it can be overridden with [`Self::with_key_id()`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key is not found, or if the PKCS#11 module returns an error.
+    pub fn find_by_label(
+        sessions: Arc<dyn Pkcs11Sessions>,
+        label: &str,
+    ) -> Result<Self, Pkcs11Error> {
+        let template = [
+            Attribute::Class(ObjectClass::PRIVATE_KEY),
+            Attribute::Label(label.as_bytes().to_vec()),
+        ];
+        let mut handles = Vec::new();
+        sessions.with_session(&mut |session| {
+            handles = session.find_objects(&template)?;
+            Ok(())
+        })?;
+
+        let handle = handles
+            .into_iter()
+            .next()
+            .ok_or_else(|| Pkcs11Error::KeyNotFound(label.into()))?;
+        Ok(Self::new(sessions, handle).with_key_id(label))
+    }
+
+    /// Sets the key ID (`kid`) for tokens signed with this key.
+    #[must_use]
+    pub fn with_key_id(mut self, key_id: impl Into<String>) -> Self {
+        self.key_id = Some(key_id.into());
+        self
+    }
+
+    /// Returns the object handle of the private key.
+    pub fn handle(&self) -> ObjectHandle {
+        self.handle
+    }
+
+    /// Returns the key ID, if set.
+    pub fn key_id(&self) -> Option<&str> {
+        self.key_id.as_deref()
+    }
+
+    /// Returns a header with the [key ID](Self::key_id()) set (if any).
+    pub fn header(&self) -> Header {
+        let header = Header::empty();
+        match &self.key_id {
+            Some(key_id) => header.with_key_id(key_id.clone()),
+            None => header,
+        }
+    }
+}
+
+/// Signing scheme supported by [`Pkcs11`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Scheme {
+    Rs256,
+    Rs384,
+    Rs512,
+    Ps256,
+    Es256,
+    Es384,
+    EdDsa,
+}
+
+impl Scheme {
+    /// Returns the PKCS#11 mechanism and the data to sign for the message.
+    fn prepare(self, message: &[u8]) -> (Mechanism<'static>, Vec<u8>) {
+        match self {
+            Self::Rs256 => (Mechanism::Sha256RsaPkcs, message.to_vec()),
+            Self::Rs384 => (Mechanism::Sha384RsaPkcs, message.to_vec()),
+            Self::Rs512 => (Mechanism::Sha512RsaPkcs, message.to_vec()),
+            Self::Ps256 => {
+                let params = PkcsPssParams {
+                    hash_alg: MechanismType::SHA256,
+                    mgf: PkcsMgfType::MGF1_SHA256,
+                    s_len: 32.into(),
+                };
+                (Mechanism::Sha256RsaPkcsPss(params), message.to_vec())
+            }
+            // Raw ECDSA with a locally computed digest is supported by more tokens
+            // than the combined `ECDSA_SHA*` mechanisms.
+            Self::Es256 => (Mechanism::Ecdsa, Sha256::digest(message).to_vec()),
+            Self::Es384 => (Mechanism::Ecdsa, Sha384::digest(message).to_vec()),
+            Self::EdDsa => (Mechanism::Eddsa, message.to_vec()),
+        }
+    }
+}
+
+/// Signing algorithm using private keys held by PKCS#11 tokens. Supports `RS256`, `RS384`,
+/// `RS512`, `PS256` (RSA keys), `ES256`, `ES384` (EC keys on the corresponding curves)
+/// and `EdDSA` (Ed25519 keys; requires vendor support for `CKM_EDDSA`).
+///
+/// PKCS#11 produces ECDSA signatures in the `r || s` form, which is the same as in JWS,
+/// so signatures are used as-is. Tokens are verified with the corresponding local algorithm
+/// (e.g., [`Es256`](crate::alg::Es256)) using the public key exported from the token.
+///
+/// This algorithm implements [`AsyncAlgorithm`] since signing with a token may fail.
+/// Note that the PKCS#11 call is blocking and is performed when the signing future is polled;
+/// for network HSMs, consider signing on a blocking thread (e.g., via `spawn_blocking`
+/// in Tokio).
+///
+/// # Examples
+///
+/// ```no_run
+/// # use chrono::Duration;
+/// use cryptoki::{context::{CInitializeArgs, Pkcs11 as Context}, types::AuthPin};
+/// use jwt_compact::{alg::{Pkcs11, Pkcs11Key}, prelude::*, AsyncAlgorithmExt};
+/// use std::sync::{Arc, Mutex};
+///
+/// # async fn test_wrapper() -> anyhow::Result<()> {
+/// // Session management (incl. login) is performed by the application.
+/// let context = Context::new("/usr/lib/softhsm/libsofthsm2.so")?;
+/// context.initialize(CInitializeArgs::OsThreads)?;
+/// let slot = context.get_slots_with_token()?[0];
+/// let session = context.open_ro_session(slot)?;
+/// session.login(cryptoki::session::UserType::User, Some(&AuthPin::new("1234".into())))?;
+///
+/// let key = Pkcs11Key::find_by_label(Arc::new(Mutex::new(session)), "token-signing")?;
+/// let claims = Claims::new(serde_json::json!({ "sub": "service" }))
+///     .set_duration_and_issuance(&TimeOptions::default(), Duration::try_minutes(10).unwrap());
+/// let token = Pkcs11::es256().token_async(&key.header(), &claims, &key).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(docsrs, doc(cfg(feature = "pkcs11")))]
+pub struct Pkcs11 {
+    scheme: Scheme,
+}
+
+impl Pkcs11 {
+    /// `RS256` algorithm (RSASSA-PKCS1-v1_5 with SHA-256).
+    pub const fn rs256() -> Self {
+        Self {
+            scheme: Scheme::Rs256,
+        }
+    }
+
+    /// `RS384` algorithm (RSASSA-PKCS1-v1_5 with SHA-384).
+    pub const fn rs384() -> Self {
+        Self {
+            scheme: Scheme::Rs384,
+        }
+    }
+
+    /// `RS512` algorithm (RSASSA-PKCS1-v1_5 with SHA-512).
+    pub const fn rs512() -> Self {
+        Self {
+            scheme: Scheme::Rs512,
+        }
+    }
+
+    /// `PS256` algorithm (RSASSA-PSS with SHA-256).
+    pub const fn ps256() -> Self {
+        Self {
+            scheme: Scheme::Ps256,
+        }
+    }
+
+    /// `ES256` algorithm (ECDSA on the P-256 curve with SHA-256).
+    pub const fn es256() -> Self {
+        Self {
+            scheme: Scheme::Es256,
+        }
+    }
+
+    /// `ES384` algorithm (ECDSA on the P-384 curve with SHA-384).
+    pub const fn es384() -> Self {
+        Self {
+            scheme: Scheme::Es384,
+        }
+    }
+
+    /// `EdDSA` algorithm (Ed25519).
+    pub const fn eddsa() -> Self {
+        Self {
+            scheme: Scheme::EdDsa,
+        }
+    }
+
+    /// Signs `message` with the key synchronously.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the session provider or the PKCS#11 module return an error.
+    pub fn sign_blocking(
+        &self,
+        key: &Pkcs11Key,
+        message: &[u8],
+    ) -> Result<RawSignature, Pkcs11Error> {
+        let (mechanism, data) = self.scheme.prepare(message);
+        let mut signature = Vec::new();
+        key.sessions.with_session(&mut |session| {
+            signature = session.sign(&mechanism, key.handle, &data)?;
+            Ok(())
+        })?;
+        Ok(RawSignature::new(signature))
+    }
+}
+
+impl AsyncAlgorithm for Pkcs11 {
+    type SigningKey = Pkcs11Key;
+    type Signature = RawSignature;
+
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Borrowed(match self.scheme {
+            Scheme::Rs256 => "RS256",
+            Scheme::Rs384 => "RS384",
+            Scheme::Rs512 => "RS512",
+            Scheme::Ps256 => "PS256",
+            Scheme::Es256 => "ES256",
+            Scheme::Es384 => "ES384",
+            Scheme::EdDsa => "EdDSA",
+        })
+    }
+
+    fn sign_async<'a>(
+        &'a self,
+        signing_key: &'a Self::SigningKey,
+        message: &'a [u8],
+    ) -> SignFuture<'a, Self::Signature> {
+        Box::pin(async move { Ok(self.sign_blocking(signing_key, message)?) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn preparing_signing_data() {
+        let (mechanism, data) = Scheme::Es256.prepare(b"test");
+        assert_eq!(mechanism.mechanism_type(), MechanismType::ECDSA);
+        assert_eq!(data, Sha256::digest(b"test").as_slice());
+
+        let (mechanism, data) = Scheme::Ps256.prepare(b"test");
+        assert_eq!(
+            mechanism.mechanism_type(),
+            MechanismType::SHA256_RSA_PKCS_PSS
+        );
+        assert_eq!(data, b"test");
+    }
+}
@@ -0,0 +1,285 @@
+//! Shared functionality for signing backends that delegate signing to a remote party
+//! (e.g., a KMS).
+
+#[cfg(any(
+    feature = "gcp-kms",
+    feature = "azure-key-vault",
+    feature = "vault-transit"
+))]
+use core::pin::Pin;
+use core::{fmt, future::Future, marker::PhantomData};
+
+#[cfg(any(
+    feature = "gcp-kms",
+    feature = "azure-key-vault",
+    feature = "vault-transit"
+))]
+use crate::alloc::String;
+use crate::{
+    alloc::{Box, Cow, Vec},
+    Algorithm, AlgorithmSignature, AsyncAlgorithm, AsyncVerifier, SignFuture, VerifyFuture,
+};
+
+/// Signature represented as raw bytes in the JWS format (e.g., `r || s` concatenation
+/// for ECDSA signatures).
+///
+/// This signature type is used by [`AsyncAlgorithm`](crate::AsyncAlgorithm)s backed by remote
+/// signers. Tokens signed in this way can be verified by the corresponding local
+/// [`Algorithm`](crate::Algorithm) (e.g., [`Es256`](crate::alg::Es256)).
+#[derive(Clone, PartialEq, Eq)]
+pub struct RawSignature(Vec<u8>);
+
+impl fmt::Debug for RawSignature {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_tuple("RawSignature")
+            .field(&self.0.len())
+            .finish()
+    }
+}
+
+impl RawSignature {
+    /// Wraps the provided signature bytes.
+    pub fn new(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+
+    /// Returns the signature bytes.
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.0
+    }
+}
+
+impl AlgorithmSignature for RawSignature {
+    fn try_from_slice(slice: &[u8]) -> anyhow::Result<Self> {
+        Ok(Self(slice.to_vec()))
+    }
+
+    fn as_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(&self.0)
+    }
+}
+
+/// Party signing messages on behalf of the algorithm `A`, e.g., another process, an HSM
+/// or a secure enclave.
+///
+/// A remote signer is used as a signing key for the [`Remote`] algorithm adapter: the token
+/// header and claims are assembled by this crate, and only the signing input is passed
+/// to the signer. Tokens produced in this way are verified by `A` as usual.
+///
+/// The trait is implemented for functions taking the message as an owned `Vec<u8>`
+/// and returning a future resolving to a signature.
+pub trait RemoteSigner<A: Algorithm + ?Sized> {
+    /// Signs the `message`.
+    fn sign<'a>(&'a self, message: &'a [u8]) -> SignFuture<'a, A::Signature>;
+}
+
+impl<A, F, Fut> RemoteSigner<A> for F
+where
+    A: Algorithm + ?Sized,
+    F: Fn(Vec<u8>) -> Fut,
+    Fut: Future<Output = anyhow::Result<A::Signature>> + Send + 'static,
+{
+    fn sign<'a>(&'a self, message: &'a [u8]) -> SignFuture<'a, A::Signature> {
+        Box::pin(self(message.to_vec()))
+    }
+}
+
+/// [`AsyncAlgorithm`] adapter for an [`Algorithm`] that delegates signing
+/// to a [`RemoteSigner`] of type `S`, which is used as the signing key.
+///
+/// # Examples
+///
+/// ```
+/// use jwt_compact::{
+///     prelude::*, alg::{Hs256, Hs256Key, Remote}, Algorithm, AsyncAlgorithmExt,
+/// };
+///
+/// # async fn test_remote() -> anyhow::Result<()> {
+/// // In real use cases, the signer would communicate with another process or an HSM.
+/// let signer = |message: Vec<u8>| async move {
+///     let key = Hs256Key::new(b"super_secret_key_donut_steel");
+///     anyhow::Ok(Hs256.sign(&key, &message))
+/// };
+/// let claims = Claims::new(serde_json::json!({ "sub": "alice" }));
+/// let token = Remote::new(Hs256)
+///     .token_async(&Header::empty(), &claims, &signer)
+///     .await?;
+///
+/// // The token is verified by the wrapped algorithm.
+/// let key = Hs256Key::new(b"super_secret_key_donut_steel");
+/// let token = UntrustedToken::new(&token)?;
+/// Hs256.validator::<serde_json::Value>(&key).validate(&token)?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct Remote<A, S> {
+    algorithm: A,
+    _signer: PhantomData<fn(&S)>,
+}
+
+impl<A: fmt::Debug, S> fmt::Debug for Remote<A, S> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_tuple("Remote")
+            .field(&self.algorithm)
+            .finish()
+    }
+}
+
+impl<A: Clone, S> Clone for Remote<A, S> {
+    fn clone(&self) -> Self {
+        Self::new(self.algorithm.clone())
+    }
+}
+
+impl<A: Copy, S> Copy for Remote<A, S> {}
+
+impl<A: Default, S> Default for Remote<A, S> {
+    fn default() -> Self {
+        Self::new(A::default())
+    }
+}
+
+impl<A, S> Remote<A, S> {
+    /// Wraps the specified algorithm.
+    pub const fn new(algorithm: A) -> Self {
+        Self {
+            algorithm,
+            _signer: PhantomData,
+        }
+    }
+
+    /// Returns the wrapped algorithm, which can be used to verify produced tokens.
+    pub fn inner(&self) -> &A {
+        &self.algorithm
+    }
+}
+
+impl<A, S> AsyncAlgorithm for Remote<A, S>
+where
+    A: Algorithm,
+    S: RemoteSigner<A>,
+{
+    type SigningKey = S;
+    type Signature = A::Signature;
+
+    fn name(&self) -> Cow<'static, str> {
+        self.algorithm.name()
+    }
+
+    fn sign_async<'a>(
+        &'a self,
+        signing_key: &'a S,
+        message: &'a [u8],
+    ) -> SignFuture<'a, A::Signature> {
+        signing_key.sign(message)
+    }
+}
+
+/// Verifies tokens locally with the wrapped algorithm.
+impl<A: Algorithm, S> AsyncVerifier for Remote<A, S> {
+    type VerifyingKey = A::VerifyingKey;
+    type Signature = A::Signature;
+
+    fn name(&self) -> Cow<'static, str> {
+        self.algorithm.name()
+    }
+
+    fn verify_signature_async<'a>(
+        &'a self,
+        signature: &'a A::Signature,
+        verifying_key: &'a A::VerifyingKey,
+        message: &'a [u8],
+    ) -> VerifyFuture<'a> {
+        let is_valid = self
+            .algorithm
+            .verify_signature(signature, verifying_key, message);
+        Box::pin(async move { Ok(is_valid) })
+    }
+}
+
+/// Boxed future resolving to an OAuth 2.0 access token.
+#[cfg(any(
+    feature = "gcp-kms",
+    feature = "azure-key-vault",
+    feature = "vault-transit"
+))]
+pub(crate) type AccessTokenFuture =
+    Pin<Box<dyn Future<Output = anyhow::Result<String>> + Send + 'static>>;
+
+/// Hook providing access tokens to authenticate requests to a cloud KMS.
+#[cfg(any(
+    feature = "gcp-kms",
+    feature = "azure-key-vault",
+    feature = "vault-transit"
+))]
+pub(crate) type AccessTokenFn = dyn Fn() -> AccessTokenFuture + Send + Sync;
+
+/// Wraps an access token provider into an [`AccessTokenFn`].
+#[cfg(any(
+    feature = "gcp-kms",
+    feature = "azure-key-vault",
+    feature = "vault-transit"
+))]
+pub(crate) fn access_token_fn<F, Fut>(provider: F) -> std::sync::Arc<AccessTokenFn>
+where
+    F: Fn() -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = anyhow::Result<String>> + Send + 'static,
+{
+    std::sync::Arc::new(move || -> AccessTokenFuture { Box::pin(provider()) })
+}
+
+/// Extracts DER bytes from a PEM-encoded document (e.g., a public key).
+#[cfg(any(feature = "gcp-kms", feature = "vault-transit"))]
+pub(crate) fn pem_to_der(pem: &str) -> Option<Vec<u8>> {
+    use base64ct::{Base64, Encoding};
+
+    let mut lines = pem.lines().map(str::trim).filter(|line| !line.is_empty());
+    if !lines.next()?.starts_with("-----BEGIN ") {
+        return None;
+    }
+    let mut encoded = String::new();
+    for line in lines {
+        if line.starts_with("-----END ") {
+            return Base64::decode_vec(&encoded).ok();
+        }
+        encoded.push_str(line);
+    }
+    None
+}
+
+/// Parses an RSA public key from a DER-encoded `SubjectPublicKeyInfo`.
+#[cfg(all(
+    feature = "rsa",
+    any(feature = "aws-kms", feature = "gcp-kms", feature = "vault-transit")
+))]
+pub(crate) fn rsa_public_key_from_der(der: &[u8]) -> anyhow::Result<rsa::RsaPublicKey> {
+    use rsa::pkcs8::DecodePublicKey as _;
+
+    rsa::RsaPublicKey::from_public_key_der(der).map_err(|err| anyhow::anyhow!(err))
+}
+
+/// Parses a P-256 public key from a DER-encoded `SubjectPublicKeyInfo`.
+#[cfg(all(
+    feature = "p256",
+    any(feature = "aws-kms", feature = "gcp-kms", feature = "vault-transit")
+))]
+pub(crate) fn es256_public_key_from_der(der: &[u8]) -> anyhow::Result<p256::ecdsa::VerifyingKey> {
+    use p256::pkcs8::DecodePublicKey as _;
+
+    p256::ecdsa::VerifyingKey::from_public_key_der(der).map_err(|err| anyhow::anyhow!(err))
+}
+
+#[cfg(all(test, any(feature = "gcp-kms", feature = "vault-transit")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracting_der_from_pem() {
+        let pem = "-----BEGIN PUBLIC KEY-----\nAAEC\nAwQ=\n-----END PUBLIC KEY-----\n";
+        assert_eq!(pem_to_der(pem).unwrap(), [0, 1, 2, 3, 4]);
+        assert!(pem_to_der("AAEC").is_none());
+        assert!(pem_to_der("-----BEGIN PUBLIC KEY-----\nAAEC\n").is_none());
+    }
+}
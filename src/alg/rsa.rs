@@ -2,21 +2,41 @@
 
 pub use rsa::{errors::Error as RsaError, RsaPrivateKey, RsaPublicKey};
 
+#[cfg(feature = "sign")]
 use rand_core::{CryptoRng, RngCore};
-use rsa::{
-    traits::{PrivateKeyParts, PublicKeyParts},
-    BigUint, Pkcs1v15Sign, Pss,
-};
+#[cfg(feature = "sign")]
+use rsa::traits::PrivateKeyParts;
+use rsa::{traits::PublicKeyParts, BigUint, Pkcs1v15Sign, Pss};
 use sha2::{Digest, Sha256, Sha384, Sha512};
 
 use core::{fmt, str::FromStr};
 
+#[cfg(feature = "pem")]
+use crate::pem::{PemError, PemFormat, PemKey};
+#[cfg(feature = "sign")]
+use crate::{
+    alg::{GenerateKey, SecretBytes},
+    jwk::{RsaPrimeFactor, RsaPrivateParts},
+};
 use crate::{
-    alg::{SecretBytes, StrongKey, WeakKeyError},
+    alg::{StrongKey, WeakKeyError},
     alloc::{Cow, String, ToOwned, Vec},
-    jwk::{JsonWebKey, JwkError, KeyType, RsaPrimeFactor, RsaPrivateParts},
+    jwk::{JsonWebKey, JwkError, KeyType},
+    validation::{ExpectationError, Expectations},
     Algorithm, AlgorithmSignature,
 };
+#[cfg(all(feature = "pem", feature = "sign"))]
+use rsa::{
+    pkcs1::{DecodeRsaPrivateKey, EncodeRsaPrivateKey},
+    pkcs8::{DecodePrivateKey, EncodePrivateKey},
+};
+#[cfg(feature = "pem")]
+use rsa::{
+    pkcs1::{DecodeRsaPublicKey, EncodeRsaPublicKey},
+    pkcs8::{DecodePublicKey, EncodePublicKey},
+};
+#[cfg(feature = "pem")]
+use zeroize::Zeroizing;
 
 /// RSA signature.
 #[derive(Debug)]
@@ -82,12 +102,13 @@ enum PaddingScheme {
     Pss(Pss),
 }
 
-/// Bit length of an RSA key modulus (aka RSA key length).
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+/// Bit length of an RSA key modulus (aka RSA key length). The default value is 2048 bits.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
 #[non_exhaustive]
 #[cfg_attr(docsrs, doc(cfg(feature = "rsa")))]
 pub enum ModulusBits {
     /// 2048 bits. This is the minimum recommended key length as of 2020.
+    #[default]
     TwoKibibytes,
     /// 3072 bits.
     ThreeKibibytes,
@@ -137,8 +158,7 @@ impl fmt::Display for ModulusBitsError {
     }
 }
 
-#[cfg(feature = "std")]
-impl std::error::Error for ModulusBitsError {}
+impl core::error::Error for ModulusBitsError {}
 
 /// Integrity algorithm using [RSA] digital signatures.
 ///
@@ -178,6 +198,7 @@ impl Algorithm for Rsa {
         Cow::Borrowed(self.alg_name())
     }
 
+    #[cfg(feature = "sign")]
     fn sign(&self, signing_key: &Self::SigningKey, message: &[u8]) -> Self::Signature {
         let digest = self.hash_alg.digest(message);
         let digest = digest.as_ref();
@@ -208,6 +229,8 @@ impl Algorithm for Rsa {
     }
 }
 
+impl_prepare_verifying_key!(Rsa);
+
 impl Rsa {
     const fn new(hash_alg: HashAlg, padding_alg: Padding) -> Self {
         Rsa {
@@ -251,11 +274,42 @@ impl Rsa {
     /// # Panics
     ///
     /// - Panics if the name is not one of the six RSA-based JWS algorithms. Prefer using
-    ///   the [`FromStr`] trait if the conversion is potentially fallible.
+    ///   the [`FromStr`] trait if the conversion is potentially fallible, or
+    ///   [`Self::with_allowed_name()`] if the name is selected dynamically.
     pub fn with_name(name: &str) -> Self {
         name.parse().unwrap()
     }
 
+    /// RSA based on the specified algorithm name, which must be allowed by `expectations`
+    /// (see [`Expectations::with_allowed_algorithm()`]). Use this method if the name
+    /// is selected dynamically, e.g., based on the `alg` field of the token header.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ExpectationError::DisallowedAlgorithm`] if the name is not allowed
+    /// or is not one of the six RSA-based JWS algorithms.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use jwt_compact::{alg::Rsa, validation::Expectations};
+    /// let expectations = Expectations::default()
+    ///     .with_allowed_algorithm("RS256")
+    ///     .with_allowed_algorithm("HS256");
+    /// assert_eq!(Rsa::with_allowed_name("RS256", &expectations)?, Rsa::rs256());
+    /// assert!(Rsa::with_allowed_name("PS256", &expectations).is_err());
+    /// assert!(Rsa::with_allowed_name("HS256", &expectations).is_err());
+    /// # Ok::<_, jwt_compact::validation::ExpectationError>(())
+    /// ```
+    pub fn with_allowed_name(
+        name: &str,
+        expectations: &Expectations,
+    ) -> Result<Self, ExpectationError> {
+        expectations.check_algorithm(name)?;
+        name.parse()
+            .map_err(|_| ExpectationError::DisallowedAlgorithm(name.to_owned()))
+    }
+
     fn padding_scheme(self) -> PaddingScheme {
         match self.padding_alg {
             Padding::Pkcs1v15 => PaddingScheme::Pkcs1v15(match self.hash_alg {
@@ -286,6 +340,8 @@ impl Rsa {
         }
     }
 
+    #[cfg(feature = "sign")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "sign")))]
     /// Generates a new key pair with the specified modulus bit length (aka key length).
     pub fn generate<R: CryptoRng + RngCore>(
         rng: &mut R,
@@ -324,9 +380,9 @@ impl fmt::Display for RsaParseError {
     }
 }
 
-#[cfg(feature = "std")]
-impl std::error::Error for RsaParseError {}
+impl core::error::Error for RsaParseError {}
 
+#[cfg(feature = "sign")]
 impl StrongKey<RsaPrivateKey> {
     /// Converts this private key to a public key.
     pub fn to_public_key(&self) -> StrongKey<RsaPublicKey> {
@@ -334,6 +390,23 @@ impl StrongKey<RsaPrivateKey> {
     }
 }
 
+#[cfg(feature = "sign")]
+/// Key generation is parameterized by the modulus length. Generated keys are guaranteed
+/// to be [strong](StrongKey).
+impl GenerateKey<Rsa> for RsaPrivateKey {
+    type Params = ModulusBits;
+
+    fn generate_pair_with<R: CryptoRng + RngCore>(
+        rng: &mut R,
+        modulus_bits: ModulusBits,
+    ) -> (Self, RsaPublicKey) {
+        let (signing_key, verifying_key) = Rsa::generate(rng, modulus_bits)
+            .expect("RSA key generation cannot fail for supported modulus lengths");
+        (signing_key.into_inner(), verifying_key.into_inner())
+    }
+}
+
+#[cfg(feature = "sign")]
 impl TryFrom<RsaPrivateKey> for StrongKey<RsaPrivateKey> {
     type Error = WeakKeyError<RsaPrivateKey>;
 
@@ -387,6 +460,7 @@ impl TryFrom<&JsonWebKey<'_>> for RsaPublicKey {
     }
 }
 
+#[cfg(feature = "sign")]
 /// ⚠ **Warning.** Contrary to [RFC 7518], this implementation does not set `dp`, `dq`, and `qi`
 /// fields in the JWK root object, as well as `d` and `t` fields for additional factors
 /// (i.e., in the `oth` array).
@@ -424,6 +498,7 @@ impl<'a> From<&'a RsaPrivateKey> for JsonWebKey<'a> {
     }
 }
 
+#[cfg(feature = "sign")]
 /// ⚠ **Warning.** Contrary to [RFC 7518] (at least, in spirit), this conversion ignores
 /// `dp`, `dq`, and `qi` fields from JWK, as well as `d` and `t` fields for additional factors.
 ///
@@ -471,3 +546,59 @@ impl TryFrom<&JsonWebKey<'_>> for RsaPrivateKey {
         Ok(key)
     }
 }
+
+#[cfg(all(feature = "pem", feature = "sign"))]
+impl PemKey for RsaPrivateKey {
+    const DEFAULT_FORMAT: PemFormat = PemFormat::Pkcs8;
+
+    fn decode_der(format: PemFormat, der: &[u8]) -> Result<Self, PemError> {
+        match format {
+            PemFormat::Pkcs8 => {
+                <Self as DecodePrivateKey>::from_pkcs8_der(der).map_err(PemError::malformed)
+            }
+            PemFormat::Pkcs1PrivateKey => {
+                <Self as DecodeRsaPrivateKey>::from_pkcs1_der(der).map_err(PemError::malformed)
+            }
+            _ => Err(PemError::UnsupportedFormat(format)),
+        }
+    }
+
+    fn encode_der(&self, format: PemFormat) -> Result<Zeroizing<Vec<u8>>, PemError> {
+        let document = match format {
+            PemFormat::Pkcs8 => {
+                EncodePrivateKey::to_pkcs8_der(self).map_err(PemError::malformed)?
+            }
+            PemFormat::Pkcs1PrivateKey => {
+                EncodeRsaPrivateKey::to_pkcs1_der(self).map_err(PemError::malformed)?
+            }
+            _ => return Err(PemError::UnsupportedFormat(format)),
+        };
+        Ok(Zeroizing::new(document.as_bytes().to_vec()))
+    }
+}
+
+#[cfg(feature = "pem")]
+impl PemKey for RsaPublicKey {
+    const DEFAULT_FORMAT: PemFormat = PemFormat::Spki;
+
+    fn decode_der(format: PemFormat, der: &[u8]) -> Result<Self, PemError> {
+        match format {
+            PemFormat::Spki => Self::from_public_key_der(der).map_err(PemError::malformed),
+            PemFormat::Pkcs1PublicKey => {
+                <Self as DecodeRsaPublicKey>::from_pkcs1_der(der).map_err(PemError::malformed)
+            }
+            _ => Err(PemError::UnsupportedFormat(format)),
+        }
+    }
+
+    fn encode_der(&self, format: PemFormat) -> Result<Zeroizing<Vec<u8>>, PemError> {
+        let document = match format {
+            PemFormat::Spki => self.to_public_key_der().map_err(PemError::malformed)?,
+            PemFormat::Pkcs1PublicKey => {
+                EncodeRsaPublicKey::to_pkcs1_der(self).map_err(PemError::malformed)?
+            }
+            _ => return Err(PemError::UnsupportedFormat(format)),
+        };
+        Ok(Zeroizing::new(document.as_bytes().to_vec()))
+    }
+}
@@ -0,0 +1,491 @@
+//! `EdDSA` and `ES256` signing using keys held by an SSH agent.
+
+use base64ct::{Base64Unpadded, Encoding};
+use sha2::{Digest, Sha256};
+
+use core::fmt;
+use std::{
+    io::{self, Read, Write},
+    sync::Arc,
+};
+
+use super::remote::RawSignature;
+use crate::{
+    alloc::{Box, Cow, String, ToOwned, Vec},
+    AsyncAlgorithm, Header, SignFuture,
+};
+
+const SSH_AGENT_FAILURE: u8 = 5;
+const SSH2_AGENTC_REQUEST_IDENTITIES: u8 = 11;
+const SSH2_AGENT_IDENTITIES_ANSWER: u8 = 12;
+const SSH2_AGENTC_SIGN_REQUEST: u8 = 13;
+const SSH2_AGENT_SIGN_RESPONSE: u8 = 14;
+
+/// Maximum agent message length accepted by the client (the same as in OpenSSH).
+const MAX_MESSAGE_LEN: usize = 256 * 1_024;
+
+const ED25519_KEY_TYPE: &str = "ssh-ed25519";
+const ECDSA_P256_KEY_TYPE: &str = "ecdsa-sha2-nistp256";
+
+/// Errors that can occur when signing with keys held by an SSH agent.
+#[derive(Debug)]
+#[non_exhaustive]
+#[cfg_attr(docsrs, doc(cfg(feature = "ssh-agent")))]
+pub enum SshAgentError {
+    /// `SSH_AUTH_SOCK` environment variable is not set.
+    NoAgent,
+    /// I/O error communicating with the agent.
+    Io(io::Error),
+    /// Agent has sent a malformed or unexpected message.
+    Protocol(&'static str),
+    /// Agent has refused to perform the operation (e.g., because the key was removed
+    /// or the user has denied confirmation).
+    Refused,
+    /// Key with the specified comment is not held by the agent.
+    KeyNotFound(String),
+    /// Key type doesn't match the signing algorithm.
+    AlgorithmMismatch {
+        /// SSH type of the key (e.g., `ssh-ed25519`).
+        key: String,
+        /// Name of the signing algorithm.
+        signing: &'static str,
+    },
+}
+
+impl fmt::Display for SshAgentError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoAgent => formatter.write_str("SSH_AUTH_SOCK is not set"),
+            Self::Io(err) => write!(formatter, "cannot communicate with SSH agent: {err}"),
+            Self::Protocol(message) => write!(formatter, "SSH agent protocol error: {message}"),
+            Self::Refused => formatter.write_str("SSH agent has refused the operation"),
+            Self::KeyNotFound(comment) => {
+                write!(
+                    formatter,
+                    "key with comment `{comment}` is not held by SSH agent"
+                )
+            }
+            Self::AlgorithmMismatch { key, signing } => write!(
+                formatter,
+                "`{key}` key cannot be used with `{signing}` algorithm"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SshAgentError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for SshAgentError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+/// Bidirectional connection to an SSH agent.
+#[cfg_attr(docsrs, doc(cfg(feature = "ssh-agent")))]
+pub trait AgentConnection: Read + Write {}
+
+impl<T: Read + Write> AgentConnection for T {}
+
+type Connector = dyn Fn() -> io::Result<Box<dyn AgentConnection>> + Send + Sync;
+
+/// Client of the [SSH agent protocol] used to list and access keys held by the agent.
+///
+/// The client opens a new connection for each request, so it doesn't hold any resources
+/// between requests and can be freely cloned.
+///
+/// [SSH agent protocol]: https://datatracker.ietf.org/doc/html/draft-miller-ssh-agent
+#[derive(Clone)]
+#[cfg_attr(docsrs, doc(cfg(feature = "ssh-agent")))]
+pub struct SshAgentClient {
+    connector: Arc<Connector>,
+}
+
+impl fmt::Debug for SshAgentClient {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("SshAgentClient")
+            .finish_non_exhaustive()
+    }
+}
+
+impl SshAgentClient {
+    /// Creates a client connecting to the agent specified by the `SSH_AUTH_SOCK`
+    /// environment variable.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the environment variable is not set.
+    #[cfg(unix)]
+    pub fn from_env() -> Result<Self, SshAgentError> {
+        let path = std::env::var_os("SSH_AUTH_SOCK").ok_or(SshAgentError::NoAgent)?;
+        Ok(Self::unix(path))
+    }
+
+    /// Creates a client connecting to the agent listening on the specified Unix socket.
+    #[cfg(unix)]
+    pub fn unix(path: impl Into<std::path::PathBuf>) -> Self {
+        let path = path.into();
+        Self::with_connector(move || std::os::unix::net::UnixStream::connect(&path))
+    }
+
+    /// Creates a client using a custom connector (e.g., for Windows named pipes
+    /// or forwarded agents).
+    pub fn with_connector<F, C>(connector: F) -> Self
+    where
+        F: Fn() -> io::Result<C> + Send + Sync + 'static,
+        C: AgentConnection + 'static,
+    {
+        Self {
+            connector: Arc::new(move || -> io::Result<Box<dyn AgentConnection>> {
+                Ok(Box::new(connector()?))
+            }),
+        }
+    }
+
+    fn request(&self, message: &[u8]) -> Result<(u8, Vec<u8>), SshAgentError> {
+        let mut connection = (self.connector)()?;
+        let len = u32::try_from(message.len())
+            .map_err(|_| SshAgentError::Protocol("request is too large"))?;
+        connection.write_all(&len.to_be_bytes())?;
+        connection.write_all(message)?;
+        connection.flush()?;
+
+        let mut len = [0_u8; 4];
+        connection.read_exact(&mut len)?;
+        let len = u32::from_be_bytes(len) as usize;
+        if len == 0 || len > MAX_MESSAGE_LEN {
+            return Err(SshAgentError::Protocol("invalid response length"));
+        }
+        let mut response = vec![0_u8; len];
+        connection.read_exact(&mut response)?;
+        let message_type = response.remove(0);
+        if message_type == SSH_AGENT_FAILURE {
+            return Err(SshAgentError::Refused);
+        }
+        Ok((message_type, response))
+    }
+
+    /// Lists keys held by the agent. Keys of types unsupported by [`SshAgent`]
+    /// (e.g., RSA keys) are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if communicating with the agent fails.
+    pub fn keys(&self) -> Result<Vec<SshAgentKey>, SshAgentError> {
+        let (message_type, response) = self.request(&[SSH2_AGENTC_REQUEST_IDENTITIES])?;
+        if message_type != SSH2_AGENT_IDENTITIES_ANSWER {
+            return Err(SshAgentError::Protocol(
+                "unexpected response to identities request",
+            ));
+        }
+
+        let mut reader = WireReader(&response);
+        let count = reader.read_u32()?;
+        let mut keys = Vec::new();
+        for _ in 0..count {
+            let blob = reader.read_string()?;
+            let comment = reader.read_string()?;
+            let comment = String::from_utf8_lossy(comment).into_owned();
+            if let Some(key) = SshAgentKey::new(self.clone(), blob, comment)? {
+                keys.push(key);
+            }
+        }
+        Ok(keys)
+    }
+
+    /// Finds a key by its comment (usually, the path to the key file or `user@host`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if communicating with the agent fails, or if the agent doesn't hold
+    /// a supported key with the specified comment.
+    pub fn find_key(&self, comment: &str) -> Result<SshAgentKey, SshAgentError> {
+        self.keys()?
+            .into_iter()
+            .find(|key| key.comment == comment)
+            .ok_or_else(|| SshAgentError::KeyNotFound(comment.to_owned()))
+    }
+
+    fn sign(&self, key_blob: &[u8], message: &[u8]) -> Result<Vec<u8>, SshAgentError> {
+        let mut request = vec![SSH2_AGENTC_SIGN_REQUEST];
+        write_string(&mut request, key_blob)?;
+        write_string(&mut request, message)?;
+        request.extend_from_slice(&0_u32.to_be_bytes()); // flags
+
+        let (message_type, response) = self.request(&request)?;
+        if message_type != SSH2_AGENT_SIGN_RESPONSE {
+            return Err(SshAgentError::Protocol(
+                "unexpected response to sign request",
+            ));
+        }
+        let mut reader = WireReader(&response);
+        let signature = reader.read_string()?;
+        Ok(signature.to_vec())
+    }
+}
+
+/// Reader for SSH wire encoding.
+struct WireReader<'a>(&'a [u8]);
+
+impl<'a> WireReader<'a> {
+    fn read_u32(&mut self) -> Result<u32, SshAgentError> {
+        if self.0.len() < 4 {
+            return Err(SshAgentError::Protocol("unexpected end of message"));
+        }
+        let (head, tail) = self.0.split_at(4);
+        self.0 = tail;
+        Ok(u32::from_be_bytes(head.try_into().unwrap()))
+    }
+
+    fn read_string(&mut self) -> Result<&'a [u8], SshAgentError> {
+        let len = self.read_u32()? as usize;
+        if self.0.len() < len {
+            return Err(SshAgentError::Protocol("unexpected end of message"));
+        }
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+        Ok(head)
+    }
+}
+
+fn write_string(buffer: &mut Vec<u8>, bytes: &[u8]) -> Result<(), SshAgentError> {
+    let len =
+        u32::try_from(bytes.len()).map_err(|_| SshAgentError::Protocol("string is too large"))?;
+    buffer.extend_from_slice(&len.to_be_bytes());
+    buffer.extend_from_slice(bytes);
+    Ok(())
+}
+
+/// Converts an SSH `mpint` into a fixed-size big-endian scalar.
+fn mpint_to_scalar(mpint: &[u8], scalar_len: usize, output: &mut Vec<u8>) -> Option<()> {
+    let mpint = &mpint[mpint.iter().take_while(|&&byte| byte == 0).count()..];
+    if mpint.len() > scalar_len {
+        return None;
+    }
+    output.resize(output.len() + scalar_len - mpint.len(), 0);
+    output.extend_from_slice(mpint);
+    Some(())
+}
+
+/// Key held by an SSH agent. Used as a signing key for the [`SshAgent`] algorithm;
+/// the private key never leaves the agent.
+///
+/// Supported key types are `ssh-ed25519` and `ecdsa-sha2-nistp256`. By default, the key ID
+/// is the SHA-256 fingerprint of the key in the OpenSSH format (e.g., `SHA256:...`), i.e.,
+/// the same value that is output by `ssh-add -l`.
+#[derive(Clone)]
+#[cfg_attr(docsrs, doc(cfg(feature = "ssh-agent")))]
+pub struct SshAgentKey {
+    client: SshAgentClient,
+    blob: Vec<u8>,
+    key_type: &'static str,
+    public_key: Vec<u8>,
+    comment: String,
+    key_id: String,
+}
+
+impl fmt::Debug for SshAgentKey {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("SshAgentKey")
+            .field("key_type", &self.key_type)
+            .field("comment", &self.comment)
+            .field("key_id", &self.key_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SshAgentKey {
+    /// Parses a key blob, returning `None` if the key type is not supported.
+    fn new(
+        client: SshAgentClient,
+        blob: &[u8],
+        comment: String,
+    ) -> Result<Option<Self>, SshAgentError> {
+        let mut reader = WireReader(blob);
+        let (key_type, public_key) = match reader.read_string()? {
+            b"ssh-ed25519" => (ED25519_KEY_TYPE, reader.read_string()?),
+            b"ecdsa-sha2-nistp256" => {
+                if reader.read_string()? != b"nistp256" {
+                    return Err(SshAgentError::Protocol("invalid ECDSA key curve"));
+                }
+                (ECDSA_P256_KEY_TYPE, reader.read_string()?)
+            }
+            _ => return Ok(None),
+        };
+
+        let fingerprint = Base64Unpadded::encode_string(&Sha256::digest(blob));
+        Ok(Some(Self {
+            client,
+            blob: blob.to_vec(),
+            key_type,
+            public_key: public_key.to_vec(),
+            comment,
+            key_id: format!("SHA256:{fingerprint}"),
+        }))
+    }
+
+    /// Sets the key ID (`kid`) for tokens signed with this key.
+    #[must_use]
+    pub fn with_key_id(mut self, key_id: impl Into<String>) -> Self {
+        self.key_id = key_id.into();
+        self
+    }
+
+    /// Returns the SSH type of the key (`ssh-ed25519` or `ecdsa-sha2-nistp256`).
+    pub fn key_type(&self) -> &str {
+        self.key_type
+    }
+
+    /// Returns the key comment.
+    pub fn comment(&self) -> &str {
+        &self.comment
+    }
+
+    /// Returns the raw public key: 32 bytes for Ed25519 keys, or an uncompressed SEC1-encoded
+    /// point for ECDSA keys. The public key can be converted to a verifying key for
+    /// the corresponding [`Algorithm`](crate::Algorithm) (e.g., [`Es256`](crate::alg::Es256)).
+    pub fn public_key(&self) -> &[u8] {
+        &self.public_key
+    }
+
+    /// Returns the key ID, which is the SHA-256 key fingerprint unless overridden.
+    pub fn key_id(&self) -> &str {
+        &self.key_id
+    }
+
+    /// Returns a header with the [key ID](Self::key_id()) set.
+    pub fn header(&self) -> Header {
+        Header::empty().with_key_id(self.key_id.clone())
+    }
+}
+
+/// Signing algorithm using keys held by an SSH agent. Supports `EdDSA` (`ssh-ed25519` keys)
+/// and `ES256` (`ecdsa-sha2-nistp256` keys).
+///
+/// This allows minting tokens from existing SSH identities (including keys on hardware tokens
+/// exposed via the agent) without exporting private keys. Tokens can be verified
+/// by the corresponding local [`Algorithm`](crate::Algorithm).
+///
+/// This algorithm implements [`AsyncAlgorithm`] since signing with an agent may fail. Note that
+/// communication with the agent is blocking and is performed when the signing future is polled;
+/// [`SshAgent::sign_blocking()`] can be used in synchronous contexts.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use chrono::Duration;
+/// use jwt_compact::{
+///     alg::{SshAgent, SshAgentClient},
+///     prelude::*,
+///     AsyncAlgorithmExt,
+/// };
+///
+/// # async fn test_wrapper() -> anyhow::Result<()> {
+/// let agent = SshAgentClient::from_env()?;
+/// let key = agent.find_key("alice@laptop")?;
+/// let claims = Claims::new(serde_json::json!({ "sub": "alice" }))
+///     .set_duration_and_issuance(&TimeOptions::default(), Duration::try_minutes(5).unwrap());
+/// let token = SshAgent::eddsa()
+///     .token_async(&key.header(), &claims, &key)
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(docsrs, doc(cfg(feature = "ssh-agent")))]
+pub struct SshAgent {
+    key_type: &'static str,
+}
+
+impl SshAgent {
+    /// `EdDSA` algorithm using `ssh-ed25519` keys.
+    pub const fn eddsa() -> Self {
+        Self {
+            key_type: ED25519_KEY_TYPE,
+        }
+    }
+
+    /// `ES256` algorithm using `ecdsa-sha2-nistp256` keys.
+    pub const fn es256() -> Self {
+        Self {
+            key_type: ECDSA_P256_KEY_TYPE,
+        }
+    }
+
+    fn jws_name(self) -> &'static str {
+        if self.key_type == ED25519_KEY_TYPE {
+            "EdDSA"
+        } else {
+            "ES256"
+        }
+    }
+
+    /// Signs `message` with the key synchronously.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if communicating with the agent fails, or if the key type
+    /// doesn't match the algorithm.
+    pub fn sign_blocking(
+        &self,
+        key: &SshAgentKey,
+        message: &[u8],
+    ) -> Result<RawSignature, SshAgentError> {
+        if key.key_type != self.key_type {
+            return Err(SshAgentError::AlgorithmMismatch {
+                key: key.key_type.to_owned(),
+                signing: self.jws_name(),
+            });
+        }
+
+        let signature = key.client.sign(&key.blob, message)?;
+        let mut reader = WireReader(&signature);
+        if reader.read_string()? != self.key_type.as_bytes() {
+            return Err(SshAgentError::Protocol("unexpected signature type"));
+        }
+        let blob = reader.read_string()?;
+
+        let signature = if self.key_type == ED25519_KEY_TYPE {
+            if blob.len() != 64 {
+                return Err(SshAgentError::Protocol("invalid Ed25519 signature"));
+            }
+            blob.to_vec()
+        } else {
+            // ECDSA signature blob consists of `r` and `s` encoded as `mpint`s.
+            let mut reader = WireReader(blob);
+            let mut raw = Vec::with_capacity(64);
+            for _ in 0..2 {
+                mpint_to_scalar(reader.read_string()?, 32, &mut raw)
+                    .ok_or(SshAgentError::Protocol("invalid ECDSA signature"))?;
+            }
+            raw
+        };
+        Ok(RawSignature::new(signature))
+    }
+}
+
+impl AsyncAlgorithm for SshAgent {
+    type SigningKey = SshAgentKey;
+    type Signature = RawSignature;
+
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Borrowed(self.jws_name())
+    }
+
+    fn sign_async<'a>(
+        &'a self,
+        signing_key: &'a Self::SigningKey,
+        message: &'a [u8],
+    ) -> SignFuture<'a, Self::Signature> {
+        Box::pin(async move { Ok(self.sign_blocking(signing_key, message)?) })
+    }
+}
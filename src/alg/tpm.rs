@@ -0,0 +1,391 @@
+//! `ES256` and `RS256` signing using keys resident in a TPM 2.0 via the [`tss_esapi`] crate.
+
+use base64ct::{Base64UrlUnpadded, Encoding};
+use sha2::{Digest as _, Sha256};
+use tss_esapi::{
+    constants::tss::{TPM2_RH_NULL, TPM2_ST_HASHCHECK},
+    handles::{KeyHandle, ObjectHandle, PersistentTpmHandle, TpmHandle},
+    interface_types::algorithm::HashingAlgorithm,
+    structures::{Digest, HashScheme, HashcheckTicket, Public, Signature, SignatureScheme},
+    tss2_esys::{TPM2B_DIGEST, TPMT_TK_HASHCHECK},
+    Context,
+};
+
+use core::fmt;
+use std::sync::Arc;
+
+use super::RawSignature;
+use crate::{
+    alloc::{Box, Cow, String, Vec},
+    AsyncAlgorithm, Header, SignFuture,
+};
+
+/// Errors that can occur when signing with TPM-resident keys.
+#[derive(Debug)]
+#[non_exhaustive]
+#[cfg_attr(docsrs, doc(cfg(feature = "tpm")))]
+pub enum TpmError {
+    /// Error returned by the TPM or the TSS libraries.
+    Tss(tss_esapi::Error),
+    /// Context provider cannot provide a TPM context.
+    Context(anyhow::Error),
+    /// Handle is not in the persistent handle range (`0x81xx_xxxx`).
+    InvalidHandle(u32),
+    /// TPM has returned a signature of an unexpected type or size.
+    UnexpectedSignature,
+}
+
+impl fmt::Display for TpmError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tss(err) => write!(formatter, "TPM error: {err}"),
+            Self::Context(err) => write!(formatter, "cannot obtain TPM context: {err}"),
+            Self::InvalidHandle(handle) => {
+                write!(formatter, "{handle:#010x} is not a persistent TPM handle")
+            }
+            Self::UnexpectedSignature => formatter.write_str("TPM returned unexpected signature"),
+        }
+    }
+}
+
+impl std::error::Error for TpmError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Tss(err) => Some(err),
+            Self::Context(err) => Some(err.as_ref()),
+            Self::InvalidHandle(_) | Self::UnexpectedSignature => None,
+        }
+    }
+}
+
+impl From<tss_esapi::Error> for TpmError {
+    fn from(err: tss_esapi::Error) -> Self {
+        Self::Tss(err)
+    }
+}
+
+/// Action performed by [`TpmContexts`] with a TPM context.
+pub type ContextAction<'a> = dyn FnMut(&mut Context) -> tss_esapi::Result<()> + 'a;
+
+/// Provider of TPM contexts used by [`TpmKey`]s.
+///
+/// ESAPI contexts are not thread-safe, so this crate doesn't own them. Instead, the provider
+/// decides how contexts are created and shared (e.g., a context per thread, or a dedicated
+/// thread serving signing requests), and which TCTI is used to reach the TPM.
+///
+/// # Examples
+///
+/// ```no_run
+/// use jwt_compact::alg::{ContextAction, TpmContexts, TpmError};
+/// use tss_esapi::{Context, TctiNameConf};
+/// use std::cell::RefCell;
+///
+/// thread_local! {
+///     static CONTEXT: RefCell<Option<Context>> = RefCell::new(None);
+/// }
+///
+/// struct ThreadLocalContexts;
+///
+/// impl TpmContexts for ThreadLocalContexts {
+///     fn with_context(&self, action: &mut ContextAction<'_>) -> Result<(), TpmError> {
+///         CONTEXT.with(|context| {
+///             let mut context = context.borrow_mut();
+///             if context.is_none() {
+///                 let tcti = TctiNameConf::from_environment_variable()?;
+///                 *context = Some(Context::new(tcti)?);
+///             }
+///             Ok(action(context.as_mut().unwrap())?)
+///         })
+///     }
+/// }
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "tpm")))]
+pub trait TpmContexts: Send + Sync {
+    /// Runs `action` with a TPM context.
+    ///
+    /// # Errors
+    ///
+    /// Should return an error if a context cannot be obtained, or if `action` fails.
+    fn with_context(&self, action: &mut ContextAction<'_>) -> Result<(), TpmError>;
+}
+
+/// Handle to a signing key persisted in a TPM (e.g., via `TPM2_EvictControl`). Used
+/// as a signing key for the [`Tpm`] algorithm; the private key never leaves the TPM.
+///
+/// Persistent handles (rather than ESAPI object handles, which are bound to a context)
+/// make keys addressable across processes and reboots. The TPM [name](Self::name()) of
+/// the key, which is a digest of its public area, is used as the key ID by default. This name
+/// is what TPM attestation structures (e.g., produced by `TPM2_Certify`) refer to, so verifiers
+/// can tie a token to an attested key.
+///
+/// The key must be a signing key without an authorization value, or the authorization
+/// must be set up by the [context provider](TpmContexts).
+#[derive(Clone)]
+#[cfg_attr(docsrs, doc(cfg(feature = "tpm")))]
+pub struct TpmKey {
+    contexts: Arc<dyn TpmContexts>,
+    handle: PersistentTpmHandle,
+    name: Vec<u8>,
+    key_id: String,
+}
+
+impl fmt::Debug for TpmKey {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("TpmKey")
+            .field("handle", &self.handle)
+            .field("key_id", &self.key_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl TpmKey {
+    /// Loads the key with the specified persistent handle (e.g., `0x8101_0001`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the handle is invalid or doesn't exist on the TPM.
+    pub fn persistent(contexts: Arc<dyn TpmContexts>, handle: u32) -> Result<Self, TpmError> {
+        let handle =
+            PersistentTpmHandle::new(handle).map_err(|_| TpmError::InvalidHandle(handle))?;
+        let mut name = Vec::new();
+        with_key(&*contexts, handle, &mut |context, key| {
+            name = context.tr_get_name(key.into())?.value().to_vec();
+            Ok(())
+        })?;
+
+        let key_id = Base64UrlUnpadded::encode_string(&name);
+        Ok(Self {
+            contexts,
+            handle,
+            name,
+            key_id,
+        })
+    }
+
+    /// Sets the key ID (`kid`) for tokens signed with this key.
+    #[must_use]
+    pub fn with_key_id(mut self, key_id: impl Into<String>) -> Self {
+        self.key_id = key_id.into();
+        self
+    }
+
+    /// Returns the persistent handle of the key.
+    pub fn handle(&self) -> u32 {
+        TpmHandle::from(self.handle).into()
+    }
+
+    /// Returns the TPM name of the key (the hash algorithm ID followed by the digest
+    /// of the public area).
+    pub fn name(&self) -> &[u8] {
+        &self.name
+    }
+
+    /// Reads the public area of the key from the TPM. The public area can be used to export
+    /// the verifying key, or to check key attributes (e.g., `fixedTPM`) during attestation.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the TPM returns an error.
+    pub fn public(&self) -> Result<Public, TpmError> {
+        let mut public = None;
+        with_key(&*self.contexts, self.handle, &mut |context, key| {
+            public = Some(context.read_public(key)?.0);
+            Ok(())
+        })?;
+        public.ok_or_else(|| TpmError::Context(anyhow::anyhow!("context action was not run")))
+    }
+
+    /// Returns the key ID, which is the base64url-encoded [TPM name](Self::name())
+    /// unless overridden.
+    pub fn key_id(&self) -> &str {
+        &self.key_id
+    }
+
+    /// Returns a header with the [key ID](Self::key_id()) set.
+    pub fn header(&self) -> Header {
+        Header::empty().with_key_id(self.key_id.clone())
+    }
+}
+
+/// Resolves a persistent handle in a context, runs `action` and closes the ESAPI handle.
+fn with_key(
+    contexts: &dyn TpmContexts,
+    handle: PersistentTpmHandle,
+    action: &mut dyn FnMut(&mut Context, KeyHandle) -> tss_esapi::Result<()>,
+) -> Result<(), TpmError> {
+    contexts.with_context(&mut |context| {
+        let mut object: ObjectHandle = context.tr_from_tpm_public(handle.into())?;
+        let result = action(context, object.into());
+        context.tr_close(&mut object)?;
+        result
+    })
+}
+
+/// Signing scheme supported by [`Tpm`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Scheme {
+    Es256,
+    Rs256,
+}
+
+/// Signing algorithm using keys resident in a TPM 2.0. Supports `ES256` (P-256 keys)
+/// and `RS256` (RSA keys).
+///
+/// The message digest is computed locally; the TPM signs it using a null hashcheck ticket,
+/// so the key must not be restricted. ECDSA signatures returned by the TPM are converted
+/// to the JWS format.
+///
+/// This algorithm implements [`AsyncAlgorithm`] since signing with a TPM may fail. Note that
+/// TPM commands are blocking and are executed when the signing future is polled;
+/// [`Tpm::sign_blocking()`] can be used in synchronous contexts.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use chrono::Duration;
+/// use jwt_compact::{
+///     alg::{ContextAction, Tpm, TpmContexts, TpmError, TpmKey},
+///     prelude::*,
+///     AsyncAlgorithmExt,
+/// };
+/// use tss_esapi::{Context, TctiNameConf};
+/// use std::sync::Arc;
+///
+/// // Creates a new TPM context for each operation; see `TpmContexts` docs
+/// // for a way to reuse contexts.
+/// struct Contexts;
+///
+/// impl TpmContexts for Contexts {
+///     fn with_context(&self, action: &mut ContextAction<'_>) -> Result<(), TpmError> {
+///         let tcti = TctiNameConf::from_environment_variable()?;
+///         Ok(action(&mut Context::new(tcti)?)?)
+///     }
+/// }
+///
+/// # async fn test_wrapper() -> anyhow::Result<()> {
+/// let key = TpmKey::persistent(Arc::new(Contexts), 0x8101_0001)?;
+/// let claims = Claims::new(serde_json::json!({ "sub": "device-42" }))
+///     .set_duration_and_issuance(&TimeOptions::default(), Duration::try_minutes(10).unwrap());
+/// // The header contains the TPM name of the key as `kid`.
+/// let token = Tpm::es256().token_async(&key.header(), &claims, &key).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(docsrs, doc(cfg(feature = "tpm")))]
+pub struct Tpm {
+    scheme: Scheme,
+}
+
+impl Tpm {
+    /// `ES256` algorithm (ECDSA on the P-256 curve with SHA-256).
+    pub const fn es256() -> Self {
+        Self {
+            scheme: Scheme::Es256,
+        }
+    }
+
+    /// `RS256` algorithm (RSASSA-PKCS1-v1_5 with SHA-256).
+    pub const fn rs256() -> Self {
+        Self {
+            scheme: Scheme::Rs256,
+        }
+    }
+
+    /// Signs `message` with the key synchronously.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the context provider or the TPM return an error, or if the key type
+    /// doesn't match the algorithm.
+    pub fn sign_blocking(&self, key: &TpmKey, message: &[u8]) -> Result<RawSignature, TpmError> {
+        let hash_scheme = HashScheme::new(HashingAlgorithm::Sha256);
+        let scheme = match self.scheme {
+            Scheme::Es256 => SignatureScheme::EcDsa { hash_scheme },
+            Scheme::Rs256 => SignatureScheme::RsaSsa { hash_scheme },
+        };
+        let digest = Sha256::digest(message);
+        let null_ticket = TPMT_TK_HASHCHECK {
+            tag: TPM2_ST_HASHCHECK,
+            hierarchy: TPM2_RH_NULL,
+            digest: TPM2B_DIGEST::default(),
+        };
+
+        let mut signature = None;
+        with_key(&*key.contexts, key.handle, &mut |context, key| {
+            let digest = Digest::try_from(digest.as_slice())?;
+            let validation = HashcheckTicket::try_from(null_ticket)?;
+            let output = context.execute_with_nullauth_session(|context| {
+                context.sign(key, digest, scheme, validation)
+            })?;
+            signature = Some(output);
+            Ok(())
+        })?;
+
+        let signature = match (self.scheme, signature) {
+            (Scheme::Es256, Some(Signature::EcDsa(signature))) => {
+                let r = signature.signature_r().value();
+                let s = signature.signature_s().value();
+                ecdsa_raw_signature(r, s, 32).ok_or(TpmError::UnexpectedSignature)?
+            }
+            (Scheme::Rs256, Some(Signature::RsaSsa(signature))) => {
+                signature.signature().value().to_vec()
+            }
+            _ => return Err(TpmError::UnexpectedSignature),
+        };
+        Ok(RawSignature::new(signature))
+    }
+}
+
+/// Concatenates ECDSA signature scalars, left-padding them to `scalar_len`. The TPM returns
+/// scalars without leading zeros.
+fn ecdsa_raw_signature(r: &[u8], s: &[u8], scalar_len: usize) -> Option<Vec<u8>> {
+    let mut raw = Vec::with_capacity(2 * scalar_len);
+    for scalar in [r, s] {
+        let scalar = &scalar[scalar.iter().take_while(|&&byte| byte == 0).count()..];
+        if scalar.len() > scalar_len {
+            return None;
+        }
+        raw.resize(raw.len() + scalar_len - scalar.len(), 0);
+        raw.extend_from_slice(scalar);
+    }
+    Some(raw)
+}
+
+impl AsyncAlgorithm for Tpm {
+    type SigningKey = TpmKey;
+    type Signature = RawSignature;
+
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Borrowed(match self.scheme {
+            Scheme::Es256 => "ES256",
+            Scheme::Rs256 => "RS256",
+        })
+    }
+
+    fn sign_async<'a>(
+        &'a self,
+        signing_key: &'a Self::SigningKey,
+        message: &'a [u8],
+    ) -> SignFuture<'a, Self::Signature> {
+        Box::pin(async move { Ok(self.sign_blocking(signing_key, message)?) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ecdsa_scalars_are_padded() {
+        let raw = ecdsa_raw_signature(&[1; 31], &[0, 2, 3], 32).unwrap();
+        assert_eq!(raw.len(), 64);
+        assert_eq!(raw[0], 0);
+        assert_eq!(raw[1..32], [1; 31]);
+        assert!(raw[32..62].iter().all(|&byte| byte == 0));
+        assert_eq!(raw[62..], [2, 3]);
+
+        assert!(ecdsa_raw_signature(&[1; 33], &[1], 32).is_none());
+    }
+}
@@ -0,0 +1,521 @@
+//! `EdDSA`, `ES256`, `RS256` and `PS256` signing using keys held by the
+//! [HashiCorp Vault transit secrets engine](https://developer.hashicorp.com/vault/docs/secrets/transit).
+
+use base64ct::{Base64, Encoding};
+use reqwest::{Client, Method, StatusCode};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use core::{fmt, future::Future};
+use std::sync::Arc;
+
+use super::{
+    ecdsa_signature_from_der,
+    remote::{self, AccessTokenFn, RawSignature},
+    EcdsaCurve,
+};
+use crate::{
+    alloc::{format, Box, Cow, String, ToOwned, Vec},
+    AsyncAlgorithm, Header, SignFuture,
+};
+
+/// Default mount path of the transit secrets engine.
+const DEFAULT_MOUNT: &str = "transit";
+
+/// Errors that can occur when interacting with the Vault transit engine.
+#[derive(Debug)]
+#[non_exhaustive]
+#[cfg_attr(docsrs, doc(cfg(feature = "vault-transit")))]
+pub enum VaultTransitError {
+    /// Error obtaining a Vault token.
+    Token(anyhow::Error),
+    /// HTTP error calling the Vault API.
+    Http(reqwest::Error),
+    /// Vault API has returned an error response.
+    Api {
+        /// HTTP status of the response.
+        status: u16,
+        /// Error messages from the response, joined with `; `.
+        message: String,
+    },
+    /// Vault response does not contain an expected field, or the field is malformed.
+    InvalidResponse(&'static str),
+    /// Signature returned by Vault is malformed.
+    MalformedSignature,
+    /// Vault has signed the message with a key version different from the one
+    /// the key handle is pinned to.
+    VersionMismatch {
+        /// Version the key handle is pinned to.
+        expected: u32,
+        /// Version used by Vault.
+        actual: u32,
+    },
+    /// Requested key version does not exist.
+    UnknownVersion(u32),
+    /// Public key returned by Vault cannot be converted to the requested key type.
+    PublicKey(anyhow::Error),
+}
+
+impl fmt::Display for VaultTransitError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Token(err) => write!(formatter, "cannot obtain Vault token: {err}"),
+            Self::Http(err) => write!(formatter, "error calling Vault: {err}"),
+            Self::Api { status, message } => {
+                write!(formatter, "Vault returned error (HTTP {status}): {message}")
+            }
+            Self::InvalidResponse(field) => write!(
+                formatter,
+                "field `{field}` is missing or invalid in the Vault response"
+            ),
+            Self::MalformedSignature => formatter.write_str("Vault returned malformed signature"),
+            Self::VersionMismatch { expected, actual } => write!(
+                formatter,
+                "Vault signed message with key version {actual}, while version {expected} \
+                 was expected"
+            ),
+            Self::UnknownVersion(version) => {
+                write!(formatter, "key version {version} does not exist in Vault")
+            }
+            Self::PublicKey(err) => write!(formatter, "cannot parse Vault public key: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for VaultTransitError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Token(err) | Self::PublicKey(err) => Some(err.as_ref()),
+            Self::Http(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// Handle to a named key in the Vault transit engine. Used as a signing key
+/// for the [`VaultTransit`] algorithm; the private key never leaves Vault.
+///
+/// Requests are authenticated with Vault tokens supplied by a caller-provided hook
+/// (e.g., reading a token renewed by Vault Agent). The hook is called for each request,
+/// so it should cache tokens if necessary.
+///
+/// # Key rotation
+///
+/// Transit keys are versioned. A handle can be [pinned](Self::with_version()) to a specific
+/// key version, in which case Vault is asked to sign with this version, and the version
+/// is included into the [key ID](Self::key_id()) as `{name}:v{version}`. Verifiers can thus
+/// select the correct public key (see [`Self::public_key()`]) after the key is rotated.
+/// [`Self::pin_latest_version()`] pins the handle to the current latest version.
+#[derive(Clone)]
+#[cfg_attr(docsrs, doc(cfg(feature = "vault-transit")))]
+pub struct VaultTransitKey {
+    client: Client,
+    address: String,
+    mount: String,
+    namespace: Option<String>,
+    name: String,
+    version: Option<u32>,
+    token: Arc<AccessTokenFn>,
+}
+
+impl fmt::Debug for VaultTransitKey {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("VaultTransitKey")
+            .field("address", &self.address)
+            .field("mount", &self.mount)
+            .field("namespace", &self.namespace)
+            .field("name", &self.name)
+            .field("version", &self.version)
+            .finish_non_exhaustive()
+    }
+}
+
+impl VaultTransitKey {
+    /// Creates a key handle. `address` is the Vault address (e.g., `https://vault.example:8200`),
+    /// and `name` is the name of the transit key.
+    pub fn new<F, Fut>(
+        client: Client,
+        address: impl Into<String>,
+        name: impl Into<String>,
+        token: F,
+    ) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = anyhow::Result<String>> + Send + 'static,
+    {
+        let mut address = address.into();
+        if address.ends_with('/') {
+            address.pop();
+        }
+        Self {
+            client,
+            address,
+            mount: DEFAULT_MOUNT.to_owned(),
+            namespace: None,
+            name: name.into(),
+            version: None,
+            token: remote::access_token_fn(token),
+        }
+    }
+
+    /// Sets the mount path of the transit engine. By default, `transit` is used.
+    #[must_use]
+    pub fn with_mount(mut self, mount: impl Into<String>) -> Self {
+        self.mount = mount.into();
+        self
+    }
+
+    /// Sets the Vault Enterprise namespace sent with requests.
+    #[must_use]
+    pub fn with_namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.namespace = Some(namespace.into());
+        self
+    }
+
+    /// Pins the handle to the specified key version.
+    #[must_use]
+    pub fn with_version(mut self, version: u32) -> Self {
+        self.version = Some(version);
+        self
+    }
+
+    /// Pins the handle to the latest key version as reported by Vault.
+    pub async fn pin_latest_version(self) -> Result<Self, VaultTransitError> {
+        let version = self.latest_version().await?;
+        Ok(self.with_version(version))
+    }
+
+    /// Returns the name of the transit key.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the key version the handle is pinned to, if any.
+    pub fn version(&self) -> Option<u32> {
+        self.version
+    }
+
+    /// Returns the key ID (`kid`) for tokens signed with this key: `{name}:v{version}`
+    /// if the handle is pinned to a version, or the key name otherwise.
+    pub fn key_id(&self) -> String {
+        match self.version {
+            Some(version) => format!("{}:v{version}", self.name),
+            None => self.name.clone(),
+        }
+    }
+
+    /// Returns a header with the [key ID](Self::key_id()) set.
+    pub fn header(&self) -> Header {
+        Header::empty().with_key_id(self.key_id())
+    }
+
+    async fn call(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<Value>,
+    ) -> Result<Value, VaultTransitError> {
+        let token = (self.token)().await.map_err(VaultTransitError::Token)?;
+        let url = format!("{}/v1/{}/{path}/{}", self.address, self.mount, self.name);
+        let mut request = self
+            .client
+            .request(method, url)
+            .header("X-Vault-Token", token);
+        if let Some(namespace) = &self.namespace {
+            request = request.header("X-Vault-Namespace", namespace);
+        }
+        if let Some(body) = body {
+            request = request
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(body.to_string());
+        }
+        let response = request.send().await.map_err(VaultTransitError::Http)?;
+        let status = response.status();
+        let body = response.bytes().await.map_err(VaultTransitError::Http)?;
+
+        if status != StatusCode::OK {
+            let message = serde_json::from_slice::<Value>(&body)
+                .ok()
+                .and_then(|body| {
+                    let errors = body["errors"].as_array()?.iter();
+                    let errors: Vec<_> = errors.filter_map(Value::as_str).collect();
+                    Some(errors.join("; "))
+                })
+                .unwrap_or_else(|| String::from_utf8_lossy(&body).into_owned());
+            return Err(VaultTransitError::Api {
+                status: status.as_u16(),
+                message,
+            });
+        }
+        let mut body: Value = serde_json::from_slice(&body)
+            .map_err(|_| VaultTransitError::InvalidResponse("body"))?;
+        Ok(body["data"].take())
+    }
+
+    /// Returns the latest version of the key.
+    pub async fn latest_version(&self) -> Result<u32, VaultTransitError> {
+        let data = self.call(Method::GET, "keys", None).await?;
+        data["latest_version"]
+            .as_u64()
+            .and_then(|version| u32::try_from(version).ok())
+            .ok_or(VaultTransitError::InvalidResponse("latest_version"))
+    }
+
+    /// Fetches the public key with the specified version from Vault. The key is returned
+    /// as a DER-encoded `SubjectPublicKeyInfo` for ECDSA and RSA keys, and as raw 32 bytes
+    /// for Ed25519 keys. The public key of a version never changes, so it can be cached
+    /// indefinitely.
+    pub async fn public_key(&self, version: u32) -> Result<Vec<u8>, VaultTransitError> {
+        let data = self.call(Method::GET, "keys", None).await?;
+        let key = &data["keys"][version.to_string()];
+        if key.is_null() {
+            return Err(VaultTransitError::UnknownVersion(version));
+        }
+        let public_key = key["public_key"]
+            .as_str()
+            .ok_or(VaultTransitError::InvalidResponse("public_key"))?;
+
+        let public_key = if data["type"] == "ed25519" {
+            Base64::decode_vec(public_key).ok()
+        } else {
+            remote::pem_to_der(public_key)
+        };
+        public_key.ok_or(VaultTransitError::InvalidResponse("public_key"))
+    }
+
+    /// Fetches the RSA public key with the specified version from Vault. The key can be used
+    /// to verify tokens with the [`Rsa`](crate::alg::Rsa) algorithm.
+    #[cfg(feature = "rsa")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "rsa")))]
+    pub async fn rsa_public_key(
+        &self,
+        version: u32,
+    ) -> Result<rsa::RsaPublicKey, VaultTransitError> {
+        let der = self.public_key(version).await?;
+        remote::rsa_public_key_from_der(&der).map_err(VaultTransitError::PublicKey)
+    }
+
+    /// Fetches the P-256 public key with the specified version from Vault. The key can be used
+    /// to verify tokens with the [`Es256`](crate::alg::Es256) algorithm.
+    #[cfg(feature = "p256")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "p256")))]
+    pub async fn es256_public_key(
+        &self,
+        version: u32,
+    ) -> Result<p256::ecdsa::VerifyingKey, VaultTransitError> {
+        let der = self.public_key(version).await?;
+        remote::es256_public_key_from_der(&der).map_err(VaultTransitError::PublicKey)
+    }
+}
+
+/// Signing scheme supported by [`VaultTransit`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Scheme {
+    EdDsa,
+    Es256,
+    Rs256,
+    Ps256,
+}
+
+/// Signing algorithm using keys held by the Vault transit engine. Supports `EdDSA`
+/// (`ed25519` keys), `ES256` (`ecdsa-p256` keys), `RS256` and `PS256`
+/// (`rsa-2048`, `rsa-3072` and `rsa-4096` keys).
+///
+/// For ECDSA and RSA, the message digest is computed locally and sent to Vault as prehashed
+/// input. ECDSA signatures returned by Vault are converted from DER to the JWS format.
+///
+/// Since signing requires a network call, this algorithm implements [`AsyncAlgorithm`]
+/// rather than [`Algorithm`](crate::Algorithm). Tokens are verified with the corresponding
+/// local algorithm (e.g., [`Es256`](crate::alg::Es256)) using the public key fetched
+/// via [`VaultTransitKey`] methods.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use chrono::Duration;
+/// use jwt_compact::{alg::{VaultTransit, VaultTransitKey}, prelude::*, AsyncAlgorithmExt};
+///
+/// # async fn test_wrapper() -> anyhow::Result<()> {
+/// let key = VaultTransitKey::new(
+///     reqwest::Client::new(),
+///     "https://vault.example:8200",
+///     "token-signing",
+///     || async { Ok(std::env::var("VAULT_TOKEN")?) },
+/// );
+/// let key = key.pin_latest_version().await?;
+/// let claims = Claims::new(serde_json::json!({ "sub": "service" }))
+///     .set_duration_and_issuance(&TimeOptions::default(), Duration::try_minutes(10).unwrap());
+/// // The header contains `"kid": "token-signing:v{latest_version}"`.
+/// let token = VaultTransit::es256().token_async(&key.header(), &claims, &key).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(docsrs, doc(cfg(feature = "vault-transit")))]
+pub struct VaultTransit {
+    scheme: Scheme,
+}
+
+impl VaultTransit {
+    /// `EdDSA` algorithm (Ed25519).
+    pub const fn eddsa() -> Self {
+        Self {
+            scheme: Scheme::EdDsa,
+        }
+    }
+
+    /// `ES256` algorithm (ECDSA on the P-256 curve with SHA-256).
+    pub const fn es256() -> Self {
+        Self {
+            scheme: Scheme::Es256,
+        }
+    }
+
+    /// `RS256` algorithm (RSASSA-PKCS1-v1_5 with SHA-256).
+    pub const fn rs256() -> Self {
+        Self {
+            scheme: Scheme::Rs256,
+        }
+    }
+
+    /// `PS256` algorithm (RSASSA-PSS with SHA-256).
+    pub const fn ps256() -> Self {
+        Self {
+            scheme: Scheme::Ps256,
+        }
+    }
+
+    fn request_body(self, message: &[u8], version: Option<u32>) -> Value {
+        let mut body = if self.scheme == Scheme::EdDsa {
+            json!({ "input": Base64::encode_string(message) })
+        } else {
+            let digest = Sha256::digest(message);
+            json!({
+                "input": Base64::encode_string(&digest),
+                "prehashed": true,
+                "hash_algorithm": "sha2-256",
+            })
+        };
+        match self.scheme {
+            Scheme::Rs256 => {
+                body["signature_algorithm"] = "pkcs1v15".into();
+            }
+            Scheme::Ps256 => {
+                body["signature_algorithm"] = "pss".into();
+                // JWS requires the salt length to be equal to the digest length.
+                body["salt_length"] = "hash".into();
+            }
+            Scheme::EdDsa | Scheme::Es256 => { /* no additional params */ }
+        }
+        if let Some(version) = version {
+            body["key_version"] = version.into();
+        }
+        body
+    }
+
+    /// Parses a Vault signature in the `vault:v{version}:{base64}` format.
+    fn parse_signature(self, signature: &str) -> Option<(u32, Vec<u8>)> {
+        let mut parts = signature.splitn(3, ':');
+        if parts.next()? != "vault" {
+            return None;
+        }
+        let version = parts.next()?.strip_prefix('v')?.parse().ok()?;
+        let signature = Base64::decode_vec(parts.next()?).ok()?;
+
+        let signature = if self.scheme == Scheme::Es256 {
+            ecdsa_signature_from_der(&signature, EcdsaCurve::P256).ok()?
+        } else {
+            signature
+        };
+        Some((version, signature))
+    }
+
+    async fn sign(
+        &self,
+        key: &VaultTransitKey,
+        message: &[u8],
+    ) -> Result<RawSignature, VaultTransitError> {
+        let body = self.request_body(message, key.version);
+        let mut data = key.call(Method::POST, "sign", Some(body)).await?;
+        let signature = data["signature"]
+            .take()
+            .as_str()
+            .map(str::to_owned)
+            .ok_or(VaultTransitError::InvalidResponse("signature"))?;
+        let (version, signature) = self
+            .parse_signature(&signature)
+            .ok_or(VaultTransitError::MalformedSignature)?;
+
+        match key.version {
+            Some(expected) if expected != version => Err(VaultTransitError::VersionMismatch {
+                expected,
+                actual: version,
+            }),
+            _ => Ok(RawSignature::new(signature)),
+        }
+    }
+}
+
+impl AsyncAlgorithm for VaultTransit {
+    type SigningKey = VaultTransitKey;
+    type Signature = RawSignature;
+
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Borrowed(match self.scheme {
+            Scheme::EdDsa => "EdDSA",
+            Scheme::Es256 => "ES256",
+            Scheme::Rs256 => "RS256",
+            Scheme::Ps256 => "PS256",
+        })
+    }
+
+    fn sign_async<'a>(
+        &'a self,
+        signing_key: &'a Self::SigningKey,
+        message: &'a [u8],
+    ) -> SignFuture<'a, Self::Signature> {
+        Box::pin(async move { Ok(self.sign(signing_key, message).await?) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsing_vault_signatures() {
+        let (version, signature) = VaultTransit::rs256()
+            .parse_signature("vault:v3:AAEC")
+            .unwrap();
+        assert_eq!(version, 3);
+        assert_eq!(signature, [0, 1, 2]);
+
+        let der = Base64::encode_string(&[0x30, 0x06, 0x02, 0x01, 0x01, 0x02, 0x01, 0x02]);
+        let (_, signature) = VaultTransit::es256()
+            .parse_signature(&format!("vault:v1:{der}"))
+            .unwrap();
+        assert_eq!(signature.len(), 64);
+        assert_eq!((signature[31], signature[63]), (1, 2));
+
+        let alg = VaultTransit::eddsa();
+        assert!(alg.parse_signature("AAEC").is_none());
+        assert!(alg.parse_signature("vault:3:AAEC").is_none());
+        assert!(alg.parse_signature("vault:v3:!!").is_none());
+        assert!(VaultTransit::es256()
+            .parse_signature("vault:v3:AAEC")
+            .is_none());
+    }
+
+    #[test]
+    fn request_bodies() {
+        let body = VaultTransit::ps256().request_body(b"test", Some(2));
+        assert_eq!(body["prehashed"], true);
+        assert_eq!(body["signature_algorithm"], "pss");
+        assert_eq!(body["salt_length"], "hash");
+        assert_eq!(body["key_version"], 2);
+
+        let body = VaultTransit::eddsa().request_body(b"test", None);
+        assert_eq!(body, json!({ "input": "dGVzdA==" }));
+    }
+}
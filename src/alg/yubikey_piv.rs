@@ -0,0 +1,422 @@
+//! `ES256`, `ES384` and `RS256` signing using private keys resident in YubiKey PIV slots
+//! via the [`yubikey`] crate.
+
+use sha2::{Digest, Sha256, Sha384};
+use yubikey::{
+    piv::{self, AlgorithmId, ManagementAlgorithmId, SlotId},
+    PinPolicy, TouchPolicy, YubiKey,
+};
+
+use core::fmt;
+use std::sync::{Arc, Mutex};
+
+use super::{ecdsa_signature_from_der, EcdsaCurve, RawSignature, SecretBytes};
+use crate::{
+    alloc::{Box, Cow, String, Vec},
+    AsyncAlgorithm, Header, SignFuture,
+};
+
+/// `DigestInfo` prefix for SHA-256 used in PKCS#1 v1.5 signature padding (RFC 8017, section 9.2).
+const SHA256_DIGEST_INFO: &[u8] = &[
+    0x30, 0x31, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01, 0x05,
+    0x00, 0x04, 0x20,
+];
+
+/// Errors that can occur when signing with a YubiKey.
+#[derive(Debug)]
+#[non_exhaustive]
+#[cfg_attr(docsrs, doc(cfg(feature = "yubikey")))]
+pub enum YubiKeyError {
+    /// The slot PIN policy requires PIN verification, but the PIN was not verified.
+    PinRequired,
+    /// The provided PIN is wrong.
+    WrongPin {
+        /// Number of remaining PIN verification attempts.
+        tries: u8,
+    },
+    /// The PIN is blocked after too many wrong attempts.
+    PinLocked,
+    /// The slot touch policy requires touching the YubiKey, but it wasn't touched in time.
+    TouchRequired,
+    /// Algorithm of the key in the slot is incompatible with the signing algorithm.
+    AlgorithmMismatch {
+        /// Algorithm of the key in the slot.
+        key: AlgorithmId,
+        /// Name of the JWS signing algorithm.
+        signing: &'static str,
+    },
+    /// YubiKey has returned a malformed signature.
+    MalformedSignature,
+    /// Other YubiKey or PC/SC error.
+    Device(yubikey::Error),
+}
+
+impl fmt::Display for YubiKeyError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::PinRequired => formatter.write_str("YubiKey PIN verification is required"),
+            Self::WrongPin { tries } => {
+                write!(formatter, "wrong YubiKey PIN ({tries} tries remaining)")
+            }
+            Self::PinLocked => formatter.write_str("YubiKey PIN is locked"),
+            Self::TouchRequired => formatter.write_str("YubiKey was not touched in time"),
+            Self::AlgorithmMismatch { key, signing } => write!(
+                formatter,
+                "YubiKey slot holds {key:?} key, which cannot be used for {signing}"
+            ),
+            Self::MalformedSignature => formatter.write_str("YubiKey returned malformed signature"),
+            Self::Device(err) => write!(formatter, "YubiKey error: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for YubiKeyError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Device(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<yubikey::Error> for YubiKeyError {
+    fn from(err: yubikey::Error) -> Self {
+        match err {
+            yubikey::Error::WrongPin { tries } => Self::WrongPin { tries },
+            yubikey::Error::PinLocked => Self::PinLocked,
+            err => Self::Device(err),
+        }
+    }
+}
+
+/// Handle to a private key in a YubiKey PIV slot. Used as a signing key
+/// for the [`YubiKeyPiv`] algorithm; the private key never leaves the YubiKey.
+///
+/// The YubiKey is shared via a mutex, so that the application can continue using it
+/// (e.g., to verify the PIN). Alternatively, the PIN can be [attached](Self::with_pin())
+/// to the key handle, in which case it is verified before each signing operation, as required
+/// by the [`PinPolicy::Always`] policy.
+///
+/// The slot PIN and touch policies are used to classify authentication failures
+/// into [`YubiKeyError::PinRequired`] and [`YubiKeyError::TouchRequired`]. The policies
+/// are set via [`Self::with_policy()`] or read from the YubiKey via [`Self::load_metadata()`].
+#[derive(Clone)]
+#[cfg_attr(docsrs, doc(cfg(feature = "yubikey")))]
+pub struct YubiKeyPivKey {
+    yubikey: Arc<Mutex<YubiKey>>,
+    slot: SlotId,
+    algorithm: AlgorithmId,
+    policy: (PinPolicy, TouchPolicy),
+    pin: Option<SecretBytes<'static>>,
+    key_id: Option<String>,
+}
+
+impl fmt::Debug for YubiKeyPivKey {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("YubiKeyPivKey")
+            .field("slot", &self.slot)
+            .field("algorithm", &self.algorithm)
+            .field("policy", &self.policy)
+            .field("key_id", &self.key_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl YubiKeyPivKey {
+    /// Creates a handle to the key of the specified algorithm in `slot`. The slot policies
+    /// are set to the defaults.
+    pub fn new(yubikey: Arc<Mutex<YubiKey>>, slot: SlotId, algorithm: AlgorithmId) -> Self {
+        Self {
+            yubikey,
+            slot,
+            algorithm,
+            policy: (PinPolicy::Default, TouchPolicy::Default),
+            pin: None,
+            key_id: None,
+        }
+    }
+
+    /// Creates a handle to the key in `slot`, reading the key algorithm and slot policies
+    /// from the YubiKey. Requires YubiKey firmware 5.3 or newer.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if slot metadata cannot be read, or if the slot doesn't contain
+    /// an asymmetric key.
+    pub fn load_metadata(yubikey: Arc<Mutex<YubiKey>>, slot: SlotId) -> Result<Self, YubiKeyError> {
+        let metadata = {
+            let mut device = lock(&yubikey)?;
+            piv::metadata(&mut device, slot)?
+        };
+        let ManagementAlgorithmId::Asymmetric(algorithm) = metadata.algorithm else {
+            return Err(YubiKeyError::Device(yubikey::Error::AlgorithmError));
+        };
+        let mut key = Self::new(yubikey, slot, algorithm);
+        if let Some((pin_policy, touch_policy)) = metadata.policy {
+            key = key.with_policy(pin_policy, touch_policy);
+        }
+        Ok(key)
+    }
+
+    /// Sets the PIN and touch policies of the slot.
+    #[must_use]
+    pub fn with_policy(mut self, pin_policy: PinPolicy, touch_policy: TouchPolicy) -> Self {
+        self.policy = (pin_policy, touch_policy);
+        self
+    }
+
+    /// Sets the PIN verified before each signing operation.
+    #[must_use]
+    pub fn with_pin(mut self, pin: impl Into<String>) -> Self {
+        self.pin = Some(SecretBytes::owned(pin.into().into_bytes()));
+        self
+    }
+
+    /// Sets the key ID (`kid`) for tokens signed with this key.
+    #[must_use]
+    pub fn with_key_id(mut self, key_id: impl Into<String>) -> Self {
+        self.key_id = Some(key_id.into());
+        self
+    }
+
+    /// Returns the PIV slot of the key.
+    pub fn slot(&self) -> SlotId {
+        self.slot
+    }
+
+    /// Returns the algorithm of the key.
+    pub fn algorithm(&self) -> AlgorithmId {
+        self.algorithm
+    }
+
+    /// Returns the key ID, if set.
+    pub fn key_id(&self) -> Option<&str> {
+        self.key_id.as_deref()
+    }
+
+    /// Returns a header with the [key ID](Self::key_id()) set (if any).
+    pub fn header(&self) -> Header {
+        let header = Header::empty();
+        match &self.key_id {
+            Some(key_id) => header.with_key_id(key_id.clone()),
+            None => header,
+        }
+    }
+
+    fn requires_pin(&self) -> bool {
+        match self.policy.0 {
+            PinPolicy::Never => false,
+            PinPolicy::Once | PinPolicy::Always => true,
+            // Card authentication is the only PIV slot not requiring PIN by default.
+            PinPolicy::Default => self.slot != SlotId::CardAuthentication,
+        }
+    }
+
+    fn requires_touch(&self) -> bool {
+        matches!(self.policy.1, TouchPolicy::Always | TouchPolicy::Cached)
+    }
+
+    /// Classifies an authentication failure based on the slot policies.
+    fn authentication_error(&self) -> YubiKeyError {
+        if self.requires_pin() && self.pin.is_none() {
+            YubiKeyError::PinRequired
+        } else if self.requires_touch() {
+            YubiKeyError::TouchRequired
+        } else {
+            YubiKeyError::Device(yubikey::Error::AuthenticationError)
+        }
+    }
+
+    fn sign_raw(&self, input: &[u8]) -> Result<Vec<u8>, YubiKeyError> {
+        let mut device = lock(&self.yubikey)?;
+        if let Some(pin) = &self.pin {
+            device.verify_pin(pin.as_ref())?;
+        }
+        match piv::sign_data(&mut device, input, self.algorithm, self.slot) {
+            Ok(signature) => Ok(signature.to_vec()),
+            Err(yubikey::Error::AuthenticationError) => Err(self.authentication_error()),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
+fn lock(yubikey: &Mutex<YubiKey>) -> Result<std::sync::MutexGuard<'_, YubiKey>, YubiKeyError> {
+    yubikey
+        .lock()
+        .map_err(|_| YubiKeyError::Device(yubikey::Error::GenericError))
+}
+
+/// Applies EMSA-PKCS1-v1_5 encoding to a SHA-256 digest. PIV performs raw RSA operations,
+/// so the padding must be applied by the host.
+fn pkcs1v15_pad(digest: &[u8], modulus_len: usize) -> Vec<u8> {
+    let data_len = SHA256_DIGEST_INFO.len() + digest.len();
+    let mut padded = Vec::with_capacity(modulus_len);
+    padded.extend_from_slice(&[0x00, 0x01]);
+    padded.resize(modulus_len - data_len - 1, 0xff);
+    padded.push(0x00);
+    padded.extend_from_slice(SHA256_DIGEST_INFO);
+    padded.extend_from_slice(digest);
+    padded
+}
+
+/// Signing scheme supported by [`YubiKeyPiv`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Scheme {
+    Es256,
+    Es384,
+    Rs256,
+}
+
+/// Signing algorithm using private keys in YubiKey PIV slots. Supports `ES256` (P-256 keys),
+/// `ES384` (P-384 keys) and `RS256` (RSA-2048 keys; 1024-bit keys are rejected
+/// as insufficiently strong).
+///
+/// Message digests and PKCS#1 padding are computed locally. ECDSA signatures returned by
+/// the YubiKey are converted from DER to the JWS format.
+///
+/// This algorithm implements [`AsyncAlgorithm`] since signing with a YubiKey may fail (e.g.,
+/// if the key requires a touch). Note that signing is blocking and is performed when
+/// the signing future is polled; with the touch policy enabled, it blocks until the YubiKey
+/// is touched or the operation times out. Consider signing on a blocking thread,
+/// or using [`YubiKeyPiv::sign_blocking()`].
+///
+/// # Examples
+///
+/// ```no_run
+/// # use chrono::Duration;
+/// use jwt_compact::{alg::{YubiKeyPiv, YubiKeyPivKey}, prelude::*, AsyncAlgorithmExt};
+/// use yubikey::{piv::SlotId, YubiKey};
+/// use std::sync::{Arc, Mutex};
+///
+/// # async fn test_wrapper() -> anyhow::Result<()> {
+/// let yubikey = Arc::new(Mutex::new(YubiKey::open()?));
+/// let key = YubiKeyPivKey::load_metadata(yubikey, SlotId::Signature)?
+///     .with_pin("123456")
+///     .with_key_id("ci-signing");
+/// let claims = Claims::new(serde_json::json!({ "sub": "ci" }))
+///     .set_duration_and_issuance(&TimeOptions::default(), Duration::try_minutes(10).unwrap());
+/// let token = YubiKeyPiv::es256().token_async(&key.header(), &claims, &key).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(docsrs, doc(cfg(feature = "yubikey")))]
+pub struct YubiKeyPiv {
+    scheme: Scheme,
+}
+
+impl YubiKeyPiv {
+    /// `ES256` algorithm (ECDSA on the P-256 curve with SHA-256).
+    pub const fn es256() -> Self {
+        Self {
+            scheme: Scheme::Es256,
+        }
+    }
+
+    /// `ES384` algorithm (ECDSA on the P-384 curve with SHA-384).
+    pub const fn es384() -> Self {
+        Self {
+            scheme: Scheme::Es384,
+        }
+    }
+
+    /// `RS256` algorithm (RSASSA-PKCS1-v1_5 with SHA-256).
+    pub const fn rs256() -> Self {
+        Self {
+            scheme: Scheme::Rs256,
+        }
+    }
+
+    fn jws_name(self) -> &'static str {
+        match self.scheme {
+            Scheme::Es256 => "ES256",
+            Scheme::Es384 => "ES384",
+            Scheme::Rs256 => "RS256",
+        }
+    }
+
+    /// Signs `message` with the key synchronously.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key algorithm doesn't match this algorithm,
+    /// or if the YubiKey returns an error.
+    pub fn sign_blocking(
+        &self,
+        key: &YubiKeyPivKey,
+        message: &[u8],
+    ) -> Result<RawSignature, YubiKeyError> {
+        let signature = match (self.scheme, key.algorithm) {
+            (Scheme::Es256, AlgorithmId::EccP256) => {
+                let der = key.sign_raw(&Sha256::digest(message))?;
+                ecdsa_signature_from_der(&der, EcdsaCurve::P256)
+                    .map_err(|_| YubiKeyError::MalformedSignature)?
+            }
+            (Scheme::Es384, AlgorithmId::EccP384) => {
+                let der = key.sign_raw(&Sha384::digest(message))?;
+                ecdsa_signature_from_der(&der, EcdsaCurve::P384)
+                    .map_err(|_| YubiKeyError::MalformedSignature)?
+            }
+            (Scheme::Rs256, AlgorithmId::Rsa2048) => {
+                let padded = pkcs1v15_pad(&Sha256::digest(message), 256);
+                key.sign_raw(&padded)?
+            }
+            (_, key_algorithm) => {
+                return Err(YubiKeyError::AlgorithmMismatch {
+                    key: key_algorithm,
+                    signing: self.jws_name(),
+                })
+            }
+        };
+        Ok(RawSignature::new(signature))
+    }
+}
+
+impl AsyncAlgorithm for YubiKeyPiv {
+    type SigningKey = YubiKeyPivKey;
+    type Signature = RawSignature;
+
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Borrowed(self.jws_name())
+    }
+
+    fn sign_async<'a>(
+        &'a self,
+        signing_key: &'a Self::SigningKey,
+        message: &'a [u8],
+    ) -> SignFuture<'a, Self::Signature> {
+        Box::pin(async move { Ok(self.sign_blocking(signing_key, message)?) })
+    }
+}
+
+#[cfg(all(test, feature = "rsa"))]
+mod tests {
+    use rand::thread_rng;
+    use rsa::{
+        traits::{PrivateKeyParts, PublicKeyParts},
+        BigUint, Pkcs1v15Sign, RsaPrivateKey,
+    };
+
+    use super::*;
+
+    #[test]
+    fn pkcs1v15_padding_matches_rsa_signatures() {
+        let private_key = RsaPrivateKey::new(&mut thread_rng(), 2_048).unwrap();
+        let digest = Sha256::digest(b"test");
+        let padded = pkcs1v15_pad(&digest, 256);
+        assert_eq!(padded.len(), 256);
+
+        // Emulate the raw RSA operation performed by the YubiKey.
+        let padded = BigUint::from_bytes_be(&padded);
+        let signature = padded.modpow(private_key.d(), private_key.n());
+        let mut signature = signature.to_bytes_be();
+        while signature.len() < 256 {
+            signature.insert(0, 0);
+        }
+        private_key
+            .to_public_key()
+            .verify(Pkcs1v15Sign::new::<Sha256>(), &digest, &signature)
+            .unwrap();
+    }
+}
@@ -0,0 +1,404 @@
+//! [Branca] tokens sharing the claims pipeline with JWTs.
+//!
+//! Branca tokens are encrypted and authenticated with `XChaCha20-Poly1305` using a symmetric key,
+//! and are encoded with base62. Branca does not prescribe the payload format; this module
+//! uses JSON-serialized [`Claims`] (with time-related claims represented as Unix timestamps,
+//! same as in JWTs), which allows handling Branca tokens and JWTs in the same way,
+//! e.g., during a migration between the two formats.
+//!
+//! Besides the claims, each token contains the creation timestamp, which can be checked
+//! with [`BrancaToken::validate_ttl()`].
+//!
+//! [Branca]: https://github.com/tuupola/branca-spec
+//!
+//! # Examples
+//!
+//! ```
+//! # use chrono::Duration;
+//! # use jwt_compact::{prelude::*, branca::{self, BrancaKey}};
+//! # use serde::{Deserialize, Serialize};
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct CustomClaims {
+//!     #[serde(rename = "sub")]
+//!     subject: String,
+//! }
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let key = BrancaKey::generate(&mut rand::thread_rng());
+//! let time_options = TimeOptions::default();
+//! let claims = Claims::new(CustomClaims { subject: "alice".to_owned() })
+//!     .set_duration_and_issuance(&time_options, Duration::try_hours(1).unwrap());
+//! let token = branca::encrypt(&key, &claims, &time_options, &mut rand::thread_rng())?;
+//!
+//! let token = branca::decrypt::<CustomClaims>(&key, &token)?;
+//! token.validate_ttl(Duration::try_hours(1).unwrap(), &time_options)?;
+//! token.claims().validate_expiration(&time_options)?;
+//! assert_eq!(token.claims().custom.subject, "alice");
+//! # Ok(())
+//! # }
+//! ```
+
+use chacha20poly1305::{AeadInPlace, KeyInit, XChaCha20Poly1305};
+use chrono::Duration;
+use rand_core::{CryptoRng, RngCore};
+use serde::{de::DeserializeOwned, Serialize};
+use zeroize::Zeroize;
+
+use core::{fmt, iter};
+
+use crate::{
+    alloc::{String, Vec},
+    Claims, TimeOptions, Timestamp,
+};
+
+const VERSION: u8 = 0xba;
+const TIMESTAMP_LEN: usize = 4;
+const NONCE_LEN: usize = 24;
+const HEADER_LEN: usize = 1 + TIMESTAMP_LEN + NONCE_LEN;
+const TAG_LEN: usize = 16;
+const BASE62_ALPHABET: &[u8; 62] =
+    b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+/// Errors that can occur when creating or verifying Branca tokens.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum BrancaError {
+    /// Token has invalid structure or an unsupported version.
+    InvalidFormat,
+    /// Cannot decode base62.
+    InvalidBase62Encoding,
+    /// Symmetric key has invalid length.
+    InvalidKeyLength(usize),
+    /// Token authentication tag has failed verification.
+    InvalidTag,
+    /// Token timestamp cannot be represented as an unsigned 32-bit integer.
+    TimestampOutOfRange,
+    /// Token is older than the allowed time-to-live.
+    Expired,
+    /// Claims cannot be serialized or deserialized.
+    Claims(serde_json::Error),
+}
+
+impl fmt::Display for BrancaError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidFormat => formatter.write_str("invalid Branca token structure"),
+            Self::InvalidBase62Encoding => formatter.write_str("invalid base62 encoding"),
+            Self::InvalidKeyLength(len) => {
+                write!(
+                    formatter,
+                    "invalid key length: expected 32 bytes, got {len}"
+                )
+            }
+            Self::InvalidTag => formatter.write_str("token has failed verification"),
+            Self::TimestampOutOfRange => formatter.write_str("token timestamp is out of range"),
+            Self::Expired => formatter.write_str("token has expired"),
+            Self::Claims(err) => write!(formatter, "cannot (de)serialize claims: {err}"),
+        }
+    }
+}
+
+impl core::error::Error for BrancaError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::Claims(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// Symmetric key for Branca tokens.
+#[derive(Clone)]
+pub struct BrancaKey([u8; 32]);
+
+/// Outputs only the key length, never the key bytes.
+impl fmt::Debug for BrancaKey {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("BrancaKey")
+            .field("len", &self.0.len())
+            .finish()
+    }
+}
+
+impl Drop for BrancaKey {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl BrancaKey {
+    /// Creates a key from the provided bytes.
+    pub fn new(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+
+    /// Creates a key from a byte slice, which must have length 32.
+    pub fn from_slice(bytes: &[u8]) -> Result<Self, BrancaError> {
+        let bytes = bytes
+            .try_into()
+            .map_err(|_| BrancaError::InvalidKeyLength(bytes.len()))?;
+        Ok(Self(bytes))
+    }
+
+    /// Generates a random key.
+    pub fn generate(rng: &mut (impl CryptoRng + RngCore)) -> Self {
+        let mut bytes = [0_u8; 32];
+        rng.fill_bytes(&mut bytes);
+        Self(bytes)
+    }
+
+    fn cipher(&self) -> XChaCha20Poly1305 {
+        XChaCha20Poly1305::new(&self.0.into())
+    }
+}
+
+/// Branca token with verified claims and the creation timestamp.
+#[derive(Debug, Clone)]
+pub struct BrancaToken<T> {
+    claims: Claims<T>,
+    timestamp: Timestamp,
+}
+
+impl<T> BrancaToken<T> {
+    /// Returns the token claims.
+    pub fn claims(&self) -> &Claims<T> {
+        &self.claims
+    }
+
+    /// Returns the creation timestamp of the token.
+    pub fn timestamp(&self) -> Timestamp {
+        self.timestamp
+    }
+
+    /// Validates that the token is not older than the specified time-to-live,
+    /// taking into account the leeway from `options`.
+    pub fn validate_ttl<F>(
+        &self,
+        ttl: Duration,
+        options: &TimeOptions<F>,
+    ) -> Result<&Self, BrancaError>
+    where
+        F: Fn() -> Timestamp,
+    {
+        if self.timestamp + ttl < (options.clock_fn)() - options.leeway {
+            Err(BrancaError::Expired)
+        } else {
+            Ok(self)
+        }
+    }
+
+    /// Converts this token into claims.
+    pub fn into_claims(self) -> Claims<T> {
+        self.claims
+    }
+}
+
+/// Creates a Branca token encrypting the provided claims. The token timestamp is set
+/// to the current time according to `options`.
+pub fn encrypt<T: Serialize, F>(
+    key: &BrancaKey,
+    claims: &Claims<T>,
+    options: &TimeOptions<F>,
+    rng: &mut (impl CryptoRng + RngCore),
+) -> Result<String, BrancaError>
+where
+    F: Fn() -> Timestamp,
+{
+    let timestamp = u32::try_from((options.clock_fn)().as_secs())
+        .map_err(|_| BrancaError::TimestampOutOfRange)?;
+    let mut nonce = [0_u8; NONCE_LEN];
+    rng.fill_bytes(&mut nonce);
+    let message = serde_json::to_vec(claims).map_err(BrancaError::Claims)?;
+    Ok(encrypt_with_nonce(key, &message, timestamp, nonce))
+}
+
+fn encrypt_with_nonce(
+    key: &BrancaKey,
+    message: &[u8],
+    timestamp: u32,
+    nonce: [u8; NONCE_LEN],
+) -> String {
+    let mut token = Vec::with_capacity(HEADER_LEN + message.len() + TAG_LEN);
+    token.push(VERSION);
+    token.extend_from_slice(&timestamp.to_be_bytes());
+    token.extend_from_slice(&nonce);
+    token.extend_from_slice(message);
+
+    let (header, ciphertext) = token.split_at_mut(HEADER_LEN);
+    let tag = key
+        .cipher()
+        .encrypt_in_place_detached(&nonce.into(), header, ciphertext)
+        .expect("message length is within limits");
+    token.extend_from_slice(&tag);
+    encode_base62(&token)
+}
+
+/// Decrypts and verifies a Branca token. The token timestamp is not checked; use
+/// [`BrancaToken::validate_ttl()`] to check it.
+pub fn decrypt<T: DeserializeOwned>(
+    key: &BrancaKey,
+    token: &str,
+) -> Result<BrancaToken<T>, BrancaError> {
+    let mut token = decode_base62(token).ok_or(BrancaError::InvalidBase62Encoding)?;
+    if token.len() < HEADER_LEN + TAG_LEN || token[0] != VERSION {
+        return Err(BrancaError::InvalidFormat);
+    }
+    let (header, rest) = token.split_at_mut(HEADER_LEN);
+    let (ciphertext, tag) = rest.split_at_mut(rest.len() - TAG_LEN);
+    let nonce = &header[1 + TIMESTAMP_LEN..];
+    key.cipher()
+        .decrypt_in_place_detached(nonce.into(), header, ciphertext, (&*tag).into())
+        .map_err(|_| BrancaError::InvalidTag)?;
+
+    let mut timestamp = [0_u8; TIMESTAMP_LEN];
+    timestamp.copy_from_slice(&header[1..=TIMESTAMP_LEN]);
+    let timestamp = Timestamp::from_secs(u32::from_be_bytes(timestamp).into());
+    let claims = serde_json::from_slice(ciphertext).map_err(BrancaError::Claims)?;
+    Ok(BrancaToken { claims, timestamp })
+}
+
+#[allow(clippy::cast_possible_truncation)] // truncation is intentional
+fn encode_base62(bytes: &[u8]) -> String {
+    let leading_zeros = bytes.iter().take_while(|&&byte| byte == 0).count();
+    // Base62 digits in the little-endian order.
+    let mut digits = Vec::<u8>::with_capacity(bytes.len() * 138 / 100 + 1);
+    for &byte in &bytes[leading_zeros..] {
+        let mut carry = u32::from(byte);
+        for digit in &mut digits {
+            carry += u32::from(*digit) << 8;
+            *digit = (carry % 62) as u8;
+            carry /= 62;
+        }
+        while carry > 0 {
+            digits.push((carry % 62) as u8);
+            carry /= 62;
+        }
+    }
+
+    let zeros = iter::repeat('0').take(leading_zeros);
+    let digits = digits
+        .iter()
+        .rev()
+        .map(|&digit| char::from(BASE62_ALPHABET[usize::from(digit)]));
+    zeros.chain(digits).collect()
+}
+
+#[allow(clippy::cast_possible_truncation)] // truncation is intentional
+fn decode_base62(encoded: &str) -> Option<Vec<u8>> {
+    let leading_zeros = encoded.bytes().take_while(|&ch| ch == b'0').count();
+    // Bytes in the little-endian order.
+    let mut bytes = Vec::<u8>::with_capacity(encoded.len() * 3 / 4 + 1);
+    for ch in encoded.bytes().skip(leading_zeros) {
+        let digit = BASE62_ALPHABET.iter().position(|&c| c == ch)?;
+        let mut carry = digit as u32;
+        for byte in &mut bytes {
+            carry += u32::from(*byte) * 62;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+    bytes.extend(iter::repeat(0).take(leading_zeros));
+    bytes.reverse();
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use chrono::{TimeZone, Utc};
+    use const_decoder::Decoder::Hex;
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::alloc::ToOwned;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct TestClaims {
+        data: String,
+    }
+
+    // Test vectors 0 to 2 from https://github.com/tuupola/branca-spec
+    const KEY: [u8; 32] = *b"supersecretkeyyoushouldnotcommit";
+    const NONCE: [u8; NONCE_LEN] = Hex.decode(b"beefbeefbeefbeefbeefbeefbeefbeefbeefbeefbeefbeef");
+    const TEST_VECTORS: [(u32, &str); 3] = [
+        (
+            0,
+            "870S4BYxgHw0KnP3W9fgVUHEhT5g86vJ17etaC5Kh5uIraWHCI1psNQGv298ZmjPwoYbjDQ9chy2z",
+        ),
+        (
+            u32::MAX,
+            "89i7YCwu5tWAJNHUDdmIqhzOi5hVHOd4afjZcGMcVmM4enl4yeLiDyYv41eMkNmTX6IwYEFErCSqr",
+        ),
+        (
+            123_206_400,
+            "875GH23U0Dr6nHFA63DhOyd9LkYudBkX8RsCTOMz5xoYAMw9sMd5QwcEqLDRnTDHPenOX7nP2trlT",
+        ),
+    ];
+    const TOKEN: &str = TEST_VECTORS[2].1;
+
+    #[test]
+    fn base62_roundtrip() {
+        for bytes in [&[][..], &[0], &[0, 0, 1], &[255; 5], b"Hello world!"] {
+            let encoded = encode_base62(bytes);
+            assert_eq!(decode_base62(&encoded).unwrap(), bytes);
+        }
+        assert_eq!(encode_base62(&[0, 61]), "0z");
+        assert!(decode_base62("abc+").is_none());
+    }
+
+    #[test]
+    fn test_vectors() {
+        let key = BrancaKey::new(KEY);
+        for (timestamp, expected_token) in TEST_VECTORS {
+            let token = encrypt_with_nonce(&key, b"Hello world!", timestamp, NONCE);
+            assert_eq!(token, expected_token);
+        }
+    }
+
+    #[test]
+    fn claims_roundtrip() {
+        let key = BrancaKey::new(KEY);
+        let now = Timestamp::from(Utc.with_ymd_and_hms(2021, 12, 31, 12, 0, 0).unwrap());
+        let options = TimeOptions::new(Duration::zero(), || now);
+        let mut claims = Claims::new(TestClaims {
+            data: "test".to_owned(),
+        });
+        claims.expiration = Some(Utc.with_ymd_and_hms(2022, 1, 1, 0, 0, 0).unwrap().into());
+        let token = encrypt(&key, &claims, &options, &mut rand::thread_rng()).unwrap();
+
+        let token = decrypt::<TestClaims>(&key, &token).unwrap();
+        assert_eq!(token.claims().custom, claims.custom);
+        assert_eq!(token.claims().expiration, claims.expiration);
+        token
+            .validate_ttl(Duration::try_minutes(1).unwrap(), &options)
+            .unwrap();
+
+        assert_eq!(token.timestamp(), now);
+        let stale_options =
+            TimeOptions::new(Duration::zero(), || now + Duration::try_hours(1).unwrap());
+        let err = token
+            .validate_ttl(Duration::try_minutes(1).unwrap(), &stale_options)
+            .unwrap_err();
+        assert_matches!(err, BrancaError::Expired);
+    }
+
+    #[test]
+    fn invalid_tokens() {
+        let key = BrancaKey::new(KEY);
+        let other_key = BrancaKey::generate(&mut rand::thread_rng());
+        let err = decrypt::<TestClaims>(&other_key, TOKEN).unwrap_err();
+        assert_matches!(err, BrancaError::InvalidTag);
+        let err = decrypt::<TestClaims>(&key, "875GH").unwrap_err();
+        assert_matches!(err, BrancaError::InvalidFormat);
+        let err = decrypt::<TestClaims>(&key, "875GH_").unwrap_err();
+        assert_matches!(err, BrancaError::InvalidBase62Encoding);
+        // The test vector payload is not JSON.
+        let err = decrypt::<TestClaims>(&key, TOKEN).unwrap_err();
+        assert_matches!(err, BrancaError::Claims(_));
+    }
+}
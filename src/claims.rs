@@ -1,7 +1,11 @@
-use chrono::{DateTime, Duration, Utc};
-use serde::{Deserialize, Serialize};
+use chrono::Duration;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{Map, Value};
 
-use crate::{Claim, ValidationError};
+use crate::{
+    alloc::{String, Vec},
+    Claim, Timestamp, ValidationError,
+};
 
 /// Time-related options for token creation and validation.
 ///
@@ -9,49 +13,84 @@ use crate::{Claim, ValidationError};
 /// using the `Default` impl or [`Self::from_leeway()`]. If the feature is off,
 /// you can still create options using [a generic constructor](Self::new).
 ///
+/// The clock function returns a [`Timestamp`]; a clock based on a date / time library
+/// (e.g., `chrono`) can convert its output using the corresponding `From` impl.
+///
+/// On the `wasm32-unknown-unknown` target, [`Utc::now()`] panics unless `chrono` is built
+/// with its `wasmbind` feature. Enable the `js-clock` crate feature to make the default clock
+/// use `js_sys::Date::now()` on this target instead.
+///
 /// # Examples
 ///
 /// ```
 /// # use chrono::{Duration, Utc};
-/// # use jwt_compact::TimeOptions;
+/// # use jwt_compact::{TimeOptions, Timestamp};
 /// // Default options.
 /// let default_options = TimeOptions::default();
 /// let options_with_custom_leeway =
 ///     TimeOptions::from_leeway(Duration::try_seconds(5).unwrap());
 /// // Options that have a fixed time. Can be useful for testing.
-/// let clock_time = Utc::now();
+/// let clock_time = Timestamp::from(Utc::now());
 /// let options_with_stopped_clock =
 ///     TimeOptions::new(Duration::try_seconds(10).unwrap(), move || clock_time);
 /// ```
+///
+/// [`Utc::now()`]: chrono::Utc::now()
 #[derive(Debug, Clone, Copy)]
 #[non_exhaustive]
-pub struct TimeOptions<F = fn() -> DateTime<Utc>> {
+pub struct TimeOptions<F = fn() -> Timestamp> {
     /// Leeway to use during validation.
     pub leeway: Duration,
     /// Source of the current timestamps.
     pub clock_fn: F,
 }
 
-impl<F: Fn() -> DateTime<Utc>> TimeOptions<F> {
+impl<F: Fn() -> Timestamp> TimeOptions<F> {
     /// Creates options based on the specified time leeway and clock function.
     pub fn new(leeway: Duration, clock_fn: F) -> Self {
         Self { leeway, clock_fn }
     }
+
+    /// Returns the current timestamp according to the clock function.
+    pub fn now(&self) -> Timestamp {
+        (self.clock_fn)()
+    }
 }
 
 impl TimeOptions {
-    /// Creates options based on the specified time leeway. The clock source is [`Utc::now()`].
+    /// Creates options based on the specified time leeway. The clock source is [`Utc::now()`]
+    /// (or `js_sys::Date::now()` on `wasm32-unknown-unknown` if the `js-clock` feature is on).
+    ///
+    /// [`Utc::now()`]: chrono::Utc::now()
     #[cfg(feature = "clock")]
     #[cfg_attr(docsrs, doc(cfg(feature = "clock")))]
     pub fn from_leeway(leeway: Duration) -> Self {
         Self {
             leeway,
-            clock_fn: Utc::now,
+            clock_fn: current_time,
         }
     }
 }
 
-/// Creates options with a default leeway (60 seconds) and the [`Utc::now()`] clock.
+#[cfg(all(feature = "js-clock", target_arch = "wasm32", target_os = "unknown"))]
+fn current_time() -> Timestamp {
+    #[allow(clippy::cast_possible_truncation)] // JS timestamps are integers well within `i64`
+    let millis = js_sys::Date::now() as i64;
+    Timestamp::from_secs(millis.div_euclid(1_000))
+}
+
+#[cfg(all(
+    feature = "clock",
+    not(all(feature = "js-clock", target_arch = "wasm32", target_os = "unknown"))
+))]
+fn current_time() -> Timestamp {
+    chrono::Utc::now().into()
+}
+
+/// Creates options with a default leeway (60 seconds) and the [`Utc::now()`] clock
+/// (see [`TimeOptions::from_leeway()`] for details).
+///
+/// [`Utc::now()`]: chrono::Utc::now()
 ///
 /// This impl is supported on **crate feature `clock`** only.
 #[cfg(feature = "clock")]
@@ -62,45 +101,137 @@ impl Default for TimeOptions {
 }
 
 /// A structure with no fields that can be used as a type parameter to `Claims`.
+///
+/// Use this type for tokens that only contain standard claims (`exp`, `nbf` and `iat`);
+/// other claims present in a token are ignored during deserialization.
+///
+/// # Examples
+///
+/// ```
+/// # use jwt_compact::{prelude::*, alg::{Hs256, Hs256Key}, Empty};
+/// # fn main() -> anyhow::Result<()> {
+/// let key = Hs256Key::new(b"super_secret_key_donut_steel");
+/// let token = Hs256.token(&Header::empty(), &Claims::empty(), &key)?;
+/// let token = UntrustedToken::new(&token)?;
+/// let token = Hs256.validator::<Empty>(&key).validate(&token)?;
+/// assert_eq!(token.claims().custom, Empty {});
+/// # Ok(())
+/// # }
+/// ```
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Empty {}
 
+/// Dynamically typed custom claims backed by a JSON map.
+///
+/// This type can be used as a type parameter to `Claims` if the claims in a token are not known
+/// in advance, or if declaring a dedicated struct for them is not worth it (e.g., in tests).
+/// Besides custom claims, the map contains all registered claims not covered by [`Claims`],
+/// such as `iss`, `sub` or `aud`. The map itself is accessible via `Deref` / `DerefMut`.
+///
+/// # Examples
+///
+/// ```
+/// # use jwt_compact::{prelude::*, alg::{Hs256, Hs256Key}, DynamicClaims};
+/// # fn main() -> anyhow::Result<()> {
+/// let key = Hs256Key::new(b"super_secret_key_donut_steel");
+/// let claims = DynamicClaims::new()
+///     .with_claim("sub", "alice")
+///     .with_claim("roles", ["admin", "user"]);
+/// let token = Hs256.token(&Header::empty(), &Claims::new(claims), &key)?;
+///
+/// let token = UntrustedToken::new(&token)?;
+/// let token = Hs256.validator::<DynamicClaims>(&key).validate(&token)?;
+/// let claims = &token.claims().custom;
+/// assert_eq!(claims.get_str("sub"), Some("alice"));
+/// let roles: Option<Vec<String>> = claims.get_as("roles")?;
+/// assert_eq!(roles.unwrap(), ["admin", "user"]);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct DynamicClaims(Map<String, Value>);
+
+impl DynamicClaims {
+    /// Creates an empty set of claims.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a claim with the specified name and value, replacing the existing value if any.
+    #[must_use]
+    pub fn with_claim(mut self, name: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.0.insert(name.into(), value.into());
+        self
+    }
+
+    /// Returns the value of the claim with the specified name if it is present
+    /// and is a string.
+    pub fn get_str(&self, name: &str) -> Option<&str> {
+        self.0.get(name)?.as_str()
+    }
+
+    /// Deserializes the claim with the specified name, returning `Ok(None)`
+    /// if the claim is not present.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the claim value cannot be deserialized into the specified type.
+    pub fn get_as<T: DeserializeOwned>(&self, name: &str) -> Result<Option<T>, serde_json::Error> {
+        self.0
+            .get(name)
+            .map(|value| T::deserialize(value))
+            .transpose()
+    }
+
+    /// Converts these claims into the underlying map.
+    pub fn into_inner(self) -> Map<String, Value> {
+        self.0
+    }
+}
+
+impl From<Map<String, Value>> for DynamicClaims {
+    fn from(map: Map<String, Value>) -> Self {
+        Self(map)
+    }
+}
+
+impl core::ops::Deref for DynamicClaims {
+    type Target = Map<String, Value>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl core::ops::DerefMut for DynamicClaims {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
 /// Claims encoded in a token.
 ///
 /// Claims are comprised of a "standard" part (`exp`, `nbf` and `iat` claims as per [JWT spec]),
 /// and custom fields. `iss`, `sub` and `aud` claims are not in the standard part
-/// due to a variety of data types they can be reasonably represented by.
+/// due to a variety of data types they can be reasonably represented by. These claims
+/// can be added as strings via [`RegisteredClaims`], e.g., using [`Self::builder()`].
 ///
 /// [JWT spec]: https://tools.ietf.org/html/rfc7519#section-4.1
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[non_exhaustive]
 pub struct Claims<T> {
     /// Expiration time of the token.
-    #[serde(
-        rename = "exp",
-        default,
-        skip_serializing_if = "Option::is_none",
-        with = "self::serde_timestamp"
-    )]
-    pub expiration: Option<DateTime<Utc>>,
+    #[serde(rename = "exp", default, skip_serializing_if = "Option::is_none")]
+    pub expiration: Option<Timestamp>,
 
     /// Minimum time at which token is valid.
-    #[serde(
-        rename = "nbf",
-        default,
-        skip_serializing_if = "Option::is_none",
-        with = "self::serde_timestamp"
-    )]
-    pub not_before: Option<DateTime<Utc>>,
+    #[serde(rename = "nbf", default, skip_serializing_if = "Option::is_none")]
+    pub not_before: Option<Timestamp>,
 
     /// Time of token issuance.
-    #[serde(
-        rename = "iat",
-        default,
-        skip_serializing_if = "Option::is_none",
-        with = "self::serde_timestamp"
-    )]
-    pub issued_at: Option<DateTime<Utc>>,
+    #[serde(rename = "iat", default, skip_serializing_if = "Option::is_none")]
+    pub issued_at: Option<Timestamp>,
 
     /// Custom claims.
     #[serde(flatten)]
@@ -119,6 +250,50 @@ impl Claims<Empty> {
     }
 }
 
+impl Claims<RegisteredClaims> {
+    /// Creates a builder for claims including registered string claims (`iss`, `sub`, `aud`
+    /// and `jti`), which allows issuing tokens without embedding these claims into custom claims.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chrono::Duration;
+    /// # use serde::{Deserialize, Serialize};
+    /// # use jwt_compact::{prelude::*, alg::{Hs256, Hs256Key}, RegisteredClaims};
+    /// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    /// struct CustomClaims {
+    ///     admin: bool,
+    /// }
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let time_options = TimeOptions::default();
+    /// let claims = Claims::builder()
+    ///     .issuer("https://auth.example.com/")
+    ///     .subject("alice")
+    ///     .audience(["api"])
+    ///     .expires_in(Duration::try_hours(1).unwrap())
+    ///     .custom(CustomClaims { admin: true })
+    ///     .build(&time_options);
+    /// assert!(claims.issued_at.is_some());
+    ///
+    /// let key = Hs256Key::new(b"super_secret_key_donut_steel");
+    /// let token = Hs256.token(&Header::empty(), &claims, &key)?;
+    /// let token = UntrustedToken::new(&token)?;
+    /// let token = Hs256
+    ///     .validator::<RegisteredClaims<CustomClaims>>(&key)
+    ///     .validate(&token)?;
+    /// let claims = &token.claims().custom;
+    /// assert_eq!(claims.subject.as_deref(), Some("alice"));
+    /// assert!(claims.audience.as_ref().unwrap().contains("api"));
+    /// assert!(claims.custom.admin);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn builder() -> ClaimsBuilder {
+        ClaimsBuilder::default()
+    }
+}
+
 impl<T> Claims<T> {
     /// Creates a new instance with the provided custom claims.
     pub fn new(custom_claims: T) -> Self {
@@ -135,7 +310,7 @@ impl<T> Claims<T> {
     #[must_use]
     pub fn set_duration<F>(self, options: &TimeOptions<F>, duration: Duration) -> Self
     where
-        F: Fn() -> DateTime<Utc>,
+        F: Fn() -> Timestamp,
     {
         Self {
             expiration: Some((options.clock_fn)() + duration),
@@ -148,7 +323,7 @@ impl<T> Claims<T> {
     #[must_use]
     pub fn set_duration_and_issuance<F>(self, options: &TimeOptions<F>, duration: Duration) -> Self
     where
-        F: Fn() -> DateTime<Utc>,
+        F: Fn() -> Timestamp,
     {
         let issued_at = (options.clock_fn)();
         Self {
@@ -158,11 +333,56 @@ impl<T> Claims<T> {
         }
     }
 
-    /// Sets the `nbf` claim.
+    /// Sets the `nbf` claim. The `moment` can be specified as a [`Timestamp`] or as any type
+    /// convertible to it (e.g., `DateTime<Utc>` from `chrono`).
+    #[must_use]
+    pub fn set_not_before(self, moment: impl Into<Timestamp>) -> Self {
+        Self {
+            not_before: Some(moment.into()),
+            ..self
+        }
+    }
+
+    /// Sets the `exp` claim to the specified Unix timestamp. Unlike [`Self::set_duration()`],
+    /// this does not require [`TimeOptions`]; the timestamp can come from any source
+    /// (e.g., a consensus layer).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use jwt_compact::{Claims, Timestamp};
+    /// let claims = Claims::empty()
+    ///     .set_issued_at_timestamp(Timestamp::from_secs(1_700_000_000))
+    ///     .set_not_before_timestamp(Timestamp::from_secs(1_700_000_000))
+    ///     .set_expiration_timestamp(Timestamp::from_secs(1_700_003_600));
+    /// assert_eq!(
+    ///     serde_json::to_string(&claims)?,
+    ///     r#"{"exp":1700003600,"nbf":1700000000,"iat":1700000000}"#
+    /// );
+    /// # Ok::<_, anyhow::Error>(())
+    /// ```
+    #[must_use]
+    pub fn set_expiration_timestamp(self, timestamp: Timestamp) -> Self {
+        Self {
+            expiration: Some(timestamp),
+            ..self
+        }
+    }
+
+    /// Sets the `nbf` claim to the specified Unix timestamp.
     #[must_use]
-    pub fn set_not_before(self, moment: DateTime<Utc>) -> Self {
+    pub fn set_not_before_timestamp(self, timestamp: Timestamp) -> Self {
         Self {
-            not_before: Some(moment),
+            not_before: Some(timestamp),
+            ..self
+        }
+    }
+
+    /// Sets the `iat` claim to the specified Unix timestamp.
+    #[must_use]
+    pub fn set_issued_at_timestamp(self, timestamp: Timestamp) -> Self {
+        Self {
+            issued_at: Some(timestamp),
             ..self
         }
     }
@@ -173,14 +393,14 @@ impl<T> Claims<T> {
     /// or if it is in the past (subject to the provided `options`).
     pub fn validate_expiration<F>(&self, options: &TimeOptions<F>) -> Result<&Self, ValidationError>
     where
-        F: Fn() -> DateTime<Utc>,
+        F: Fn() -> Timestamp,
     {
         self.expiration.map_or(
             Err(ValidationError::NoClaim(Claim::Expiration)),
             |expiration| {
                 let expiration_with_leeway = expiration
                     .checked_add_signed(options.leeway)
-                    .unwrap_or(DateTime::<Utc>::MAX_UTC);
+                    .unwrap_or(Timestamp::MAX);
                 if (options.clock_fn)() > expiration_with_leeway {
                     Err(ValidationError::Expired)
                 } else {
@@ -196,12 +416,15 @@ impl<T> Claims<T> {
     /// or if it is in the future (subject to the provided `options`).
     pub fn validate_maturity<F>(&self, options: &TimeOptions<F>) -> Result<&Self, ValidationError>
     where
-        F: Fn() -> DateTime<Utc>,
+        F: Fn() -> Timestamp,
     {
         self.not_before.map_or(
             Err(ValidationError::NoClaim(Claim::NotBefore)),
             |not_before| {
-                if (options.clock_fn)() < not_before - options.leeway {
+                let not_before_with_leeway = not_before
+                    .checked_sub_signed(options.leeway)
+                    .unwrap_or(Timestamp::MIN);
+                if (options.clock_fn)() < not_before_with_leeway {
                     Err(ValidationError::NotMature)
                 } else {
                     Ok(self)
@@ -209,69 +432,301 @@ impl<T> Claims<T> {
             },
         )
     }
+
+    /// Returns the time remaining until the token expires (not accounting for leeway),
+    /// e.g., to schedule a proactive token refresh or to set a cache TTL. Returns zero
+    /// if the token has already expired, and `None` if the `exp` claim is not set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chrono::{Duration, TimeZone, Utc};
+    /// # use jwt_compact::{Claims, Empty, TimeOptions, Timestamp};
+    /// let now = Timestamp::from(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap());
+    /// let options = TimeOptions::new(Duration::try_seconds(30).unwrap(), move || now);
+    /// let claims = Claims::empty()
+    ///     .set_duration_and_issuance(&options, Duration::try_minutes(10).unwrap());
+    /// assert_eq!(claims.expires_in(&options), Some(Duration::try_minutes(10).unwrap()));
+    /// assert_eq!(claims.age(&options), Some(Duration::zero()));
+    /// assert_eq!(claims.lifetime(), Some(Duration::try_minutes(10).unwrap()));
+    /// assert!(!claims.is_expired(&options));
+    /// ```
+    pub fn expires_in<F>(&self, options: &TimeOptions<F>) -> Option<Duration>
+    where
+        F: Fn() -> Timestamp,
+    {
+        let expiration = self.expiration?;
+        Some((expiration - (options.clock_fn)()).max(Duration::zero()))
+    }
+
+    /// Checks whether the token has expired, subject to the leeway in `options`. This is
+    /// consistent with [`Self::validate_expiration()`], except that a token without
+    /// the `exp` claim is considered non-expiring.
+    pub fn is_expired<F>(&self, options: &TimeOptions<F>) -> bool
+    where
+        F: Fn() -> Timestamp,
+    {
+        matches!(
+            self.validate_expiration(options),
+            Err(ValidationError::Expired)
+        )
+    }
+
+    /// Returns the time elapsed since the token was issued. Returns zero if the issuance
+    /// time is in the future (e.g., because of clock skew), and `None` if the `iat` claim
+    /// is not set.
+    pub fn age<F>(&self, options: &TimeOptions<F>) -> Option<Duration>
+    where
+        F: Fn() -> Timestamp,
+    {
+        let issued_at = self.issued_at?;
+        Some(((options.clock_fn)() - issued_at).max(Duration::zero()))
+    }
+
+    /// Returns the total lifetime of the token, i.e., the time between its issuance (`iat`)
+    /// and expiration (`exp`). Returns `None` if either of these claims is not set.
+    pub fn lifetime(&self) -> Option<Duration> {
+        Some(self.expiration? - self.issued_at?)
+    }
 }
 
-mod serde_timestamp {
-    use chrono::{offset::TimeZone, DateTime, Utc};
-    use serde::{
-        de::{Error as DeError, Visitor},
-        Deserializer, Serializer,
-    };
+/// Registered string claims (`iss`, `sub`, `aud` and `jti`) together with custom claims.
+/// Usually created with a [`ClaimsBuilder`].
+///
+/// All registered claims are optional and are skipped during serialization if not set.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct RegisteredClaims<T = Empty> {
+    /// Issuer of the token (`iss`).
+    #[serde(rename = "iss", default, skip_serializing_if = "Option::is_none")]
+    pub issuer: Option<String>,
+    /// Subject of the token (`sub`).
+    #[serde(rename = "sub", default, skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+    /// Intended recipients of the token (`aud`).
+    #[serde(rename = "aud", default, skip_serializing_if = "Option::is_none")]
+    pub audience: Option<Audience>,
+    /// Unique identifier of the token (`jti`).
+    #[serde(rename = "jti", default, skip_serializing_if = "Option::is_none")]
+    pub jwt_id: Option<String>,
+    /// Custom claims.
+    #[serde(flatten)]
+    pub custom: T,
+}
+
+impl<T> RegisteredClaims<T> {
+    /// Creates claims with the provided custom claims and no registered claims.
+    pub fn new(custom_claims: T) -> Self {
+        Self {
+            issuer: None,
+            subject: None,
+            audience: None,
+            jwt_id: None,
+            custom: custom_claims,
+        }
+    }
+}
+
+/// Builder of [`Claims`] with [registered claims](RegisteredClaims). Created using
+/// [`Claims::builder()`].
+///
+/// Time-related claims are set when the claims are [built](Self::build()): `iat` is always set
+/// to the current time, and `exp` is set if the token lifetime is specified
+/// via [`Self::expires_in()`].
+#[derive(Debug, Clone, Default)]
+pub struct ClaimsBuilder<T = Empty> {
+    registered: RegisteredClaims<T>,
+    expires_in: Option<Duration>,
+    not_before: Option<Timestamp>,
+}
+
+impl<T> ClaimsBuilder<T> {
+    /// Sets the issuer of the token (`iss`).
+    #[must_use]
+    pub fn issuer(mut self, issuer: impl Into<String>) -> Self {
+        self.registered.issuer = Some(issuer.into());
+        self
+    }
+
+    /// Sets the subject of the token (`sub`).
+    #[must_use]
+    pub fn subject(mut self, subject: impl Into<String>) -> Self {
+        self.registered.subject = Some(subject.into());
+        self
+    }
+
+    /// Sets intended recipients of the token (`aud`).
+    #[must_use]
+    pub fn audience<S: Into<String>>(mut self, audience: impl IntoIterator<Item = S>) -> Self {
+        self.registered.audience = Some(audience.into_iter().collect());
+        self
+    }
 
-    use core::fmt;
+    /// Sets the unique identifier of the token (`jti`).
+    #[must_use]
+    pub fn jwt_id(mut self, jwt_id: impl Into<String>) -> Self {
+        self.registered.jwt_id = Some(jwt_id.into());
+        self
+    }
 
-    struct TimestampVisitor;
+    /// Sets the token lifetime. The expiration time (`exp`) is computed when the claims
+    /// are built.
+    #[must_use]
+    pub fn expires_in(mut self, duration: Duration) -> Self {
+        self.expires_in = Some(duration);
+        self
+    }
 
-    impl<'de> Visitor<'de> for TimestampVisitor {
-        type Value = DateTime<Utc>;
+    /// Sets the minimum time at which the token is valid (`nbf`). The `moment` can be specified
+    /// as a [`Timestamp`] or as any type convertible to it (e.g., `DateTime<Utc>` from `chrono`).
+    #[must_use]
+    pub fn not_before(mut self, moment: impl Into<Timestamp>) -> Self {
+        self.not_before = Some(moment.into());
+        self
+    }
 
-        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
-            formatter.write_str("UTC timestamp")
+    /// Sets custom claims, replacing the previously set ones.
+    pub fn custom<U>(self, custom_claims: U) -> ClaimsBuilder<U> {
+        let registered = self.registered;
+        ClaimsBuilder {
+            registered: RegisteredClaims {
+                issuer: registered.issuer,
+                subject: registered.subject,
+                audience: registered.audience,
+                jwt_id: registered.jwt_id,
+                custom: custom_claims,
+            },
+            expires_in: self.expires_in,
+            not_before: self.not_before,
         }
+    }
 
-        fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E>
-        where
-            E: DeError,
-        {
-            Utc.timestamp_opt(value, 0)
-                .single()
-                .ok_or_else(|| E::custom("UTC timestamp overflow"))
+    /// Builds the claims. The current timestamp is taken from `options`.
+    pub fn build<F>(self, options: &TimeOptions<F>) -> Claims<RegisteredClaims<T>>
+    where
+        F: Fn() -> Timestamp,
+    {
+        let issued_at = (options.clock_fn)();
+        Claims {
+            expiration: self.expires_in.map(|duration| issued_at + duration),
+            not_before: self.not_before,
+            issued_at: Some(issued_at),
+            custom: self.registered,
         }
+    }
+}
+
+/// Value of the `aud` claim, which may be either a single string or an array of strings.
+///
+/// # Examples
+///
+/// ```
+/// # use jwt_compact::Audience;
+/// let audience: Audience = serde_json::from_str(r#""api""#)?;
+/// assert_eq!(audience, Audience::One("api".to_owned()));
+/// assert!(audience.contains("api"));
+///
+/// let audience: Audience = serde_json::from_str(r#"["api","admin"]"#)?;
+/// assert!(audience.contains("admin"));
+/// let values: Vec<_> = audience.iter().map(String::as_str).collect();
+/// assert_eq!(values, ["api", "admin"]);
+/// # Ok::<_, serde_json::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Audience {
+    /// Single audience value, serialized as a string.
+    One(String),
+    /// Multiple audience values, serialized as an array of strings.
+    Many(Vec<String>),
+}
+
+impl Audience {
+    /// Checks whether this audience contains the specified value.
+    pub fn contains(&self, audience: &str) -> bool {
+        self.iter().any(|value| value == audience)
+    }
 
-        fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
-        where
-            E: DeError,
-        {
-            let value = i64::try_from(value).map_err(DeError::custom)?;
-            Utc.timestamp_opt(value, 0)
-                .single()
-                .ok_or_else(|| E::custom("UTC timestamp overflow"))
+    /// Iterates over audience values.
+    pub fn iter(&self) -> core::slice::Iter<'_, String> {
+        self.as_slice().iter()
+    }
+
+    /// Returns audience values as a slice.
+    pub fn as_slice(&self) -> &[String] {
+        match self {
+            Self::One(value) => core::slice::from_ref(value),
+            Self::Many(values) => values,
         }
+    }
+}
+
+impl From<String> for Audience {
+    fn from(value: String) -> Self {
+        Self::One(value)
+    }
+}
 
-        #[allow(clippy::cast_possible_truncation)]
-        // ^ If truncation occurs, the `timestamp_opt()` won't return a single value anyway
-        fn visit_f64<E>(self, value: f64) -> Result<Self::Value, E>
-        where
-            E: DeError,
-        {
-            Utc.timestamp_opt(value as i64, 0)
-                .single()
-                .ok_or_else(|| E::custom("UTC timestamp overflow"))
+impl From<&str> for Audience {
+    fn from(value: &str) -> Self {
+        Self::One(value.into())
+    }
+}
+
+impl From<Vec<String>> for Audience {
+    fn from(values: Vec<String>) -> Self {
+        Self::Many(values)
+    }
+}
+
+impl From<Audience> for Vec<String> {
+    fn from(audience: Audience) -> Self {
+        match audience {
+            Audience::One(value) => Vec::from([value]),
+            Audience::Many(values) => values,
         }
     }
+}
+
+/// Collects values into [`Audience::Many`].
+impl<S: Into<String>> FromIterator<S> for Audience {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        Self::Many(iter.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<'a> IntoIterator for &'a Audience {
+    type Item = &'a String;
+    type IntoIter = core::slice::Iter<'a, String>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// (De)serialization of the `aud` claim into a `Vec`.
+pub(crate) mod serde_audience {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::Audience;
+    use crate::alloc::{String, Vec};
 
+    #[allow(clippy::ptr_arg)] // signature required by `serde(with)`
     pub fn serialize<S: Serializer>(
-        time: &Option<DateTime<Utc>>,
+        audience: &Vec<String>,
         serializer: S,
     ) -> Result<S::Ok, S::Error> {
-        // `unwrap` is safe due to `skip_serializing_if` option
-        serializer.serialize_i64(time.unwrap().timestamp())
+        if let [single] = audience.as_slice() {
+            single.serialize(serializer)
+        } else {
+            audience.serialize(serializer)
+        }
     }
 
     pub fn deserialize<'de, D: Deserializer<'de>>(
         deserializer: D,
-    ) -> Result<Option<DateTime<Utc>>, D::Error> {
-        deserializer.deserialize_i64(TimestampVisitor).map(Some)
+    ) -> Result<Vec<String>, D::Error> {
+        Audience::deserialize(deserializer).map(Vec::from)
     }
 }
 
@@ -279,29 +734,139 @@ mod serde_timestamp {
 mod tests {
     use super::*;
     use assert_matches::assert_matches;
-    use chrono::TimeZone;
+    use chrono::{TimeZone, Utc};
 
     #[test]
     fn empty_claims_can_be_serialized() {
         let mut claims = Claims::empty();
         assert!(serde_json::to_string(&claims).is_ok());
-        claims.expiration = Some(Utc::now());
+        claims.expiration = Some(Utc::now().into());
         assert!(serde_json::to_string(&claims).is_ok());
-        claims.not_before = Some(Utc::now());
+        claims.not_before = Some(Utc::now().into());
         assert!(serde_json::to_string(&claims).is_ok());
     }
 
+    #[test]
+    fn setting_timestamps() {
+        let claims = Claims::new(Empty {})
+            .set_expiration_timestamp(Timestamp::from_secs(2_000))
+            .set_issued_at_timestamp(Timestamp::from_secs(-1));
+        assert_eq!(claims.expiration, Some(Timestamp::from_secs(2_000)));
+        assert_eq!(claims.issued_at, Some(Timestamp::from_secs(-1)));
+        assert_eq!(claims.not_before, None);
+
+        let claims = claims.set_not_before_timestamp(Timestamp::MAX);
+        assert_eq!(claims.not_before, Some(Timestamp::MAX));
+        let json = serde_json::to_value(&claims).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({ "exp": 2_000, "nbf": i64::MAX, "iat": -1 })
+        );
+    }
+
+    #[test]
+    fn time_remaining_and_lifetime() {
+        let issued_at = Timestamp::from(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap());
+        let claims = Claims::empty().set_duration_and_issuance(
+            &TimeOptions::new(Duration::zero(), move || issued_at),
+            Duration::try_minutes(10).unwrap(),
+        );
+        let later = |minutes: i64| {
+            let now = issued_at + Duration::try_minutes(minutes).unwrap();
+            TimeOptions::new(Duration::try_minutes(1).unwrap(), move || now)
+        };
+
+        assert_eq!(claims.lifetime(), Duration::try_minutes(10));
+        assert_eq!(claims.age(&later(3)), Duration::try_minutes(3));
+        assert_eq!(claims.age(&later(-3)), Some(Duration::zero()));
+        assert_eq!(claims.expires_in(&later(3)), Duration::try_minutes(7));
+        assert!(!claims.is_expired(&later(3)));
+        // Leeway is taken into account by `is_expired`, but not by `expires_in`.
+        assert_eq!(claims.expires_in(&later(11)), Some(Duration::zero()));
+        assert!(!claims.is_expired(&later(11)));
+        assert!(claims.is_expired(&later(12)));
+
+        let claims = Claims::empty();
+        assert_eq!(claims.lifetime(), None);
+        assert_eq!(claims.age(&later(0)), None);
+        assert_eq!(claims.expires_in(&later(0)), None);
+        assert!(!claims.is_expired(&later(0)));
+    }
+
+    #[test]
+    fn dynamic_claims_serialization() {
+        let claims = DynamicClaims::new()
+            .with_claim("iss", "https://issuer.example.com")
+            .with_claim("scope", "read write");
+        let claims = Claims::new(claims).set_not_before(Utc.timestamp_opt(1_000, 0).unwrap());
+        let json = serde_json::to_value(&claims).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "nbf": 1_000,
+                "iss": "https://issuer.example.com",
+                "scope": "read write",
+            })
+        );
+
+        let restored: Claims<DynamicClaims> = serde_json::from_value(json).unwrap();
+        assert_eq!(restored.not_before, claims.not_before);
+        assert!(!restored.custom.contains_key("nbf"));
+        assert_eq!(restored.custom, claims.custom);
+        assert_eq!(restored.custom.get_str("scope"), Some("read write"));
+        assert_eq!(restored.custom.get_as::<u64>("exp").unwrap(), None);
+        restored.custom.get_as::<u64>("scope").unwrap_err();
+    }
+
     #[test]
     #[cfg(feature = "ciborium")]
     fn empty_claims_can_be_serialized_to_cbor() {
         let mut claims = Claims::empty();
         assert!(ciborium::into_writer(&claims, &mut vec![]).is_ok());
-        claims.expiration = Some(Utc::now());
+        claims.expiration = Some(Utc::now().into());
         assert!(ciborium::into_writer(&claims, &mut vec![]).is_ok());
-        claims.not_before = Some(Utc::now());
+        claims.not_before = Some(Utc::now().into());
         assert!(ciborium::into_writer(&claims, &mut vec![]).is_ok());
     }
 
+    #[test]
+    fn building_claims() {
+        let now = Timestamp::from(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap());
+        let time_options = TimeOptions::new(Duration::zero(), move || now);
+        let claims = Claims::builder()
+            .subject("alice")
+            .audience(["api", "admin"])
+            .jwt_id("token-id")
+            .expires_in(Duration::try_hours(1).unwrap())
+            .build(&time_options);
+
+        assert_eq!(claims.issued_at, Some(now));
+        assert_eq!(
+            claims.expiration,
+            Some(now + Duration::try_hours(1).unwrap())
+        );
+        assert_eq!(claims.not_before, None);
+        let json = serde_json::to_value(&claims).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "iat": 1_704_067_200,
+                "exp": 1_704_070_800,
+                "sub": "alice",
+                "aud": ["api", "admin"],
+                "jti": "token-id",
+            })
+        );
+
+        let json = serde_json::json!({ "iss": "me", "aud": "api", "custom": 42 });
+        let restored: Claims<RegisteredClaims<serde_json::Value>> =
+            serde_json::from_value(json).unwrap();
+        let restored = restored.custom;
+        assert_eq!(restored.issuer.as_deref(), Some("me"));
+        assert_eq!(restored.audience, Some(Audience::One("api".to_owned())));
+        assert_eq!(restored.custom, serde_json::json!({ "custom": 42 }));
+    }
+
     #[test]
     fn expired_claim() {
         let mut claims = Claims::empty();
@@ -311,16 +876,16 @@ mod tests {
             ValidationError::NoClaim(Claim::Expiration)
         );
 
-        claims.expiration = Some(DateTime::<Utc>::MAX_UTC);
+        claims.expiration = Some(Timestamp::MAX);
         assert!(claims.validate_expiration(&time_options).is_ok());
 
-        claims.expiration = Some(Utc::now() - Duration::try_hours(1).unwrap());
+        claims.expiration = Some((Utc::now() - Duration::try_hours(1).unwrap()).into());
         assert_matches!(
             claims.validate_expiration(&time_options).unwrap_err(),
             ValidationError::Expired
         );
 
-        claims.expiration = Some(Utc::now() - Duration::try_seconds(10).unwrap());
+        claims.expiration = Some((Utc::now() - Duration::try_seconds(10).unwrap()).into());
         // With the default leeway, this claim is still valid.
         assert!(claims.validate_expiration(&time_options).is_ok());
         // If we set leeway lower, then the claim will be considered expired.
@@ -349,13 +914,13 @@ mod tests {
             ValidationError::NoClaim(Claim::NotBefore)
         );
 
-        claims.not_before = Some(Utc::now() + Duration::try_hours(1).unwrap());
+        claims.not_before = Some((Utc::now() + Duration::try_hours(1).unwrap()).into());
         assert_matches!(
             claims.validate_maturity(&time_options).unwrap_err(),
             ValidationError::NotMature
         );
 
-        claims.not_before = Some(Utc::now() + Duration::try_seconds(10).unwrap());
+        claims.not_before = Some((Utc::now() + Duration::try_seconds(10).unwrap()).into());
         // With the default leeway, this claim is still valid.
         assert!(claims.validate_maturity(&time_options).is_ok());
         // If we set leeway lower, then the claim will be considered expired.
@@ -370,8 +935,7 @@ mod tests {
     fn float_timestamp() {
         let claims = "{\"exp\": 1.691203462e+9}";
         let claims: Claims<Empty> = serde_json::from_str(claims).unwrap();
-        let timestamp = Utc.timestamp_opt(1_691_203_462, 0).single().unwrap();
-        assert_eq!(claims.expiration, Some(timestamp));
+        assert_eq!(claims.expiration, Some(Timestamp::from_secs(1_691_203_462)));
     }
 
     #[test]
@@ -11,6 +11,97 @@ pub(crate) type CborDeError<E = anyhow::Error> = ciborium::de::Error<E>;
 #[cfg(feature = "ciborium")]
 pub(crate) type CborSerError<E = Infallible> = ciborium::ser::Error<E>;
 
+/// Kind of a token parsing or validation error. Allows handling errors programmatically,
+/// e.g., mapping them to HTTP status codes or metric labels, without matching on error variants
+/// of specific error types.
+///
+/// The kind is returned by the `kind()` method of error types, such as
+/// [`ParseError::kind()`] and [`ValidationError::kind()`].
+///
+/// # Examples
+///
+/// ```
+/// # use jwt_compact::{alg::{Hs256, Hs256Key}, prelude::*, Empty, ErrorKind};
+/// # fn main() -> anyhow::Result<()> {
+/// # let key = Hs256Key::new(b"super_secret_key_donut_steel");
+/// let err = UntrustedToken::new("not a token").unwrap_err();
+/// assert_eq!(err.kind(), ErrorKind::MalformedToken);
+///
+/// let token = Hs256.token(&Header::empty(), &Claims::empty(), &key)?;
+/// let token = UntrustedToken::new(&token)?;
+/// let other_key = Hs256Key::new(b"other_secret_key_donut_steel");
+/// let err = Hs256.validator::<Empty>(&other_key).validate(&token).unwrap_err();
+/// assert_eq!(err.kind(), ErrorKind::InvalidSignature);
+/// assert_eq!(err.kind().as_str(), "invalid_signature");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// Token is malformed, e.g., cannot be parsed or has claims of an unexpected format.
+    MalformedToken,
+    /// Token is secured with an unsupported or unexpected algorithm.
+    UnsupportedAlgorithm,
+    /// Token signature has failed verification.
+    InvalidSignature,
+    /// Token is signed with an unknown key.
+    UnknownKey,
+    /// Token has expired.
+    Expired,
+    /// Token is not yet valid as per the `nbf` claim.
+    NotMature,
+    /// Claim required during validation is missing.
+    MissingClaim,
+    /// Token header lists a critical extension not understood by the application.
+    UnsupportedExtension,
+    /// Token issuer (the `iss` claim) differs from the expected one.
+    IssuerMismatch,
+    /// Token audience (the `aud` claim) does not include the expected one.
+    AudienceMismatch,
+    /// Claim other than `iss` or `aud` has an unexpected value.
+    InvalidClaim,
+    /// Token cannot be validated because of an issue not related to the token itself,
+    /// e.g., an unavailable key server or an invalid verifying key.
+    Internal,
+}
+
+impl ErrorKind {
+    /// Returns the kind for a mismatch of the specified claim.
+    pub(crate) fn claim_mismatch(claim: &str) -> Self {
+        match claim {
+            "iss" => Self::IssuerMismatch,
+            "aud" => Self::AudienceMismatch,
+            _ => Self::InvalidClaim,
+        }
+    }
+
+    /// Returns a short snake-case identifier of this kind (e.g., `invalid_signature`),
+    /// which can be used as a metric label.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::MalformedToken => "malformed_token",
+            Self::UnsupportedAlgorithm => "unsupported_algorithm",
+            Self::InvalidSignature => "invalid_signature",
+            Self::UnknownKey => "unknown_key",
+            Self::Expired => "expired",
+            Self::NotMature => "not_mature",
+            Self::MissingClaim => "missing_claim",
+            Self::UnsupportedExtension => "unsupported_extension",
+            Self::IssuerMismatch => "issuer_mismatch",
+            Self::AudienceMismatch => "audience_mismatch",
+            Self::InvalidClaim => "invalid_claim",
+            Self::Internal => "internal",
+        }
+    }
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.as_str())
+    }
+}
+
 /// Errors that may occur during token parsing.
 #[derive(Debug)]
 #[non_exhaustive]
@@ -31,6 +122,23 @@ pub enum ParseError {
     ///
     /// [cty]: https://tools.ietf.org/html/rfc7515#section-4.1.10
     UnsupportedContentType(String),
+    /// Token string is longer than allowed by [`ParseOptions::max_token_len`].
+    ///
+    /// [`ParseOptions::max_token_len`]: crate::ParseOptions::max_token_len
+    TokenTooLong,
+    /// Decoded token payload is larger than allowed by [`ParseOptions::max_payload_len`].
+    ///
+    /// [`ParseOptions::max_payload_len`]: crate::ParseOptions::max_payload_len
+    PayloadTooLarge,
+    /// Nesting depth of the token header or claims exceeds [`ParseOptions::max_nesting_depth`].
+    ///
+    /// [`ParseOptions::max_nesting_depth`]: crate::ParseOptions::max_nesting_depth
+    NestingTooDeep,
+    /// A JSON object in the token header or claims contains the enclosed key more than once.
+    /// Only checked if [`ParseOptions::reject_duplicate_keys`] is enabled.
+    ///
+    /// [`ParseOptions::reject_duplicate_keys`]: crate::ParseOptions::reject_duplicate_keys
+    DuplicateKey(String),
 }
 
 impl fmt::Display for ParseError {
@@ -42,13 +150,23 @@ impl fmt::Display for ParseError {
             Self::UnsupportedContentType(ty) => {
                 write!(formatter, "unsupported content type: {ty}")
             }
+            Self::TokenTooLong => formatter.write_str("token is too long"),
+            Self::PayloadTooLarge => formatter.write_str("token payload is too large"),
+            Self::NestingTooDeep => formatter.write_str("token nesting depth is too large"),
+            Self::DuplicateKey(key) => write!(formatter, "duplicate JSON key `{key}` in token"),
         }
     }
 }
 
-#[cfg(feature = "std")]
-impl std::error::Error for ParseError {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+impl ParseError {
+    /// Returns the kind of this error, which is always [`ErrorKind::MalformedToken`].
+    pub fn kind(&self) -> ErrorKind {
+        ErrorKind::MalformedToken
+    }
+}
+
+impl core::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
         match self {
             Self::MalformedHeader(err) => Some(err),
             _ => None,
@@ -78,6 +196,9 @@ pub enum ValidationError {
     MalformedSignature(anyhow::Error),
     /// Token signature has failed verification.
     InvalidSignature,
+    /// Token signature cannot be verified, e.g., because a remote verifier is unavailable.
+    /// Returned by [`AsyncValidator`](crate::AsyncValidator).
+    Verification(anyhow::Error),
     /// Token claims cannot be deserialized from JSON.
     MalformedClaims(serde_json::Error),
     /// Token claims cannot be deserialized from CBOR.
@@ -90,6 +211,13 @@ pub enum ValidationError {
     Expired,
     /// Token is not yet valid as per `nbf` claim.
     NotMature,
+    /// Verifying key for the token cannot be found in a [`KeyStore`](crate::key_store::KeyStore).
+    UnknownKey,
+    /// Token header lists a [critical extension][crit] (the enclosed value) that was not declared
+    /// as understood during validation.
+    ///
+    /// [crit]: https://www.rfc-editor.org/rfc/rfc7515#section-4.1.11
+    UnknownCriticalExtension(String),
 }
 
 /// Identifier of a claim in `Claims`.
@@ -124,6 +252,7 @@ impl fmt::Display for ValidationError {
             ),
             Self::MalformedSignature(err) => write!(formatter, "malformed token signature: {err}"),
             Self::InvalidSignature => formatter.write_str("signature has failed verification"),
+            Self::Verification(err) => write!(formatter, "cannot verify signature: {err}"),
             Self::MalformedClaims(err) => write!(formatter, "cannot deserialize claims: {err}"),
             #[cfg(feature = "ciborium")]
             Self::MalformedCborClaims(err) => write!(formatter, "cannot deserialize claims: {err}"),
@@ -133,15 +262,42 @@ impl fmt::Display for ValidationError {
             ),
             Self::Expired => formatter.write_str("token has expired"),
             Self::NotMature => formatter.write_str("token is not yet ready"),
+            Self::UnknownKey => formatter.write_str("token is signed with an unknown key"),
+            Self::UnknownCriticalExtension(name) => {
+                write!(
+                    formatter,
+                    "unknown critical extension `{name}` in token header"
+                )
+            }
+        }
+    }
+}
+
+impl ValidationError {
+    /// Returns the kind of this error.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Self::AlgorithmMismatch { .. } => ErrorKind::UnsupportedAlgorithm,
+            Self::InvalidSignatureLen { .. }
+            | Self::MalformedSignature(_)
+            | Self::MalformedClaims(_) => ErrorKind::MalformedToken,
+            #[cfg(feature = "ciborium")]
+            Self::MalformedCborClaims(_) => ErrorKind::MalformedToken,
+            Self::InvalidSignature => ErrorKind::InvalidSignature,
+            Self::Verification(_) => ErrorKind::Internal,
+            Self::NoClaim(_) => ErrorKind::MissingClaim,
+            Self::Expired => ErrorKind::Expired,
+            Self::NotMature => ErrorKind::NotMature,
+            Self::UnknownKey => ErrorKind::UnknownKey,
+            Self::UnknownCriticalExtension(_) => ErrorKind::UnsupportedExtension,
         }
     }
 }
 
-#[cfg(feature = "std")]
-impl std::error::Error for ValidationError {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+impl core::error::Error for ValidationError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
         match self {
-            Self::MalformedSignature(err) => Some(err.as_ref()),
+            Self::MalformedSignature(err) | Self::Verification(err) => Some(err.as_ref()),
             Self::MalformedClaims(err) => Some(err),
             #[cfg(feature = "ciborium")]
             Self::MalformedCborClaims(err) => Some(err),
@@ -162,6 +318,8 @@ pub enum CreationError {
     #[cfg(feature = "ciborium")]
     #[cfg_attr(docsrs, doc(cfg(feature = "ciborium")))]
     CborClaims(CborSerError),
+    /// Token signing input cannot be signed (e.g., because a remote signer has failed).
+    Signing(anyhow::Error),
 }
 
 impl fmt::Display for CreationError {
@@ -171,17 +329,18 @@ impl fmt::Display for CreationError {
             Self::Claims(err) => write!(formatter, "cannot serialize claims: {err}"),
             #[cfg(feature = "ciborium")]
             Self::CborClaims(err) => write!(formatter, "cannot serialize claims into CBOR: {err}"),
+            Self::Signing(err) => write!(formatter, "cannot sign token: {err}"),
         }
     }
 }
 
-#[cfg(feature = "std")]
-impl std::error::Error for CreationError {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+impl core::error::Error for CreationError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
         match self {
             Self::Header(err) | Self::Claims(err) => Some(err),
             #[cfg(feature = "ciborium")]
             Self::CborClaims(err) => Some(err),
+            Self::Signing(err) => Some(err.as_ref()),
         }
     }
 }
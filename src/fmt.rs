@@ -0,0 +1,217 @@
+//! Human-readable rendering of tokens for debugging.
+//!
+//! [`TokenDescription`] is produced by [`UntrustedToken::describe()`](crate::UntrustedToken::describe())
+//! and renders the token header, claims and signature metadata via its `Display` implementation.
+//! Numeric date claims are rendered both as UNIX timestamps and in the ISO 8601 format.
+//! The token is **not** verified, so the description must not be used for any security decisions.
+
+use chrono::{DateTime, Utc};
+use serde_json::{Map, Value};
+
+use core::fmt;
+
+use crate::{
+    alloc::{BTreeSet, String},
+    Claims, Timestamp,
+};
+
+/// Custom claims that are rendered as numeric dates if they are integers.
+const NUMERIC_DATE_CLAIMS: &[&str] = &["auth_time"];
+
+/// Human-readable description of a token header, claims and signature.
+///
+/// # Examples
+///
+/// ```
+/// # use jwt_compact::{prelude::*, alg::{Hs256, Hs256Key}};
+/// # use chrono::{TimeZone, Utc};
+/// # fn main() -> anyhow::Result<()> {
+/// # let key = Hs256Key::new(b"super_secret_key_donut_steel");
+/// let claims = Claims::new(serde_json::json!({ "email": "alice@example.com", "sub": "alice" }))
+///     .set_not_before(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap());
+/// let token = Hs256.token(&Header::empty().with_key_id("k1"), &claims, &key)?;
+/// let token = UntrustedToken::new(&token)?;
+/// let description = token.describe().redact(|name| name == "email");
+/// assert_eq!(
+///     description.to_string(),
+///     "Header:\n  alg: \"HS256\"\n  kid: \"k1\"\n\
+///      Claims:\n  nbf: 1704067200 (2024-01-01T00:00:00Z)\n  email: <redacted>\n  sub: \"alice\"\n\
+///      Signature: 32 bytes (not verified)\n"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct TokenDescription {
+    header: Map<String, Value>,
+    claims: Result<Claims<Map<String, Value>>, String>,
+    signature_len: usize,
+    redacted_claims: BTreeSet<String>,
+}
+
+impl TokenDescription {
+    pub(crate) fn new(
+        header: Map<String, Value>,
+        claims: Result<Claims<Map<String, Value>>, String>,
+        signature_len: usize,
+    ) -> Self {
+        Self {
+            header,
+            claims,
+            signature_len,
+            redacted_claims: BTreeSet::new(),
+        }
+    }
+
+    /// Returns the token header, including the `alg` field.
+    pub fn header(&self) -> &Map<String, Value> {
+        &self.header
+    }
+
+    /// Returns the token claims, or `None` if the claims cannot be deserialized.
+    pub fn claims(&self) -> Option<&Claims<Map<String, Value>>> {
+        self.claims.as_ref().ok()
+    }
+
+    /// Returns the signature length in bytes.
+    pub fn signature_len(&self) -> usize {
+        self.signature_len
+    }
+
+    /// Redacts custom claims matching the `predicate` (e.g., claims with secrets or personally
+    /// identifiable information), so that their values are not output by the `Display`
+    /// implementation. Redacted values are also replaced with `null` in [`Self::claims()`].
+    #[must_use]
+    pub fn redact(mut self, predicate: impl Fn(&str) -> bool) -> Self {
+        if let Ok(claims) = &mut self.claims {
+            let redacted = claims.custom.keys().filter(|name| predicate(name));
+            self.redacted_claims.extend(redacted.cloned());
+            for name in &self.redacted_claims {
+                claims.custom.insert(name.clone(), Value::Null);
+            }
+        }
+        self
+    }
+}
+
+fn write_date(
+    formatter: &mut fmt::Formatter<'_>,
+    name: &str,
+    timestamp: Option<Timestamp>,
+) -> fmt::Result {
+    let Some(timestamp) = timestamp else {
+        return Ok(());
+    };
+    if let Ok(date) = DateTime::<Utc>::try_from(timestamp) {
+        writeln!(formatter, "  {name}: {timestamp} ({date:?})")
+    } else {
+        writeln!(formatter, "  {name}: {timestamp}")
+    }
+}
+
+impl fmt::Display for TokenDescription {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(formatter, "Header:")?;
+        // `alg` is output first since it's the most important header field.
+        if let Some(alg) = self.header.get("alg") {
+            writeln!(formatter, "  alg: {alg}")?;
+        }
+        for (name, value) in self.header.iter().filter(|(name, _)| *name != "alg") {
+            writeln!(formatter, "  {name}: {value}")?;
+        }
+
+        match &self.claims {
+            Ok(claims) => {
+                writeln!(formatter, "Claims:")?;
+                write_date(formatter, "exp", claims.expiration)?;
+                write_date(formatter, "nbf", claims.not_before)?;
+                write_date(formatter, "iat", claims.issued_at)?;
+                for (name, value) in &claims.custom {
+                    if self.redacted_claims.contains(name) {
+                        writeln!(formatter, "  {name}: <redacted>")?;
+                        continue;
+                    }
+                    let date = value
+                        .as_i64()
+                        .filter(|_| NUMERIC_DATE_CLAIMS.contains(&name.as_str()))
+                        .and_then(|secs| DateTime::from_timestamp(secs, 0));
+                    if let Some(date) = date {
+                        writeln!(formatter, "  {name}: {value} ({date:?})")?;
+                    } else {
+                        writeln!(formatter, "  {name}: {value}")?;
+                    }
+                }
+            }
+            Err(err) => writeln!(formatter, "Claims: <malformed: {err}>")?,
+        }
+        writeln!(
+            formatter,
+            "Signature: {} bytes (not verified)",
+            self.signature_len
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base64ct::{Base64UrlUnpadded, Encoding};
+
+    use super::*;
+    use crate::{
+        alg::{Hs256, Hs256Key},
+        alloc::{format, ToString},
+        AlgorithmExt, Header, UntrustedToken,
+    };
+
+    #[test]
+    fn describing_token() {
+        let key = Hs256Key::new(b"super_secret_key_donut_steel");
+        let header = Header::new(serde_json::json!({ "crit": ["custom"], "custom": 1 }));
+        let claims = Claims::new(serde_json::json!({ "auth_time": 1_000, "nested": { "a": [1] } }));
+        let claims = claims.set_expiration_timestamp(Timestamp::from_secs(2_000));
+        let token = Hs256.token(&header, &claims, &key).unwrap();
+        let token = UntrustedToken::<serde_json::Value>::try_from(token.as_str()).unwrap();
+
+        let description = token.describe();
+        assert_eq!(description.signature_len(), 32);
+        assert_eq!(description.header()["crit"], serde_json::json!(["custom"]));
+        let description = description.to_string();
+        assert!(
+            description.contains("  crit: [\"custom\"]\n"),
+            "{description}"
+        );
+        assert!(description.contains("  custom: 1\n"), "{description}");
+        assert!(
+            description.contains("  exp: 2000 (1970-01-01T00:33:20Z)\n"),
+            "{description}"
+        );
+        assert!(
+            description.contains("  auth_time: 1000 (1970-01-01T00:16:40Z)\n"),
+            "{description}"
+        );
+        assert!(
+            description.contains("  nested: {\"a\":[1]}\n"),
+            "{description}"
+        );
+    }
+
+    #[test]
+    fn describing_token_with_malformed_claims() {
+        let header = Base64UrlUnpadded::encode_string(br#"{"alg":"HS256"}"#);
+        let claims = Base64UrlUnpadded::encode_string(b"[1]");
+        let token = format!("{header}.{claims}.c2ln");
+        let token = UntrustedToken::new(&token).unwrap();
+        let description = token.describe();
+        assert!(description.claims().is_none());
+
+        let description = description.redact(|_| true).to_string();
+        assert!(
+            description.starts_with("Header:\n  alg: \"HS256\"\nClaims: <malformed: "),
+            "{description}"
+        );
+        assert!(
+            description.ends_with("Signature: 3 bytes (not verified)\n"),
+            "{description}"
+        );
+    }
+}
@@ -0,0 +1,498 @@
+//! Integrations with web frameworks.
+//!
+//! The framework-agnostic core of integrations is [`Authenticator`], which extracts a bearer token
+//! from the `Authorization` header value as per [RFC 6750], validates it and applies authorization
+//! policies. Framework-specific modules are gated by the crate features with the same name
+//! as the framework.
+//!
+//! [RFC 6750]: https://www.rfc-editor.org/rfc/rfc6750.html
+
+use chrono::Duration;
+use serde::de::DeserializeOwned;
+
+use core::fmt;
+use std::{collections::BTreeMap, sync::Arc};
+
+use crate::{
+    jwk::{JsonWebKey, JsonWebKeySet, JwkError},
+    validation::{Expectations, ValidationFailure, ValidationReport},
+    Algorithm, AlgorithmExt, Claims, ErrorKind, ParseError, TimeOptions, Token, UntrustedToken,
+    ValidationError,
+};
+
+#[cfg(feature = "actix-web")]
+#[cfg_attr(docsrs, doc(cfg(feature = "actix-web")))]
+pub mod actix_web;
+#[cfg(feature = "aws-lambda")]
+#[cfg_attr(docsrs, doc(cfg(feature = "aws-lambda")))]
+pub mod aws_lambda;
+#[cfg(feature = "axum")]
+#[cfg_attr(docsrs, doc(cfg(feature = "axum")))]
+pub mod axum;
+#[cfg(feature = "reqwest")]
+#[cfg_attr(docsrs, doc(cfg(feature = "reqwest")))]
+pub mod reqwest;
+#[cfg(feature = "rocket")]
+#[cfg_attr(docsrs, doc(cfg(feature = "rocket")))]
+pub mod rocket;
+#[cfg(feature = "tonic")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tonic")))]
+pub mod tonic;
+#[cfg(feature = "tower")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tower")))]
+pub mod tower;
+#[cfg(feature = "warp")]
+#[cfg_attr(docsrs, doc(cfg(feature = "warp")))]
+pub mod warp;
+
+type Verifier<T> = dyn Fn(&UntrustedToken<'_>) -> Result<Token<T>, AuthError> + Send + Sync;
+type Policy<T> = dyn Fn(&Claims<T>) -> bool + Send + Sync;
+type NamedPolicy<T> = (Option<Arc<str>>, Arc<Policy<T>>);
+
+/// Extracts a bearer token from the value of the `Authorization` header.
+/// The authentication scheme is matched case-insensitively.
+pub fn extract_bearer_token(authorization: &str) -> Option<&str> {
+    let (scheme, token) = authorization.split_once(' ')?;
+    let token = token.trim();
+    (scheme.eq_ignore_ascii_case("bearer") && !token.is_empty()).then_some(token)
+}
+
+/// Errors that can occur during request authentication.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum AuthError {
+    /// The request does not contain a bearer token.
+    MissingToken,
+    /// The `Authorization` header is malformed (e.g., is not valid UTF-8).
+    MalformedHeader,
+    /// The token cannot be parsed.
+    Parse(ParseError),
+    /// The key referenced by the token (via the `kid` header field) is unknown.
+    UnknownKey,
+    /// The token has failed validation (e.g., has an invalid signature or is expired).
+    Validation(ValidationError),
+    /// The token is valid, but does not satisfy an authorization policy.
+    Forbidden,
+}
+
+impl AuthError {
+    /// Returns the HTTP status code corresponding to this error: 403 for [`Self::Forbidden`],
+    /// and 401 for other errors.
+    pub fn status_code(&self) -> u16 {
+        match self {
+            Self::Forbidden => 403,
+            _ => 401,
+        }
+    }
+
+    /// Returns the kind of the token error, or `None` if the error is not related to the token
+    /// (i.e., for [`Self::MissingToken`], [`Self::MalformedHeader`] and [`Self::Forbidden`]).
+    pub fn kind(&self) -> Option<ErrorKind> {
+        match self {
+            Self::Parse(err) => Some(err.kind()),
+            Self::UnknownKey => Some(ErrorKind::UnknownKey),
+            Self::Validation(err) => Some(err.kind()),
+            Self::MissingToken | Self::MalformedHeader | Self::Forbidden => None,
+        }
+    }
+
+    /// Returns the value of the `WWW-Authenticate` header to be included into the error response
+    /// as per [RFC 6750](https://www.rfc-editor.org/rfc/rfc6750.html#section-3).
+    pub fn challenge(&self) -> &'static str {
+        match self {
+            Self::MissingToken => "Bearer",
+            Self::MalformedHeader => r#"Bearer error="invalid_request""#,
+            Self::Forbidden => r#"Bearer error="insufficient_scope""#,
+            _ => r#"Bearer error="invalid_token""#,
+        }
+    }
+}
+
+impl fmt::Display for AuthError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingToken => formatter.write_str("bearer token is missing"),
+            Self::MalformedHeader => formatter.write_str("malformed `Authorization` header"),
+            Self::Parse(err) => write!(formatter, "cannot parse token: {err}"),
+            Self::UnknownKey => formatter.write_str("token is signed with an unknown key"),
+            Self::Validation(err) => write!(formatter, "token validation failed: {err}"),
+            Self::Forbidden => formatter.write_str("access is forbidden"),
+        }
+    }
+}
+
+impl std::error::Error for AuthError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Parse(err) => Some(err),
+            Self::Validation(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<ValidationError> for AuthError {
+    fn from(err: ValidationError) -> Self {
+        Self::Validation(err)
+    }
+}
+
+/// Authenticator of requests bearing tokens with claims of type `T`.
+///
+/// The authenticator checks token integrity and expiration (the `exp` claim is required);
+/// if the `nbf` claim is present, token maturity is checked as well. Additional checks
+/// (e.g., on the issuer or scopes) can be added as [policies](Self::with_policy());
+/// failing a policy results in [`AuthError::Forbidden`].
+///
+/// The authenticator is cheaply cloneable and can be shared among threads.
+///
+/// # Examples
+///
+/// ```
+/// # use chrono::Duration;
+/// # use jwt_compact::{alg::{Hs256, Hs256Key}, prelude::*};
+/// use jwt_compact::integrations::{AuthError, Authenticator};
+/// # use serde::{Deserialize, Serialize};
+///
+/// #[derive(Debug, Clone, Serialize, Deserialize)]
+/// struct CustomClaims {
+///     #[serde(rename = "sub")]
+///     subject: String,
+///     admin: bool,
+/// }
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let key = Hs256Key::new(b"super_secret_key_donut_steel");
+/// let authenticator = Authenticator::<CustomClaims>::new(Hs256, key.clone())
+///     .with_leeway(Duration::try_seconds(30).unwrap())
+///     .with_policy(|claims| claims.custom.admin);
+///
+/// # let claims = CustomClaims { subject: "alice".to_owned(), admin: false };
+/// # let claims = Claims::new(claims)
+/// #     .set_duration_and_issuance(&TimeOptions::default(), Duration::try_minutes(5).unwrap());
+/// # let token = Hs256.token(&Header::empty(), &claims, &key)?;
+/// let header_value = format!("Bearer {token}");
+/// let err = authenticator.authenticate(Some(&header_value)).unwrap_err();
+/// assert!(matches!(err, AuthError::Forbidden));
+/// assert_eq!(err.status_code(), 403);
+/// # Ok(())
+/// # }
+/// ```
+pub struct Authenticator<T> {
+    verifier: Arc<Verifier<T>>,
+    policies: Vec<NamedPolicy<T>>,
+    leeway: Duration,
+}
+
+impl<T> fmt::Debug for Authenticator<T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("Authenticator")
+            .field("policies", &self.policies.len())
+            .field("leeway", &self.leeway)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> Clone for Authenticator<T> {
+    fn clone(&self) -> Self {
+        Self {
+            verifier: Arc::clone(&self.verifier),
+            policies: self.policies.clone(),
+            leeway: self.leeway,
+        }
+    }
+}
+
+impl<T: DeserializeOwned + 'static> Authenticator<T> {
+    /// Creates an authenticator verifying tokens with the specified algorithm and key.
+    /// The leeway for time-related checks is set to 60 seconds.
+    pub fn new<A>(algorithm: A, verifying_key: A::VerifyingKey) -> Self
+    where
+        A: Algorithm + Send + Sync + 'static,
+        A::VerifyingKey: Send + Sync + 'static,
+    {
+        Self::from_verifier(move |token| {
+            Ok(algorithm.validator::<T>(&verifying_key).validate(token)?)
+        })
+    }
+
+    /// Creates an authenticator verifying tokens with keys from the provided key set,
+    /// selected by the `kid` header field. Keys without an identifier and keys that cannot
+    /// be converted to verifying keys of the algorithm (e.g., keys of another type)
+    /// are skipped.
+    pub fn from_key_set<A>(algorithm: A, key_set: &JsonWebKeySet<'_>) -> Self
+    where
+        A: Algorithm + Send + Sync + 'static,
+        A::VerifyingKey: for<'k> TryFrom<&'k JsonWebKey<'k>, Error = JwkError> + Send + Sync,
+    {
+        let keys: BTreeMap<_, _> = key_set
+            .keys
+            .iter()
+            .filter_map(|entry| {
+                let key_id = entry.key_id.clone()?;
+                let key = A::VerifyingKey::try_from(&entry.key).ok()?;
+                Some((key_id, key))
+            })
+            .collect();
+
+        Self::from_verifier(move |token| {
+            let key_id = token.header().key_id.as_deref();
+            let key = key_id
+                .and_then(|key_id| keys.get(key_id))
+                .ok_or(AuthError::UnknownKey)?;
+            Ok(algorithm.validator::<T>(key).validate(token)?)
+        })
+    }
+
+    pub(crate) fn from_verifier<F>(verifier: F) -> Self
+    where
+        F: Fn(&UntrustedToken<'_>) -> Result<Token<T>, AuthError> + Send + Sync + 'static,
+    {
+        Self {
+            verifier: Arc::new(verifier),
+            policies: Vec::new(),
+            leeway: Duration::try_seconds(60).unwrap(),
+        }
+    }
+}
+
+impl<T> Authenticator<T> {
+    /// Sets the leeway for time-related checks.
+    #[must_use]
+    pub fn with_leeway(mut self, leeway: Duration) -> Self {
+        self.leeway = leeway;
+        self
+    }
+
+    /// Adds an authorization policy. Tokens with claims not satisfying the policy
+    /// are rejected with [`AuthError::Forbidden`].
+    #[must_use]
+    pub fn with_policy<F>(mut self, policy: F) -> Self
+    where
+        F: Fn(&Claims<T>) -> bool + Send + Sync + 'static,
+    {
+        self.policies.push((None, Arc::new(policy)));
+        self
+    }
+
+    /// Adds a named authorization policy. The name is used to identify a failed policy
+    /// in a [`ValidationReport`]; otherwise, this method is equivalent to [`Self::with_policy()`].
+    #[must_use]
+    pub fn with_named_policy<F>(mut self, name: &str, policy: F) -> Self
+    where
+        F: Fn(&Claims<T>) -> bool + Send + Sync + 'static,
+    {
+        self.policies.push((Some(name.into()), Arc::new(policy)));
+        self
+    }
+
+    /// Authenticates a request given the value of its `Authorization` header.
+    pub fn authenticate(&self, authorization: Option<&str>) -> Result<Token<T>, AuthError> {
+        let authorization = authorization.ok_or(AuthError::MissingToken)?;
+        let token = extract_bearer_token(authorization).ok_or(AuthError::MissingToken)?;
+        self.authenticate_token(token)
+    }
+
+    /// Authenticates a raw token, e.g., one obtained from a cookie.
+    pub fn authenticate_token(&self, token: &str) -> Result<Token<T>, AuthError> {
+        let token = UntrustedToken::new(token).map_err(AuthError::Parse)?;
+        self.authenticate_untrusted(&token)
+    }
+
+    pub(crate) fn authenticate_untrusted(
+        &self,
+        token: &UntrustedToken<'_>,
+    ) -> Result<Token<T>, AuthError> {
+        let token = (self.verifier)(token)?;
+        self.check_claims(token)
+    }
+
+    /// Checks time-related claims and policies for a token with a verified signature.
+    pub(crate) fn check_claims(&self, token: Token<T>) -> Result<Token<T>, AuthError> {
+        let claims = token.claims();
+        let options = TimeOptions::from_leeway(self.leeway);
+        claims.validate_expiration(&options)?;
+        if claims.not_before.is_some() {
+            claims.validate_maturity(&options)?;
+        }
+
+        if self.policies.iter().all(|(_, policy)| policy(claims)) {
+            Ok(token)
+        } else {
+            Err(AuthError::Forbidden)
+        }
+    }
+}
+
+impl<T: DeserializeOwned> Authenticator<T> {
+    /// Evaluates all checks for a raw token and returns a report listing every failed check,
+    /// instead of stopping at the first failure as [`Self::authenticate_token()`] does.
+    /// This is useful for diagnostics, e.g., to explain why a token was rejected.
+    ///
+    /// If the token signature cannot be verified, time-related checks and policies
+    /// are still evaluated for the unverified claims. Thus, the report must not be used
+    /// to authorize requests other than via [`ValidationReport::is_valid()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chrono::Duration;
+    /// # use jwt_compact::{alg::{Hs256, Hs256Key}, prelude::*};
+    /// use jwt_compact::{integrations::Authenticator, validation::ValidationFailure};
+    /// # use serde_json::{json, Value};
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let key = Hs256Key::new(b"super_secret_key_donut_steel");
+    /// let authenticator = Authenticator::<Value>::new(Hs256, key.clone())
+    ///     .with_named_policy("aud", |claims| claims.custom["aud"] == "api")
+    ///     .with_named_policy("scope", |claims| claims.custom["scope"] == "read");
+    ///
+    /// let claims = Claims::new(json!({ "aud": "other" }))
+    ///     .set_duration(&TimeOptions::default(), -Duration::try_minutes(5).unwrap());
+    /// let token = Hs256.token(&Header::empty(), &claims, &key)?;
+    /// let report = authenticator.validation_report(&token);
+    /// assert!(!report.is_valid());
+    /// assert_eq!(report.failures().len(), 3);
+    /// assert!(matches!(&report.failures()[1], ValidationFailure::Policy(name) if name == "aud"));
+    /// assert_eq!(
+    ///     report.to_string(),
+    ///     "token has expired; policy `aud` is not satisfied; policy `scope` is not satisfied"
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn validation_report(&self, token: &str) -> ValidationReport {
+        let mut report = ValidationReport::default();
+        let expectations = Expectations::new(TimeOptions::from_leeway(self.leeway));
+        let verify = |token: &UntrustedToken<'_>| {
+            (self.verifier)(token).map_err(|err| match err {
+                AuthError::Validation(err) => err,
+                // Verifiers only fail with validation errors or because of an unknown key.
+                _ => ValidationError::UnknownKey,
+            })
+        };
+        let Some(claims) = expectations.evaluate(token, verify, &mut report) else {
+            return report;
+        };
+
+        for (i, (name, policy)) in self.policies.iter().enumerate() {
+            if !policy(&claims) {
+                let name = name
+                    .as_deref()
+                    .map_or_else(|| format!("#{i}"), str::to_owned);
+                report.push(ValidationFailure::Policy(name));
+            }
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::{
+        alg::{Hs256, Hs256Key},
+        jwk::KeySetEntry,
+        test_helpers::{key, sign},
+        Header,
+    };
+
+    fn token(claims: Value, key_id: Option<&str>) -> String {
+        let mut header = Header::empty();
+        if let Some(key_id) = key_id {
+            header = header.with_key_id(key_id);
+        }
+        sign(&claims, &header)
+    }
+
+    #[test]
+    fn extracting_bearer_token() {
+        assert_eq!(extract_bearer_token("Bearer abc"), Some("abc"));
+        assert_eq!(extract_bearer_token("bearer  abc "), Some("abc"));
+        assert_eq!(extract_bearer_token("Basic abc"), None);
+        assert_eq!(extract_bearer_token("Bearer "), None);
+        assert_eq!(extract_bearer_token("Bearer"), None);
+    }
+
+    #[test]
+    fn authenticating_requests() {
+        let authenticator = Authenticator::<Value>::new(Hs256, key())
+            .with_policy(|claims| claims.custom["scope"] == "read");
+        let token = token(json!({ "scope": "read" }), None);
+        let header = format!("Bearer {token}");
+        authenticator.authenticate(Some(&header)).unwrap();
+
+        let err = authenticator.authenticate(None).unwrap_err();
+        assert_matches!(err, AuthError::MissingToken);
+        assert_eq!(err.challenge(), "Bearer");
+        let err = authenticator.authenticate(Some("Bearer !")).unwrap_err();
+        assert_matches!(err, AuthError::Parse(_));
+        assert_eq!(err.status_code(), 401);
+
+        let token = super::tests::token(json!({ "scope": "write" }), None);
+        let err = authenticator.authenticate_token(&token).unwrap_err();
+        assert_matches!(err, AuthError::Forbidden);
+        assert_eq!(err.status_code(), 403);
+    }
+
+    #[test]
+    fn validation_report() {
+        let authenticator = Authenticator::<Value>::new(Hs256, key())
+            .with_policy(|claims| claims.custom["sub"] == "alice")
+            .with_named_policy("scope", |claims| claims.custom["scope"] == "read");
+        let report = authenticator
+            .validation_report(&token(json!({ "sub": "alice", "scope": "read" }), None));
+        assert!(report.is_valid());
+        assert_eq!(report.to_string(), "token is valid");
+
+        let report = authenticator.validation_report(&token(json!({ "sub": "bob" }), None));
+        assert_matches!(
+            report.failures(),
+            [ValidationFailure::Policy(first), ValidationFailure::Policy(second)]
+                if first == "#0" && second == "scope"
+        );
+
+        let report = authenticator.validation_report("!");
+        assert_matches!(report.failures(), [ValidationFailure::Parse(_)]);
+
+        let claims = Claims::new(json!({ "sub": "alice" }))
+            .set_duration(&TimeOptions::default(), -Duration::try_minutes(5).unwrap());
+        let other_key = Hs256Key::new(b"other_secret_key_donut_steel");
+        let token = Hs256.token(&Header::empty(), &claims, &other_key).unwrap();
+        let report = authenticator.validation_report(&token);
+        assert_matches!(
+            report.failures(),
+            [
+                ValidationFailure::Integrity(ValidationError::InvalidSignature),
+                ValidationFailure::Time(ValidationError::Expired),
+                ValidationFailure::Policy(name),
+            ] if name == "scope"
+        );
+        assert!(!report.is_valid());
+    }
+
+    #[test]
+    fn authenticating_with_key_set() {
+        let jwk = serde_json::to_value(JsonWebKey::from(&key())).unwrap();
+        let mut entry = KeySetEntry::new(serde_json::from_value(jwk).unwrap());
+        entry.key_id = Some("k1".to_owned());
+        let key_set = JsonWebKeySet { keys: vec![entry] };
+        let authenticator = Authenticator::<Value>::from_key_set(Hs256, &key_set);
+
+        authenticator
+            .authenticate_token(&token(json!({}), Some("k1")))
+            .unwrap();
+        let err = authenticator
+            .authenticate_token(&token(json!({}), Some("k2")))
+            .unwrap_err();
+        assert_matches!(err, AuthError::UnknownKey);
+        let err = authenticator
+            .authenticate_token(&token(json!({}), None))
+            .unwrap_err();
+        assert_matches!(err, AuthError::UnknownKey);
+    }
+}
@@ -0,0 +1,324 @@
+//! Integration with [`actix-web`](https://docs.rs/actix-web/).
+//!
+//! - [`AuthMiddleware`] authenticates all requests to the wrapped app, scope or resource,
+//!   inserting validated claims into request extensions. Error responses can be customized
+//!   via [`AuthMiddleware::with_error_handler()`].
+//! - [`Claims`] can be used as an extractor in handlers. Validated claims inserted by
+//!   [`AuthMiddleware`] are used if present; otherwise, the token is validated using
+//!   the [`Authenticator`] registered as app data (via [`App::app_data()`]).
+//! - [`AuthError`] implements [`ResponseError`], converting into a 401 / 403 response
+//!   with the `WWW-Authenticate` header.
+//!
+//! [`App::app_data()`]: actix_web::App::app_data()
+//!
+//! # Examples
+//!
+//! ```
+//! use actix_web::{web, App, HttpResponse};
+//! # use jwt_compact::alg::{Hs256, Hs256Key};
+//! use jwt_compact::{integrations::{actix_web::AuthMiddleware, Authenticator}, Claims};
+//! # use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Clone, Serialize, Deserialize)]
+//! struct CustomClaims {
+//!     #[serde(rename = "sub")]
+//!     subject: String,
+//! }
+//!
+//! async fn whoami(claims: Claims<CustomClaims>) -> String {
+//!     claims.custom.subject
+//! }
+//!
+//! let key = Hs256Key::new(b"super_secret_key_donut_steel");
+//! let authenticator = Authenticator::<CustomClaims>::new(Hs256, key);
+//! let middleware = AuthMiddleware::new(authenticator).with_error_handler(|_, err| {
+//!     HttpResponse::Unauthorized().json(serde_json::json!({ "error": err.to_string() }))
+//! });
+//! let app = App::new()
+//!     .wrap(middleware)
+//!     .route("/whoami", web::get().to(whoami));
+//! ```
+
+use actix_web::{
+    body::EitherBody,
+    dev::{forward_ready, Payload, Service, ServiceRequest, ServiceResponse, Transform},
+    http::{
+        header::{HeaderMap, AUTHORIZATION, WWW_AUTHENTICATE},
+        StatusCode,
+    },
+    Error, FromRequest, HttpMessage, HttpRequest, HttpResponse, ResponseError,
+};
+use futures_util::{
+    future::{self, Either, MapOk, Ready},
+    TryFutureExt,
+};
+use serde::de::DeserializeOwned;
+
+use core::fmt;
+use std::sync::Arc;
+
+use super::{AuthError, Authenticator};
+use crate::Claims;
+
+type ErrorHandler = dyn Fn(&HttpRequest, AuthError) -> HttpResponse + Send + Sync;
+type IntoLeftBody<B> = fn(ServiceResponse<B>) -> ServiceResponse<EitherBody<B>>;
+
+/// Gets the value of the `Authorization` header.
+fn authorization(headers: &HeaderMap) -> Result<Option<&str>, AuthError> {
+    headers
+        .get(AUTHORIZATION)
+        .map(|value| value.to_str().map_err(|_| AuthError::MalformedHeader))
+        .transpose()
+}
+
+impl ResponseError for AuthError {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::from_u16(AuthError::status_code(self)).unwrap_or(StatusCode::UNAUTHORIZED)
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(ResponseError::status_code(self))
+            .insert_header((WWW_AUTHENTICATE, self.challenge()))
+            .body(self.to_string())
+    }
+}
+
+/// Extracts claims inserted by [`AuthMiddleware`], or validates the bearer token
+/// with the [`Authenticator`] registered as app data. If neither is available,
+/// extraction fails with [`AuthError::MissingToken`].
+impl<T> FromRequest for Claims<T>
+where
+    T: Clone + DeserializeOwned + 'static,
+{
+    type Error = AuthError;
+    type Future = Ready<Result<Self, AuthError>>;
+
+    fn from_request(request: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        if let Some(claims) = request.extensions().get::<Self>() {
+            return future::ok(claims.clone());
+        }
+        let result = request
+            .app_data::<Authenticator<T>>()
+            .ok_or(AuthError::MissingToken)
+            .and_then(|authenticator| {
+                authenticator.authenticate(authorization(request.headers())?)
+            });
+        future::ready(result.map(|token| token.into_parts().1))
+    }
+}
+
+/// Middleware authenticating requests with an [`Authenticator`]. Requests that fail
+/// authentication are rejected with an [`AuthError`] response (or a response produced
+/// by the [custom error handler](Self::with_error_handler())); for authenticated requests,
+/// [`Claims`] are inserted into request extensions.
+pub struct AuthMiddleware<T> {
+    authenticator: Authenticator<T>,
+    error_handler: Option<Arc<ErrorHandler>>,
+}
+
+impl<T> fmt::Debug for AuthMiddleware<T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("AuthMiddleware")
+            .field("authenticator", &self.authenticator)
+            .field("has_error_handler", &self.error_handler.is_some())
+            .finish()
+    }
+}
+
+impl<T> Clone for AuthMiddleware<T> {
+    fn clone(&self) -> Self {
+        Self {
+            authenticator: self.authenticator.clone(),
+            error_handler: self.error_handler.clone(),
+        }
+    }
+}
+
+impl<T> AuthMiddleware<T> {
+    /// Creates middleware with the specified authenticator.
+    pub fn new(authenticator: Authenticator<T>) -> Self {
+        Self {
+            authenticator,
+            error_handler: None,
+        }
+    }
+
+    /// Sets the handler producing responses for requests failing authentication.
+    /// By default, the [`ResponseError`] implementation for [`AuthError`] is used.
+    #[must_use]
+    pub fn with_error_handler<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(&HttpRequest, AuthError) -> HttpResponse + Send + Sync + 'static,
+    {
+        self.error_handler = Some(Arc::new(handler));
+        self
+    }
+}
+
+impl<S, B, T> Transform<S, ServiceRequest> for AuthMiddleware<T>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    T: Clone + 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = AuthMiddlewareService<S, T>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, ()>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        future::ok(AuthMiddlewareService {
+            service,
+            authenticator: self.authenticator.clone(),
+            error_handler: self.error_handler.clone(),
+        })
+    }
+}
+
+/// Service produced by [`AuthMiddleware`].
+pub struct AuthMiddlewareService<S, T> {
+    service: S,
+    authenticator: Authenticator<T>,
+    error_handler: Option<Arc<ErrorHandler>>,
+}
+
+impl<S: fmt::Debug, T> fmt::Debug for AuthMiddlewareService<S, T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("AuthMiddlewareService")
+            .field("service", &self.service)
+            .field("authenticator", &self.authenticator)
+            .field("has_error_handler", &self.error_handler.is_some())
+            .finish()
+    }
+}
+
+impl<S, B, T> Service<ServiceRequest> for AuthMiddlewareService<S, T>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    T: Clone + 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = Either<Ready<Result<Self::Response, Error>>, MapOk<S::Future, IntoLeftBody<B>>>;
+
+    forward_ready!(service);
+
+    fn call(&self, request: ServiceRequest) -> Self::Future {
+        let token = authorization(request.headers())
+            .and_then(|authorization| self.authenticator.authenticate(authorization));
+        match token {
+            Ok(token) => {
+                request.extensions_mut().insert(token.into_parts().1);
+                let into_left_body: IntoLeftBody<B> = ServiceResponse::map_into_left_body;
+                Either::Right(self.service.call(request).map_ok(into_left_body))
+            }
+            Err(err) => {
+                let response = match &self.error_handler {
+                    Some(handler) => handler(request.request(), err),
+                    None => err.error_response(),
+                };
+                let response = request.into_response(response).map_into_right_body();
+                Either::Left(future::ok(response))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{rt::System, test, web, App};
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::{
+        alg::Hs256,
+        test_helpers::{key, sign},
+        Header,
+    };
+
+    fn authenticator() -> Authenticator<Value> {
+        Authenticator::<Value>::new(Hs256, key())
+            .with_policy(|claims| claims.custom["admin"] == true)
+    }
+
+    fn test_request(claims: Option<Value>) -> test::TestRequest {
+        let mut request = test::TestRequest::get().uri("/");
+        if let Some(claims) = claims {
+            let token = sign(&claims, &Header::empty());
+            request = request.insert_header((AUTHORIZATION, format!("Bearer {token}")));
+        }
+        request
+    }
+
+    async fn handler(claims: Claims<Value>) -> String {
+        claims.custom["sub"].as_str().unwrap().to_owned()
+    }
+
+    #[test]
+    fn extractor_with_app_data() {
+        System::new().block_on(async {
+            let app = App::new()
+                .app_data(authenticator())
+                .route("/", web::get().to(handler));
+            let app = test::init_service(app).await;
+
+            let claims = json!({ "sub": "alice", "admin": true });
+            let request = test_request(Some(claims)).to_request();
+            let response = test::call_service(&app, request).await;
+            assert_eq!(response.status(), StatusCode::OK);
+
+            let response = test::call_service(&app, test_request(None).to_request()).await;
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+            assert_eq!(response.headers().get(WWW_AUTHENTICATE).unwrap(), "Bearer");
+
+            let claims = json!({ "sub": "bob", "admin": false });
+            let request = test_request(Some(claims)).to_request();
+            let response = test::call_service(&app, request).await;
+            assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        });
+    }
+
+    #[test]
+    fn middleware() {
+        System::new().block_on(async {
+            let middleware = AuthMiddleware::new(authenticator());
+            let app = App::new()
+                .wrap(middleware)
+                .route("/", web::get().to(handler));
+            let app = test::init_service(app).await;
+
+            let claims = json!({ "sub": "alice", "admin": true });
+            let request = test_request(Some(claims)).to_request();
+            let body = test::call_and_read_body(&app, request).await;
+            assert_eq!(body, "alice");
+
+            let claims = json!({ "sub": "bob", "admin": false });
+            let request = test_request(Some(claims)).to_request();
+            let response = test::call_service(&app, request).await;
+            assert_eq!(response.status(), StatusCode::FORBIDDEN);
+            assert_eq!(
+                response.headers().get(WWW_AUTHENTICATE).unwrap(),
+                r#"Bearer error="insufficient_scope""#
+            );
+        });
+    }
+
+    #[test]
+    fn middleware_with_custom_error_handler() {
+        System::new().block_on(async {
+            let middleware = AuthMiddleware::new(authenticator())
+                .with_error_handler(|_, err| HttpResponse::ImATeapot().body(err.to_string()));
+            let app = App::new()
+                .wrap(middleware)
+                .route("/", web::get().to(handler));
+            let app = test::init_service(app).await;
+
+            let response = test::call_service(&app, test_request(None).to_request()).await;
+            assert_eq!(response.status(), StatusCode::IM_A_TEAPOT);
+            let body = test::read_body(response).await;
+            assert_eq!(body, "bearer token is missing");
+        });
+    }
+}
@@ -0,0 +1,466 @@
+//! Helpers for [AWS API Gateway Lambda authorizers].
+//!
+//! [`LambdaAuthorizer`] authenticates authorizer events with an [`Authenticator`] and produces
+//! the responses API Gateway expects from the authorizer:
+//!
+//! - [`IamPolicyResponse`] for `TOKEN` authorizers of REST APIs
+//!   (and HTTP API authorizers using IAM policy responses)
+//! - [`SimpleResponse`] for HTTP API authorizers with simple responses enabled.
+//!
+//! Selected claims are propagated to the integration via the authorizer context.
+//!
+//! Unlike other integrations, this module does not depend on a particular Lambda runtime;
+//! the event and response types are plain `serde` types that can be used with any runtime.
+//! Per API Gateway conventions, the Lambda should fail with the [`UNAUTHORIZED`] message
+//! to make API Gateway respond with 401 Unauthorized.
+//!
+//! [AWS API Gateway Lambda authorizers]: https://docs.aws.amazon.com/apigateway/latest/developerguide/apigateway-use-lambda-authorizer.html
+//!
+//! # Examples
+//!
+//! ```
+//! # use chrono::Duration;
+//! # use jwt_compact::{alg::{Hs256, Hs256Key}, prelude::*};
+//! use jwt_compact::integrations::{
+//!     aws_lambda::{LambdaAuthorizer, TokenAuthorizerEvent, UNAUTHORIZED},
+//!     AuthError, Authenticator,
+//! };
+//! # use serde_json::{json, Value};
+//!
+//! let key = Hs256Key::new(b"super_secret_key_donut_steel");
+//! # let claims = Claims::new(json!({ "sub": "alice", "email": "alice@example.com" }))
+//! #     .set_duration_and_issuance(&TimeOptions::default(), Duration::try_minutes(5).unwrap());
+//! # let token = Hs256.token(&Header::empty(), &claims, &key)?;
+//! let authorizer = LambdaAuthorizer::new(Authenticator::<Value>::new(Hs256, key))
+//!     .with_context_claim("email", "userEmail")
+//!     .with_wildcard_resource();
+//!
+//! // Lambda handler body:
+//! let event: TokenAuthorizerEvent = serde_json::from_value(json!({
+//!     "type": "TOKEN",
+//!     "authorizationToken": format!("Bearer {token}"),
+//!     "methodArn": "arn:aws:execute-api:us-east-1:123456789012:abc/prod/GET/",
+//! }))?;
+//! let response = match authorizer.authorize_token(&event) {
+//!     Ok(response) => response,
+//!     Err(AuthError::Forbidden) => authorizer.deny(&event.method_arn),
+//!     Err(_) => return Err(UNAUTHORIZED.into()),
+//! };
+//! assert_eq!(response.principal_id, "alice");
+//! assert_eq!(response.context["userEmail"], "alice@example.com");
+//! // Return `response` serialized to JSON from the Lambda.
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use std::collections::HashMap;
+
+use super::{AuthError, Authenticator};
+use crate::Claims;
+
+/// Error message that the Lambda should fail with to make API Gateway respond
+/// with 401 Unauthorized.
+pub const UNAUTHORIZED: &str = "Unauthorized";
+/// Version of IAM policy documents.
+const POLICY_VERSION: &str = "2012-10-17";
+/// Action allowing to invoke API Gateway methods.
+const INVOKE_ACTION: &str = "execute-api:Invoke";
+
+/// Event received by a `TOKEN` authorizer of a REST API.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct TokenAuthorizerEvent {
+    /// Value of the identity source header (usually, `Authorization`).
+    pub authorization_token: String,
+    /// ARN of the invoked method.
+    pub method_arn: String,
+}
+
+/// Event received by an HTTP API authorizer (payload format version 2.0).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct HttpApiAuthorizerEvent {
+    /// ARN of the invoked route.
+    pub route_arn: String,
+    /// Values of identity sources.
+    #[serde(default)]
+    pub identity_source: Vec<String>,
+    /// Request headers (with lowercase names).
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+}
+
+impl HttpApiAuthorizerEvent {
+    /// Returns the value of the `Authorization` header, falling back to the first
+    /// identity source.
+    fn authorization(&self) -> Option<&str> {
+        self.headers
+            .get("authorization")
+            .or_else(|| self.identity_source.first())
+            .map(String::as_str)
+    }
+}
+
+/// Effect of an IAM policy statement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum Effect {
+    /// Allows the action.
+    Allow,
+    /// Denies the action.
+    Deny,
+}
+
+/// Statement of an IAM policy.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+#[non_exhaustive]
+pub struct Statement {
+    /// Action, always `execute-api:Invoke`.
+    pub action: String,
+    /// Effect of the statement.
+    pub effect: Effect,
+    /// ARNs of resources the statement applies to.
+    pub resource: Vec<String>,
+}
+
+/// IAM policy document.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+#[non_exhaustive]
+pub struct PolicyDocument {
+    /// Policy version, always `2012-10-17`.
+    pub version: String,
+    /// Policy statements.
+    pub statement: Vec<Statement>,
+}
+
+/// Authorizer response containing an IAM policy.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct IamPolicyResponse {
+    /// Principal identifier.
+    pub principal_id: String,
+    /// Policy allowing or denying the invocation.
+    pub policy_document: PolicyDocument,
+    /// Context passed to the integration.
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    pub context: Map<String, Value>,
+}
+
+impl IamPolicyResponse {
+    /// Creates a response with the specified principal, effect and resource.
+    pub fn new(
+        principal_id: impl Into<String>,
+        effect: Effect,
+        resource: impl Into<String>,
+    ) -> Self {
+        Self {
+            principal_id: principal_id.into(),
+            policy_document: PolicyDocument {
+                version: POLICY_VERSION.to_owned(),
+                statement: vec![Statement {
+                    action: INVOKE_ACTION.to_owned(),
+                    effect,
+                    resource: vec![resource.into()],
+                }],
+            },
+            context: Map::new(),
+        }
+    }
+
+    /// Returns the effect of the first policy statement.
+    pub fn effect(&self) -> Option<Effect> {
+        self.policy_document
+            .statement
+            .first()
+            .map(|statement| statement.effect)
+    }
+}
+
+/// Simple authorizer response for HTTP APIs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct SimpleResponse {
+    /// Is the request authorized?
+    pub is_authorized: bool,
+    /// Context passed to the integration.
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    pub context: Map<String, Value>,
+}
+
+/// Replaces the method and path in a method / route ARN with wildcards, so that the policy
+/// applies to all methods of the API stage. This is useful if authorizer responses are cached.
+fn wildcard_resource(arn: &str) -> String {
+    // ARN format: `arn:aws:execute-api:{region}:{account}:{api_id}/{stage}/{method}/{path}`
+    let mut parts = arn.splitn(3, '/');
+    match (parts.next(), parts.next()) {
+        (Some(api), Some(stage)) => format!("{api}/{stage}/*/*"),
+        _ => arn.to_owned(),
+    }
+}
+
+/// Authorizer authenticating API Gateway authorizer events. See the [module docs](self)
+/// for details.
+#[derive(Debug)]
+pub struct LambdaAuthorizer<T> {
+    authenticator: Authenticator<T>,
+    principal_claim: String,
+    context_claims: Vec<(String, String)>,
+    wildcard_resource: bool,
+}
+
+impl<T> Clone for LambdaAuthorizer<T> {
+    fn clone(&self) -> Self {
+        Self {
+            authenticator: self.authenticator.clone(),
+            principal_claim: self.principal_claim.clone(),
+            context_claims: self.context_claims.clone(),
+            wildcard_resource: self.wildcard_resource,
+        }
+    }
+}
+
+impl<T: Serialize> LambdaAuthorizer<T> {
+    /// Creates an authorizer with the specified authenticator. By default, the principal ID
+    /// is taken from the `sub` claim, and no claims are propagated to the context.
+    pub fn new(authenticator: Authenticator<T>) -> Self {
+        Self {
+            authenticator,
+            principal_claim: "sub".to_owned(),
+            context_claims: Vec::new(),
+            wildcard_resource: false,
+        }
+    }
+
+    /// Sets the claim used as the principal ID.
+    #[must_use]
+    pub fn with_principal_claim(mut self, claim: impl Into<String>) -> Self {
+        self.principal_claim = claim.into();
+        self
+    }
+
+    /// Propagates the specified claim to the authorizer context under the specified key.
+    /// Since API Gateway only supports strings, numbers and booleans as context values,
+    /// other values (e.g., arrays) are serialized to JSON strings. Missing claims are skipped.
+    #[must_use]
+    pub fn with_context_claim(mut self, claim: impl Into<String>, key: impl Into<String>) -> Self {
+        self.context_claims.push((claim.into(), key.into()));
+        self
+    }
+
+    /// Makes IAM policies apply to all methods and paths of the API stage rather than only
+    /// to the invoked method. This should be enabled if authorizer responses are cached,
+    /// since a cached policy is applied to all methods.
+    #[must_use]
+    pub fn with_wildcard_resource(mut self) -> Self {
+        self.wildcard_resource = true;
+        self
+    }
+
+    fn resource(&self, arn: &str) -> String {
+        if self.wildcard_resource {
+            wildcard_resource(arn)
+        } else {
+            arn.to_owned()
+        }
+    }
+
+    fn claims_object(claims: &Claims<T>) -> Map<String, Value> {
+        match serde_json::to_value(claims) {
+            Ok(Value::Object(map)) => map,
+            _ => Map::new(),
+        }
+    }
+
+    fn context(&self, claims: &Map<String, Value>) -> Map<String, Value> {
+        let entries = self.context_claims.iter().filter_map(|(claim, key)| {
+            let value = match claims.get(claim)? {
+                Value::Null => return None,
+                value @ (Value::Bool(_) | Value::Number(_) | Value::String(_)) => value.clone(),
+                value => Value::String(value.to_string()),
+            };
+            Some((key.clone(), value))
+        });
+        entries.collect()
+    }
+
+    fn authenticate(&self, authorization: Option<&str>) -> Result<Map<String, Value>, AuthError> {
+        let token = self.authenticator.authenticate(authorization)?;
+        Ok(Self::claims_object(token.claims()))
+    }
+
+    /// Authorizes a `TOKEN` authorizer event. Returns an allowing IAM policy if the token
+    /// is valid.
+    pub fn authorize_token(
+        &self,
+        event: &TokenAuthorizerEvent,
+    ) -> Result<IamPolicyResponse, AuthError> {
+        let claims = self.authenticate(Some(&event.authorization_token))?;
+        let principal_id = match claims.get(&self.principal_claim) {
+            Some(Value::String(principal_id)) => principal_id.clone(),
+            Some(value) => value.to_string(),
+            None => String::new(),
+        };
+        let resource = self.resource(&event.method_arn);
+        let mut response = IamPolicyResponse::new(principal_id, Effect::Allow, resource);
+        response.context = self.context(&claims);
+        Ok(response)
+    }
+
+    /// Creates an IAM policy denying access to the specified method / route. This should be
+    /// returned if authentication fails with [`AuthError::Forbidden`] (API Gateway will respond
+    /// with 403 Forbidden).
+    pub fn deny(&self, arn: &str) -> IamPolicyResponse {
+        IamPolicyResponse::new("", Effect::Deny, self.resource(arn))
+    }
+
+    /// Authorizes an HTTP API authorizer event producing a simple response. Authentication errors
+    /// other than [`AuthError::Forbidden`] are returned as errors, so that the Lambda can fail
+    /// with [`UNAUTHORIZED`].
+    pub fn authorize_http_api(
+        &self,
+        event: &HttpApiAuthorizerEvent,
+    ) -> Result<SimpleResponse, AuthError> {
+        match self.authenticate(event.authorization()) {
+            Ok(claims) => Ok(SimpleResponse {
+                is_authorized: true,
+                context: self.context(&claims),
+            }),
+            Err(AuthError::Forbidden) => Ok(SimpleResponse {
+                is_authorized: false,
+                context: Map::new(),
+            }),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        alg::Hs256,
+        test_helpers::{key, sign},
+        Header,
+    };
+
+    const METHOD_ARN: &str =
+        "arn:aws:execute-api:us-east-1:123456789012:abcdef123/prod/GET/orders/1";
+
+    fn authorizer() -> LambdaAuthorizer<Value> {
+        let authenticator = Authenticator::<Value>::new(Hs256, key())
+            .with_policy(|claims| claims.custom["admin"] == true);
+        LambdaAuthorizer::new(authenticator)
+            .with_context_claim("email", "userEmail")
+            .with_context_claim("groups", "groups")
+            .with_context_claim("admin", "admin")
+            .with_context_claim("missing", "missing")
+    }
+
+    fn token_event(claims: Value) -> TokenAuthorizerEvent {
+        let event = json!({
+            "type": "TOKEN",
+            "authorizationToken": format!("Bearer {}", sign(&claims, &Header::empty())),
+            "methodArn": METHOD_ARN,
+        });
+        serde_json::from_value(event).unwrap()
+    }
+
+    #[test]
+    fn authorizing_token_event() {
+        let claims = json!({
+            "sub": "alice",
+            "email": "alice@example.com",
+            "groups": ["admins", "users"],
+            "admin": true,
+        });
+        let response = authorizer().authorize_token(&token_event(claims)).unwrap();
+        assert_eq!(
+            serde_json::to_value(response).unwrap(),
+            json!({
+                "principalId": "alice",
+                "policyDocument": {
+                    "Version": "2012-10-17",
+                    "Statement": [{
+                        "Action": "execute-api:Invoke",
+                        "Effect": "Allow",
+                        "Resource": [METHOD_ARN],
+                    }],
+                },
+                "context": {
+                    "userEmail": "alice@example.com",
+                    "groups": r#"["admins","users"]"#,
+                    "admin": true,
+                },
+            })
+        );
+
+        let authorizer = authorizer().with_wildcard_resource();
+        let event = token_event(json!({ "sub": "bob", "admin": false }));
+        let err = authorizer.authorize_token(&event).unwrap_err();
+        assert_matches!(err, AuthError::Forbidden);
+        let response = authorizer.deny(&event.method_arn);
+        assert_eq!(response.effect(), Some(Effect::Deny));
+        assert_eq!(
+            response.policy_document.statement[0].resource,
+            ["arn:aws:execute-api:us-east-1:123456789012:abcdef123/prod/*/*"]
+        );
+
+        let mut event = event;
+        event.authorization_token = "Bearer !".to_owned();
+        let err = authorizer.authorize_token(&event).unwrap_err();
+        assert_matches!(err, AuthError::Parse(_));
+    }
+
+    #[test]
+    fn authorizing_http_api_event() {
+        let alice_claims = json!({ "sub": "alice", "email": "alice@example.com", "admin": true });
+        let alice_token = sign(&alice_claims, &Header::empty());
+        let event = json!({
+            "version": "2.0",
+            "type": "REQUEST",
+            "routeArn": METHOD_ARN,
+            "identitySource": [format!("Bearer {alice_token}")],
+            "routeKey": "GET /orders/{id}",
+            "headers": { "accept": "*/*" },
+        });
+        let event: HttpApiAuthorizerEvent = serde_json::from_value(event).unwrap();
+        let response = authorizer().authorize_http_api(&event).unwrap();
+        assert_eq!(
+            serde_json::to_value(response).unwrap(),
+            json!({
+                "isAuthorized": true,
+                "context": { "userEmail": "alice@example.com", "admin": true },
+            })
+        );
+
+        let bob_token = sign(&json!({ "sub": "bob", "admin": false }), &Header::empty());
+        let mut event = event;
+        event.identity_source = vec![format!("Bearer {bob_token}")];
+        let response = authorizer().authorize_http_api(&event).unwrap();
+        assert!(!response.is_authorized);
+
+        event.identity_source.clear();
+        let err = authorizer().authorize_http_api(&event).unwrap_err();
+        assert_matches!(err, AuthError::MissingToken);
+    }
+
+    #[test]
+    fn wildcard_resources() {
+        assert_eq!(
+            wildcard_resource("arn:aws:execute-api:us-east-1:123:api/stage/POST/"),
+            "arn:aws:execute-api:us-east-1:123:api/stage/*/*"
+        );
+        assert_eq!(wildcard_resource("not-an-arn"), "not-an-arn");
+    }
+}
@@ -0,0 +1,254 @@
+//! Integration with [`axum`](https://docs.rs/axum/).
+//!
+//! - [`Claims`] can be used as an extractor in handlers. Validated claims inserted by
+//!   [`AuthLayer`] are used if present; otherwise, the token is validated using
+//!   the [`Authenticator`] obtained from the router state via [`FromRef`].
+//! - [`AuthLayer`] authenticates all requests to the wrapped router or route, inserting
+//!   validated claims into request extensions.
+//! - [`AuthError`] converts into a 401 / 403 response with the `WWW-Authenticate` header.
+//!
+//! # Examples
+//!
+//! ```
+//! # use chrono::Duration;
+//! use axum::{extract::FromRef, routing::get, Router};
+//! # use jwt_compact::alg::{Hs256, Hs256Key};
+//! use jwt_compact::{integrations::{axum::AuthLayer, Authenticator}, Claims};
+//! # use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Clone, Serialize, Deserialize)]
+//! struct CustomClaims {
+//!     #[serde(rename = "sub")]
+//!     subject: String,
+//! }
+//!
+//! #[derive(Clone)]
+//! struct AppState {
+//!     authenticator: Authenticator<CustomClaims>,
+//! }
+//!
+//! impl FromRef<AppState> for Authenticator<CustomClaims> {
+//!     fn from_ref(state: &AppState) -> Self {
+//!         state.authenticator.clone()
+//!     }
+//! }
+//!
+//! async fn whoami(claims: Claims<CustomClaims>) -> String {
+//!     claims.custom.subject
+//! }
+//!
+//! let key = Hs256Key::new(b"super_secret_key_donut_steel");
+//! let authenticator = Authenticator::<CustomClaims>::new(Hs256, key)
+//!     .with_leeway(Duration::try_seconds(30).unwrap());
+//! // Validate tokens in the handler extractor...
+//! let router: Router = Router::new()
+//!     .route("/whoami", get(whoami))
+//!     .with_state(AppState { authenticator: authenticator.clone() });
+//! // ...or for all routes using a layer.
+//! let router: Router = Router::new()
+//!     .route("/whoami", get(whoami))
+//!     .layer(AuthLayer::new(authenticator.clone()))
+//!     .with_state(AppState { authenticator });
+//! ```
+
+use axum::{
+    async_trait,
+    extract::{FromRef, FromRequestParts},
+    http::{
+        header::{AUTHORIZATION, WWW_AUTHENTICATE},
+        request::Parts,
+        HeaderMap, HeaderValue, Request, StatusCode,
+    },
+    response::{IntoResponse, Response},
+};
+use futures_util::future::{self, Either, Ready};
+use serde::de::DeserializeOwned;
+use tower_layer::Layer;
+use tower_service::Service;
+
+use core::task::{Context, Poll};
+
+use super::{AuthError, Authenticator};
+use crate::Claims;
+
+/// Gets the value of the `Authorization` header.
+fn authorization(headers: &HeaderMap) -> Result<Option<&str>, AuthError> {
+    headers
+        .get(AUTHORIZATION)
+        .map(|value| value.to_str().map_err(|_| AuthError::MalformedHeader))
+        .transpose()
+}
+
+impl IntoResponse for AuthError {
+    fn into_response(self) -> Response {
+        let status = StatusCode::from_u16(self.status_code()).unwrap_or(StatusCode::UNAUTHORIZED);
+        let challenge = HeaderValue::from_static(self.challenge());
+        (status, [(WWW_AUTHENTICATE, challenge)], self.to_string()).into_response()
+    }
+}
+
+#[async_trait]
+impl<S, T> FromRequestParts<S> for Claims<T>
+where
+    S: Send + Sync,
+    Authenticator<T>: FromRef<S>,
+    T: Clone + DeserializeOwned + Send + Sync + 'static,
+{
+    type Rejection = AuthError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        if let Some(claims) = parts.extensions.get::<Self>() {
+            return Ok(claims.clone());
+        }
+        let authenticator = Authenticator::<T>::from_ref(state);
+        let token = authenticator.authenticate(authorization(&parts.headers)?)?;
+        Ok(token.into_parts().1)
+    }
+}
+
+/// Layer authenticating requests with an [`Authenticator`]. Requests that fail authentication
+/// are rejected with an [`AuthError`] response; for authenticated requests, [`Claims`]
+/// are inserted into request extensions.
+#[derive(Debug)]
+pub struct AuthLayer<T> {
+    authenticator: Authenticator<T>,
+}
+
+impl<T> Clone for AuthLayer<T> {
+    fn clone(&self) -> Self {
+        Self {
+            authenticator: self.authenticator.clone(),
+        }
+    }
+}
+
+impl<T> AuthLayer<T> {
+    /// Creates a layer with the specified authenticator.
+    pub fn new(authenticator: Authenticator<T>) -> Self {
+        Self { authenticator }
+    }
+}
+
+impl<S, T> Layer<S> for AuthLayer<T> {
+    type Service = AuthService<S, T>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        AuthService {
+            inner,
+            authenticator: self.authenticator.clone(),
+        }
+    }
+}
+
+/// Service produced by [`AuthLayer`].
+#[derive(Debug)]
+pub struct AuthService<S, T> {
+    inner: S,
+    authenticator: Authenticator<T>,
+}
+
+impl<S: Clone, T> Clone for AuthService<S, T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            authenticator: self.authenticator.clone(),
+        }
+    }
+}
+
+impl<S, T, B> Service<Request<B>> for AuthService<S, T>
+where
+    S: Service<Request<B>, Response = Response>,
+    T: Clone + Send + Sync + 'static,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = Either<Ready<Result<Response, S::Error>>, S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request<B>) -> Self::Future {
+        let token = authorization(request.headers())
+            .and_then(|authorization| self.authenticator.authenticate(authorization));
+        match token {
+            Ok(token) => {
+                request.extensions_mut().insert(token.into_parts().1);
+                Either::Right(self.inner.call(request))
+            }
+            Err(err) => Either::Left(future::ready(Ok(err.into_response()))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{body::Body, routing::get, Router};
+    use serde_json::{json, Value};
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::{
+        alg::Hs256,
+        test_helpers::{key, sign},
+        Header,
+    };
+
+    fn authenticator() -> Authenticator<Value> {
+        Authenticator::<Value>::new(Hs256, key())
+            .with_policy(|claims| claims.custom["admin"] == true)
+    }
+
+    fn request(claims: Option<Value>) -> Request<Body> {
+        let mut request = Request::builder().uri("/");
+        if let Some(claims) = claims {
+            let token = sign(&claims, &Header::empty());
+            request = request.header(AUTHORIZATION, format!("Bearer {token}"));
+        }
+        request.body(Body::empty()).unwrap()
+    }
+
+    async fn handler(claims: Claims<Value>) -> String {
+        claims.custom["sub"].as_str().unwrap().to_owned()
+    }
+
+    async fn check_router(router: Router) {
+        let response = router
+            .clone()
+            .oneshot(request(Some(json!({ "sub": "alice", "admin": true }))))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = router.clone().oneshot(request(None)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(response.headers()[WWW_AUTHENTICATE], "Bearer");
+
+        let claims = json!({ "sub": "bob", "admin": false });
+        let response = router.oneshot(request(Some(claims))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert_eq!(
+            response.headers()[WWW_AUTHENTICATE],
+            r#"Bearer error="insufficient_scope""#
+        );
+    }
+
+    #[tokio::test]
+    async fn extractor_with_state() {
+        let router = Router::new()
+            .route("/", get(handler))
+            .with_state(authenticator());
+        check_router(router).await;
+    }
+
+    #[tokio::test]
+    async fn auth_layer() {
+        let router = Router::new()
+            .route("/", get(handler))
+            .layer(AuthLayer::new(authenticator()))
+            .with_state(authenticator().with_policy(|_| false));
+        // The state authenticator must not be used since claims are inserted by the layer.
+        check_router(router).await;
+    }
+}
@@ -0,0 +1,607 @@
+//! Client-side integration with [`reqwest`](https://docs.rs/reqwest/) via
+//! [`reqwest-middleware`](https://docs.rs/reqwest-middleware/).
+//!
+//! [`BearerTokenMiddleware`] attaches a bearer token to outgoing requests. Tokens are obtained
+//! from a [`TokenSource`] and cached until they are close to expiration (or until the server
+//! responds with 401 Unauthorized). The following sources are provided:
+//!
+//! - [`SignedTokenSource`] signs tokens locally, e.g., self-signed JWT assertions used
+//!   as bearer tokens in service-to-service calls.
+//! - [`TokenEndpointSource`] exchanges a signed JWT assertion for an access token at the token
+//!   endpoint of an authorization server, using the JWT bearer grant from [RFC 7523].
+//!
+//! [RFC 7523]: https://www.rfc-editor.org/rfc/rfc7523.html#section-2.1
+//!
+//! # Examples
+//!
+//! ```
+//! # use jwt_compact::alg::{Hs256, Hs256Key};
+//! use jwt_compact::integrations::reqwest::{BearerTokenMiddleware, TokenEndpointSource};
+//! use reqwest_middleware::ClientBuilder;
+//!
+//! let key = Hs256Key::new(b"super_secret_key_donut_steel");
+//! let source = TokenEndpointSource::new(
+//!     reqwest::Client::new(),
+//!     Hs256,
+//!     key,
+//!     "my-service",
+//!     "https://auth.example.com/token",
+//! )
+//! .with_scope("orders:read");
+//! let client = ClientBuilder::new(reqwest::Client::new())
+//!     .with(BearerTokenMiddleware::new(source))
+//!     .build();
+//! // `client` will now authenticate all requests.
+//! ```
+
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use http::{header::AUTHORIZATION, Extensions, HeaderValue, StatusCode};
+use rand_core::OsRng;
+use reqwest::{Client, Request, Response};
+use reqwest_middleware::{Middleware, Next};
+use serde::{Deserialize, Serialize};
+
+use core::{fmt, future::Future, pin::Pin};
+use std::sync::{Mutex, PoisonError};
+
+use crate::{
+    oauth::ClientAssertion, Algorithm, AlgorithmExt, Claims, CreationError, Header, Timestamp,
+};
+
+/// Value of the `grant_type` parameter for the JWT bearer grant as per [RFC 7523].
+///
+/// [RFC 7523]: https://www.rfc-editor.org/rfc/rfc7523.html#section-2.1
+pub const JWT_BEARER_GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:jwt-bearer";
+
+/// Default lifetime of tokens produced by [`SignedTokenSource`].
+const DEFAULT_LIFETIME: Duration = Duration::minutes(5);
+/// Default margin before token expiration after which the token is refreshed.
+const DEFAULT_REFRESH_MARGIN: Duration = Duration::seconds(30);
+
+/// Boxed future returned by [`TokenSource`]s.
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Errors that can occur when issuing tokens.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum TokenSourceError {
+    /// Error signing a token or an assertion.
+    Creation(CreationError),
+    /// Error sending a request to the token endpoint.
+    Http(reqwest::Error),
+    /// Token endpoint has responded with an error.
+    Endpoint {
+        /// HTTP status code of the response.
+        status: u16,
+        /// Response body.
+        body: String,
+    },
+    /// Token endpoint response cannot be parsed.
+    Response(serde_json::Error),
+}
+
+impl fmt::Display for TokenSourceError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Creation(err) => write!(formatter, "cannot create token: {err}"),
+            Self::Http(err) => write!(formatter, "cannot send request to token endpoint: {err}"),
+            Self::Endpoint { status, body } => {
+                write!(formatter, "token endpoint responded with {status}: {body}")
+            }
+            Self::Response(err) => write!(formatter, "cannot parse token endpoint response: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for TokenSourceError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Creation(err) => Some(err),
+            Self::Http(err) => Some(err),
+            Self::Response(err) => Some(err),
+            Self::Endpoint { .. } => None,
+        }
+    }
+}
+
+impl From<CreationError> for TokenSourceError {
+    fn from(err: CreationError) -> Self {
+        Self::Creation(err)
+    }
+}
+
+/// Token issued by a [`TokenSource`].
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct IssuedToken {
+    /// Token value.
+    pub token: String,
+    /// Token expiration, if known. Tokens without expiration are cached until the server
+    /// responds with 401 Unauthorized.
+    pub expiration: Option<Timestamp>,
+}
+
+impl IssuedToken {
+    /// Creates a token with the specified value and expiration.
+    pub fn new(token: impl Into<String>, expiration: Option<Timestamp>) -> Self {
+        Self {
+            token: token.into(),
+            expiration,
+        }
+    }
+
+    fn is_fresh(&self, now: Timestamp, margin: Duration) -> bool {
+        self.expiration
+            .map_or(true, |expiration| now + margin < expiration)
+    }
+}
+
+/// Source of bearer tokens for [`BearerTokenMiddleware`].
+pub trait TokenSource: Send + Sync + 'static {
+    /// Issues a new token.
+    fn issue(&self) -> BoxFuture<'_, Result<IssuedToken, TokenSourceError>>;
+}
+
+/// [`TokenSource`] signing tokens with the specified custom claims. Each token
+/// gets fresh `iat` and `exp` claims; by default, tokens are valid for 5 minutes.
+pub struct SignedTokenSource<A: Algorithm, T> {
+    algorithm: A,
+    signing_key: A::SigningKey,
+    header: Header,
+    claims: T,
+    lifetime: Duration,
+}
+
+impl<A, T> fmt::Debug for SignedTokenSource<A, T>
+where
+    A: Algorithm + fmt::Debug,
+    T: fmt::Debug,
+{
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("SignedTokenSource")
+            .field("algorithm", &self.algorithm)
+            .field("header", &self.header)
+            .field("claims", &self.claims)
+            .field("lifetime", &self.lifetime)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<A: Algorithm, T> SignedTokenSource<A, T> {
+    /// Creates a source with the specified algorithm, signing key and custom claims.
+    pub fn new(algorithm: A, signing_key: A::SigningKey, claims: T) -> Self {
+        Self {
+            algorithm,
+            signing_key,
+            header: Header::empty(),
+            claims,
+            lifetime: DEFAULT_LIFETIME,
+        }
+    }
+
+    /// Sets the header of produced tokens (e.g., to specify the key ID).
+    #[must_use]
+    pub fn with_header(mut self, header: Header) -> Self {
+        self.header = header;
+        self
+    }
+
+    /// Sets the lifetime of produced tokens.
+    #[must_use]
+    pub fn with_lifetime(mut self, lifetime: Duration) -> Self {
+        self.lifetime = lifetime;
+        self
+    }
+
+    fn sign(&self) -> Result<IssuedToken, TokenSourceError>
+    where
+        T: Clone + Serialize,
+    {
+        let claims = Claims::new(self.claims.clone())
+            .set_duration_and_issuance(&crate::TimeOptions::default(), self.lifetime);
+        let token = self
+            .algorithm
+            .token(&self.header, &claims, &self.signing_key)?;
+        Ok(IssuedToken::new(token, claims.expiration))
+    }
+}
+
+impl<A, T> TokenSource for SignedTokenSource<A, T>
+where
+    A: Algorithm + Send + Sync + 'static,
+    A::SigningKey: Send + Sync,
+    T: Clone + Serialize + Send + Sync + 'static,
+{
+    fn issue(&self) -> BoxFuture<'_, Result<IssuedToken, TokenSourceError>> {
+        Box::pin(core::future::ready(self.sign()))
+    }
+}
+
+/// Successful response of the token endpoint.
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    #[serde(default)]
+    expires_in: Option<i64>,
+}
+
+/// [`TokenSource`] obtaining access tokens from the token endpoint of an authorization server
+/// using the JWT bearer grant from [RFC 7523]. Assertions are created with [`ClientAssertion`],
+/// i.e., have the `iss` and `sub` claims set to the client ID and the `aud` claim set
+/// to the token endpoint.
+///
+/// [RFC 7523]: https://www.rfc-editor.org/rfc/rfc7523.html#section-2.1
+pub struct TokenEndpointSource<A: Algorithm> {
+    client: Client,
+    token_endpoint: String,
+    assertion: ClientAssertion,
+    algorithm: A,
+    signing_key: A::SigningKey,
+    header: Header,
+    scope: Option<String>,
+}
+
+impl<A: Algorithm + fmt::Debug> fmt::Debug for TokenEndpointSource<A> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("TokenEndpointSource")
+            .field("token_endpoint", &self.token_endpoint)
+            .field("assertion", &self.assertion)
+            .field("algorithm", &self.algorithm)
+            .field("header", &self.header)
+            .field("scope", &self.scope)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<A: Algorithm> TokenEndpointSource<A> {
+    /// Creates a source with the specified HTTP client (used for token requests), assertion
+    /// signing parameters, client ID and token endpoint URL.
+    pub fn new(
+        client: Client,
+        algorithm: A,
+        signing_key: A::SigningKey,
+        client_id: impl Into<String>,
+        token_endpoint: impl Into<String>,
+    ) -> Self {
+        let token_endpoint = token_endpoint.into();
+        Self {
+            client,
+            assertion: ClientAssertion::new(client_id, token_endpoint.clone()),
+            token_endpoint,
+            algorithm,
+            signing_key,
+            header: Header::empty(),
+            scope: None,
+        }
+    }
+
+    /// Sets the header of assertions (e.g., to specify the key ID).
+    #[must_use]
+    pub fn with_header(mut self, header: Header) -> Self {
+        self.header = header;
+        self
+    }
+
+    /// Sets the lifetime of assertions.
+    #[must_use]
+    pub fn with_assertion_lifetime(mut self, lifetime: Duration) -> Self {
+        self.assertion = self.assertion.with_lifetime(lifetime);
+        self
+    }
+
+    /// Sets the scope requested for access tokens.
+    #[must_use]
+    pub fn with_scope(mut self, scope: impl Into<String>) -> Self {
+        self.scope = Some(scope.into());
+        self
+    }
+
+    async fn request_token(&self) -> Result<IssuedToken, TokenSourceError> {
+        let options = crate::TimeOptions::default();
+        let assertion = self.assertion.sign(
+            &self.algorithm,
+            &self.header,
+            &self.signing_key,
+            &options,
+            &mut OsRng,
+        )?;
+        let mut params = vec![
+            ("grant_type", JWT_BEARER_GRANT_TYPE),
+            ("assertion", &assertion),
+        ];
+        if let Some(scope) = &self.scope {
+            params.push(("scope", scope));
+        }
+
+        let response = self
+            .client
+            .post(&self.token_endpoint)
+            .form(&params)
+            .send()
+            .await
+            .map_err(TokenSourceError::Http)?;
+        let status = response.status();
+        let body = response.bytes().await.map_err(TokenSourceError::Http)?;
+        if !status.is_success() {
+            return Err(TokenSourceError::Endpoint {
+                status: status.as_u16(),
+                body: String::from_utf8_lossy(&body).into_owned(),
+            });
+        }
+
+        let response: TokenResponse =
+            serde_json::from_slice(&body).map_err(TokenSourceError::Response)?;
+        let expiration = response
+            .expires_in
+            .and_then(Duration::try_seconds)
+            .map(|expires_in| (options.clock_fn)() + expires_in);
+        Ok(IssuedToken::new(response.access_token, expiration))
+    }
+}
+
+impl<A> TokenSource for TokenEndpointSource<A>
+where
+    A: Algorithm + Send + Sync + 'static,
+    A::SigningKey: Send + Sync,
+{
+    fn issue(&self) -> BoxFuture<'_, Result<IssuedToken, TokenSourceError>> {
+        Box::pin(self.request_token())
+    }
+}
+
+/// Middleware attaching bearer tokens from a [`TokenSource`] to requests.
+///
+/// The token is cached and reused until it is within the refresh margin (30 seconds
+/// by default) of its expiration. If the server responds with 401 Unauthorized, the cached
+/// token is discarded, so that the next request uses a fresh token. Note that concurrent
+/// requests encountering an expired token may issue several new tokens.
+#[derive(Debug)]
+pub struct BearerTokenMiddleware<S> {
+    source: S,
+    cached: Mutex<Option<IssuedToken>>,
+    refresh_margin: Duration,
+}
+
+impl<S: TokenSource> BearerTokenMiddleware<S> {
+    /// Creates middleware with the specified token source.
+    pub fn new(source: S) -> Self {
+        Self {
+            source,
+            cached: Mutex::new(None),
+            refresh_margin: DEFAULT_REFRESH_MARGIN,
+        }
+    }
+
+    /// Sets the margin before token expiration after which the token is refreshed.
+    #[must_use]
+    pub fn with_refresh_margin(mut self, margin: Duration) -> Self {
+        self.refresh_margin = margin;
+        self
+    }
+
+    /// Returns a token to attach to requests, issuing a new one if necessary.
+    pub async fn token(&self) -> Result<String, TokenSourceError> {
+        let cached = self
+            .cached
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+        if let Some(cached) = cached {
+            if cached.is_fresh(Utc::now().into(), self.refresh_margin) {
+                return Ok(cached.token);
+            }
+        }
+
+        let issued = self.source.issue().await?;
+        let token = issued.token.clone();
+        *self.cached.lock().unwrap_or_else(PoisonError::into_inner) = Some(issued);
+        Ok(token)
+    }
+
+    /// Discards the cached token.
+    pub fn invalidate(&self) {
+        *self.cached.lock().unwrap_or_else(PoisonError::into_inner) = None;
+    }
+}
+
+#[async_trait]
+impl<S: TokenSource> Middleware for BearerTokenMiddleware<S> {
+    async fn handle(
+        &self,
+        mut request: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        let token = self
+            .token()
+            .await
+            .map_err(reqwest_middleware::Error::middleware)?;
+        let mut value = HeaderValue::try_from(format!("Bearer {token}"))
+            .map_err(reqwest_middleware::Error::middleware)?;
+        value.set_sensitive(true);
+        request.headers_mut().insert(AUTHORIZATION, value);
+
+        let response = next.run(request, extensions).await?;
+        if response.status() == StatusCode::UNAUTHORIZED {
+            self.invalidate();
+        }
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use reqwest_middleware::ClientBuilder;
+    use serde_json::{json, Value};
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{alg::Hs256, test_helpers::key, UntrustedToken};
+
+    /// Accepts a single HTTP request and responds to it with the specified status and body.
+    /// Returns the received request.
+    async fn serve(listener: &TcpListener, status: &str, body: &str) -> String {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut request = Vec::new();
+        let mut buffer = [0_u8; 1_024];
+        loop {
+            let len = stream.read(&mut buffer).await.unwrap();
+            request.extend_from_slice(&buffer[..len]);
+            let text = String::from_utf8_lossy(&request);
+            if let Some((head, content)) = text.split_once("\r\n\r\n") {
+                let content_len = head.lines().find_map(|line| {
+                    let (name, value) = line.split_once(':')?;
+                    let is_len = name.eq_ignore_ascii_case("content-length");
+                    is_len.then(|| value.trim().parse::<usize>().unwrap())
+                });
+                if content.len() >= content_len.unwrap_or(0) {
+                    break;
+                }
+            }
+            if len == 0 {
+                break;
+            }
+        }
+
+        let response = format!(
+            "HTTP/1.1 {status}\r\ncontent-type: application/json\r\n\
+             content-length: {}\r\nconnection: close\r\n\r\n{body}",
+            body.len()
+        );
+        stream.write_all(response.as_bytes()).await.unwrap();
+        String::from_utf8(request).unwrap()
+    }
+
+    fn authorization(request: &str) -> &str {
+        request
+            .lines()
+            .find_map(|line| line.strip_prefix("authorization: Bearer "))
+            .unwrap()
+    }
+
+    #[derive(Debug, Default)]
+    struct CountingSource {
+        count: AtomicUsize,
+    }
+
+    impl TokenSource for CountingSource {
+        fn issue(&self) -> BoxFuture<'_, Result<IssuedToken, TokenSourceError>> {
+            let count = self.count.fetch_add(1, Ordering::SeqCst);
+            let expiration = Timestamp::from(Utc::now()) + Duration::try_minutes(1).unwrap();
+            let token = IssuedToken::new(format!("token-{count}"), Some(expiration));
+            Box::pin(core::future::ready(Ok(token)))
+        }
+    }
+
+    #[tokio::test]
+    async fn attaching_signed_tokens() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let source = SignedTokenSource::new(Hs256, key(), json!({ "sub": "service" }))
+            .with_header(Header::empty().with_key_id("k1"));
+        let middleware = Arc::new(BearerTokenMiddleware::new(source));
+        let client = ClientBuilder::new(Client::new())
+            .with_arc(middleware.clone())
+            .build();
+
+        let mut tokens = vec![];
+        for _ in 0..2 {
+            let (request, response) =
+                tokio::join!(serve(&listener, "200 OK", "{}"), client.get(&url).send());
+            assert_eq!(response.unwrap().status(), StatusCode::OK);
+            tokens.push(authorization(&request).to_owned());
+        }
+        assert_eq!(tokens[0], tokens[1]);
+
+        let token = UntrustedToken::new(&tokens[0]).unwrap();
+        assert_eq!(token.header().key_id.as_deref(), Some("k1"));
+        let token = Hs256.validator::<Value>(&key()).validate(&token).unwrap();
+        assert_eq!(token.claims().custom["sub"], "service");
+        let lifetime = token.claims().expiration.unwrap() - token.claims().issued_at.unwrap();
+        assert_eq!(lifetime, DEFAULT_LIFETIME);
+    }
+
+    #[tokio::test]
+    async fn refreshing_tokens() {
+        let middleware = BearerTokenMiddleware::new(CountingSource::default());
+        assert_eq!(middleware.token().await.unwrap(), "token-0");
+        assert_eq!(middleware.token().await.unwrap(), "token-0");
+        middleware.invalidate();
+        assert_eq!(middleware.token().await.unwrap(), "token-1");
+
+        // Tokens expiring within the refresh margin are not reused.
+        let middleware = BearerTokenMiddleware::new(CountingSource::default())
+            .with_refresh_margin(Duration::try_minutes(2).unwrap());
+        assert_eq!(middleware.token().await.unwrap(), "token-0");
+        assert_eq!(middleware.token().await.unwrap(), "token-1");
+    }
+
+    #[tokio::test]
+    async fn invalidating_token_on_unauthorized_response() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let middleware = Arc::new(BearerTokenMiddleware::new(CountingSource::default()));
+        let client = ClientBuilder::new(Client::new())
+            .with_arc(middleware.clone())
+            .build();
+
+        let (request, _) = tokio::join!(
+            serve(&listener, "401 Unauthorized", "{}"),
+            client.get(&url).send()
+        );
+        assert_eq!(authorization(&request), "token-0");
+        let (request, _) = tokio::join!(serve(&listener, "200 OK", "{}"), client.get(&url).send());
+        assert_eq!(authorization(&request), "token-1");
+    }
+
+    #[tokio::test]
+    async fn obtaining_tokens_from_endpoint() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/token", listener.local_addr().unwrap());
+        let source = TokenEndpointSource::new(Client::new(), Hs256, key(), "service", &url)
+            .with_scope("read");
+
+        let response = r#"{"access_token":"abc","token_type":"Bearer","expires_in":3600}"#;
+        let (request, token) = tokio::join!(serve(&listener, "200 OK", response), source.issue());
+        let token = token.unwrap();
+        assert_eq!(token.token, "abc");
+        let expires_in = token.expiration.unwrap() - Timestamp::from(Utc::now());
+        assert!(
+            expires_in > Duration::try_minutes(59).unwrap(),
+            "{expires_in:?}"
+        );
+
+        let (_, form) = request.split_once("\r\n\r\n").unwrap();
+        let form: Vec<_> = form
+            .split('&')
+            .map(|param| param.split_once('=').unwrap())
+            .collect();
+        let grant_type = JWT_BEARER_GRANT_TYPE.replace(':', "%3A");
+        assert_eq!(form[0], ("grant_type", grant_type.as_str()));
+        assert_eq!(form[2], ("scope", "read"));
+        let assertion = UntrustedToken::new(form[1].1).unwrap();
+        let assertion = Hs256
+            .validator::<crate::oauth::ClientAssertionClaims>(&key())
+            .validate(&assertion)
+            .unwrap();
+        assert_eq!(assertion.claims().custom.audience, url);
+
+        let response = r#"{"error":"invalid_grant"}"#;
+        let (_, err) = tokio::join!(
+            serve(&listener, "400 Bad Request", response),
+            source.issue()
+        );
+        let err = err.unwrap_err();
+        assert_matches!(err, TokenSourceError::Endpoint { status: 400, body } if body == response);
+    }
+}
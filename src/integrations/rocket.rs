@@ -0,0 +1,261 @@
+//! Integration with [`rocket`](https://docs.rs/rocket/).
+//!
+//! - [`AuthenticatedClaims`] is a request guard authenticating requests by the `Authorization`
+//!   header. It uses the [`Authenticator`] managed by Rocket, which is usually registered
+//!   by [`AuthFairing`].
+//! - [`AuthFairing`] configures the authenticator, either directly or from the Rocket
+//!   configuration (e.g., `Rocket.toml` or environment variables), and adds
+//!   the `WWW-Authenticate` header to responses for requests failing authentication.
+//!
+//! # Examples
+//!
+//! ```
+//! # use jwt_compact::alg::{Hs256, Hs256Key};
+//! use jwt_compact::integrations::{
+//!     rocket::{AuthFairing, AuthenticatedClaims},
+//!     Authenticator,
+//! };
+//! use rocket::get;
+//! # use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Clone, Serialize, Deserialize)]
+//! struct CustomClaims {
+//!     #[serde(rename = "sub")]
+//!     subject: String,
+//! }
+//!
+//! #[get("/whoami")]
+//! fn whoami(claims: AuthenticatedClaims<CustomClaims>) -> String {
+//!     claims.0.custom.subject
+//! }
+//!
+//! // The key is taken from the `jwt_secret` config param.
+//! let fairing = AuthFairing::<CustomClaims>::from_config(|figment| {
+//!     let secret: String = figment.extract_inner("jwt_secret")?;
+//!     Ok(Authenticator::new(Hs256, Hs256Key::new(secret)))
+//! });
+//! let rocket = rocket::build()
+//!     .attach(fairing)
+//!     .mount("/", rocket::routes![whoami]);
+//! ```
+
+use rocket::{
+    async_trait,
+    fairing::{self, Fairing, Info, Kind},
+    figment::{self, Figment},
+    http::{Header, Status},
+    request::{FromRequest, Outcome},
+    Build, Request, Response, Rocket,
+};
+
+use core::{fmt, marker::PhantomData};
+
+use super::{AuthError, Authenticator};
+use crate::Claims;
+
+type Configure<T> = dyn Fn(&Figment) -> Result<Authenticator<T>, Box<figment::Error>> + Send + Sync;
+
+/// Challenge to put into the `WWW-Authenticate` header, cached in the request.
+struct Challenge(Option<&'static str>);
+
+/// Request guard providing claims from a validated bearer token.
+///
+/// If the token is missing or invalid, the guard fails with 401 Unauthorized; if the token
+/// does not satisfy an authorization policy, the guard fails with 403 Forbidden.
+/// If no [`Authenticator`] for claims of type `T` is managed by Rocket, the guard fails
+/// with 500 Internal Server Error.
+#[derive(Debug, Clone)]
+pub struct AuthenticatedClaims<T>(pub Claims<T>);
+
+#[async_trait]
+impl<'r, T: Send + Sync + 'static> FromRequest<'r> for AuthenticatedClaims<T> {
+    type Error = AuthError;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let Some(authenticator) = request.rocket().state::<Authenticator<T>>() else {
+            return Outcome::Error((Status::InternalServerError, AuthError::MissingToken));
+        };
+        let authorization = request.headers().get_one("Authorization");
+        match authenticator.authenticate(authorization) {
+            Ok(token) => Outcome::Success(Self(token.into_parts().1)),
+            Err(err) => {
+                request.local_cache(|| Challenge(Some(err.challenge())));
+                let status = Status::from_code(err.status_code()).unwrap_or(Status::Unauthorized);
+                Outcome::Error((status, err))
+            }
+        }
+    }
+}
+
+/// Fairing registering an [`Authenticator`] for claims of type `T` as managed state
+/// on ignition, and adding the `WWW-Authenticate` header to responses for requests
+/// that have failed [`AuthenticatedClaims`] guards.
+pub struct AuthFairing<T> {
+    configure: Box<Configure<T>>,
+    _claims: PhantomData<fn() -> T>,
+}
+
+impl<T> fmt::Debug for AuthFairing<T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("AuthFairing")
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: Send + Sync + 'static> AuthFairing<T> {
+    /// Creates a fairing with the specified authenticator.
+    pub fn new(authenticator: Authenticator<T>) -> Self {
+        Self::from_config(move |_| Ok(authenticator.clone()))
+    }
+
+    /// Creates a fairing building the authenticator from the Rocket configuration.
+    /// If the closure returns an error, ignition fails. (The error is boxed since
+    /// `figment::Error` is quite large; the `?` operator performs boxing automatically.)
+    pub fn from_config<F>(configure: F) -> Self
+    where
+        F: Fn(&Figment) -> Result<Authenticator<T>, Box<figment::Error>> + Send + Sync + 'static,
+    {
+        Self {
+            configure: Box::new(configure),
+            _claims: PhantomData,
+        }
+    }
+}
+
+#[async_trait]
+impl<T: Send + Sync + 'static> Fairing for AuthFairing<T> {
+    fn info(&self) -> Info {
+        Info {
+            name: "JWT authentication",
+            kind: Kind::Ignite | Kind::Response | Kind::Singleton,
+        }
+    }
+
+    async fn on_ignite(&self, rocket: Rocket<Build>) -> fairing::Result {
+        match (self.configure)(rocket.figment()) {
+            Ok(authenticator) => Ok(rocket.manage(authenticator)),
+            Err(err) => {
+                rocket::error!("cannot configure JWT authentication: {err}");
+                Err(rocket)
+            }
+        }
+    }
+
+    async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
+        if let Challenge(Some(challenge)) = request.local_cache(|| Challenge(None)) {
+            let status = response.status();
+            if status == Status::Unauthorized || status == Status::Forbidden {
+                response.set_header(Header::new("WWW-Authenticate", *challenge));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use chrono::Duration;
+    use rocket::{
+        config::LogLevel, error::ErrorKind, get, local::blocking::Client, routes, Config,
+    };
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::{
+        alg::{Hs256, Hs256Key},
+        AlgorithmExt, Header as JwtHeader, TimeOptions,
+    };
+
+    const SECRET: &str = "super_secret_key_donut_steel";
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct TestClaims {
+        sub: String,
+        admin: bool,
+    }
+
+    impl TestClaims {
+        fn new(sub: &str, admin: bool) -> Self {
+            Self {
+                sub: sub.to_owned(),
+                admin,
+            }
+        }
+    }
+
+    #[get("/")]
+    fn whoami(claims: AuthenticatedClaims<TestClaims>) -> String {
+        claims.0.custom.sub
+    }
+
+    fn config() -> Figment {
+        let config = Config {
+            log_level: LogLevel::Off,
+            ..Config::debug_default()
+        };
+        Figment::from(config)
+    }
+
+    fn authorization(claims: TestClaims) -> Header<'static> {
+        let claims = Claims::new(claims)
+            .set_duration_and_issuance(&TimeOptions::default(), Duration::try_minutes(5).unwrap());
+        let key = Hs256Key::new(SECRET);
+        let token = Hs256.token(&JwtHeader::empty(), &claims, &key).unwrap();
+        Header::new("Authorization", format!("Bearer {token}"))
+    }
+
+    fn fairing() -> AuthFairing<TestClaims> {
+        AuthFairing::from_config(|figment| {
+            let secret: String = figment.extract_inner("jwt_secret")?;
+            let authenticator = Authenticator::<TestClaims>::new(Hs256, Hs256Key::new(secret))
+                .with_policy(|claims| claims.custom.admin);
+            Ok(authenticator)
+        })
+    }
+
+    #[test]
+    fn authenticating_requests() {
+        let rocket = rocket::custom(config().merge(("jwt_secret", SECRET)))
+            .attach(fairing())
+            .mount("/", routes![whoami]);
+        let client = Client::tracked(rocket).unwrap();
+
+        let header = authorization(TestClaims::new("alice", true));
+        let response = client.get("/").header(header).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.into_string().unwrap(), "alice");
+
+        let response = client.get("/").dispatch();
+        assert_eq!(response.status(), Status::Unauthorized);
+        assert_eq!(
+            response.headers().get_one("WWW-Authenticate"),
+            Some("Bearer")
+        );
+
+        let header = authorization(TestClaims::new("bob", false));
+        let response = client.get("/").header(header).dispatch();
+        assert_eq!(response.status(), Status::Forbidden);
+        assert_eq!(
+            response.headers().get_one("WWW-Authenticate"),
+            Some(r#"Bearer error="insufficient_scope""#)
+        );
+    }
+
+    #[test]
+    fn ignition_fails_with_invalid_config() {
+        let rocket = rocket::custom(config())
+            .attach(fairing())
+            .mount("/", routes![whoami]);
+        let err = Client::tracked(rocket).unwrap_err();
+        assert_matches!(err.kind(), ErrorKind::FailedFairings(_));
+    }
+
+    #[test]
+    fn guard_without_authenticator() {
+        let rocket = rocket::custom(config()).mount("/", routes![whoami]);
+        let client = Client::tracked(rocket).unwrap();
+        let response = client.get("/").dispatch();
+        assert_eq!(response.status(), Status::InternalServerError);
+    }
+}
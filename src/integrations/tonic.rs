@@ -0,0 +1,294 @@
+//! Integration with [`tonic`](https://docs.rs/tonic/).
+//!
+//! - [`AuthInterceptor`] is a server-side interceptor authenticating requests by the `authorization`
+//!   metadata and inserting validated [`Claims`] into request extensions; claims can be retrieved
+//!   in service handlers via [`claims()`].
+//! - [`SigningInterceptor`] is a client-side interceptor attaching signed tokens to requests.
+//!   Tokens are reused until they are close to expiration.
+//! - [`AuthError`] converts into a [`Status`] with the `UNAUTHENTICATED` code,
+//!   or `PERMISSION_DENIED` for [`AuthError::Forbidden`].
+//!
+//! Both interceptors can be used with `InterceptedService` (e.g., via `with_interceptor` methods
+//! of generated servers and clients).
+//!
+//! # Examples
+//!
+//! ```
+//! # use chrono::Duration;
+//! # use jwt_compact::alg::{Hs256, Hs256Key};
+//! use jwt_compact::integrations::{
+//!     tonic::{claims, AuthInterceptor, SigningInterceptor},
+//!     Authenticator,
+//! };
+//! # use serde::{Deserialize, Serialize};
+//! use tonic::service::Interceptor;
+//!
+//! #[derive(Debug, Clone, Serialize, Deserialize)]
+//! struct CustomClaims {
+//!     #[serde(rename = "sub")]
+//!     subject: String,
+//! }
+//!
+//! let key = Hs256Key::new(b"super_secret_key_donut_steel");
+//! // Client side
+//! let mut client_interceptor = SigningInterceptor::new(Hs256, key.clone(), CustomClaims {
+//!     subject: "service".to_owned(),
+//! })
+//! .with_lifetime(Duration::try_minutes(10).unwrap());
+//! let request = client_interceptor.call(tonic::Request::new(()))?;
+//!
+//! // Server side
+//! let authenticator = Authenticator::<CustomClaims>::new(Hs256, key);
+//! let mut server_interceptor = AuthInterceptor::new(authenticator);
+//! let request = server_interceptor.call(request)?;
+//! let claims = claims::<CustomClaims, _>(&request).unwrap();
+//! assert_eq!(claims.custom.subject, "service");
+//! # Ok::<_, tonic::Status>(())
+//! ```
+
+#[cfg(feature = "sign")]
+use chrono::Duration;
+#[cfg(feature = "sign")]
+use serde::Serialize;
+#[cfg(feature = "sign")]
+use tonic::metadata::{Ascii, MetadataValue};
+use tonic::{service::Interceptor, Request, Status};
+
+#[cfg(feature = "sign")]
+use core::fmt;
+#[cfg(feature = "sign")]
+use std::sync::{Arc, Mutex, PoisonError};
+
+use super::{AuthError, Authenticator};
+use crate::Claims;
+#[cfg(feature = "sign")]
+use crate::{Algorithm, AlgorithmExt, CreationError, Header, TimeOptions, Timestamp};
+
+/// Name of the metadata entry containing the bearer token.
+const AUTHORIZATION: &str = "authorization";
+#[cfg(feature = "sign")]
+/// Default lifetime of tokens produced by [`SigningInterceptor`].
+const DEFAULT_LIFETIME: Duration = Duration::minutes(5);
+#[cfg(feature = "sign")]
+/// Margin before token expiration after which [`SigningInterceptor`] issues a new token.
+const REFRESH_MARGIN: Duration = Duration::seconds(30);
+
+impl From<AuthError> for Status {
+    fn from(err: AuthError) -> Self {
+        match err {
+            AuthError::Forbidden => Status::permission_denied(err.to_string()),
+            _ => Status::unauthenticated(err.to_string()),
+        }
+    }
+}
+
+/// Returns claims inserted into the request extensions by [`AuthInterceptor`].
+pub fn claims<T: Send + Sync + 'static, M>(request: &Request<M>) -> Option<&Claims<T>> {
+    request.extensions().get::<Claims<T>>()
+}
+
+/// Server-side interceptor authenticating requests with an [`Authenticator`].
+#[derive(Debug)]
+pub struct AuthInterceptor<T> {
+    authenticator: Authenticator<T>,
+}
+
+impl<T> Clone for AuthInterceptor<T> {
+    fn clone(&self) -> Self {
+        Self {
+            authenticator: self.authenticator.clone(),
+        }
+    }
+}
+
+impl<T> AuthInterceptor<T> {
+    /// Creates an interceptor with the specified authenticator.
+    pub fn new(authenticator: Authenticator<T>) -> Self {
+        Self { authenticator }
+    }
+}
+
+impl<T: Clone + Send + Sync + 'static> Interceptor for AuthInterceptor<T> {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        let authorization = request
+            .metadata()
+            .get(AUTHORIZATION)
+            .map(|value| value.to_str().map_err(|_| AuthError::MalformedHeader))
+            .transpose()?;
+        let token = self.authenticator.authenticate(authorization)?;
+        request.extensions_mut().insert(token.into_parts().1);
+        Ok(request)
+    }
+}
+
+#[cfg(feature = "sign")]
+#[derive(Debug)]
+struct CachedToken {
+    value: MetadataValue<Ascii>,
+    expiration: Timestamp,
+}
+
+/// Client-side interceptor attaching tokens with the specified custom claims to requests.
+/// Each token gets fresh `iat` and `exp` claims; by default, tokens are valid for 5 minutes.
+/// A token is reused until it is within 30 seconds of its expiration; clones of the interceptor
+/// share the cached token.
+#[cfg(feature = "sign")]
+#[cfg_attr(docsrs, doc(cfg(feature = "sign")))]
+pub struct SigningInterceptor<A: Algorithm, T> {
+    algorithm: Arc<A>,
+    signing_key: Arc<A::SigningKey>,
+    header: Header,
+    claims: T,
+    lifetime: Duration,
+    cached: Arc<Mutex<Option<CachedToken>>>,
+}
+
+#[cfg(feature = "sign")]
+impl<A, T> fmt::Debug for SigningInterceptor<A, T>
+where
+    A: Algorithm + fmt::Debug,
+    T: fmt::Debug,
+{
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("SigningInterceptor")
+            .field("algorithm", &self.algorithm)
+            .field("header", &self.header)
+            .field("claims", &self.claims)
+            .field("lifetime", &self.lifetime)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "sign")]
+impl<A: Algorithm, T: Clone> Clone for SigningInterceptor<A, T> {
+    fn clone(&self) -> Self {
+        Self {
+            algorithm: Arc::clone(&self.algorithm),
+            signing_key: Arc::clone(&self.signing_key),
+            header: self.header.clone(),
+            claims: self.claims.clone(),
+            lifetime: self.lifetime,
+            cached: Arc::clone(&self.cached),
+        }
+    }
+}
+
+#[cfg(feature = "sign")]
+impl<A: Algorithm, T: Serialize + Clone> SigningInterceptor<A, T> {
+    /// Creates an interceptor with the specified algorithm, signing key and custom claims.
+    pub fn new(algorithm: A, signing_key: A::SigningKey, claims: T) -> Self {
+        Self {
+            algorithm: Arc::new(algorithm),
+            signing_key: Arc::new(signing_key),
+            header: Header::empty(),
+            claims,
+            lifetime: DEFAULT_LIFETIME,
+            cached: Arc::default(),
+        }
+    }
+
+    /// Sets the header of produced tokens (e.g., to specify the key ID).
+    #[must_use]
+    pub fn with_header(mut self, header: Header) -> Self {
+        self.header = header;
+        self
+    }
+
+    /// Sets the lifetime of produced tokens.
+    #[must_use]
+    pub fn with_lifetime(mut self, lifetime: Duration) -> Self {
+        self.lifetime = lifetime;
+        self
+    }
+
+    fn token(&self) -> Result<MetadataValue<Ascii>, CreationError> {
+        let options = TimeOptions::default();
+        let now = (options.clock_fn)();
+        let mut cached = self.cached.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(cached) = &*cached {
+            if now + REFRESH_MARGIN < cached.expiration {
+                return Ok(cached.value.clone());
+            }
+        }
+
+        let claims =
+            Claims::new(self.claims.clone()).set_duration_and_issuance(&options, self.lifetime);
+        let token = self
+            .algorithm
+            .token(&self.header, &claims, &self.signing_key)?;
+        let value: MetadataValue<_> = format!("Bearer {token}")
+            .parse()
+            .expect("tokens consist of ASCII chars");
+        *cached = Some(CachedToken {
+            value: value.clone(),
+            expiration: now + self.lifetime,
+        });
+        Ok(value)
+    }
+}
+
+#[cfg(feature = "sign")]
+impl<A: Algorithm, T: Serialize + Clone> Interceptor for SigningInterceptor<A, T> {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        let token = self
+            .token()
+            .map_err(|err| Status::internal(format!("cannot sign token: {err}")))?;
+        request.metadata_mut().insert(AUTHORIZATION, token);
+        Ok(request)
+    }
+}
+
+#[cfg(all(test, feature = "sign"))]
+mod tests {
+    use serde_json::{json, Value};
+    use tonic::Code;
+
+    use super::*;
+    use crate::{alg::Hs256, test_helpers::key};
+
+    fn server_interceptor() -> AuthInterceptor<Value> {
+        let authenticator = Authenticator::<Value>::new(Hs256, key())
+            .with_policy(|claims| claims.custom["admin"] == true);
+        AuthInterceptor::new(authenticator)
+    }
+
+    #[test]
+    fn authenticating_requests() {
+        let mut client = SigningInterceptor::new(Hs256, key(), json!({ "admin": true }));
+        let request = client.call(Request::new(())).unwrap();
+        let request = server_interceptor().call(request).unwrap();
+        let claims = claims::<Value, _>(&request).unwrap();
+        assert_eq!(claims.custom["admin"], true);
+        let lifetime = claims.expiration.unwrap() - claims.issued_at.unwrap();
+        assert_eq!(lifetime, DEFAULT_LIFETIME);
+
+        let status = server_interceptor().call(Request::new(())).unwrap_err();
+        assert_eq!(status.code(), Code::Unauthenticated);
+        assert_eq!(status.message(), "bearer token is missing");
+
+        let mut client = SigningInterceptor::new(Hs256, key(), json!({ "admin": false }));
+        let request = client.call(Request::new(())).unwrap();
+        let status = server_interceptor().call(request).unwrap_err();
+        assert_eq!(status.code(), Code::PermissionDenied);
+    }
+
+    #[test]
+    fn signing_interceptor_caches_tokens() {
+        let client = SigningInterceptor::new(Hs256, key(), json!({ "sub": "service" }));
+        let first = client.clone().call(Request::new(())).unwrap();
+        let second = client.clone().call(Request::new(())).unwrap();
+        assert_eq!(
+            first.metadata().get(AUTHORIZATION),
+            second.metadata().get(AUTHORIZATION)
+        );
+
+        // Tokens with lifetime shorter than the refresh margin are never reused.
+        let mut client = SigningInterceptor::new(Hs256, key(), json!({ "sub": "service" }))
+            .with_lifetime(Duration::try_seconds(10).unwrap());
+        client.call(Request::new(())).unwrap();
+        let cached = client.cached.lock().unwrap();
+        let expiration = cached.as_ref().unwrap().expiration;
+        assert!(expiration < Timestamp::from(chrono::Utc::now()) + REFRESH_MARGIN);
+    }
+}
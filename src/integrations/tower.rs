@@ -0,0 +1,481 @@
+//! Generic [`tower`](https://docs.rs/tower/) authentication layer.
+//!
+//! [`AuthLayer`] wraps any service handling [`http::Request`]s, so it can be used with `hyper`
+//! (via `hyper-util`), `tonic`, `axum` and other `tower`-based stacks. The layer is configured with
+//!
+//! - A [`TokenExtractor`] determining where the token is taken from. By default, it is
+//!   a bearer token from the `Authorization` header ([`BearerToken`]); tokens can also be taken
+//!   from other headers ([`HeaderToken`]) or cookies ([`CookieToken`]).
+//! - Either an [`Authenticator`] with pre-configured keys, or a [`KeyResolver`] that
+//!   obtains verifying keys asynchronously (e.g., fetching and caching a remote key set).
+//!
+//! For authenticated requests, [`Claims`] are inserted into request extensions.
+//! Requests failing authentication are rejected with a response with an empty body,
+//! the status code and the `WWW-Authenticate` header as per [`AuthError`].
+//!
+//! # Examples
+//!
+//! ```
+//! # use jwt_compact::alg::{Hs256, Hs256Key};
+//! use jwt_compact::{
+//!     integrations::{tower::{AuthLayer, CookieToken}, AuthError},
+//!     UntrustedToken,
+//! };
+//! # use serde_json::Value;
+//!
+//! // Asynchronously resolves keys, e.g. using a cached remote key set.
+//! async fn find_key(key_id: Option<String>) -> Result<Hs256Key, AuthError> {
+//!     match key_id.as_deref() {
+//!         Some("main") => Ok(Hs256Key::new(b"super_secret_key_donut_steel")),
+//!         _ => Err(AuthError::UnknownKey),
+//!     }
+//! }
+//!
+//! let layer = AuthLayer::<Value>::with_key_resolver(Hs256, |token: &UntrustedToken| {
+//!     find_key(token.header().key_id.clone())
+//! })
+//! .with_extractor(CookieToken::new("session"))
+//! .with_policy(|claims| claims.custom["admin"] == true);
+//! // `layer` can now be used with `tower::ServiceBuilder`, `axum::Router::layer` etc.
+//! ```
+
+use http::{
+    header::{HeaderName, AUTHORIZATION, COOKIE, WWW_AUTHENTICATE},
+    HeaderValue, Request, Response, StatusCode,
+};
+use serde::de::DeserializeOwned;
+use tower_layer::Layer;
+use tower_service::Service;
+
+use core::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use std::{borrow::Cow, sync::Arc};
+
+use super::{extract_bearer_token, AuthError, Authenticator};
+use crate::{Algorithm, AlgorithmExt, Claims, Token, UntrustedToken};
+
+/// Boxed future returned by [`KeyResolver`]s and [`AuthService`].
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+type AsyncVerifier<T> = dyn Fn(UntrustedToken<'static>) -> BoxFuture<'static, Result<Token<T>, AuthError>>
+    + Send
+    + Sync;
+
+/// Strategy of extracting a token from an HTTP request.
+pub trait TokenExtractor: Clone + Send + Sync + 'static {
+    /// Extracts a token from the request. Returns `Ok(None)` if the request does not
+    /// contain a token.
+    fn extract<'r, B>(&self, request: &'r Request<B>) -> Result<Option<&'r str>, AuthError>;
+}
+
+/// Extracts a bearer token from the `Authorization` header. This is the default
+/// [`TokenExtractor`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BearerToken;
+
+impl TokenExtractor for BearerToken {
+    fn extract<'r, B>(&self, request: &'r Request<B>) -> Result<Option<&'r str>, AuthError> {
+        let Some(value) = request.headers().get(AUTHORIZATION) else {
+            return Ok(None);
+        };
+        let value = value.to_str().map_err(|_| AuthError::MalformedHeader)?;
+        Ok(extract_bearer_token(value))
+    }
+}
+
+/// Extracts a token from the entire value of the specified header (e.g., `X-Api-Token`).
+#[derive(Debug, Clone)]
+pub struct HeaderToken {
+    name: HeaderName,
+}
+
+impl HeaderToken {
+    /// Creates an extractor for the specified header.
+    pub fn new(name: HeaderName) -> Self {
+        Self { name }
+    }
+}
+
+impl TokenExtractor for HeaderToken {
+    fn extract<'r, B>(&self, request: &'r Request<B>) -> Result<Option<&'r str>, AuthError> {
+        let Some(value) = request.headers().get(&self.name) else {
+            return Ok(None);
+        };
+        let value = value.to_str().map_err(|_| AuthError::MalformedHeader)?;
+        Ok(Some(value.trim()).filter(|token| !token.is_empty()))
+    }
+}
+
+/// Extracts a token from the cookie with the specified name.
+#[derive(Debug, Clone)]
+pub struct CookieToken {
+    name: Cow<'static, str>,
+}
+
+impl CookieToken {
+    /// Creates an extractor for the specified cookie.
+    pub fn new(name: impl Into<Cow<'static, str>>) -> Self {
+        Self { name: name.into() }
+    }
+}
+
+impl TokenExtractor for CookieToken {
+    fn extract<'r, B>(&self, request: &'r Request<B>) -> Result<Option<&'r str>, AuthError> {
+        for value in request.headers().get_all(COOKIE) {
+            let value = value.to_str().map_err(|_| AuthError::MalformedHeader)?;
+            let token = value.split(';').find_map(|pair| {
+                let (name, value) = pair.trim().split_once('=')?;
+                (name == self.name).then_some(value)
+            });
+            if let Some(token) = token {
+                return Ok(Some(token).filter(|token| !token.is_empty()));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Asynchronous resolver of verifying keys for tokens.
+///
+/// The resolver is implemented for functions / closures taking `&UntrustedToken` and returning
+/// a `'static` future; i.e., the closure should copy the necessary data (such as the key ID)
+/// from the token before returning a future.
+pub trait KeyResolver<K>: Send + Sync + 'static {
+    /// Resolves the verifying key for the specified token.
+    fn resolve(&self, token: &UntrustedToken<'_>) -> BoxFuture<'static, Result<K, AuthError>>;
+}
+
+impl<K, F, Fut> KeyResolver<K> for F
+where
+    F: Fn(&UntrustedToken<'_>) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<K, AuthError>> + Send + 'static,
+{
+    fn resolve(&self, token: &UntrustedToken<'_>) -> BoxFuture<'static, Result<K, AuthError>> {
+        Box::pin(self(token))
+    }
+}
+
+/// Layer authenticating HTTP requests. See the [module docs](self) for details.
+pub struct AuthLayer<T, E = BearerToken> {
+    authenticator: Authenticator<T>,
+    resolver: Option<Arc<AsyncVerifier<T>>>,
+    extractor: E,
+}
+
+impl<T, E: fmt::Debug> fmt::Debug for AuthLayer<T, E> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("AuthLayer")
+            .field("authenticator", &self.authenticator)
+            .field("has_resolver", &self.resolver.is_some())
+            .field("extractor", &self.extractor)
+            .finish()
+    }
+}
+
+impl<T, E: Clone> Clone for AuthLayer<T, E> {
+    fn clone(&self) -> Self {
+        Self {
+            authenticator: self.authenticator.clone(),
+            resolver: self.resolver.clone(),
+            extractor: self.extractor.clone(),
+        }
+    }
+}
+
+impl<T> AuthLayer<T> {
+    /// Creates a layer with the specified authenticator.
+    pub fn new(authenticator: Authenticator<T>) -> Self {
+        Self {
+            authenticator,
+            resolver: None,
+            extractor: BearerToken,
+        }
+    }
+}
+
+impl<T: DeserializeOwned + Send + 'static> AuthLayer<T> {
+    /// Creates a layer verifying tokens with the specified algorithm and keys provided
+    /// by an asynchronous resolver. As with [`Authenticator`], the leeway for time-related
+    /// checks is set to 60 seconds.
+    pub fn with_key_resolver<A, R>(algorithm: A, resolver: R) -> Self
+    where
+        A: Algorithm + Send + Sync + 'static,
+        R: KeyResolver<A::VerifyingKey>,
+        A::VerifyingKey: Send,
+    {
+        let algorithm = Arc::new(algorithm);
+        let verifier = move |token: UntrustedToken<'static>| -> BoxFuture<'static, _> {
+            let key = resolver.resolve(&token);
+            let algorithm = Arc::clone(&algorithm);
+            Box::pin(async move {
+                let key = key.await?;
+                Ok(algorithm.validator::<T>(&key).validate(&token)?)
+            })
+        };
+        // The verifier of the authenticator is never called; it is only used to check claims.
+        let authenticator = Authenticator::from_verifier(|_| Err(AuthError::UnknownKey));
+        Self {
+            authenticator,
+            resolver: Some(Arc::new(verifier)),
+            extractor: BearerToken,
+        }
+    }
+}
+
+impl<T, E> AuthLayer<T, E> {
+    /// Sets the token extraction strategy.
+    pub fn with_extractor<F: TokenExtractor>(self, extractor: F) -> AuthLayer<T, F> {
+        AuthLayer {
+            authenticator: self.authenticator,
+            resolver: self.resolver,
+            extractor,
+        }
+    }
+
+    /// Sets the leeway for time-related checks.
+    #[must_use]
+    pub fn with_leeway(mut self, leeway: chrono::Duration) -> Self {
+        self.authenticator = self.authenticator.with_leeway(leeway);
+        self
+    }
+
+    /// Adds an authorization policy. Requests with claims not satisfying the policy
+    /// are rejected with a 403 response.
+    #[must_use]
+    pub fn with_policy<F>(mut self, policy: F) -> Self
+    where
+        F: Fn(&Claims<T>) -> bool + Send + Sync + 'static,
+    {
+        self.authenticator = self.authenticator.with_policy(policy);
+        self
+    }
+}
+
+impl<S, T, E: Clone> Layer<S> for AuthLayer<T, E> {
+    type Service = AuthService<S, T, E>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        AuthService {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+/// Service produced by [`AuthLayer`].
+pub struct AuthService<S, T, E = BearerToken> {
+    inner: S,
+    layer: AuthLayer<T, E>,
+}
+
+impl<S: fmt::Debug, T, E: fmt::Debug> fmt::Debug for AuthService<S, T, E> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("AuthService")
+            .field("inner", &self.inner)
+            .field("layer", &self.layer)
+            .finish()
+    }
+}
+
+impl<S: Clone, T, E: Clone> Clone for AuthService<S, T, E> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            layer: self.layer.clone(),
+        }
+    }
+}
+
+fn error_response<B: Default>(err: &AuthError) -> Response<B> {
+    let mut response = Response::new(B::default());
+    *response.status_mut() =
+        StatusCode::from_u16(err.status_code()).unwrap_or(StatusCode::UNAUTHORIZED);
+    let challenge = HeaderValue::from_static(err.challenge());
+    response.headers_mut().insert(WWW_AUTHENTICATE, challenge);
+    response
+}
+
+impl<S, T, E, B, ResBody> Service<Request<B>> for AuthService<S, T, E>
+where
+    S: Service<Request<B>, Response = Response<ResBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    T: Clone + Send + Sync + 'static,
+    E: TokenExtractor,
+    B: Send + 'static,
+    ResBody: Default + 'static,
+{
+    type Response = Response<ResBody>;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request<B>) -> Self::Future {
+        let token = self.layer.extractor.extract(&request).and_then(|token| {
+            let token = token.ok_or(AuthError::MissingToken)?;
+            UntrustedToken::new(token)
+                .map(UntrustedToken::into_owned)
+                .map_err(AuthError::Parse)
+        });
+        let token = match token {
+            Ok(token) => token,
+            Err(err) => return Box::pin(async move { Ok(error_response(&err)) }),
+        };
+
+        let Some(resolver) = &self.layer.resolver else {
+            let token = self.layer.authenticator.authenticate_untrusted(&token);
+            return match token {
+                Ok(token) => {
+                    request.extensions_mut().insert(token.into_parts().1);
+                    Box::pin(self.inner.call(request))
+                }
+                Err(err) => Box::pin(async move { Ok(error_response(&err)) }),
+            };
+        };
+
+        let verified = resolver(token);
+        let authenticator = self.layer.authenticator.clone();
+        // Take the service that was driven to readiness, leaving a clone in its place.
+        let clone = self.inner.clone();
+        let mut inner = core::mem::replace(&mut self.inner, clone);
+        Box::pin(async move {
+            match verified
+                .await
+                .and_then(|token| authenticator.check_claims(token))
+            {
+                Ok(token) => {
+                    request.extensions_mut().insert(token.into_parts().1);
+                    inner.call(request).await
+                }
+                Err(err) => Ok(error_response(&err)),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use core::future;
+    use serde_json::{json, Value};
+    use tower::{service_fn, ServiceExt};
+
+    use super::*;
+    use crate::{
+        alg::{Hs256, Hs256Key},
+        test_helpers::{key, sign},
+        Header,
+    };
+
+    fn token(claims: Value, key_id: &str) -> String {
+        sign(&claims, &Header::empty().with_key_id(key_id))
+    }
+
+    fn resolve_key(key_id: Option<&str>) -> future::Ready<Result<Hs256Key, AuthError>> {
+        future::ready(match key_id {
+            Some("k1") => Ok(key()),
+            _ => Err(AuthError::UnknownKey),
+        })
+    }
+
+    async fn handler(request: Request<()>) -> Result<Response<String>, AuthError> {
+        let claims = request.extensions().get::<Claims<Value>>().unwrap();
+        let subject = claims.custom["sub"].as_str().unwrap().to_owned();
+        Ok(Response::new(subject))
+    }
+
+    #[test]
+    fn extracting_tokens() {
+        let request = Request::builder()
+            .header(AUTHORIZATION, "Bearer abc")
+            .header("x-token", " def ")
+            .header(COOKIE, "theme=dark")
+            .header(COOKIE, "session=ghi; lang=en")
+            .body(())
+            .unwrap();
+        assert_eq!(BearerToken.extract(&request).unwrap(), Some("abc"));
+        let extractor = HeaderToken::new(HeaderName::from_static("x-token"));
+        assert_eq!(extractor.extract(&request).unwrap(), Some("def"));
+        let extractor = CookieToken::new("session");
+        assert_eq!(extractor.extract(&request).unwrap(), Some("ghi"));
+        let extractor = CookieToken::new("lang_");
+        assert_eq!(extractor.extract(&request).unwrap(), None);
+
+        let request = Request::builder()
+            .header(
+                AUTHORIZATION,
+                HeaderValue::from_bytes(b"Bearer \xff").unwrap(),
+            )
+            .body(())
+            .unwrap();
+        let err = BearerToken.extract(&request).unwrap_err();
+        assert_matches!(err, AuthError::MalformedHeader);
+    }
+
+    #[tokio::test]
+    async fn layer_with_authenticator() {
+        let authenticator = Authenticator::<Value>::new(Hs256, key());
+        let layer =
+            AuthLayer::new(authenticator).with_policy(|claims| claims.custom["admin"] == true);
+        let service = layer.layer(service_fn(handler));
+
+        let token = token(json!({ "sub": "alice", "admin": true }), "k1");
+        let request = Request::builder()
+            .header(AUTHORIZATION, format!("Bearer {token}"))
+            .body(())
+            .unwrap();
+        let response = service.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.into_body(), "alice");
+
+        let token = super::tests::token(json!({ "sub": "bob" }), "k1");
+        let request = Request::builder()
+            .header(AUTHORIZATION, format!("Bearer {token}"))
+            .body(())
+            .unwrap();
+        let response = service.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let request = Request::builder().body(()).unwrap();
+        let response = service.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(response.headers()[WWW_AUTHENTICATE], "Bearer");
+        assert!(response.body().is_empty());
+    }
+
+    #[tokio::test]
+    async fn layer_with_key_resolver() {
+        let layer = AuthLayer::<Value>::with_key_resolver(Hs256, |token: &UntrustedToken| {
+            resolve_key(token.header().key_id.as_deref())
+        })
+        .with_extractor(CookieToken::new("session"));
+        let service = layer.layer(service_fn(handler));
+
+        let token = token(json!({ "sub": "alice" }), "k1");
+        let request = Request::builder()
+            .header(COOKIE, format!("session={token}"))
+            .body(())
+            .unwrap();
+        let response = service.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.into_body(), "alice");
+
+        let token = super::tests::token(json!({ "sub": "alice" }), "k2");
+        let request = Request::builder()
+            .header(COOKIE, format!("session={token}"))
+            .body(())
+            .unwrap();
+        let response = service.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            response.headers()[WWW_AUTHENTICATE],
+            r#"Bearer error="invalid_token""#
+        );
+    }
+}
@@ -0,0 +1,147 @@
+//! Integration with [`warp`](https://docs.rs/warp/).
+//!
+//! - [`claims()`] creates a filter authenticating requests with an [`Authenticator`] and
+//!   extracting validated [`Claims`]. Requests failing authentication are rejected
+//!   with an [`AuthError`].
+//! - [`recover()`] converts [`AuthError`] rejections into 401 / 403 responses
+//!   with the `WWW-Authenticate` header; it should be used with [`Filter::recover()`].
+//!
+//! # Examples
+//!
+//! ```
+//! # use jwt_compact::alg::{Hs256, Hs256Key};
+//! use jwt_compact::{integrations::{warp as jwt_warp, Authenticator}, Claims};
+//! # use serde::{Deserialize, Serialize};
+//! use warp::Filter;
+//!
+//! #[derive(Debug, Clone, Serialize, Deserialize)]
+//! struct CustomClaims {
+//!     #[serde(rename = "sub")]
+//!     subject: String,
+//! }
+//!
+//! let key = Hs256Key::new(b"super_secret_key_donut_steel");
+//! let authenticator = Authenticator::<CustomClaims>::new(Hs256, key);
+//! let whoami = warp::path("whoami")
+//!     .and(jwt_warp::claims(authenticator))
+//!     .map(|claims: Claims<CustomClaims>| claims.custom.subject)
+//!     .recover(jwt_warp::recover);
+//! # let _ = whoami;
+//! ```
+
+use futures_util::future::{self, Ready};
+use warp::{
+    http::{header::WWW_AUTHENTICATE, StatusCode},
+    reject::{Reject, Rejection},
+    reply::{Reply, Response},
+    Filter,
+};
+
+use super::{AuthError, Authenticator};
+use crate::Claims;
+
+impl Reject for AuthError {}
+
+impl Reply for AuthError {
+    fn into_response(self) -> Response {
+        error_response(&self)
+    }
+}
+
+fn error_response(err: &AuthError) -> Response {
+    let status = StatusCode::from_u16(err.status_code()).unwrap_or(StatusCode::UNAUTHORIZED);
+    let reply = warp::reply::with_status(err.to_string(), status);
+    warp::reply::with_header(reply, WWW_AUTHENTICATE, err.challenge()).into_response()
+}
+
+/// Creates a filter authenticating requests by the `Authorization` header and extracting
+/// validated claims.
+pub fn claims<T>(
+    authenticator: Authenticator<T>,
+) -> impl Filter<Extract = (Claims<T>,), Error = Rejection> + Clone
+where
+    T: Send + Sync + 'static,
+{
+    warp::header::optional::<String>("authorization").and_then(
+        move |authorization: Option<String>| {
+            let result = authenticator
+                .authenticate(authorization.as_deref())
+                .map(|token| token.into_parts().1)
+                .map_err(warp::reject::custom);
+            future::ready(result)
+        },
+    )
+}
+
+/// Converts [`AuthError`] rejections into responses, passing through other rejections.
+pub fn recover(rejection: Rejection) -> Ready<Result<Response, Rejection>> {
+    future::ready(match rejection.find::<AuthError>() {
+        Some(err) => Ok(error_response(err)),
+        None => Err(rejection),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::{
+        alg::Hs256,
+        test_helpers::{key, sign},
+        Header,
+    };
+
+    fn authenticator() -> Authenticator<Value> {
+        Authenticator::<Value>::new(Hs256, key())
+            .with_policy(|claims| claims.custom["admin"] == true)
+    }
+
+    fn authorization(claims: &Value) -> String {
+        format!("Bearer {}", sign(claims, &Header::empty()))
+    }
+
+    #[tokio::test]
+    async fn extracting_claims() {
+        let filter = claims(authenticator());
+        let header = authorization(&json!({ "sub": "alice", "admin": true }));
+        let claims = warp::test::request()
+            .header("authorization", header)
+            .filter(&filter)
+            .await
+            .unwrap();
+        assert_eq!(claims.custom["sub"], "alice");
+
+        let rejection = warp::test::request().filter(&filter).await.unwrap_err();
+        assert_matches!(rejection.find::<AuthError>(), Some(AuthError::MissingToken));
+    }
+
+    #[tokio::test]
+    async fn recovering_from_auth_errors() {
+        let route = claims(authenticator())
+            .map(|claims: Claims<Value>| claims.custom["sub"].to_string())
+            .recover(recover);
+
+        let header = authorization(&json!({ "sub": "bob", "admin": false }));
+        let response = warp::test::request()
+            .header("authorization", header)
+            .reply(&route)
+            .await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert_eq!(
+            response.headers()[WWW_AUTHENTICATE],
+            r#"Bearer error="insufficient_scope""#
+        );
+
+        let response = warp::test::request()
+            .header("authorization", "Bearer !")
+            .reply(&route)
+            .await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            response.headers()[WWW_AUTHENTICATE],
+            r#"Bearer error="invalid_token""#
+        );
+    }
+}
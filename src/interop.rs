@@ -0,0 +1,447 @@
+//! Helpers for migrating from other Rust JWT libraries, namely [`jsonwebtoken`] and [`josekit`].
+//!
+//! Tokens produced by this crate and by these libraries are interchangeable as long as
+//! the same algorithm and key are used, so a codebase can be migrated incrementally
+//! (e.g., by switching token validation first and token issuance later). This module translates
+//! representations used by the libraries:
+//!
+//! - [`JsonwebtokenValidation`] mirrors `jsonwebtoken::Validation` options and converts them
+//!   into [`Expectations`] used by [`validate()`](crate::validation::validate()).
+//! - [`claims_from_payload()`] and [`claims_to_payload()`] convert between [`Claims`]
+//!   and flat JSON objects, such as the `josekit::jwt::JwtPayload` claims set
+//!   (`JwtPayload::claims_set()` / `JwtPayload::from_map()`).
+//! - [`KeyComponents`] extracts public key components accepted by `jsonwebtoken::DecodingKey`
+//!   constructors (e.g., `DecodingKey::from_rsa_components()`) from a [`JsonWebKey`].
+//!   `josekit` can import keys from JWKs directly, e.g., via `josekit::jwk::Jwk::from_bytes()`.
+//!
+//! The module does not depend on the mentioned libraries, so it is not affected
+//! by their breaking releases.
+//!
+//! [`jsonwebtoken`]: https://docs.rs/jsonwebtoken/
+//! [`josekit`]: https://docs.rs/josekit/
+//!
+//! # Examples
+//!
+//! ```
+//! # use jsonwebtoken::{encode, EncodingKey};
+//! # use serde_json::json;
+//! # use jwt_compact::{
+//! #     alg::{Hs256, Hs256Key}, interop::JsonwebtokenValidation, validation::validate,
+//! #     prelude::*, DynamicClaims,
+//! # };
+//! # fn main() -> anyhow::Result<()> {
+//! const SECRET: &[u8] = b"super_secret_key_donut_steel";
+//! // Token issued by `jsonwebtoken`
+//! let claims = json!({
+//!     "sub": "alice",
+//!     "iss": "https://issuer.example.com",
+//!     "aud": "api",
+//!     "exp": 4_102_444_800_i64, // 2100-01-01
+//! });
+//! let token = encode(
+//!     &jsonwebtoken::Header::default(),
+//!     &claims,
+//!     &EncodingKey::from_secret(SECRET),
+//! )?;
+//! let key = Hs256Key::new(SECRET);
+//!
+//! // Previously: `let mut validation = Validation::new(Algorithm::HS256);` etc.
+//! let mut validation = JsonwebtokenValidation::new("HS256");
+//! validation.iss = Some(vec!["https://issuer.example.com".to_owned()]);
+//! validation.aud = Some(vec!["api".to_owned()]);
+//! let expectations = validation.to_expectations(TimeOptions::default())?;
+//!
+//! let token: Token<DynamicClaims> = validate(&Hs256, &token, &key, &expectations)?;
+//! assert_eq!(token.claims().custom.get_str("sub"), Some("alice"));
+//! # Ok(())
+//! # }
+//! ```
+
+use base64ct::{Base64UrlUnpadded, Encoding};
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use core::fmt;
+
+use crate::{
+    alloc::{String, ToOwned, Vec},
+    jwk::JsonWebKey,
+    validation::Expectations,
+    Claims, DynamicClaims, TimeOptions,
+};
+
+/// Errors that can occur when converting [`JsonwebtokenValidation`] into [`Expectations`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum InteropError {
+    /// Validation option (the enclosed value) has a value not supported by [`Expectations`].
+    UnsupportedOption(&'static str),
+}
+
+impl fmt::Display for InteropError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnsupportedOption(option) => {
+                write!(
+                    formatter,
+                    "validation option `{option}` has unsupported value"
+                )
+            }
+        }
+    }
+}
+
+impl core::error::Error for InteropError {}
+
+/// Token validation options mirroring [`jsonwebtoken::Validation`].
+///
+/// Fields have the same names, semantics and default values as in `jsonwebtoken`,
+/// so that validation configs can be ported verbatim (or deserialized from the same config files).
+/// Use [`Self::to_expectations()`] to convert options into [`Expectations`].
+///
+/// Unlike `jsonwebtoken`, token maturity (the `nbf` claim) is always checked if the claim
+/// is present, regardless of [`Self::validate_nbf`].
+///
+/// [`jsonwebtoken::Validation`]: https://docs.rs/jsonwebtoken/latest/jsonwebtoken/struct.Validation.html
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JsonwebtokenValidation {
+    /// Claims that must be present in the token. Defaults to `["exp"]`.
+    pub required_spec_claims: Vec<String>,
+    /// Leeway for time-related checks in seconds. Defaults to 60.
+    pub leeway: u64,
+    /// Rejects tokens expiring in less than the specified number of seconds. Defaults to 0.
+    /// Non-zero values are not supported.
+    pub reject_tokens_expiring_in_less_than: u64,
+    /// Whether to check token expiration. Defaults to `true`; `false` is not supported.
+    pub validate_exp: bool,
+    /// Whether to check token maturity. Defaults to `false`.
+    pub validate_nbf: bool,
+    /// Whether to check the token audience. Defaults to `true`.
+    pub validate_aud: bool,
+    /// Accepted audiences.
+    pub aud: Option<Vec<String>>,
+    /// Accepted issuers. Only a single issuer is supported.
+    pub iss: Option<Vec<String>>,
+    /// Expected subject. Not supported.
+    pub sub: Option<String>,
+    /// Allowed algorithms. Defaults to `["HS256"]`.
+    pub algorithms: Vec<String>,
+}
+
+impl Default for JsonwebtokenValidation {
+    fn default() -> Self {
+        Self::new("HS256")
+    }
+}
+
+impl JsonwebtokenValidation {
+    /// Creates default options with the specified allowed algorithm.
+    pub fn new(algorithm: &str) -> Self {
+        Self {
+            required_spec_claims: Vec::from(["exp".to_owned()]),
+            leeway: 60,
+            reject_tokens_expiring_in_less_than: 0,
+            validate_exp: true,
+            validate_nbf: false,
+            validate_aud: true,
+            aud: None,
+            iss: None,
+            sub: None,
+            algorithms: Vec::from([algorithm.to_owned()]),
+        }
+    }
+
+    /// Converts these options into [`Expectations`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if some options are not supported by `Expectations`:
+    ///
+    /// - Disabled expiration check
+    /// - Non-zero `reject_tokens_expiring_in_less_than`
+    /// - Multiple issuers
+    /// - Subject check
+    /// - Leeway not representable as [`Duration`]
+    pub fn to_expectations(&self, time_options: TimeOptions) -> Result<Expectations, InteropError> {
+        if !self.validate_exp {
+            return Err(InteropError::UnsupportedOption("validate_exp"));
+        }
+        if self.reject_tokens_expiring_in_less_than != 0 {
+            return Err(InteropError::UnsupportedOption(
+                "reject_tokens_expiring_in_less_than",
+            ));
+        }
+        if self.sub.is_some() {
+            return Err(InteropError::UnsupportedOption("sub"));
+        }
+        let leeway = i64::try_from(self.leeway)
+            .ok()
+            .and_then(Duration::try_seconds)
+            .ok_or(InteropError::UnsupportedOption("leeway"))?;
+
+        let mut expectations = Expectations::new(time_options).with_leeway(leeway);
+        match self.iss.as_deref() {
+            None | Some([]) => { /* no issuer check */ }
+            Some([issuer]) => expectations = expectations.with_issuer(issuer.clone()),
+            Some(_) => return Err(InteropError::UnsupportedOption("iss")),
+        }
+        if self.validate_aud {
+            for audience in self.aud.iter().flatten() {
+                expectations = expectations.with_audience(audience.clone());
+            }
+        }
+        for claim in &self.required_spec_claims {
+            expectations = expectations.with_required_claim(claim.clone());
+        }
+        for algorithm in &self.algorithms {
+            expectations = expectations.with_allowed_algorithm(algorithm.clone());
+        }
+        Ok(expectations)
+    }
+}
+
+/// Converts a flat JSON object with claims (e.g., the claims set of a `josekit::jwt::JwtPayload`)
+/// into [`Claims`].
+///
+/// # Errors
+///
+/// Returns an error if time-related claims (`exp`, `nbf` or `iat`) are malformed.
+pub fn claims_from_payload(
+    payload: Map<String, Value>,
+) -> Result<Claims<DynamicClaims>, serde_json::Error> {
+    serde_json::from_value(Value::Object(payload))
+}
+
+/// Converts [`Claims`] into a flat JSON object (e.g., to create a `josekit::jwt::JwtPayload`
+/// via `JwtPayload::from_map()`).
+///
+/// # Errors
+///
+/// Returns an error if the claims cannot be serialized or are not serialized as an object.
+pub fn claims_to_payload<T: Serialize>(
+    claims: &Claims<T>,
+) -> Result<Map<String, Value>, serde_json::Error> {
+    match serde_json::to_value(claims)? {
+        Value::Object(map) => Ok(map),
+        _ => Err(serde::ser::Error::custom(
+            "claims are not serialized as an object",
+        )),
+    }
+}
+
+/// Public key components accepted by `jsonwebtoken::DecodingKey` constructors.
+/// All components are base64url-encoded.
+///
+/// Symmetric keys are not covered; their bytes can be passed to `DecodingKey::from_secret()`
+/// directly.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum KeyComponents {
+    /// RSA key; arguments for `DecodingKey::from_rsa_components()`.
+    Rsa {
+        /// Key modulus (`n`).
+        modulus: String,
+        /// Public exponent (`e`).
+        exponent: String,
+    },
+    /// Elliptic curve key; arguments for `DecodingKey::from_ec_components()`.
+    EllipticCurve {
+        /// Curve name (`crv`), such as `P-256`.
+        curve: String,
+        /// `x` coordinate of the curve point.
+        x: String,
+        /// `y` coordinate of the curve point.
+        y: String,
+    },
+    /// Octet key pair (e.g., Ed25519); argument for `DecodingKey::from_ed_components()`.
+    KeyPair {
+        /// Curve name (`crv`), such as `Ed25519`.
+        curve: String,
+        /// Public key.
+        x: String,
+    },
+}
+
+impl KeyComponents {
+    /// Extracts public components from a key. Returns `None` for symmetric keys.
+    /// Private key parts, if any, are ignored.
+    pub fn from_jwk(jwk: &JsonWebKey<'_>) -> Option<Self> {
+        Some(match jwk {
+            JsonWebKey::Rsa {
+                modulus,
+                public_exponent,
+                ..
+            } => Self::Rsa {
+                modulus: Base64UrlUnpadded::encode_string(modulus),
+                exponent: Base64UrlUnpadded::encode_string(public_exponent),
+            },
+            JsonWebKey::EllipticCurve { curve, x, y, .. } => Self::EllipticCurve {
+                curve: curve.as_ref().to_owned(),
+                x: Base64UrlUnpadded::encode_string(x),
+                y: Base64UrlUnpadded::encode_string(y),
+            },
+            JsonWebKey::KeyPair { curve, x, .. } => Self::KeyPair {
+                curve: curve.as_ref().to_owned(),
+                x: Base64UrlUnpadded::encode_string(x),
+            },
+            _ => return None,
+        })
+    }
+}
+
+#[cfg(all(test, feature = "clock"))]
+mod tests {
+    use assert_matches::assert_matches;
+    use chrono::{TimeZone, Utc};
+
+    use super::*;
+    use crate::{
+        alg::{Hs256, Hs256Key},
+        validation::{validate, ExpectationError},
+        AlgorithmExt, Empty, Header,
+    };
+
+    /// Token issued by `jsonwebtoken` with the `{"typ":"JWT","alg":"HS256"}` header.
+    const TOKEN: &str = "eyJ0eXAiOiJKV1QiLCJhbGciOiJIUzI1NiJ9.\
+        eyJzdWIiOiJhbGljZSIsImlzcyI6Imh0dHBzOi8vaXNzdWVyLmV4YW1wbGUuY29tIiwiYXVkIjoiYXBpIiwiZXhwI\
+        jo0MTAyNDQ0ODAwfQ.frleeooQkMwW2tziyJF_dnp1YkzQgu2UaYOOmC6N-7k";
+
+    #[test]
+    fn converting_validation() {
+        let key = Hs256Key::new(b"super_secret_key_donut_steel");
+        let validation: JsonwebtokenValidation = serde_json::from_value(serde_json::json!({
+            "iss": ["https://issuer.example.com"],
+            "aud": ["other", "api"],
+            "required_spec_claims": ["exp", "sub"],
+        }))
+        .unwrap();
+        assert_eq!(validation.leeway, 60);
+        assert_eq!(validation.algorithms, ["HS256"]);
+        let expectations = validation.to_expectations(TimeOptions::default()).unwrap();
+        let token =
+            validate::<_, DynamicClaims, Empty>(&Hs256, TOKEN, &key, &expectations).unwrap();
+        assert_eq!(token.claims().custom.get_str("sub"), Some("alice"));
+
+        let mut validation = JsonwebtokenValidation::new("HS384");
+        validation.aud = Some(vec!["other".to_owned()]);
+        let expectations = validation.to_expectations(TimeOptions::default()).unwrap();
+        let err =
+            validate::<_, DynamicClaims, Empty>(&Hs256, TOKEN, &key, &expectations).unwrap_err();
+        assert_matches!(err, ExpectationError::DisallowedAlgorithm(alg) if alg == "HS256");
+
+        validation.algorithms.push("HS256".to_owned());
+        let expectations = validation.to_expectations(TimeOptions::default()).unwrap();
+        let err =
+            validate::<_, DynamicClaims, Empty>(&Hs256, TOKEN, &key, &expectations).unwrap_err();
+        assert_matches!(err, ExpectationError::ClaimMismatch("aud"));
+
+        validation.validate_aud = false;
+        let expectations = validation.to_expectations(TimeOptions::default()).unwrap();
+        validate::<_, DynamicClaims, Empty>(&Hs256, TOKEN, &key, &expectations).unwrap();
+    }
+
+    #[test]
+    fn unsupported_validation_options() {
+        let cases = [
+            (
+                JsonwebtokenValidation {
+                    iss: Some(vec!["a".to_owned(), "b".to_owned()]),
+                    ..JsonwebtokenValidation::default()
+                },
+                "iss",
+            ),
+            (
+                JsonwebtokenValidation {
+                    validate_exp: false,
+                    ..JsonwebtokenValidation::default()
+                },
+                "validate_exp",
+            ),
+            (
+                JsonwebtokenValidation {
+                    sub: Some("alice".to_owned()),
+                    ..JsonwebtokenValidation::default()
+                },
+                "sub",
+            ),
+            (
+                JsonwebtokenValidation {
+                    leeway: u64::MAX,
+                    ..JsonwebtokenValidation::default()
+                },
+                "leeway",
+            ),
+        ];
+
+        for (validation, option) in cases {
+            let err = validation
+                .to_expectations(TimeOptions::default())
+                .unwrap_err();
+            assert_matches!(err, InteropError::UnsupportedOption(opt) if opt == option);
+        }
+    }
+
+    #[test]
+    fn converting_claims() {
+        let payload = serde_json::json!({ "sub": "alice", "exp": 4_102_444_800_i64 });
+        let serde_json::Value::Object(payload) = payload else {
+            unreachable!();
+        };
+        let claims = claims_from_payload(payload.clone()).unwrap();
+        assert_eq!(
+            claims.expiration,
+            Some(Utc.with_ymd_and_hms(2100, 1, 1, 0, 0, 0).unwrap().into())
+        );
+        assert_eq!(claims.custom.get_str("sub"), Some("alice"));
+        assert_eq!(claims_to_payload(&claims).unwrap(), payload);
+
+        let mut malformed = payload;
+        malformed.insert("exp".to_owned(), "tomorrow".into());
+        claims_from_payload(malformed).unwrap_err();
+
+        // Tokens created from the payload are accepted by `jsonwebtoken`-style validation.
+        let key = Hs256Key::new(b"super_secret_key_donut_steel");
+        let header = Header::empty().with_token_type("JWT");
+        let token = Hs256.token(&header, &claims, &key).unwrap();
+        let expectations = JsonwebtokenValidation::default()
+            .to_expectations(TimeOptions::default())
+            .unwrap();
+        validate::<_, DynamicClaims, Empty>(&Hs256, token.as_str(), &key, &expectations).unwrap();
+    }
+
+    #[test]
+    fn extracting_key_components() {
+        let jwk: JsonWebKey<'_> = serde_json::from_value(serde_json::json!({
+            "kty": "EC",
+            "crv": "P-256",
+            "x": "f83OJ3D2xF1Bg8vub9tLe1gHMzV76e8Tus9uPHvRVEU",
+            "y": "x_FEzRu9m36HLN_tue659LNpXW6pCyStikYjKIWI5a0",
+            "d": "jpsQnnGQmL-YBIffH1136cspYG6-0iY7X1fCE9-E9LI",
+        }))
+        .unwrap();
+        assert_eq!(
+            KeyComponents::from_jwk(&jwk).unwrap(),
+            KeyComponents::EllipticCurve {
+                curve: "P-256".to_owned(),
+                x: "f83OJ3D2xF1Bg8vub9tLe1gHMzV76e8Tus9uPHvRVEU".to_owned(),
+                y: "x_FEzRu9m36HLN_tue659LNpXW6pCyStikYjKIWI5a0".to_owned(),
+            }
+        );
+
+        let jwk: JsonWebKey<'_> = serde_json::from_value(serde_json::json!({
+            "kty": "RSA",
+            "n": "AQAB",
+            "e": "AQAB",
+        }))
+        .unwrap();
+        assert_matches!(
+            KeyComponents::from_jwk(&jwk).unwrap(),
+            KeyComponents::Rsa { modulus, exponent } if modulus == "AQAB" && exponent == "AQAB"
+        );
+
+        let key = Hs256Key::new(b"super_secret_key_donut_steel");
+        assert!(KeyComponents::from_jwk(&JsonWebKey::from(&key)).is_none());
+    }
+}
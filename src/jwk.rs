@@ -33,14 +33,19 @@
 //! # }
 //! ```
 
+use base64ct::{Base64UrlUnpadded, Encoding};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
-use sha2::digest::{Digest, Output};
+use sha2::{
+    digest::{Digest, Output},
+    Sha256,
+};
 
 use core::fmt;
 
 use crate::{
     alg::SecretBytes,
-    alloc::{Cow, String, ToString, Vec},
+    alloc::{Cow, String, ToOwned, ToString, Vec},
+    Algorithm, Thumbprint,
 };
 
 /// Type of a [`JsonWebKey`].
@@ -145,9 +150,8 @@ impl fmt::Display for JwkError {
     }
 }
 
-#[cfg(feature = "std")]
-impl std::error::Error for JwkError {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+impl core::error::Error for JwkError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
         match self {
             Self::Custom(err) => Some(err.as_ref()),
             _ => None,
@@ -340,6 +344,11 @@ impl JsonWebKey<'_> {
         };
         D::digest(hashed_key.to_string().as_bytes())
     }
+
+    /// Computes the key ID as the base64url-encoded SHA-256 thumbprint of this key.
+    pub(crate) fn thumbprint_key_id(&self) -> String {
+        Base64UrlUnpadded::encode_string(&self.thumbprint::<Sha256>())
+    }
 }
 
 impl fmt::Display for JsonWebKey<'_> {
@@ -350,7 +359,7 @@ impl fmt::Display for JsonWebKey<'_> {
         // ^ unwrap() is safe: `JsonWebKey` serialization is always an object.
 
         let mut json_entries: Vec<_> = json_value.iter().collect();
-        json_entries.sort_unstable_by(|(x, _), (y, _)| x.cmp(y));
+        json_entries.sort_unstable_by_key(|(name, _)| *name);
 
         formatter.write_str("{")?;
         let field_count = json_entries.len();
@@ -364,6 +373,116 @@ impl fmt::Display for JsonWebKey<'_> {
     }
 }
 
+/// Entry in a [`JsonWebKeySet`]: a [`JsonWebKey`] together with its common parameters.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct KeySetEntry<'a> {
+    /// Key identifier (`kid`).
+    #[serde(rename = "kid", default, skip_serializing_if = "Option::is_none")]
+    pub key_id: Option<String>,
+    /// Algorithm intended for use with the key (`alg`).
+    #[serde(rename = "alg", default, skip_serializing_if = "Option::is_none")]
+    pub algorithm: Option<String>,
+    /// Intended use of the key (`use`), such as `sig`.
+    #[serde(rename = "use", default, skip_serializing_if = "Option::is_none")]
+    pub key_use: Option<String>,
+    /// SHA-256 thumbprint of the X.509 certificate for the key (`x5t#S256`).
+    #[serde(rename = "x5t#S256", default, skip_serializing_if = "Option::is_none")]
+    pub certificate_thumbprint: Option<Thumbprint<32>>,
+    /// Key itself.
+    #[serde(flatten)]
+    pub key: JsonWebKey<'a>,
+}
+
+impl<'a> KeySetEntry<'a> {
+    /// Creates an entry with the specified key and no parameters.
+    pub fn new(key: JsonWebKey<'a>) -> Self {
+        Self {
+            key_id: None,
+            algorithm: None,
+            key_use: None,
+            certificate_thumbprint: None,
+            key,
+        }
+    }
+
+    /// Creates an entry for a key used to verify signatures (`use` is set to `sig`).
+    pub(crate) fn for_signatures(key: JsonWebKey<'a>, key_id: String, algorithm: &str) -> Self {
+        Self {
+            key_id: Some(key_id),
+            algorithm: Some(algorithm.to_owned()),
+            key_use: Some("sig".to_owned()),
+            ..Self::new(key)
+        }
+    }
+}
+
+/// [JWK set] (JWKS), e.g., one published by an identity provider.
+///
+/// [JWK set]: https://www.rfc-editor.org/rfc/rfc7517.html#section-5
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct JsonWebKeySet<'a> {
+    /// Keys in the set.
+    pub keys: Vec<KeySetEntry<'a>>,
+}
+
+impl<'a> JsonWebKeySet<'a> {
+    /// Creates a set that can be published by a token issuer (e.g., at `/.well-known/jwks.json`)
+    /// from the keys used with the specified `algorithm`.
+    ///
+    /// `keys` may be signing or verifying keys; in either case, only the public parts
+    /// of the keys are included. Each entry has `alg` set to the algorithm name,
+    /// `use` set to `sig`, and `kid` set to the base64url-encoded SHA-256 [RFC 7638] thumbprint
+    /// of the key. Symmetric keys (e.g., for `HS256`) are skipped since they must not be published.
+    ///
+    /// [RFC 7638]: https://www.rfc-editor.org/rfc/rfc7638.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use jwt_compact::{jwk::{JsonWebKey, JsonWebKeySet}, Algorithm};
+    ///
+    /// /// Produces the JSON to serve at `/.well-known/jwks.json`.
+    /// fn jwks_json<A: Algorithm>(
+    ///     algorithm: &A,
+    ///     signing_keys: &[A::SigningKey],
+    /// ) -> serde_json::Result<String>
+    /// where
+    ///     for<'a> JsonWebKey<'a>: From<&'a A::SigningKey>,
+    /// {
+    ///     let jwks = JsonWebKeySet::from_keys(algorithm, signing_keys);
+    ///     serde_json::to_string(&jwks)
+    /// }
+    /// ```
+    pub fn from_keys<A, K>(algorithm: &A, keys: impl IntoIterator<Item = &'a K>) -> Self
+    where
+        A: Algorithm + ?Sized,
+        K: 'a + ?Sized,
+        JsonWebKey<'a>: From<&'a K>,
+    {
+        let algorithm_name = algorithm.name();
+        let keys = keys.into_iter().filter_map(|key| {
+            let jwk = JsonWebKey::from(key);
+            if jwk.key_type() == KeyType::Symmetric {
+                return None;
+            }
+            let jwk = jwk.to_verifying_key();
+            let key_id = jwk.thumbprint_key_id();
+            Some(KeySetEntry::for_signatures(jwk, key_id, &algorithm_name))
+        });
+        Self {
+            keys: keys.collect(),
+        }
+    }
+
+    /// Finds a key by its identifier.
+    pub fn find(&self, key_id: &str) -> Option<&KeySetEntry<'a>> {
+        self.keys
+            .iter()
+            .find(|entry| entry.key_id.as_deref() == Some(key_id))
+    }
+}
+
 /// Parts of [`JsonWebKey::Rsa`] that are specific to private keys.
 ///
 /// # Serialization
@@ -526,7 +645,7 @@ mod base64url {
 
         struct BytesVisitor;
 
-        impl<'de> Visitor<'de> for BytesVisitor {
+        impl Visitor<'_> for BytesVisitor {
             type Value = Vec<u8>;
 
             fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -580,6 +699,32 @@ mod tests {
         assert_eq!(restored, jwk);
     }
 
+    #[test]
+    fn deserializing_key_set() {
+        let json = r#"{
+            "keys": [{
+                "kty": "OKP", "kid": "key-1", "use": "sig", "alg": "EdDSA",
+                "crv": "Ed25519", "x": "dGVzdA"
+            }, {
+                "kty": "oct", "kid": "key-2", "k": "t-bdv41MJXExXnpquHBuDn7n1YGyX7gLQchVHAoNu50"
+            }]
+        }"#;
+        let json: serde_json::Value = serde_json::from_str(json).unwrap();
+        let key_set: JsonWebKeySet<'_> = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(key_set.keys.len(), 2);
+        let entry = key_set.find("key-1").unwrap();
+        assert_eq!(entry.algorithm.as_deref(), Some("EdDSA"));
+        assert_eq!(entry.key_use.as_deref(), Some("sig"));
+        assert_eq!(entry.key, create_jwk());
+        assert_eq!(
+            key_set.find("key-2").unwrap().key.key_type(),
+            KeyType::Symmetric
+        );
+        assert!(key_set.find("key-3").is_none());
+
+        assert_eq!(serde_json::to_value(&key_set).unwrap(), json);
+    }
+
     #[test]
     fn jwk_deserialization_errors() {
         let missing_field_json = r#"{"crv":"Ed25519"}"#;
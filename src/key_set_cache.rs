@@ -0,0 +1,341 @@
+//! Background refreshing of JWK sets.
+//!
+//! [`KeySetRefresher`] spawns a `tokio` task that periodically fetches a [`JsonWebKeySet`]
+//! (e.g., from an identity provider's `jwks_uri`) and publishes it via a [`CachedKeySet`].
+//! Getting keys from a `CachedKeySet` never waits for network I/O: it always returns
+//! the latest successfully fetched set. Refreshes are spread out with a random jitter;
+//! failed fetches are retried with exponential backoff, during which the previously fetched
+//! keys remain in use.
+//!
+//! `CachedKeySet` implements [`KeyResolver`], so it can be plugged into a [`TokenValidator`].
+//!
+//! [`TokenValidator`]: crate::validation::TokenValidator
+//!
+//! # Examples
+//!
+//! ```
+//! # use chrono::Duration;
+//! use jwt_compact::{
+//!     alg::Hs256, jwk::JsonWebKeySet, key_set_cache::KeySetRefresher,
+//!     validation::TokenValidator, TimeOptions,
+//! };
+//!
+//! async fn fetch_jwks() -> anyhow::Result<JsonWebKeySet<'static>> {
+//!     // In real use cases, the set would be fetched over HTTP.
+//!     Ok(JsonWebKeySet::default())
+//! }
+//!
+//! # async fn test_cache() -> anyhow::Result<()> {
+//! let (key_set, task) = KeySetRefresher::new(Hs256, fetch_jwks)
+//!     .with_refresh_interval(Duration::try_minutes(10).unwrap())
+//!     .start()
+//!     .await?;
+//! let validator =
+//!     TokenValidator::<_, _, serde_json::Value>::new(Hs256, key_set, TimeOptions::default());
+//! // Use `validator` to validate tokens...
+//! task.abort();
+//! # Ok(())
+//! # }
+//! ```
+
+use chrono::Duration;
+use rand_core::{OsRng, RngCore};
+use tokio::{sync::watch, task::JoinHandle};
+
+use core::{fmt, future::Future};
+use std::sync::Arc;
+
+use crate::{
+    alloc::Box,
+    jwk::{JsonWebKey, JsonWebKeySet, JwkError},
+    key_store::{JwkKeyStore, KeyStore},
+    validation::{KeyResolver, ResolveFuture},
+    Algorithm, Header,
+};
+
+const DEFAULT_REFRESH_INTERVAL: Duration = Duration::minutes(5);
+const DEFAULT_JITTER: Duration = Duration::seconds(30);
+const DEFAULT_MIN_BACKOFF: Duration = Duration::seconds(1);
+const DEFAULT_MAX_BACKOFF: Duration = Duration::minutes(5);
+
+/// Shared handle to a JWK set kept up to date by a [`KeySetRefresher`].
+///
+/// The handle is cheap to clone. Keys are converted from JWKs once per refresh; keys
+/// not suitable for the algorithm are skipped as described in [`JwkKeyStore`] docs.
+/// The refresh task stops once all handles are dropped.
+pub struct CachedKeySet<A: Algorithm + ?Sized> {
+    receiver: watch::Receiver<Arc<JwkKeyStore<A>>>,
+}
+
+impl<A: Algorithm + ?Sized> fmt::Debug for CachedKeySet<A> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("CachedKeySet")
+            .field("keys", &*self.receiver.borrow())
+            .finish()
+    }
+}
+
+impl<A: Algorithm + ?Sized> Clone for CachedKeySet<A> {
+    fn clone(&self) -> Self {
+        Self {
+            receiver: self.receiver.clone(),
+        }
+    }
+}
+
+impl<A: Algorithm + ?Sized> CachedKeySet<A> {
+    /// Returns the latest successfully fetched keys. This never blocks on a refresh.
+    pub fn current(&self) -> Arc<JwkKeyStore<A>> {
+        Arc::clone(&self.receiver.borrow())
+    }
+
+    /// Waits until the keys are refreshed. Returns `false` if the refresh task has stopped
+    /// (e.g., was aborted), meaning that the keys will no longer change.
+    pub async fn changed(&mut self) -> bool {
+        self.receiver.changed().await.is_ok()
+    }
+}
+
+impl<A, H> KeyResolver<A, H> for CachedKeySet<A>
+where
+    A: Algorithm + ?Sized,
+    A::VerifyingKey: Clone + Send,
+{
+    fn resolve_key<'a>(&'a self, header: &'a Header<H>) -> ResolveFuture<'a, A::VerifyingKey> {
+        let key = self.current().key_for(header).ok().cloned();
+        Box::pin(async move { Ok(key) })
+    }
+}
+
+/// Handle to the task spawned by [`KeySetRefresher::start()`].
+///
+/// Dropping the handle detaches the task; it keeps running until all [`CachedKeySet`]
+/// handles are dropped.
+#[derive(Debug)]
+pub struct KeySetRefreshTask {
+    handle: JoinHandle<()>,
+}
+
+impl KeySetRefreshTask {
+    /// Checks whether the task has finished.
+    pub fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+
+    /// Aborts the task. Keys in [`CachedKeySet`]s remain available, but will no longer
+    /// be refreshed.
+    pub fn abort(&self) {
+        self.handle.abort();
+    }
+}
+
+/// Builder for a background task periodically refreshing a JWK set using the `fetch` function.
+/// See the [module docs](self) for an example of usage.
+pub struct KeySetRefresher<A, F> {
+    algorithm: A,
+    fetch: F,
+    refresh_interval: Duration,
+    jitter: Duration,
+    min_backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl<A: fmt::Debug, F> fmt::Debug for KeySetRefresher<A, F> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("KeySetRefresher")
+            .field("algorithm", &self.algorithm)
+            .field("refresh_interval", &self.refresh_interval)
+            .field("jitter", &self.jitter)
+            .field("min_backoff", &self.min_backoff)
+            .field("max_backoff", &self.max_backoff)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<A, F, Fut> KeySetRefresher<A, F>
+where
+    A: Algorithm + Send + 'static,
+    A::VerifyingKey: for<'jwk> TryFrom<&'jwk JsonWebKey<'jwk>, Error = JwkError> + Send + Sync,
+    F: Fn() -> Fut + Send + 'static,
+    Fut: Future<Output = anyhow::Result<JsonWebKeySet<'static>>> + Send + 'static,
+{
+    /// Creates a refresher with the default settings: refreshing every 5 minutes
+    /// with up to 30 seconds of jitter, and retrying failed fetches with backoff
+    /// from 1 second to 5 minutes.
+    pub fn new(algorithm: A, fetch: F) -> Self {
+        Self {
+            algorithm,
+            fetch,
+            refresh_interval: DEFAULT_REFRESH_INTERVAL,
+            jitter: DEFAULT_JITTER,
+            min_backoff: DEFAULT_MIN_BACKOFF,
+            max_backoff: DEFAULT_MAX_BACKOFF,
+        }
+    }
+
+    /// Sets the interval between successful refreshes.
+    #[must_use]
+    pub fn with_refresh_interval(mut self, interval: Duration) -> Self {
+        self.refresh_interval = interval;
+        self
+    }
+
+    /// Sets the maximum random jitter added to each delay, so that multiple instances
+    /// do not refresh the set simultaneously.
+    #[must_use]
+    pub fn with_jitter(mut self, jitter: Duration) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Sets the bounds of the delay before retrying a failed fetch. The delay starts from `min`
+    /// and doubles after each consecutive failure, up to `max`.
+    #[must_use]
+    pub fn with_backoff(mut self, min: Duration, max: Duration) -> Self {
+        self.min_backoff = min;
+        self.max_backoff = max;
+        self
+    }
+
+    /// Fetches the initial JWK set and spawns the refresh task on the current `tokio` runtime.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the initial fetch fails.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside a `tokio` runtime.
+    pub async fn start(self) -> anyhow::Result<(CachedKeySet<A>, KeySetRefreshTask)> {
+        let key_set = (self.fetch)().await?;
+        let store = JwkKeyStore::new(&self.algorithm, &key_set);
+        let (sender, receiver) = watch::channel(Arc::new(store));
+        let handle = tokio::spawn(self.run(sender));
+        Ok((CachedKeySet { receiver }, KeySetRefreshTask { handle }))
+    }
+
+    async fn run(self, sender: watch::Sender<Arc<JwkKeyStore<A>>>) {
+        let mut delay = self.refresh_interval;
+        let mut backoff = self.min_backoff;
+        loop {
+            let delay_with_jitter = (delay + random_jitter(self.jitter))
+                .to_std()
+                .unwrap_or_default();
+            let closed = tokio::time::timeout(delay_with_jitter, sender.closed()).await;
+            if closed.is_ok() {
+                return; // All `CachedKeySet` handles are dropped.
+            }
+
+            if let Ok(key_set) = (self.fetch)().await {
+                let store = JwkKeyStore::new(&self.algorithm, &key_set);
+                sender.send_replace(Arc::new(store));
+                delay = self.refresh_interval;
+                backoff = self.min_backoff;
+            } else {
+                delay = backoff;
+                backoff = (backoff * 2).min(self.max_backoff);
+            }
+        }
+    }
+}
+
+fn random_jitter(max: Duration) -> Duration {
+    let max_millis = u64::try_from(max.num_milliseconds()).unwrap_or(0);
+    if max_millis == 0 {
+        return Duration::zero();
+    }
+    let millis = OsRng.next_u64() % (max_millis + 1);
+    // `millis` does not exceed `max_millis`, which was obtained from an `i64`.
+    Duration::try_milliseconds(i64::try_from(millis).unwrap()).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::{
+        alg::{Hs256, Hs256Key},
+        jwk::KeySetEntry,
+    };
+
+    fn key_set(key_id: &str) -> JsonWebKeySet<'static> {
+        let key = Hs256Key::new(b"super_secret_key_donut_steel");
+        let jwk = JsonWebKey::from(&key);
+        let jwk: JsonWebKey<'static> =
+            serde_json::from_value(serde_json::to_value(jwk).unwrap()).unwrap();
+        let mut entry = KeySetEntry::new(jwk);
+        entry.key_id = Some(key_id.to_owned());
+        JsonWebKeySet { keys: vec![entry] }
+    }
+
+    fn has_key(key_set: &CachedKeySet<Hs256>, key_id: &str) -> bool {
+        let header = Header::empty().with_key_id(key_id);
+        key_set.current().key_for(&header).is_ok()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn refreshing_key_set() {
+        let fetch_count = Arc::new(AtomicUsize::new(0));
+        let fetch = {
+            let fetch_count = Arc::clone(&fetch_count);
+            move || {
+                let count = fetch_count.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    match count {
+                        0 => Ok(key_set("first")),
+                        1 | 2 => Err(anyhow::anyhow!("JWKS endpoint is unavailable")),
+                        _ => Ok(key_set("second")),
+                    }
+                }
+            }
+        };
+        let (mut cached, task) = KeySetRefresher::new(Hs256, fetch)
+            .with_refresh_interval(Duration::try_minutes(1).unwrap())
+            .with_backoff(
+                Duration::try_seconds(1).unwrap(),
+                Duration::try_seconds(2).unwrap(),
+            )
+            .start()
+            .await
+            .unwrap();
+        assert!(has_key(&cached, "first"));
+        assert!(!has_key(&cached, "second"));
+
+        assert!(cached.changed().await);
+        assert!(has_key(&cached, "second"));
+        assert!(!has_key(&cached, "first"));
+        assert_eq!(fetch_count.load(Ordering::SeqCst), 4);
+
+        task.abort();
+        assert!(!cached.changed().await);
+        assert!(has_key(&cached, "second"));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn refresh_task_stops_after_handles_are_dropped() {
+        let fetch = || async { Ok(key_set("main")) };
+        let (cached, task) = KeySetRefresher::new(Hs256, fetch).start().await.unwrap();
+        let resolved =
+            KeyResolver::<Hs256>::resolve_key(&cached, &Header::empty().with_key_id("main"))
+                .await
+                .unwrap();
+        assert!(resolved.is_some());
+
+        drop(cached);
+        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+        assert!(task.is_finished());
+    }
+
+    #[tokio::test]
+    async fn initial_fetch_error() {
+        let fetch = || async { Err(anyhow::anyhow!("JWKS endpoint is unavailable")) };
+        let err = KeySetRefresher::new(Hs256, fetch)
+            .start()
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("unavailable"));
+    }
+}
@@ -0,0 +1,931 @@
+//! Pluggable resolution of verifying keys during token validation.
+//!
+//! A [`KeyStore`] selects the verifying key for a token based on its [`Header`]
+//! (e.g., the `kid` or `x5t#S256` fields), which decouples choosing the key from verifying
+//! the token signature. Key stores are used by the [`KeyStoreValidator`] produced by
+//! [`AlgorithmExt::key_store_validator()`]. If each key should additionally be bound
+//! to a specific algorithm instance, use [`KeyRegistry`].
+//!
+//! # Examples
+//!
+//! ```
+//! # use rand::thread_rng;
+//! use jwt_compact::{
+//!     prelude::*, alg::{Hs256, Hs256Key}, key_store::StaticKeyStore, Empty,
+//! };
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let key = Hs256Key::generate(&mut thread_rng()).into_inner();
+//! let other_key = Hs256Key::generate(&mut thread_rng()).into_inner();
+//! let header = Header::empty().with_key_id("first");
+//! let token = Hs256.token(&header, &Claims::empty(), &key)?;
+//!
+//! let store = StaticKeyStore::<Hs256>::new()
+//!     .with_key("first", key)
+//!     .with_key("second", other_key);
+//! let token = UntrustedToken::new(&token)?;
+//! let token = Hs256.key_store_validator::<Empty, _>(&store).validate(&token)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use serde::de::DeserializeOwned;
+
+use core::{fmt, marker::PhantomData};
+
+use crate::{
+    alloc::{BTreeMap, String, ToOwned, Vec},
+    jwk::{JsonWebKey, JsonWebKeySet, JwkError, KeySetEntry},
+    traits::validate_with,
+    Algorithm, AlgorithmExt, Header, SignedToken, Thumbprint, Token, UntrustedToken,
+    ValidationError,
+};
+
+/// Store of verifying keys for an [`Algorithm`], which selects the key for a token
+/// based on the token header.
+pub trait KeyStore<A: Algorithm + ?Sized> {
+    /// Returns the verifying key for a token with the specified `header`.
+    ///
+    /// # Errors
+    ///
+    /// Should return [`ValidationError::UnknownKey`] if the store does not contain
+    /// a matching key.
+    fn key_for<H>(&self, header: &Header<H>) -> Result<&A::VerifyingKey, ValidationError>;
+}
+
+/// Static [`KeyStore`] mapping key IDs (the `kid` header field) to verifying keys.
+pub struct StaticKeyStore<A: Algorithm + ?Sized> {
+    keys: BTreeMap<String, A::VerifyingKey>,
+}
+
+impl<A: Algorithm + ?Sized> fmt::Debug for StaticKeyStore<A> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("StaticKeyStore")
+            .field("key_ids", &self.keys.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl<A: Algorithm + ?Sized> Default for StaticKeyStore<A> {
+    fn default() -> Self {
+        Self {
+            keys: BTreeMap::new(),
+        }
+    }
+}
+
+impl<A: Algorithm + ?Sized> StaticKeyStore<A> {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a key with the specified ID to this store, replacing the previous key
+    /// with the same ID if any.
+    #[must_use]
+    pub fn with_key(mut self, key_id: impl Into<String>, key: A::VerifyingKey) -> Self {
+        self.insert(key_id, key);
+        self
+    }
+
+    /// Inserts a key with the specified ID into this store. Returns the previous key
+    /// with the same ID if any.
+    pub fn insert(
+        &mut self,
+        key_id: impl Into<String>,
+        key: A::VerifyingKey,
+    ) -> Option<A::VerifyingKey> {
+        self.keys.insert(key_id.into(), key)
+    }
+
+    /// Removes a key with the specified ID from this store.
+    pub fn remove(&mut self, key_id: &str) -> Option<A::VerifyingKey> {
+        self.keys.remove(key_id)
+    }
+
+    /// Returns the number of keys in this store.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Checks whether this store is empty.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+}
+
+impl<A: Algorithm + ?Sized, S: Into<String>> FromIterator<(S, A::VerifyingKey)>
+    for StaticKeyStore<A>
+{
+    fn from_iter<I: IntoIterator<Item = (S, A::VerifyingKey)>>(iter: I) -> Self {
+        Self {
+            keys: iter
+                .into_iter()
+                .map(|(key_id, key)| (key_id.into(), key))
+                .collect(),
+        }
+    }
+}
+
+impl<A: Algorithm + ?Sized> KeyStore<A> for StaticKeyStore<A> {
+    fn key_for<H>(&self, header: &Header<H>) -> Result<&A::VerifyingKey, ValidationError> {
+        let key_id = header
+            .key_id
+            .as_deref()
+            .ok_or(ValidationError::UnknownKey)?;
+        self.keys.get(key_id).ok_or(ValidationError::UnknownKey)
+    }
+}
+
+struct JwkEntry<K> {
+    key_id: Option<String>,
+    certificate_thumbprint: Option<Thumbprint<32>>,
+    key: K,
+}
+
+/// [`KeyStore`] backed by a [JWK set](JsonWebKeySet), e.g., one published by an identity
+/// provider.
+///
+/// Keys are converted from JWKs when the store is created. Keys not suitable for the algorithm
+/// (i.e., having another `alg`, a `use` other than `sig`, or a key type (`kty`) that cannot be
+/// converted to a verifying key) are skipped. A key is selected by the `kid`
+/// header field, or by the `x5t#S256` field if `kid` is absent.
+pub struct JwkKeyStore<A: Algorithm + ?Sized> {
+    entries: Vec<JwkEntry<A::VerifyingKey>>,
+    understood_extensions: Vec<String>,
+}
+
+impl<A: Algorithm + ?Sized> fmt::Debug for JwkKeyStore<A> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let key_ids: Vec<_> = self.entries.iter().map(|entry| &entry.key_id).collect();
+        formatter
+            .debug_struct("JwkKeyStore")
+            .field("key_ids", &key_ids)
+            .finish()
+    }
+}
+
+impl<A> JwkKeyStore<A>
+where
+    A: Algorithm + ?Sized,
+    A::VerifyingKey: for<'jwk> TryFrom<&'jwk JsonWebKey<'jwk>, Error = JwkError>,
+{
+    /// Creates a store with keys from the specified JWK set that are suitable for `algorithm`.
+    pub fn new(algorithm: &A, key_set: &JsonWebKeySet<'_>) -> Self {
+        let algorithm_name = algorithm.name();
+        let entries = key_set.keys.iter().filter_map(|entry| {
+            if entry
+                .algorithm
+                .as_deref()
+                .is_some_and(|alg| alg != algorithm_name)
+            {
+                return None;
+            }
+            if entry
+                .key_use
+                .as_deref()
+                .is_some_and(|key_use| key_use != "sig")
+            {
+                return None;
+            }
+            let key = A::VerifyingKey::try_from(&entry.key).ok()?;
+            Some(JwkEntry {
+                key_id: entry.key_id.clone(),
+                certificate_thumbprint: entry.certificate_thumbprint.clone(),
+                key,
+            })
+        });
+        Self {
+            entries: entries.collect(),
+            understood_extensions: Vec::new(),
+        }
+    }
+}
+
+impl<A: Algorithm + ?Sized> JwkKeyStore<A> {
+    /// Declares [critical header extensions][crit] understood by the application
+    /// for [`Self::validate_with_any_key()`]. See
+    /// [`Validator::with_understood_extensions()`](crate::Validator::with_understood_extensions())
+    /// for details.
+    ///
+    /// [crit]: https://www.rfc-editor.org/rfc/rfc7515#section-4.1.11
+    #[must_use]
+    pub fn with_understood_extensions(mut self, extensions: &[&str]) -> Self {
+        self.understood_extensions = extensions.iter().map(|&ext| ext.to_owned()).collect();
+        self
+    }
+
+    /// Returns the number of keys in this store.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Checks whether this store is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl<A: Algorithm + ?Sized> JwkKeyStore<A> {
+    /// Validates the token integrity. If the token header contains the `kid` or `x5t#S256` field,
+    /// the key is selected as in the [`KeyStore`] implementation. Otherwise, all keys in the store
+    /// are tried one by one until the token signature is successfully verified.
+    ///
+    /// This is useful for identity providers publishing keys without key IDs. The token signature
+    /// and claims are parsed only once regardless of the number of tried keys. With the `rayon`
+    /// feature, keys can be tried in parallel using [`Self::par_validate_with_any_key()`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ValidationError::UnknownKey`] if the store is empty or the selected key
+    /// is not found, and [`ValidationError::InvalidSignature`] if none of the keys matches.
+    pub fn validate_with_any_key<T, H>(
+        &self,
+        algorithm: &A,
+        token: &UntrustedToken<'_, H>,
+    ) -> Result<Token<T, H>, ValidationError>
+    where
+        T: DeserializeOwned,
+        H: Clone,
+    {
+        let selected_key = self.selected_key(token.header())?;
+        let signed = validate_with::<A, _, _>(
+            &algorithm.name(),
+            &self.understood_extensions,
+            token,
+            |signature, message| {
+                if let Some(key) = selected_key {
+                    algorithm.verify_signature(signature, key, message)
+                } else {
+                    self.entries
+                        .iter()
+                        .any(|entry| algorithm.verify_signature(signature, &entry.key, message))
+                }
+            },
+        )?;
+        Ok(signed.token)
+    }
+
+    /// Parallel version of [`Self::validate_with_any_key()`] that tries keys on the `rayon`
+    /// thread pool and stops once a matching key is found.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`Self::validate_with_any_key()`].
+    #[cfg(feature = "rayon")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "rayon")))]
+    pub fn par_validate_with_any_key<T, H>(
+        &self,
+        algorithm: &A,
+        token: &UntrustedToken<'_, H>,
+    ) -> Result<Token<T, H>, ValidationError>
+    where
+        A: Sync,
+        A::VerifyingKey: Sync,
+        A::Signature: Sync,
+        T: DeserializeOwned,
+        H: Clone,
+    {
+        use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+
+        let selected_key = self.selected_key(token.header())?;
+        let signed = validate_with::<A, _, _>(
+            &algorithm.name(),
+            &self.understood_extensions,
+            token,
+            |signature, message| {
+                if let Some(key) = selected_key {
+                    algorithm.verify_signature(signature, key, message)
+                } else {
+                    self.entries
+                        .par_iter()
+                        .any(|entry| algorithm.verify_signature(signature, &entry.key, message))
+                }
+            },
+        )?;
+        Ok(signed.token)
+    }
+
+    /// Returns the key selected by the token header, or `None` if the header does not
+    /// reference a key, in which case all keys are candidates.
+    fn selected_key<H>(
+        &self,
+        header: &Header<H>,
+    ) -> Result<Option<&A::VerifyingKey>, ValidationError> {
+        if self.entries.is_empty() {
+            Err(ValidationError::UnknownKey)
+        } else if header.key_id.is_some() || header.certificate_thumbprint.is_some() {
+            self.key_for(header).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+impl<A: Algorithm + ?Sized> KeyStore<A> for JwkKeyStore<A> {
+    fn key_for<H>(&self, header: &Header<H>) -> Result<&A::VerifyingKey, ValidationError> {
+        let entry = if let Some(key_id) = &header.key_id {
+            self.entries
+                .iter()
+                .find(|entry| entry.key_id.as_ref() == Some(key_id))
+        } else if let Some(thumbprint) = &header.certificate_thumbprint {
+            self.entries
+                .iter()
+                .find(|entry| entry.certificate_thumbprint.as_ref() == Some(thumbprint))
+        } else {
+            None
+        };
+        entry
+            .map(|entry| &entry.key)
+            .ok_or(ValidationError::UnknownKey)
+    }
+}
+
+/// Errors that can occur when inserting keys into a [`KeyRegistry`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum KeyRegistryError {
+    /// Key ID is empty.
+    EmptyKeyId,
+    /// Key with the same ID is already present in the registry.
+    DuplicateKeyId(String),
+    /// JWK set entry does not specify the key ID.
+    MissingKeyId,
+    /// Algorithm specified by the JWK set entry (the `alg` field) differs from the expected one.
+    AlgorithmMismatch {
+        /// Expected algorithm name.
+        expected: String,
+        /// Algorithm name in the JWK set entry.
+        actual: String,
+    },
+    /// JWK cannot be converted to a verifying key.
+    Jwk(JwkError),
+}
+
+impl fmt::Display for KeyRegistryError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyKeyId => formatter.write_str("key ID is empty"),
+            Self::DuplicateKeyId(key_id) => {
+                write!(formatter, "key with ID `{key_id}` is already registered")
+            }
+            Self::MissingKeyId => formatter.write_str("key set entry does not specify key ID"),
+            Self::AlgorithmMismatch { expected, actual } => write!(
+                formatter,
+                "key algorithm ({actual}) differs from expected ({expected})"
+            ),
+            Self::Jwk(err) => write!(formatter, "invalid JWK: {err}"),
+        }
+    }
+}
+
+impl core::error::Error for KeyRegistryError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::Jwk(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// Registry mapping key IDs (the `kid` header field) to verifying keys together
+/// with the algorithm each key is bound to.
+///
+/// Unlike [`StaticKeyStore`], the registry binds each key to an algorithm instance,
+/// so that, e.g., an RSA key registered for `PS256` cannot be used to validate `RS256` tokens.
+/// Key IDs are checked for uniqueness on insertion.
+///
+/// # Examples
+///
+/// ```
+/// # use rand::thread_rng;
+/// use jwt_compact::{prelude::*, alg::{Hs256, Hs256Key}, key_store::KeyRegistry, Empty};
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let key = Hs256Key::generate(&mut thread_rng()).into_inner();
+/// let mut registry = KeyRegistry::new();
+/// registry.insert("main", Hs256, key.clone())?;
+/// // Key IDs must be unique.
+/// assert!(registry.insert("main", Hs256, key.clone()).is_err());
+///
+/// let header = Header::empty().with_key_id("main");
+/// let token = Hs256.token(&header, &Claims::empty(), &key)?;
+/// let token = UntrustedToken::new(&token)?;
+/// let token = registry.validate::<Empty, _>(&token)?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct KeyRegistry<A: Algorithm> {
+    entries: BTreeMap<String, (A, A::VerifyingKey)>,
+    understood_extensions: Vec<String>,
+}
+
+impl<A: Algorithm> fmt::Debug for KeyRegistry<A> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let entries = self
+            .entries
+            .iter()
+            .map(|(key_id, (algorithm, _))| (key_id, algorithm.name()));
+        formatter.debug_map().entries(entries).finish()
+    }
+}
+
+impl<A: Algorithm> Default for KeyRegistry<A> {
+    fn default() -> Self {
+        Self {
+            entries: BTreeMap::new(),
+            understood_extensions: Vec::new(),
+        }
+    }
+}
+
+impl<A: Algorithm> KeyRegistry<A> {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declares [critical header extensions][crit] understood by the application.
+    /// See [`Validator::with_understood_extensions()`](crate::Validator::with_understood_extensions())
+    /// for details.
+    ///
+    /// [crit]: https://www.rfc-editor.org/rfc/rfc7515#section-4.1.11
+    #[must_use]
+    pub fn with_understood_extensions(mut self, extensions: &[&str]) -> Self {
+        self.understood_extensions = extensions.iter().map(|&ext| ext.to_owned()).collect();
+        self
+    }
+
+    /// Inserts a key bound to `algorithm` into the registry.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key ID is empty or is already present in the registry.
+    pub fn insert(
+        &mut self,
+        key_id: impl Into<String>,
+        algorithm: A,
+        key: A::VerifyingKey,
+    ) -> Result<(), KeyRegistryError> {
+        let key_id = key_id.into();
+        if key_id.is_empty() {
+            return Err(KeyRegistryError::EmptyKeyId);
+        }
+        if self.entries.contains_key(&key_id) {
+            return Err(KeyRegistryError::DuplicateKeyId(key_id));
+        }
+        self.entries.insert(key_id, (algorithm, key));
+        Ok(())
+    }
+
+    /// Inserts a key from a JWK set entry bound to `algorithm` into the registry.
+    ///
+    /// # Errors
+    ///
+    /// In addition to errors returned by [`Self::insert()`], returns an error if the entry
+    /// does not specify a key ID, its `alg` field differs from the `algorithm` name,
+    /// or its key cannot be converted to a verifying key (e.g., because of another key type).
+    pub fn insert_jwk(
+        &mut self,
+        algorithm: A,
+        entry: &KeySetEntry<'_>,
+    ) -> Result<(), KeyRegistryError>
+    where
+        A::VerifyingKey: for<'jwk> TryFrom<&'jwk JsonWebKey<'jwk>, Error = JwkError>,
+    {
+        let key_id = entry
+            .key_id
+            .as_deref()
+            .ok_or(KeyRegistryError::MissingKeyId)?;
+        if let Some(actual) = &entry.algorithm {
+            let expected = algorithm.name();
+            if *actual != expected {
+                return Err(KeyRegistryError::AlgorithmMismatch {
+                    expected: expected.into_owned(),
+                    actual: actual.clone(),
+                });
+            }
+        }
+        let key = A::VerifyingKey::try_from(&entry.key).map_err(KeyRegistryError::Jwk)?;
+        self.insert(key_id, algorithm, key)
+    }
+
+    /// Removes a key with the specified ID from the registry, returning it together
+    /// with the bound algorithm.
+    pub fn remove(&mut self, key_id: &str) -> Option<(A, A::VerifyingKey)> {
+        self.entries.remove(key_id)
+    }
+
+    /// Returns the algorithm and the verifying key with the specified ID.
+    pub fn get(&self, key_id: &str) -> Option<(&A, &A::VerifyingKey)> {
+        let (algorithm, key) = self.entries.get(key_id)?;
+        Some((algorithm, key))
+    }
+
+    /// Returns the number of keys in the registry.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Checks whether the registry is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn lookup<H>(
+        &self,
+        token: &UntrustedToken<'_, H>,
+    ) -> Result<(&A, &A::VerifyingKey), ValidationError> {
+        let key_id = token
+            .header()
+            .key_id
+            .as_deref()
+            .ok_or(ValidationError::UnknownKey)?;
+        self.get(key_id).ok_or(ValidationError::UnknownKey)
+    }
+
+    /// Validates the token integrity using the key referenced by the `kid` field
+    /// of the token header and the algorithm bound to this key.
+    pub fn validate<T, H>(
+        &self,
+        token: &UntrustedToken<'_, H>,
+    ) -> Result<Token<T, H>, ValidationError>
+    where
+        T: DeserializeOwned,
+        H: Clone,
+    {
+        self.validate_for_signed_token(token)
+            .map(|signed| signed.token)
+    }
+
+    /// Validates the token integrity using the key referenced by the `kid` field
+    /// of the token header and the algorithm bound to this key, and returns the validated
+    /// [`Token`] together with its signature.
+    pub fn validate_for_signed_token<T, H>(
+        &self,
+        token: &UntrustedToken<'_, H>,
+    ) -> Result<SignedToken<A, T, H>, ValidationError>
+    where
+        T: DeserializeOwned,
+        H: Clone,
+    {
+        let (algorithm, key) = self.lookup(token)?;
+        validate_with(
+            &algorithm.name(),
+            &self.understood_extensions,
+            token,
+            |signature, message| algorithm.verify_signature(signature, key, message),
+        )
+    }
+}
+
+/// Validator for a certain signing [`Algorithm`] that selects verifying keys from a [`KeyStore`].
+/// Produced by the [`AlgorithmExt::key_store_validator()`] method.
+pub struct KeyStoreValidator<'a, A, S, T> {
+    pub(crate) algorithm: &'a A,
+    pub(crate) key_store: &'a S,
+    pub(crate) understood_extensions: &'a [&'a str],
+    pub(crate) _claims: PhantomData<fn() -> T>,
+}
+
+impl<A, S, T> fmt::Debug for KeyStoreValidator<'_, A, S, T>
+where
+    A: Algorithm,
+    S: fmt::Debug,
+{
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("KeyStoreValidator")
+            .field("algorithm", &self.algorithm.name())
+            .field("key_store", self.key_store)
+            .finish()
+    }
+}
+
+impl<A, S, T> Clone for KeyStoreValidator<'_, A, S, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<A, S, T> Copy for KeyStoreValidator<'_, A, S, T> {}
+
+impl<'a, A, S, T> KeyStoreValidator<'a, A, S, T> {
+    /// Declares [critical header extensions][crit] understood by the application.
+    /// See [`Validator::with_understood_extensions()`](crate::Validator::with_understood_extensions())
+    /// for details.
+    ///
+    /// [crit]: https://www.rfc-editor.org/rfc/rfc7515#section-4.1.11
+    #[must_use]
+    pub fn with_understood_extensions(mut self, extensions: &'a [&'a str]) -> Self {
+        self.understood_extensions = extensions;
+        self
+    }
+}
+
+impl<A, S, T> KeyStoreValidator<'_, A, S, T>
+where
+    A: Algorithm,
+    S: KeyStore<A>,
+    T: DeserializeOwned,
+{
+    /// Validates the token integrity against a key selected from the key store.
+    pub fn validate<H: Clone>(
+        self,
+        token: &UntrustedToken<'_, H>,
+    ) -> Result<Token<T, H>, ValidationError> {
+        let verifying_key = self.key_store.key_for(token.header())?;
+        self.algorithm
+            .validator(verifying_key)
+            .with_understood_extensions(self.understood_extensions)
+            .validate(token)
+    }
+
+    /// Validates the token integrity against a key selected from the key store,
+    /// and returns the validated [`Token`] together with its signature.
+    pub fn validate_for_signed_token<H: Clone>(
+        self,
+        token: &UntrustedToken<'_, H>,
+    ) -> Result<SignedToken<A, T, H>, ValidationError> {
+        let verifying_key = self.key_store.key_for(token.header())?;
+        self.algorithm
+            .validator(verifying_key)
+            .with_understood_extensions(self.understood_extensions)
+            .validate_for_signed_token(token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use rand::thread_rng;
+
+    use super::*;
+    use crate::{
+        alg::{Hs256, Hs256Key, Hs384},
+        alloc::ToOwned,
+        jwk::KeySetEntry,
+        AlgorithmExt, Claims, Empty, Renamed,
+    };
+
+    fn key() -> Hs256Key {
+        Hs256Key::generate(&mut thread_rng()).into_inner()
+    }
+
+    fn create_token(header: &Header, key: &Hs256Key) -> String {
+        Hs256.token(header, &Claims::empty(), key).unwrap().into()
+    }
+
+    #[test]
+    fn static_key_store() {
+        let (key, other_key) = (key(), key());
+        let store: StaticKeyStore<Hs256> = [("first", key.clone()), ("second", other_key.clone())]
+            .into_iter()
+            .collect();
+        assert_eq!(store.len(), 2);
+
+        for (key_id, key) in [("first", &key), ("second", &other_key)] {
+            let token = create_token(&Header::empty().with_key_id(key_id), key);
+            let token = UntrustedToken::new(&token).unwrap();
+            Hs256
+                .key_store_validator::<Empty, _>(&store)
+                .validate(&token)
+                .unwrap();
+        }
+
+        let bogus_headers = [Header::empty(), Header::empty().with_key_id("third")];
+        for header in &bogus_headers {
+            let token = create_token(header, &key);
+            let token = UntrustedToken::new(&token).unwrap();
+            let err = Hs256
+                .key_store_validator::<Empty, _>(&store)
+                .validate(&token)
+                .unwrap_err();
+            assert_matches!(err, ValidationError::UnknownKey);
+        }
+
+        // Key mismatch should lead to a signature error.
+        let token = create_token(&Header::empty().with_key_id("second"), &key);
+        let token = UntrustedToken::new(&token).unwrap();
+        let err = Hs256
+            .key_store_validator::<Empty, _>(&store)
+            .validate(&token)
+            .unwrap_err();
+        assert_matches!(err, ValidationError::InvalidSignature);
+    }
+
+    fn key_set(keys: &[(&Hs256Key, Option<&str>, Option<&str>)]) -> JsonWebKeySet<'static> {
+        let keys = keys.iter().map(|&(key, key_id, algorithm)| {
+            let jwk = serde_json::to_value(JsonWebKey::from(key)).unwrap();
+            let mut entry = KeySetEntry::new(serde_json::from_value(jwk).unwrap());
+            entry.key_id = key_id.map(ToOwned::to_owned);
+            entry.algorithm = algorithm.map(ToOwned::to_owned);
+            entry
+        });
+        JsonWebKeySet {
+            keys: keys.collect(),
+        }
+    }
+
+    #[test]
+    fn jwk_key_store() {
+        let (key, other_key, hs384_key) = (key(), key(), key());
+        let key_set = key_set(&[
+            (&key, Some("first"), Some("HS256")),
+            (&other_key, Some("second"), None),
+            (&hs384_key, Some("third"), Some("HS384")),
+        ]);
+        let store = JwkKeyStore::new(&Hs256, &key_set);
+        assert_eq!(store.len(), 2);
+
+        for (key_id, key) in [("first", &key), ("second", &other_key)] {
+            let token = create_token(&Header::empty().with_key_id(key_id), key);
+            let token = UntrustedToken::new(&token).unwrap();
+            Hs256
+                .key_store_validator::<Empty, _>(&store)
+                .validate(&token)
+                .unwrap();
+        }
+
+        let token = create_token(&Header::empty().with_key_id("third"), &hs384_key);
+        let token = UntrustedToken::new(&token).unwrap();
+        let err = Hs256
+            .key_store_validator::<Empty, _>(&store)
+            .validate(&token)
+            .unwrap_err();
+        assert_matches!(err, ValidationError::UnknownKey);
+
+        // The key without `alg` is suitable for any algorithm.
+        let hs384_store = JwkKeyStore::new(&Hs384, &key_set);
+        assert_eq!(hs384_store.len(), 2);
+    }
+
+    fn test_validation_with_any_key(
+        validate: impl Fn(
+            &JwkKeyStore<Hs256>,
+            &UntrustedToken<'_>,
+        ) -> Result<Token<Empty>, ValidationError>,
+    ) {
+        let keys: Vec<_> = (0..5).map(|_| key()).collect();
+        let key_refs: Vec<_> = keys.iter().map(|key| (key, None, None)).collect();
+        let store = JwkKeyStore::new(&Hs256, &key_set(&key_refs));
+
+        for key in &keys {
+            let token = create_token(&Header::empty(), key);
+            let token = UntrustedToken::new(&token).unwrap();
+            validate(&store, &token).unwrap();
+        }
+
+        let token = create_token(&Header::empty(), &key());
+        let token = UntrustedToken::new(&token).unwrap();
+        let err = validate(&store, &token).unwrap_err();
+        assert_matches!(err, ValidationError::InvalidSignature);
+
+        // If the token references a key, other keys are not tried.
+        let token = create_token(&Header::empty().with_key_id("first"), &keys[0]);
+        let token = UntrustedToken::new(&token).unwrap();
+        let err = validate(&store, &token).unwrap_err();
+        assert_matches!(err, ValidationError::UnknownKey);
+
+        let token = create_token(&Header::empty(), &keys[0]);
+        let token = UntrustedToken::new(&token).unwrap();
+        let empty_store = JwkKeyStore::new(&Hs256, &JsonWebKeySet::default());
+        let err = validate(&empty_store, &token).unwrap_err();
+        assert_matches!(err, ValidationError::UnknownKey);
+    }
+
+    #[test]
+    fn jwk_key_store_validation_with_any_key() {
+        test_validation_with_any_key(|store, token| store.validate_with_any_key(&Hs256, token));
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn jwk_key_store_parallel_validation_with_any_key() {
+        test_validation_with_any_key(|store, token| store.par_validate_with_any_key(&Hs256, token));
+    }
+
+    #[test]
+    fn jwk_key_store_with_certificate_thumbprints() {
+        let key = key();
+        let mut key_set = key_set(&[(&key, None, None)]);
+        key_set.keys[0].certificate_thumbprint = Some(Thumbprint::Bytes([1; 32]));
+        let store = JwkKeyStore::new(&Hs256, &key_set);
+
+        let header = Header::empty().with_certificate_thumbprint([1; 32]);
+        let token = create_token(&header, &key);
+        let token = UntrustedToken::new(&token).unwrap();
+        Hs256
+            .key_store_validator::<Empty, _>(&store)
+            .validate(&token)
+            .unwrap();
+
+        let header = Header::empty().with_certificate_thumbprint([2; 32]);
+        let token = create_token(&header, &key);
+        let token = UntrustedToken::new(&token).unwrap();
+        let err = Hs256
+            .key_store_validator::<Empty, _>(&store)
+            .validate(&token)
+            .unwrap_err();
+        assert_matches!(err, ValidationError::UnknownKey);
+    }
+
+    #[test]
+    fn understood_extensions_in_key_stores() {
+        let key = key();
+        let header =
+            Header::new(serde_json::json!({ "crit": ["b64"], "b64": false })).with_key_id("first");
+        let token = Hs256.token(&header, &Claims::empty(), &key).unwrap();
+        let token = UntrustedToken::new(&token).unwrap();
+
+        let store = JwkKeyStore::new(&Hs256, &key_set(&[(&key, Some("first"), None)]));
+        let validator = Hs256.key_store_validator::<Empty, _>(&store);
+        let err = validator.validate(&token).unwrap_err();
+        assert_matches!(err, ValidationError::UnknownCriticalExtension(_));
+        validator
+            .with_understood_extensions(&["b64"])
+            .validate(&token)
+            .unwrap();
+
+        let err = store
+            .validate_with_any_key::<Empty, _>(&Hs256, &token)
+            .unwrap_err();
+        assert_matches!(err, ValidationError::UnknownCriticalExtension(_));
+        let store = store.with_understood_extensions(&["b64"]);
+        store
+            .validate_with_any_key::<Empty, _>(&Hs256, &token)
+            .unwrap();
+
+        let mut registry = KeyRegistry::new();
+        registry.insert("first", Hs256, key).unwrap();
+        let err = registry.validate::<Empty, _>(&token).unwrap_err();
+        assert_matches!(err, ValidationError::UnknownCriticalExtension(_));
+        let registry = registry.with_understood_extensions(&["b64"]);
+        registry.validate::<Empty, _>(&token).unwrap();
+    }
+
+    #[test]
+    fn key_registry() {
+        let (key, other_key) = (key(), key());
+        let mut registry = KeyRegistry::new();
+        registry
+            .insert("first", Renamed::new(Hs256, "HS256"), key.clone())
+            .unwrap();
+        registry
+            .insert("second", Renamed::new(Hs256, "X-HS256"), other_key.clone())
+            .unwrap();
+        assert_eq!(registry.len(), 2);
+
+        let err = registry
+            .insert("first", Renamed::new(Hs256, "HS256"), other_key.clone())
+            .unwrap_err();
+        assert_matches!(err, KeyRegistryError::DuplicateKeyId(id) if id == "first");
+        let err = registry
+            .insert("", Renamed::new(Hs256, "HS256"), other_key.clone())
+            .unwrap_err();
+        assert_matches!(err, KeyRegistryError::EmptyKeyId);
+
+        let token = create_token(&Header::empty().with_key_id("first"), &key);
+        let token = UntrustedToken::new(&token).unwrap();
+        registry.validate::<Empty, _>(&token).unwrap();
+
+        // The second key is bound to another algorithm.
+        let token = create_token(&Header::empty().with_key_id("second"), &other_key);
+        let token = UntrustedToken::new(&token).unwrap();
+        let err = registry.validate::<Empty, _>(&token).unwrap_err();
+        assert_matches!(
+            err,
+            ValidationError::AlgorithmMismatch { expected, .. } if expected == "X-HS256"
+        );
+
+        let token = create_token(&Header::empty(), &key);
+        let token = UntrustedToken::new(&token).unwrap();
+        let err = registry.validate::<Empty, _>(&token).unwrap_err();
+        assert_matches!(err, ValidationError::UnknownKey);
+
+        assert!(registry.remove("first").is_some());
+        assert!(registry.get("first").is_none());
+    }
+
+    #[test]
+    fn inserting_jwks_into_key_registry() {
+        let (key, other_key) = (key(), key());
+        let key_set = key_set(&[
+            (&key, Some("first"), Some("HS256")),
+            (&other_key, Some("second"), Some("HS384")),
+            (&other_key, None, None),
+        ]);
+        let mut registry = KeyRegistry::new();
+        registry.insert_jwk(Hs256, &key_set.keys[0]).unwrap();
+        let err = registry.insert_jwk(Hs256, &key_set.keys[1]).unwrap_err();
+        assert_matches!(
+            err,
+            KeyRegistryError::AlgorithmMismatch { expected, actual }
+                if expected == "HS256" && actual == "HS384"
+        );
+        let err = registry.insert_jwk(Hs256, &key_set.keys[2]).unwrap_err();
+        assert_matches!(err, KeyRegistryError::MissingKeyId);
+        assert_eq!(registry.len(), 1);
+    }
+}
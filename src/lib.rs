@@ -5,12 +5,18 @@
 //!
 //! - JWT signature algorithms (i.e., cryptographic algorithms providing JWT integrity)
 //!   are expressed via the [`Algorithm`] trait, which uses fully typed keys and signatures.
+//!   Conversions of keys from JWKs check the JWK key type (the `kty` field), so that, e.g.,
+//!   an RSA public key cannot be used as an `HS256` secret.
 //! - [JWT header] is represented by the [`Header`] struct. Notably, `Header` does not
 //!   expose the [`alg` field].
 //!   Instead, `alg` is filled automatically during token creation, and is compared to the
 //!   expected value during verification. (If you do not know the JWT signature algorithm during
 //!   verification, you're doing something wrong.) This eliminates the possibility
 //!   of [algorithm switching attacks][switching].
+//! - Comparisons involving secrets are constant-time. This includes verification of `HS*`
+//!   signatures, comparisons of [`SecretBytes`](alg::SecretBytes) and [`SecretString`]s,
+//!   and checks of values bound to a token, such as the OAuth `state`, OIDC `nonce`
+//!   or SD-JWT `sd_hash`. Applications can use [`constant_time_eq()`] for their own comparisons.
 //!
 //! # Additional features
 //!
@@ -21,12 +27,67 @@
 //! - Supports basic [JSON Web Key](https://tools.ietf.org/html/rfc7517.html) functionality,
 //!   e.g., for converting keys to / from JSON or computing
 //!   [a key thumbprint](https://tools.ietf.org/html/rfc7638).
+//! - Supports importing and exporting asymmetric keys in PEM (PKCS#1, PKCS#8, SEC1 and SPKI
+//!   formats) via the `pem` module (requires the `pem` feature). Password-encrypted PKCS#8
+//!   private keys are supported with the `pem-encryption` feature, and verifying keys
+//!   can be extracted from X.509 certificates with the `x509` feature.
+//! - Provides signing key rotation for token issuers in the [`rotation`] module, with automatic
+//!   `kid` stamping and publishing of verifying keys as a JWK set.
+//! - Provides issuance of linked access and refresh tokens in the [`token_pair`] module.
+//! - Provides an asynchronous token validation pipeline with pluggable key resolution
+//!   (e.g., fetching a JWK set) in the [`validation`] module. With the `tokio` feature,
+//!   JWK sets can be refreshed in the background via the [`key_set_cache`] module.
+//! - Provides helpers for JWT-based OAuth 2.0 profiles in the [`oauth`] module,
+//!   e.g., for creating [client assertions](https://www.rfc-editor.org/rfc/rfc7523.html)
+//!   and [request objects](https://www.rfc-editor.org/rfc/rfc9101.html),
+//!   validating [JWT-secured authorization responses](https://openid.net/specs/oauth-v2-jarm.html),
+//!   checking [certificate-bound tokens](https://www.rfc-editor.org/rfc/rfc8705.html)
+//!   and handling [token exchange](https://www.rfc-editor.org/rfc/rfc8693.html) delegation claims.
+//! - Supports [selective disclosure](https://datatracker.ietf.org/doc/draft-ietf-oauth-selective-disclosure-jwt/)
+//!   for JWTs (SD-JWT) in the [`sd_jwt`] module.
+//! - Supports [security event tokens](https://www.rfc-editor.org/rfc/rfc8417.html)
+//!   in the [`set`] module.
+//! - Supports validation of [OIDC logout tokens](https://openid.net/specs/openid-connect-backchannel-1_0.html)
+//!   and signed `UserInfo` responses, and of `OpenID` Federation trust chains in the [`oidc`]
+//!   module, with replay protection provided by the [`replay`] module.
+//! - Supports issuing and verifying [PASETO](https://github.com/paseto-standard/paseto-spec) v4
+//!   tokens with the same claims as JWTs via the `paseto` module (requires the `paseto` feature).
+//! - Supports issuing and verifying [Branca](https://github.com/tuupola/branca-spec) tokens
+//!   with the same claims as JWTs via the `branca` module (requires the `branca` feature).
+//! - Provides the `Claims` derive macro generating `serde` implementations and declarative
+//!   validation for custom claims (requires the `derive` feature).
+//! - Provides preset validators for ID tokens issued by popular identity providers
+//!   (e.g., Sign in with Apple, Google and Firebase), for SPIFFE JWT-SVIDs and for Kubernetes
+//!   service account tokens in the [`presets`] module.
+//! - Provides request authentication for web frameworks in the [`integrations`] module
+//!   (e.g., for `axum`, `actix-web`, `warp`, `rocket`, `tonic` and generic `tower` stacks
+//!   with the features of the same name), bearer token attachment for `reqwest` clients
+//!   and AWS API Gateway Lambda authorizers.
+//! - Supports asynchronous signing via the [`AsyncAlgorithm`] trait, e.g., with keys held
+//!   by AWS KMS, Google Cloud KMS, Azure Key Vault or the Vault transit engine
+//!   (requires the `aws-kms` / `gcp-kms` / `azure-key-vault` / `vault-transit` features),
+//!   by hardware tokens (requires the `pkcs11` / `yubikey` / `tpm` features),
+//!   by an SSH agent (requires the `ssh-agent` feature), or by the Apple keychain
+//!   and Secure Enclave (requires the `apple-platforms` feature).
+//! - Supports storing HMAC secrets in the OS credential store
+//!   (requires the `keyring` feature).
+//! - Provides JWS helpers for [ACME](https://www.rfc-editor.org/rfc/rfc8555.html) clients
+//!   in the [`acme`] module.
+//! - Eases migration from the `jsonwebtoken` and `josekit` crates via the `interop` module
+//!   (requires the `interop` feature).
+//! - Provides RFC 7515 and RFC 7520 (JOSE cookbook) test vectors for conformance checks
+//!   of custom algorithm implementations via the `test_vectors` module, and a mock clock,
+//!   a mock algorithm and fixture keys for tests via the `testing` module (both require
+//!   the `test-util` feature). Headers, claims and tokens can be fuzzed or property-tested
+//!   via `arbitrary` and `proptest` (requires the features of the same name).
+//! - Zeroizes secret key material on drop; see [`alg`](alg#zeroization) for details
+//!   (the `zeroize` feature, enabled by default, extends this to the `ed25519-dalek` backend).
 //!
 //! ## Supported algorithms
 //!
 //! | Algorithm(s) | Feature | Description |
 //! |--------------|---------|-------------|
-//! | `HS256`, `HS384`, `HS512` | - | Uses pure Rust [`sha2`] crate |
+//! | `HS256`, `HS384`, `HS512` | `hs` (on by default) | Uses pure Rust [`sha2`] crate |
 //! | `EdDSA` (Ed25519) | [`exonum-crypto`] | [`libsodium`] binding |
 //! | `EdDSA` (Ed25519) | [`ed25519-dalek`] | Pure Rust implementation |
 //! | `EdDSA` (Ed25519) | [`ed25519-compact`] | Compact pure Rust implementation, WASM-compatible |
@@ -35,6 +96,18 @@
 //! | `ES256`  | [`p256`] | Pure Rust implementation |
 //! | `RS*`, `PS*` (RSA) | `rsa` | Uses pure Rust [`rsa`] crate with blinding |
 //!
+//! Each algorithm family is gated behind its own feature, so that only the required crypto code
+//! is compiled. For example, a WASM verifier that only needs `EdDSA` can depend on the crate
+//! with `default-features = false, features = ["ed25519-compact"]`; this excludes HMAC
+//! (the `hs` feature) and RSA code from the build.
+//!
+//! Signing is gated behind the `sign` feature, which is enabled by default. Without it, the crate
+//! is built in the verification-only profile: [`Algorithm::sign()`], token creation methods
+//! (e.g., [`AlgorithmExt::token()`]), modules and methods issuing tokens, and RSA private
+//! key handling are compiled out. This reduces the binary size for verifiers (e.g., edge
+//! workers targeting WASM), which can depend on the crate with
+//! `default-features = false, features = ["std", "clock", "rsa", "p256"]`.
+//!
 //! Beware that the `rsa` crate (along with other RSA implementations) may be susceptible to
 //! [the "Marvin" timing side-channel attack](https://github.com/RustCrypto/RSA/security/advisories/GHSA-c38w-74pg-36hr)
 //! at the time of writing; use with caution.
@@ -46,7 +119,7 @@
 //! securely generated). These algs have 128-bit security, making them an alternative
 //! to `ES256`.
 //!
-//! RSA support requires a system-wide RNG retrieved via the [`getrandom`] crate.
+//! RSA signing requires a system-wide RNG retrieved via the [`getrandom`] crate.
 //! In case of a compilation failure in the `getrandom` crate, you may want
 //! to include it as a direct dependency and specify one of its features
 //! to assist `getrandom` with choosing an appropriate RNG implementation; consult `getrandom` docs
@@ -75,8 +148,11 @@
 //!   Without it, some [`TimeOptions`] constructors, such as the `Default` impl,
 //!   are not available. It is still possible to create `TimeOptions` with an explicitly specified
 //!   clock function, or to set / verify time-related [`Claims`] fields manually.
+//! - The `js-clock` feature (implies `clock`) makes the clock use `js_sys::Date::now()`
+//!   on the `wasm32-unknown-unknown` target, so that the default [`TimeOptions`] work
+//!   in browsers and other JS environments without further setup.
 //! - The `std` feature is propagated to the core dependencies and enables `std`-specific
-//!   functionality (such as error types implementing the standard `Error` trait).
+//!   functionality. Error types implement `core::error::Error` regardless of this feature.
 //!
 //! Some `alloc` types are still used in the `no_std` mode, such as `String`, `Vec` and `Cow`.
 //!
@@ -239,12 +315,62 @@
     clippy::module_name_repetitions
 )]
 
+#[cfg(feature = "derive")]
+#[doc(hidden)] // used by the `Claims` derive macro; not a part of the public API
+#[path = "private.rs"]
+pub mod __private;
+#[cfg(feature = "sign")]
+#[cfg_attr(docsrs, doc(cfg(feature = "sign")))]
+pub mod acme;
 pub mod alg;
+#[cfg(feature = "branca")]
+#[cfg_attr(docsrs, doc(cfg(feature = "branca")))]
+pub mod branca;
 mod claims;
 mod error;
+pub mod fmt;
+#[cfg(all(feature = "std", feature = "clock"))]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "std", feature = "clock"))))]
+pub mod integrations;
+#[cfg(feature = "interop")]
+#[cfg_attr(docsrs, doc(cfg(feature = "interop")))]
+pub mod interop;
 pub mod jwk;
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+pub mod key_set_cache;
+pub mod key_store;
+pub mod oauth;
+pub mod oidc;
+#[cfg(feature = "paseto")]
+#[cfg_attr(docsrs, doc(cfg(feature = "paseto")))]
+pub mod paseto;
+#[cfg(feature = "pem")]
+#[cfg_attr(docsrs, doc(cfg(feature = "pem")))]
+pub mod pem;
+pub mod presets;
+#[cfg(feature = "sign")]
+mod reissue;
+pub mod replay;
+pub mod rotation;
+pub mod sd_jwt;
+mod secret;
+pub mod set;
+#[cfg(all(test, feature = "hs"))]
+mod test_helpers;
+#[cfg(feature = "test-util")]
+#[cfg_attr(docsrs, doc(cfg(feature = "test-util")))]
+pub mod test_vectors;
+#[cfg(feature = "test-util")]
+#[cfg_attr(docsrs, doc(cfg(feature = "test-util")))]
+pub mod testing;
+mod timestamp;
 mod token;
+#[cfg(feature = "sign")]
+#[cfg_attr(docsrs, doc(cfg(feature = "sign")))]
+pub mod token_pair;
 mod traits;
+pub mod validation;
 
 // Polyfill for `alloc` types.
 mod alloc {
@@ -253,6 +379,8 @@ mod alloc {
 
     pub use std::{
         borrow::{Cow, ToOwned},
+        boxed::Box,
+        collections::{BTreeMap, BTreeSet},
         format,
         string::{String, ToString},
         vec::Vec,
@@ -266,11 +394,86 @@ pub mod prelude {
 }
 
 pub use crate::{
-    claims::{Claims, Empty, TimeOptions},
-    error::{Claim, CreationError, ParseError, ValidationError},
-    token::{Header, SignedToken, Thumbprint, Token, UntrustedToken},
-    traits::{Algorithm, AlgorithmExt, AlgorithmSignature, Renamed, Validator},
+    claims::{
+        Audience, Claims, ClaimsBuilder, DynamicClaims, Empty, RegisteredClaims, TimeOptions,
+    },
+    error::{Claim, CreationError, ErrorKind, ParseError, ValidationError},
+    secret::{constant_time_eq, Redact, SecretString},
+    timestamp::{Timestamp, TimestampOverflow},
+    token::{
+        Header, ParseOptions, SignedToken, Thumbprint, Token, TokenDraft, TokenString,
+        UntrustedToken, VerifiedToken,
+    },
+    traits::{
+        Algorithm, AlgorithmExt, AlgorithmSignature, AsyncAdapter, AsyncValidator, AsyncVerifier,
+        AsyncVerifierExt, PrepareVerifyingKey, PreparedVerifier, Renamed, UnsignedToken, Validator,
+        VerifyFuture, VerifyToken,
+    },
 };
+#[cfg(feature = "sign")]
+#[cfg_attr(docsrs, doc(cfg(feature = "sign")))]
+pub use crate::{
+    reissue::Reissue,
+    traits::{AsyncAlgorithm, AsyncAlgorithmExt, SignFuture, TokenFuture},
+};
+
+/// Derives `serde` (de)serialization and validation for custom claims.
+///
+/// The macro can be applied to non-generic structs with named fields. It generates
+/// [`Serialize`](serde::Serialize) and [`Deserialize`](serde::Deserialize) implementations,
+/// so the struct can be used as custom claims in [`Claims`](struct@Claims), and
+/// a `validate()` method returning a [`PresetError`](presets::PresetError) if a check fails.
+/// Fields are customized via `#[claim(..)]` attributes:
+///
+/// - `rename = "name"`: name of the claim in the token (by default, the field name).
+/// - `required`: the claim must be present. Only applies to `Option` fields; other fields
+///   are always required (their absence is a deserialization error). Absent `Option` fields
+///   are deserialized as `None` and skipped during serialization.
+/// - `issuer = "value"`: the claim (a string) must be equal to the specified value.
+/// - `audience = "value"`: the claim (a string, an array of strings or an [`Audience`])
+///   must contain the specified value.
+///
+/// # Examples
+///
+/// ```
+/// use jwt_compact::{prelude::*, alg::{Hs256, Hs256Key}, presets::PresetError};
+///
+/// #[derive(Debug, Clone, PartialEq, jwt_compact::Claims)]
+/// struct ApiClaims {
+///     #[claim(rename = "sub")]
+///     subject: String,
+///     #[claim(rename = "iss", issuer = "https://auth.example.com/")]
+///     issuer: String,
+///     #[claim(rename = "aud", required, audience = "api://orders")]
+///     audience: Option<Vec<String>>,
+///     scope: Option<String>,
+/// }
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let key = Hs256Key::new(b"super_secret_key_donut_steel");
+/// let claims = ApiClaims {
+///     subject: "alice".to_owned(),
+///     issuer: "https://auth.example.com/".to_owned(),
+///     audience: Some(vec!["api://orders".to_owned()]),
+///     scope: None,
+/// };
+/// let token = Hs256.token(&Header::empty(), &Claims::new(claims), &key)?;
+/// let token = UntrustedToken::new(&token)?;
+/// let token = Hs256.validator::<ApiClaims>(&key).validate(&token)?;
+/// token.claims().custom.validate()?;
+///
+/// let claims = ApiClaims {
+///     audience: None,
+///     ..token.claims().custom.clone()
+/// };
+/// let err = claims.validate().unwrap_err();
+/// assert!(matches!(err, PresetError::MissingClaim("aud")));
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "derive")]
+#[cfg_attr(docsrs, doc(cfg(feature = "derive")))]
+pub use jwt_compact_derive::Claims;
 
 #[cfg(doctest)]
 doc_comment::doctest!("../README.md");
@@ -0,0 +1,22 @@
+//! Helpers for JWT-based [OAuth 2.0] profiles.
+//!
+//! [OAuth 2.0]: https://www.rfc-editor.org/rfc/rfc6749.html
+
+mod client_assertion;
+mod jar;
+mod jarm;
+mod mtls;
+mod token_exchange;
+
+pub use self::{
+    client_assertion::{ClientAssertion, ClientAssertionClaims},
+    jar::{
+        RequestObject, RequestObjectClaims, RequestObjectError, RequestObjectValidator,
+        RequestUriResolver, REQUEST_OBJECT_TOKEN_TYPE,
+    },
+    jarm::{
+        AuthorizationResponseClaims, AuthorizationResponseError, AuthorizationResponseValidator,
+    },
+    mtls::{certificate_thumbprint, CertificateBindingError, Confirmation},
+    token_exchange::{Actor, DelegationClaims, DelegationError, DelegationPolicy},
+};
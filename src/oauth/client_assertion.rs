@@ -0,0 +1,204 @@
+//! JWT client assertions as per [RFC 7523].
+//!
+//! [RFC 7523]: https://www.rfc-editor.org/rfc/rfc7523.html
+
+use base64ct::{Base64UrlUnpadded, Encoding};
+use chrono::Duration;
+use rand_core::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    alloc::{String, ToOwned},
+    Claims, TimeOptions, Timestamp,
+};
+#[cfg(feature = "sign")]
+use crate::{Algorithm, AlgorithmExt, CreationError, Header, TokenString};
+
+/// Byte length of randomly generated JWT IDs.
+const JWT_ID_LEN: usize = 16;
+/// Default lifetime of client assertions.
+const DEFAULT_LIFETIME: Duration = Duration::minutes(5);
+
+/// Custom claims of a [`ClientAssertion`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct ClientAssertionClaims {
+    /// Issuer of the assertion (`iss`). Equal to the client ID.
+    #[serde(rename = "iss")]
+    pub issuer: String,
+    /// Subject of the assertion (`sub`). Equal to the client ID.
+    #[serde(rename = "sub")]
+    pub subject: String,
+    /// Audience of the assertion (`aud`), usually the token endpoint URL of
+    /// the authorization server.
+    #[serde(rename = "aud")]
+    pub audience: String,
+    /// Unique identifier of the assertion (`jti`) preventing its replay.
+    #[serde(rename = "jti")]
+    pub jwt_id: String,
+}
+
+/// Builder of client assertions used for client authentication in OAuth token requests
+/// (the `private_key_jwt` and `client_secret_jwt` methods) as per [RFC 7523].
+///
+/// The produced assertions have `iss` and `sub` claims set to the client ID, `aud` set
+/// to the token endpoint, a short-lived `exp` claim (5 minutes by default), `iat` claim and
+/// a random `jti` claim.
+///
+/// [RFC 7523]: https://www.rfc-editor.org/rfc/rfc7523.html#section-2.2
+///
+/// # Examples
+///
+/// ```
+/// # use rand::thread_rng;
+/// # use jwt_compact::{alg::{Hs256, Hs256Key}, oauth::ClientAssertion, prelude::*};
+/// # fn main() -> anyhow::Result<()> {
+/// let key = Hs256Key::new(b"super_secret_key_donut_steel");
+/// let assertion = ClientAssertion::new("my-client", "https://auth.example.com/token");
+/// let token = assertion.sign(
+///     &Hs256,
+///     &Header::empty().with_key_id("my-key"),
+///     &key,
+///     &TimeOptions::default(),
+///     &mut thread_rng(),
+/// )?;
+///
+/// // Form parameters to include into the token request.
+/// let params = ClientAssertion::form_params(&token);
+/// assert_eq!(params[0].1, ClientAssertion::ASSERTION_TYPE);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ClientAssertion {
+    client_id: String,
+    audience: String,
+    lifetime: Duration,
+}
+
+impl ClientAssertion {
+    /// Value of the `client_assertion_type` form parameter for JWT client assertions.
+    pub const ASSERTION_TYPE: &'static str =
+        "urn:ietf:params:oauth:client-assertion-type:jwt-bearer";
+
+    /// Creates a builder for the specified client ID and the token endpoint
+    /// of the authorization server.
+    pub fn new(client_id: impl Into<String>, token_endpoint: impl Into<String>) -> Self {
+        Self {
+            client_id: client_id.into(),
+            audience: token_endpoint.into(),
+            lifetime: DEFAULT_LIFETIME,
+        }
+    }
+
+    /// Sets the lifetime of produced assertions.
+    #[must_use]
+    pub fn with_lifetime(mut self, lifetime: Duration) -> Self {
+        self.lifetime = lifetime;
+        self
+    }
+
+    /// Creates assertion claims with the specified JWT ID.
+    pub fn claims_with_id<F>(
+        &self,
+        options: &TimeOptions<F>,
+        jwt_id: impl Into<String>,
+    ) -> Claims<ClientAssertionClaims>
+    where
+        F: Fn() -> Timestamp,
+    {
+        let custom = ClientAssertionClaims {
+            issuer: self.client_id.clone(),
+            subject: self.client_id.clone(),
+            audience: self.audience.clone(),
+            jwt_id: jwt_id.into(),
+        };
+        Claims::new(custom).set_duration_and_issuance(options, self.lifetime)
+    }
+
+    /// Creates assertion claims with a random JWT ID generated using the provided RNG.
+    pub fn claims<F>(
+        &self,
+        options: &TimeOptions<F>,
+        rng: &mut (impl CryptoRng + RngCore),
+    ) -> Claims<ClientAssertionClaims>
+    where
+        F: Fn() -> Timestamp,
+    {
+        let mut jwt_id = [0_u8; JWT_ID_LEN];
+        rng.fill_bytes(&mut jwt_id);
+        self.claims_with_id(options, Base64UrlUnpadded::encode_string(&jwt_id))
+    }
+
+    /// Creates and signs an assertion.
+    #[cfg(feature = "sign")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "sign")))]
+    pub fn sign<A: Algorithm, F>(
+        &self,
+        algorithm: &A,
+        header: &Header<impl Serialize>,
+        signing_key: &A::SigningKey,
+        options: &TimeOptions<F>,
+        rng: &mut (impl CryptoRng + RngCore),
+    ) -> Result<TokenString, CreationError>
+    where
+        F: Fn() -> Timestamp,
+    {
+        let claims = self.claims(options, rng);
+        algorithm.token(header, &claims, signing_key)
+    }
+
+    /// Returns form parameters (`client_assertion_type` and `client_assertion`)
+    /// authenticating the client in a token request.
+    pub fn form_params(assertion: &str) -> [(&'static str, String); 2] {
+        [
+            ("client_assertion_type", Self::ASSERTION_TYPE.to_owned()),
+            ("client_assertion", assertion.to_owned()),
+        ]
+    }
+}
+
+#[cfg(all(test, feature = "clock"))]
+mod tests {
+    use rand::thread_rng;
+
+    use super::*;
+    use crate::{
+        alg::{Hs256, Hs256Key},
+        UntrustedToken,
+    };
+
+    #[test]
+    fn creating_client_assertion() {
+        let key = Hs256Key::new(b"super_secret_key_donut_steel");
+        let options = TimeOptions::default();
+        let assertion = ClientAssertion::new("client", "https://example.com/token")
+            .with_lifetime(Duration::try_seconds(60).unwrap());
+        let token = assertion
+            .sign(&Hs256, &Header::empty(), &key, &options, &mut thread_rng())
+            .unwrap();
+
+        let token = UntrustedToken::new(&token).unwrap();
+        let token = Hs256
+            .validator::<ClientAssertionClaims>(&key)
+            .validate(&token)
+            .unwrap();
+        let claims = token.claims();
+        assert_eq!(claims.custom.issuer, "client");
+        assert_eq!(claims.custom.subject, "client");
+        assert_eq!(claims.custom.audience, "https://example.com/token");
+        assert_eq!(claims.custom.jwt_id.len(), 22);
+        let lifetime = claims.expiration.unwrap() - claims.issued_at.unwrap();
+        assert_eq!(lifetime, Duration::try_seconds(60).unwrap());
+        claims.validate_expiration(&options).unwrap();
+    }
+
+    #[test]
+    fn client_assertion_ids_are_unique() {
+        let assertion = ClientAssertion::new("client", "https://example.com/token");
+        let options = TimeOptions::default();
+        let first = assertion.claims(&options, &mut thread_rng());
+        let second = assertion.claims(&options, &mut thread_rng());
+        assert_ne!(first.custom.jwt_id, second.custom.jwt_id);
+    }
+}
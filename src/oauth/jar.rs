@@ -0,0 +1,542 @@
+//! JWT-secured authorization requests (JAR) as per [RFC 9101].
+//!
+//! [RFC 9101]: https://www.rfc-editor.org/rfc/rfc9101.html
+
+use base64ct::{Base64UrlUnpadded, Encoding};
+use chrono::Duration;
+use rand_core::{CryptoRng, RngCore};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use core::fmt;
+
+use crate::{
+    alloc::{String, ToOwned, Vec},
+    claims::serde_audience,
+    Algorithm, AlgorithmExt, Claims, ParseError, TimeOptions, Timestamp, Token, UntrustedToken,
+    ValidationError,
+};
+#[cfg(feature = "sign")]
+use crate::{CreationError, Header, TokenString};
+
+/// Token type (`typ`) of request objects.
+pub const REQUEST_OBJECT_TOKEN_TYPE: &str = "oauth-authz-req+jwt";
+
+/// Byte length of randomly generated JWT IDs.
+const JWT_ID_LEN: usize = 16;
+/// Default lifetime of request objects.
+const DEFAULT_LIFETIME: Duration = Duration::minutes(5);
+/// Authorization request parameters that must not be present in request objects.
+const FORBIDDEN_PARAMS: &[&str] = &["request", "request_uri"];
+
+/// Claims of a request object.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct RequestObjectClaims<T> {
+    /// Issuer of the request object (`iss`). Equal to the client ID.
+    #[serde(rename = "iss")]
+    pub issuer: String,
+    /// Audience of the request object (`aud`), i.e., the issuer identifier
+    /// of the authorization server.
+    #[serde(rename = "aud", with = "serde_audience")]
+    pub audience: Vec<String>,
+    /// Client ID.
+    pub client_id: String,
+    /// Unique identifier of the request object (`jti`).
+    #[serde(rename = "jti", default, skip_serializing_if = "Option::is_none")]
+    pub jwt_id: Option<String>,
+    /// Authorization request parameters, such as `response_type`, `redirect_uri`
+    /// and `scope`.
+    #[serde(flatten)]
+    pub params: T,
+}
+
+/// Builder of request objects used by clients.
+///
+/// The produced request objects have the `iss` and `client_id` claims set to the client ID,
+/// `aud` set to the issuer identifier of the authorization server, a short-lived `exp` claim
+/// (5 minutes by default), `iat` and `nbf` claims and a random `jti` claim. The `typ` header
+/// field is set to [`REQUEST_OBJECT_TOKEN_TYPE`].
+///
+/// # Examples
+///
+/// ```
+/// # use rand::thread_rng;
+/// # use jwt_compact::{alg::{Hs256, Hs256Key}, prelude::*};
+/// use jwt_compact::oauth::RequestObject;
+/// # use serde_json::json;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// # let key = Hs256Key::new(b"super_secret_key_donut_steel");
+/// let request = RequestObject::new("s6BhdRkqt3", "https://server.example.com");
+/// let params = json!({
+///     "response_type": "code",
+///     "redirect_uri": "https://client.example.org/cb",
+///     "scope": "openid",
+///     "state": "af0ifjsldkj",
+/// });
+/// let request_object = request.sign(
+///     &Hs256,
+///     &Header::empty(),
+///     &key,
+///     &params,
+///     &TimeOptions::default(),
+///     &mut thread_rng(),
+/// )?;
+/// // Query parameters to include into the authorization request URL.
+/// let query = request.query_params(&request_object);
+/// assert_eq!(query[0], ("client_id", "s6BhdRkqt3".to_owned()));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct RequestObject {
+    client_id: String,
+    audience: String,
+    lifetime: Duration,
+}
+
+impl RequestObject {
+    /// Creates a builder for the specified client ID and the issuer identifier
+    /// of the authorization server.
+    pub fn new(client_id: impl Into<String>, issuer: impl Into<String>) -> Self {
+        Self {
+            client_id: client_id.into(),
+            audience: issuer.into(),
+            lifetime: DEFAULT_LIFETIME,
+        }
+    }
+
+    /// Sets the lifetime of produced request objects.
+    #[must_use]
+    pub fn with_lifetime(mut self, lifetime: Duration) -> Self {
+        self.lifetime = lifetime;
+        self
+    }
+
+    /// Creates request object claims with the specified authorization request parameters
+    /// and a random JWT ID generated using the provided RNG.
+    pub fn claims<T, F>(
+        &self,
+        params: T,
+        options: &TimeOptions<F>,
+        rng: &mut (impl CryptoRng + RngCore),
+    ) -> Claims<RequestObjectClaims<T>>
+    where
+        F: Fn() -> Timestamp,
+    {
+        let mut jwt_id = [0_u8; JWT_ID_LEN];
+        rng.fill_bytes(&mut jwt_id);
+        let custom = RequestObjectClaims {
+            issuer: self.client_id.clone(),
+            audience: Vec::from([self.audience.clone()]),
+            client_id: self.client_id.clone(),
+            jwt_id: Some(Base64UrlUnpadded::encode_string(&jwt_id)),
+            params,
+        };
+        let mut claims = Claims::new(custom).set_duration_and_issuance(options, self.lifetime);
+        claims.not_before = claims.issued_at;
+        claims
+    }
+
+    /// Creates and signs a request object.
+    #[cfg(feature = "sign")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "sign")))]
+    pub fn sign<A: Algorithm, T: Serialize, F>(
+        &self,
+        algorithm: &A,
+        header: &Header<impl Serialize + Clone>,
+        signing_key: &A::SigningKey,
+        params: &T,
+        options: &TimeOptions<F>,
+        rng: &mut (impl CryptoRng + RngCore),
+    ) -> Result<TokenString, CreationError>
+    where
+        F: Fn() -> Timestamp,
+    {
+        let header = header.clone().with_token_type(REQUEST_OBJECT_TOKEN_TYPE);
+        let claims = self.claims(params, options, rng);
+        algorithm.token(&header, &claims, signing_key)
+    }
+
+    /// Returns query parameters (`client_id` and `request`) of the authorization request
+    /// passing the request object by value.
+    pub fn query_params(&self, request_object: &str) -> [(&'static str, String); 2] {
+        [
+            ("client_id", self.client_id.clone()),
+            ("request", request_object.to_owned()),
+        ]
+    }
+}
+
+/// Hook fetching request objects passed by reference (via the `request_uri` parameter).
+///
+/// The implementation is responsible for allowing only trusted URIs (e.g., ones registered
+/// for the client or issued by the pushed authorization request endpoint) to prevent
+/// server-side request forgery. The hook is implemented for closures.
+pub trait RequestUriResolver {
+    /// Returns the request object for the specified `request_uri`, or `None` if it cannot
+    /// be resolved.
+    fn resolve(&mut self, request_uri: &str) -> Option<String>;
+}
+
+impl<F> RequestUriResolver for F
+where
+    F: FnMut(&str) -> Option<String>,
+{
+    fn resolve(&mut self, request_uri: &str) -> Option<String> {
+        self(request_uri)
+    }
+}
+
+/// Validator of request objects used by authorization servers.
+///
+/// Besides the signature, the validator checks that:
+///
+/// - The `typ` header field, if present, is equal to [`REQUEST_OBJECT_TOKEN_TYPE`].
+///   The field can be made mandatory via [`Self::require_token_type()`].
+/// - The `iss` and `client_id` claims are equal to the `client_id` parameter
+///   of the authorization request.
+/// - The `aud` claim contains the issuer identifier of the authorization server.
+/// - The `exp` claim is present and the request object is not expired. If the `nbf` claim
+///   is present, the request object must be mature.
+/// - The request object does not contain `request` or `request_uri` parameters.
+///
+/// # Examples
+///
+/// ```
+/// # use rand::thread_rng;
+/// # use jwt_compact::{alg::{Hs256, Hs256Key}, prelude::*};
+/// use jwt_compact::oauth::{RequestObject, RequestObjectValidator};
+/// # use serde::Deserialize;
+/// # use serde_json::json;
+///
+/// #[derive(Debug, Deserialize)]
+/// struct AuthorizationParams {
+///     response_type: String,
+///     redirect_uri: String,
+///     state: Option<String>,
+/// }
+///
+/// # fn main() -> anyhow::Result<()> {
+/// # let key = Hs256Key::new(b"super_secret_key_donut_steel");
+/// # let options = TimeOptions::default();
+/// # let request_object = RequestObject::new("s6BhdRkqt3", "https://server.example.com").sign(
+/// #     &Hs256,
+/// #     &Header::empty(),
+/// #     &key,
+/// #     &json!({ "response_type": "code", "redirect_uri": "https://client.example.org/cb" }),
+/// #     &options,
+/// #     &mut thread_rng(),
+/// # )?;
+/// let validator = RequestObjectValidator::new("https://server.example.com");
+/// let request_uri = "urn:ietf:params:oauth:request_uri:6esc_11ACC5bwc014ltc14eY22c";
+/// let resolver = |uri: &str| (uri == request_uri).then(|| request_object.to_string());
+/// let token = validator.validate_request_uri::<_, AuthorizationParams, _>(
+///     &Hs256,
+///     &key,
+///     "s6BhdRkqt3",
+///     request_uri,
+///     resolver,
+///     &options,
+/// )?;
+/// assert_eq!(token.claims().custom.params.response_type, "code");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct RequestObjectValidator {
+    issuer: String,
+    require_token_type: bool,
+}
+
+impl RequestObjectValidator {
+    /// Creates a validator with the issuer identifier of the authorization server.
+    pub fn new(issuer: impl Into<String>) -> Self {
+        Self {
+            issuer: issuer.into(),
+            require_token_type: false,
+        }
+    }
+
+    /// Requires the `typ` header field to be present.
+    #[must_use]
+    pub fn require_token_type(mut self) -> Self {
+        self.require_token_type = true;
+        self
+    }
+
+    /// Validates the request object passed by value (via the `request` parameter).
+    /// `client_id` is the value of the corresponding authorization request parameter.
+    pub fn validate<A: Algorithm, T: DeserializeOwned, F>(
+        &self,
+        algorithm: &A,
+        verifying_key: &A::VerifyingKey,
+        client_id: &str,
+        token: &UntrustedToken<'_>,
+        options: &TimeOptions<F>,
+    ) -> Result<Token<RequestObjectClaims<T>>, RequestObjectError>
+    where
+        F: Fn() -> Timestamp,
+    {
+        match token.header().token_type.as_deref() {
+            Some(REQUEST_OBJECT_TOKEN_TYPE) => { /* OK */ }
+            None if !self.require_token_type => { /* OK */ }
+            _ => return Err(RequestObjectError::InvalidTokenType),
+        }
+        let (_, claims) = algorithm
+            .validator::<Map<String, Value>>(verifying_key)
+            .validate(token)?
+            .into_parts();
+        for &param in FORBIDDEN_PARAMS {
+            if claims.custom.contains_key(param) {
+                return Err(RequestObjectError::ForbiddenClaim(param));
+            }
+        }
+        let custom = RequestObjectClaims::<T>::deserialize(Value::Object(claims.custom))
+            .map_err(ValidationError::MalformedClaims)?;
+        let claims = Claims {
+            expiration: claims.expiration,
+            not_before: claims.not_before,
+            issued_at: claims.issued_at,
+            custom,
+        };
+
+        if claims.expiration.is_none() {
+            return Err(RequestObjectError::MissingClaim("exp"));
+        }
+        claims.validate_expiration(options)?;
+        if claims.not_before.is_some() {
+            claims.validate_maturity(options)?;
+        }
+
+        let custom = &claims.custom;
+        if custom.issuer != client_id {
+            return Err(RequestObjectError::ClaimMismatch("iss"));
+        }
+        if custom.client_id != client_id {
+            return Err(RequestObjectError::ClaimMismatch("client_id"));
+        }
+        if !custom.audience.contains(&self.issuer) {
+            return Err(RequestObjectError::ClaimMismatch("aud"));
+        }
+        Ok(Token::new(token, claims))
+    }
+
+    /// Resolves the request object passed by reference (via the `request_uri` parameter)
+    /// using the provided `resolver` and validates it.
+    pub fn validate_request_uri<A: Algorithm, T: DeserializeOwned, F>(
+        &self,
+        algorithm: &A,
+        verifying_key: &A::VerifyingKey,
+        client_id: &str,
+        request_uri: &str,
+        mut resolver: impl RequestUriResolver,
+        options: &TimeOptions<F>,
+    ) -> Result<Token<RequestObjectClaims<T>>, RequestObjectError>
+    where
+        F: Fn() -> Timestamp,
+    {
+        let request_object = resolver
+            .resolve(request_uri)
+            .ok_or(RequestObjectError::UnresolvedRequestUri)?;
+        let token = UntrustedToken::new(&request_object).map_err(RequestObjectError::Parse)?;
+        self.validate(algorithm, verifying_key, client_id, &token, options)
+    }
+}
+
+/// Errors that can occur during request object validation.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum RequestObjectError {
+    /// Request object has failed generic validation (e.g., has an invalid signature).
+    Validation(ValidationError),
+    /// The `typ` header field is missing or has an unexpected value.
+    InvalidTokenType,
+    /// Required claim is missing.
+    MissingClaim(&'static str),
+    /// Claim has an unexpected value.
+    ClaimMismatch(&'static str),
+    /// Claim that must not be present in request objects is present.
+    ForbiddenClaim(&'static str),
+    /// The `request_uri` could not be resolved.
+    UnresolvedRequestUri,
+    /// Request object resolved from the `request_uri` is not a well-formed JWT.
+    Parse(ParseError),
+}
+
+impl fmt::Display for RequestObjectError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Validation(err) => write!(formatter, "request object validation failed: {err}"),
+            Self::InvalidTokenType => formatter.write_str("token is not a request object"),
+            Self::MissingClaim(claim) => write!(formatter, "claim `{claim}` is missing"),
+            Self::ClaimMismatch(claim) => write!(formatter, "claim `{claim}` has unexpected value"),
+            Self::ForbiddenClaim(claim) => {
+                write!(
+                    formatter,
+                    "claim `{claim}` must not be present in request objects"
+                )
+            }
+            Self::UnresolvedRequestUri => formatter.write_str("request URI cannot be resolved"),
+            Self::Parse(err) => write!(formatter, "malformed request object: {err}"),
+        }
+    }
+}
+
+impl core::error::Error for RequestObjectError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::Validation(err) => Some(err),
+            Self::Parse(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<ValidationError> for RequestObjectError {
+    fn from(err: ValidationError) -> Self {
+        Self::Validation(err)
+    }
+}
+
+#[cfg(all(test, feature = "clock"))]
+mod tests {
+    use assert_matches::assert_matches;
+    use rand::thread_rng;
+    use serde_json::json;
+
+    use super::*;
+    use crate::{alg::Hs256, test_helpers::key};
+
+    const ISSUER: &str = "https://server.example.com";
+    const CLIENT_ID: &str = "s6BhdRkqt3";
+
+    fn params() -> Value {
+        json!({
+            "response_type": "code",
+            "redirect_uri": "https://client.example.org/cb",
+            "state": "af0ifjsldkj",
+        })
+    }
+
+    fn validate(
+        request_object: &str,
+    ) -> Result<Token<RequestObjectClaims<Value>>, RequestObjectError> {
+        let token = UntrustedToken::new(request_object).unwrap();
+        RequestObjectValidator::new(ISSUER)
+            .require_token_type()
+            .validate(&Hs256, &key(), CLIENT_ID, &token, &TimeOptions::default())
+    }
+
+    #[test]
+    fn creating_and_validating_request_object() {
+        let request = RequestObject::new(CLIENT_ID, ISSUER);
+        let options = TimeOptions::default();
+        let request_object = request
+            .sign(
+                &Hs256,
+                &Header::empty(),
+                &key(),
+                &params(),
+                &options,
+                &mut thread_rng(),
+            )
+            .unwrap();
+
+        let token = validate(&request_object).unwrap();
+        assert_eq!(
+            token.header().token_type.as_deref(),
+            Some(REQUEST_OBJECT_TOKEN_TYPE)
+        );
+        let claims = token.claims();
+        assert_eq!(claims.custom.issuer, CLIENT_ID);
+        assert_eq!(claims.custom.audience, [ISSUER]);
+        assert_eq!(claims.custom.params, params());
+        assert!(claims.not_before.is_some());
+
+        let query = request.query_params(&request_object);
+        assert_eq!(query[1], ("request", request_object.into_string()));
+    }
+
+    #[test]
+    fn invalid_request_objects() {
+        let options = TimeOptions::default();
+        let sign =
+            |claims: &Claims<Value>, header: &Header| Hs256.token(header, claims, &key()).unwrap();
+        let header = Header::empty().with_token_type(REQUEST_OBJECT_TOKEN_TYPE);
+        let request = RequestObject::new(CLIENT_ID, ISSUER);
+        let claims = request.claims(params(), &options, &mut thread_rng());
+        let claims = Claims {
+            expiration: claims.expiration,
+            not_before: claims.not_before,
+            issued_at: claims.issued_at,
+            custom: serde_json::to_value(&claims.custom).unwrap(),
+        };
+
+        let err = validate(&sign(&claims, &Header::empty())).unwrap_err();
+        assert_matches!(err, RequestObjectError::InvalidTokenType);
+
+        let mut with_request_uri = claims.clone();
+        with_request_uri.custom["request_uri"] = "https://client.example.org/request".into();
+        let err = validate(&sign(&with_request_uri, &header)).unwrap_err();
+        assert_matches!(err, RequestObjectError::ForbiddenClaim("request_uri"));
+
+        let mut other_client = claims.clone();
+        other_client.custom["client_id"] = "other".into();
+        let err = validate(&sign(&other_client, &header)).unwrap_err();
+        assert_matches!(err, RequestObjectError::ClaimMismatch("client_id"));
+
+        let mut other_audience = claims.clone();
+        other_audience.custom["aud"] = "https://other.example.com".into();
+        let err = validate(&sign(&other_audience, &header)).unwrap_err();
+        assert_matches!(err, RequestObjectError::ClaimMismatch("aud"));
+
+        let mut without_expiration = claims;
+        without_expiration.expiration = None;
+        let err = validate(&sign(&without_expiration, &header)).unwrap_err();
+        assert_matches!(err, RequestObjectError::MissingClaim("exp"));
+    }
+
+    #[test]
+    fn resolving_request_uri() {
+        let options = TimeOptions::default();
+        let request_object = RequestObject::new(CLIENT_ID, ISSUER)
+            .sign(
+                &Hs256,
+                &Header::empty(),
+                &key(),
+                &params(),
+                &options,
+                &mut thread_rng(),
+            )
+            .unwrap();
+        let request_uri = "urn:ietf:params:oauth:request_uri:abc";
+        let validator = RequestObjectValidator::new(ISSUER);
+
+        let resolver = |uri: &str| (uri == request_uri).then(|| request_object.to_string());
+        let token = validator
+            .validate_request_uri::<_, Value, _>(
+                &Hs256,
+                &key(),
+                CLIENT_ID,
+                request_uri,
+                resolver,
+                &options,
+            )
+            .unwrap();
+        assert_eq!(token.claims().custom.params, params());
+
+        let err = validator
+            .validate_request_uri::<_, Value, _>(
+                &Hs256,
+                &key(),
+                CLIENT_ID,
+                "urn:ietf:params:oauth:request_uri:other",
+                resolver,
+                &options,
+            )
+            .unwrap_err();
+        assert_matches!(err, RequestObjectError::UnresolvedRequestUri);
+    }
+}
@@ -0,0 +1,283 @@
+//! JWT-secured authorization response mode (JARM) as per [the JARM specification].
+//!
+//! [the JARM specification]: https://openid.net/specs/oauth-v2-jarm.html
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use core::fmt;
+
+use crate::{
+    alloc::{String, Vec},
+    claims::serde_audience,
+    constant_time_eq, Algorithm, AlgorithmExt, TimeOptions, Timestamp, Token, UntrustedToken,
+    ValidationError,
+};
+
+/// Claims of a JWT-secured authorization response.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct AuthorizationResponseClaims {
+    /// Issuer of the response (`iss`), i.e., the authorization server.
+    #[serde(rename = "iss")]
+    pub issuer: String,
+    /// Intended recipients of the response (`aud`), i.e., the client ID.
+    #[serde(rename = "aud", with = "serde_audience")]
+    pub audience: Vec<String>,
+    /// Authorization code, present in successful responses of the authorization code flow.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    /// State passed by the client in the authorization request.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+    /// Error code, present in error responses.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Human-readable error description.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_description: Option<String>,
+    /// Other response parameters, e.g., `access_token` in the implicit flow.
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+impl AuthorizationResponseClaims {
+    /// Extracts the authorization code from the response, checking that the response
+    /// is not an error response and that its `state` matches the one sent in
+    /// the authorization request.
+    pub fn into_code(
+        self,
+        expected_state: Option<&str>,
+    ) -> Result<String, AuthorizationResponseError> {
+        let is_state_matching = match (self.state.as_deref(), expected_state) {
+            (Some(state), Some(expected)) => {
+                constant_time_eq(state.as_bytes(), expected.as_bytes())
+            }
+            (None, None) => true,
+            _ => false,
+        };
+        if !is_state_matching {
+            return Err(AuthorizationResponseError::StateMismatch);
+        }
+        if let Some(error) = self.error {
+            return Err(AuthorizationResponseError::Denied {
+                error,
+                description: self.error_description,
+            });
+        }
+        self.code
+            .ok_or(AuthorizationResponseError::MissingClaim("code"))
+    }
+}
+
+/// Validator of JWT-secured authorization responses used by clients.
+///
+/// Besides the signature, the validator checks that the `iss` claim matches the expected issuer,
+/// the `aud` claim contains the client ID, and the `exp` claim is present and the response
+/// is not expired. Encrypted responses are not supported.
+///
+/// # Examples
+///
+/// ```
+/// # use chrono::Duration;
+/// # use jwt_compact::{alg::{Hs256, Hs256Key}, prelude::*};
+/// use jwt_compact::oauth::AuthorizationResponseValidator;
+/// # use serde_json::json;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// # let key = Hs256Key::new(b"super_secret_key_donut_steel");
+/// # let options = TimeOptions::default();
+/// # let claims = json!({
+/// #     "iss": "https://accounts.example.com",
+/// #     "aud": "s6BhdRkqt3",
+/// #     "code": "PyyFaux2o7Q0YfXBU32jhw.5FXSQpvr8akv9CeRDSd0QA",
+/// #     "state": "S8NJ7uqk5fY4EjNvP_G_FtyJu6pUsvH9jsYni9dMAJw",
+/// # });
+/// # let claims = Claims::new(claims).set_duration(&options, Duration::try_minutes(10).unwrap());
+/// # let response = Hs256.token(&Header::empty(), &claims, &key)?;
+/// let validator =
+///     AuthorizationResponseValidator::new("https://accounts.example.com", "s6BhdRkqt3");
+/// let response = UntrustedToken::new(&response)?;
+/// let response = validator.validate(&Hs256, &key, &response, &options)?;
+/// let code = response
+///     .into_parts()
+///     .1
+///     .custom
+///     .into_code(Some("S8NJ7uqk5fY4EjNvP_G_FtyJu6pUsvH9jsYni9dMAJw"))?;
+/// // Exchange `code` for tokens...
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct AuthorizationResponseValidator {
+    issuer: String,
+    client_id: String,
+}
+
+impl AuthorizationResponseValidator {
+    /// Creates a validator with the expected issuer and the client ID.
+    pub fn new(issuer: impl Into<String>, client_id: impl Into<String>) -> Self {
+        Self {
+            issuer: issuer.into(),
+            client_id: client_id.into(),
+        }
+    }
+
+    /// Validates the authorization response, i.e., the value of the `response` parameter
+    /// passed to the redirect URI.
+    pub fn validate<A: Algorithm, F>(
+        &self,
+        algorithm: &A,
+        verifying_key: &A::VerifyingKey,
+        token: &UntrustedToken<'_>,
+        options: &TimeOptions<F>,
+    ) -> Result<Token<AuthorizationResponseClaims>, AuthorizationResponseError>
+    where
+        F: Fn() -> Timestamp,
+    {
+        let token = algorithm
+            .validator::<AuthorizationResponseClaims>(verifying_key)
+            .validate(token)?;
+        let claims = token.claims();
+        if claims.expiration.is_none() {
+            return Err(AuthorizationResponseError::MissingClaim("exp"));
+        }
+        claims.validate_expiration(options)?;
+
+        if claims.custom.issuer != self.issuer {
+            return Err(AuthorizationResponseError::ClaimMismatch("iss"));
+        }
+        if !claims.custom.audience.contains(&self.client_id) {
+            return Err(AuthorizationResponseError::ClaimMismatch("aud"));
+        }
+        Ok(token)
+    }
+}
+
+/// Errors that can occur during validation of authorization responses.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum AuthorizationResponseError {
+    /// Response has failed generic validation (e.g., has an invalid signature).
+    Validation(ValidationError),
+    /// Required claim is missing.
+    MissingClaim(&'static str),
+    /// Claim has an unexpected value.
+    ClaimMismatch(&'static str),
+    /// The `state` claim differs from the one sent in the authorization request.
+    StateMismatch,
+    /// The response is an error response.
+    Denied {
+        /// Error code (e.g., `access_denied`).
+        error: String,
+        /// Human-readable error description.
+        description: Option<String>,
+    },
+}
+
+impl fmt::Display for AuthorizationResponseError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Validation(err) => write!(formatter, "response validation failed: {err}"),
+            Self::MissingClaim(claim) => write!(formatter, "claim `{claim}` is missing"),
+            Self::ClaimMismatch(claim) => write!(formatter, "claim `{claim}` has unexpected value"),
+            Self::StateMismatch => formatter.write_str("`state` claim has unexpected value"),
+            Self::Denied { error, description } => {
+                write!(formatter, "authorization failed: {error}")?;
+                if let Some(description) = description {
+                    write!(formatter, " ({description})")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl core::error::Error for AuthorizationResponseError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::Validation(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<ValidationError> for AuthorizationResponseError {
+    fn from(err: ValidationError) -> Self {
+        Self::Validation(err)
+    }
+}
+
+#[cfg(all(test, feature = "clock"))]
+mod tests {
+    use assert_matches::assert_matches;
+    use chrono::Duration;
+    use serde_json::json;
+
+    use super::*;
+    use crate::{alg::Hs256, test_helpers::key, Claims, Header};
+
+    const ISSUER: &str = "https://accounts.example.com";
+    const CLIENT_ID: &str = "s6BhdRkqt3";
+
+    fn validate(
+        claims: Value,
+        expires: bool,
+    ) -> Result<AuthorizationResponseClaims, AuthorizationResponseError> {
+        let options = TimeOptions::default();
+        let mut claims = Claims::new(claims);
+        if expires {
+            claims = claims.set_duration(&options, Duration::try_minutes(10).unwrap());
+        }
+        let token = Hs256.token(&Header::empty(), &claims, &key()).unwrap();
+        let token = UntrustedToken::new(&token).unwrap();
+        let token = AuthorizationResponseValidator::new(ISSUER, CLIENT_ID).validate(
+            &Hs256,
+            &key(),
+            &token,
+            &options,
+        )?;
+        Ok(token.into_parts().1.custom)
+    }
+
+    #[test]
+    fn successful_response() {
+        let claims = json!({ "iss": ISSUER, "aud": CLIENT_ID, "code": "abc", "state": "xyz" });
+        let response = validate(claims.clone(), true).unwrap();
+        assert_eq!(response.code.as_deref(), Some("abc"));
+        assert_eq!(response.clone().into_code(Some("xyz")).unwrap(), "abc");
+        let err = response.into_code(Some("other")).unwrap_err();
+        assert_matches!(err, AuthorizationResponseError::StateMismatch);
+
+        let err = validate(claims, false).unwrap_err();
+        assert_matches!(err, AuthorizationResponseError::MissingClaim("exp"));
+    }
+
+    #[test]
+    fn error_response() {
+        let claims = json!({
+            "iss": ISSUER,
+            "aud": CLIENT_ID,
+            "error": "access_denied",
+            "state": "xyz",
+        });
+        let response = validate(claims, true).unwrap();
+        let err = response.into_code(Some("xyz")).unwrap_err();
+        assert_matches!(
+            err,
+            AuthorizationResponseError::Denied { error, description: None }
+                if error == "access_denied"
+        );
+    }
+
+    #[test]
+    fn response_with_invalid_claims() {
+        let claims = json!({ "iss": "https://evil.example.com", "aud": CLIENT_ID, "code": "abc" });
+        let err = validate(claims, true).unwrap_err();
+        assert_matches!(err, AuthorizationResponseError::ClaimMismatch("iss"));
+
+        let claims = json!({ "iss": ISSUER, "aud": "other", "code": "abc" });
+        let err = validate(claims, true).unwrap_err();
+        assert_matches!(err, AuthorizationResponseError::ClaimMismatch("aud"));
+    }
+}
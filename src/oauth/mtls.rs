@@ -0,0 +1,154 @@
+//! Certificate-bound access tokens as per [RFC 8705].
+//!
+//! [RFC 8705]: https://www.rfc-editor.org/rfc/rfc8705.html
+
+use base64ct::{Base64UrlUnpadded, Encoding};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use core::fmt;
+
+use crate::{alloc::String, constant_time_eq};
+
+/// Computes the `x5t#S256` thumbprint of a DER-encoded X.509 certificate, i.e.,
+/// the base64url-encoded SHA-256 digest of the certificate.
+pub fn certificate_thumbprint(certificate_der: &[u8]) -> String {
+    Base64UrlUnpadded::encode_string(&Sha256::digest(certificate_der))
+}
+
+/// Confirmation claim (`cnf`) binding an access token to the client certificate
+/// as per [RFC 8705, Section 3.1].
+///
+/// [RFC 8705, Section 3.1]: https://www.rfc-editor.org/rfc/rfc8705.html#section-3.1
+///
+/// # Examples
+///
+/// ```
+/// # use serde::{Deserialize, Serialize};
+/// use jwt_compact::oauth::Confirmation;
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct AccessTokenClaims {
+///     #[serde(rename = "cnf", default)]
+///     confirmation: Option<Confirmation>,
+///     // other claims...
+/// }
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let certificate_der = b"<DER-encoded client certificate>";
+/// let claims = AccessTokenClaims {
+///     confirmation: Some(Confirmation::for_certificate(certificate_der)),
+/// };
+/// // Later, on the resource server:
+/// Confirmation::verify_binding(claims.confirmation.as_ref(), certificate_der)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct Confirmation {
+    /// Base64url-encoded SHA-256 thumbprint of the client certificate (`x5t#S256`).
+    #[serde(rename = "x5t#S256", default, skip_serializing_if = "Option::is_none")]
+    pub certificate_thumbprint: Option<String>,
+}
+
+impl Confirmation {
+    /// Creates a confirmation binding the token to the DER-encoded client certificate.
+    pub fn for_certificate(certificate_der: &[u8]) -> Self {
+        Self {
+            certificate_thumbprint: Some(certificate_thumbprint(certificate_der)),
+        }
+    }
+
+    /// Verifies that the token is bound to the DER-encoded client certificate presented
+    /// during the TLS handshake. The thumbprints are compared in constant time.
+    pub fn verify_certificate(
+        &self,
+        certificate_der: &[u8],
+    ) -> Result<(), CertificateBindingError> {
+        let expected = self
+            .certificate_thumbprint
+            .as_deref()
+            .ok_or(CertificateBindingError::Missing)?;
+        let actual = certificate_thumbprint(certificate_der);
+        if constant_time_eq(expected.as_bytes(), actual.as_bytes()) {
+            Ok(())
+        } else {
+            Err(CertificateBindingError::Mismatch)
+        }
+    }
+
+    /// Same as [`Self::verify_certificate()`], but treats a missing `cnf` claim
+    /// as a missing binding.
+    pub fn verify_binding(
+        confirmation: Option<&Self>,
+        certificate_der: &[u8],
+    ) -> Result<(), CertificateBindingError> {
+        confirmation
+            .ok_or(CertificateBindingError::Missing)?
+            .verify_certificate(certificate_der)
+    }
+}
+
+/// Errors that can occur when verifying certificate binding of a token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CertificateBindingError {
+    /// The token has no `x5t#S256` confirmation, i.e., is not bound to a certificate.
+    Missing,
+    /// The token is bound to another certificate.
+    Mismatch,
+}
+
+impl fmt::Display for CertificateBindingError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(match self {
+            Self::Missing => "token is not bound to a client certificate",
+            Self::Mismatch => "token is bound to another client certificate",
+        })
+    }
+}
+
+impl core::error::Error for CertificateBindingError {}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn certificate_binding() {
+        let certificate = b"certificate";
+        let confirmation = Confirmation::for_certificate(certificate);
+        assert_eq!(
+            serde_json::to_value(&confirmation).unwrap(),
+            json!({ "x5t#S256": certificate_thumbprint(certificate) })
+        );
+        confirmation.verify_certificate(certificate).unwrap();
+        Confirmation::verify_binding(Some(&confirmation), certificate).unwrap();
+
+        let err = confirmation.verify_certificate(b"other").unwrap_err();
+        assert_matches!(err, CertificateBindingError::Mismatch);
+        let err = Confirmation::verify_binding(None, certificate).unwrap_err();
+        assert_matches!(err, CertificateBindingError::Missing);
+        let err = Confirmation::default()
+            .verify_certificate(certificate)
+            .unwrap_err();
+        assert_matches!(err, CertificateBindingError::Missing);
+    }
+
+    #[test]
+    fn parsing_confirmation() {
+        let confirmation: Confirmation = serde_json::from_value(json!({
+            "x5t#S256": "bwcK0esc3ACC3DB2Y5_lESsXE8o9ltc05O89jdN-dg2",
+            "jkt": "0ZcOCORZNYy-DWpqq30jZyJGHTN0d2HglBV3uiguA4I",
+        }))
+        .unwrap();
+        assert_eq!(
+            confirmation.certificate_thumbprint.as_deref(),
+            Some("bwcK0esc3ACC3DB2Y5_lESsXE8o9ltc05O89jdN-dg2")
+        );
+    }
+}
@@ -0,0 +1,368 @@
+//! Delegation claims used in token exchange as per [RFC 8693].
+//!
+//! [RFC 8693]: https://www.rfc-editor.org/rfc/rfc8693.html
+
+use serde::{Deserialize, Serialize};
+
+use core::{fmt, iter};
+
+use crate::alloc::{BTreeSet, Box, String};
+
+/// Party acting on behalf of the token subject. Used as the actor claim (`act`),
+/// in which case it can contain a nested chain of prior actors, and as the authorized actor
+/// claim (`may_act`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct Actor {
+    /// Subject of the actor (`sub`).
+    #[serde(rename = "sub", default, skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+    /// Issuer of the actor subject (`iss`).
+    #[serde(rename = "iss", default, skip_serializing_if = "Option::is_none")]
+    pub issuer: Option<String>,
+    /// Client ID of the actor (`client_id`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+    /// Prior actor in the delegation chain (`act`). Must not be used in `may_act` claims.
+    #[serde(rename = "act", default, skip_serializing_if = "Option::is_none")]
+    pub actor: Option<Box<Actor>>,
+}
+
+impl Actor {
+    /// Creates an actor with the specified subject.
+    pub fn new(subject: impl Into<String>) -> Self {
+        Self {
+            subject: Some(subject.into()),
+            issuer: None,
+            client_id: None,
+            actor: None,
+        }
+    }
+
+    /// Creates an actor identified by the client ID.
+    pub fn client(client_id: impl Into<String>) -> Self {
+        Self {
+            subject: None,
+            issuer: None,
+            client_id: Some(client_id.into()),
+            actor: None,
+        }
+    }
+
+    /// Sets the issuer of the actor subject.
+    #[must_use]
+    pub fn with_issuer(mut self, issuer: impl Into<String>) -> Self {
+        self.issuer = Some(issuer.into());
+        self
+    }
+
+    /// Sets the client ID of the actor.
+    #[must_use]
+    pub fn with_client_id(mut self, client_id: impl Into<String>) -> Self {
+        self.client_id = Some(client_id.into());
+        self
+    }
+
+    /// Sets the prior actor in the delegation chain. This is used when exchanging a token
+    /// that already has an `act` claim.
+    #[must_use]
+    pub fn with_prior_actor(mut self, actor: Self) -> Self {
+        self.actor = Some(Box::new(actor));
+        self
+    }
+
+    /// Iterates over the delegation chain, starting from this (i.e., the current) actor.
+    pub fn chain(&self) -> impl Iterator<Item = &Self> + '_ {
+        iter::successors(Some(self), |actor| actor.actor.as_deref())
+    }
+
+    /// Checks whether this actor matches the authorized actor description from the `may_act`
+    /// claim. Only fields present in `authorized` are compared; the prior actors are ignored.
+    pub fn matches(&self, authorized: &Self) -> bool {
+        fn field_matches(actual: Option<&String>, expected: Option<&String>) -> bool {
+            expected.map_or(true, |expected| actual == Some(expected))
+        }
+
+        field_matches(self.subject.as_ref(), authorized.subject.as_ref())
+            && field_matches(self.issuer.as_ref(), authorized.issuer.as_ref())
+            && field_matches(self.client_id.as_ref(), authorized.client_id.as_ref())
+    }
+}
+
+/// Delegation-related claims of a token. Can be flattened into custom claims.
+///
+/// # Examples
+///
+/// ```
+/// # use serde::{Deserialize, Serialize};
+/// use jwt_compact::oauth::{Actor, DelegationClaims, DelegationPolicy};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct AccessTokenClaims {
+///     #[serde(rename = "sub")]
+///     subject: String,
+///     #[serde(flatten)]
+///     delegation: DelegationClaims,
+/// }
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let claims = AccessTokenClaims {
+///     subject: "user@example.com".to_owned(),
+///     delegation: DelegationClaims::with_actor(
+///         Actor::new("https://service16.example.com")
+///             .with_prior_actor(Actor::new("https://service77.example.com")),
+///     ),
+/// };
+///
+/// let policy = DelegationPolicy::new()
+///     .require_actor()
+///     .with_allowed_actor("https://service16.example.com")
+///     .with_max_chain_length(2);
+/// policy.validate(&claims.delegation)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct DelegationClaims {
+    /// Current actor (`act`).
+    #[serde(rename = "act", default, skip_serializing_if = "Option::is_none")]
+    pub actor: Option<Actor>,
+    /// Party authorized to become the actor (`may_act`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub may_act: Option<Actor>,
+}
+
+impl DelegationClaims {
+    /// Creates claims with the specified current actor.
+    pub fn with_actor(actor: Actor) -> Self {
+        Self {
+            actor: Some(actor),
+            may_act: None,
+        }
+    }
+
+    /// Creates claims with the specified authorized actor.
+    pub fn with_may_act(authorized: Actor) -> Self {
+        Self {
+            actor: None,
+            may_act: Some(authorized),
+        }
+    }
+
+    /// Checks whether `actor` is authorized to act on behalf of the subject according to
+    /// the `may_act` claim. This check is performed by the authorization server on
+    /// the subject token during the exchange. If the `may_act` claim is absent,
+    /// returns `true`.
+    pub fn may_be_acted_by(&self, actor: &Actor) -> bool {
+        self.may_act
+            .as_ref()
+            .map_or(true, |authorized| actor.matches(authorized))
+    }
+
+    /// Returns delegation claims for a token issued by exchanging a token with these claims:
+    /// `actor` becomes the current actor, and the current actor (if any) becomes the prior one.
+    #[must_use]
+    pub fn delegate_to(&self, mut actor: Actor) -> Self {
+        actor.actor = self.actor.clone().map(Box::new);
+        Self::with_actor(actor)
+    }
+}
+
+/// Validation policy for [`DelegationClaims`].
+#[derive(Debug, Clone, Default)]
+pub struct DelegationPolicy {
+    actor_required: bool,
+    allowed_actors: Option<BTreeSet<String>>,
+    max_chain_length: Option<usize>,
+}
+
+impl DelegationPolicy {
+    /// Creates a policy that accepts any delegation claims.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requires the `act` claim to be present.
+    #[must_use]
+    pub fn require_actor(mut self) -> Self {
+        self.actor_required = true;
+        self
+    }
+
+    /// Adds an allowed subject of the current actor. If at least one allowed actor is set,
+    /// the current actor must have one of allowed subjects.
+    #[must_use]
+    pub fn with_allowed_actor(mut self, subject: impl Into<String>) -> Self {
+        self.allowed_actors
+            .get_or_insert_with(BTreeSet::new)
+            .insert(subject.into());
+        self
+    }
+
+    /// Sets the maximum length of the delegation chain, including the current actor.
+    #[must_use]
+    pub fn with_max_chain_length(mut self, max_length: usize) -> Self {
+        self.max_chain_length = Some(max_length);
+        self
+    }
+
+    /// Validates the delegation claims against this policy.
+    pub fn validate(&self, claims: &DelegationClaims) -> Result<(), DelegationError> {
+        let Some(actor) = &claims.actor else {
+            return if self.actor_required {
+                Err(DelegationError::MissingActor)
+            } else {
+                Ok(())
+            };
+        };
+
+        if let Some(allowed_actors) = &self.allowed_actors {
+            let is_allowed = actor
+                .subject
+                .as_ref()
+                .is_some_and(|subject| allowed_actors.contains(subject));
+            if !is_allowed {
+                return Err(DelegationError::ActorNotAllowed);
+            }
+        }
+        if let Some(max_length) = self.max_chain_length {
+            let length = actor.chain().count();
+            if length > max_length {
+                return Err(DelegationError::ChainTooLong { length, max_length });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Errors that can occur when validating [`DelegationClaims`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DelegationError {
+    /// The `act` claim is required, but is missing.
+    MissingActor,
+    /// The current actor is not allowed.
+    ActorNotAllowed,
+    /// The delegation chain is too long.
+    ChainTooLong {
+        /// Actual length of the chain.
+        length: usize,
+        /// Maximum allowed length.
+        max_length: usize,
+    },
+}
+
+impl fmt::Display for DelegationError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingActor => formatter.write_str("`act` claim is missing"),
+            Self::ActorNotAllowed => formatter.write_str("actor is not allowed"),
+            Self::ChainTooLong { length, max_length } => write!(
+                formatter,
+                "delegation chain is too long: {length}, expected at most {max_length}"
+            ),
+        }
+    }
+}
+
+impl core::error::Error for DelegationError {}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use serde_json::json;
+
+    use super::*;
+    use crate::alloc::Vec;
+
+    #[test]
+    fn parsing_nested_actors() {
+        // Example from RFC 8693, Section 4.1.
+        let claims: DelegationClaims = serde_json::from_value(json!({
+            "act": {
+                "sub": "https://service16.example.com",
+                "act": { "sub": "https://service77.example.com" },
+            },
+        }))
+        .unwrap();
+
+        let actor = claims.actor.as_ref().unwrap();
+        let subjects: Vec<_> = actor
+            .chain()
+            .map(|actor| actor.subject.as_deref().unwrap())
+            .collect();
+        assert_eq!(
+            subjects,
+            [
+                "https://service16.example.com",
+                "https://service77.example.com"
+            ]
+        );
+
+        let delegated = claims.delegate_to(Actor::client("gateway"));
+        assert_eq!(
+            serde_json::to_value(&delegated).unwrap(),
+            json!({
+                "act": {
+                    "client_id": "gateway",
+                    "act": {
+                        "sub": "https://service16.example.com",
+                        "act": { "sub": "https://service77.example.com" },
+                    },
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn checking_may_act() {
+        let claims: DelegationClaims = serde_json::from_value(json!({
+            "may_act": { "sub": "admin@example.net", "iss": "https://issuer.example.net" },
+        }))
+        .unwrap();
+
+        let actor = Actor::new("admin@example.net").with_issuer("https://issuer.example.net");
+        assert!(claims.may_be_acted_by(&actor));
+        assert!(claims.may_be_acted_by(&actor.clone().with_client_id("client")));
+        assert!(!claims.may_be_acted_by(&Actor::new("admin@example.net")));
+        assert!(!claims.may_be_acted_by(&Actor::new("user@example.net")));
+        assert!(DelegationClaims::default().may_be_acted_by(&actor));
+    }
+
+    #[test]
+    fn delegation_policy() {
+        let claims = DelegationClaims::with_actor(
+            Actor::new("service").with_prior_actor(Actor::new("other-service")),
+        );
+        DelegationPolicy::new().validate(&claims).unwrap();
+        DelegationPolicy::new()
+            .validate(&DelegationClaims::default())
+            .unwrap();
+
+        let policy = DelegationPolicy::new().require_actor();
+        policy.validate(&claims).unwrap();
+        let err = policy.validate(&DelegationClaims::default()).unwrap_err();
+        assert_matches!(err, DelegationError::MissingActor);
+
+        let policy = DelegationPolicy::new().with_allowed_actor("service");
+        policy.validate(&claims).unwrap();
+        let err = DelegationPolicy::new()
+            .with_allowed_actor("other-service")
+            .validate(&claims)
+            .unwrap_err();
+        assert_matches!(err, DelegationError::ActorNotAllowed);
+
+        let err = DelegationPolicy::new()
+            .with_max_chain_length(1)
+            .validate(&claims)
+            .unwrap_err();
+        assert_matches!(
+            err,
+            DelegationError::ChainTooLong {
+                length: 2,
+                max_length: 1
+            }
+        );
+    }
+}
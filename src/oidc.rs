@@ -0,0 +1,19 @@
+//! Helpers for [OpenID Connect] tokens beyond ID tokens.
+//!
+//! [OpenID Connect]: https://openid.net/specs/openid-connect-core-1_0.html
+
+mod federation;
+mod logout;
+mod userinfo;
+
+pub use self::{
+    federation::{
+        EntityStatementClaims, EntityStatementFetcher, FederationError, TrustChain,
+        TrustChainValidator, ENTITY_CONFIGURATION_PATH, ENTITY_STATEMENT_TOKEN_TYPE,
+    },
+    logout::{
+        LogoutTokenClaims, LogoutTokenError, LogoutTokenValidator, BACKCHANNEL_LOGOUT_EVENT,
+        LOGOUT_TOKEN_TYPE,
+    },
+    userinfo::{UserInfoClaims, UserInfoError, UserInfoValidator},
+};
@@ -0,0 +1,679 @@
+//! Entity statements and trust chains as per [OpenID Federation].
+//!
+//! [OpenID Federation]: https://openid.net/specs/openid-federation-1_0.html
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use core::fmt;
+
+use crate::{
+    alloc::{BTreeMap, String, Vec},
+    jwk::{JsonWebKey, JsonWebKeySet, JwkError},
+    Algorithm, AlgorithmExt, ParseError, TimeOptions, Timestamp, Token, UntrustedToken,
+    ValidationError,
+};
+
+/// Token type (`typ`) of entity statements.
+pub const ENTITY_STATEMENT_TOKEN_TYPE: &str = "entity-statement+jwt";
+/// Path of the entity configuration relative to the entity identifier.
+pub const ENTITY_CONFIGURATION_PATH: &str = "/.well-known/openid-federation";
+
+/// Claims of an entity statement: either a self-signed entity configuration,
+/// or a subordinate statement issued by a superior entity about its subordinate.
+///
+/// Metadata policies are exposed as is; applying them to the metadata is up to the caller.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct EntityStatementClaims {
+    /// Issuer of the statement (`iss`).
+    #[serde(rename = "iss")]
+    pub issuer: String,
+    /// Subject of the statement (`sub`). Equal to the issuer for entity configurations.
+    #[serde(rename = "sub")]
+    pub subject: String,
+    /// Federation keys of the subject.
+    pub jwks: JsonWebKeySet<'static>,
+    /// Immediate superiors of the entity. Only present in entity configurations.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub authority_hints: Vec<String>,
+    /// Metadata of the subject keyed by the entity type (e.g., `openid_relying_party`).
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    pub metadata: Map<String, Value>,
+    /// Metadata policy applying to the subject and its subordinates.
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    pub metadata_policy: Map<String, Value>,
+    /// Trust chain constraints.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub constraints: Option<Value>,
+    /// Other claims.
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+impl EntityStatementClaims {
+    /// Creates claims for an entity configuration of the specified entity.
+    pub fn configuration(entity_id: impl Into<String>, jwks: JsonWebKeySet<'static>) -> Self {
+        let entity_id = entity_id.into();
+        Self::subordinate(entity_id.clone(), entity_id, jwks)
+    }
+
+    /// Creates claims for a subordinate statement.
+    pub fn subordinate(
+        issuer: impl Into<String>,
+        subject: impl Into<String>,
+        jwks: JsonWebKeySet<'static>,
+    ) -> Self {
+        Self {
+            issuer: issuer.into(),
+            subject: subject.into(),
+            jwks,
+            authority_hints: Vec::new(),
+            metadata: Map::new(),
+            metadata_policy: Map::new(),
+            constraints: None,
+            other: Map::new(),
+        }
+    }
+
+    /// Adds an authority hint.
+    #[must_use]
+    pub fn with_authority_hint(mut self, superior: impl Into<String>) -> Self {
+        self.authority_hints.push(superior.into());
+        self
+    }
+
+    /// Sets metadata for the specified entity type.
+    #[must_use]
+    pub fn with_metadata(mut self, entity_type: impl Into<String>, metadata: Value) -> Self {
+        self.metadata.insert(entity_type.into(), metadata);
+        self
+    }
+
+    /// Checks whether these claims belong to an entity configuration.
+    pub fn is_configuration(&self) -> bool {
+        self.issuer == self.subject
+    }
+}
+
+/// Source of entity statements used to [resolve](TrustChainValidator::resolve()) trust chains.
+///
+/// The crate does not perform HTTP requests; implementations are expected to fetch
+/// entity configurations from [`ENTITY_CONFIGURATION_PATH`] and subordinate statements
+/// from the fetch endpoint of the issuer (possibly with caching).
+pub trait EntityStatementFetcher {
+    /// Fetches the entity configuration of the specified entity.
+    fn fetch_entity_configuration(&mut self, entity_id: &str) -> Option<String>;
+
+    /// Fetches the subordinate statement issued by `issuer` about `subject`.
+    fn fetch_subordinate_statement(&mut self, issuer: &str, subject: &str) -> Option<String>;
+}
+
+/// Validated trust chain.
+#[derive(Debug, Clone)]
+pub struct TrustChain {
+    statements: Vec<Token<EntityStatementClaims>>,
+    expiration: Timestamp,
+}
+
+impl TrustChain {
+    /// Returns the entity configuration of the leaf entity.
+    pub fn entity_configuration(&self) -> &Token<EntityStatementClaims> {
+        &self.statements[0]
+    }
+
+    /// Returns all statements in the chain, starting from the leaf entity configuration.
+    pub fn statements(&self) -> &[Token<EntityStatementClaims>] {
+        &self.statements
+    }
+
+    /// Returns the identifier of the trust anchor terminating the chain.
+    pub fn trust_anchor(&self) -> &str {
+        &self.statements[self.statements.len() - 1]
+            .claims()
+            .custom
+            .issuer
+    }
+
+    /// Returns the expiration time of the chain, i.e., the earliest expiration time
+    /// of its statements.
+    pub fn expiration(&self) -> Timestamp {
+        self.expiration
+    }
+
+    /// Converts this chain into statements.
+    pub fn into_statements(self) -> Vec<Token<EntityStatementClaims>> {
+        self.statements
+    }
+}
+
+/// Validator of entity statements and trust chains.
+///
+/// All statements must be signed using the same algorithm `A`, and have:
+///
+/// - `typ` header field equal to [`ENTITY_STATEMENT_TOKEN_TYPE`]
+/// - `kid` header field referencing a key in the key set of the issuer
+/// - `iat` claim not in the future
+/// - `exp` claim that has not passed
+///
+/// A trust chain starts from the self-signed entity configuration of the leaf entity,
+/// followed by subordinate statements, each issued by the subject of the following statement.
+/// The last statement must be issued by one of the configured trust anchors (it may be
+/// the entity configuration of the trust anchor), and is verified using the anchor keys
+/// provided to [`Self::with_trust_anchor()`].
+///
+/// # Examples
+///
+/// ```
+/// # use chrono::Duration;
+/// # use jwt_compact::{alg::{Hs256, Hs256Key}, jwk::{JsonWebKey, JsonWebKeySet, KeySetEntry}, prelude::*};
+/// use jwt_compact::oidc::{
+///     EntityStatementClaims, EntityStatementFetcher, TrustChainValidator,
+///     ENTITY_STATEMENT_TOKEN_TYPE,
+/// };
+///
+/// struct Federation { leaf: String, statement: String }
+///
+/// impl EntityStatementFetcher for Federation {
+///     fn fetch_entity_configuration(&mut self, entity_id: &str) -> Option<String> {
+///         (entity_id == "https://rp.example.org").then(|| self.leaf.clone())
+///     }
+///
+///     fn fetch_subordinate_statement(&mut self, issuer: &str, subject: &str) -> Option<String> {
+///         (issuer == "https://ta.example.org" && subject == "https://rp.example.org")
+///             .then(|| self.statement.clone())
+///     }
+/// }
+///
+/// # fn main() -> anyhow::Result<()> {
+/// # fn key_set(key: &Hs256Key, id: &str) -> JsonWebKeySet<'static> {
+/// #     let jwk = serde_json::to_value(JsonWebKey::from(key)).unwrap();
+/// #     let mut entry = KeySetEntry::new(serde_json::from_value(jwk).unwrap());
+/// #     entry.key_id = Some(id.to_owned());
+/// #     JsonWebKeySet { keys: vec![entry] }
+/// # }
+/// # let options = TimeOptions::default();
+/// # let leaf_key = Hs256Key::new(b"super_secret_key_donut_steel");
+/// # let anchor_key = Hs256Key::new(b"another_secret_key_for_anchor");
+/// # let sign = |claims: EntityStatementClaims, key: &Hs256Key, id: &str| {
+/// #     let header = Header::empty()
+/// #         .with_token_type(ENTITY_STATEMENT_TOKEN_TYPE)
+/// #         .with_key_id(id);
+/// #     let claims = Claims::new(claims)
+/// #         .set_duration_and_issuance(&options, Duration::try_days(1).unwrap());
+/// #     Hs256.token(&header, &claims, key).map(String::from)
+/// # };
+/// # let leaf = EntityStatementClaims::configuration("https://rp.example.org", key_set(&leaf_key, "rp"))
+/// #     .with_authority_hint("https://ta.example.org");
+/// # let statement = EntityStatementClaims::subordinate(
+/// #     "https://ta.example.org",
+/// #     "https://rp.example.org",
+/// #     key_set(&leaf_key, "rp"),
+/// # );
+/// # let mut federation = Federation {
+/// #     leaf: sign(leaf, &leaf_key, "rp")?,
+/// #     statement: sign(statement, &anchor_key, "ta")?,
+/// # };
+/// let validator = TrustChainValidator::new()
+///     .with_trust_anchor("https://ta.example.org", key_set(&anchor_key, "ta"));
+/// let chain =
+///     validator.resolve(&Hs256, "https://rp.example.org", &mut federation, &options)?;
+/// assert_eq!(chain.trust_anchor(), "https://ta.example.org");
+/// let metadata = &chain.entity_configuration().claims().custom.metadata;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct TrustChainValidator {
+    trust_anchors: BTreeMap<String, JsonWebKeySet<'static>>,
+    max_path_length: usize,
+}
+
+impl Default for TrustChainValidator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TrustChainValidator {
+    /// Creates a validator without trust anchors. The maximum number of intermediate entities
+    /// traversed during [resolution](Self::resolve()) is set to 5.
+    pub fn new() -> Self {
+        Self {
+            trust_anchors: BTreeMap::new(),
+            max_path_length: 5,
+        }
+    }
+
+    /// Adds a trust anchor with the specified keys.
+    #[must_use]
+    pub fn with_trust_anchor(
+        mut self,
+        entity_id: impl Into<String>,
+        jwks: JsonWebKeySet<'static>,
+    ) -> Self {
+        self.trust_anchors.insert(entity_id.into(), jwks);
+        self
+    }
+
+    /// Sets the maximum number of intermediate entities traversed during
+    /// [resolution](Self::resolve()).
+    #[must_use]
+    pub fn with_max_path_length(mut self, max_path_length: usize) -> Self {
+        self.max_path_length = max_path_length;
+        self
+    }
+
+    /// Validates a self-signed entity configuration. This does not establish trust
+    /// in the entity; use [`Self::validate_chain()`] or [`Self::resolve()`] for that.
+    pub fn validate_entity_configuration<A, F>(
+        &self,
+        algorithm: &A,
+        token: &UntrustedToken<'_>,
+        options: &TimeOptions<F>,
+    ) -> Result<Token<EntityStatementClaims>, FederationError>
+    where
+        A: Algorithm,
+        A::VerifyingKey: for<'k> TryFrom<&'k JsonWebKey<'k>, Error = JwkError>,
+        F: Fn() -> Timestamp,
+    {
+        let claims = token.deserialize_claims_unverified::<EntityStatementClaims>()?;
+        let token = Self::verify_statement(algorithm, &claims.custom.jwks, token, options)?;
+        if !token.claims().custom.is_configuration() {
+            return Err(FederationError::ClaimMismatch("sub"));
+        }
+        Ok(token)
+    }
+
+    /// Validates a trust chain, starting from the leaf entity configuration.
+    pub fn validate_chain<A, F>(
+        &self,
+        algorithm: &A,
+        chain: &[impl AsRef<str>],
+        options: &TimeOptions<F>,
+    ) -> Result<TrustChain, FederationError>
+    where
+        A: Algorithm,
+        A::VerifyingKey: for<'k> TryFrom<&'k JsonWebKey<'k>, Error = JwkError>,
+        F: Fn() -> Timestamp,
+    {
+        let tokens = chain
+            .iter()
+            .map(|statement| UntrustedToken::new(statement))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(FederationError::Parse)?;
+        let Some(last_token) = tokens.last() else {
+            return Err(FederationError::Unresolved);
+        };
+
+        // Verify statements starting from the trust anchor, so that keys used for verification
+        // always come from an already verified statement.
+        let last_claims = last_token.deserialize_claims_unverified::<EntityStatementClaims>()?;
+        let anchor_keys = self
+            .trust_anchors
+            .get(&last_claims.custom.issuer)
+            .ok_or(FederationError::UntrustedAnchor)?;
+        let anchor_statement = Self::verify_statement(algorithm, anchor_keys, last_token, options)?;
+        let mut expiration = anchor_statement.claims().expiration;
+        let mut statements = Vec::with_capacity(tokens.len());
+        statements.push(anchor_statement);
+
+        for token in tokens.iter().rev().skip(1) {
+            let superior = &statements[statements.len() - 1].claims().custom;
+            let statement = Self::verify_statement(algorithm, &superior.jwks, token, options)?;
+            if statement.claims().custom.issuer != superior.subject {
+                return Err(FederationError::ClaimMismatch("iss"));
+            }
+            expiration = expiration.min(statement.claims().expiration);
+            statements.push(statement);
+        }
+        statements.reverse();
+
+        // The leaf entity configuration must be additionally self-signed.
+        let leaf = self.validate_entity_configuration(algorithm, &tokens[0], options)?;
+        if leaf.claims().custom != statements[0].claims().custom {
+            return Err(FederationError::ClaimMismatch("sub"));
+        }
+        Ok(TrustChain {
+            statements,
+            expiration: expiration.ok_or(FederationError::MissingClaim("exp"))?,
+        })
+    }
+
+    /// Resolves a trust chain for the specified entity by following authority hints
+    /// starting from its entity configuration. Returns the first chain leading to
+    /// a configured trust anchor that passes validation.
+    pub fn resolve<A, F>(
+        &self,
+        algorithm: &A,
+        entity_id: &str,
+        fetcher: &mut impl EntityStatementFetcher,
+        options: &TimeOptions<F>,
+    ) -> Result<TrustChain, FederationError>
+    where
+        A: Algorithm,
+        A::VerifyingKey: for<'k> TryFrom<&'k JsonWebKey<'k>, Error = JwkError>,
+        F: Fn() -> Timestamp,
+    {
+        let configuration = fetcher
+            .fetch_entity_configuration(entity_id)
+            .ok_or(FederationError::Unresolved)?;
+        let token = UntrustedToken::new(&configuration).map_err(FederationError::Parse)?;
+        self.validate_entity_configuration(algorithm, &token, options)?;
+
+        let mut chain = Vec::from([configuration.clone()]);
+        self.extend_chain(algorithm, &configuration, &mut chain, fetcher, options, 0)
+            .ok_or(FederationError::Unresolved)
+    }
+
+    fn extend_chain<A, F>(
+        &self,
+        algorithm: &A,
+        configuration: &str,
+        chain: &mut Vec<String>,
+        fetcher: &mut impl EntityStatementFetcher,
+        options: &TimeOptions<F>,
+        depth: usize,
+    ) -> Option<TrustChain>
+    where
+        A: Algorithm,
+        A::VerifyingKey: for<'k> TryFrom<&'k JsonWebKey<'k>, Error = JwkError>,
+        F: Fn() -> Timestamp,
+    {
+        // Authority hints are only used for discovery; the resulting chain is fully validated.
+        let claims = UntrustedToken::new(configuration)
+            .ok()?
+            .deserialize_claims_unverified::<EntityStatementClaims>()
+            .ok()?
+            .custom;
+
+        for superior in &claims.authority_hints {
+            let Some(statement) = fetcher.fetch_subordinate_statement(superior, &claims.subject)
+            else {
+                continue;
+            };
+            chain.push(statement);
+            if self.trust_anchors.contains_key(superior) {
+                if let Ok(trust_chain) = self.validate_chain(algorithm, chain, options) {
+                    return Some(trust_chain);
+                }
+            } else if depth < self.max_path_length {
+                if let Some(superior_configuration) = fetcher.fetch_entity_configuration(superior) {
+                    let trust_chain = self.extend_chain(
+                        algorithm,
+                        &superior_configuration,
+                        chain,
+                        fetcher,
+                        options,
+                        depth + 1,
+                    );
+                    if trust_chain.is_some() {
+                        return trust_chain;
+                    }
+                }
+            }
+            chain.pop();
+        }
+        None
+    }
+
+    fn verify_statement<A, F>(
+        algorithm: &A,
+        key_set: &JsonWebKeySet<'_>,
+        token: &UntrustedToken<'_>,
+        options: &TimeOptions<F>,
+    ) -> Result<Token<EntityStatementClaims>, FederationError>
+    where
+        A: Algorithm,
+        A::VerifyingKey: for<'k> TryFrom<&'k JsonWebKey<'k>, Error = JwkError>,
+        F: Fn() -> Timestamp,
+    {
+        let header = token.header();
+        if header.token_type.as_deref() != Some(ENTITY_STATEMENT_TOKEN_TYPE) {
+            return Err(FederationError::InvalidTokenType);
+        }
+        let key_id = header
+            .key_id
+            .as_deref()
+            .ok_or(FederationError::UnknownKey)?;
+        let entry = key_set.find(key_id).ok_or(FederationError::UnknownKey)?;
+        let verifying_key = A::VerifyingKey::try_from(&entry.key).map_err(FederationError::Jwk)?;
+
+        let token = algorithm
+            .validator::<EntityStatementClaims>(&verifying_key)
+            .validate(token)?;
+        let claims = token.claims();
+        if claims.expiration.is_none() {
+            return Err(FederationError::MissingClaim("exp"));
+        }
+        claims.validate_expiration(options)?;
+        let issued_at = claims
+            .issued_at
+            .ok_or(FederationError::MissingClaim("iat"))?;
+        // `iat` comes from the token, so the arithmetic must not panic on extreme values.
+        let is_issued = issued_at
+            .checked_sub_signed(options.leeway)
+            .is_some_and(|issued_at| issued_at <= (options.clock_fn)());
+        if !is_issued {
+            return Err(FederationError::ClaimMismatch("iat"));
+        }
+        Ok(token)
+    }
+}
+
+/// Errors that can occur during validation of entity statements and trust chains.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum FederationError {
+    /// Statement has failed generic validation (e.g., has an invalid signature).
+    Validation(ValidationError),
+    /// Statement cannot be parsed.
+    Parse(ParseError),
+    /// The `typ` header field is not equal to [`ENTITY_STATEMENT_TOKEN_TYPE`].
+    InvalidTokenType,
+    /// The key referenced by the statement (via the `kid` header field) is not present
+    /// in the key set of the issuer, or the statement does not reference a key.
+    UnknownKey,
+    /// The key from the key set cannot be converted to a verifying key.
+    Jwk(JwkError),
+    /// Required claim is missing.
+    MissingClaim(&'static str),
+    /// Claim has an unexpected value.
+    ClaimMismatch(&'static str),
+    /// The chain is not terminated by a configured trust anchor.
+    UntrustedAnchor,
+    /// No trust chain leading to a configured trust anchor could be established.
+    Unresolved,
+}
+
+impl fmt::Display for FederationError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Validation(err) => write!(formatter, "statement validation failed: {err}"),
+            Self::Parse(err) => write!(formatter, "cannot parse statement: {err}"),
+            Self::InvalidTokenType => formatter.write_str("statement has unexpected token type"),
+            Self::UnknownKey => formatter.write_str("statement is signed with an unknown key"),
+            Self::Jwk(err) => write!(formatter, "invalid key in key set: {err}"),
+            Self::MissingClaim(claim) => write!(formatter, "claim `{claim}` is missing"),
+            Self::ClaimMismatch(claim) => write!(formatter, "claim `{claim}` has unexpected value"),
+            Self::UntrustedAnchor => {
+                formatter.write_str("trust chain is not terminated by a trust anchor")
+            }
+            Self::Unresolved => formatter.write_str("cannot resolve trust chain"),
+        }
+    }
+}
+
+impl core::error::Error for FederationError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::Validation(err) => Some(err),
+            Self::Parse(err) => Some(err),
+            Self::Jwk(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<ValidationError> for FederationError {
+    fn from(err: ValidationError) -> Self {
+        Self::Validation(err)
+    }
+}
+
+#[cfg(all(test, feature = "clock"))]
+mod tests {
+    use assert_matches::assert_matches;
+    use chrono::Duration;
+
+    use super::*;
+    use crate::{
+        alg::{Hs256, Hs256Key},
+        alloc::ToOwned,
+        jwk::KeySetEntry,
+        Claims, Header,
+    };
+
+    const LEAF: &str = "https://rp.example.org";
+    const INTERMEDIATE: &str = "https://uni.example.org";
+    const ANCHOR: &str = "https://ta.example.org";
+
+    fn key(id: &str) -> Hs256Key {
+        Hs256Key::new(id.repeat(4))
+    }
+
+    fn key_set(id: &str) -> JsonWebKeySet<'static> {
+        let jwk = serde_json::to_value(JsonWebKey::from(&key(id))).unwrap();
+        let mut entry = KeySetEntry::new(serde_json::from_value(jwk).unwrap());
+        entry.key_id = Some(id.to_owned());
+        JsonWebKeySet {
+            keys: Vec::from([entry]),
+        }
+    }
+
+    fn sign(claims: EntityStatementClaims, key_id: &str) -> String {
+        let header = Header::empty()
+            .with_token_type(ENTITY_STATEMENT_TOKEN_TYPE)
+            .with_key_id(key_id);
+        let claims = Claims::new(claims)
+            .set_duration_and_issuance(&TimeOptions::default(), Duration::try_hours(1).unwrap());
+        Hs256.token(&header, &claims, &key(key_id)).unwrap().into()
+    }
+
+    #[derive(Default)]
+    struct Federation {
+        configurations: BTreeMap<String, String>,
+        statements: BTreeMap<(String, String), String>,
+    }
+
+    impl EntityStatementFetcher for Federation {
+        fn fetch_entity_configuration(&mut self, entity_id: &str) -> Option<String> {
+            self.configurations.get(entity_id).cloned()
+        }
+
+        fn fetch_subordinate_statement(&mut self, issuer: &str, subject: &str) -> Option<String> {
+            self.statements
+                .get(&(issuer.to_owned(), subject.to_owned()))
+                .cloned()
+        }
+    }
+
+    fn federation() -> Federation {
+        let mut federation = Federation::default();
+        let leaf = EntityStatementClaims::configuration(LEAF, key_set("rp"))
+            .with_authority_hint("https://unknown.example.org")
+            .with_authority_hint(INTERMEDIATE);
+        let intermediate = EntityStatementClaims::configuration(INTERMEDIATE, key_set("uni"))
+            .with_authority_hint(ANCHOR);
+        federation
+            .configurations
+            .insert(LEAF.to_owned(), sign(leaf, "rp"));
+        federation
+            .configurations
+            .insert(INTERMEDIATE.to_owned(), sign(intermediate, "uni"));
+
+        let statement = EntityStatementClaims::subordinate(INTERMEDIATE, LEAF, key_set("rp"));
+        federation.statements.insert(
+            (INTERMEDIATE.to_owned(), LEAF.to_owned()),
+            sign(statement, "uni"),
+        );
+        let statement = EntityStatementClaims::subordinate(ANCHOR, INTERMEDIATE, key_set("uni"));
+        federation.statements.insert(
+            (ANCHOR.to_owned(), INTERMEDIATE.to_owned()),
+            sign(statement, "ta"),
+        );
+        federation
+    }
+
+    fn validator() -> TrustChainValidator {
+        TrustChainValidator::new().with_trust_anchor(ANCHOR, key_set("ta"))
+    }
+
+    #[test]
+    fn resolving_trust_chain() {
+        let mut federation = federation();
+        let options = TimeOptions::default();
+        let chain = validator()
+            .resolve(&Hs256, LEAF, &mut federation, &options)
+            .unwrap();
+        assert_eq!(chain.statements().len(), 3);
+        assert_eq!(chain.trust_anchor(), ANCHOR);
+        assert_eq!(chain.entity_configuration().claims().custom.subject, LEAF);
+
+        let err = validator()
+            .with_max_path_length(0)
+            .resolve(&Hs256, LEAF, &mut federation, &options)
+            .unwrap_err();
+        assert_matches!(err, FederationError::Unresolved);
+    }
+
+    #[test]
+    fn validating_chain() {
+        let federation = federation();
+        let options = TimeOptions::default();
+        let leaf = &federation.configurations[LEAF];
+        let intermediate = &federation.statements[&(INTERMEDIATE.to_owned(), LEAF.to_owned())];
+        let anchor = &federation.statements[&(ANCHOR.to_owned(), INTERMEDIATE.to_owned())];
+        validator()
+            .validate_chain(&Hs256, &[leaf, intermediate, anchor], &options)
+            .unwrap();
+
+        let err = validator()
+            .validate_chain(&Hs256, &[leaf, intermediate], &options)
+            .unwrap_err();
+        assert_matches!(err, FederationError::UntrustedAnchor);
+        let err = validator()
+            .validate_chain(&Hs256, &[leaf, anchor], &options)
+            .unwrap_err();
+        assert_matches!(err, FederationError::UnknownKey);
+    }
+
+    #[test]
+    fn statement_with_invalid_token_type() {
+        let claims = Claims::new(EntityStatementClaims::configuration(LEAF, key_set("rp")))
+            .set_duration_and_issuance(&TimeOptions::default(), Duration::try_hours(1).unwrap());
+        let header = Header::empty().with_key_id("rp");
+        let token = Hs256.token(&header, &claims, &key("rp")).unwrap();
+        let token = UntrustedToken::new(&token).unwrap();
+        let err = validator()
+            .validate_entity_configuration(&Hs256, &token, &TimeOptions::default())
+            .unwrap_err();
+        assert_matches!(err, FederationError::InvalidTokenType);
+    }
+
+    #[test]
+    fn statement_with_extreme_issuance_time() {
+        let mut claims = Claims::new(EntityStatementClaims::configuration(LEAF, key_set("rp")))
+            .set_duration_and_issuance(&TimeOptions::default(), Duration::try_hours(1).unwrap());
+        claims.issued_at = Some(Timestamp::MIN);
+        let header = Header::empty()
+            .with_token_type(ENTITY_STATEMENT_TOKEN_TYPE)
+            .with_key_id("rp");
+        let token = Hs256.token(&header, &claims, &key("rp")).unwrap();
+        let token = UntrustedToken::new(&token).unwrap();
+        let err = validator()
+            .validate_entity_configuration(&Hs256, &token, &TimeOptions::default())
+            .unwrap_err();
+        assert_matches!(err, FederationError::ClaimMismatch("iat"));
+    }
+}
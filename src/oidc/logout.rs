@@ -0,0 +1,417 @@
+//! Logout tokens as per [OpenID Connect Back-Channel Logout].
+//!
+//! [OpenID Connect Back-Channel Logout]: https://openid.net/specs/openid-connect-backchannel-1_0.html
+
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use core::fmt;
+
+use crate::{
+    alloc::{BTreeMap, String, ToOwned, Vec},
+    claims::serde_audience,
+    replay::ReplayGuard,
+    Algorithm, AlgorithmExt, Claims, TimeOptions, Timestamp, Token, UntrustedToken,
+    ValidationError,
+};
+
+/// Event type identifying logout tokens in the `events` claim.
+pub const BACKCHANNEL_LOGOUT_EVENT: &str = "http://schemas.openid.net/event/backchannel-logout";
+/// Recommended token type (`typ`) of logout tokens.
+pub const LOGOUT_TOKEN_TYPE: &str = "logout+jwt";
+
+/// Claims of a logout token.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct LogoutTokenClaims {
+    /// Issuer of the token (`iss`), i.e., the `OpenID` provider.
+    #[serde(rename = "iss")]
+    pub issuer: String,
+    /// Intended recipients of the token (`aud`), i.e., client IDs of relying parties.
+    #[serde(rename = "aud", with = "serde_audience")]
+    pub audience: Vec<String>,
+    /// Unique identifier of the token (`jti`).
+    #[serde(rename = "jti")]
+    pub jwt_id: String,
+    /// Subject whose sessions should be terminated (`sub`).
+    #[serde(rename = "sub", default, skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+    /// Session to terminate (`sid`).
+    #[serde(rename = "sid", default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    /// Events claim. Must contain the [`BACKCHANNEL_LOGOUT_EVENT`] member.
+    pub events: BTreeMap<String, Value>,
+}
+
+impl LogoutTokenClaims {
+    /// Creates claims with the back-channel logout event. Either [the subject](Self::with_subject())
+    /// or [the session ID](Self::with_session_id()) must be set for the token to be valid.
+    pub fn new(
+        issuer: impl Into<String>,
+        audience: impl Into<String>,
+        jwt_id: impl Into<String>,
+    ) -> Self {
+        let mut events = BTreeMap::new();
+        events.insert(
+            BACKCHANNEL_LOGOUT_EVENT.to_owned(),
+            Value::Object(Map::new()),
+        );
+        Self {
+            issuer: issuer.into(),
+            audience: Vec::from([audience.into()]),
+            jwt_id: jwt_id.into(),
+            subject: None,
+            session_id: None,
+            events,
+        }
+    }
+
+    /// Sets the subject.
+    #[must_use]
+    pub fn with_subject(mut self, subject: impl Into<String>) -> Self {
+        self.subject = Some(subject.into());
+        self
+    }
+
+    /// Sets the session ID.
+    #[must_use]
+    pub fn with_session_id(mut self, session_id: impl Into<String>) -> Self {
+        self.session_id = Some(session_id.into());
+        self
+    }
+}
+
+/// Validator of logout tokens used by relying parties.
+///
+/// Besides the signature, the validator checks that:
+///
+/// - The `typ` header field, if present, is equal to [`LOGOUT_TOKEN_TYPE`] or `JWT`.
+/// - The `iss` claim matches the expected issuer, and the `aud` claim contains the client ID.
+/// - The `iat` claim is present and is not in the future. If [the maximum age](Self::with_max_age())
+///   is set, the token must not be older than it. If the `exp` claim is present,
+///   the token must not be expired.
+/// - The `jti` claim is not empty.
+/// - The `events` claim contains the [`BACKCHANNEL_LOGOUT_EVENT`] member with a JSON object value.
+/// - At least one of `sub` and `sid` claims is present.
+/// - The `nonce` claim is *not* present (this prevents confusing logout tokens with ID tokens).
+///
+/// [`Self::validate_once()`] additionally checks the token ID against a [`ReplayGuard`].
+///
+/// # Examples
+///
+/// ```
+/// # use chrono::Duration;
+/// # use jwt_compact::{alg::{Hs256, Hs256Key}, prelude::*, replay::MemoryReplayGuard};
+/// use jwt_compact::oidc::{LogoutTokenClaims, LogoutTokenValidator};
+///
+/// # fn main() -> anyhow::Result<()> {
+/// # let key = Hs256Key::new(b"super_secret_key_donut_steel");
+/// # let options = TimeOptions::default();
+/// # let claims = LogoutTokenClaims::new("https://op.example.com", "my-client", "bWJq")
+/// #     .with_session_id("08a5019c-17e1-4977-8f42-65a12843ea02");
+/// # let claims = Claims::new(claims)
+/// #     .set_duration_and_issuance(&options, Duration::try_minutes(2).unwrap());
+/// # let logout_token = Hs256.token(&Header::empty(), &claims, &key)?;
+/// let mut replay_guard = MemoryReplayGuard::new();
+/// let validator = LogoutTokenValidator::new("https://op.example.com", "my-client");
+/// let token = UntrustedToken::new(&logout_token)?;
+/// let token = validator.validate_once(&Hs256, &key, &token, &options, &mut replay_guard)?;
+/// let session_id = token.claims().custom.session_id.as_deref();
+/// // Terminate the session...
+///
+/// // Repeated processing of the same token will fail.
+/// let token = UntrustedToken::new(&logout_token)?;
+/// assert!(validator.validate_once(&Hs256, &key, &token, &options, &mut replay_guard).is_err());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct LogoutTokenValidator {
+    issuer: String,
+    client_id: String,
+    max_age: Option<Duration>,
+}
+
+impl LogoutTokenValidator {
+    /// Creates a validator with the expected issuer and the client ID of the relying party.
+    pub fn new(issuer: impl Into<String>, client_id: impl Into<String>) -> Self {
+        Self {
+            issuer: issuer.into(),
+            client_id: client_id.into(),
+            max_age: None,
+        }
+    }
+
+    /// Sets the maximum age of tokens as determined by the `iat` claim.
+    #[must_use]
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Validates the token.
+    pub fn validate<A: Algorithm, F>(
+        &self,
+        algorithm: &A,
+        verifying_key: &A::VerifyingKey,
+        token: &UntrustedToken<'_>,
+        options: &TimeOptions<F>,
+    ) -> Result<Token<LogoutTokenClaims>, LogoutTokenError>
+    where
+        F: Fn() -> Timestamp,
+    {
+        if let Some(token_type) = token.header().token_type.as_deref() {
+            if token_type != LOGOUT_TOKEN_TYPE && !token_type.eq_ignore_ascii_case("JWT") {
+                return Err(LogoutTokenError::InvalidTokenType);
+            }
+        }
+        let (_, claims) = algorithm
+            .validator::<Map<String, Value>>(verifying_key)
+            .validate(token)?
+            .into_parts();
+        if claims.custom.contains_key("nonce") {
+            return Err(LogoutTokenError::ForbiddenClaim("nonce"));
+        }
+        let custom = LogoutTokenClaims::deserialize(Value::Object(claims.custom))
+            .map_err(ValidationError::MalformedClaims)?;
+        let claims = Claims {
+            expiration: claims.expiration,
+            not_before: claims.not_before,
+            issued_at: claims.issued_at,
+            custom,
+        };
+
+        let issued_at = claims
+            .issued_at
+            .ok_or(LogoutTokenError::MissingClaim("iat"))?;
+        let now = (options.clock_fn)();
+        let is_issued = issued_at
+            .checked_sub_signed(options.leeway)
+            .is_some_and(|issued_at| issued_at <= now);
+        if !is_issued {
+            return Err(LogoutTokenError::NotYetIssued);
+        }
+        if let Some(max_age) = self.max_age {
+            let is_fresh = issued_at
+                .checked_add_signed(max_age)
+                .and_then(|expiration| expiration.checked_add_signed(options.leeway))
+                .is_some_and(|expiration| expiration >= now);
+            if !is_fresh {
+                return Err(LogoutTokenError::TooOld);
+            }
+        }
+        if claims.expiration.is_some() {
+            claims.validate_expiration(options)?;
+        }
+
+        let custom = &claims.custom;
+        if custom.issuer != self.issuer {
+            return Err(LogoutTokenError::ClaimMismatch("iss"));
+        }
+        if !custom.audience.contains(&self.client_id) {
+            return Err(LogoutTokenError::ClaimMismatch("aud"));
+        }
+        if custom.jwt_id.is_empty() {
+            return Err(LogoutTokenError::MissingClaim("jti"));
+        }
+        match custom.events.get(BACKCHANNEL_LOGOUT_EVENT) {
+            Some(Value::Object(_)) => { /* OK */ }
+            Some(_) => return Err(LogoutTokenError::ClaimMismatch("events")),
+            None => return Err(LogoutTokenError::MissingClaim("events")),
+        }
+        if custom.subject.is_none() && custom.session_id.is_none() {
+            return Err(LogoutTokenError::MissingClaim("sub"));
+        }
+        Ok(Token::new(token, claims))
+    }
+
+    /// Validates the token and records its ID in the provided `replay_guard`. If the ID
+    /// was already recorded, returns [`LogoutTokenError::Replayed`].
+    pub fn validate_once<A: Algorithm, F>(
+        &self,
+        algorithm: &A,
+        verifying_key: &A::VerifyingKey,
+        token: &UntrustedToken<'_>,
+        options: &TimeOptions<F>,
+        mut replay_guard: impl ReplayGuard,
+    ) -> Result<Token<LogoutTokenClaims>, LogoutTokenError>
+    where
+        F: Fn() -> Timestamp,
+    {
+        let token = self.validate(algorithm, verifying_key, token, options)?;
+        let claims = token.claims();
+        let expires_at = claims.expiration.unwrap_or_else(|| {
+            // `issued_at` is checked to be present in `validate()`.
+            let issued_at = claims.issued_at.unwrap_or(Timestamp::MAX);
+            self.max_age
+                .and_then(|max_age| issued_at.checked_add_signed(max_age))
+                .unwrap_or(Timestamp::MAX)
+        });
+        if replay_guard.record(&claims.custom.jwt_id, expires_at) {
+            Ok(token)
+        } else {
+            Err(LogoutTokenError::Replayed)
+        }
+    }
+}
+
+/// Errors that can occur during logout token validation.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum LogoutTokenError {
+    /// Token has failed generic validation (e.g., has an invalid signature).
+    Validation(ValidationError),
+    /// The `typ` header field has an unexpected value.
+    InvalidTokenType,
+    /// Required claim is missing or empty. If neither `sub` nor `sid` is present,
+    /// the error refers to `sub`.
+    MissingClaim(&'static str),
+    /// Claim has an unexpected value.
+    ClaimMismatch(&'static str),
+    /// Claim that must not be present in logout tokens is present.
+    ForbiddenClaim(&'static str),
+    /// Token is issued in the future.
+    NotYetIssued,
+    /// Token is older than the maximum age.
+    TooOld,
+    /// Token was already processed.
+    Replayed,
+}
+
+impl fmt::Display for LogoutTokenError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Validation(err) => write!(formatter, "token validation failed: {err}"),
+            Self::InvalidTokenType => formatter.write_str("token is not a logout token"),
+            Self::MissingClaim(claim) => write!(formatter, "claim `{claim}` is missing"),
+            Self::ClaimMismatch(claim) => write!(formatter, "claim `{claim}` has unexpected value"),
+            Self::ForbiddenClaim(claim) => {
+                write!(
+                    formatter,
+                    "claim `{claim}` must not be present in logout tokens"
+                )
+            }
+            Self::NotYetIssued => formatter.write_str("token is issued in the future"),
+            Self::TooOld => formatter.write_str("token is too old"),
+            Self::Replayed => formatter.write_str("token was already processed"),
+        }
+    }
+}
+
+impl core::error::Error for LogoutTokenError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::Validation(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<ValidationError> for LogoutTokenError {
+    fn from(err: ValidationError) -> Self {
+        Self::Validation(err)
+    }
+}
+
+#[cfg(all(test, feature = "clock"))]
+mod tests {
+    use assert_matches::assert_matches;
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        alg::Hs256,
+        replay::MemoryReplayGuard,
+        test_helpers::{key, sign},
+        Header,
+    };
+
+    const ISSUER: &str = "https://op.example.com";
+    const CLIENT_ID: &str = "s6BhdRkqt3";
+
+    fn validate(token: &str) -> Result<Token<LogoutTokenClaims>, LogoutTokenError> {
+        let token = UntrustedToken::new(token).unwrap();
+        LogoutTokenValidator::new(ISSUER, CLIENT_ID).validate(
+            &Hs256,
+            &key(),
+            &token,
+            &TimeOptions::default(),
+        )
+    }
+
+    #[test]
+    fn valid_logout_token() {
+        let claims = LogoutTokenClaims::new(ISSUER, CLIENT_ID, "bWJq").with_subject("alice");
+        let header = Header::empty().with_token_type(LOGOUT_TOKEN_TYPE);
+        let token = validate(&sign(&claims, &header)).unwrap();
+        assert_eq!(token.claims().custom, claims);
+    }
+
+    #[test]
+    fn invalid_logout_tokens() {
+        let claims = LogoutTokenClaims::new(ISSUER, CLIENT_ID, "bWJq");
+        let err = validate(&sign(&claims, &Header::empty())).unwrap_err();
+        assert_matches!(err, LogoutTokenError::MissingClaim("sub"));
+
+        let mut with_nonce = serde_json::to_value(claims.clone().with_subject("alice")).unwrap();
+        with_nonce["nonce"] = "n-0S6_WzA2Mj".into();
+        let err = validate(&sign(&with_nonce, &Header::empty())).unwrap_err();
+        assert_matches!(err, LogoutTokenError::ForbiddenClaim("nonce"));
+
+        let mut without_event = claims.clone().with_session_id("sid");
+        without_event.events.clear();
+        let err = validate(&sign(&without_event, &Header::empty())).unwrap_err();
+        assert_matches!(err, LogoutTokenError::MissingClaim("events"));
+
+        let mut invalid_event = claims.clone().with_session_id("sid");
+        invalid_event
+            .events
+            .insert(BACKCHANNEL_LOGOUT_EVENT.to_owned(), json!("logout"));
+        let err = validate(&sign(&invalid_event, &Header::empty())).unwrap_err();
+        assert_matches!(err, LogoutTokenError::ClaimMismatch("events"));
+
+        let other_client = LogoutTokenClaims::new(ISSUER, "other", "bWJq").with_subject("alice");
+        let err = validate(&sign(&other_client, &Header::empty())).unwrap_err();
+        assert_matches!(err, LogoutTokenError::ClaimMismatch("aud"));
+
+        let header = Header::empty().with_token_type("secevent+jwt");
+        let err = validate(&sign(&claims.with_subject("alice"), &header)).unwrap_err();
+        assert_matches!(err, LogoutTokenError::InvalidTokenType);
+    }
+
+    #[test]
+    fn logout_token_with_extreme_issuance_time() {
+        let claims = LogoutTokenClaims::new(ISSUER, CLIENT_ID, "bWJq").with_subject("alice");
+        let mut claims = Claims::new(claims);
+        claims.issued_at = Some(Timestamp::MAX);
+        let token = Hs256.token(&Header::empty(), &claims, &key()).unwrap();
+        let token = UntrustedToken::new(&token).unwrap();
+
+        let options = TimeOptions::new(Duration::zero(), || Timestamp::MAX);
+        let err = LogoutTokenValidator::new(ISSUER, CLIENT_ID)
+            .with_max_age(Duration::try_minutes(5).unwrap())
+            .validate(&Hs256, &key(), &token, &options)
+            .unwrap_err();
+        assert_matches!(err, LogoutTokenError::TooOld);
+    }
+
+    #[test]
+    fn replayed_logout_token() {
+        let claims = LogoutTokenClaims::new(ISSUER, CLIENT_ID, "bWJq").with_session_id("sid");
+        let token = sign(&claims, &Header::empty());
+        let token = UntrustedToken::new(&token).unwrap();
+        let validator = LogoutTokenValidator::new(ISSUER, CLIENT_ID);
+        let options = TimeOptions::default();
+        let mut guard = MemoryReplayGuard::new();
+
+        validator
+            .validate_once(&Hs256, &key(), &token, &options, &mut guard)
+            .unwrap();
+        assert_eq!(guard.len(), 1);
+        let err = validator
+            .validate_once(&Hs256, &key(), &token, &options, &mut guard)
+            .unwrap_err();
+        assert_matches!(err, LogoutTokenError::Replayed);
+    }
+}
@@ -0,0 +1,330 @@
+//! Signed `UserInfo` responses as per [OpenID Connect Core, Section 5.3.2].
+//!
+//! [OpenID Connect Core, Section 5.3.2]: https://openid.net/specs/openid-connect-core-1_0.html#UserInfoResponse
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use core::fmt;
+
+use crate::{
+    alloc::{String, Vec},
+    claims::serde_audience,
+    Algorithm, AlgorithmExt, TimeOptions, Timestamp, Token, UntrustedToken, ValidationError,
+};
+
+/// Claims that describe the token itself rather than the end user. These claims
+/// are not merged in [`UserInfoClaims::merge_with_id_token()`].
+const TOKEN_CLAIMS: &[&str] = &[
+    "iss",
+    "aud",
+    "exp",
+    "iat",
+    "nbf",
+    "jti",
+    "nonce",
+    "azp",
+    "at_hash",
+    "c_hash",
+    "auth_time",
+    "acr",
+    "amr",
+    "sid",
+];
+
+/// Claims of a signed `UserInfo` response.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct UserInfoClaims {
+    /// Subject, i.e., the end user identifier (`sub`).
+    #[serde(rename = "sub")]
+    pub subject: String,
+    /// Issuer of the response (`iss`), i.e., the `OpenID` provider.
+    #[serde(rename = "iss", default, skip_serializing_if = "Option::is_none")]
+    pub issuer: Option<String>,
+    /// Intended recipients of the response (`aud`), i.e., client IDs of relying parties.
+    #[serde(
+        rename = "aud",
+        with = "serde_audience",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub audience: Vec<String>,
+    /// Other claims about the end user, such as `name` or `email`.
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+impl UserInfoClaims {
+    /// Merges these claims with claims from the ID token, returning the combined claims
+    /// about the end user.
+    ///
+    /// The `sub` claim must be equal to the one in the ID token. Other user claims present both
+    /// in the `UserInfo` response and in the ID token must have equal values. Claims describing
+    /// the token rather than the user (such as `iss`, `exp` or `nonce`) are taken from
+    /// the ID token.
+    pub fn merge_with_id_token(
+        &self,
+        id_token_claims: &Map<String, Value>,
+    ) -> Result<Map<String, Value>, UserInfoError> {
+        match id_token_claims.get("sub") {
+            Some(Value::String(subject)) if *subject == self.subject => { /* OK */ }
+            _ => return Err(UserInfoError::SubjectMismatch),
+        }
+
+        let mut merged = id_token_claims.clone();
+        for (name, value) in &self.other {
+            if TOKEN_CLAIMS.contains(&name.as_str()) {
+                continue;
+            }
+            match merged.get(name) {
+                Some(existing) if existing != value => {
+                    return Err(UserInfoError::ConflictingClaim(name.clone()));
+                }
+                Some(_) => { /* Same value; nothing to do */ }
+                None => {
+                    merged.insert(name.clone(), value.clone());
+                }
+            }
+        }
+        Ok(merged)
+    }
+}
+
+/// Validator of signed `UserInfo` responses used by relying parties.
+///
+/// Besides the signature, the validator checks that the `iss` claim (if present) matches
+/// the expected issuer, and the `aud` claim (if present) contains the client ID.
+/// These claims can be made mandatory via [`Self::require_issuer_and_audience()`].
+/// If the `exp` claim is present, the response must not be expired.
+///
+/// Encrypted responses are not supported.
+///
+/// # Examples
+///
+/// ```
+/// # use jwt_compact::{alg::{Hs256, Hs256Key}, prelude::*};
+/// use jwt_compact::oidc::UserInfoValidator;
+/// # use serde_json::json;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// # let key = Hs256Key::new(b"super_secret_key_donut_steel");
+/// # let claims = Claims::new(json!({
+/// #     "sub": "248289761001",
+/// #     "iss": "https://op.example.com",
+/// #     "aud": "my-client",
+/// #     "email": "janedoe@example.com",
+/// # }));
+/// # let response = Hs256.token(&Header::empty(), &claims, &key)?;
+/// # let id_token_claims = json!({ "sub": "248289761001", "name": "Jane Doe" });
+/// # let id_token_claims = id_token_claims.as_object().unwrap();
+/// let validator = UserInfoValidator::new("https://op.example.com", "my-client")
+///     .require_issuer_and_audience();
+/// let response = UntrustedToken::new(&response)?;
+/// let user_info =
+///     validator.validate(&Hs256, &key, &response, &TimeOptions::default())?;
+/// let user = user_info.claims().custom.merge_with_id_token(id_token_claims)?;
+/// assert_eq!(user["email"], "janedoe@example.com");
+/// assert_eq!(user["name"], "Jane Doe");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct UserInfoValidator {
+    issuer: String,
+    client_id: String,
+    require_issuer_and_audience: bool,
+}
+
+impl UserInfoValidator {
+    /// Creates a validator with the expected issuer and the client ID of the relying party.
+    pub fn new(issuer: impl Into<String>, client_id: impl Into<String>) -> Self {
+        Self {
+            issuer: issuer.into(),
+            client_id: client_id.into(),
+            require_issuer_and_audience: false,
+        }
+    }
+
+    /// Requires the `iss` and `aud` claims to be present in responses.
+    #[must_use]
+    pub fn require_issuer_and_audience(mut self) -> Self {
+        self.require_issuer_and_audience = true;
+        self
+    }
+
+    /// Validates the signed `UserInfo` response.
+    pub fn validate<A: Algorithm, F>(
+        &self,
+        algorithm: &A,
+        verifying_key: &A::VerifyingKey,
+        token: &UntrustedToken<'_>,
+        options: &TimeOptions<F>,
+    ) -> Result<Token<UserInfoClaims>, UserInfoError>
+    where
+        F: Fn() -> Timestamp,
+    {
+        let token = algorithm
+            .validator::<UserInfoClaims>(verifying_key)
+            .validate(token)?;
+        let claims = token.claims();
+        if claims.expiration.is_some() {
+            claims.validate_expiration(options)?;
+        }
+
+        let custom = &claims.custom;
+        match &custom.issuer {
+            Some(issuer) if *issuer != self.issuer => {
+                return Err(UserInfoError::ClaimMismatch("iss"));
+            }
+            None if self.require_issuer_and_audience => {
+                return Err(UserInfoError::MissingClaim("iss"));
+            }
+            _ => { /* OK */ }
+        }
+        if custom.audience.is_empty() {
+            if self.require_issuer_and_audience {
+                return Err(UserInfoError::MissingClaim("aud"));
+            }
+        } else if !custom.audience.contains(&self.client_id) {
+            return Err(UserInfoError::ClaimMismatch("aud"));
+        }
+        Ok(token)
+    }
+}
+
+/// Errors that can occur during validation of `UserInfo` responses.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum UserInfoError {
+    /// Response has failed generic validation (e.g., has an invalid signature).
+    Validation(ValidationError),
+    /// Required claim is missing.
+    MissingClaim(&'static str),
+    /// Claim has an unexpected value.
+    ClaimMismatch(&'static str),
+    /// The `sub` claim differs from the one in the ID token.
+    SubjectMismatch,
+    /// Claim has different values in the response and in the ID token.
+    ConflictingClaim(String),
+}
+
+impl fmt::Display for UserInfoError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Validation(err) => write!(formatter, "response validation failed: {err}"),
+            Self::MissingClaim(claim) => write!(formatter, "claim `{claim}` is missing"),
+            Self::ClaimMismatch(claim) => write!(formatter, "claim `{claim}` has unexpected value"),
+            Self::SubjectMismatch => {
+                formatter.write_str("`sub` claim differs from the one in the ID token")
+            }
+            Self::ConflictingClaim(claim) => {
+                write!(formatter, "claim `{claim}` conflicts with the ID token")
+            }
+        }
+    }
+}
+
+impl core::error::Error for UserInfoError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::Validation(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<ValidationError> for UserInfoError {
+    fn from(err: ValidationError) -> Self {
+        Self::Validation(err)
+    }
+}
+
+#[cfg(all(test, feature = "clock"))]
+mod tests {
+    use assert_matches::assert_matches;
+    use serde_json::json;
+
+    use super::*;
+    use crate::{alg::Hs256, test_helpers::key, Claims, Header};
+
+    const ISSUER: &str = "https://op.example.com";
+    const CLIENT_ID: &str = "s6BhdRkqt3";
+
+    fn validate(
+        claims: &Value,
+        validator: &UserInfoValidator,
+    ) -> Result<UserInfoClaims, UserInfoError> {
+        let token = Hs256
+            .token(&Header::empty(), &Claims::new(claims), &key())
+            .unwrap();
+        let token = UntrustedToken::new(&token).unwrap();
+        let token = validator.validate(&Hs256, &key(), &token, &TimeOptions::default())?;
+        Ok(token.into_parts().1.custom)
+    }
+
+    #[test]
+    fn validating_userinfo() {
+        let validator = UserInfoValidator::new(ISSUER, CLIENT_ID);
+        let claims = json!({ "sub": "alice", "email": "alice@example.com" });
+        let user_info = validate(&claims, &validator).unwrap();
+        assert_eq!(user_info.subject, "alice");
+        assert_eq!(user_info.other["email"], "alice@example.com");
+
+        let strict_validator = validator.clone().require_issuer_and_audience();
+        let err = validate(&claims, &strict_validator).unwrap_err();
+        assert_matches!(err, UserInfoError::MissingClaim("iss"));
+        let claims = json!({ "sub": "alice", "iss": ISSUER, "aud": [CLIENT_ID, "other"] });
+        validate(&claims, &strict_validator).unwrap();
+
+        let claims = json!({ "sub": "alice", "iss": "https://evil.example.com" });
+        let err = validate(&claims, &validator).unwrap_err();
+        assert_matches!(err, UserInfoError::ClaimMismatch("iss"));
+        let claims = json!({ "sub": "alice", "aud": "other" });
+        let err = validate(&claims, &validator).unwrap_err();
+        assert_matches!(err, UserInfoError::ClaimMismatch("aud"));
+    }
+
+    #[test]
+    fn merging_with_id_token() {
+        let user_info: UserInfoClaims = serde_json::from_value(json!({
+            "sub": "alice",
+            "iss": ISSUER,
+            "email": "alice@example.com",
+            "name": "Alice",
+            "nonce": "ignored",
+        }))
+        .unwrap();
+        let id_token = json!({
+            "sub": "alice",
+            "iss": ISSUER,
+            "name": "Alice",
+            "nonce": "n-0S6_WzA2Mj",
+        });
+        let merged = user_info
+            .merge_with_id_token(id_token.as_object().unwrap())
+            .unwrap();
+        assert_eq!(
+            Value::Object(merged),
+            json!({
+                "sub": "alice",
+                "iss": ISSUER,
+                "name": "Alice",
+                "email": "alice@example.com",
+                "nonce": "n-0S6_WzA2Mj",
+            })
+        );
+
+        let id_token = json!({ "sub": "bob" });
+        let err = user_info
+            .merge_with_id_token(id_token.as_object().unwrap())
+            .unwrap_err();
+        assert_matches!(err, UserInfoError::SubjectMismatch);
+
+        let id_token = json!({ "sub": "alice", "name": "Alicia" });
+        let err = user_info
+            .merge_with_id_token(id_token.as_object().unwrap())
+            .unwrap_err();
+        assert_matches!(err, UserInfoError::ConflictingClaim(name) if name == "name");
+    }
+}
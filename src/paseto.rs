@@ -0,0 +1,612 @@
+//! [PASETO] v4 tokens sharing the claims pipeline with JWTs.
+//!
+//! This module allows issuing and verifying `v4.local` (symmetric authenticated encryption
+//! with `XChaCha20` and `BLAKE2b`) and `v4.public` (Ed25519 signatures) PASETO tokens using
+//! the same [`Claims`] as JWTs. Time-related claims are validated in the same way as for JWTs,
+//! e.g., via [`Claims::validate_expiration()`].
+//!
+//! PASETO represents time-related claims (`exp`, `nbf` and `iat`) as RFC 3339 strings rather
+//! than Unix timestamps used by JWTs; [`to_paseto_payload()`] and [`from_paseto_payload()`]
+//! convert between the two representations.
+//!
+//! `v4.public` tokens can be produced by any Ed25519 [`Algorithm`] implementation
+//! provided by the crate (i.e., `Ed25519` with any of the backends).
+//!
+//! [PASETO]: https://github.com/paseto-standard/paseto-spec
+//!
+//! # Examples
+//!
+//! ```
+//! # use chrono::Duration;
+//! # use jwt_compact::{prelude::*, paseto::{self, LocalKey}};
+//! # use serde::{Deserialize, Serialize};
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct CustomClaims {
+//!     #[serde(rename = "sub")]
+//!     subject: String,
+//! }
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let key = LocalKey::generate(&mut rand::thread_rng());
+//! let time_options = TimeOptions::default();
+//! let claims = Claims::new(CustomClaims { subject: "alice".to_owned() })
+//!     .set_duration_and_issuance(&time_options, Duration::try_hours(1).unwrap());
+//! let token = paseto::encrypt_local(&key, &claims, b"", b"", &mut rand::thread_rng())?;
+//! assert!(token.starts_with("v4.local."));
+//!
+//! let token = paseto::decrypt_local::<CustomClaims>(&key, &token, b"")?;
+//! token.claims().validate_expiration(&time_options)?;
+//! assert_eq!(token.claims().custom.subject, "alice");
+//! # Ok(())
+//! # }
+//! ```
+
+use base64ct::{Base64UrlUnpadded, Encoding};
+use blake2::{
+    digest::{
+        consts::{U32, U56, U64},
+        generic_array::ArrayLength,
+        typenum::{IsLessOrEqual, LeEq, NonZero},
+        KeyInit, Mac,
+    },
+    Blake2bMac,
+};
+use chacha20::{
+    cipher::{KeyIvInit, StreamCipher},
+    Key, XChaCha20,
+};
+use chrono::{DateTime, Datelike, Timelike, Utc};
+use rand_core::{CryptoRng, RngCore};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{Map, Value};
+use zeroize::{Zeroize, Zeroizing};
+
+use core::{fmt, num::NonZeroUsize};
+
+use crate::{
+    alloc::{format, String, ToOwned, Vec},
+    Algorithm, AlgorithmSignature, Claims,
+};
+
+const LOCAL_HEADER: &str = "v4.local.";
+const PUBLIC_HEADER: &str = "v4.public.";
+const NONCE_LEN: usize = 32;
+const TAG_LEN: usize = 32;
+const SIGNATURE_LEN: usize = 64;
+/// Time-related claims that have different representations in JWTs and PASETO tokens.
+const TIME_CLAIMS: [&str; 3] = ["exp", "nbf", "iat"];
+
+/// Errors that can occur when creating or verifying PASETO tokens.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum PasetoError {
+    /// Token has invalid structure or an unsupported version / purpose.
+    InvalidFormat,
+    /// Cannot decode base64.
+    InvalidBase64Encoding,
+    /// Algorithm is not suitable for `v4.public` tokens.
+    UnsupportedAlgorithm(String),
+    /// Symmetric key has invalid length.
+    InvalidKeyLength(usize),
+    /// Token signature or authentication tag has failed verification.
+    InvalidSignature,
+    /// Claims cannot be serialized or deserialized.
+    Claims(serde_json::Error),
+    /// Claims are not a JSON object, or a time-related claim has invalid format.
+    MalformedClaims(&'static str),
+}
+
+impl fmt::Display for PasetoError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidFormat => formatter.write_str("invalid PASETO token structure"),
+            Self::InvalidBase64Encoding => formatter.write_str("invalid base64 decoding"),
+            Self::UnsupportedAlgorithm(alg) => {
+                write!(
+                    formatter,
+                    "algorithm `{alg}` is not supported for v4.public tokens"
+                )
+            }
+            Self::InvalidKeyLength(len) => {
+                write!(
+                    formatter,
+                    "invalid key length: expected 32 bytes, got {len}"
+                )
+            }
+            Self::InvalidSignature => formatter.write_str("token has failed verification"),
+            Self::Claims(err) => write!(formatter, "cannot (de)serialize claims: {err}"),
+            Self::MalformedClaims(claim) => write!(formatter, "malformed claim `{claim}`"),
+        }
+    }
+}
+
+impl core::error::Error for PasetoError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::Claims(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// Symmetric key for `v4.local` tokens.
+#[derive(Clone)]
+pub struct LocalKey([u8; 32]);
+
+/// Outputs only the key length, never the key bytes.
+impl fmt::Debug for LocalKey {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("LocalKey")
+            .field("len", &self.0.len())
+            .finish()
+    }
+}
+
+impl Drop for LocalKey {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl LocalKey {
+    /// Creates a key from the provided bytes.
+    pub fn new(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+
+    /// Creates a key from a byte slice, which must have length 32.
+    pub fn from_slice(bytes: &[u8]) -> Result<Self, PasetoError> {
+        let bytes = bytes
+            .try_into()
+            .map_err(|_| PasetoError::InvalidKeyLength(bytes.len()))?;
+        Ok(Self(bytes))
+    }
+
+    /// Generates a random key.
+    pub fn generate(rng: &mut (impl CryptoRng + RngCore)) -> Self {
+        let mut bytes = [0_u8; 32];
+        rng.fill_bytes(&mut bytes);
+        Self(bytes)
+    }
+
+    /// Derives the encryption key, the `XChaCha20` nonce and the authentication key.
+    /// The keys are zeroized on drop.
+    fn derive_keys(&self, nonce: &[u8]) -> (Zeroizing<[u8; 32]>, [u8; 24], Zeroizing<[u8; 32]>) {
+        let mut mac = keyed_blake2b::<U56>(&self.0);
+        mac.update(b"paseto-encryption-key");
+        mac.update(nonce);
+        let mut tmp: [u8; 56] = mac.finalize().into_bytes().into();
+        let mut encryption_key = Zeroizing::new([0_u8; 32]);
+        encryption_key.copy_from_slice(&tmp[..32]);
+        let mut counter_nonce = [0_u8; 24];
+        counter_nonce.copy_from_slice(&tmp[32..]);
+        tmp.zeroize();
+
+        let mut mac = keyed_blake2b::<U32>(&self.0);
+        mac.update(b"paseto-auth-key-for-aead");
+        mac.update(nonce);
+        let mut auth_key = Zeroizing::new([0_u8; 32]);
+        auth_key.copy_from_slice(&mac.finalize().into_bytes());
+        (encryption_key, counter_nonce, auth_key)
+    }
+}
+
+fn keyed_blake2b<N>(key: &[u8; 32]) -> Blake2bMac<N>
+where
+    N: ArrayLength<u8> + IsLessOrEqual<U64>,
+    LeEq<N, U64>: NonZero,
+{
+    <Blake2bMac<N> as KeyInit>::new_from_slice(key)
+        .expect("32-byte key is always valid for BLAKE2b")
+}
+
+/// PASETO token with verified claims and the footer.
+#[derive(Debug, Clone)]
+pub struct PasetoToken<T> {
+    claims: Claims<T>,
+    footer: Vec<u8>,
+}
+
+impl<T> PasetoToken<T> {
+    /// Returns the token claims.
+    pub fn claims(&self) -> &Claims<T> {
+        &self.claims
+    }
+
+    /// Returns the unencrypted footer of the token (empty if the token has no footer).
+    pub fn footer(&self) -> &[u8] {
+        &self.footer
+    }
+
+    /// Converts this token into claims.
+    pub fn into_claims(self) -> Claims<T> {
+        self.claims
+    }
+}
+
+/// Converts claims into the PASETO payload, representing time-related claims
+/// as RFC 3339 strings.
+pub fn to_paseto_payload<T: Serialize>(
+    claims: &Claims<T>,
+) -> Result<Map<String, Value>, PasetoError> {
+    let Value::Object(mut payload) = serde_json::to_value(claims).map_err(PasetoError::Claims)?
+    else {
+        return Err(PasetoError::MalformedClaims("*"));
+    };
+    for claim in TIME_CLAIMS {
+        if let Some(value) = payload.get_mut(claim) {
+            let timestamp = value
+                .as_i64()
+                .and_then(|ts| DateTime::from_timestamp(ts, 0))
+                .ok_or(PasetoError::MalformedClaims(claim))?;
+            *value = Value::String(format_timestamp(timestamp));
+        }
+    }
+    Ok(payload)
+}
+
+/// Converts the PASETO payload into claims, parsing time-related claims from RFC 3339 strings.
+pub fn from_paseto_payload<T: DeserializeOwned>(
+    mut payload: Map<String, Value>,
+) -> Result<Claims<T>, PasetoError> {
+    for claim in TIME_CLAIMS {
+        if let Some(value) = payload.get_mut(claim) {
+            let timestamp = value
+                .as_str()
+                .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+                .ok_or(PasetoError::MalformedClaims(claim))?;
+            *value = timestamp.timestamp().into();
+        }
+    }
+    serde_json::from_value(Value::Object(payload)).map_err(PasetoError::Claims)
+}
+
+fn format_timestamp(timestamp: DateTime<Utc>) -> String {
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}+00:00",
+        timestamp.year(),
+        timestamp.month(),
+        timestamp.day(),
+        timestamp.hour(),
+        timestamp.minute(),
+        timestamp.second()
+    )
+}
+
+/// Pre-authentication encoding (PAE) of the provided pieces.
+fn pae(pieces: &[&[u8]]) -> Vec<u8> {
+    let mut output = Vec::new();
+    output.extend_from_slice(&(pieces.len() as u64).to_le_bytes());
+    for piece in pieces {
+        output.extend_from_slice(&(piece.len() as u64).to_le_bytes());
+        output.extend_from_slice(piece);
+    }
+    output
+}
+
+fn encode_token(header: &str, body: &[u8], footer: &[u8]) -> String {
+    let mut token = header.to_owned();
+    token.push_str(&Base64UrlUnpadded::encode_string(body));
+    if !footer.is_empty() {
+        token.push('.');
+        token.push_str(&Base64UrlUnpadded::encode_string(footer));
+    }
+    token
+}
+
+/// Splits the token into the decoded body and footer.
+fn decode_token(token: &str, header: &str) -> Result<(Vec<u8>, Vec<u8>), PasetoError> {
+    let rest = token
+        .strip_prefix(header)
+        .ok_or(PasetoError::InvalidFormat)?;
+    let (body, footer) = match rest.split_once('.') {
+        Some((body, footer)) => (body, footer),
+        None => (rest, ""),
+    };
+    let body =
+        Base64UrlUnpadded::decode_vec(body).map_err(|_| PasetoError::InvalidBase64Encoding)?;
+    let footer =
+        Base64UrlUnpadded::decode_vec(footer).map_err(|_| PasetoError::InvalidBase64Encoding)?;
+    Ok((body, footer))
+}
+
+fn serialize_payload<T: Serialize>(claims: &Claims<T>) -> Result<Vec<u8>, PasetoError> {
+    let payload = to_paseto_payload(claims)?;
+    serde_json::to_vec(&payload).map_err(PasetoError::Claims)
+}
+
+fn deserialize_payload<T: DeserializeOwned>(payload: &[u8]) -> Result<Claims<T>, PasetoError> {
+    let payload = serde_json::from_slice(payload).map_err(PasetoError::Claims)?;
+    from_paseto_payload(payload)
+}
+
+/// Creates a `v4.local` token encrypting the provided claims. The `footer` is authenticated,
+/// but not encrypted; the `implicit` assertion is authenticated, but not included
+/// into the token.
+pub fn encrypt_local<T: Serialize>(
+    key: &LocalKey,
+    claims: &Claims<T>,
+    footer: &[u8],
+    implicit: &[u8],
+    rng: &mut (impl CryptoRng + RngCore),
+) -> Result<String, PasetoError> {
+    let mut nonce = [0_u8; NONCE_LEN];
+    rng.fill_bytes(&mut nonce);
+    let message = serialize_payload(claims)?;
+    Ok(encrypt_local_with_nonce(
+        key, &message, footer, implicit, nonce,
+    ))
+}
+
+fn encrypt_local_with_nonce(
+    key: &LocalKey,
+    message: &[u8],
+    footer: &[u8],
+    implicit: &[u8],
+    nonce: [u8; NONCE_LEN],
+) -> String {
+    let (encryption_key, counter_nonce, auth_key) = key.derive_keys(&nonce);
+    let mut ciphertext = message.to_vec();
+    XChaCha20::new(Key::from_slice(&*encryption_key), &counter_nonce.into())
+        .apply_keystream(&mut ciphertext);
+
+    let pre_auth = pae(&[
+        LOCAL_HEADER.as_bytes(),
+        &nonce,
+        &ciphertext,
+        footer,
+        implicit,
+    ]);
+    let mut mac = keyed_blake2b::<U32>(&auth_key);
+    mac.update(&pre_auth);
+    let tag = mac.finalize().into_bytes();
+
+    let mut body = Vec::with_capacity(NONCE_LEN + ciphertext.len() + TAG_LEN);
+    body.extend_from_slice(&nonce);
+    body.extend_from_slice(&ciphertext);
+    body.extend_from_slice(&tag);
+    encode_token(LOCAL_HEADER, &body, footer)
+}
+
+/// Decrypts and verifies a `v4.local` token.
+pub fn decrypt_local<T: DeserializeOwned>(
+    key: &LocalKey,
+    token: &str,
+    implicit: &[u8],
+) -> Result<PasetoToken<T>, PasetoError> {
+    let (body, footer) = decode_token(token, LOCAL_HEADER)?;
+    if body.len() < NONCE_LEN + TAG_LEN {
+        return Err(PasetoError::InvalidFormat);
+    }
+    let (nonce, rest) = body.split_at(NONCE_LEN);
+    let (ciphertext, tag) = rest.split_at(rest.len() - TAG_LEN);
+
+    let (encryption_key, counter_nonce, auth_key) = key.derive_keys(nonce);
+    let pre_auth = pae(&[
+        LOCAL_HEADER.as_bytes(),
+        nonce,
+        ciphertext,
+        &footer,
+        implicit,
+    ]);
+    let mut mac = keyed_blake2b::<U32>(&auth_key);
+    mac.update(&pre_auth);
+    drop(auth_key);
+    // `verify_slice` performs constant-time comparison.
+    mac.verify_slice(tag)
+        .map_err(|_| PasetoError::InvalidSignature)?;
+
+    let mut message = ciphertext.to_vec();
+    XChaCha20::new(Key::from_slice(&*encryption_key), &counter_nonce.into())
+        .apply_keystream(&mut message);
+    drop(encryption_key);
+    let claims = deserialize_payload(&message)?;
+    Ok(PasetoToken { claims, footer })
+}
+
+fn check_public_algorithm<A: Algorithm>(algorithm: &A) -> Result<(), PasetoError> {
+    let name = algorithm.name();
+    let is_ed25519 = matches!(name.as_ref(), "EdDSA" | "Ed25519");
+    if is_ed25519 && A::Signature::LENGTH == NonZeroUsize::new(SIGNATURE_LEN) {
+        Ok(())
+    } else {
+        Err(PasetoError::UnsupportedAlgorithm(name.into_owned()))
+    }
+}
+
+/// Creates a `v4.public` token signing the provided claims with an Ed25519 `algorithm`.
+/// The `footer` is signed and included into the token; the `implicit` assertion is signed,
+/// but not included into the token.
+#[cfg(feature = "sign")]
+#[cfg_attr(docsrs, doc(cfg(feature = "sign")))]
+pub fn sign_public<A: Algorithm, T: Serialize>(
+    algorithm: &A,
+    signing_key: &A::SigningKey,
+    claims: &Claims<T>,
+    footer: &[u8],
+    implicit: &[u8],
+) -> Result<String, PasetoError> {
+    check_public_algorithm(algorithm)?;
+    let mut message = serialize_payload(claims)?;
+    let pre_auth = pae(&[PUBLIC_HEADER.as_bytes(), &message, footer, implicit]);
+    let signature = algorithm.sign(signing_key, &pre_auth);
+    message.extend_from_slice(&signature.as_bytes());
+    Ok(encode_token(PUBLIC_HEADER, &message, footer))
+}
+
+/// Verifies a `v4.public` token using an Ed25519 `algorithm`.
+pub fn verify_public<A: Algorithm, T: DeserializeOwned>(
+    algorithm: &A,
+    verifying_key: &A::VerifyingKey,
+    token: &str,
+    implicit: &[u8],
+) -> Result<PasetoToken<T>, PasetoError> {
+    check_public_algorithm(algorithm)?;
+    let (body, footer) = decode_token(token, PUBLIC_HEADER)?;
+    if body.len() < SIGNATURE_LEN {
+        return Err(PasetoError::InvalidFormat);
+    }
+    let (message, signature) = body.split_at(body.len() - SIGNATURE_LEN);
+    let signature =
+        A::Signature::try_from_slice(signature).map_err(|_| PasetoError::InvalidSignature)?;
+    let pre_auth = pae(&[PUBLIC_HEADER.as_bytes(), message, &footer, implicit]);
+    if !algorithm.verify_signature(&signature, verifying_key, &pre_auth) {
+        return Err(PasetoError::InvalidSignature);
+    }
+    let claims = deserialize_payload(message)?;
+    Ok(PasetoToken { claims, footer })
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use chrono::TimeZone;
+    use const_decoder::Decoder::Hex;
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct TestClaims {
+        data: String,
+    }
+
+    fn expected_claims(data: &str) -> Claims<TestClaims> {
+        let mut claims = Claims::new(TestClaims {
+            data: data.to_owned(),
+        });
+        claims.expiration = Utc
+            .with_ymd_and_hms(2022, 1, 1, 0, 0, 0)
+            .single()
+            .map(Into::into);
+        claims
+    }
+
+    // Test vectors 4-E-1 and 4-E-5 from https://github.com/paseto-standard/test-vectors
+    const LOCAL_KEY: [u8; 32] =
+        Hex.decode(b"707172737475767778797a7b7c7d7e7f808182838485868788898a8b8c8d8e8f");
+    const LOCAL_TOKEN: &str =
+        "v4.local.AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAQAr68PS4AXe7If_ZgesdkUMvSwscFlAl1pk5HC0e8\
+         kApeaqMfGo_7OpBnwJOAbY9V7WU6abu74MmcUE8YWAiaArVI8XJ5hOb_4v9RmDkneN0S92dx0OW4pgy7omxgf3S8c3LlQg";
+    const LOCAL_NONCE_WITH_FOOTER: [u8; 32] =
+        Hex.decode(b"df654812bac492663825520ba2f6e67cf5ca5bdc13d4e7507a98cc4c2fcc3ad8");
+    const LOCAL_TOKEN_WITH_FOOTER: &str =
+        "v4.local.32VIErrEkmY4JVILovbmfPXKW9wT1OdQepjMTC_MOtjA4kiqw7_tcaOM5GNEcnTxl60WkwMsYXw6FSNb_Ud\
+         JPXjpzm0KW9ojM5f4O2mRvE2IcweP-PRdoHjd5-RHCiExR1IK6t4x-RMNXtQNbz7FvFZ_G-lFpk5RG3EOrwDL6CgDqc\
+         erSQ.eyJraWQiOiJ6VmhNaVBCUDlmUmYyc25FY1Q3Z0ZUaW9lQTlDT2NOeTlEZmdMMVc2MGhhTiJ9";
+    const FOOTER: &[u8] = br#"{"kid":"zVhMiPBP9fRf2snEcT7gFTioeA9COcNy9DfgL1W60haN"}"#;
+
+    #[test]
+    fn local_token_test_vectors() {
+        let key = LocalKey::new(LOCAL_KEY);
+        let claims = expected_claims("this is a secret message");
+        let message = serialize_payload(&claims).unwrap();
+        assert_eq!(
+            message,
+            br#"{"data":"this is a secret message","exp":"2022-01-01T00:00:00+00:00"}"#
+        );
+
+        let token = encrypt_local_with_nonce(&key, &message, b"", b"", [0; 32]);
+        assert_eq!(token, LOCAL_TOKEN);
+        let token = decrypt_local::<TestClaims>(&key, LOCAL_TOKEN, b"").unwrap();
+        assert_eq!(token.claims().custom, claims.custom);
+        assert_eq!(token.claims().expiration, claims.expiration);
+        assert!(token.footer().is_empty());
+
+        let token = encrypt_local_with_nonce(&key, &message, FOOTER, b"", LOCAL_NONCE_WITH_FOOTER);
+        assert_eq!(token, LOCAL_TOKEN_WITH_FOOTER);
+        let token = decrypt_local::<TestClaims>(&key, LOCAL_TOKEN_WITH_FOOTER, b"").unwrap();
+        assert_eq!(token.footer(), FOOTER);
+    }
+
+    #[test]
+    fn local_token_errors() {
+        let key = LocalKey::new(LOCAL_KEY);
+        let err = decrypt_local::<TestClaims>(&key, LOCAL_TOKEN, b"implicit").unwrap_err();
+        assert_matches!(err, PasetoError::InvalidSignature);
+        let other_key = LocalKey::generate(&mut rand::thread_rng());
+        let err = decrypt_local::<TestClaims>(&other_key, LOCAL_TOKEN, b"").unwrap_err();
+        assert_matches!(err, PasetoError::InvalidSignature);
+
+        let tampered_footer = format!("{LOCAL_TOKEN}.e30");
+        let err = decrypt_local::<TestClaims>(&key, &tampered_footer, b"").unwrap_err();
+        assert_matches!(err, PasetoError::InvalidSignature);
+        let public_token = LOCAL_TOKEN.replace("v4.local.", "v4.public.");
+        let err = decrypt_local::<TestClaims>(&key, &public_token, b"").unwrap_err();
+        assert_matches!(err, PasetoError::InvalidFormat);
+        let err = decrypt_local::<TestClaims>(&key, "v4.local.AAAA", b"").unwrap_err();
+        assert_matches!(err, PasetoError::InvalidFormat);
+    }
+
+    #[test]
+    fn converting_time_claims() {
+        let claims = expected_claims("test").set_not_before(Utc.timestamp_opt(0, 0).unwrap());
+        let payload = to_paseto_payload(&claims).unwrap();
+        assert_eq!(payload["exp"], "2022-01-01T00:00:00+00:00");
+        assert_eq!(payload["nbf"], "1970-01-01T00:00:00+00:00");
+
+        let mut payload = payload;
+        payload.insert("iat".to_owned(), "2021-12-31T23:00:00-01:00".into());
+        let restored: Claims<TestClaims> = from_paseto_payload(payload.clone()).unwrap();
+        assert_eq!(restored.expiration, claims.expiration);
+        assert_eq!(restored.issued_at, claims.expiration);
+
+        payload.insert("iat".to_owned(), 1_640_995_200.into());
+        let err = from_paseto_payload::<TestClaims>(payload).unwrap_err();
+        assert_matches!(err, PasetoError::MalformedClaims("iat"));
+    }
+
+    #[cfg(any(
+        feature = "exonum-crypto",
+        feature = "ed25519-dalek",
+        feature = "ed25519-compact"
+    ))]
+    #[test]
+    fn public_token_test_vectors() {
+        use crate::alg::{Ed25519, SigningKey};
+
+        // Test vectors 4-S-1 and 4-S-3 from https://github.com/paseto-standard/test-vectors
+        const SECRET_KEY: [u8; 64] = Hex.decode(
+            b"b4cbfb43df4ce210727d953e4a713307fa19bb7d9f85041438d9e11b942a3774\
+              1eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2",
+        );
+        const TOKEN: &str = "v4.public.eyJkYXRhIjoidGhpcyBpcyBhIHNpZ25lZCBtZXNzYWdlIiwiZXhwIjoi\
+            MjAyMi0wMS0wMVQwMDowMDowMCswMDowMCJ9bg_XBBzds8lTZShVlwwKSgeKpLT3yukTw6JUz3W4h_ExsQV-P0V54\
+            zemZDcAxFaSeef1QlXEFtkqxT1ciiQEDA";
+        const TOKEN_WITH_IMPLICIT: &str = "v4.public.eyJkYXRhIjoidGhpcyBpcyBhIHNpZ25lZCBtZXNzYWdlIi\
+            wiZXhwIjoiMjAyMi0wMS0wMVQwMDowMDowMCswMDowMCJ9NPWciuD3d0o5eXJXG5pJy-DiVEoyPYWs1YSTwWHNJq6\
+            DZD3je5gf-0M4JR9ipdUSJbIovzmBECeaWmaqcaP0DQ.eyJraWQiOiJ6VmhNaVBCUDlmUmYyc25FY1Q3Z0ZUaW9lQ\
+            TlDT2NOeTlEZmdMMVc2MGhhTiJ9";
+        const IMPLICIT: &[u8] = br#"{"test-vector":"4-S-3"}"#;
+
+        type SecretKey = <Ed25519 as Algorithm>::SigningKey;
+        let secret_key = SecretKey::from_slice(&SECRET_KEY).unwrap();
+        let public_key = secret_key.to_verifying_key();
+        let claims = expected_claims("this is a signed message");
+
+        let token = sign_public(&Ed25519, &secret_key, &claims, b"", b"").unwrap();
+        assert_eq!(token, TOKEN);
+        let token = sign_public(&Ed25519, &secret_key, &claims, FOOTER, IMPLICIT).unwrap();
+        assert_eq!(token, TOKEN_WITH_IMPLICIT);
+
+        let token =
+            verify_public::<_, TestClaims>(&Ed25519, &public_key, TOKEN_WITH_IMPLICIT, IMPLICIT)
+                .unwrap();
+        assert_eq!(token.claims().custom, claims.custom);
+        assert_eq!(token.footer(), FOOTER);
+        let err = verify_public::<_, TestClaims>(&Ed25519, &public_key, TOKEN_WITH_IMPLICIT, b"")
+            .unwrap_err();
+        assert_matches!(err, PasetoError::InvalidSignature);
+    }
+
+    #[test]
+    fn public_token_requires_ed25519() {
+        use crate::alg::{Hs256, Hs256Key};
+
+        let key = Hs256Key::new(b"super_secret_key_donut_steel");
+        let claims = expected_claims("test");
+        let err = sign_public(&Hs256, &key, &claims, b"", b"").unwrap_err();
+        assert_matches!(err, PasetoError::UnsupportedAlgorithm(alg) if alg == "HS256");
+    }
+}
@@ -0,0 +1,139 @@
+//! Preset validators for tokens issued by popular identity providers.
+//!
+//! Each preset fixes the provider-specific issuer, audience and claim checks and provides
+//! typed claims. The crate does not perform HTTP requests; the provider's [JWK set]
+//! should be fetched (and cached) by the caller. The URL of the key set is provided
+//! as a constant for each preset.
+//!
+//! [JWK set]: crate::jwk::JsonWebKeySet
+
+use core::fmt;
+
+use crate::{
+    jwk::{JsonWebKey, JsonWebKeySet, JwkError},
+    ErrorKind, UntrustedToken, ValidationError,
+};
+
+mod apple;
+mod google;
+mod kubernetes;
+mod spiffe;
+
+pub use self::{
+    apple::{
+        AppleIdTokenClaims, AppleIdTokenValidator, RealUserStatus, APPLE_ISSUER, APPLE_JWKS_URL,
+    },
+    google::{
+        FirebaseIdTokenClaims, FirebaseIdTokenValidator, FirebaseInfo, GoogleIdTokenClaims,
+        GoogleIdTokenValidator, FIREBASE_ISSUER_PREFIX, FIREBASE_JWKS_URL, GOOGLE_ISSUERS,
+        GOOGLE_JWKS_URL,
+    },
+    kubernetes::{
+        KubernetesClaims, ObjectReference, ServiceAccountTokenClaims, ServiceAccountTokenValidator,
+        KUBERNETES_DISCOVERY_PATH, SERVICE_ACCOUNT_SUBJECT_PREFIX,
+    },
+    spiffe::{BundleRefresh, JwtSvidBundles, JwtSvidClaims, JwtSvidValidator, SpiffeId},
+};
+
+/// Errors that can occur during validation with a preset validator.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum PresetError {
+    /// Token has failed generic validation (e.g., has an invalid signature).
+    Validation(ValidationError),
+    /// The key referenced by the token (via the `kid` header field) is not present
+    /// in the key set, or the token does not reference a key.
+    UnknownKey,
+    /// The key from the key set cannot be converted to a verifying key.
+    Jwk(JwkError),
+    /// Required claim is missing.
+    MissingClaim(&'static str),
+    /// Claim has an unexpected value.
+    ClaimMismatch(&'static str),
+}
+
+impl fmt::Display for PresetError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Validation(err) => write!(formatter, "token validation failed: {err}"),
+            Self::UnknownKey => formatter.write_str("token is signed with an unknown key"),
+            Self::Jwk(err) => write!(formatter, "invalid key in key set: {err}"),
+            Self::MissingClaim(claim) => write!(formatter, "claim `{claim}` is missing"),
+            Self::ClaimMismatch(claim) => write!(formatter, "claim `{claim}` has unexpected value"),
+        }
+    }
+}
+
+impl PresetError {
+    /// Returns the kind of this error.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Validation(err) => err.kind(),
+            Self::UnknownKey => ErrorKind::UnknownKey,
+            Self::Jwk(_) => ErrorKind::Internal,
+            Self::MissingClaim(_) => ErrorKind::MissingClaim,
+            Self::ClaimMismatch(claim) => ErrorKind::claim_mismatch(claim),
+        }
+    }
+}
+
+impl core::error::Error for PresetError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::Validation(err) => Some(err),
+            Self::Jwk(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<ValidationError> for PresetError {
+    fn from(err: ValidationError) -> Self {
+        Self::Validation(err)
+    }
+}
+
+/// Deserializer for boolean claims that some providers encode as strings (`"true"`).
+mod serde_lenient_bool {
+    use serde::{de, Deserialize, Deserializer};
+
+    use crate::alloc::String;
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum BoolOrString {
+        Bool(bool),
+        String(String),
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<bool>, D::Error> {
+        match Option::<BoolOrString>::deserialize(deserializer)? {
+            None => Ok(None),
+            Some(BoolOrString::Bool(value)) => Ok(Some(value)),
+            Some(BoolOrString::String(value)) => match value.as_str() {
+                "true" => Ok(Some(true)),
+                "false" => Ok(Some(false)),
+                other => Err(de::Error::invalid_value(
+                    de::Unexpected::Str(other),
+                    &"boolean or boolean string",
+                )),
+            },
+        }
+    }
+}
+
+/// Finds a verifying key referenced by the token (via the `kid` header field) in the key set.
+fn find_key<K>(key_set: &JsonWebKeySet<'_>, token: &UntrustedToken<'_>) -> Result<K, PresetError>
+where
+    K: for<'k> TryFrom<&'k JsonWebKey<'k>, Error = JwkError>,
+{
+    let key_id = token
+        .header()
+        .key_id
+        .as_deref()
+        .ok_or(PresetError::UnknownKey)?;
+    let entry = key_set.find(key_id).ok_or(PresetError::UnknownKey)?;
+    K::try_from(&entry.key).map_err(PresetError::Jwk)
+}
@@ -0,0 +1,353 @@
+//! Preset for [Sign in with Apple] ID tokens.
+//!
+//! [Sign in with Apple]: https://developer.apple.com/documentation/sign_in_with_apple/sign_in_with_apple_rest_api/authenticating_users_with_sign_in_with_apple
+
+use serde::{Deserialize, Serialize};
+
+use super::{serde_lenient_bool, PresetError};
+use crate::{
+    alloc::{String, Vec},
+    claims::serde_audience,
+    constant_time_eq, Algorithm, AlgorithmExt, TimeOptions, Timestamp, Token, UntrustedToken,
+};
+
+/// Issuer of Apple ID tokens.
+pub const APPLE_ISSUER: &str = "https://appleid.apple.com";
+/// URL of the JWK set with Apple public keys.
+pub const APPLE_JWKS_URL: &str = "https://appleid.apple.com/auth/keys";
+
+/// Indicator whether the user appears to be a real person (the `real_user_status` claim).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "u8", into = "u8")]
+#[non_exhaustive]
+pub enum RealUserStatus {
+    /// The status is not supported by the platform (e.g., on the web).
+    Unsupported,
+    /// The system cannot determine whether the user is real.
+    Unknown,
+    /// The user is likely real.
+    LikelyReal,
+}
+
+impl TryFrom<u8> for RealUserStatus {
+    type Error = &'static str;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Unsupported),
+            1 => Ok(Self::Unknown),
+            2 => Ok(Self::LikelyReal),
+            _ => Err("invalid real user status"),
+        }
+    }
+}
+
+impl From<RealUserStatus> for u8 {
+    fn from(status: RealUserStatus) -> Self {
+        match status {
+            RealUserStatus::Unsupported => 0,
+            RealUserStatus::Unknown => 1,
+            RealUserStatus::LikelyReal => 2,
+        }
+    }
+}
+
+/// Claims of a Sign in with Apple ID token.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct AppleIdTokenClaims {
+    /// Issuer of the token (`iss`); always [`APPLE_ISSUER`].
+    #[serde(rename = "iss")]
+    pub issuer: String,
+    /// Audience of the token (`aud`), i.e., the client ID (the app bundle ID
+    /// or the services ID).
+    #[serde(rename = "aud", with = "serde_audience")]
+    pub audience: Vec<String>,
+    /// Stable user identifier (`sub`).
+    #[serde(rename = "sub")]
+    pub subject: String,
+    /// Nonce passed in the authorization request.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,
+    /// Whether the platform supports nonces. If `false`, the token has no `nonce` claim.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce_supported: Option<bool>,
+    /// Time of the user authentication (`auth_time`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_time: Option<Timestamp>,
+    /// User email; may be a private relay address.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    /// Whether the email is verified.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "serde_lenient_bool::deserialize"
+    )]
+    pub email_verified: Option<bool>,
+    /// Whether the email is a private relay address.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "serde_lenient_bool::deserialize"
+    )]
+    pub is_private_email: Option<bool>,
+    /// Whether the user appears to be a real person.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub real_user_status: Option<RealUserStatus>,
+    /// Subject identifier before the app was transferred to another team.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transfer_sub: Option<String>,
+}
+
+/// Validator of Sign in with Apple ID tokens.
+///
+/// Besides the signature, the validator checks that:
+///
+/// - The `iss` claim is equal to [`APPLE_ISSUER`].
+/// - The `aud` claim contains one of the configured client IDs.
+/// - The `exp` claim is present and the token is not expired.
+/// - If a nonce is expected, the `nonce` claim is equal to it, unless the `nonce_supported`
+///   claim is `false`.
+///
+/// Apple signs ID tokens with `RS256`; with the `rsa` crate feature, tokens can be validated
+/// directly against the Apple [key set](crate::jwk::JsonWebKeySet)
+/// using [`Self::validate_with_key_set()`].
+///
+/// # Examples
+///
+/// ```
+/// # use jwt_compact::{alg::{Hs256, Hs256Key}, prelude::*};
+/// use jwt_compact::presets::AppleIdTokenValidator;
+/// # use chrono::Duration;
+/// # use serde_json::json;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// # // Apple uses `RS256`; `HS256` is only used here to keep the example short.
+/// # let key = Hs256Key::new(b"super_secret_key_donut_steel");
+/// # let options = TimeOptions::default();
+/// # let claims = json!({
+/// #     "iss": "https://appleid.apple.com",
+/// #     "aud": "com.example.app",
+/// #     "sub": "001234.56789abcdef.0123",
+/// #     "nonce": "n-0S6_WzA2Mj",
+/// #     "email": "user@privaterelay.appleid.com",
+/// #     "email_verified": "true",
+/// #     "is_private_email": "true",
+/// #     "real_user_status": 2,
+/// # });
+/// # let claims = Claims::new(claims)
+/// #     .set_duration_and_issuance(&options, Duration::try_minutes(10).unwrap());
+/// # let id_token = Hs256.token(&Header::empty(), &claims, &key)?;
+/// let validator = AppleIdTokenValidator::new("com.example.app");
+/// let id_token = UntrustedToken::new(&id_token)?;
+/// let token = validator.validate(&Hs256, &key, &id_token, Some("n-0S6_WzA2Mj"), &options)?;
+/// let claims = &token.claims().custom;
+/// assert_eq!(claims.email_verified, Some(true));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct AppleIdTokenValidator {
+    client_ids: Vec<String>,
+}
+
+impl AppleIdTokenValidator {
+    /// Creates a validator for the specified client ID.
+    pub fn new(client_id: impl Into<String>) -> Self {
+        Self {
+            client_ids: Vec::from([client_id.into()]),
+        }
+    }
+
+    /// Adds another accepted client ID. This is useful if the same backend serves
+    /// an iOS app (with the bundle ID as the client ID) and a website (with the services ID).
+    #[must_use]
+    pub fn with_client_id(mut self, client_id: impl Into<String>) -> Self {
+        self.client_ids.push(client_id.into());
+        self
+    }
+
+    /// Validates the ID token using the specified algorithm and key.
+    pub fn validate<A: Algorithm, F>(
+        &self,
+        algorithm: &A,
+        verifying_key: &A::VerifyingKey,
+        token: &UntrustedToken<'_>,
+        expected_nonce: Option<&str>,
+        options: &TimeOptions<F>,
+    ) -> Result<Token<AppleIdTokenClaims>, PresetError>
+    where
+        F: Fn() -> Timestamp,
+    {
+        let token = algorithm
+            .validator::<AppleIdTokenClaims>(verifying_key)
+            .validate(token)?;
+        let claims = token.claims();
+        if claims.expiration.is_none() {
+            return Err(PresetError::MissingClaim("exp"));
+        }
+        claims.validate_expiration(options)?;
+
+        let custom = &claims.custom;
+        if custom.issuer != APPLE_ISSUER {
+            return Err(PresetError::ClaimMismatch("iss"));
+        }
+        let has_client_id = custom
+            .audience
+            .iter()
+            .any(|aud| self.client_ids.contains(aud));
+        if !has_client_id {
+            return Err(PresetError::ClaimMismatch("aud"));
+        }
+        if let Some(expected_nonce) = expected_nonce {
+            if custom.nonce_supported != Some(false) {
+                match custom.nonce.as_deref() {
+                    Some(nonce)
+                        if constant_time_eq(nonce.as_bytes(), expected_nonce.as_bytes()) =>
+                    { /* OK */ }
+                    Some(_) => return Err(PresetError::ClaimMismatch("nonce")),
+                    None => return Err(PresetError::MissingClaim("nonce")),
+                }
+            }
+        }
+        Ok(token)
+    }
+
+    /// Validates the ID token using the key from the Apple key set
+    /// (can be fetched from [`APPLE_JWKS_URL`]) referenced by the `kid` header field.
+    #[cfg(feature = "rsa")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "rsa")))]
+    pub fn validate_with_key_set<F>(
+        &self,
+        key_set: &crate::jwk::JsonWebKeySet<'_>,
+        token: &UntrustedToken<'_>,
+        expected_nonce: Option<&str>,
+        options: &TimeOptions<F>,
+    ) -> Result<Token<AppleIdTokenClaims>, PresetError>
+    where
+        F: Fn() -> Timestamp,
+    {
+        let verifying_key = super::find_key::<rsa::RsaPublicKey>(key_set, token)?;
+        let rs256 = crate::alg::Rsa::rs256();
+        self.validate(&rs256, &verifying_key, token, expected_nonce, options)
+    }
+}
+
+#[cfg(all(test, feature = "clock"))]
+mod tests {
+    use assert_matches::assert_matches;
+    use chrono::Duration;
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::{
+        alg::Hs256,
+        test_helpers::{key, sign},
+        Claims, Header,
+    };
+
+    const CLIENT_ID: &str = "com.example.app";
+
+    fn validate(claims: Value, nonce: Option<&str>) -> Result<AppleIdTokenClaims, PresetError> {
+        let options = TimeOptions::default();
+        let token = sign(&claims, &Header::empty());
+        let token = UntrustedToken::new(&token).unwrap();
+        let token = AppleIdTokenValidator::new("com.example.web")
+            .with_client_id(CLIENT_ID)
+            .validate(&Hs256, &key(), &token, nonce, &options)?;
+        Ok(token.into_parts().1.custom)
+    }
+
+    #[test]
+    fn valid_apple_token() {
+        let claims = json!({
+            "iss": APPLE_ISSUER,
+            "aud": CLIENT_ID,
+            "sub": "001234.56789abcdef.0123",
+            "nonce": "abc",
+            "nonce_supported": true,
+            "auth_time": 1_700_000_000,
+            "email": "user@privaterelay.appleid.com",
+            "email_verified": "true",
+            "is_private_email": false,
+            "real_user_status": 1,
+        });
+        let claims = validate(claims, Some("abc")).unwrap();
+        assert_eq!(claims.email_verified, Some(true));
+        assert_eq!(claims.is_private_email, Some(false));
+        assert_eq!(claims.real_user_status, Some(RealUserStatus::Unknown));
+        assert_eq!(claims.auth_time.unwrap().as_secs(), 1_700_000_000);
+    }
+
+    #[test]
+    fn nonce_handling() {
+        let claims = json!({ "iss": APPLE_ISSUER, "aud": CLIENT_ID, "sub": "user" });
+        let err = validate(claims.clone(), Some("abc")).unwrap_err();
+        assert_matches!(err, PresetError::MissingClaim("nonce"));
+        validate(claims, None).unwrap();
+
+        let claims = json!({
+            "iss": APPLE_ISSUER,
+            "aud": CLIENT_ID,
+            "sub": "user",
+            "nonce_supported": false,
+        });
+        validate(claims, Some("abc")).unwrap();
+
+        let claims = json!({ "iss": APPLE_ISSUER, "aud": CLIENT_ID, "sub": "user", "nonce": "x" });
+        let err = validate(claims, Some("abc")).unwrap_err();
+        assert_matches!(err, PresetError::ClaimMismatch("nonce"));
+    }
+
+    #[test]
+    fn invalid_apple_tokens() {
+        let claims = json!({ "iss": "https://evil.example.com", "aud": CLIENT_ID, "sub": "user" });
+        let err = validate(claims, None).unwrap_err();
+        assert_matches!(err, PresetError::ClaimMismatch("iss"));
+
+        let claims = json!({ "iss": APPLE_ISSUER, "aud": "com.other.app", "sub": "user" });
+        let err = validate(claims, None).unwrap_err();
+        assert_matches!(err, PresetError::ClaimMismatch("aud"));
+    }
+
+    #[cfg(feature = "rsa")]
+    #[test]
+    fn validating_with_key_set() {
+        use rsa::{RsaPrivateKey, RsaPublicKey};
+
+        use crate::{
+            alg::Rsa,
+            jwk::{JsonWebKey, JsonWebKeySet, KeySetEntry},
+        };
+
+        let signing_key = RsaPrivateKey::new(&mut rand::thread_rng(), 2_048).unwrap();
+        let verifying_key = RsaPublicKey::from(&signing_key);
+        let mut entry = KeySetEntry::new(JsonWebKey::from(&verifying_key));
+        entry.key_id = Some("W6WcOKB".into());
+        let key_set = JsonWebKeySet {
+            keys: Vec::from([entry]),
+        };
+
+        let options = TimeOptions::default();
+        let claims = json!({ "iss": APPLE_ISSUER, "aud": CLIENT_ID, "sub": "user" });
+        let claims = Claims::new(claims)
+            .set_duration_and_issuance(&options, Duration::try_minutes(5).unwrap());
+        let validator = AppleIdTokenValidator::new(CLIENT_ID);
+
+        let header = Header::empty().with_key_id("W6WcOKB");
+        let token = Rsa::rs256().token(&header, &claims, &signing_key).unwrap();
+        let token = UntrustedToken::new(&token).unwrap();
+        validator
+            .validate_with_key_set(&key_set, &token, None, &options)
+            .unwrap();
+
+        let header = Header::empty().with_key_id("other");
+        let token = Rsa::rs256().token(&header, &claims, &signing_key).unwrap();
+        let token = UntrustedToken::new(&token).unwrap();
+        let err = validator
+            .validate_with_key_set(&key_set, &token, None, &options)
+            .unwrap_err();
+        assert_matches!(err, PresetError::UnknownKey);
+    }
+}
@@ -0,0 +1,448 @@
+//! Presets for [Google] and [Firebase Authentication] ID tokens.
+//!
+//! [Google]: https://developers.google.com/identity/openid-connect/openid-connect
+//! [Firebase Authentication]: https://firebase.google.com/docs/auth/admin/verify-id-tokens
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use super::{serde_lenient_bool, PresetError};
+use crate::{
+    alloc::{format, String, Vec},
+    claims::serde_audience,
+    Algorithm, AlgorithmExt, TimeOptions, Timestamp, Token, UntrustedToken,
+};
+
+/// Issuers of Google ID tokens. Google uses both forms interchangeably.
+pub const GOOGLE_ISSUERS: [&str; 2] = ["https://accounts.google.com", "accounts.google.com"];
+/// URL of the JWK set with Google public keys.
+pub const GOOGLE_JWKS_URL: &str = "https://www.googleapis.com/oauth2/v3/certs";
+/// URL of the JWK set with public keys for Firebase ID tokens.
+pub const FIREBASE_JWKS_URL: &str =
+    "https://www.googleapis.com/service_accounts/v1/jwk/securetoken@system.gserviceaccount.com";
+/// Prefix of Firebase ID token issuers; the issuer is this prefix followed by the project ID.
+pub const FIREBASE_ISSUER_PREFIX: &str = "https://securetoken.google.com/";
+
+/// Claims of a Google ID token.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct GoogleIdTokenClaims {
+    /// Issuer of the token (`iss`); one of [`GOOGLE_ISSUERS`].
+    #[serde(rename = "iss")]
+    pub issuer: String,
+    /// Audience of the token (`aud`), i.e., the OAuth client ID.
+    #[serde(rename = "aud", with = "serde_audience")]
+    pub audience: Vec<String>,
+    /// Stable user identifier (`sub`).
+    #[serde(rename = "sub")]
+    pub subject: String,
+    /// Client ID of the authorized presenter (`azp`).
+    #[serde(rename = "azp", default, skip_serializing_if = "Option::is_none")]
+    pub authorized_party: Option<String>,
+    /// Hosted Google Workspace domain of the user (`hd`).
+    #[serde(rename = "hd", default, skip_serializing_if = "Option::is_none")]
+    pub hosted_domain: Option<String>,
+    /// User email.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    /// Whether the email is verified.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "serde_lenient_bool::deserialize"
+    )]
+    pub email_verified: Option<bool>,
+    /// Full name of the user.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// URL of the user profile picture.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub picture: Option<String>,
+    /// Nonce passed in the authorization request.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,
+}
+
+/// Validator of Google ID tokens.
+///
+/// Besides the signature, the validator checks that the `iss` claim is one of [`GOOGLE_ISSUERS`],
+/// the `aud` claim contains the client ID, and the `exp` claim is present and the token
+/// is not expired. Optionally, the hosted domain (`hd` claim) can be checked as well.
+///
+/// Google signs ID tokens with `RS256`; with the `rsa` crate feature, tokens can be validated
+/// directly against the Google [key set](crate::jwk::JsonWebKeySet)
+/// using [`Self::validate_with_key_set()`].
+#[derive(Debug, Clone)]
+pub struct GoogleIdTokenValidator {
+    client_id: String,
+    hosted_domain: Option<String>,
+}
+
+impl GoogleIdTokenValidator {
+    /// Creates a validator for the specified OAuth client ID.
+    pub fn new(client_id: impl Into<String>) -> Self {
+        Self {
+            client_id: client_id.into(),
+            hosted_domain: None,
+        }
+    }
+
+    /// Requires the user to belong to the specified Google Workspace domain.
+    #[must_use]
+    pub fn with_hosted_domain(mut self, domain: impl Into<String>) -> Self {
+        self.hosted_domain = Some(domain.into());
+        self
+    }
+
+    /// Validates the ID token using the specified algorithm and key.
+    pub fn validate<A: Algorithm, F>(
+        &self,
+        algorithm: &A,
+        verifying_key: &A::VerifyingKey,
+        token: &UntrustedToken<'_>,
+        options: &TimeOptions<F>,
+    ) -> Result<Token<GoogleIdTokenClaims>, PresetError>
+    where
+        F: Fn() -> Timestamp,
+    {
+        let token = algorithm
+            .validator::<GoogleIdTokenClaims>(verifying_key)
+            .validate(token)?;
+        let claims = token.claims();
+        if claims.expiration.is_none() {
+            return Err(PresetError::MissingClaim("exp"));
+        }
+        claims.validate_expiration(options)?;
+
+        let custom = &claims.custom;
+        if !GOOGLE_ISSUERS.contains(&custom.issuer.as_str()) {
+            return Err(PresetError::ClaimMismatch("iss"));
+        }
+        if !custom.audience.contains(&self.client_id) {
+            return Err(PresetError::ClaimMismatch("aud"));
+        }
+        if let Some(expected_domain) = &self.hosted_domain {
+            match &custom.hosted_domain {
+                Some(domain) if domain == expected_domain => { /* OK */ }
+                Some(_) => return Err(PresetError::ClaimMismatch("hd")),
+                None => return Err(PresetError::MissingClaim("hd")),
+            }
+        }
+        Ok(token)
+    }
+
+    /// Validates the ID token using the key from the Google key set
+    /// (can be fetched from [`GOOGLE_JWKS_URL`]) referenced by the `kid` header field.
+    #[cfg(feature = "rsa")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "rsa")))]
+    pub fn validate_with_key_set<F>(
+        &self,
+        key_set: &crate::jwk::JsonWebKeySet<'_>,
+        token: &UntrustedToken<'_>,
+        options: &TimeOptions<F>,
+    ) -> Result<Token<GoogleIdTokenClaims>, PresetError>
+    where
+        F: Fn() -> Timestamp,
+    {
+        let verifying_key = super::find_key::<rsa::RsaPublicKey>(key_set, token)?;
+        self.validate(&crate::alg::Rsa::rs256(), &verifying_key, token, options)
+    }
+}
+
+/// Firebase-specific information in a Firebase ID token (the `firebase` claim).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct FirebaseInfo {
+    /// Provider used to sign in, such as `password`, `google.com` or `custom`.
+    pub sign_in_provider: String,
+    /// Identities of the user in sign-in providers.
+    #[serde(default)]
+    pub identities: Map<String, Value>,
+    /// Tenant ID, if the user belongs to a tenant.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant: Option<String>,
+}
+
+/// Claims of a Firebase ID token.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct FirebaseIdTokenClaims {
+    /// Issuer of the token (`iss`): [`FIREBASE_ISSUER_PREFIX`] followed by the project ID.
+    #[serde(rename = "iss")]
+    pub issuer: String,
+    /// Audience of the token (`aud`), i.e., the Firebase project ID.
+    #[serde(rename = "aud", with = "serde_audience")]
+    pub audience: Vec<String>,
+    /// Firebase user ID (`sub`).
+    #[serde(rename = "sub")]
+    pub subject: String,
+    /// Time of the user authentication (`auth_time`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_time: Option<Timestamp>,
+    /// User email.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    /// Whether the email is verified.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "serde_lenient_bool::deserialize"
+    )]
+    pub email_verified: Option<bool>,
+    /// User phone number.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub phone_number: Option<String>,
+    /// Display name of the user.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// URL of the user profile picture.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub picture: Option<String>,
+    /// Firebase-specific information.
+    pub firebase: FirebaseInfo,
+    /// Custom claims set via the Firebase Admin SDK.
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+/// Validator of Firebase ID tokens.
+///
+/// Besides the signature, the validator checks [the Firebase requirements]:
+///
+/// - The `iss` claim is equal to [`FIREBASE_ISSUER_PREFIX`] followed by the project ID,
+///   and the `aud` claim contains the project ID.
+/// - The `exp` claim is present and the token is not expired.
+/// - The `iat` and `auth_time` claims are present and are not in the future.
+///   Firebase tokens are often used immediately after issuance, so the leeway from
+///   [`TimeOptions`] is essential to tolerate clock skew between the backend and Google servers.
+/// - The `sub` claim is not empty.
+///
+/// Firebase signs ID tokens with `RS256`; with the `rsa` crate feature, tokens can be validated
+/// directly against the Firebase [key set](crate::jwk::JsonWebKeySet)
+/// using [`Self::validate_with_key_set()`].
+///
+/// [the Firebase requirements]: https://firebase.google.com/docs/auth/admin/verify-id-tokens#verify_id_tokens_using_a_third-party_jwt_library
+///
+/// # Examples
+///
+/// ```
+/// # use jwt_compact::{alg::{Hs256, Hs256Key}, prelude::*};
+/// use jwt_compact::presets::FirebaseIdTokenValidator;
+/// # use chrono::{Duration, Utc};
+/// # use serde_json::json;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// # // Firebase uses `RS256`; `HS256` is only used here to keep the example short.
+/// # let key = Hs256Key::new(b"super_secret_key_donut_steel");
+/// # let options = TimeOptions::default();
+/// # let claims = json!({
+/// #     "iss": "https://securetoken.google.com/my-project",
+/// #     "aud": "my-project",
+/// #     "sub": "tKyTRjH5QGVfHgJ2jZBhTBkQmHG2",
+/// #     "auth_time": Utc::now().timestamp(),
+/// #     "firebase": { "sign_in_provider": "password", "identities": {} },
+/// #     "role": "admin",
+/// # });
+/// # let claims = Claims::new(claims)
+/// #     .set_duration_and_issuance(&options, Duration::try_hours(1).unwrap());
+/// # let id_token = Hs256.token(&Header::empty(), &claims, &key)?;
+/// let validator = FirebaseIdTokenValidator::new("my-project");
+/// let id_token = UntrustedToken::new(&id_token)?;
+/// let token = validator.validate(&Hs256, &key, &id_token, &options)?;
+/// let claims = &token.claims().custom;
+/// assert_eq!(claims.firebase.sign_in_provider, "password");
+/// assert_eq!(claims.other["role"], "admin");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct FirebaseIdTokenValidator {
+    project_id: String,
+    issuer: String,
+}
+
+impl FirebaseIdTokenValidator {
+    /// Creates a validator for the specified Firebase project ID.
+    pub fn new(project_id: impl Into<String>) -> Self {
+        let project_id = project_id.into();
+        Self {
+            issuer: format!("{FIREBASE_ISSUER_PREFIX}{project_id}"),
+            project_id,
+        }
+    }
+
+    /// Validates the ID token using the specified algorithm and key.
+    pub fn validate<A: Algorithm, F>(
+        &self,
+        algorithm: &A,
+        verifying_key: &A::VerifyingKey,
+        token: &UntrustedToken<'_>,
+        options: &TimeOptions<F>,
+    ) -> Result<Token<FirebaseIdTokenClaims>, PresetError>
+    where
+        F: Fn() -> Timestamp,
+    {
+        let token = algorithm
+            .validator::<FirebaseIdTokenClaims>(verifying_key)
+            .validate(token)?;
+        let claims = token.claims();
+        if claims.expiration.is_none() {
+            return Err(PresetError::MissingClaim("exp"));
+        }
+        claims.validate_expiration(options)?;
+
+        let now = (options.clock_fn)();
+        // Timestamps come from the token, so the arithmetic must not panic on extreme values.
+        let is_in_past = |time: Timestamp| {
+            time.checked_sub_signed(options.leeway)
+                .is_some_and(|time| time <= now)
+        };
+        let issued_at = claims.issued_at.ok_or(PresetError::MissingClaim("iat"))?;
+        if !is_in_past(issued_at) {
+            return Err(PresetError::ClaimMismatch("iat"));
+        }
+        let custom = &claims.custom;
+        let auth_time = custom
+            .auth_time
+            .ok_or(PresetError::MissingClaim("auth_time"))?;
+        if !is_in_past(auth_time) {
+            return Err(PresetError::ClaimMismatch("auth_time"));
+        }
+
+        if custom.issuer != self.issuer {
+            return Err(PresetError::ClaimMismatch("iss"));
+        }
+        if !custom.audience.contains(&self.project_id) {
+            return Err(PresetError::ClaimMismatch("aud"));
+        }
+        if custom.subject.is_empty() {
+            return Err(PresetError::MissingClaim("sub"));
+        }
+        Ok(token)
+    }
+
+    /// Validates the ID token using the key from the Firebase key set
+    /// (can be fetched from [`FIREBASE_JWKS_URL`]) referenced by the `kid` header field.
+    #[cfg(feature = "rsa")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "rsa")))]
+    pub fn validate_with_key_set<F>(
+        &self,
+        key_set: &crate::jwk::JsonWebKeySet<'_>,
+        token: &UntrustedToken<'_>,
+        options: &TimeOptions<F>,
+    ) -> Result<Token<FirebaseIdTokenClaims>, PresetError>
+    where
+        F: Fn() -> Timestamp,
+    {
+        let verifying_key = super::find_key::<rsa::RsaPublicKey>(key_set, token)?;
+        self.validate(&crate::alg::Rsa::rs256(), &verifying_key, token, options)
+    }
+}
+
+#[cfg(all(test, feature = "clock"))]
+mod tests {
+    use assert_matches::assert_matches;
+    use chrono::Utc;
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        alg::Hs256,
+        test_helpers::{key, sign},
+        Header,
+    };
+
+    #[test]
+    fn google_token() {
+        let claims = json!({
+            "iss": "accounts.google.com",
+            "aud": "client.apps.googleusercontent.com",
+            "sub": "110169484474386276334",
+            "hd": "example.com",
+            "email_verified": "true",
+        });
+        let token = sign(&claims, &Header::empty());
+        let token = UntrustedToken::new(&token).unwrap();
+        let options = TimeOptions::default();
+
+        let validator = GoogleIdTokenValidator::new("client.apps.googleusercontent.com");
+        let token_claims = validator
+            .validate(&Hs256, &key(), &token, &options)
+            .unwrap()
+            .into_parts()
+            .1
+            .custom;
+        assert_eq!(token_claims.email_verified, Some(true));
+
+        let validator = validator.with_hosted_domain("other.com");
+        let err = validator
+            .validate(&Hs256, &key(), &token, &options)
+            .unwrap_err();
+        assert_matches!(err, PresetError::ClaimMismatch("hd"));
+
+        let err = GoogleIdTokenValidator::new("other.apps.googleusercontent.com")
+            .validate(&Hs256, &key(), &token, &options)
+            .unwrap_err();
+        assert_matches!(err, PresetError::ClaimMismatch("aud"));
+    }
+
+    fn firebase_claims() -> Value {
+        json!({
+            "iss": "https://securetoken.google.com/my-project",
+            "aud": "my-project",
+            "sub": "tKyTRjH5QGVfHgJ2jZBhTBkQmHG2",
+            "auth_time": Utc::now().timestamp() - 10,
+            "firebase": {
+                "sign_in_provider": "google.com",
+                "identities": { "google.com": ["110169484474386276334"] },
+            },
+        })
+    }
+
+    fn validate_firebase(claims: Value) -> Result<FirebaseIdTokenClaims, PresetError> {
+        let token = sign(&claims, &Header::empty());
+        let token = UntrustedToken::new(&token).unwrap();
+        let token = FirebaseIdTokenValidator::new("my-project").validate(
+            &Hs256,
+            &key(),
+            &token,
+            &TimeOptions::default(),
+        )?;
+        Ok(token.into_parts().1.custom)
+    }
+
+    #[test]
+    fn firebase_token() {
+        let claims = validate_firebase(firebase_claims()).unwrap();
+        assert_eq!(claims.firebase.sign_in_provider, "google.com");
+        assert!(claims.other.is_empty());
+    }
+
+    #[test]
+    fn invalid_firebase_tokens() {
+        let mut claims = firebase_claims();
+        claims["iss"] = "https://securetoken.google.com/other-project".into();
+        let err = validate_firebase(claims).unwrap_err();
+        assert_matches!(err, PresetError::ClaimMismatch("iss"));
+
+        let mut claims = firebase_claims();
+        claims["auth_time"] = (Utc::now().timestamp() + 3_600).into();
+        let err = validate_firebase(claims).unwrap_err();
+        assert_matches!(err, PresetError::ClaimMismatch("auth_time"));
+
+        let mut claims = firebase_claims();
+        claims["auth_time"] = Timestamp::MIN.as_secs().into();
+        let err = validate_firebase(claims).unwrap_err();
+        assert_matches!(err, PresetError::ClaimMismatch("auth_time"));
+
+        let mut claims = firebase_claims();
+        claims.as_object_mut().unwrap().remove("auth_time");
+        let err = validate_firebase(claims).unwrap_err();
+        assert_matches!(err, PresetError::MissingClaim("auth_time"));
+
+        let mut claims = firebase_claims();
+        claims["sub"] = "".into();
+        let err = validate_firebase(claims).unwrap_err();
+        assert_matches!(err, PresetError::MissingClaim("sub"));
+    }
+}
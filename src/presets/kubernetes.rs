@@ -0,0 +1,331 @@
+//! Preset for [Kubernetes service account tokens].
+//!
+//! [Kubernetes service account tokens]: https://kubernetes.io/docs/reference/access-authn-authz/service-accounts-admin/
+
+use serde::{Deserialize, Serialize};
+
+use super::PresetError;
+use crate::{
+    alloc::{format, String, Vec},
+    claims::serde_audience,
+    jwk::{JsonWebKey, JsonWebKeySet, JwkError},
+    Algorithm, AlgorithmExt, TimeOptions, Timestamp, Token, UntrustedToken,
+};
+
+/// Prefix of the `sub` claim of service account tokens.
+pub const SERVICE_ACCOUNT_SUBJECT_PREFIX: &str = "system:serviceaccount:";
+/// Path of the OIDC discovery document relative to the service account issuer. The document
+/// references the JWK set used to sign tokens (usually at `/openid/v1/jwks`).
+pub const KUBERNETES_DISCOVERY_PATH: &str = "/.well-known/openid-configuration";
+
+/// Reference to a Kubernetes object in the `kubernetes.io` claim.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct ObjectReference {
+    /// Object name.
+    pub name: String,
+    /// Object UID.
+    pub uid: String,
+}
+
+/// Kubernetes-specific claims of a service account token (the `kubernetes.io` claim).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct KubernetesClaims {
+    /// Namespace of the service account.
+    pub namespace: String,
+    /// Service account the token is issued for.
+    #[serde(rename = "serviceaccount")]
+    pub service_account: ObjectReference,
+    /// Pod the token is bound to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pod: Option<ObjectReference>,
+    /// Node the token is bound to, or the node of the bound pod.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub node: Option<ObjectReference>,
+    /// Secret the token is bound to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret: Option<ObjectReference>,
+    /// Time after which the kubelet should have refreshed the token; set for
+    /// tokens with extended expiration.
+    #[serde(rename = "warnafter", default, skip_serializing_if = "Option::is_none")]
+    pub warn_after: Option<Timestamp>,
+}
+
+/// Claims of a projected Kubernetes service account token.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct ServiceAccountTokenClaims {
+    /// Issuer of the token (`iss`), i.e., the service account issuer of the cluster.
+    #[serde(rename = "iss")]
+    pub issuer: String,
+    /// Audience of the token (`aud`).
+    #[serde(rename = "aud", with = "serde_audience")]
+    pub audience: Vec<String>,
+    /// Subject of the token (`sub`) in the `system:serviceaccount:<namespace>:<name>` format.
+    #[serde(rename = "sub")]
+    pub subject: String,
+    /// Kubernetes-specific claims.
+    #[serde(rename = "kubernetes.io")]
+    pub kubernetes: KubernetesClaims,
+}
+
+/// Validator of projected Kubernetes service account tokens, e.g., for use in
+/// admission webhooks or services authenticating workloads in the cluster.
+///
+/// Besides the signature, the validator checks that:
+///
+/// - The `iss` claim matches the cluster issuer, and the `aud` claim contains
+///   the expected audience.
+/// - The `exp` claim is present and the token is not expired. If the `nbf` claim is present,
+///   the token must be mature.
+/// - The `sub` claim is consistent with the namespace and the service account name
+///   in the `kubernetes.io` claim.
+/// - If [namespaces](Self::with_namespace()) or [service accounts](Self::with_service_account())
+///   are restricted, the service account matches one of them.
+///
+/// The cluster signing keys can be obtained from the JWK set referenced by the OIDC discovery
+/// document at [`KUBERNETES_DISCOVERY_PATH`]; use [`Self::validate_with_key_set()`]
+/// to validate tokens against it.
+///
+/// # Examples
+///
+/// ```
+/// # use chrono::Duration;
+/// # use jwt_compact::{alg::{Hs256, Hs256Key}, prelude::*};
+/// use jwt_compact::presets::ServiceAccountTokenValidator;
+/// # use serde_json::json;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// # // Clusters sign tokens with `RS256` or `ES256`; `HS256` is only used here
+/// # // to keep the example short.
+/// # let key = Hs256Key::new(b"super_secret_key_donut_steel");
+/// # let options = TimeOptions::default();
+/// # let claims = json!({
+/// #     "iss": "https://kubernetes.default.svc.cluster.local",
+/// #     "aud": ["webhook"],
+/// #     "sub": "system:serviceaccount:prod:web",
+/// #     "kubernetes.io": {
+/// #         "namespace": "prod",
+/// #         "serviceaccount": { "name": "web", "uid": "13dc8c8b-2ef4-4b6a-9b5e-0a1f6f4e1c2d" },
+/// #         "pod": { "name": "web-5d9c7", "uid": "7a3c1e2b-8f0d-4f4e-9c1a-2b3d4e5f6a7b" },
+/// #     },
+/// # });
+/// # let claims = Claims::new(claims)
+/// #     .set_duration_and_issuance(&options, Duration::try_hours(1).unwrap());
+/// # let token = Hs256.token(&Header::empty(), &claims, &key)?;
+/// let validator = ServiceAccountTokenValidator::new(
+///     "https://kubernetes.default.svc.cluster.local",
+///     "webhook",
+/// )
+/// .with_namespace("prod");
+/// let token = UntrustedToken::new(&token)?;
+/// let token = validator.validate(&Hs256, &key, &token, &options)?;
+/// let claims = &token.claims().custom.kubernetes;
+/// assert_eq!(claims.service_account.name, "web");
+/// assert_eq!(claims.pod.as_ref().unwrap().name, "web-5d9c7");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ServiceAccountTokenValidator {
+    issuer: String,
+    audience: String,
+    namespaces: Vec<String>,
+    service_accounts: Vec<(String, String)>,
+}
+
+impl ServiceAccountTokenValidator {
+    /// Creates a validator with the specified cluster issuer and expected audience.
+    pub fn new(issuer: impl Into<String>, audience: impl Into<String>) -> Self {
+        Self {
+            issuer: issuer.into(),
+            audience: audience.into(),
+            namespaces: Vec::new(),
+            service_accounts: Vec::new(),
+        }
+    }
+
+    /// Allows service accounts from the specified namespace.
+    #[must_use]
+    pub fn with_namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.namespaces.push(namespace.into());
+        self
+    }
+
+    /// Allows the specified service account.
+    #[must_use]
+    pub fn with_service_account(
+        mut self,
+        namespace: impl Into<String>,
+        name: impl Into<String>,
+    ) -> Self {
+        self.service_accounts.push((namespace.into(), name.into()));
+        self
+    }
+
+    /// Validates the token using the specified algorithm and key.
+    pub fn validate<A: Algorithm, F>(
+        &self,
+        algorithm: &A,
+        verifying_key: &A::VerifyingKey,
+        token: &UntrustedToken<'_>,
+        options: &TimeOptions<F>,
+    ) -> Result<Token<ServiceAccountTokenClaims>, PresetError>
+    where
+        F: Fn() -> Timestamp,
+    {
+        let token = algorithm
+            .validator::<ServiceAccountTokenClaims>(verifying_key)
+            .validate(token)?;
+        let claims = token.claims();
+        if claims.expiration.is_none() {
+            return Err(PresetError::MissingClaim("exp"));
+        }
+        claims.validate_expiration(options)?;
+        if claims.not_before.is_some() {
+            claims.validate_maturity(options)?;
+        }
+
+        let custom = &claims.custom;
+        if custom.issuer != self.issuer {
+            return Err(PresetError::ClaimMismatch("iss"));
+        }
+        if !custom.audience.contains(&self.audience) {
+            return Err(PresetError::ClaimMismatch("aud"));
+        }
+        let namespace = &custom.kubernetes.namespace;
+        let name = &custom.kubernetes.service_account.name;
+        if custom.subject != format!("{SERVICE_ACCOUNT_SUBJECT_PREFIX}{namespace}:{name}") {
+            return Err(PresetError::ClaimMismatch("sub"));
+        }
+        if !self.is_allowed(namespace, name) {
+            return Err(PresetError::ClaimMismatch("sub"));
+        }
+        Ok(token)
+    }
+
+    /// Validates the token using the key from the cluster key set referenced
+    /// by the `kid` header field.
+    pub fn validate_with_key_set<A: Algorithm, F>(
+        &self,
+        algorithm: &A,
+        key_set: &JsonWebKeySet<'_>,
+        token: &UntrustedToken<'_>,
+        options: &TimeOptions<F>,
+    ) -> Result<Token<ServiceAccountTokenClaims>, PresetError>
+    where
+        A::VerifyingKey: for<'k> TryFrom<&'k JsonWebKey<'k>, Error = JwkError>,
+        F: Fn() -> Timestamp,
+    {
+        let verifying_key = super::find_key::<A::VerifyingKey>(key_set, token)?;
+        self.validate(algorithm, &verifying_key, token, options)
+    }
+
+    fn is_allowed(&self, namespace: &str, name: &str) -> bool {
+        if self.namespaces.is_empty() && self.service_accounts.is_empty() {
+            return true;
+        }
+        self.namespaces.iter().any(|ns| ns == namespace)
+            || self
+                .service_accounts
+                .iter()
+                .any(|(ns, sa)| ns == namespace && sa == name)
+    }
+}
+
+#[cfg(all(test, feature = "clock"))]
+mod tests {
+    use assert_matches::assert_matches;
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::{
+        alg::Hs256,
+        alloc::ToOwned,
+        jwk::KeySetEntry,
+        test_helpers::{key, sign},
+        Header,
+    };
+
+    const ISSUER: &str = "https://kubernetes.default.svc.cluster.local";
+
+    fn token_claims() -> Value {
+        json!({
+            "iss": ISSUER,
+            "aud": "webhook",
+            "sub": "system:serviceaccount:prod:web",
+            "kubernetes.io": {
+                "namespace": "prod",
+                "serviceaccount": { "name": "web", "uid": "13dc8c8b" },
+                "node": { "name": "node-1", "uid": "4e5f6a7b" },
+                "warnafter": 1_700_000_000,
+            },
+        })
+    }
+
+    fn validate(
+        validator: &ServiceAccountTokenValidator,
+        claims: Value,
+    ) -> Result<ServiceAccountTokenClaims, PresetError> {
+        let token = sign(&claims, &Header::empty().with_key_id("k1"));
+        let token = UntrustedToken::new(&token).unwrap();
+        let token = validator.validate(&Hs256, &key(), &token, &TimeOptions::default())?;
+        Ok(token.into_parts().1.custom)
+    }
+
+    #[test]
+    fn valid_token() {
+        let validator = ServiceAccountTokenValidator::new(ISSUER, "webhook");
+        let claims = validate(&validator, token_claims()).unwrap();
+        assert_eq!(claims.kubernetes.namespace, "prod");
+        assert_eq!(claims.kubernetes.node.unwrap().name, "node-1");
+        assert!(claims.kubernetes.warn_after.is_some());
+
+        let validator = validator.with_service_account("prod", "web");
+        validate(&validator, token_claims()).unwrap();
+    }
+
+    #[test]
+    fn invalid_tokens() {
+        let validator = ServiceAccountTokenValidator::new(ISSUER, "webhook");
+        let mut claims = token_claims();
+        claims["sub"] = "system:serviceaccount:prod:admin".into();
+        let err = validate(&validator, claims).unwrap_err();
+        assert_matches!(err, PresetError::ClaimMismatch("sub"));
+
+        let err =
+            validate(&validator.clone().with_namespace("staging"), token_claims()).unwrap_err();
+        assert_matches!(err, PresetError::ClaimMismatch("sub"));
+        let validator = ServiceAccountTokenValidator::new(ISSUER, "api");
+        let err = validate(&validator, token_claims()).unwrap_err();
+        assert_matches!(err, PresetError::ClaimMismatch("aud"));
+    }
+
+    #[test]
+    fn validating_with_key_set() {
+        let jwk = serde_json::to_value(JsonWebKey::from(&key())).unwrap();
+        let mut entry = KeySetEntry::new(serde_json::from_value(jwk).unwrap());
+        entry.key_id = Some("k1".to_owned());
+        let key_set = JsonWebKeySet {
+            keys: Vec::from([entry]),
+        };
+
+        let token = sign(&token_claims(), &Header::empty().with_key_id("k1"));
+        let token = UntrustedToken::new(&token).unwrap();
+        let validator = ServiceAccountTokenValidator::new(ISSUER, "webhook");
+        validator
+            .validate_with_key_set(&Hs256, &key_set, &token, &TimeOptions::default())
+            .unwrap();
+
+        let err = validator
+            .validate_with_key_set(
+                &Hs256,
+                &JsonWebKeySet::default(),
+                &token,
+                &TimeOptions::default(),
+            )
+            .unwrap_err();
+        assert_matches!(err, PresetError::UnknownKey);
+    }
+}
@@ -0,0 +1,507 @@
+//! Preset for [SPIFFE JWT-SVIDs].
+//!
+//! [SPIFFE JWT-SVIDs]: https://github.com/spiffe/spiffe/blob/main/standards/JWT-SVID.md
+
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use core::{fmt, str::FromStr};
+
+use super::PresetError;
+use crate::{
+    alloc::{BTreeMap, String, ToOwned, Vec},
+    claims::serde_audience,
+    jwk::{JsonWebKey, JsonWebKeySet, JwkError},
+    Algorithm, AlgorithmExt, TimeOptions, Timestamp, Token, UntrustedToken,
+};
+
+const SCHEME: &str = "spiffe://";
+const MAX_ID_LEN: usize = 2_048;
+
+/// [SPIFFE ID] consisting of a trust domain and a path, e.g. `spiffe://example.org/service`.
+///
+/// [SPIFFE ID]: https://github.com/spiffe/spiffe/blob/main/standards/SPIFFE-ID.md
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct SpiffeId {
+    id: String,
+    path_start: usize,
+}
+
+impl SpiffeId {
+    /// Returns the trust domain of this ID.
+    pub fn trust_domain(&self) -> &str {
+        &self.id[SCHEME.len()..self.path_start]
+    }
+
+    /// Returns the path of this ID (empty or starting with `/`).
+    pub fn path(&self) -> &str {
+        &self.id[self.path_start..]
+    }
+
+    /// Returns the string presentation of this ID.
+    pub fn as_str(&self) -> &str {
+        &self.id
+    }
+
+    fn is_valid_trust_domain(domain: &str) -> bool {
+        !domain.is_empty()
+            && domain.bytes().all(|ch| {
+                ch.is_ascii_lowercase() || ch.is_ascii_digit() || matches!(ch, b'.' | b'-' | b'_')
+            })
+    }
+
+    fn is_valid_segment(segment: &str) -> bool {
+        !matches!(segment, "" | "." | "..")
+            && segment
+                .bytes()
+                .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, b'.' | b'-' | b'_'))
+    }
+}
+
+impl fmt::Display for SpiffeId {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(&self.id)
+    }
+}
+
+impl FromStr for SpiffeId {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(s.to_owned())
+    }
+}
+
+impl TryFrom<String> for SpiffeId {
+    type Error = &'static str;
+
+    fn try_from(id: String) -> Result<Self, Self::Error> {
+        if id.len() > MAX_ID_LEN {
+            return Err("SPIFFE ID is too long");
+        }
+        let rest = id.strip_prefix(SCHEME).ok_or("invalid SPIFFE ID scheme")?;
+        let path_start = rest.find('/').unwrap_or(rest.len());
+        if !Self::is_valid_trust_domain(&rest[..path_start]) {
+            return Err("invalid SPIFFE ID trust domain");
+        }
+        let path = &rest[path_start..];
+        if !path.is_empty() && !path[1..].split('/').all(Self::is_valid_segment) {
+            return Err("invalid SPIFFE ID path");
+        }
+        Ok(Self {
+            path_start: SCHEME.len() + path_start,
+            id,
+        })
+    }
+}
+
+impl From<SpiffeId> for String {
+    fn from(id: SpiffeId) -> Self {
+        id.id
+    }
+}
+
+/// Claims of a JWT-SVID.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct JwtSvidClaims {
+    /// SPIFFE ID of the workload (`sub`).
+    #[serde(rename = "sub")]
+    pub subject: SpiffeId,
+    /// Audience of the token (`aud`).
+    #[serde(rename = "aud", with = "serde_audience")]
+    pub audience: Vec<String>,
+    /// Other claims.
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+/// Source of fresh JWT trust bundles used by [`JwtSvidBundles`] when a token references
+/// an unknown key (e.g., after key rotation).
+///
+/// The hook is blanket-implemented for functions with the matching signature.
+pub trait BundleRefresh {
+    /// Fetches the current JWT bundle for the specified trust domain.
+    fn refresh(&mut self, trust_domain: &str) -> Option<JsonWebKeySet<'static>>;
+}
+
+impl<F> BundleRefresh for F
+where
+    F: FnMut(&str) -> Option<JsonWebKeySet<'static>>,
+{
+    fn refresh(&mut self, trust_domain: &str) -> Option<JsonWebKeySet<'static>> {
+        self(trust_domain)
+    }
+}
+
+/// JWT trust bundles (JWK sets) keyed by the trust domain.
+#[derive(Debug, Clone, Default)]
+pub struct JwtSvidBundles {
+    bundles: BTreeMap<String, JsonWebKeySet<'static>>,
+}
+
+impl JwtSvidBundles {
+    /// Creates an empty bundle collection.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses bundles in the format returned by the `FetchJWTBundles` method of
+    /// the SPIFFE Workload API (e.g., exposed by SPIRE agents), i.e., a map from trust domains
+    /// to JSON-encoded JWK sets.
+    pub fn from_workload_api<'a>(
+        bundles: impl IntoIterator<Item = (&'a str, &'a [u8])>,
+    ) -> Result<Self, serde_json::Error> {
+        let bundles = bundles
+            .into_iter()
+            .map(|(trust_domain, jwks)| {
+                Ok((trust_domain.to_owned(), serde_json::from_slice(jwks)?))
+            })
+            .collect::<Result<_, serde_json::Error>>()?;
+        Ok(Self { bundles })
+    }
+
+    /// Inserts or replaces the bundle for the specified trust domain.
+    pub fn insert(&mut self, trust_domain: impl Into<String>, jwks: JsonWebKeySet<'static>) {
+        self.bundles.insert(trust_domain.into(), jwks);
+    }
+
+    /// Returns the bundle for the specified trust domain.
+    pub fn get(&self, trust_domain: &str) -> Option<&JsonWebKeySet<'static>> {
+        self.bundles.get(trust_domain)
+    }
+
+    fn find_key<K>(&self, trust_domain: &str, key_id: &str) -> Option<Result<K, JwkError>>
+    where
+        K: for<'k> TryFrom<&'k JsonWebKey<'k>, Error = JwkError>,
+    {
+        let entry = self.bundles.get(trust_domain)?.find(key_id)?;
+        Some(K::try_from(&entry.key))
+    }
+}
+
+/// Validator of JWT-SVIDs.
+///
+/// Besides the signature, the validator checks that:
+///
+/// - The `sub` claim is a valid SPIFFE ID. If [trust domains](Self::with_trust_domain())
+///   are set, the ID must belong to one of them.
+/// - The `aud` claim contains the expected audience.
+/// - The `exp` claim is present and the token is not expired.
+/// - The token lifetime does not exceed [the maximum](Self::with_max_lifetime()) (1 hour
+///   by default). The lifetime is measured from the `iat` claim if present, and from
+///   the current time otherwise.
+///
+/// [`Self::validate_with_bundles()`] additionally selects the verifying key from
+/// the trust bundle of the SVID trust domain, refreshing the bundle if necessary.
+///
+/// # Examples
+///
+/// ```
+/// # use chrono::Duration;
+/// # use jwt_compact::{alg::{Hs256, Hs256Key}, prelude::*};
+/// use jwt_compact::presets::{JwtSvidBundles, JwtSvidValidator};
+/// # use serde_json::json;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// # // SVIDs are signed with asymmetric algorithms; `HS256` is only used to keep the example short.
+/// # let key = Hs256Key::new(b"super_secret_key_donut_steel");
+/// # let options = TimeOptions::default();
+/// # let claims = json!({ "sub": "spiffe://example.org/frontend", "aud": "backend" });
+/// # let claims = Claims::new(claims)
+/// #     .set_duration_and_issuance(&options, Duration::try_minutes(5).unwrap());
+/// # let svid = Hs256.token(&Header::empty().with_key_id("k1"), &claims, &key)?;
+/// # let jwks = json!({
+/// #     "keys": [{ "kty": "oct", "kid": "k1", "k": "c3VwZXJfc2VjcmV0X2tleV9kb251dF9zdGVlbA" }],
+/// # });
+/// # let jwks = serde_json::to_vec(&jwks)?;
+/// // Bundles returned by the Workload API.
+/// let mut bundles = JwtSvidBundles::from_workload_api([("example.org", jwks.as_slice())])?;
+/// let validator = JwtSvidValidator::new("backend").with_trust_domain("example.org");
+/// let svid = UntrustedToken::new(&svid)?;
+/// let mut refresh = |_: &str| None; // Would call the Workload API in real code
+/// let svid =
+///     validator.validate_with_bundles(&Hs256, &mut bundles, &mut refresh, &svid, &options)?;
+/// assert_eq!(svid.claims().custom.subject.path(), "/frontend");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct JwtSvidValidator {
+    audience: String,
+    trust_domains: Vec<String>,
+    max_lifetime: Duration,
+}
+
+impl JwtSvidValidator {
+    /// Creates a validator for the specified audience.
+    pub fn new(audience: impl Into<String>) -> Self {
+        Self {
+            audience: audience.into(),
+            trust_domains: Vec::new(),
+            max_lifetime: Duration::try_hours(1).unwrap(),
+        }
+    }
+
+    /// Adds an allowed trust domain. If no trust domains are added, SVIDs from any trust domain
+    /// are accepted.
+    #[must_use]
+    pub fn with_trust_domain(mut self, trust_domain: impl Into<String>) -> Self {
+        self.trust_domains.push(trust_domain.into());
+        self
+    }
+
+    /// Sets the maximum lifetime of SVIDs.
+    #[must_use]
+    pub fn with_max_lifetime(mut self, max_lifetime: Duration) -> Self {
+        self.max_lifetime = max_lifetime;
+        self
+    }
+
+    /// Validates the SVID using the specified algorithm and key.
+    pub fn validate<A: Algorithm, F>(
+        &self,
+        algorithm: &A,
+        verifying_key: &A::VerifyingKey,
+        token: &UntrustedToken<'_>,
+        options: &TimeOptions<F>,
+    ) -> Result<Token<JwtSvidClaims>, PresetError>
+    where
+        F: Fn() -> Timestamp,
+    {
+        let token = algorithm
+            .validator::<JwtSvidClaims>(verifying_key)
+            .validate(token)?;
+        let claims = token.claims();
+        let expiration = claims.expiration.ok_or(PresetError::MissingClaim("exp"))?;
+        claims.validate_expiration(options)?;
+        // `iat` comes from the token and the clock is user-provided, so the arithmetic
+        // must not panic on extreme values.
+        let lifetime_start = claims.issued_at.map_or_else(
+            || (options.clock_fn)().checked_sub_signed(options.leeway),
+            Some,
+        );
+        let is_within_lifetime = lifetime_start
+            .and_then(|start| start.checked_add_signed(self.max_lifetime))
+            .is_some_and(|lifetime_end| expiration <= lifetime_end);
+        if !is_within_lifetime {
+            return Err(PresetError::ClaimMismatch("exp"));
+        }
+
+        let custom = &claims.custom;
+        if !self.is_trusted(custom.subject.trust_domain()) {
+            return Err(PresetError::ClaimMismatch("sub"));
+        }
+        if !custom.audience.contains(&self.audience) {
+            return Err(PresetError::ClaimMismatch("aud"));
+        }
+        Ok(token)
+    }
+
+    /// Validates the SVID using the key from the trust bundle for the SVID trust domain
+    /// referenced by the `kid` header field. If the bundle or the key is missing,
+    /// the bundle is refreshed using `refresh`, and the lookup is retried.
+    pub fn validate_with_bundles<A: Algorithm, F>(
+        &self,
+        algorithm: &A,
+        bundles: &mut JwtSvidBundles,
+        refresh: &mut impl BundleRefresh,
+        token: &UntrustedToken<'_>,
+        options: &TimeOptions<F>,
+    ) -> Result<Token<JwtSvidClaims>, PresetError>
+    where
+        A::VerifyingKey: for<'k> TryFrom<&'k JsonWebKey<'k>, Error = JwkError>,
+        F: Fn() -> Timestamp,
+    {
+        let key_id = token
+            .header()
+            .key_id
+            .as_deref()
+            .ok_or(PresetError::UnknownKey)?;
+        // The trust domain is taken from unverified claims only to select the bundle.
+        let claims = token.deserialize_claims_unverified::<JwtSvidClaims>()?;
+        let trust_domain = claims.custom.subject.trust_domain();
+        if !self.is_trusted(trust_domain) {
+            return Err(PresetError::ClaimMismatch("sub"));
+        }
+
+        let key = if let Some(key) = bundles.find_key::<A::VerifyingKey>(trust_domain, key_id) {
+            key
+        } else {
+            let bundle = refresh
+                .refresh(trust_domain)
+                .ok_or(PresetError::UnknownKey)?;
+            bundles.insert(trust_domain, bundle);
+            bundles
+                .find_key(trust_domain, key_id)
+                .ok_or(PresetError::UnknownKey)?
+        };
+        let key = key.map_err(PresetError::Jwk)?;
+        self.validate(algorithm, &key, token, options)
+    }
+
+    fn is_trusted(&self, trust_domain: &str) -> bool {
+        self.trust_domains.is_empty() || self.trust_domains.iter().any(|td| td == trust_domain)
+    }
+}
+
+#[cfg(all(test, feature = "clock"))]
+mod tests {
+    use assert_matches::assert_matches;
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        alg::Hs256,
+        jwk::KeySetEntry,
+        test_helpers::{key, sign},
+        AlgorithmExt, Claims, Header, ValidationError,
+    };
+
+    fn bundle() -> JsonWebKeySet<'static> {
+        let jwk = serde_json::to_value(JsonWebKey::from(&key())).unwrap();
+        let mut entry = KeySetEntry::new(serde_json::from_value(jwk).unwrap());
+        entry.key_id = Some("k1".to_owned());
+        JsonWebKeySet {
+            keys: Vec::from([entry]),
+        }
+    }
+
+    #[test]
+    fn parsing_spiffe_ids() {
+        let id: SpiffeId = "spiffe://example.org/ns/prod/sa/web".parse().unwrap();
+        assert_eq!(id.trust_domain(), "example.org");
+        assert_eq!(id.path(), "/ns/prod/sa/web");
+        let id: SpiffeId = "spiffe://example.org".parse().unwrap();
+        assert_eq!(id.path(), "");
+
+        let invalid_ids = [
+            "https://example.org/web",
+            "spiffe://",
+            "spiffe://Example.org/web",
+            "spiffe://example.org:8080/web",
+            "spiffe://example.org/",
+            "spiffe://example.org//web",
+            "spiffe://example.org/../web",
+            "spiffe://example.org/web?query",
+        ];
+        for id in invalid_ids {
+            assert!(id.parse::<SpiffeId>().is_err(), "{id}");
+        }
+    }
+
+    #[test]
+    fn validating_svid() {
+        let claims = json!({ "sub": "spiffe://example.org/web", "aud": ["backend", "other"] });
+        let token = sign(&claims, &Header::empty().with_key_id("k1"));
+        let token = UntrustedToken::new(&token).unwrap();
+        let options = TimeOptions::default();
+
+        let validator = JwtSvidValidator::new("backend");
+        validator
+            .validate(&Hs256, &key(), &token, &options)
+            .unwrap();
+        let err = JwtSvidValidator::new("frontend")
+            .validate(&Hs256, &key(), &token, &options)
+            .unwrap_err();
+        assert_matches!(err, PresetError::ClaimMismatch("aud"));
+        let err = validator
+            .clone()
+            .with_trust_domain("other.org")
+            .validate(&Hs256, &key(), &token, &options)
+            .unwrap_err();
+        assert_matches!(err, PresetError::ClaimMismatch("sub"));
+        let err = validator
+            .with_max_lifetime(Duration::try_minutes(1).unwrap())
+            .validate(&Hs256, &key(), &token, &options)
+            .unwrap_err();
+        assert_matches!(err, PresetError::ClaimMismatch("exp"));
+    }
+
+    #[test]
+    fn svid_with_extreme_timestamps() {
+        let header = Header::empty().with_key_id("k1");
+        let validator = JwtSvidValidator::new("backend");
+        let custom = json!({ "sub": "spiffe://example.org/web", "aud": "backend" });
+
+        let mut claims = Claims::new(&custom);
+        claims.expiration = Some(Timestamp::MAX);
+        claims.issued_at = Some(Timestamp::MAX);
+        let token = Hs256.token(&header, &claims, &key()).unwrap();
+        let token = UntrustedToken::new(&token).unwrap();
+        let err = validator
+            .validate(&Hs256, &key(), &token, &TimeOptions::default())
+            .unwrap_err();
+        assert_matches!(err, PresetError::ClaimMismatch("exp"));
+
+        let mut claims = Claims::new(&custom);
+        claims.expiration = Some(Timestamp::MIN);
+        let token = Hs256.token(&header, &claims, &key()).unwrap();
+        let token = UntrustedToken::new(&token).unwrap();
+        let options = TimeOptions::new(Duration::try_minutes(1).unwrap(), || Timestamp::MIN);
+        let err = validator
+            .validate(&Hs256, &key(), &token, &options)
+            .unwrap_err();
+        assert_matches!(err, PresetError::ClaimMismatch("exp"));
+    }
+
+    #[test]
+    fn svid_with_invalid_subject() {
+        let claims = json!({ "sub": "spiffe://example.org/../web", "aud": "backend" });
+        let token = sign(&claims, &Header::empty().with_key_id("k1"));
+        let token = UntrustedToken::new(&token).unwrap();
+        let err = JwtSvidValidator::new("backend")
+            .validate(&Hs256, &key(), &token, &TimeOptions::default())
+            .unwrap_err();
+        assert_matches!(
+            err,
+            PresetError::Validation(ValidationError::MalformedClaims(_))
+        );
+    }
+
+    #[test]
+    fn refreshing_bundles() {
+        let claims = json!({ "sub": "spiffe://example.org/web", "aud": "backend" });
+        let token = sign(&claims, &Header::empty().with_key_id("k1"));
+        let token = UntrustedToken::new(&token).unwrap();
+        let options = TimeOptions::default();
+        let validator = JwtSvidValidator::new("backend");
+
+        let mut bundles = JwtSvidBundles::new();
+        let mut refresh_count = 0;
+        let mut refresh = |trust_domain: &str| {
+            refresh_count += 1;
+            (trust_domain == "example.org").then(bundle)
+        };
+        validator
+            .validate_with_bundles(&Hs256, &mut bundles, &mut refresh, &token, &options)
+            .unwrap();
+        validator
+            .validate_with_bundles(&Hs256, &mut bundles, &mut refresh, &token, &options)
+            .unwrap();
+        assert_eq!(refresh_count, 1);
+        assert!(bundles.get("example.org").is_some());
+
+        let mut no_refresh = |_: &str| None;
+        let err = validator
+            .validate_with_bundles(
+                &Hs256,
+                &mut JwtSvidBundles::new(),
+                &mut no_refresh,
+                &token,
+                &options,
+            )
+            .unwrap_err();
+        assert_matches!(err, PresetError::UnknownKey);
+    }
+
+    #[test]
+    fn parsing_workload_api_bundles() {
+        let jwks = serde_json::to_vec(&bundle()).unwrap();
+        let bundles =
+            JwtSvidBundles::from_workload_api([("example.org", jwks.as_slice())]).unwrap();
+        assert_eq!(bundles.get("example.org").unwrap().keys.len(), 1);
+    }
+}
@@ -0,0 +1,78 @@
+//! Items used by the code generated by the `Claims` derive macro. Not a part of the public API.
+
+pub use serde;
+
+use crate::{
+    alloc::{Box, Cow, String, Vec},
+    Audience,
+};
+
+/// Used in `skip_serializing_if` for optional claims.
+#[allow(clippy::ref_option_ref, clippy::trivially_copy_pass_by_ref)] // signature required by `serde`
+pub fn is_none<T>(value: &&Option<T>) -> bool {
+    value.is_none()
+}
+
+/// String claim, such as `iss`.
+pub trait ClaimStr {
+    fn claim_str(&self) -> &str;
+}
+
+impl ClaimStr for str {
+    fn claim_str(&self) -> &str {
+        self
+    }
+}
+
+impl ClaimStr for String {
+    fn claim_str(&self) -> &str {
+        self
+    }
+}
+
+impl ClaimStr for Box<str> {
+    fn claim_str(&self) -> &str {
+        self
+    }
+}
+
+impl ClaimStr for Cow<'_, str> {
+    fn claim_str(&self) -> &str {
+        self
+    }
+}
+
+impl<T: ClaimStr + ?Sized> ClaimStr for &T {
+    fn claim_str(&self) -> &str {
+        (**self).claim_str()
+    }
+}
+
+/// Claim containing one or more strings, such as `aud`.
+pub trait ClaimStrs {
+    fn contains_str(&self, expected: &str) -> bool;
+}
+
+impl<T: ClaimStr + ?Sized> ClaimStrs for T {
+    fn contains_str(&self, expected: &str) -> bool {
+        self.claim_str() == expected
+    }
+}
+
+impl<T: ClaimStr> ClaimStrs for [T] {
+    fn contains_str(&self, expected: &str) -> bool {
+        self.iter().any(|value| value.claim_str() == expected)
+    }
+}
+
+impl<T: ClaimStr> ClaimStrs for Vec<T> {
+    fn contains_str(&self, expected: &str) -> bool {
+        self.as_slice().contains_str(expected)
+    }
+}
+
+impl ClaimStrs for Audience {
+    fn contains_str(&self, expected: &str) -> bool {
+        self.contains(expected)
+    }
+}
@@ -0,0 +1,213 @@
+//! Re-issuing validated tokens with refreshed time-related claims.
+
+use base64ct::{Base64UrlUnpadded, Encoding};
+use chrono::Duration;
+use rand_core::{CryptoRng, RngCore};
+use serde::{ser::Error as _, Serialize};
+use serde_json::Value;
+
+use crate::{
+    alloc::String, Algorithm, AlgorithmExt, Claims, CreationError, DynamicClaims, TimeOptions,
+    Timestamp, Token, TokenString,
+};
+
+/// Byte length of randomly generated JWT IDs.
+const JWT_ID_LEN: usize = 16;
+
+/// Re-issuer of validated tokens, e.g., for session tokens with sliding expiration.
+///
+/// A re-issued token has the same header and claims as the original token, except for
+/// the following:
+///
+/// - `iat` is set to the current time, and `exp` is set to the current time plus
+///   the configured duration.
+/// - `jti` is set to a new random value (16 bytes, base64url-encoded).
+/// - `kid` in the header is replaced if [`Self::with_key_id()`] was called
+///   (e.g., after a key rotation).
+///
+/// Claims are copied via their serialized form, so all fields captured by the claims type
+/// in the validated token are preserved. To preserve all claims regardless of their names,
+/// validate the original token with [`DynamicClaims`].
+///
+/// # Examples
+///
+/// ```
+/// # use chrono::Duration;
+/// # use rand::thread_rng;
+/// use jwt_compact::{prelude::*, alg::{Hs256, Hs256Key}, DynamicClaims, Reissue};
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let key = Hs256Key::new(b"super_secret_key_donut_steel");
+/// let time_options = TimeOptions::default();
+/// let claims = Claims::new(DynamicClaims::new().with_claim("sub", "alice"))
+///     .set_duration_and_issuance(&time_options, Duration::try_minutes(15).unwrap());
+/// let token_string = Hs256.token(&Header::empty().with_key_id("k1"), &claims, &key)?;
+/// let token = UntrustedToken::new(&token_string)?;
+/// let token = Hs256.validator::<DynamicClaims>(&key).validate(&token)?;
+///
+/// let new_token = Reissue::new(&time_options, Duration::try_minutes(15).unwrap())
+///     .with_key_id("k2")
+///     .sign(&Hs256, &key, &token, &mut thread_rng())?;
+/// let new_token = UntrustedToken::new(&new_token)?;
+/// assert_eq!(new_token.header().key_id.as_deref(), Some("k2"));
+/// let new_token = Hs256.validator::<DynamicClaims>(&key).validate(&new_token)?;
+/// assert_eq!(new_token.claims().custom.get_str("sub"), Some("alice"));
+/// assert!(new_token.claims().custom.get_str("jti").is_some());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Reissue<'a, F = fn() -> Timestamp> {
+    time_options: &'a TimeOptions<F>,
+    duration: Duration,
+    key_id: Option<String>,
+}
+
+impl<'a, F: Fn() -> Timestamp> Reissue<'a, F> {
+    /// Creates a re-issuer with the specified time options and the `duration` of re-issued
+    /// tokens.
+    pub fn new(time_options: &'a TimeOptions<F>, duration: Duration) -> Self {
+        Self {
+            time_options,
+            duration,
+            key_id: None,
+        }
+    }
+
+    /// Sets the key ID (`kid` header field) for re-issued tokens. By default, the key ID
+    /// of the original token is retained.
+    #[must_use]
+    pub fn with_key_id(mut self, key_id: impl Into<String>) -> Self {
+        self.key_id = Some(key_id.into());
+        self
+    }
+
+    /// Re-issues the validated `token` using the specified algorithm and signing key.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the token claims do not serialize to a JSON object,
+    /// or if the token cannot be created.
+    pub fn sign<A, T, H, R>(
+        &self,
+        algorithm: &A,
+        signing_key: &A::SigningKey,
+        token: &Token<T, H>,
+        rng: &mut R,
+    ) -> Result<TokenString, CreationError>
+    where
+        A: Algorithm,
+        T: Serialize,
+        H: Serialize + Clone,
+        R: CryptoRng + RngCore,
+    {
+        let mut custom = match serde_json::to_value(&token.claims().custom) {
+            Ok(Value::Object(map)) => DynamicClaims::from(map),
+            Ok(Value::Null) => DynamicClaims::new(),
+            Ok(_) => {
+                let err = serde_json::Error::custom("custom claims must serialize to an object");
+                return Err(CreationError::Claims(err));
+            }
+            Err(err) => return Err(CreationError::Claims(err)),
+        };
+        let mut jwt_id = [0_u8; JWT_ID_LEN];
+        rng.fill_bytes(&mut jwt_id);
+        custom.insert(
+            "jti".into(),
+            Base64UrlUnpadded::encode_string(&jwt_id).into(),
+        );
+
+        let original = token.claims();
+        let mut claims =
+            Claims::new(custom).set_duration_and_issuance(self.time_options, self.duration);
+        claims.not_before = original.not_before;
+
+        let mut header = token.header().clone();
+        if let Some(key_id) = &self.key_id {
+            header.key_id = Some(key_id.clone());
+        }
+        algorithm.token(&header, &claims, signing_key)
+    }
+}
+
+#[cfg(all(test, feature = "clock"))]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use rand::thread_rng;
+
+    use super::*;
+    use crate::{alg::Hs256, test_helpers::key, Empty, Header, UntrustedToken};
+
+    fn validate(token: &str) -> Token<DynamicClaims> {
+        let token = UntrustedToken::new(token).unwrap();
+        Hs256.validator(&key()).validate(&token).unwrap()
+    }
+
+    #[test]
+    fn reissuing_token() {
+        let issued_at = Timestamp::from(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap());
+        let old_options = TimeOptions::new(Duration::zero(), move || issued_at);
+        let claims = Claims::new(
+            DynamicClaims::new()
+                .with_claim("sub", "alice")
+                .with_claim("jti", "old"),
+        )
+        .set_duration_and_issuance(&old_options, Duration::try_minutes(10).unwrap())
+        .set_not_before(issued_at);
+        let header = Header::empty().with_key_id("old").with_token_type("JWT");
+        let token = Hs256.token(&header, &claims, &key()).unwrap();
+        let token = UntrustedToken::new(&token).unwrap();
+        let token = Hs256
+            .validator::<DynamicClaims>(&key())
+            .validate(&token)
+            .unwrap();
+
+        let now = issued_at + Duration::try_minutes(5).unwrap();
+        let options = TimeOptions::new(Duration::zero(), move || now);
+        let reissue = Reissue::new(&options, Duration::try_minutes(10).unwrap());
+        let new_token = reissue
+            .sign(&Hs256, &key(), &token, &mut thread_rng())
+            .unwrap();
+        let new_token = validate(&new_token);
+
+        let new_claims = new_token.claims();
+        assert_eq!(new_claims.issued_at, Some(now));
+        assert_eq!(
+            new_claims.expiration,
+            Some(now + Duration::try_minutes(10).unwrap())
+        );
+        assert_eq!(new_claims.not_before, Some(issued_at));
+        assert_eq!(new_claims.custom.get_str("sub"), Some("alice"));
+        let jwt_id = new_claims.custom.get_str("jti").unwrap();
+        assert_ne!(jwt_id, "old");
+        assert_eq!(jwt_id.len(), 22);
+        assert_eq!(new_token.header().key_id.as_deref(), Some("old"));
+        assert_eq!(new_token.header().token_type.as_deref(), Some("JWT"));
+
+        let rotated = reissue
+            .with_key_id("new")
+            .sign(&Hs256, &key(), &token, &mut thread_rng())
+            .unwrap();
+        let rotated = validate(&rotated);
+        assert_eq!(rotated.header().key_id.as_deref(), Some("new"));
+        assert_ne!(rotated.claims().custom.get_str("jti").unwrap(), jwt_id);
+    }
+
+    #[test]
+    fn reissuing_token_with_empty_claims() {
+        let options = TimeOptions::default();
+        let token = Hs256
+            .token(&Header::empty(), &Claims::empty(), &key())
+            .unwrap();
+        let token = UntrustedToken::new(&token).unwrap();
+        let token = Hs256.validator::<Empty>(&key()).validate(&token).unwrap();
+
+        let reissue = Reissue::new(&options, Duration::try_minutes(1).unwrap());
+        let new_token = reissue
+            .sign(&Hs256, &key(), &token, &mut thread_rng())
+            .unwrap();
+        let new_token = validate(&new_token);
+        assert!(new_token.claims().expiration.is_some());
+        assert_eq!(new_token.claims().custom.len(), 1);
+    }
+}
@@ -0,0 +1,84 @@
+//! Protection against token replay based on the `jti` claim.
+
+use crate::{
+    alloc::{BTreeMap, String, ToOwned},
+    Timestamp,
+};
+
+/// Storage of token IDs (`jti` claims) that have already been accepted. Used to reject
+/// replayed single-use tokens, such as logout tokens.
+pub trait ReplayGuard {
+    /// Records the token ID that remains relevant until `expires_at` (after that moment,
+    /// the token will be rejected anyway, so the ID can be forgotten). Returns `false`
+    /// if the ID was already recorded, i.e., the token is replayed.
+    fn record(&mut self, jwt_id: &str, expires_at: Timestamp) -> bool;
+}
+
+impl<G: ReplayGuard + ?Sized> ReplayGuard for &mut G {
+    fn record(&mut self, jwt_id: &str, expires_at: Timestamp) -> bool {
+        (**self).record(jwt_id, expires_at)
+    }
+}
+
+/// In-memory [`ReplayGuard`] suitable for single-process deployments.
+///
+/// Recorded IDs are kept until [`Self::prune()`] is called with a moment after their expiration.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryReplayGuard {
+    ids: BTreeMap<String, Timestamp>,
+}
+
+impl MemoryReplayGuard {
+    /// Creates an empty guard.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of recorded IDs.
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Checks whether the guard has no recorded IDs.
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Forgets IDs that have expired before `now`.
+    pub fn prune(&mut self, now: Timestamp) {
+        self.ids.retain(|_, expires_at| *expires_at >= now);
+    }
+}
+
+impl ReplayGuard for MemoryReplayGuard {
+    fn record(&mut self, jwt_id: &str, expires_at: Timestamp) -> bool {
+        if self.ids.contains_key(jwt_id) {
+            false
+        } else {
+            self.ids.insert(jwt_id.to_owned(), expires_at);
+            true
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    #[test]
+    fn memory_guard_basics() {
+        let now = Timestamp::from_secs(1_700_000_000);
+        let mut guard = MemoryReplayGuard::new();
+        assert!(guard.record("first", now + Duration::try_minutes(1).unwrap()));
+        assert!(guard.record("second", now + Duration::try_minutes(10).unwrap()));
+        assert!(!guard.record("first", now + Duration::try_minutes(1).unwrap()));
+        assert_eq!(guard.len(), 2);
+
+        guard.prune(now + Duration::try_minutes(5).unwrap());
+        assert_eq!(guard.len(), 1);
+        assert!(guard.record("first", now + Duration::try_minutes(1).unwrap()));
+        assert!(!guard.record("second", now));
+    }
+}
@@ -0,0 +1,513 @@
+//! Rotation of signing keys for token issuers.
+//!
+//! [`KeyRing`] holds the active signing key together with a bounded number of previous
+//! verifying keys. Keys are identified by their [RFC 7638] thumbprints, which are stamped
+//! into the `kid` field of created tokens. This allows verifiers to select the correct key
+//! from the [JWK set](KeyRing::to_jwks()) published by the issuer, so that tokens signed
+//! before a rotation remain valid until the corresponding key is discarded.
+//!
+//! [RFC 7638]: https://www.rfc-editor.org/rfc/rfc7638.html
+
+use chrono::Duration;
+use rand_core::{CryptoRng, RngCore};
+use serde::de::DeserializeOwned;
+#[cfg(feature = "sign")]
+use serde::Serialize;
+
+use core::fmt;
+
+use crate::{
+    alg::{GenerateKey, SigningKey},
+    alloc::{String, Vec},
+    jwk::{JsonWebKey, JsonWebKeySet, KeySetEntry},
+    key_store::KeyStore,
+    Algorithm, AlgorithmExt, Header, TimeOptions, Timestamp, Token, UntrustedToken,
+    ValidationError,
+};
+#[cfg(feature = "sign")]
+use crate::{Claims, CreationError, TokenString};
+
+/// Errors that can occur during token validation with a [`KeyRing`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum RotationError {
+    /// The key referenced by the token (via the `kid` header field) is not present
+    /// in the key ring, or the token does not reference a key.
+    UnknownKey,
+    /// Token has failed generic validation (e.g., has an invalid signature).
+    Validation(ValidationError),
+}
+
+impl fmt::Display for RotationError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownKey => formatter.write_str("token is signed with an unknown key"),
+            Self::Validation(err) => write!(formatter, "token validation failed: {err}"),
+        }
+    }
+}
+
+impl core::error::Error for RotationError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::Validation(err) => Some(err),
+            Self::UnknownKey => None,
+        }
+    }
+}
+
+impl From<ValidationError> for RotationError {
+    fn from(err: ValidationError) -> Self {
+        Self::Validation(err)
+    }
+}
+
+/// Computes the key ID as the base64url-encoded RFC 7638 thumbprint of the verifying key.
+fn key_id<K>(verifying_key: &K) -> String
+where
+    for<'a> JsonWebKey<'a>: From<&'a K>,
+{
+    JsonWebKey::from(verifying_key).thumbprint_key_id()
+}
+
+struct ActiveKey<A: Algorithm> {
+    key_id: String,
+    signing_key: A::SigningKey,
+    verifying_key: A::VerifyingKey,
+    activated_at: Timestamp,
+}
+
+struct RetiredKey<A: Algorithm> {
+    key_id: String,
+    verifying_key: A::VerifyingKey,
+}
+
+/// Key ring holding the active signing key and previous verifying keys for an algorithm.
+///
+/// The active key can be rotated on demand ([`Self::rotate()`], [`Self::rotate_to()`]) or
+/// on schedule ([`Self::rotate_if_due()`] together with [`Self::with_rotation_period()`]).
+/// After rotation, the verifying key of the previously active key is retained so that
+/// tokens signed with it can still be validated; the number of retained keys is bounded
+/// (see [`Self::with_retained_keys()`]).
+///
+/// Symmetric keys (e.g., for `HS256`) are never included into the [JWK set](Self::to_jwks())
+/// since it is meant to be published.
+///
+/// # Examples
+///
+/// ```
+/// # use chrono::Duration;
+/// # use rand::thread_rng;
+/// use jwt_compact::{prelude::*, alg::{Hs256, Hs256Key}, rotation::KeyRing};
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let time_options = TimeOptions::default();
+/// let key = Hs256Key::generate(&mut thread_rng()).into_inner();
+/// let mut key_ring = KeyRing::new(Hs256, key, &time_options)
+///     .with_rotation_period(Duration::try_days(30).unwrap())
+///     .with_retained_keys(2);
+///
+/// let claims = Claims::new(serde_json::json!({ "sub": "alice" }))
+///     .set_duration_and_issuance(&time_options, Duration::try_minutes(10).unwrap());
+/// let token = key_ring.token(Header::empty(), &claims)?;
+///
+/// key_ring.rotate(&mut thread_rng(), &time_options);
+/// // Tokens signed with the previous key are still valid.
+/// let token = UntrustedToken::new(&token)?;
+/// let token = key_ring.validate::<serde_json::Value, _>(&token)?;
+/// assert_eq!(token.claims().custom["sub"], "alice");
+/// # Ok(())
+/// # }
+/// ```
+pub struct KeyRing<A: Algorithm> {
+    algorithm: A,
+    rotation_period: Option<Duration>,
+    retained_keys: usize,
+    active: ActiveKey<A>,
+    /// Retired keys, from the most recent to the oldest.
+    retired: Vec<RetiredKey<A>>,
+}
+
+impl<A: Algorithm> fmt::Debug for KeyRing<A> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let retired_ids: Vec<_> = self.retired.iter().map(|key| &key.key_id).collect();
+        formatter
+            .debug_struct("KeyRing")
+            .field("algorithm", &self.algorithm.name())
+            .field("rotation_period", &self.rotation_period)
+            .field("retained_keys", &self.retained_keys)
+            .field("active_key_id", &self.active.key_id)
+            .field("activated_at", &self.active.activated_at)
+            .field("retired_key_ids", &retired_ids)
+            .finish()
+    }
+}
+
+impl<A> KeyRing<A>
+where
+    A: Algorithm,
+    A::SigningKey: SigningKey<A>,
+    for<'a> JsonWebKey<'a>: From<&'a A::VerifyingKey>,
+{
+    /// Default number of retained previous keys.
+    pub const DEFAULT_RETAINED_KEYS: usize = 2;
+
+    /// Creates a key ring with the specified active signing key. The activation time
+    /// of the key is taken from `time_options`.
+    pub fn new<F>(algorithm: A, signing_key: A::SigningKey, time_options: &TimeOptions<F>) -> Self
+    where
+        F: Fn() -> Timestamp,
+    {
+        Self {
+            algorithm,
+            rotation_period: None,
+            retained_keys: Self::DEFAULT_RETAINED_KEYS,
+            active: Self::active_key(signing_key, time_options),
+            retired: Vec::new(),
+        }
+    }
+
+    fn active_key<F>(signing_key: A::SigningKey, time_options: &TimeOptions<F>) -> ActiveKey<A>
+    where
+        F: Fn() -> Timestamp,
+    {
+        let verifying_key = signing_key.to_verifying_key();
+        ActiveKey {
+            key_id: key_id(&verifying_key),
+            signing_key,
+            verifying_key,
+            activated_at: (time_options.clock_fn)(),
+        }
+    }
+
+    /// Sets the period after which the active key should be rotated by [`Self::rotate_if_due()`].
+    /// By default, the period is not set, i.e., keys are only rotated on demand.
+    #[must_use]
+    pub fn with_rotation_period(mut self, period: Duration) -> Self {
+        self.rotation_period = Some(period);
+        self
+    }
+
+    /// Sets the maximum number of previous verifying keys retained after rotation.
+    /// The default value is [`Self::DEFAULT_RETAINED_KEYS`].
+    #[must_use]
+    pub fn with_retained_keys(mut self, count: usize) -> Self {
+        self.retained_keys = count;
+        self.retired.truncate(count);
+        self
+    }
+
+    /// Returns the algorithm used by this key ring.
+    pub fn algorithm(&self) -> &A {
+        &self.algorithm
+    }
+
+    /// Returns the ID of the active key.
+    pub fn active_key_id(&self) -> &str {
+        &self.active.key_id
+    }
+
+    /// Returns the active signing key.
+    pub fn signing_key(&self) -> &A::SigningKey {
+        &self.active.signing_key
+    }
+
+    /// Returns the time at which the active key was activated.
+    pub fn activated_at(&self) -> Timestamp {
+        self.active.activated_at
+    }
+
+    /// Returns the verifying key with the specified ID, which may correspond either
+    /// to the active key or to one of the retained previous keys.
+    pub fn verifying_key(&self, key_id: &str) -> Option<&A::VerifyingKey> {
+        if self.active.key_id == key_id {
+            return Some(&self.active.verifying_key);
+        }
+        self.retired
+            .iter()
+            .find(|key| key.key_id == key_id)
+            .map(|key| &key.verifying_key)
+    }
+
+    /// Iterates over IDs of all keys in this ring, starting from the active key
+    /// and then from the most recently retired key to the oldest one.
+    pub fn key_ids(&self) -> impl Iterator<Item = &str> + '_ {
+        let retired_ids = self.retired.iter().map(|key| key.key_id.as_str());
+        core::iter::once(self.active.key_id.as_str()).chain(retired_ids)
+    }
+
+    /// Replaces the active key with `signing_key`, retaining the verifying key
+    /// of the previously active key.
+    pub fn rotate_to<F>(&mut self, signing_key: A::SigningKey, time_options: &TimeOptions<F>)
+    where
+        F: Fn() -> Timestamp,
+    {
+        let new_key = Self::active_key(signing_key, time_options);
+        let old_key = core::mem::replace(&mut self.active, new_key);
+        // The new key may coincide with one of the retired keys; we don't want duplicates.
+        self.retired.retain(|key| key.key_id != self.active.key_id);
+        if old_key.key_id != self.active.key_id {
+            self.retired.insert(
+                0,
+                RetiredKey {
+                    key_id: old_key.key_id,
+                    verifying_key: old_key.verifying_key,
+                },
+            );
+        }
+        self.retired.truncate(self.retained_keys);
+    }
+
+    /// Replaces the active key with a randomly generated one, retaining the verifying key
+    /// of the previously active key.
+    pub fn rotate<R, F>(&mut self, rng: &mut R, time_options: &TimeOptions<F>)
+    where
+        A::SigningKey: GenerateKey<A>,
+        R: CryptoRng + RngCore,
+        F: Fn() -> Timestamp,
+    {
+        self.rotate_to(A::SigningKey::generate(rng), time_options);
+    }
+
+    /// Checks whether the active key is older than the [rotation period](Self::with_rotation_period()).
+    /// Always returns `false` if the rotation period is not set.
+    pub fn is_rotation_due<F>(&self, time_options: &TimeOptions<F>) -> bool
+    where
+        F: Fn() -> Timestamp,
+    {
+        self.rotation_period
+            .is_some_and(|period| (time_options.clock_fn)() >= self.active.activated_at + period)
+    }
+
+    /// Rotates the active key to a randomly generated one if [rotation is due](Self::is_rotation_due()).
+    /// Returns `true` if the key was rotated.
+    pub fn rotate_if_due<R, F>(&mut self, rng: &mut R, time_options: &TimeOptions<F>) -> bool
+    where
+        A::SigningKey: GenerateKey<A>,
+        R: CryptoRng + RngCore,
+        F: Fn() -> Timestamp,
+    {
+        let is_due = self.is_rotation_due(time_options);
+        if is_due {
+            self.rotate(rng, time_options);
+        }
+        is_due
+    }
+
+    /// Creates a token signed by the active key. The `kid` field of the `header` is set
+    /// to the [active key ID](Self::active_key_id()).
+    #[cfg(feature = "sign")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "sign")))]
+    pub fn token<H, T>(
+        &self,
+        header: Header<H>,
+        claims: &Claims<T>,
+    ) -> Result<TokenString, CreationError>
+    where
+        H: Serialize,
+        T: Serialize,
+    {
+        let header = header.with_key_id(self.active.key_id.clone());
+        self.algorithm
+            .token(&header, claims, &self.active.signing_key)
+    }
+
+    /// Validates the token integrity using the key referenced by the `kid` field
+    /// of the token header.
+    pub fn validate<T, H>(
+        &self,
+        token: &UntrustedToken<'_, H>,
+    ) -> Result<Token<T, H>, RotationError>
+    where
+        T: DeserializeOwned,
+        H: Clone,
+    {
+        let key_id = token
+            .header()
+            .key_id
+            .as_deref()
+            .ok_or(RotationError::UnknownKey)?;
+        let verifying_key = self
+            .verifying_key(key_id)
+            .ok_or(RotationError::UnknownKey)?;
+        Ok(self.algorithm.validator(verifying_key).validate(token)?)
+    }
+
+    /// Returns the JWK set with verifying keys in this ring, which can be published
+    /// by the issuer. Each key in the set has `kid`, `alg` and `use` (`sig`) fields set.
+    pub fn to_jwks(&self) -> JsonWebKeySet<'_> {
+        let retired_keys = self
+            .retired
+            .iter()
+            .map(|key| (&key.key_id, &key.verifying_key));
+        let all_keys =
+            core::iter::once((&self.active.key_id, &self.active.verifying_key)).chain(retired_keys);
+
+        let keys = all_keys.filter_map(|(key_id, verifying_key)| {
+            let jwk = JsonWebKey::from(verifying_key);
+            if jwk.is_signing_key() {
+                return None; // symmetric key; must not be published
+            }
+            Some(KeySetEntry::for_signatures(
+                jwk,
+                key_id.clone(),
+                &self.algorithm.name(),
+            ))
+        });
+        JsonWebKeySet {
+            keys: keys.collect(),
+        }
+    }
+}
+
+/// Selects verifying keys by the `kid` header field, similar to [`KeyRing::validate()`].
+impl<A> KeyStore<A> for KeyRing<A>
+where
+    A: Algorithm,
+    A::SigningKey: SigningKey<A>,
+    for<'a> JsonWebKey<'a>: From<&'a A::VerifyingKey>,
+{
+    fn key_for<H>(&self, header: &Header<H>) -> Result<&A::VerifyingKey, ValidationError> {
+        header
+            .key_id
+            .as_deref()
+            .and_then(|key_id| self.verifying_key(key_id))
+            .ok_or(ValidationError::UnknownKey)
+    }
+}
+
+#[cfg(all(test, feature = "clock"))]
+mod tests {
+    use assert_matches::assert_matches;
+    use chrono::Utc;
+    use rand::thread_rng;
+
+    use super::*;
+    use crate::alg::{Hs256, Hs256Key};
+
+    fn time_options(time: Timestamp) -> TimeOptions<impl Fn() -> Timestamp> {
+        TimeOptions::new(Duration::try_seconds(60).unwrap(), move || time)
+    }
+
+    fn create_token<A>(key_ring: &KeyRing<A>) -> String
+    where
+        A: Algorithm,
+        A::SigningKey: SigningKey<A>,
+        for<'a> JsonWebKey<'a>: From<&'a A::VerifyingKey>,
+    {
+        let claims = Claims::new(serde_json::json!({ "sub": "alice" }));
+        key_ring.token(Header::empty(), &claims).unwrap().into()
+    }
+
+    #[test]
+    fn rotating_keys_on_demand() {
+        let now = Timestamp::from(Utc::now());
+        let key = Hs256Key::generate(&mut thread_rng()).into_inner();
+        let mut key_ring =
+            KeyRing::new(Hs256, key.clone(), &time_options(now)).with_retained_keys(1);
+        let first_id = key_ring.active_key_id().to_owned();
+        assert_eq!(first_id, key_id(&key));
+
+        let token = create_token(&key_ring);
+        let token = UntrustedToken::new(&token).unwrap();
+        assert_eq!(token.header().key_id.as_deref(), Some(first_id.as_str()));
+        key_ring.validate::<serde_json::Value, _>(&token).unwrap();
+        Hs256
+            .key_store_validator::<serde_json::Value, _>(&key_ring)
+            .validate(&token)
+            .unwrap();
+
+        key_ring.rotate(&mut thread_rng(), &time_options(now));
+        let second_id = key_ring.active_key_id().to_owned();
+        assert_ne!(second_id, first_id);
+        assert_eq!(
+            key_ring.key_ids().collect::<Vec<_>>(),
+            [&second_id, &first_id]
+        );
+        key_ring.validate::<serde_json::Value, _>(&token).unwrap();
+
+        key_ring.rotate(&mut thread_rng(), &time_options(now));
+        assert_eq!(key_ring.key_ids().count(), 2);
+        assert!(key_ring.verifying_key(&first_id).is_none());
+        let err = key_ring
+            .validate::<serde_json::Value, _>(&token)
+            .unwrap_err();
+        assert_matches!(err, RotationError::UnknownKey);
+
+        // Rotating to a retired key should not lead to duplicate entries.
+        key_ring.rotate_to(key, &time_options(now));
+        assert_eq!(key_ring.active_key_id(), first_id);
+        assert_eq!(key_ring.key_ids().count(), 2);
+    }
+
+    #[test]
+    fn rotating_keys_on_schedule() {
+        let now = Timestamp::from(Utc::now());
+        let key = Hs256Key::generate(&mut thread_rng()).into_inner();
+        let mut key_ring = KeyRing::new(Hs256, key, &time_options(now))
+            .with_rotation_period(Duration::try_days(1).unwrap());
+        let first_id = key_ring.active_key_id().to_owned();
+
+        let later = now + Duration::try_hours(23).unwrap();
+        assert!(!key_ring.rotate_if_due(&mut thread_rng(), &time_options(later)));
+        assert_eq!(key_ring.active_key_id(), first_id);
+
+        let later = now + Duration::try_days(1).unwrap();
+        assert!(key_ring.rotate_if_due(&mut thread_rng(), &time_options(later)));
+        assert_ne!(key_ring.active_key_id(), first_id);
+        assert_eq!(key_ring.activated_at(), later);
+        assert!(!key_ring.is_rotation_due(&time_options(later)));
+    }
+
+    #[test]
+    fn token_without_key_id_is_rejected() {
+        let key = Hs256Key::generate(&mut thread_rng()).into_inner();
+        let key_ring = KeyRing::new(Hs256, key.clone(), &TimeOptions::default());
+        let claims = Claims::new(serde_json::json!({ "sub": "alice" }));
+        let token = Hs256.token(&Header::empty(), &claims, &key).unwrap();
+        let token = UntrustedToken::new(&token).unwrap();
+        let err = key_ring
+            .validate::<serde_json::Value, _>(&token)
+            .unwrap_err();
+        assert_matches!(err, RotationError::UnknownKey);
+    }
+
+    #[test]
+    fn symmetric_keys_are_not_published() {
+        let key = Hs256Key::generate(&mut thread_rng()).into_inner();
+        let key_ring = KeyRing::new(Hs256, key, &TimeOptions::default());
+        assert!(key_ring.to_jwks().keys.is_empty());
+    }
+
+    #[cfg(feature = "ed25519-compact")]
+    #[test]
+    fn publishing_jwks() {
+        use crate::alg::Ed25519;
+
+        let now = Timestamp::from(Utc::now());
+        let (signing_key, _) = GenerateKey::generate_pair(&mut thread_rng());
+        let mut key_ring = KeyRing::new(Ed25519, signing_key, &time_options(now));
+        key_ring.rotate(&mut thread_rng(), &time_options(now));
+        let token = create_token(&key_ring);
+
+        let jwks = key_ring.to_jwks();
+        assert_eq!(jwks.keys.len(), 2);
+        for (entry, key_id) in jwks.keys.iter().zip(key_ring.key_ids()) {
+            assert_eq!(entry.key_id.as_deref(), Some(key_id));
+            assert_eq!(entry.algorithm.as_deref(), Some("EdDSA"));
+            assert_eq!(entry.key_use.as_deref(), Some("sig"));
+            assert!(!entry.key.is_signing_key());
+        }
+
+        // Check that the token can be verified using the published key set.
+        let token = UntrustedToken::new(&token).unwrap();
+        let entry = jwks
+            .find(token.header().key_id.as_deref().unwrap())
+            .unwrap();
+        let verifying_key = <Ed25519 as Algorithm>::VerifyingKey::try_from(&entry.key).unwrap();
+        Ed25519
+            .validator::<serde_json::Value>(&verifying_key)
+            .validate(&token)
+            .unwrap();
+    }
+}
@@ -0,0 +1,751 @@
+//! [Selective disclosure for JWTs][SD-JWT] (SD-JWT).
+//!
+//! An SD-JWT consists of an issuer-signed JWT, in which some claims are replaced with
+//! digests, and a set of [`Disclosure`]s revealing the concealed claims. The holder
+//! of an SD-JWT can choose which disclosures to present to a verifier, optionally proving
+//! possession of a holder key via a key binding JWT (KB-JWT).
+//!
+//! The [`vc`] module implements the SD-JWT VC profile for verifiable credentials on top
+//! of SD-JWTs.
+//!
+//! [SD-JWT]: https://datatracker.ietf.org/doc/draft-ietf-oauth-selective-disclosure-jwt/
+//!
+//! # Examples
+//!
+//! ```
+//! # use rand::thread_rng;
+//! # use serde::{Deserialize, Serialize};
+//! # use jwt_compact::{alg::{Hs256, Hs256Key}, prelude::*, sd_jwt::{SdJwt, SdJwtBuilder}};
+//! #[derive(Debug, Serialize, Deserialize)]
+//! struct Person {
+//!     name: String,
+//!     #[serde(default)]
+//!     email: Option<String>,
+//! }
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let key = Hs256Key::new(b"super_secret_key_donut_steel");
+//! let claims = Claims::new(Person {
+//!     name: "Alice".to_owned(),
+//!     email: Some("alice@example.com".to_owned()),
+//! });
+//! // Issuer: make the email selectively disclosable.
+//! let sd_jwt = SdJwtBuilder::new(&claims)?
+//!     .conceal("/email", &mut thread_rng())?
+//!     .sign(&Hs256, &Header::empty(), &key)?;
+//! let sd_jwt = sd_jwt.to_string();
+//!
+//! // Holder: present the SD-JWT without disclosing the email.
+//! let sd_jwt: SdJwt = sd_jwt.parse()?;
+//! let presentation = sd_jwt.presentation(|_| false);
+//!
+//! // Verifier: validate the presentation.
+//! let token = presentation.validate::<_, Person>(&Hs256, &key)?;
+//! assert_eq!(token.claims().custom.name, "Alice");
+//! assert_eq!(token.claims().custom.email, None);
+//! # Ok(())
+//! # }
+//! ```
+
+use chrono::Duration;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use core::{fmt, str::FromStr};
+
+mod builder;
+mod disclosure;
+mod error;
+pub mod vc;
+
+pub use self::{
+    builder::SdJwtBuilder,
+    disclosure::{Disclosure, SdHashAlgorithm},
+    error::SdJwtError,
+};
+
+use self::disclosure::parse_disclosures;
+#[cfg(feature = "sign")]
+use crate::Header;
+use crate::{
+    alloc::{BTreeMap, BTreeSet, String, ToOwned, ToString, Vec},
+    constant_time_eq, Algorithm, AlgorithmExt, Claims, TimeOptions, Timestamp, Token,
+    UntrustedToken, ValidationError,
+};
+
+/// Name of the claim containing digests of selectively disclosable object properties.
+const DIGESTS_KEY: &str = "_sd";
+/// Name of the key containing the digest of a selectively disclosable array element.
+const ARRAY_DIGEST_KEY: &str = "...";
+/// Name of the claim specifying the hash algorithm for disclosure digests.
+const HASH_ALGORITHM_KEY: &str = "_sd_alg";
+/// Token type of key binding JWTs.
+const KEY_BINDING_TYPE: &str = "kb+jwt";
+
+/// Custom claims of a key binding JWT.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct KeyBindingClaims {
+    /// Nonce provided by the verifier (`nonce`) to ensure the freshness of the presentation.
+    pub nonce: String,
+    /// Intended recipient of the presentation (`aud`).
+    #[serde(rename = "aud")]
+    pub audience: String,
+    /// Digest of the presented SD-JWT (`sd_hash`) binding the KB-JWT to it.
+    pub sd_hash: String,
+}
+
+/// SD-JWT in the combined serialization: the issuer-signed JWT, disclosures and an optional
+/// key binding JWT.
+///
+/// SD-JWTs are created using [`SdJwtBuilder`] and can be parsed from the `~`-separated
+/// string format via [`FromStr`]. The [`Display`](fmt::Display) implementation produces
+/// the same format.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SdJwt {
+    jwt: String,
+    disclosures: Vec<Disclosure>,
+    key_binding: Option<String>,
+}
+
+impl SdJwt {
+    /// Returns the issuer-signed JWT.
+    pub fn jwt(&self) -> &str {
+        &self.jwt
+    }
+
+    /// Returns disclosures included into this SD-JWT.
+    pub fn disclosures(&self) -> &[Disclosure] {
+        &self.disclosures
+    }
+
+    /// Returns the key binding JWT, if any.
+    pub fn key_binding(&self) -> Option<&str> {
+        self.key_binding.as_deref()
+    }
+
+    /// Creates a presentation of this SD-JWT retaining only disclosures matching
+    /// the provided `filter`. The key binding JWT is not retained.
+    #[must_use]
+    pub fn presentation(&self, mut filter: impl FnMut(&Disclosure) -> bool) -> Self {
+        Self {
+            jwt: self.jwt.clone(),
+            disclosures: self
+                .disclosures
+                .iter()
+                .filter(|&disclosure| filter(disclosure))
+                .cloned()
+                .collect(),
+            key_binding: None,
+        }
+    }
+
+    /// Adds a key binding JWT to this SD-JWT, signing it with the holder key.
+    #[cfg(feature = "sign")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "sign")))]
+    pub fn with_key_binding<A: Algorithm, F>(
+        mut self,
+        algorithm: &A,
+        signing_key: &A::SigningKey,
+        audience: impl Into<String>,
+        nonce: impl Into<String>,
+        options: &TimeOptions<F>,
+    ) -> Result<Self, SdJwtError>
+    where
+        F: Fn() -> Timestamp,
+    {
+        let sd_hash = self.sd_hash()?;
+        let mut claims = Claims::new(KeyBindingClaims {
+            nonce: nonce.into(),
+            audience: audience.into(),
+            sd_hash,
+        });
+        claims.issued_at = Some((options.clock_fn)());
+        let header = Header::empty().with_token_type(KEY_BINDING_TYPE);
+        self.key_binding = Some(algorithm.token(&header, &claims, signing_key)?.into());
+        Ok(self)
+    }
+
+    /// Returns the SD-JWT without the key binding JWT, which is the input to compute `sd_hash`.
+    fn without_key_binding(&self) -> String {
+        let mut serialized = self.jwt.clone();
+        serialized.push('~');
+        for disclosure in &self.disclosures {
+            serialized.push_str(disclosure.as_str());
+            serialized.push('~');
+        }
+        serialized
+    }
+
+    /// Reads the hash algorithm from the issuer-signed JWT *without* verifying its signature.
+    fn hash_algorithm(&self) -> Result<SdHashAlgorithm, SdJwtError> {
+        let token = UntrustedToken::new(&self.jwt)?;
+        let claims = token.deserialize_claims_unverified::<Map<String, Value>>()?;
+        hash_algorithm(&claims.custom)
+    }
+
+    fn sd_hash(&self) -> Result<String, SdJwtError> {
+        let hash_algorithm = self.hash_algorithm()?;
+        Ok(hash_algorithm.digest(self.without_key_binding().as_bytes()))
+    }
+
+    /// Validates the issuer-signed JWT and the disclosures, and returns the token with
+    /// the disclosed claims.
+    ///
+    /// Concealed claims without a corresponding disclosure are omitted from the returned claims.
+    /// This method does not validate the key binding JWT; use [`Self::validate_key_binding()`]
+    /// for that.
+    pub fn validate<A: Algorithm, T: DeserializeOwned>(
+        &self,
+        algorithm: &A,
+        verifying_key: &A::VerifyingKey,
+    ) -> Result<Token<T>, SdJwtError> {
+        let token = UntrustedToken::new(&self.jwt)?;
+        let (_, claims) = algorithm
+            .validator::<Map<String, Value>>(verifying_key)
+            .validate(&token)?
+            .into_parts();
+        let Value::Object(mut payload) =
+            serde_json::to_value(claims).map_err(|_| SdJwtError::NonObjectClaims)?
+        else {
+            return Err(SdJwtError::NonObjectClaims);
+        };
+
+        let hash_algorithm = hash_algorithm(&payload)?;
+        payload.remove(HASH_ALGORITHM_KEY);
+        let mut disclosures = BTreeMap::new();
+        for disclosure in &self.disclosures {
+            let digest = disclosure.digest(hash_algorithm);
+            if disclosures.insert(digest.clone(), disclosure).is_some() {
+                return Err(SdJwtError::DuplicateDigest(digest));
+            }
+        }
+        let mut reconstructor = Reconstructor {
+            disclosures,
+            seen_digests: BTreeSet::new(),
+        };
+        reconstructor.process_object(&mut payload)?;
+        if let Some(digest) = reconstructor.disclosures.into_keys().next() {
+            return Err(SdJwtError::UnreferencedDisclosure(digest));
+        }
+
+        let claims = serde_json::from_value(Value::Object(payload))
+            .map_err(ValidationError::MalformedClaims)?;
+        Ok(Token::new(&token, claims))
+    }
+
+    /// Validates the key binding JWT against the holder key, the expected `audience`
+    /// and `nonce`. The `iat` claim of the KB-JWT must be present, not be in the future
+    /// and not be older than `max_age` (both subject to the leeway in `options`).
+    ///
+    /// The issuer-signed JWT is *not* validated by this method; use [`Self::validate()`]
+    /// for that. Note that the holder key should be obtained from the validated
+    /// issuer-signed JWT (e.g., from its `cnf` claim).
+    pub fn validate_key_binding<A: Algorithm, F>(
+        &self,
+        algorithm: &A,
+        holder_key: &A::VerifyingKey,
+        audience: &str,
+        nonce: &str,
+        max_age: Duration,
+        options: &TimeOptions<F>,
+    ) -> Result<Claims<KeyBindingClaims>, SdJwtError>
+    where
+        F: Fn() -> Timestamp,
+    {
+        let key_binding = self
+            .key_binding
+            .as_deref()
+            .ok_or(SdJwtError::MissingKeyBinding)?;
+        let token = UntrustedToken::new(key_binding)?;
+        if token.header().token_type.as_deref() != Some(KEY_BINDING_TYPE) {
+            return Err(SdJwtError::KeyBindingMismatch("typ"));
+        }
+        let token = algorithm
+            .validator::<KeyBindingClaims>(holder_key)
+            .validate(&token)?;
+        let (_, claims) = token.into_parts();
+
+        if !constant_time_eq(claims.custom.sd_hash.as_bytes(), self.sd_hash()?.as_bytes()) {
+            return Err(SdJwtError::KeyBindingMismatch("sd_hash"));
+        }
+        if claims.custom.audience != audience {
+            return Err(SdJwtError::KeyBindingMismatch("aud"));
+        }
+        if !constant_time_eq(claims.custom.nonce.as_bytes(), nonce.as_bytes()) {
+            return Err(SdJwtError::KeyBindingMismatch("nonce"));
+        }
+        let issued_at = claims
+            .issued_at
+            .ok_or(SdJwtError::KeyBindingMismatch("iat"))?;
+        // `iat` is supplied by the holder, so the arithmetic must not panic on extreme values.
+        let now = (options.clock_fn)();
+        let is_issued = issued_at
+            .checked_sub_signed(options.leeway)
+            .is_some_and(|issued_at| issued_at <= now);
+        let is_fresh = issued_at
+            .checked_add_signed(max_age)
+            .and_then(|expiration| expiration.checked_add_signed(options.leeway))
+            .is_some_and(|expiration| expiration >= now);
+        if !is_issued || !is_fresh {
+            return Err(SdJwtError::KeyBindingMismatch("iat"));
+        }
+        Ok(claims)
+    }
+}
+
+impl fmt::Display for SdJwt {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(&self.jwt)?;
+        formatter.write_str("~")?;
+        for disclosure in &self.disclosures {
+            write!(formatter, "{disclosure}~")?;
+        }
+        if let Some(key_binding) = &self.key_binding {
+            formatter.write_str(key_binding)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for SdJwt {
+    type Err = SdJwtError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (jwt, rest) = s.split_once('~').ok_or(SdJwtError::InvalidFormat)?;
+        let (disclosures, key_binding) = match rest.rsplit_once('~') {
+            Some((disclosures, key_binding)) => (Some(disclosures), key_binding),
+            None => (None, rest),
+        };
+        if jwt.is_empty() {
+            return Err(SdJwtError::InvalidFormat);
+        }
+        let disclosures = disclosures.map_or_else(
+            || Ok(Vec::new()),
+            |disclosures| parse_disclosures(disclosures.split('~')),
+        )?;
+
+        Ok(Self {
+            jwt: jwt.to_owned(),
+            disclosures,
+            key_binding: (!key_binding.is_empty()).then(|| key_binding.to_owned()),
+        })
+    }
+}
+
+fn hash_algorithm(payload: &Map<String, Value>) -> Result<SdHashAlgorithm, SdJwtError> {
+    match payload.get(HASH_ALGORITHM_KEY) {
+        None => Ok(SdHashAlgorithm::default()),
+        Some(Value::String(name)) => SdHashAlgorithm::from_name(name),
+        Some(_) => Err(SdJwtError::MalformedPayload(
+            "`_sd_alg` claim is not a string".to_owned(),
+        )),
+    }
+}
+
+/// Replaces digests in the SD-JWT payload with the disclosed values.
+struct Reconstructor<'a> {
+    disclosures: BTreeMap<String, &'a Disclosure>,
+    seen_digests: BTreeSet<String>,
+}
+
+impl Reconstructor<'_> {
+    fn check_digest(&mut self, digest: &str) -> Result<(), SdJwtError> {
+        if self.seen_digests.insert(digest.to_owned()) {
+            Ok(())
+        } else {
+            Err(SdJwtError::DuplicateDigest(digest.to_owned()))
+        }
+    }
+
+    fn process_value(&mut self, value: &mut Value) -> Result<(), SdJwtError> {
+        match value {
+            Value::Object(object) => self.process_object(object),
+            Value::Array(array) => self.process_array(array),
+            _ => Ok(()),
+        }
+    }
+
+    fn process_object(&mut self, object: &mut Map<String, Value>) -> Result<(), SdJwtError> {
+        let digests = match object.remove(DIGESTS_KEY) {
+            None => Vec::new(),
+            Some(Value::Array(digests)) => digests,
+            Some(_) => {
+                return Err(SdJwtError::MalformedPayload(
+                    "`_sd` claim is not an array".to_owned(),
+                ));
+            }
+        };
+
+        for value in object.values_mut() {
+            self.process_value(value)?;
+        }
+
+        for digest in digests {
+            let Value::String(digest) = digest else {
+                return Err(SdJwtError::MalformedPayload(
+                    "`_sd` claim contains a non-string digest".to_owned(),
+                ));
+            };
+            self.check_digest(&digest)?;
+            let Some(disclosure) = self.disclosures.remove(&digest) else {
+                continue; // decoy digest or an undisclosed claim
+            };
+            let Some(name) = disclosure.claim_name() else {
+                return Err(SdJwtError::MalformedDisclosure(
+                    "array element disclosure is referenced from `_sd` claim".to_owned(),
+                ));
+            };
+            if name == DIGESTS_KEY || name == ARRAY_DIGEST_KEY || object.contains_key(name) {
+                return Err(SdJwtError::ClaimConflict(name.to_owned()));
+            }
+            let mut value = disclosure.value().clone();
+            self.process_value(&mut value)?;
+            object.insert(name.to_owned(), value);
+        }
+        Ok(())
+    }
+
+    fn process_array(&mut self, array: &mut Vec<Value>) -> Result<(), SdJwtError> {
+        let mut processed = Vec::with_capacity(array.len());
+        for mut element in array.drain(..) {
+            let Some(digest) = array_element_digest(&element) else {
+                self.process_value(&mut element)?;
+                processed.push(element);
+                continue;
+            };
+
+            let digest = digest.to_string();
+            self.check_digest(&digest)?;
+            let Some(disclosure) = self.disclosures.remove(&digest) else {
+                continue; // decoy digest or an undisclosed element
+            };
+            if disclosure.claim_name().is_some() {
+                return Err(SdJwtError::MalformedDisclosure(
+                    "object property disclosure is referenced from an array".to_owned(),
+                ));
+            }
+            let mut value = disclosure.value().clone();
+            self.process_value(&mut value)?;
+            processed.push(value);
+        }
+        *array = processed;
+        Ok(())
+    }
+}
+
+/// Returns the digest if the `element` is a placeholder `{"...": digest}` for a selectively
+/// disclosable array element.
+fn array_element_digest(element: &Value) -> Option<&str> {
+    let Value::Object(object) = element else {
+        return None;
+    };
+    if object.len() == 1 {
+        object.get(ARRAY_DIGEST_KEY)?.as_str()
+    } else {
+        None
+    }
+}
+
+#[cfg(all(test, feature = "clock"))]
+mod tests {
+    use assert_matches::assert_matches;
+    use chrono::Utc;
+    use rand::thread_rng;
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        alg::{Hs256, Hs256Key},
+        test_helpers::key,
+    };
+
+    fn create_sd_jwt() -> SdJwt {
+        let claims = Claims::new(json!({
+            "sub": "alice",
+            "email": "alice@example.com",
+            "address": {
+                "street": "Main St",
+                "country": "US",
+            },
+            "nationalities": ["US", "DE"],
+        }));
+        let mut rng = thread_rng();
+        SdJwtBuilder::new(&claims)
+            .unwrap()
+            .conceal("/email", &mut rng)
+            .unwrap()
+            .conceal("/address/street", &mut rng)
+            .unwrap()
+            .conceal("/address", &mut rng)
+            .unwrap()
+            .conceal("/nationalities/1", &mut rng)
+            .unwrap()
+            .with_decoys(3, &mut rng)
+            .sign(&Hs256, &Header::empty(), &key())
+            .unwrap()
+    }
+
+    #[test]
+    fn issued_payload_does_not_contain_concealed_claims() {
+        let sd_jwt = create_sd_jwt();
+        assert_eq!(sd_jwt.disclosures().len(), 4);
+
+        let token = UntrustedToken::new(sd_jwt.jwt()).unwrap();
+        let claims = token
+            .deserialize_claims_unverified::<Map<String, Value>>()
+            .unwrap()
+            .custom;
+        assert_eq!(claims["_sd_alg"], "sha-256");
+        assert!(!claims.contains_key("email"));
+        assert!(!claims.contains_key("address"));
+        let digests = claims["_sd"].as_array().unwrap();
+        assert_eq!(digests.len(), 5); // 2 concealed claims + 3 decoys
+        assert!(digests
+            .windows(2)
+            .all(|window| window[0].as_str() < window[1].as_str()));
+        assert_eq!(claims["nationalities"][0], "US");
+        assert!(claims["nationalities"][1]["..."].is_string());
+    }
+
+    #[test]
+    fn full_disclosure_roundtrip() {
+        let sd_jwt = create_sd_jwt();
+        let sd_jwt: SdJwt = sd_jwt.to_string().parse().unwrap();
+        let token = sd_jwt.validate::<_, Value>(&Hs256, &key()).unwrap();
+        assert_eq!(
+            token.claims().custom,
+            json!({
+                "sub": "alice",
+                "email": "alice@example.com",
+                "address": {
+                    "street": "Main St",
+                    "country": "US",
+                },
+                "nationalities": ["US", "DE"],
+            })
+        );
+    }
+
+    #[test]
+    fn partial_disclosure() {
+        let sd_jwt = create_sd_jwt();
+        let presentation = sd_jwt
+            .presentation(|disclosure| matches!(disclosure.claim_name(), Some("address") | None));
+        assert_eq!(presentation.disclosures().len(), 2);
+
+        let token = presentation.validate::<_, Value>(&Hs256, &key()).unwrap();
+        assert_eq!(
+            token.claims().custom,
+            json!({
+                "sub": "alice",
+                "address": { "country": "US" },
+                "nationalities": ["US", "DE"],
+            })
+        );
+    }
+
+    #[test]
+    fn parsing_and_serializing_sd_jwt() {
+        let sd_jwt = create_sd_jwt();
+        let serialized = sd_jwt.to_string();
+        assert!(serialized.ends_with('~'));
+        assert_eq!(serialized.matches('~').count(), 5);
+
+        let no_disclosures = sd_jwt.presentation(|_| false).to_string();
+        assert_eq!(no_disclosures, format!("{}~", sd_jwt.jwt()));
+        let parsed: SdJwt = no_disclosures.parse().unwrap();
+        assert!(parsed.disclosures().is_empty());
+        assert_eq!(parsed.key_binding(), None);
+
+        assert_matches!(
+            sd_jwt.jwt().parse::<SdJwt>().unwrap_err(),
+            SdJwtError::InvalidFormat
+        );
+        assert_matches!("~".parse::<SdJwt>().unwrap_err(), SdJwtError::InvalidFormat);
+    }
+
+    #[test]
+    fn unreferenced_and_duplicate_disclosures() {
+        let sd_jwt = create_sd_jwt();
+        let mut tampered = sd_jwt.clone();
+        let extra = Disclosure::new("admin", true.into(), &mut thread_rng());
+        tampered.disclosures.push(extra);
+        let err = tampered.validate::<_, Value>(&Hs256, &key()).unwrap_err();
+        assert_matches!(err, SdJwtError::UnreferencedDisclosure(_));
+
+        let mut duplicated = sd_jwt;
+        let first = duplicated.disclosures[0].clone();
+        duplicated.disclosures.push(first);
+        let err = duplicated.validate::<_, Value>(&Hs256, &key()).unwrap_err();
+        assert_matches!(err, SdJwtError::DuplicateDigest(_));
+    }
+
+    #[test]
+    fn conflicting_disclosure() {
+        let claims = Claims::new(json!({ "sub": "alice" }));
+        let mut rng = thread_rng();
+        let (mut payload, _) = SdJwtBuilder::new(&claims)
+            .unwrap()
+            .conceal("/sub", &mut rng)
+            .unwrap()
+            .build();
+        payload.insert("sub".to_owned(), "bob".into());
+        let disclosure = Disclosure::new("sub", "alice".into(), &mut rng);
+        let digest = disclosure.digest(SdHashAlgorithm::Sha256);
+        payload.insert("_sd".to_owned(), json!([digest]));
+
+        let jwt = Hs256
+            .token(&Header::empty(), &Claims::new(payload), &key())
+            .unwrap()
+            .into();
+        let sd_jwt = SdJwt {
+            jwt,
+            disclosures: vec![disclosure],
+            key_binding: None,
+        };
+        let err = sd_jwt.validate::<_, Value>(&Hs256, &key()).unwrap_err();
+        assert_matches!(err, SdJwtError::ClaimConflict(name) if name == "sub");
+    }
+
+    #[test]
+    fn invalid_conceal_paths() {
+        let claims = Claims::new(json!({ "sub": "alice", "list": [1] }));
+        let builder = SdJwtBuilder::new(&claims).unwrap();
+        for path in ["", "sub", "/email", "/list/1", "/list/x", "/sub/0", "/_sd"] {
+            let err = builder
+                .clone()
+                .conceal(path, &mut thread_rng())
+                .unwrap_err();
+            assert_matches!(err, SdJwtError::InvalidPath(_));
+        }
+    }
+
+    #[test]
+    fn key_binding() {
+        let holder_key = Hs256Key::new(b"holder_secret_key_donut_steel");
+        let options = TimeOptions::default();
+        let max_age = Duration::try_minutes(5).unwrap();
+        let presentation = create_sd_jwt()
+            .presentation(|disclosure| disclosure.claim_name() == Some("email"))
+            .with_key_binding(
+                &Hs256,
+                &holder_key,
+                "https://verifier.example.com",
+                "n-0S6_WzA2Mj",
+                &options,
+            )
+            .unwrap();
+        let presentation: SdJwt = presentation.to_string().parse().unwrap();
+        assert!(presentation.key_binding().is_some());
+
+        let claims = presentation
+            .validate_key_binding(
+                &Hs256,
+                &holder_key,
+                "https://verifier.example.com",
+                "n-0S6_WzA2Mj",
+                max_age,
+                &options,
+            )
+            .unwrap();
+        assert_eq!(claims.custom.nonce, "n-0S6_WzA2Mj");
+
+        let err = presentation
+            .validate_key_binding(
+                &Hs256,
+                &holder_key,
+                "https://verifier.example.com",
+                "other-nonce",
+                max_age,
+                &options,
+            )
+            .unwrap_err();
+        assert_matches!(err, SdJwtError::KeyBindingMismatch("nonce"));
+
+        let mut tampered = presentation.clone();
+        tampered.disclosures.clear();
+        let err = tampered
+            .validate_key_binding(
+                &Hs256,
+                &holder_key,
+                "https://verifier.example.com",
+                "n-0S6_WzA2Mj",
+                max_age,
+                &options,
+            )
+            .unwrap_err();
+        assert_matches!(err, SdJwtError::KeyBindingMismatch("sd_hash"));
+
+        let future_options = TimeOptions::new(Duration::zero(), || {
+            Timestamp::from(Utc::now()) - Duration::try_hours(1).unwrap()
+        });
+        let err = presentation
+            .validate_key_binding(
+                &Hs256,
+                &holder_key,
+                "https://verifier.example.com",
+                "n-0S6_WzA2Mj",
+                max_age,
+                &future_options,
+            )
+            .unwrap_err();
+        assert_matches!(err, SdJwtError::KeyBindingMismatch("iat"));
+
+        let past_options = TimeOptions::new(Duration::zero(), || {
+            Timestamp::from(Utc::now()) + Duration::try_hours(1).unwrap()
+        });
+        let err = presentation
+            .validate_key_binding(
+                &Hs256,
+                &holder_key,
+                "https://verifier.example.com",
+                "n-0S6_WzA2Mj",
+                max_age,
+                &past_options,
+            )
+            .unwrap_err();
+        assert_matches!(err, SdJwtError::KeyBindingMismatch("iat"));
+
+        let err = presentation
+            .presentation(|_| true)
+            .validate_key_binding(
+                &Hs256,
+                &holder_key,
+                "https://verifier.example.com",
+                "n-0S6_WzA2Mj",
+                max_age,
+                &options,
+            )
+            .unwrap_err();
+        assert_matches!(err, SdJwtError::MissingKeyBinding);
+    }
+
+    #[test]
+    fn key_binding_with_extreme_issuance_time() {
+        let holder_key = Hs256Key::new(b"holder_secret_key_donut_steel");
+        let extreme_options = TimeOptions::new(Duration::zero(), || Timestamp::MAX);
+        let presentation = create_sd_jwt()
+            .presentation(|_| true)
+            .with_key_binding(
+                &Hs256,
+                &holder_key,
+                "https://verifier.example.com",
+                "n-0S6_WzA2Mj",
+                &extreme_options,
+            )
+            .unwrap();
+
+        let err = presentation
+            .validate_key_binding(
+                &Hs256,
+                &holder_key,
+                "https://verifier.example.com",
+                "n-0S6_WzA2Mj",
+                Duration::try_minutes(5).unwrap(),
+                &TimeOptions::default(),
+            )
+            .unwrap_err();
+        assert_matches!(err, SdJwtError::KeyBindingMismatch("iat"));
+    }
+}
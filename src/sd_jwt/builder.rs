@@ -0,0 +1,213 @@
+//! Builder for issuer-signed SD-JWTs.
+
+use rand_core::{CryptoRng, RngCore};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use core::mem;
+
+#[cfg(feature = "sign")]
+use super::SdJwt;
+use super::{Disclosure, SdHashAlgorithm, SdJwtError, ARRAY_DIGEST_KEY, DIGESTS_KEY};
+#[cfg(feature = "sign")]
+use crate::{alloc::Cow, Algorithm, AlgorithmExt, Header};
+use crate::{
+    alloc::{String, ToOwned, Vec},
+    jwk::JsonWebKey,
+    Claims, CreationError,
+};
+
+/// Builder of issuer-signed [`SdJwt`]s.
+///
+/// The builder is initialized with the complete set of claims. Claims (object properties
+/// or array elements) can then be made selectively disclosable using [`Self::conceal()`],
+/// which replaces the concealed value in the payload with its digest.
+///
+/// Claims are addressed using [JSON pointers]. If both a nested claim and its parent need
+/// to be concealed, the nested claim must be concealed first (otherwise, its pointer
+/// will not resolve).
+///
+/// [JSON pointers]: https://www.rfc-editor.org/rfc/rfc6901.html
+#[derive(Debug, Clone)]
+pub struct SdJwtBuilder {
+    payload: Map<String, Value>,
+    disclosures: Vec<Disclosure>,
+    hash_algorithm: SdHashAlgorithm,
+    /// Top-level claims that must not be made selectively disclosable.
+    pub(super) protected_claims: &'static [&'static str],
+    /// Token type (`typ`) enforced for the issuer-signed JWT.
+    pub(super) token_type: Option<&'static str>,
+}
+
+impl SdJwtBuilder {
+    /// Creates a builder with the specified claims.
+    pub fn new<T: Serialize>(claims: &Claims<T>) -> Result<Self, SdJwtError> {
+        let payload = serde_json::to_value(claims).map_err(CreationError::Claims)?;
+        let Value::Object(payload) = payload else {
+            return Err(SdJwtError::NonObjectClaims);
+        };
+        Ok(Self {
+            payload,
+            disclosures: Vec::new(),
+            hash_algorithm: SdHashAlgorithm::default(),
+            protected_claims: &[],
+            token_type: None,
+        })
+    }
+
+    /// Sets the hash algorithm used to compute digests of disclosures. Must be called
+    /// before concealing any claims.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any claims were concealed before calling this method.
+    #[must_use]
+    pub fn with_hash_algorithm(mut self, algorithm: SdHashAlgorithm) -> Self {
+        assert!(
+            self.disclosures.is_empty(),
+            "hash algorithm must be set before concealing claims"
+        );
+        self.hash_algorithm = algorithm;
+        self
+    }
+
+    /// Binds the SD-JWT to the holder key by adding the `cnf` claim. This key is used
+    /// to verify key binding JWTs in presentations.
+    pub fn with_holder_key(mut self, key: &JsonWebKey<'_>) -> Result<Self, SdJwtError> {
+        let key = serde_json::to_value(key.to_verifying_key()).map_err(CreationError::Claims)?;
+        let mut confirmation = Map::new();
+        confirmation.insert("jwk".to_owned(), key);
+        self.payload
+            .insert("cnf".to_owned(), Value::Object(confirmation));
+        Ok(self)
+    }
+
+    /// Makes the claim at the specified JSON `pointer` selectively disclosable.
+    pub fn conceal(
+        mut self,
+        pointer: &str,
+        rng: &mut (impl CryptoRng + RngCore),
+    ) -> Result<Self, SdJwtError> {
+        let invalid_path = || SdJwtError::InvalidPath(pointer.to_owned());
+        let (parent_pointer, last_token) = pointer.rsplit_once('/').ok_or_else(invalid_path)?;
+        let last_token = last_token.replace("~1", "/").replace("~0", "~");
+        if parent_pointer.is_empty() && self.protected_claims.contains(&last_token.as_str()) {
+            return Err(invalid_path());
+        }
+
+        let mut root = Value::Object(mem::take(&mut self.payload));
+        let disclosure = conceal_in_value(
+            &mut root,
+            parent_pointer,
+            &last_token,
+            self.hash_algorithm,
+            rng,
+        );
+        let Value::Object(payload) = root else {
+            unreachable!("root is always an object");
+        };
+        self.payload = payload;
+        self.disclosures.push(disclosure.ok_or_else(invalid_path)?);
+        Ok(self)
+    }
+
+    /// Adds the specified number of decoy digests to the top-level `_sd` claim. Decoys hide
+    /// the actual number of selectively disclosable claims.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the claims contain a top-level `_sd` claim that is not an array.
+    #[must_use]
+    pub fn with_decoys(mut self, count: usize, rng: &mut (impl CryptoRng + RngCore)) -> Self {
+        for _ in 0..count {
+            let digest = self.hash_algorithm.decoy_digest(rng);
+            push_digest(&mut self.payload, digest).expect("`_sd` claim is malformed");
+        }
+        self
+    }
+
+    /// Returns the payload of the issuer-signed JWT and the disclosures.
+    pub fn build(mut self) -> (Map<String, Value>, Vec<Disclosure>) {
+        self.payload.insert(
+            "_sd_alg".to_owned(),
+            self.hash_algorithm.name().to_owned().into(),
+        );
+        (self.payload, self.disclosures)
+    }
+
+    /// Signs the SD-JWT using the specified algorithm and key.
+    #[cfg(feature = "sign")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "sign")))]
+    pub fn sign<A: Algorithm>(
+        self,
+        algorithm: &A,
+        header: &Header<impl Serialize + Clone>,
+        signing_key: &A::SigningKey,
+    ) -> Result<SdJwt, SdJwtError> {
+        let header = match self.token_type {
+            Some(token_type) => Cow::Owned(header.clone().with_token_type(token_type)),
+            None => Cow::Borrowed(header),
+        };
+        let (payload, disclosures) = self.build();
+        let jwt = algorithm.token(&header, &Claims::new(payload), signing_key)?;
+        Ok(SdJwt {
+            jwt: jwt.into(),
+            disclosures,
+            key_binding: None,
+        })
+    }
+}
+
+fn conceal_in_value(
+    root: &mut Value,
+    parent_pointer: &str,
+    last_token: &str,
+    hash_algorithm: SdHashAlgorithm,
+    rng: &mut (impl CryptoRng + RngCore),
+) -> Option<Disclosure> {
+    match root.pointer_mut(parent_pointer)? {
+        Value::Object(parent) => conceal_property(parent, last_token, hash_algorithm, rng),
+        Value::Array(parent) => {
+            let index: usize = last_token.parse().ok()?;
+            let element = parent.get_mut(index)?;
+            let disclosure = Disclosure::array_element(element.take(), rng);
+            let mut placeholder = Map::new();
+            placeholder.insert(
+                ARRAY_DIGEST_KEY.to_owned(),
+                disclosure.digest(hash_algorithm).into(),
+            );
+            *element = Value::Object(placeholder);
+            Some(disclosure)
+        }
+        _ => None,
+    }
+}
+
+fn conceal_property(
+    parent: &mut Map<String, Value>,
+    name: &str,
+    hash_algorithm: SdHashAlgorithm,
+    rng: &mut (impl CryptoRng + RngCore),
+) -> Option<Disclosure> {
+    if name == DIGESTS_KEY || name == ARRAY_DIGEST_KEY {
+        return None;
+    }
+    let value = parent.remove(name)?;
+    let disclosure = Disclosure::new(name, value, rng);
+    push_digest(parent, disclosure.digest(hash_algorithm))?;
+    Some(disclosure)
+}
+
+/// Adds a digest to the `_sd` claim of the `object`. Digests are kept sorted so that
+/// they do not reveal the original order of claims.
+fn push_digest(object: &mut Map<String, Value>, digest: String) -> Option<()> {
+    let digests = object
+        .entry(DIGESTS_KEY)
+        .or_insert_with(|| Value::Array(Vec::new()));
+    let Value::Array(digests) = digests else {
+        return None;
+    };
+    digests.push(digest.into());
+    digests.sort_unstable_by(|x, y| x.as_str().cmp(&y.as_str()));
+    Some(())
+}
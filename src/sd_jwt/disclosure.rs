@@ -0,0 +1,246 @@
+//! SD-JWT disclosures and digests.
+
+use base64ct::{Base64UrlUnpadded, Encoding};
+use rand_core::{CryptoRng, RngCore};
+use serde_json::Value;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+
+use core::{fmt, mem};
+
+use super::SdJwtError;
+use crate::alloc::{String, ToOwned, ToString, Vec};
+
+/// Byte length of salts used in disclosures and decoy digests.
+const SALT_LEN: usize = 16;
+
+/// Hash algorithm used to compute digests of [`Disclosure`]s. Corresponds to the `_sd_alg`
+/// claim in the issuer-signed JWT.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SdHashAlgorithm {
+    /// SHA-256 digest (`sha-256`). This is the default algorithm.
+    #[default]
+    Sha256,
+    /// SHA-384 digest (`sha-384`).
+    Sha384,
+    /// SHA-512 digest (`sha-512`).
+    Sha512,
+}
+
+impl SdHashAlgorithm {
+    /// Returns the name of this algorithm as specified in the `_sd_alg` claim.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Sha256 => "sha-256",
+            Self::Sha384 => "sha-384",
+            Self::Sha512 => "sha-512",
+        }
+    }
+
+    pub(crate) fn from_name(name: &str) -> Result<Self, SdJwtError> {
+        Ok(match name {
+            "sha-256" => Self::Sha256,
+            "sha-384" => Self::Sha384,
+            "sha-512" => Self::Sha512,
+            _ => return Err(SdJwtError::UnsupportedHashAlgorithm(name.to_owned())),
+        })
+    }
+
+    /// Computes the base64url-encoded digest of the provided `data`.
+    pub fn digest(self, data: &[u8]) -> String {
+        match self {
+            Self::Sha256 => Base64UrlUnpadded::encode_string(&Sha256::digest(data)),
+            Self::Sha384 => Base64UrlUnpadded::encode_string(&Sha384::digest(data)),
+            Self::Sha512 => Base64UrlUnpadded::encode_string(&Sha512::digest(data)),
+        }
+    }
+
+    pub(crate) fn decoy_digest(self, rng: &mut (impl CryptoRng + RngCore)) -> String {
+        let mut bytes = [0_u8; SALT_LEN];
+        rng.fill_bytes(&mut bytes);
+        self.digest(&bytes)
+    }
+}
+
+impl fmt::Display for SdHashAlgorithm {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.name())
+    }
+}
+
+/// Disclosure of a selectively disclosable claim or array element in an [`SdJwt`](super::SdJwt).
+///
+/// A disclosure is a base64url-encoded JSON array `[salt, claim_name, claim_value]`
+/// for object properties, or `[salt, value]` for array elements.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Disclosure {
+    encoded: String,
+    salt: String,
+    claim_name: Option<String>,
+    value: Value,
+}
+
+impl Disclosure {
+    /// Creates a disclosure for an object property with a random salt.
+    pub fn new(
+        claim_name: impl Into<String>,
+        value: Value,
+        rng: &mut (impl CryptoRng + RngCore),
+    ) -> Self {
+        Self::from_parts(random_salt(rng), Some(claim_name.into()), value)
+    }
+
+    /// Creates a disclosure for an array element with a random salt.
+    pub fn array_element(value: Value, rng: &mut (impl CryptoRng + RngCore)) -> Self {
+        Self::from_parts(random_salt(rng), None, value)
+    }
+
+    fn from_parts(salt: String, claim_name: Option<String>, value: Value) -> Self {
+        let array = if let Some(name) = &claim_name {
+            Value::Array(Vec::from([
+                salt.clone().into(),
+                name.clone().into(),
+                value.clone(),
+            ]))
+        } else {
+            Value::Array(Vec::from([salt.clone().into(), value.clone()]))
+        };
+        let encoded = Base64UrlUnpadded::encode_string(array.to_string().as_bytes());
+        Self {
+            encoded,
+            salt,
+            claim_name,
+            value,
+        }
+    }
+
+    /// Parses a disclosure from its base64url-encoded form.
+    pub fn parse(encoded: &str) -> Result<Self, SdJwtError> {
+        let malformed = |message: &str| SdJwtError::MalformedDisclosure(message.to_owned());
+
+        let bytes = Base64UrlUnpadded::decode_vec(encoded)
+            .map_err(|_| malformed("invalid base64url encoding"))?;
+        let value: Value =
+            serde_json::from_slice(&bytes).map_err(|err| malformed(&err.to_string()))?;
+        let Value::Array(mut items) = value else {
+            return Err(malformed("disclosure is not a JSON array"));
+        };
+
+        let (salt, claim_name, value) = match items.as_mut_slice() {
+            [salt, value] => (salt.take(), None, value.take()),
+            [salt, Value::String(name), value] => {
+                (salt.take(), Some(mem::take(name)), value.take())
+            }
+            [_, _, _] => return Err(malformed("claim name is not a string")),
+            _ => return Err(malformed("disclosure must have 2 or 3 elements")),
+        };
+        let Value::String(salt) = salt else {
+            return Err(malformed("salt is not a string"));
+        };
+
+        Ok(Self {
+            encoded: encoded.to_owned(),
+            salt,
+            claim_name,
+            value,
+        })
+    }
+
+    /// Returns the base64url-encoded form of this disclosure.
+    pub fn as_str(&self) -> &str {
+        &self.encoded
+    }
+
+    /// Returns the salt of this disclosure.
+    pub fn salt(&self) -> &str {
+        &self.salt
+    }
+
+    /// Returns the disclosed claim name, or `None` if this disclosure corresponds
+    /// to an array element.
+    pub fn claim_name(&self) -> Option<&str> {
+        self.claim_name.as_deref()
+    }
+
+    /// Returns the disclosed value.
+    pub fn value(&self) -> &Value {
+        &self.value
+    }
+
+    /// Computes the digest of this disclosure using the specified hash algorithm.
+    pub fn digest(&self, algorithm: SdHashAlgorithm) -> String {
+        algorithm.digest(self.encoded.as_bytes())
+    }
+}
+
+impl fmt::Display for Disclosure {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(&self.encoded)
+    }
+}
+
+fn random_salt(rng: &mut (impl CryptoRng + RngCore)) -> String {
+    let mut salt = [0_u8; SALT_LEN];
+    rng.fill_bytes(&mut salt);
+    Base64UrlUnpadded::encode_string(&salt)
+}
+
+/// Parses `~`-separated disclosures.
+pub(super) fn parse_disclosures<'a>(
+    parts: impl Iterator<Item = &'a str>,
+) -> Result<Vec<Disclosure>, SdJwtError> {
+    parts.map(Disclosure::parse).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsing_reference_disclosure() {
+        // Example from the SD-JWT spec.
+        const DISCLOSURE: &str = "WyI2cU1RdlJMNWhhaiIsICJmYW1pbHlfbmFtZSIsICJNw7ZiaXVzIl0";
+        const DIGEST: &str = "uutlBuYeMDyjLLTpf6Jxi7yNkEF35jdyWMn9U7b_RYY";
+
+        let disclosure = Disclosure::parse(DISCLOSURE).unwrap();
+        assert_eq!(disclosure.salt(), "6qMQvRL5haj");
+        assert_eq!(disclosure.claim_name(), Some("family_name"));
+        assert_eq!(disclosure.value(), "Möbius");
+        assert_eq!(disclosure.digest(SdHashAlgorithm::Sha256), DIGEST);
+    }
+
+    #[test]
+    fn disclosure_roundtrip() {
+        let mut rng = rand::thread_rng();
+        let disclosure = Disclosure::new("email", "alice@example.com".into(), &mut rng);
+        let restored = Disclosure::parse(disclosure.as_str()).unwrap();
+        assert_eq!(restored, disclosure);
+
+        let element = Disclosure::array_element(42.into(), &mut rng);
+        let restored = Disclosure::parse(element.as_str()).unwrap();
+        assert_eq!(restored.claim_name(), None);
+        assert_eq!(restored, element);
+    }
+
+    #[test]
+    fn malformed_disclosures() {
+        let malformed = [
+            "??",
+            // `{}`
+            "e30",
+            // `["salt"]`
+            "WyJzYWx0Il0",
+            // `["salt", 1, 2]`
+            "WyJzYWx0IiwgMSwgMl0",
+            // `[1, 2]`
+            "WzEsIDJd",
+        ];
+        for disclosure in malformed {
+            let err = Disclosure::parse(disclosure).unwrap_err();
+            assert!(
+                matches!(err, SdJwtError::MalformedDisclosure(_)),
+                "{disclosure}: {err}"
+            );
+        }
+    }
+}
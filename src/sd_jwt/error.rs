@@ -0,0 +1,117 @@
+//! Errors related to SD-JWT processing.
+
+use core::fmt;
+
+use crate::{alloc::String, CreationError, ParseError, ValidationError};
+
+/// Errors that can occur when creating, presenting or verifying an [`SdJwt`](super::SdJwt).
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum SdJwtError {
+    /// Combined SD-JWT serialization is malformed. Valid SD-JWTs consist of the issuer-signed JWT,
+    /// zero or more disclosures and an optional key binding JWT, all separated by `~` chars.
+    InvalidFormat,
+    /// Issuer-signed JWT or key binding JWT cannot be parsed.
+    Parse(ParseError),
+    /// Issuer-signed JWT or key binding JWT has failed validation.
+    Validation(ValidationError),
+    /// Issuer-signed JWT or key binding JWT cannot be created.
+    Creation(CreationError),
+    /// Claims cannot be represented as a JSON object.
+    NonObjectClaims,
+    /// Disclosure is malformed.
+    MalformedDisclosure(String),
+    /// Hash algorithm specified in the `_sd_alg` claim is not supported.
+    UnsupportedHashAlgorithm(String),
+    /// JSON pointer to the claim to be made selectively disclosable is invalid, or points
+    /// to a claim that must not be selectively disclosable.
+    InvalidPath(String),
+    /// SD-JWT payload is malformed (e.g., the `_sd` claim is not an array of strings).
+    MalformedPayload(String),
+    /// The same digest is encountered in the payload more than once.
+    DuplicateDigest(String),
+    /// Disclosure is not referenced by any digest in the payload.
+    UnreferencedDisclosure(String),
+    /// Disclosed claim has a reserved name, or overwrites an existing claim.
+    ClaimConflict(String),
+    /// Key binding JWT is required, but is missing.
+    MissingKeyBinding,
+    /// Claim in the key binding JWT has an unexpected value.
+    KeyBindingMismatch(&'static str),
+    /// Verifiable credential has an invalid token type or a missing or invalid claim.
+    InvalidCredential(&'static str),
+    /// Verifiable credential status is not valid (e.g., the credential is revoked or suspended).
+    /// The enclosed value is the status retrieved from the status list.
+    CredentialStatus(u8),
+}
+
+impl fmt::Display for SdJwtError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidFormat => formatter.write_str("invalid SD-JWT structure"),
+            Self::Parse(err) => write!(formatter, "cannot parse JWT: {err}"),
+            Self::Validation(err) => write!(formatter, "JWT validation failed: {err}"),
+            Self::Creation(err) => write!(formatter, "cannot create JWT: {err}"),
+            Self::NonObjectClaims => formatter.write_str("claims are not a JSON object"),
+            Self::MalformedDisclosure(message) => {
+                write!(formatter, "malformed disclosure: {message}")
+            }
+            Self::UnsupportedHashAlgorithm(alg) => {
+                write!(formatter, "unsupported hash algorithm: {alg}")
+            }
+            Self::InvalidPath(path) => write!(formatter, "invalid claim path: {path}"),
+            Self::MalformedPayload(message) => write!(formatter, "malformed payload: {message}"),
+            Self::DuplicateDigest(digest) => {
+                write!(formatter, "digest `{digest}` is encountered more than once")
+            }
+            Self::UnreferencedDisclosure(digest) => {
+                write!(
+                    formatter,
+                    "disclosure with digest `{digest}` is not referenced"
+                )
+            }
+            Self::ClaimConflict(name) => {
+                write!(formatter, "disclosed claim `{name}` conflicts with payload")
+            }
+            Self::MissingKeyBinding => formatter.write_str("key binding JWT is missing"),
+            Self::KeyBindingMismatch(claim) => {
+                write!(formatter, "key binding JWT has unexpected `{claim}` claim")
+            }
+            Self::InvalidCredential(field) => {
+                write!(formatter, "credential has missing or invalid `{field}`")
+            }
+            Self::CredentialStatus(status) => {
+                write!(formatter, "credential status is not valid: 0x{status:02x}")
+            }
+        }
+    }
+}
+
+impl core::error::Error for SdJwtError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::Parse(err) => Some(err),
+            Self::Validation(err) => Some(err),
+            Self::Creation(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<ParseError> for SdJwtError {
+    fn from(err: ParseError) -> Self {
+        Self::Parse(err)
+    }
+}
+
+impl From<ValidationError> for SdJwtError {
+    fn from(err: ValidationError) -> Self {
+        Self::Validation(err)
+    }
+}
+
+impl From<CreationError> for SdJwtError {
+    fn from(err: CreationError) -> Self {
+        Self::Creation(err)
+    }
+}
@@ -0,0 +1,282 @@
+//! [SD-JWT-based verifiable credentials][SD-JWT VC] (SD-JWT VC).
+//!
+//! [SD-JWT VC]: https://datatracker.ietf.org/doc/draft-ietf-oauth-sd-jwt-vc/
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use super::{SdJwt, SdJwtBuilder, SdJwtError};
+use crate::{alloc::String, Algorithm, Claims, TimeOptions, Timestamp, Token};
+
+/// Token type (`typ`) of SD-JWT VC issuer-signed JWTs.
+pub const VC_TOKEN_TYPE: &str = "vc+sd-jwt";
+
+/// Claims that must not be made selectively disclosable in SD-JWT VCs.
+const PROTECTED_CLAIMS: &[&str] = &["iss", "nbf", "exp", "cnf", "vct", "status"];
+
+/// Claims of an SD-JWT VC.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct VcClaims<T> {
+    /// Issuer of the credential (`iss`).
+    #[serde(rename = "iss")]
+    pub issuer: String,
+    /// Type of the credential (`vct`).
+    pub vct: String,
+    /// Subject of the credential (`sub`).
+    #[serde(rename = "sub", default, skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+    /// Information on how to check the status of the credential (`status`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<CredentialStatus>,
+    /// Credential-specific claims.
+    #[serde(flatten)]
+    pub custom: T,
+}
+
+impl<T> VcClaims<T> {
+    /// Creates credential claims with the specified issuer, credential type and custom claims.
+    pub fn new(issuer: impl Into<String>, vct: impl Into<String>, custom: T) -> Self {
+        Self {
+            issuer: issuer.into(),
+            vct: vct.into(),
+            subject: None,
+            status: None,
+            custom,
+        }
+    }
+
+    /// Sets the credential subject.
+    #[must_use]
+    pub fn with_subject(mut self, subject: impl Into<String>) -> Self {
+        self.subject = Some(subject.into());
+        self
+    }
+
+    /// References the credential status in a [status list].
+    ///
+    /// [status list]: https://datatracker.ietf.org/doc/draft-ietf-oauth-status-list/
+    #[must_use]
+    pub fn with_status_list(mut self, uri: impl Into<String>, index: u64) -> Self {
+        self.status = Some(CredentialStatus {
+            status_list: Some(StatusListReference {
+                index,
+                uri: uri.into(),
+            }),
+        });
+        self
+    }
+
+    /// Checks the status of the credential using the provided `lookup` function, which
+    /// should retrieve the status from the referenced status list. If the credential
+    /// does not reference a status list, this check succeeds.
+    ///
+    /// The status value `0x00` means that the credential is valid; other values
+    /// (e.g., `0x01` for revoked and `0x02` for suspended credentials) result in an error.
+    pub fn check_status(
+        &self,
+        lookup: impl FnOnce(&StatusListReference) -> u8,
+    ) -> Result<(), SdJwtError> {
+        let reference = self
+            .status
+            .as_ref()
+            .and_then(|status| status.status_list.as_ref());
+        match reference.map(lookup) {
+            None | Some(0) => Ok(()),
+            Some(status) => Err(SdJwtError::CredentialStatus(status)),
+        }
+    }
+}
+
+/// Status mechanism of a credential (the `status` claim).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct CredentialStatus {
+    /// Reference to a status list.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status_list: Option<StatusListReference>,
+}
+
+/// Reference to the credential status in a [status list].
+///
+/// [status list]: https://datatracker.ietf.org/doc/draft-ietf-oauth-status-list/
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct StatusListReference {
+    /// Index of the credential status in the list (`idx`).
+    #[serde(rename = "idx")]
+    pub index: u64,
+    /// URI of the status list token (`uri`).
+    pub uri: String,
+}
+
+impl SdJwtBuilder {
+    /// Creates a builder for an SD-JWT VC with the specified claims.
+    ///
+    /// The issuer-signed JWT will have `typ` header set to [`VC_TOKEN_TYPE`]. Claims that
+    /// must always be disclosed according to the spec (`iss`, `nbf`, `exp`, `cnf`, `vct`
+    /// and `status`) cannot be concealed.
+    pub fn credential<T: Serialize>(claims: &Claims<VcClaims<T>>) -> Result<Self, SdJwtError> {
+        let mut builder = Self::new(claims)?;
+        builder.protected_claims = PROTECTED_CLAIMS;
+        builder.token_type = Some(VC_TOKEN_TYPE);
+        Ok(builder)
+    }
+}
+
+impl SdJwt {
+    /// Validates this SD-JWT as a verifiable credential.
+    ///
+    /// In addition to [`Self::validate()`], this checks the token type, the presence
+    /// of the `vct` claim, and the `exp` / `nbf` claims if they are present.
+    /// The credential status is not checked; use [`VcClaims::check_status()`] for that.
+    pub fn validate_credential<A, T, F>(
+        &self,
+        algorithm: &A,
+        verifying_key: &A::VerifyingKey,
+        options: &TimeOptions<F>,
+    ) -> Result<Token<VcClaims<T>>, SdJwtError>
+    where
+        A: Algorithm,
+        T: DeserializeOwned,
+        F: Fn() -> Timestamp,
+    {
+        let token = self.validate::<A, VcClaims<T>>(algorithm, verifying_key)?;
+        if token.header().token_type.as_deref() != Some(VC_TOKEN_TYPE) {
+            return Err(SdJwtError::InvalidCredential("typ"));
+        }
+
+        let claims = token.claims();
+        if claims.custom.vct.is_empty() {
+            return Err(SdJwtError::InvalidCredential("vct"));
+        }
+        if claims.expiration.is_some() {
+            claims.validate_expiration(options)?;
+        }
+        if claims.not_before.is_some() {
+            claims.validate_maturity(options)?;
+        }
+        Ok(token)
+    }
+}
+
+#[cfg(all(test, feature = "clock"))]
+mod tests {
+    use assert_matches::assert_matches;
+    use chrono::Duration;
+    use rand::thread_rng;
+
+    use super::*;
+    use crate::{
+        alg::{Hs256, Hs256Key},
+        Header, ValidationError,
+    };
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Diploma {
+        #[serde(default)]
+        degree: Option<String>,
+        #[serde(default)]
+        grade: Option<String>,
+    }
+
+    fn issue_credential(options: &TimeOptions, lifetime: Duration) -> SdJwt {
+        let claims = VcClaims::new(
+            "https://university.example.com",
+            "https://credentials.example.com/diploma",
+            Diploma {
+                degree: Some("MSc".to_owned()),
+                grade: Some("A".to_owned()),
+            },
+        )
+        .with_subject("alice")
+        .with_status_list("https://university.example.com/statuses/1", 42);
+        let claims = Claims::new(claims).set_duration_and_issuance(options, lifetime);
+
+        let key = Hs256Key::new(b"super_secret_key_donut_steel");
+        SdJwtBuilder::credential(&claims)
+            .unwrap()
+            .conceal("/grade", &mut thread_rng())
+            .unwrap()
+            .sign(&Hs256, &Header::empty(), &key)
+            .unwrap()
+    }
+
+    #[test]
+    fn credential_roundtrip() {
+        let options = TimeOptions::default();
+        let credential = issue_credential(&options, Duration::try_days(1).unwrap());
+        let presentation = credential.presentation(|_| false);
+
+        let key = Hs256Key::new(b"super_secret_key_donut_steel");
+        let token = presentation
+            .validate_credential::<_, Diploma, _>(&Hs256, &key, &options)
+            .unwrap();
+        assert_eq!(token.header().token_type.as_deref(), Some(VC_TOKEN_TYPE));
+        let claims = &token.claims().custom;
+        assert_eq!(claims.vct, "https://credentials.example.com/diploma");
+        assert_eq!(claims.subject.as_deref(), Some("alice"));
+        assert_eq!(claims.custom.degree.as_deref(), Some("MSc"));
+        assert_eq!(claims.custom.grade, None);
+
+        claims.check_status(|_| 0).unwrap();
+        let err = claims
+            .check_status(|reference| {
+                assert_eq!(reference.index, 42);
+                1
+            })
+            .unwrap_err();
+        assert_matches!(err, SdJwtError::CredentialStatus(1));
+    }
+
+    #[test]
+    fn expired_credential() {
+        let options = TimeOptions::default();
+        let credential = issue_credential(&options, -Duration::try_days(1).unwrap());
+        let key = Hs256Key::new(b"super_secret_key_donut_steel");
+        let err = credential
+            .validate_credential::<_, Diploma, _>(&Hs256, &key, &options)
+            .unwrap_err();
+        assert_matches!(err, SdJwtError::Validation(ValidationError::Expired));
+    }
+
+    #[test]
+    fn protected_claims_cannot_be_concealed() {
+        let claims = Claims::new(VcClaims::new(
+            "issuer",
+            "type",
+            Diploma {
+                degree: None,
+                grade: None,
+            },
+        ));
+        let builder = SdJwtBuilder::credential(&claims).unwrap();
+        for path in ["/iss", "/vct"] {
+            let err = builder
+                .clone()
+                .conceal(path, &mut thread_rng())
+                .unwrap_err();
+            assert_matches!(err, SdJwtError::InvalidPath(_));
+        }
+    }
+
+    #[test]
+    fn plain_sd_jwt_is_not_credential() {
+        let claims = Claims::new(VcClaims::new(
+            "issuer",
+            "type",
+            Diploma {
+                degree: None,
+                grade: None,
+            },
+        ));
+        let key = Hs256Key::new(b"super_secret_key_donut_steel");
+        let sd_jwt = SdJwtBuilder::new(&claims)
+            .unwrap()
+            .sign(&Hs256, &Header::empty(), &key)
+            .unwrap();
+        let err = sd_jwt
+            .validate_credential::<_, Diploma, _>(&Hs256, &key, &TimeOptions::default())
+            .unwrap_err();
+        assert_matches!(err, SdJwtError::InvalidCredential("typ"));
+    }
+}
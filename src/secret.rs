@@ -0,0 +1,166 @@
+//! Constant-time comparisons and wrappers for secret values.
+
+use serde::{Deserialize, Serialize};
+use subtle::ConstantTimeEq;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use core::{fmt, ops};
+
+use crate::alloc::String;
+
+/// Compares two byte strings in constant time, i.e., the time taken by the comparison depends
+/// only on the lengths of the strings, but not on their contents.
+///
+/// Use this function instead of `==` to compare secrets or values that an attacker
+/// should not be able to guess byte by byte via a timing side channel, such as tokens,
+/// nonces, CSRF states or API keys. Note that the lengths of the compared strings are
+/// **not** hidden; strings with different lengths are considered unequal immediately.
+///
+/// # Examples
+///
+/// ```
+/// # use jwt_compact::constant_time_eq;
+/// let expected_state = "af0ifjsldkj";
+/// assert!(constant_time_eq(expected_state.as_bytes(), b"af0ifjsldkj"));
+/// assert!(!constant_time_eq(expected_state.as_bytes(), b"af0ifjsldkx"));
+/// ```
+pub fn constant_time_eq(lhs: &[u8], rhs: &[u8]) -> bool {
+    lhs.ct_eq(rhs).into()
+}
+
+/// String containing a shared secret, such as an API key or a client secret.
+///
+/// The string is zeroized on drop, is not output by the `Debug` implementation,
+/// and is compared in constant time (see [`constant_time_eq()`]). The secret can be accessed
+/// via [`Self::expose_secret()`].
+///
+/// # Examples
+///
+/// ```
+/// # use jwt_compact::SecretString;
+/// let secret = SecretString::new("correct horse battery staple");
+/// assert_eq!(format!("{secret:?}"), "SecretString(_)");
+/// assert!(secret == *"correct horse battery staple");
+/// assert_eq!(secret.expose_secret().len(), 28);
+/// ```
+#[derive(Clone, Default)]
+pub struct SecretString(String);
+
+impl SecretString {
+    /// Wraps the provided secret.
+    pub fn new(secret: impl Into<String>) -> Self {
+        Self(secret.into())
+    }
+
+    /// Returns the wrapped secret.
+    pub fn expose_secret(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for SecretString {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("SecretString(_)")
+    }
+}
+
+impl From<String> for SecretString {
+    fn from(secret: String) -> Self {
+        Self(secret)
+    }
+}
+
+impl From<&str> for SecretString {
+    fn from(secret: &str) -> Self {
+        Self(secret.into())
+    }
+}
+
+impl PartialEq for SecretString {
+    fn eq(&self, other: &Self) -> bool {
+        constant_time_eq(self.0.as_bytes(), other.0.as_bytes())
+    }
+}
+
+impl Eq for SecretString {}
+
+impl PartialEq<str> for SecretString {
+    fn eq(&self, other: &str) -> bool {
+        constant_time_eq(self.0.as_bytes(), other.as_bytes())
+    }
+}
+
+impl Drop for SecretString {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for SecretString {}
+
+/// Marker for claim fields that should not be output by the `Debug` implementation,
+/// e.g., because they contain secrets or personally identifiable information.
+///
+/// The wrapper is transparent for (de)serialization and dereferences to the wrapped value,
+/// so it can be added to existing claim fields without changing the token format.
+/// Unlike [`SecretString`], the wrapped value is neither zeroized on drop nor compared
+/// in constant time.
+///
+/// # Examples
+///
+/// ```
+/// # use serde::{Deserialize, Serialize};
+/// # use jwt_compact::Redact;
+/// #[derive(Debug, Serialize, Deserialize)]
+/// struct CustomClaims {
+///     #[serde(rename = "sub")]
+///     subject: String,
+///     email: Redact<String>,
+/// }
+///
+/// let claims: CustomClaims = serde_json::from_value(serde_json::json!({
+///     "sub": "alice",
+///     "email": "alice@example.com",
+/// }))?;
+/// assert_eq!(*claims.email, "alice@example.com");
+/// let debug_output = format!("{claims:?}");
+/// assert!(debug_output.contains("email: Redact(_)"));
+/// assert!(!debug_output.contains("@example.com"));
+/// # Ok::<_, serde_json::Error>(())
+/// ```
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Redact<T>(pub T);
+
+impl<T> Redact<T> {
+    /// Returns the wrapped value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> fmt::Debug for Redact<T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("Redact(_)")
+    }
+}
+
+impl<T> From<T> for Redact<T> {
+    fn from(value: T) -> Self {
+        Self(value)
+    }
+}
+
+impl<T> ops::Deref for Redact<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> ops::DerefMut for Redact<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
@@ -0,0 +1,497 @@
+//! [Security Event Tokens][RFC 8417] (SETs).
+//!
+//! SETs convey statements about security events (e.g., an account being disabled)
+//! from an issuer to interested recipients. Unlike access tokens, SETs do not have
+//! a mandatory expiration time; instead, they must have `iat` and `jti` claims, and
+//! should be explicitly typed with the [`SET_TOKEN_TYPE`].
+//!
+//! Payloads of common events defined by the [RISC] and [CAEP] profiles are provided
+//! in the [`risc`] and [`caep`] modules respectively. Custom events can be defined
+//! by implementing the [`SecurityEvent`] trait.
+//!
+//! [RFC 8417]: https://www.rfc-editor.org/rfc/rfc8417.html
+//! [RISC]: https://openid.net/specs/openid-risc-profile-specification-1_0.html
+//! [CAEP]: https://openid.net/specs/openid-caep-specification-1_0.html
+//!
+//! # Examples
+//!
+//! ```
+//! # use jwt_compact::{alg::{Hs256, Hs256Key}, prelude::*};
+//! use jwt_compact::set::{risc, SecurityEventClaims, SecurityEventValidator, SubjectId};
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let key = Hs256Key::new(b"super_secret_key_donut_steel");
+//! let subject = SubjectId::Email { email: "alice@example.com".to_owned() };
+//! let claims = SecurityEventClaims::new("https://idp.example.com", "756E69717565")
+//!     .with_audience("https://rp.example.com")
+//!     .with_event(&risc::AccountDisabled::new(subject))?;
+//! let token = claims.sign(&Hs256, &Header::empty(), &key, &TimeOptions::default())?;
+//!
+//! // Recipient side.
+//! let token = UntrustedToken::new(&token)?;
+//! let token = SecurityEventValidator::new("https://idp.example.com", "https://rp.example.com")
+//!     .validate(&Hs256, &key, &token, &TimeOptions::default())?;
+//! let event = token.claims().custom.event::<risc::AccountDisabled>()?;
+//! assert!(event.is_some());
+//! # Ok(())
+//! # }
+//! ```
+
+use chrono::Duration;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+
+use core::fmt;
+
+pub mod caep;
+pub mod risc;
+
+use crate::{
+    alloc::{BTreeMap, String, ToOwned, Vec},
+    claims::serde_audience,
+    Algorithm, AlgorithmExt, CreationError, TimeOptions, Timestamp, Token, UntrustedToken,
+    ValidationError,
+};
+#[cfg(feature = "sign")]
+use crate::{Claims, Header, TokenString};
+
+/// Token type (`typ`) of security event tokens.
+pub const SET_TOKEN_TYPE: &str = "secevent+jwt";
+
+/// Payload of a security event that can be included into the `events` claim of a SET.
+pub trait SecurityEvent: Serialize + DeserializeOwned {
+    /// URI identifying the event type. Used as a key in the `events` claim.
+    const EVENT_TYPE: &'static str;
+}
+
+/// Identifier of the subject of a security event as per [RFC 9493].
+///
+/// [RFC 9493]: https://www.rfc-editor.org/rfc/rfc9493.html
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "format", rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum SubjectId {
+    /// Account at a service provider identified by a URI.
+    Account {
+        /// `acct` URI of the account.
+        uri: String,
+    },
+    /// Email address.
+    Email {
+        /// Email address of the subject.
+        email: String,
+    },
+    /// Issuer and subject pair, as used in JWT claims.
+    IssSub {
+        /// Issuer.
+        iss: String,
+        /// Subject.
+        sub: String,
+    },
+    /// Opaque identifier.
+    Opaque {
+        /// Identifier of the subject.
+        id: String,
+    },
+    /// Phone number in the E.164 format.
+    PhoneNumber {
+        /// Phone number of the subject.
+        phone_number: String,
+    },
+}
+
+/// Claims of a security event token.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct SecurityEventClaims {
+    /// Issuer of the token (`iss`).
+    #[serde(rename = "iss")]
+    pub issuer: String,
+    /// Unique identifier of the token (`jti`).
+    #[serde(rename = "jti")]
+    pub jwt_id: String,
+    /// Intended recipients of the token (`aud`).
+    #[serde(
+        rename = "aud",
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        with = "serde_audience"
+    )]
+    pub audience: Vec<String>,
+    /// Subject of the token (`sub`).
+    #[serde(rename = "sub", default, skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+    /// Transaction identifier (`txn`) correlating SETs related to the same transaction.
+    #[serde(rename = "txn", default, skip_serializing_if = "Option::is_none")]
+    pub transaction_id: Option<String>,
+    /// Time of the event (`toe`).
+    #[serde(rename = "toe", default, skip_serializing_if = "Option::is_none")]
+    pub time_of_event: Option<Timestamp>,
+    /// Security events keyed by the event type URI.
+    pub events: BTreeMap<String, Value>,
+}
+
+impl SecurityEventClaims {
+    /// Creates claims with the specified issuer and token ID, and no events.
+    pub fn new(issuer: impl Into<String>, jwt_id: impl Into<String>) -> Self {
+        Self {
+            issuer: issuer.into(),
+            jwt_id: jwt_id.into(),
+            audience: Vec::new(),
+            subject: None,
+            transaction_id: None,
+            time_of_event: None,
+            events: BTreeMap::new(),
+        }
+    }
+
+    /// Adds an intended recipient of the token.
+    #[must_use]
+    pub fn with_audience(mut self, audience: impl Into<String>) -> Self {
+        self.audience.push(audience.into());
+        self
+    }
+
+    /// Sets the token subject.
+    #[must_use]
+    pub fn with_subject(mut self, subject: impl Into<String>) -> Self {
+        self.subject = Some(subject.into());
+        self
+    }
+
+    /// Sets the transaction identifier.
+    #[must_use]
+    pub fn with_transaction_id(mut self, transaction_id: impl Into<String>) -> Self {
+        self.transaction_id = Some(transaction_id.into());
+        self
+    }
+
+    /// Sets the time of the event.
+    #[must_use]
+    pub fn with_time_of_event(mut self, time_of_event: impl Into<Timestamp>) -> Self {
+        self.time_of_event = Some(time_of_event.into());
+        self
+    }
+
+    /// Adds an event to the `events` claim.
+    pub fn with_event<E: SecurityEvent>(mut self, event: &E) -> Result<Self, CreationError> {
+        let payload = serde_json::to_value(event).map_err(CreationError::Claims)?;
+        self.events.insert(E::EVENT_TYPE.to_owned(), payload);
+        Ok(self)
+    }
+
+    /// Returns the event of the specified type, or `None` if the event is not present.
+    pub fn event<E: SecurityEvent>(&self) -> Result<Option<E>, ValidationError> {
+        let Some(payload) = self.events.get(E::EVENT_TYPE) else {
+            return Ok(None);
+        };
+        E::deserialize(payload)
+            .map(Some)
+            .map_err(ValidationError::MalformedClaims)
+    }
+
+    /// Signs a token with these claims. The `iat` claim is set to the current time
+    /// according to `options`, and the `typ` header field is set to [`SET_TOKEN_TYPE`].
+    #[cfg(feature = "sign")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "sign")))]
+    pub fn sign<A: Algorithm, F>(
+        &self,
+        algorithm: &A,
+        header: &Header<impl Serialize + Clone>,
+        signing_key: &A::SigningKey,
+        options: &TimeOptions<F>,
+    ) -> Result<TokenString, CreationError>
+    where
+        F: Fn() -> Timestamp,
+    {
+        let header = header.clone().with_token_type(SET_TOKEN_TYPE);
+        let mut claims = Claims::new(self);
+        claims.issued_at = Some((options.clock_fn)());
+        algorithm.token(&header, &claims, signing_key)
+    }
+}
+
+/// Validator of security event tokens.
+///
+/// Besides the signature, the validator checks that:
+///
+/// - The `typ` header field is equal to [`SET_TOKEN_TYPE`].
+/// - The `iss` claim matches the expected issuer, and the `aud` claim contains
+///   the expected audience.
+/// - The `iat` claim is present and is not in the future. If [the maximum age](Self::with_max_age())
+///   is set, the token must not be older than it.
+/// - The `jti` claim is not empty, and the `events` claim contains at least one event.
+/// - If the `exp` claim is present, the token is not expired.
+#[derive(Debug, Clone)]
+pub struct SecurityEventValidator {
+    issuer: String,
+    audience: String,
+    max_age: Option<Duration>,
+}
+
+impl SecurityEventValidator {
+    /// Creates a validator with the expected issuer and audience.
+    pub fn new(issuer: impl Into<String>, audience: impl Into<String>) -> Self {
+        Self {
+            issuer: issuer.into(),
+            audience: audience.into(),
+            max_age: None,
+        }
+    }
+
+    /// Sets the maximum age of tokens as determined by the `iat` claim.
+    #[must_use]
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Validates the token.
+    pub fn validate<A: Algorithm, F>(
+        &self,
+        algorithm: &A,
+        verifying_key: &A::VerifyingKey,
+        token: &UntrustedToken<'_>,
+        options: &TimeOptions<F>,
+    ) -> Result<Token<SecurityEventClaims>, SecurityEventError>
+    where
+        F: Fn() -> Timestamp,
+    {
+        if token.header().token_type.as_deref() != Some(SET_TOKEN_TYPE) {
+            return Err(SecurityEventError::InvalidTokenType);
+        }
+        let token = algorithm
+            .validator::<SecurityEventClaims>(verifying_key)
+            .validate(token)?;
+        let claims = token.claims();
+
+        let issued_at = claims
+            .issued_at
+            .ok_or(SecurityEventError::MissingClaim("iat"))?;
+        let now = (options.clock_fn)();
+        let is_issued = issued_at
+            .checked_sub_signed(options.leeway)
+            .is_some_and(|issued_at| issued_at <= now);
+        if !is_issued {
+            return Err(SecurityEventError::NotYetIssued);
+        }
+        if let Some(max_age) = self.max_age {
+            let is_fresh = issued_at
+                .checked_add_signed(max_age)
+                .and_then(|expiration| expiration.checked_add_signed(options.leeway))
+                .is_some_and(|expiration| expiration >= now);
+            if !is_fresh {
+                return Err(SecurityEventError::TooOld);
+            }
+        }
+        if claims.expiration.is_some() {
+            claims.validate_expiration(options)?;
+        }
+
+        let custom = &claims.custom;
+        if custom.jwt_id.is_empty() {
+            return Err(SecurityEventError::MissingClaim("jti"));
+        }
+        if custom.events.is_empty() {
+            return Err(SecurityEventError::MissingClaim("events"));
+        }
+        if custom.issuer != self.issuer {
+            return Err(SecurityEventError::ClaimMismatch("iss"));
+        }
+        if !custom.audience.contains(&self.audience) {
+            return Err(SecurityEventError::ClaimMismatch("aud"));
+        }
+        Ok(token)
+    }
+}
+
+/// Errors that can occur during [`SecurityEventValidator::validate()`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum SecurityEventError {
+    /// Token has failed generic validation (e.g., has an invalid signature).
+    Validation(ValidationError),
+    /// The `typ` header field is missing or is not equal to [`SET_TOKEN_TYPE`].
+    InvalidTokenType,
+    /// Required claim is missing or empty.
+    MissingClaim(&'static str),
+    /// Claim has an unexpected value.
+    ClaimMismatch(&'static str),
+    /// Token is issued in the future.
+    NotYetIssued,
+    /// Token is older than the maximum age.
+    TooOld,
+}
+
+impl fmt::Display for SecurityEventError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Validation(err) => write!(formatter, "token validation failed: {err}"),
+            Self::InvalidTokenType => formatter.write_str("token is not a security event token"),
+            Self::MissingClaim(claim) => write!(formatter, "claim `{claim}` is missing"),
+            Self::ClaimMismatch(claim) => write!(formatter, "claim `{claim}` has unexpected value"),
+            Self::NotYetIssued => formatter.write_str("token is issued in the future"),
+            Self::TooOld => formatter.write_str("token is too old"),
+        }
+    }
+}
+
+impl core::error::Error for SecurityEventError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::Validation(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<ValidationError> for SecurityEventError {
+    fn from(err: ValidationError) -> Self {
+        Self::Validation(err)
+    }
+}
+
+#[cfg(all(test, feature = "clock"))]
+mod tests {
+    use assert_matches::assert_matches;
+    use chrono::Utc;
+    use serde_json::json;
+
+    use super::*;
+    use crate::{alg::Hs256, test_helpers::key};
+
+    const ISSUER: &str = "https://idp.example.com";
+    const AUDIENCE: &str = "https://rp.example.com";
+
+    fn sample_claims() -> SecurityEventClaims {
+        let subject = SubjectId::IssSub {
+            iss: ISSUER.to_owned(),
+            sub: "alice".to_owned(),
+        };
+        SecurityEventClaims::new(ISSUER, "756E69717565")
+            .with_audience(AUDIENCE)
+            .with_event(
+                &risc::AccountDisabled::new(subject)
+                    .with_reason(risc::AccountDisabledReason::Hijacking),
+            )
+            .unwrap()
+    }
+
+    #[test]
+    fn claims_serialization() {
+        let claims = sample_claims().with_transaction_id("txn");
+        let json = serde_json::to_value(&claims).unwrap();
+        assert_eq!(
+            json,
+            json!({
+                "iss": ISSUER,
+                "jti": "756E69717565",
+                "aud": AUDIENCE,
+                "txn": "txn",
+                "events": {
+                    "https://schemas.openid.net/secevent/risc/event-type/account-disabled": {
+                        "subject": { "format": "iss_sub", "iss": ISSUER, "sub": "alice" },
+                        "reason": "hijacking",
+                    },
+                },
+            })
+        );
+
+        let restored: SecurityEventClaims = serde_json::from_value(json).unwrap();
+        assert_eq!(restored, claims);
+        let claims = claims.with_audience("https://other.example.com");
+        let json = serde_json::to_value(&claims).unwrap();
+        assert_eq!(json["aud"], json!([AUDIENCE, "https://other.example.com"]));
+    }
+
+    #[test]
+    fn token_roundtrip() {
+        let options = TimeOptions::default();
+        let token = sample_claims()
+            .sign(&Hs256, &Header::empty(), &key(), &options)
+            .unwrap();
+        let token = UntrustedToken::new(&token).unwrap();
+        assert_eq!(token.header().token_type.as_deref(), Some(SET_TOKEN_TYPE));
+
+        let token = SecurityEventValidator::new(ISSUER, AUDIENCE)
+            .with_max_age(Duration::try_minutes(5).unwrap())
+            .validate(&Hs256, &key(), &token, &options)
+            .unwrap();
+        let event = token
+            .claims()
+            .custom
+            .event::<risc::AccountDisabled>()
+            .unwrap()
+            .unwrap();
+        assert_eq!(event.reason, Some(risc::AccountDisabledReason::Hijacking));
+        let missing_event = token.claims().custom.event::<risc::AccountPurged>();
+        assert_matches!(missing_event, Ok(None));
+    }
+
+    #[test]
+    fn validation_errors() {
+        let options = TimeOptions::default();
+        let token = sample_claims()
+            .sign(&Hs256, &Header::empty(), &key(), &options)
+            .unwrap();
+        let token = UntrustedToken::new(&token).unwrap();
+
+        let err = SecurityEventValidator::new("https://evil.example.com", AUDIENCE)
+            .validate(&Hs256, &key(), &token, &options)
+            .unwrap_err();
+        assert_matches!(err, SecurityEventError::ClaimMismatch("iss"));
+        let err = SecurityEventValidator::new(ISSUER, "https://evil.example.com")
+            .validate(&Hs256, &key(), &token, &options)
+            .unwrap_err();
+        assert_matches!(err, SecurityEventError::ClaimMismatch("aud"));
+
+        let late_options = TimeOptions::new(Duration::zero(), || {
+            Timestamp::from(Utc::now()) + Duration::try_hours(1).unwrap()
+        });
+        let err = SecurityEventValidator::new(ISSUER, AUDIENCE)
+            .with_max_age(Duration::try_minutes(5).unwrap())
+            .validate(&Hs256, &key(), &token, &late_options)
+            .unwrap_err();
+        assert_matches!(err, SecurityEventError::TooOld);
+
+        let extreme_options = TimeOptions::new(Duration::zero(), || Timestamp::MAX);
+        let extreme = sample_claims()
+            .sign(&Hs256, &Header::empty(), &key(), &extreme_options)
+            .unwrap();
+        let extreme = UntrustedToken::new(&extreme).unwrap();
+        let err = SecurityEventValidator::new(ISSUER, AUDIENCE)
+            .with_max_age(Duration::try_minutes(5).unwrap())
+            .validate(&Hs256, &key(), &extreme, &extreme_options)
+            .unwrap_err();
+        assert_matches!(err, SecurityEventError::TooOld);
+
+        let untyped = Hs256
+            .token(&Header::empty(), &Claims::new(sample_claims()), &key())
+            .unwrap();
+        let untyped = UntrustedToken::new(&untyped).unwrap();
+        let err = SecurityEventValidator::new(ISSUER, AUDIENCE)
+            .validate(&Hs256, &key(), &untyped, &options)
+            .unwrap_err();
+        assert_matches!(err, SecurityEventError::InvalidTokenType);
+
+        let header = Header::empty().with_token_type(SET_TOKEN_TYPE);
+        let no_iat = Hs256
+            .token(&header, &Claims::new(sample_claims()), &key())
+            .unwrap();
+        let no_iat = UntrustedToken::new(&no_iat).unwrap();
+        let err = SecurityEventValidator::new(ISSUER, AUDIENCE)
+            .validate(&Hs256, &key(), &no_iat, &options)
+            .unwrap_err();
+        assert_matches!(err, SecurityEventError::MissingClaim("iat"));
+
+        let no_events = SecurityEventClaims::new(ISSUER, "id").with_audience(AUDIENCE);
+        let no_events = no_events
+            .sign(&Hs256, &Header::empty(), &key(), &options)
+            .unwrap();
+        let no_events = UntrustedToken::new(&no_events).unwrap();
+        let err = SecurityEventValidator::new(ISSUER, AUDIENCE)
+            .validate(&Hs256, &key(), &no_events, &options)
+            .unwrap_err();
+        assert_matches!(err, SecurityEventError::MissingClaim("events"));
+    }
+}
@@ -0,0 +1,165 @@
+//! Security events defined by the [CAEP specification].
+//!
+//! [CAEP specification]: https://openid.net/specs/openid-caep-specification-1_0.html
+
+use serde::{Deserialize, Serialize};
+
+use super::{SecurityEvent, SubjectId};
+use crate::{alloc::String, Timestamp};
+
+/// Entity that has initiated a CAEP event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum InitiatingEntity {
+    /// Administrative action.
+    Admin,
+    /// End-user action.
+    User,
+    /// Policy evaluation.
+    Policy,
+    /// System or platform action.
+    System,
+}
+
+/// Optional metadata shared by all CAEP events.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct EventMetadata {
+    /// Time when the event has occurred (`event_timestamp`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event_timestamp: Option<Timestamp>,
+    /// Entity that has initiated the event (`initiating_entity`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub initiating_entity: Option<InitiatingEntity>,
+}
+
+impl EventMetadata {
+    /// Sets the time of the event.
+    #[must_use]
+    pub fn with_timestamp(mut self, timestamp: impl Into<Timestamp>) -> Self {
+        self.event_timestamp = Some(timestamp.into());
+        self
+    }
+
+    /// Sets the entity that has initiated the event.
+    #[must_use]
+    pub fn with_initiating_entity(mut self, entity: InitiatingEntity) -> Self {
+        self.initiating_entity = Some(entity);
+        self
+    }
+}
+
+/// Session of the subject has been revoked.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct SessionRevoked {
+    /// Subject of the event.
+    pub subject: SubjectId,
+    /// Event metadata.
+    #[serde(flatten)]
+    pub metadata: EventMetadata,
+}
+
+impl SessionRevoked {
+    /// Creates an event for the specified subject.
+    pub fn new(subject: SubjectId, metadata: EventMetadata) -> Self {
+        Self { subject, metadata }
+    }
+}
+
+impl SecurityEvent for SessionRevoked {
+    const EVENT_TYPE: &'static str =
+        "https://schemas.openid.net/secevent/caep/event-type/session-revoked";
+}
+
+/// Type of change of a credential.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum CredentialChangeType {
+    /// Credential has been created.
+    Create,
+    /// Credential has been revoked.
+    Revoke,
+    /// Credential has been updated.
+    Update,
+    /// Credential has been deleted.
+    Delete,
+}
+
+/// Credential of the subject has been created, changed, revoked or deleted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct CredentialChange {
+    /// Subject of the event.
+    pub subject: SubjectId,
+    /// Type of the credential (e.g., `password` or `fido2-roaming`).
+    pub credential_type: String,
+    /// Type of the change.
+    pub change_type: CredentialChangeType,
+    /// Event metadata.
+    #[serde(flatten)]
+    pub metadata: EventMetadata,
+}
+
+impl CredentialChange {
+    /// Creates an event for the specified subject.
+    pub fn new(
+        subject: SubjectId,
+        credential_type: impl Into<String>,
+        change_type: CredentialChangeType,
+        metadata: EventMetadata,
+    ) -> Self {
+        Self {
+            subject,
+            credential_type: credential_type.into(),
+            change_type,
+            metadata,
+        }
+    }
+}
+
+impl SecurityEvent for CredentialChange {
+    const EVENT_TYPE: &'static str =
+        "https://schemas.openid.net/secevent/caep/event-type/credential-change";
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::alloc::ToOwned;
+
+    #[test]
+    fn credential_change_serialization() {
+        let subject = SubjectId::Email {
+            email: "alice@example.com".to_owned(),
+        };
+        let timestamp = Timestamp::from_secs(1_615_304_991);
+        let metadata = EventMetadata::default()
+            .with_timestamp(timestamp)
+            .with_initiating_entity(InitiatingEntity::User);
+        let event = CredentialChange::new(
+            subject,
+            "fido2-roaming",
+            CredentialChangeType::Create,
+            metadata,
+        );
+
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(
+            json,
+            json!({
+                "subject": { "format": "email", "email": "alice@example.com" },
+                "credential_type": "fido2-roaming",
+                "change_type": "create",
+                "event_timestamp": 1_615_304_991,
+                "initiating_entity": "user",
+            })
+        );
+        let restored: CredentialChange = serde_json::from_value(json).unwrap();
+        assert_eq!(restored, event);
+    }
+}
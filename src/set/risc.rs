@@ -0,0 +1,138 @@
+//! Security events defined by the [RISC profile].
+//!
+//! [RISC profile]: https://openid.net/specs/openid-risc-profile-specification-1_0.html
+
+use serde::{Deserialize, Serialize};
+
+use super::{SecurityEvent, SubjectId};
+use crate::alloc::String;
+
+/// Reason for disabling an account.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum AccountDisabledReason {
+    /// Account was disabled because it may have been hijacked.
+    #[serde(rename = "hijacking")]
+    Hijacking,
+    /// Account was disabled because it is a part of a bulk account creation.
+    #[serde(rename = "bulk-account")]
+    BulkAccount,
+}
+
+/// Account has been disabled.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct AccountDisabled {
+    /// Subject of the event.
+    pub subject: SubjectId,
+    /// Reason for disabling the account.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<AccountDisabledReason>,
+}
+
+impl AccountDisabled {
+    /// Creates an event for the specified subject.
+    pub fn new(subject: SubjectId) -> Self {
+        Self {
+            subject,
+            reason: None,
+        }
+    }
+
+    /// Sets the reason for disabling the account.
+    #[must_use]
+    pub fn with_reason(mut self, reason: AccountDisabledReason) -> Self {
+        self.reason = Some(reason);
+        self
+    }
+}
+
+impl SecurityEvent for AccountDisabled {
+    const EVENT_TYPE: &'static str =
+        "https://schemas.openid.net/secevent/risc/event-type/account-disabled";
+}
+
+/// Account has been enabled.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct AccountEnabled {
+    /// Subject of the event.
+    pub subject: SubjectId,
+}
+
+impl AccountEnabled {
+    /// Creates an event for the specified subject.
+    pub fn new(subject: SubjectId) -> Self {
+        Self { subject }
+    }
+}
+
+impl SecurityEvent for AccountEnabled {
+    const EVENT_TYPE: &'static str =
+        "https://schemas.openid.net/secevent/risc/event-type/account-enabled";
+}
+
+/// Account has been permanently deleted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct AccountPurged {
+    /// Subject of the event.
+    pub subject: SubjectId,
+}
+
+impl AccountPurged {
+    /// Creates an event for the specified subject.
+    pub fn new(subject: SubjectId) -> Self {
+        Self { subject }
+    }
+}
+
+impl SecurityEvent for AccountPurged {
+    const EVENT_TYPE: &'static str =
+        "https://schemas.openid.net/secevent/risc/event-type/account-purged";
+}
+
+/// All sessions of the account have been revoked.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct SessionsRevoked {
+    /// Subject of the event.
+    pub subject: SubjectId,
+}
+
+impl SessionsRevoked {
+    /// Creates an event for the specified subject.
+    pub fn new(subject: SubjectId) -> Self {
+        Self { subject }
+    }
+}
+
+impl SecurityEvent for SessionsRevoked {
+    const EVENT_TYPE: &'static str =
+        "https://schemas.openid.net/secevent/risc/event-type/sessions-revoked";
+}
+
+/// Credential of the account has been compromised.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct CredentialCompromise {
+    /// Subject of the event.
+    pub subject: SubjectId,
+    /// Type of the compromised credential (e.g., `password`).
+    pub credential_type: String,
+}
+
+impl CredentialCompromise {
+    /// Creates an event for the specified subject and credential type.
+    pub fn new(subject: SubjectId, credential_type: impl Into<String>) -> Self {
+        Self {
+            subject,
+            credential_type: credential_type.into(),
+        }
+    }
+}
+
+impl SecurityEvent for CredentialCompromise {
+    const EVENT_TYPE: &'static str =
+        "https://schemas.openid.net/secevent/risc/event-type/credential-compromise";
+}
@@ -0,0 +1,36 @@
+//! Fixtures shared by unit tests.
+
+#[cfg(all(feature = "sign", feature = "clock"))]
+use chrono::Duration;
+#[cfg(all(feature = "sign", feature = "clock"))]
+use serde::Serialize;
+
+use crate::alg::Hs256Key;
+#[cfg(all(feature = "sign", feature = "clock"))]
+use crate::{alloc::String, AlgorithmExt, Claims, Header, TimeOptions};
+
+/// Returns the secret key used to sign and verify tokens in tests.
+pub(crate) fn key() -> Hs256Key {
+    Hs256Key::new(b"super_secret_key_donut_steel")
+}
+
+/// Signs `claims` with [`key()`] using `HS256`. The token is issued now and expires
+/// in 5 minutes.
+#[cfg(all(feature = "sign", feature = "clock"))]
+pub(crate) fn sign(claims: &impl Serialize, header: &Header) -> String {
+    sign_with_lifetime(claims, header, Duration::try_minutes(5).unwrap())
+}
+
+/// Same as [`sign()`], but with the specified token `lifetime`.
+#[cfg(all(feature = "sign", feature = "clock"))]
+pub(crate) fn sign_with_lifetime(
+    claims: &impl Serialize,
+    header: &Header,
+    lifetime: Duration,
+) -> String {
+    let claims = Claims::new(claims).set_duration_and_issuance(&TimeOptions::default(), lifetime);
+    crate::alg::Hs256
+        .token(header, &claims, &key())
+        .unwrap()
+        .into()
+}
@@ -0,0 +1,405 @@
+//! Test vectors from [RFC 7515] and the [JOSE cookbook (RFC 7520)] for conformance checks.
+//!
+//! Each [`JwsTestVector`] contains a token in the compact serialization, its decoded payload
+//! and the key used to sign it (in the JWK format, including private parameters).
+//! Implementations of [`Algorithm`] (e.g., backed by custom key stores or hardware)
+//! can be checked against the vectors with [`JwsTestVector::check_verifier()`]
+//! and [`JwsTestVector::check_signer()`].
+//!
+//! Only vectors for algorithms supported by the crate and representable in the compact
+//! serialization are included. In particular, `ES512` (RFC 7515, Appendix A.4), unsecured tokens
+//! (Appendix A.5) and cookbook vectors using the JSON serialization or detached payloads
+//! are omitted.
+//!
+//! Payloads of the cookbook vectors are not JSON, so the vectors should be verified
+//! without deserializing claims (e.g., via [`Validator::verify_signature()`]).
+//!
+//! [RFC 7515]: https://www.rfc-editor.org/rfc/rfc7515.html#appendix-A
+//! [JOSE cookbook (RFC 7520)]: https://www.rfc-editor.org/rfc/rfc7520.html#section-4
+//! [`Validator::verify_signature()`]: crate::Validator::verify_signature()
+//!
+//! # Examples
+//!
+//! ```
+//! # use jwt_compact::{alg::{Hs256, Hs256Key}, test_vectors};
+//! # fn main() -> anyhow::Result<()> {
+//! let vectors = test_vectors::ALL.iter().filter(|vector| vector.algorithm == "HS256");
+//! for vector in vectors {
+//!     let key = Hs256Key::try_from(&vector.jwk())?;
+//!     vector.check_verifier(&Hs256, &key)?;
+//!     vector.check_signer(&Hs256, &key, &key)?;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use base64ct::{Base64UrlUnpadded, Encoding};
+
+use core::fmt;
+
+use crate::{
+    alloc::{String, ToString, Vec},
+    jwk::JsonWebKey,
+    Algorithm, AlgorithmSignature,
+};
+
+/// Payload of the RFC 7515 vectors.
+const RFC7515_PAYLOAD: &[u8] =
+    b"{\"iss\":\"joe\",\r\n \"exp\":1300819380,\r\n \"http://example.com/is_root\":true}";
+
+/// Payload of the RFC 7520 vectors.
+const RFC7520_PAYLOAD: &[u8] = "It\u{2019}s a dangerous business, Frodo, going out your door. \
+    You step onto the road, and if you don't keep your feet, there\u{2019}s no knowing where \
+    you might be swept off to."
+    .as_bytes();
+
+/// `HS256` token from [RFC 7515, Appendix A.1](https://www.rfc-editor.org/rfc/rfc7515.html#appendix-A.1).
+pub const RFC7515_HS256: JwsTestVector = JwsTestVector {
+    name: "RFC 7515, Appendix A.1",
+    algorithm: "HS256",
+    key: r#"{
+        "kty": "oct",
+        "k": "AyM1SysPpbyDfgZld3umj1qzKObwVMkoqQ-EstJQLr_T-1qS0gZH75aKtMN3Yj0iPS4hcgUuTwjAzZr1Z9CAow"
+    }"#,
+    token: "eyJ0eXAiOiJKV1QiLA0KICJhbGciOiJIUzI1NiJ9.\
+        eyJpc3MiOiJqb2UiLA0KICJleHAiOjEzMDA4MTkzODAsDQogImh0dHA6Ly9leGFtcGxlLmNvbS9pc19yb290Ijp0cnVlfQ.\
+        dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk",
+    payload: RFC7515_PAYLOAD,
+    is_deterministic: true,
+};
+
+/// `RS256` token from [RFC 7515, Appendix A.2](https://www.rfc-editor.org/rfc/rfc7515.html#appendix-A.2).
+pub const RFC7515_RS256: JwsTestVector = JwsTestVector {
+    name: "RFC 7515, Appendix A.2",
+    algorithm: "RS256",
+    key: r#"{
+        "kty": "RSA",
+        "n": "ofgWCuLjybRlzo0tZWJjNiuSfb4p4fAkd_wWJcyQoTbji9k0l8W26mPddxHmfHQp-Vaw-4qPCJrcS2mJPMEzP1Pt0Bm4d4QlL-yRT-SFd2lZS-pCgNMsD1W_YpRPEwOWvG6b32690r2jZ47soMZo9wGzjb_7OMg0LOL-bSf63kpaSHSXndS5z5rexMdbBYUsLA9e-KXBdQOS-UTo7WTBEMa2R2CapHg665xsmtdVMTBQY4uDZlxvb3qCo5ZwKh9kG4LT6_I5IhlJH7aGhyxXFvUK-DWNmoudF8NAco9_h9iaGNj8q2ethFkMLs91kzk2PAcDTW9gb54h4FRWyuXpoQ",
+        "e": "AQAB",
+        "d": "Eq5xpGnNCivDflJsRQBXHx1hdR1k6Ulwe2JZD50LpXyWPEAeP88vLNO97IjlA7_GQ5sLKMgvfTeXZx9SE-7YwVol2NXOoAJe46sui395IW_GO-pWJ1O0BkTGoVEn2bKVRUCgu-GjBVaYLU6f3l9kJfFNS3E0QbVdxzubSu3Mkqzjkn439X0M_V51gfpRLI9JYanrC4D4qAdGcopV_0ZHHzQlBjudU2QvXt4ehNYTCBr6XCLQUShb1juUO1ZdiYoFaFQT5Tw8bGUl_x_jTj3ccPDVZFD9pIuhLhBOneufuBiB4cS98l2SR_RQyGWSeWjnczT0QU91p1DhOVRuOopznQ",
+        "p": "4BzEEOtIpmVdVEZNCqS7baC4crd0pqnRH_5IB3jw3bcxGn6QLvnEtfdUdiYrqBdss1l58BQ3KhooKeQTa9AB0Hw_Py5PJdTJNPY8cQn7ouZ2KKDcmnPGBY5t7yLc1QlQ5xHdwW1VhvKn-nXqhJTBgIPgtldC-KDV5z-y2XDwGUc",
+        "q": "uQPEfgmVtjL0Uyyx88GZFF1fOunH3-7cepKmtH4pxhtCoHqpWmT8YAmZxaewHgHAjLYsp1ZSe7zFYHj7C6ul7TjeLQeZD_YwD66t62wDmpe_HlB-TnBA-njbglfIsRLtXlnDzQkv5dTltRJ11BKBBypeeF6689rjcJIDEz9RWdc",
+        "dp": "BwKfV3Akq5_MFZDFZCnW-wzl-CCo83WoZvnLQwCTeDv8uzluRSnm71I3QCLdhrqE2e9YkxvuxdBfpT_PI7Yz-FOKnu1R6HsJeDCjn12Sk3vmAktV2zb34MCdy7cpdTh_YVr7tss2u6vneTwrA86rZtu5Mbr1C1XsmvkxHQAdYo0",
+        "dq": "h_96-mK1R_7glhsum81dZxjTnYynPbZpHziZjeeHcXYsXaaMwkOlODsWa7I9xXDoRwbKgB719rrmI2oKr6N3Do9U0ajaHF-NKJnwgjMd2w9cjz3_-kyNlxAr2v4IKhGNpmM5iIgOS1VZnOZ68m6_pbLBSp3nssTdlqvd0tIiTHU",
+        "qi": "IYd7DHOhrWvxkwPQsRM2tOgrjbcrfvtQJipd-DlcxyVuuM9sQLdgjVk2oy26F0EmpScGLq2MowX7fhd_QJQ3ydy5cY7YIBi87w93IKLEdfnbJtoOPLUW0ITrJReOgo1cq9SbsxYawBgfp_gh6A5603k2-ZQwVK0JKSHuLFkuQ3U"
+    }"#,
+    token: "eyJhbGciOiJSUzI1NiJ9.\
+        eyJpc3MiOiJqb2UiLA0KICJleHAiOjEzMDA4MTkzODAsDQogImh0dHA6Ly9leGFtcGxlLmNvbS9pc19yb290Ijp0cnVlfQ.\
+        cC4hiUPoj9Eetdgtv3hF80EGrhuB__dzERat0XF9g2VtQgr9PJbu3XOiZj5RZmh7AAuHIm4Bh-0Qc_lF5YKt_O8W2Fp5\
+        jujGbds9uJdbF9CUAr7t1dnZcAcQjbKBYNX4BAynRFdiuB--f_nZLgrnbyTyWzO75vRK5h6xBArLIARNPvkSjtQBMHlb1\
+        L07Qe7K0GarZRmB_eSN9383LcOLn6_dO--xi12jzDwusC-eOkHWEsqtFZESc6BfI7noOPqvhJ1phCnvWh6IeYI2w9QOYE\
+        UipUTI8np6LbgGY9Fs98rqVt5AXLIhWkWywlVmtVrBp0igcN_IoypGlUPQGe77Rw",
+    payload: RFC7515_PAYLOAD,
+    is_deterministic: true,
+};
+
+/// `ES256` token from [RFC 7515, Appendix A.3](https://www.rfc-editor.org/rfc/rfc7515.html#appendix-A.3).
+pub const RFC7515_ES256: JwsTestVector = JwsTestVector {
+    name: "RFC 7515, Appendix A.3",
+    algorithm: "ES256",
+    key: r#"{
+        "kty": "EC",
+        "crv": "P-256",
+        "x": "f83OJ3D2xF1Bg8vub9tLe1gHMzV76e8Tus9uPHvRVEU",
+        "y": "x_FEzRu9m36HLN_tue659LNpXW6pCyStikYjKIWI5a0",
+        "d": "jpsQnnGQmL-YBIffH1136cspYG6-0iY7X1fCE9-E9LI"
+    }"#,
+    token: "eyJhbGciOiJFUzI1NiJ9.\
+        eyJpc3MiOiJqb2UiLA0KICJleHAiOjEzMDA4MTkzODAsDQogImh0dHA6Ly9leGFtcGxlLmNvbS9pc19yb290Ijp0cnVlfQ.\
+        DtEhU3ljbEg8L38VWAfUAqOyKAM6-Xx-F4GawxaepmXFCgfTjDxw5djxLa8ISlSApmWQxfKTUJqPP3-Kg6NU1Q",
+    payload: RFC7515_PAYLOAD,
+    is_deterministic: false,
+};
+
+/// `HS256` token from [RFC 7520, Section 4.4](https://www.rfc-editor.org/rfc/rfc7520.html#section-4.4).
+pub const RFC7520_HS256: JwsTestVector = JwsTestVector {
+    name: "RFC 7520, Section 4.4",
+    algorithm: "HS256",
+    key: r#"{
+        "kty": "oct",
+        "kid": "018c0ae5-4d9b-471b-bfd6-eef314bc7037",
+        "use": "sig",
+        "alg": "HS256",
+        "k": "hJtXIZ2uSN5kbQfbtTNWbpdmhkV8FJG-Onbc6mxCcYg"
+    }"#,
+    token: "eyJhbGciOiJIUzI1NiIsImtpZCI6IjAxOGMwYWU1LTRkOWItNDcxYi1iZmQ2LWVlZjMxNGJjNzAzNyJ9.\
+        SXTigJlzIGEgZGFuZ2Vyb3VzIGJ1c2luZXNzLCBGcm9kbywgZ29pbmcgb3V0IHlvdXIgZG9vci4gWW91IHN0ZXAgb25\
+        0byB0aGUgcm9hZCwgYW5kIGlmIHlvdSBkb24ndCBrZWVwIHlvdXIgZmVldCwgdGhlcmXigJlzIG5vIGtub3dpbmcgd2h\
+        lcmUgeW91IG1pZ2h0IGJlIHN3ZXB0IG9mZiB0by4.\
+        s0h6KThzkfBBBkLspW1h84VsJZFTsPPqMDA7g1Md7p0",
+    payload: RFC7520_PAYLOAD,
+    is_deterministic: true,
+};
+
+/// All test vectors defined in this module.
+pub const ALL: &[JwsTestVector] = &[RFC7515_HS256, RFC7515_RS256, RFC7515_ES256, RFC7520_HS256];
+
+/// Errors that can occur during conformance checks.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ConformanceError {
+    /// Algorithm name differs from the one in the test vector.
+    AlgorithmMismatch {
+        /// Algorithm name from the test vector.
+        expected: &'static str,
+        /// Actual algorithm name.
+        actual: String,
+    },
+    /// Signature from the test vector cannot be parsed.
+    MalformedSignature(anyhow::Error),
+    /// Signature from the test vector (or one produced by the signer) is rejected.
+    InvalidSignature,
+    /// Signature with a modified byte is accepted.
+    TamperedSignatureAccepted,
+    /// Signature is accepted for a modified payload.
+    TamperedPayloadAccepted,
+    /// Signer has produced a signature different from the test vector, although
+    /// the algorithm is deterministic.
+    SignatureMismatch,
+}
+
+impl fmt::Display for ConformanceError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AlgorithmMismatch { expected, actual } => write!(
+                formatter,
+                "algorithm mismatch: expected {expected}, got {actual}"
+            ),
+            Self::MalformedSignature(err) => write!(formatter, "malformed signature: {err}"),
+            Self::InvalidSignature => formatter.write_str("valid signature is rejected"),
+            Self::TamperedSignatureAccepted => {
+                formatter.write_str("signature with a modified byte is accepted")
+            }
+            Self::TamperedPayloadAccepted => {
+                formatter.write_str("signature is accepted for a modified payload")
+            }
+            Self::SignatureMismatch => {
+                formatter.write_str("produced signature differs from the test vector")
+            }
+        }
+    }
+}
+
+impl core::error::Error for ConformanceError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::MalformedSignature(err) => Some(err.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+/// JWS test vector in the compact serialization.
+///
+/// See the [module-level docs](self) for the list of vectors and usage examples.
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub struct JwsTestVector {
+    /// Source of the vector (e.g., `RFC 7515, Appendix A.1`).
+    pub name: &'static str,
+    /// Name of the signature algorithm (i.e., the `alg` field in the token header).
+    pub algorithm: &'static str,
+    /// Key used to sign the token as a JWK. For asymmetric algorithms, the key contains
+    /// private parameters.
+    pub key: &'static str,
+    /// Token in the compact serialization.
+    pub token: &'static str,
+    /// Decoded token payload.
+    pub payload: &'static [u8],
+    /// Whether the signature algorithm is deterministic, i.e., signing the token again
+    /// must produce the same signature.
+    pub is_deterministic: bool,
+}
+
+impl JwsTestVector {
+    /// Parses the [key](Self::key) of this vector.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key cannot be parsed, which may only happen if the vector is malformed.
+    pub fn jwk(&self) -> JsonWebKey<'static> {
+        serde_json::from_str(self.key).expect("malformed JWK in test vector")
+    }
+
+    /// Returns the signing input, i.e., the token header and payload separated by `.`.
+    pub fn signing_input(&self) -> &'static [u8] {
+        let (signing_input, _) = self.split();
+        signing_input.as_bytes()
+    }
+
+    /// Returns the decoded token signature.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the signature is not valid base64url, which may only happen
+    /// if the vector is malformed.
+    pub fn signature(&self) -> Vec<u8> {
+        let (_, signature) = self.split();
+        Base64UrlUnpadded::decode_vec(signature).expect("malformed signature in test vector")
+    }
+
+    fn split(&self) -> (&'static str, &'static str) {
+        self.token
+            .rsplit_once('.')
+            .expect("malformed token in test vector")
+    }
+
+    fn check_name<A: Algorithm>(&self, algorithm: &A) -> Result<(), ConformanceError> {
+        let actual = algorithm.name();
+        if actual == self.algorithm {
+            Ok(())
+        } else {
+            Err(ConformanceError::AlgorithmMismatch {
+                expected: self.algorithm,
+                actual: actual.to_string(),
+            })
+        }
+    }
+
+    /// Checks that `algorithm` accepts the signature from this vector, and rejects signatures
+    /// with a modified byte and signatures for a modified payload.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the checks fails.
+    pub fn check_verifier<A: Algorithm>(
+        &self,
+        algorithm: &A,
+        verifying_key: &A::VerifyingKey,
+    ) -> Result<(), ConformanceError> {
+        self.check_name(algorithm)?;
+        let signature_bytes = self.signature();
+        let signature = A::Signature::try_from_slice(&signature_bytes)
+            .map_err(ConformanceError::MalformedSignature)?;
+        let signing_input = self.signing_input();
+        if !algorithm.verify_signature(&signature, verifying_key, signing_input) {
+            return Err(ConformanceError::InvalidSignature);
+        }
+
+        // The signing input ends with the encoded payload, so this modifies the payload.
+        let mut tampered_input = signing_input.to_vec();
+        tampered_input.extend_from_slice(b"AA");
+        if algorithm.verify_signature(&signature, verifying_key, &tampered_input) {
+            return Err(ConformanceError::TamperedPayloadAccepted);
+        }
+
+        let mut tampered_signature = signature_bytes;
+        tampered_signature[0] ^= 1;
+        // A signature that cannot be parsed is rejected as well.
+        if let Ok(signature) = A::Signature::try_from_slice(&tampered_signature) {
+            if algorithm.verify_signature(&signature, verifying_key, signing_input) {
+                return Err(ConformanceError::TamperedSignatureAccepted);
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that a signature produced by `algorithm` for the signing input of this vector
+    /// is accepted by the verifying key, and, for [deterministic](Self::is_deterministic)
+    /// algorithms, that it coincides with the signature from the vector.
+    ///
+    /// This method does not check the verifier; use [`Self::check_verifier()`] for that.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the checks fails.
+    pub fn check_signer<A: Algorithm>(
+        &self,
+        algorithm: &A,
+        signing_key: &A::SigningKey,
+        verifying_key: &A::VerifyingKey,
+    ) -> Result<(), ConformanceError> {
+        self.check_name(algorithm)?;
+        let signing_input = self.signing_input();
+        let signature = algorithm.sign(signing_key, signing_input);
+        if self.is_deterministic && *signature.as_bytes() != *self.signature() {
+            return Err(ConformanceError::SignatureMismatch);
+        }
+        if algorithm.verify_signature(&signature, verifying_key, signing_input) {
+            Ok(())
+        } else {
+            Err(ConformanceError::InvalidSignature)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::*;
+
+    #[test]
+    fn vectors_are_well_formed() {
+        for vector in ALL {
+            let token = crate::UntrustedToken::new(vector.token).unwrap();
+            assert_eq!(token.algorithm(), vector.algorithm, "{}", vector.name);
+
+            let payload = vector.token.split('.').nth(1).unwrap();
+            let payload = Base64UrlUnpadded::decode_vec(payload).unwrap();
+            assert_eq!(payload, vector.payload, "{}", vector.name);
+            vector.jwk();
+        }
+    }
+
+    #[cfg(feature = "hs")]
+    #[test]
+    fn hs256_vectors() {
+        use crate::alg::{Hs256, Hs256Key, Hs384};
+
+        for vector in [RFC7515_HS256, RFC7520_HS256] {
+            let key = Hs256Key::try_from(&vector.jwk()).unwrap();
+            vector.check_verifier(&Hs256, &key).unwrap();
+            vector.check_signer(&Hs256, &key, &key).unwrap();
+        }
+
+        let err = RFC7515_HS256
+            .check_verifier(&Hs384, &crate::alg::Hs384Key::new([0; 48]))
+            .unwrap_err();
+        assert_matches!(
+            err,
+            ConformanceError::AlgorithmMismatch { expected: "HS256", actual } if actual == "HS384"
+        );
+        let other_key = Hs256Key::new([0; 32]);
+        let err = RFC7515_HS256
+            .check_verifier(&Hs256, &other_key)
+            .unwrap_err();
+        assert_matches!(err, ConformanceError::InvalidSignature);
+        let err = RFC7515_HS256
+            .check_signer(&Hs256, &other_key, &other_key)
+            .unwrap_err();
+        assert_matches!(err, ConformanceError::SignatureMismatch);
+    }
+
+    #[cfg(feature = "rsa")]
+    #[test]
+    fn rs256_vector() {
+        use crate::alg::{Rsa, RsaPrivateKey, RsaPublicKey};
+
+        let jwk = RFC7515_RS256.jwk();
+        let signing_key = RsaPrivateKey::try_from(&jwk).unwrap();
+        let verifying_key = RsaPublicKey::try_from(&jwk).unwrap();
+        RFC7515_RS256
+            .check_verifier(&Rsa::rs256(), &verifying_key)
+            .unwrap();
+        RFC7515_RS256
+            .check_signer(&Rsa::rs256(), &signing_key, &verifying_key)
+            .unwrap();
+    }
+
+    #[cfg(feature = "p256")]
+    #[test]
+    fn es256_vector() {
+        use crate::{alg::Es256, Algorithm};
+
+        let jwk = RFC7515_ES256.jwk();
+        let signing_key = <Es256 as Algorithm>::SigningKey::try_from(&jwk).unwrap();
+        let verifying_key = <Es256 as Algorithm>::VerifyingKey::try_from(&jwk).unwrap();
+        RFC7515_ES256
+            .check_verifier(&Es256, &verifying_key)
+            .unwrap();
+        RFC7515_ES256
+            .check_signer(&Es256, &signing_key, &verifying_key)
+            .unwrap();
+    }
+}
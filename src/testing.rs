@@ -0,0 +1,494 @@
+//! Utilities for testing code that creates or validates tokens.
+//!
+//! - [`MockClock`] is a manually controlled clock for [`TimeOptions`], which allows testing
+//!   expiration logic without sleeping.
+//! - [`MockAlgorithm`] is a cheap deterministic [`Algorithm`] recording its sign / verify calls.
+//! - [`FixtureKeys`] provides fixed key pairs for the supported algorithms.
+//! - [`generators`] produce random headers, claims and (possibly malformed) tokens
+//!   for property testing and fuzzing, including via `arbitrary` and `proptest`.
+//!
+//! # Examples
+//!
+//! ```
+//! # use chrono::{Duration, TimeZone, Utc};
+//! use jwt_compact::{
+//!     prelude::*, testing::{MockAlgorithm, MockClock, MockKey}, Empty, ValidationError,
+//! };
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let clock = MockClock::new(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap());
+//! let time_options = clock.time_options();
+//! let alg = MockAlgorithm::new();
+//! let key = MockKey::new("test");
+//!
+//! let claims = Claims::empty()
+//!     .set_duration_and_issuance(&time_options, Duration::try_minutes(5).unwrap());
+//! let token = alg.token(&Header::empty(), &claims, &key)?;
+//! let token = UntrustedToken::new(&token)?;
+//! let token = alg.validator::<Empty>(&key).validate(&token)?;
+//! token.claims().validate_expiration(&time_options)?;
+//!
+//! clock.advance(Duration::try_minutes(10).unwrap());
+//! let err = token.claims().validate_expiration(&time_options).unwrap_err();
+//! assert!(matches!(err, ValidationError::Expired));
+//! assert_eq!((alg.sign_count(), alg.verify_count()), (1, 1));
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod generators;
+
+use chrono::Duration;
+use sha2::{Digest, Sha256};
+
+use core::num::NonZeroUsize;
+use std::sync::{
+    atomic::{AtomicI64, Ordering},
+    Arc, Mutex, MutexGuard, PoisonError,
+};
+
+use crate::{
+    alloc::{Cow, String, Vec},
+    jwk::{JsonWebKey, JwkError},
+    Algorithm, AlgorithmSignature, TimeOptions, Timestamp,
+};
+
+/// Manually controlled clock for [`TimeOptions`].
+///
+/// Clones of the clock share the current time, so that the time can be changed after
+/// creating [`TimeOptions`] via [`Self::time_options()`]. The clock has second precision,
+/// which is the precision of time-related claims.
+#[derive(Debug, Clone)]
+pub struct MockClock {
+    timestamp: Arc<AtomicI64>,
+}
+
+impl MockClock {
+    /// Creates a clock stopped at the specified time.
+    pub fn new(time: impl Into<Timestamp>) -> Self {
+        Self {
+            timestamp: Arc::new(AtomicI64::new(time.into().as_secs())),
+        }
+    }
+
+    /// Returns the current time according to this clock.
+    pub fn now(&self) -> Timestamp {
+        Timestamp::from_secs(self.timestamp.load(Ordering::SeqCst))
+    }
+
+    /// Sets the current time.
+    pub fn set(&self, time: impl Into<Timestamp>) {
+        self.timestamp
+            .store(time.into().as_secs(), Ordering::SeqCst);
+    }
+
+    /// Advances the clock by the specified duration, which may be negative.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the resulting time is out of range.
+    pub fn advance(&self, duration: Duration) {
+        let time = self
+            .now()
+            .checked_add_signed(duration)
+            .expect("mock clock time is out of range");
+        self.set(time);
+    }
+
+    /// Creates time options backed by this clock with the default leeway (60 seconds).
+    pub fn time_options(&self) -> TimeOptions<impl Fn() -> Timestamp + Clone> {
+        let clock = self.clone();
+        TimeOptions::new(Duration::try_seconds(60).unwrap(), move || clock.now())
+    }
+}
+
+/// Key for [`MockAlgorithm`] identified by a string. The same key is used for signing
+/// and verification.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MockKey(String);
+
+impl MockKey {
+    /// Creates a key with the specified ID.
+    pub fn new(id: impl Into<String>) -> Self {
+        Self(id.into())
+    }
+
+    /// Returns the ID of this key.
+    pub fn id(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Signature produced by [`MockAlgorithm`]: SHA-256 digest of the key ID and the signed message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MockSignature([u8; 32]);
+
+impl MockSignature {
+    fn new(key: &MockKey, message: &[u8]) -> Self {
+        let mut digest = Sha256::new();
+        digest.update(key.0.as_bytes());
+        digest.update([0]);
+        digest.update(message);
+        Self(digest.finalize().into())
+    }
+}
+
+impl AlgorithmSignature for MockSignature {
+    const LENGTH: Option<NonZeroUsize> = NonZeroUsize::new(32);
+
+    fn try_from_slice(slice: &[u8]) -> anyhow::Result<Self> {
+        Ok(Self(slice.try_into()?))
+    }
+
+    fn as_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(&self.0)
+    }
+}
+
+/// Call of [`MockAlgorithm`] recorded in its log.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MockCall {
+    /// Message was signed.
+    Sign {
+        /// Signing key.
+        key: MockKey,
+        /// Signed message.
+        message: Vec<u8>,
+    },
+    /// Signature was verified.
+    Verify {
+        /// Verifying key.
+        key: MockKey,
+        /// Signed message.
+        message: Vec<u8>,
+        /// Verification outcome.
+        is_valid: bool,
+    },
+}
+
+/// Deterministic [`Algorithm`] for tests recording its sign / verify calls.
+///
+/// Signatures are SHA-256 digests of the [`MockKey`] ID and the signed message, so they are
+/// **not** secure. Clones of the algorithm share the call log.
+///
+/// See the [module-level docs](self) for an example of usage.
+#[derive(Debug, Clone)]
+pub struct MockAlgorithm {
+    name: &'static str,
+    is_rejecting: bool,
+    calls: Arc<Mutex<Vec<MockCall>>>,
+}
+
+impl Default for MockAlgorithm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockAlgorithm {
+    /// Creates an algorithm with the `MOCK` name.
+    pub fn new() -> Self {
+        Self {
+            name: "MOCK",
+            is_rejecting: false,
+            calls: Arc::default(),
+        }
+    }
+
+    /// Sets the algorithm name (i.e., the `alg` field in the token header).
+    #[must_use]
+    pub fn with_name(mut self, name: &'static str) -> Self {
+        self.name = name;
+        self
+    }
+
+    /// Makes the algorithm reject all signatures, e.g., to emulate a revoked key.
+    #[must_use]
+    pub fn rejecting(mut self) -> Self {
+        self.is_rejecting = true;
+        self
+    }
+
+    fn log(&self) -> MutexGuard<'_, Vec<MockCall>> {
+        self.calls.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Returns all calls recorded so far.
+    pub fn calls(&self) -> Vec<MockCall> {
+        self.log().clone()
+    }
+
+    /// Returns the number of signed messages.
+    pub fn sign_count(&self) -> usize {
+        let log = self.log();
+        log.iter()
+            .filter(|call| matches!(call, MockCall::Sign { .. }))
+            .count()
+    }
+
+    /// Returns the number of verified signatures.
+    pub fn verify_count(&self) -> usize {
+        let log = self.log();
+        log.iter()
+            .filter(|call| matches!(call, MockCall::Verify { .. }))
+            .count()
+    }
+
+    /// Clears the call log.
+    pub fn clear(&self) {
+        self.log().clear();
+    }
+}
+
+impl Algorithm for MockAlgorithm {
+    type SigningKey = MockKey;
+    type VerifyingKey = MockKey;
+    type Signature = MockSignature;
+
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Borrowed(self.name)
+    }
+
+    #[cfg(feature = "sign")]
+    fn sign(&self, signing_key: &Self::SigningKey, message: &[u8]) -> Self::Signature {
+        self.log().push(MockCall::Sign {
+            key: signing_key.clone(),
+            message: message.to_vec(),
+        });
+        MockSignature::new(signing_key, message)
+    }
+
+    fn verify_signature(
+        &self,
+        signature: &Self::Signature,
+        verifying_key: &Self::VerifyingKey,
+        message: &[u8],
+    ) -> bool {
+        let is_valid =
+            !self.is_rejecting && *signature == MockSignature::new(verifying_key, message);
+        self.log().push(MockCall::Verify {
+            key: verifying_key.clone(),
+            message: message.to_vec(),
+            is_valid,
+        });
+        is_valid
+    }
+}
+
+/// Algorithm with a fixed key pair for tests.
+///
+/// Keys are public (most of them are taken from RFC test vectors), so they must never be used
+/// outside tests.
+///
+/// # Examples
+///
+/// ```
+/// # use jwt_compact::{alg::Hs256, prelude::*, testing::FixtureKeys, Empty};
+/// # fn main() -> anyhow::Result<()> {
+/// let (signing_key, verifying_key) = Hs256::fixture_keys();
+/// let token = Hs256.token(&Header::empty(), &Claims::empty(), &signing_key)?;
+/// let token = UntrustedToken::new(&token)?;
+/// Hs256.validator::<Empty>(&verifying_key).validate(&token)?;
+/// # Ok(())
+/// # }
+/// ```
+pub trait FixtureKeys: Algorithm {
+    /// Returns the fixed signing key and the matching verifying key.
+    fn fixture_keys() -> (Self::SigningKey, Self::VerifyingKey);
+}
+
+#[allow(dead_code)] // unused if no algorithms are enabled
+fn keys_from_jwk<S, V>(jwk: &str) -> (S, V)
+where
+    S: for<'a> TryFrom<&'a JsonWebKey<'a>, Error = JwkError>,
+    V: for<'a> TryFrom<&'a JsonWebKey<'a>, Error = JwkError>,
+{
+    let jwk: JsonWebKey<'_> = serde_json::from_str(jwk).expect("malformed fixture JWK");
+    let signing_key = S::try_from(&jwk).expect("invalid fixture signing key");
+    let verifying_key = V::try_from(&jwk).expect("invalid fixture verifying key");
+    (signing_key, verifying_key)
+}
+
+macro_rules! impl_fixture_keys {
+    ($alg:ty, $jwk:expr) => {
+        impl FixtureKeys for $alg {
+            fn fixture_keys() -> (Self::SigningKey, Self::VerifyingKey) {
+                keys_from_jwk($jwk)
+            }
+        }
+    };
+}
+
+impl FixtureKeys for MockAlgorithm {
+    fn fixture_keys() -> (Self::SigningKey, Self::VerifyingKey) {
+        let key = MockKey::new("fixture");
+        (key.clone(), key)
+    }
+}
+
+#[cfg(feature = "hs")]
+mod hs {
+    use super::{keys_from_jwk, FixtureKeys};
+    use crate::{
+        alg::{Hs256, Hs384, Hs512},
+        test_vectors,
+    };
+
+    impl_fixture_keys!(Hs256, test_vectors::RFC7520_HS256.key);
+    // The 64-byte key is long enough for `HS384` and `HS512`.
+    impl_fixture_keys!(Hs384, test_vectors::RFC7515_HS256.key);
+    impl_fixture_keys!(Hs512, test_vectors::RFC7515_HS256.key);
+}
+
+#[cfg(feature = "p256")]
+impl_fixture_keys!(crate::alg::Es256, crate::test_vectors::RFC7515_ES256.key);
+
+#[cfg(feature = "rsa")]
+impl_fixture_keys!(crate::alg::Rsa, crate::test_vectors::RFC7515_RS256.key);
+
+#[cfg(any(feature = "es256k", feature = "k256"))]
+impl_fixture_keys!(
+    crate::alg::Es256k,
+    r#"{
+        "kty": "EC",
+        "crv": "secp256k1",
+        "x": "zGw5XTAtyIGHG-WU1IcEaj6TUFiyekqyQ5Q5tjMtjXc",
+        "y": "Hq6N8ZspKEYvK4vNSUAv38cv_jvqCQ0SEhWbdIaarE4",
+        "d": "cxOKw1Dv1ZAgxJQTCGqny3Kg4Jxu1DpnROeCX2FfWfU"
+    }"#
+);
+
+// Key from RFC 8037, Appendix A.1.
+#[cfg(any(
+    feature = "exonum-crypto",
+    feature = "ed25519-dalek",
+    feature = "ed25519-compact"
+))]
+impl_fixture_keys!(
+    crate::alg::Ed25519,
+    r#"{
+        "kty": "OKP",
+        "crv": "Ed25519",
+        "x": "11qYAYKxCrfVS_7TyWQHOg7hcvPapiMlrwIaaPcHURo",
+        "d": "nWGxne_9WmC6hEr0kuwsxERJxWl7MmkZcDusAxyuf2A"
+    }"#
+);
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use chrono::{TimeZone, Utc};
+
+    use super::*;
+    use crate::{prelude::*, Empty, ValidationError};
+
+    #[test]
+    fn mock_clock() {
+        let start = Timestamp::from(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap());
+        let clock = MockClock::new(start);
+        let time_options = clock.time_options();
+        assert_eq!((time_options.clock_fn)(), start);
+
+        clock.clone().advance(Duration::try_hours(1).unwrap());
+        assert_eq!(
+            (time_options.clock_fn)(),
+            start + Duration::try_hours(1).unwrap()
+        );
+        clock.advance(Duration::try_hours(-2).unwrap());
+        assert_eq!(clock.now(), start - Duration::try_hours(1).unwrap());
+        clock.set(start);
+        assert_eq!(time_options.now(), start);
+    }
+
+    #[test]
+    fn mock_algorithm() {
+        let alg = MockAlgorithm::new().with_name("MOCK256");
+        let (key, _) = MockAlgorithm::fixture_keys();
+        let token = alg.token(&Header::empty(), &Claims::empty(), &key).unwrap();
+        let token = UntrustedToken::new(&token).unwrap();
+        assert_eq!(token.algorithm(), "MOCK256");
+        alg.validator::<Empty>(&key).validate(&token).unwrap();
+
+        let other_key = MockKey::new("other");
+        let err = alg
+            .validator::<Empty>(&other_key)
+            .validate(&token)
+            .unwrap_err();
+        assert_matches!(err, ValidationError::InvalidSignature);
+
+        let calls = alg.calls();
+        assert_eq!(calls.len(), 3);
+        assert_matches!(&calls[0], MockCall::Sign { key: k, .. } if *k == key);
+        assert_matches!(&calls[1], MockCall::Verify { is_valid: true, .. });
+        assert_matches!(
+            &calls[2],
+            MockCall::Verify { key, is_valid: false, .. } if key.id() == "other"
+        );
+        assert_eq!((alg.sign_count(), alg.verify_count()), (1, 2));
+
+        let rejecting = alg.clone().rejecting();
+        rejecting.clear();
+        assert_eq!(alg.verify_count(), 0); // log is shared among clones
+        let err = rejecting
+            .validator::<Empty>(&key)
+            .validate(&token)
+            .unwrap_err();
+        assert_matches!(err, ValidationError::InvalidSignature);
+        assert_eq!(alg.verify_count(), 1);
+    }
+
+    fn check_fixture_keys<A: FixtureKeys>(alg: &A) {
+        let (signing_key, verifying_key) = A::fixture_keys();
+        let token = alg
+            .token(&Header::empty(), &Claims::empty(), &signing_key)
+            .unwrap();
+        let token = UntrustedToken::new(&token).unwrap();
+        alg.validator::<Empty>(&verifying_key)
+            .validate(&token)
+            .unwrap();
+    }
+
+    #[cfg(feature = "hs")]
+    #[test]
+    fn hs_fixture_keys() {
+        use crate::alg::{Hs256, Hs384, Hs512};
+
+        check_fixture_keys(&Hs256);
+        check_fixture_keys(&Hs384);
+        check_fixture_keys(&Hs512);
+        assert_eq!(Hs256::fixture_keys().0.as_ref().len(), 32);
+        assert_eq!(Hs512::fixture_keys().0.as_ref().len(), 64);
+    }
+
+    #[cfg(feature = "p256")]
+    #[test]
+    fn es256_fixture_keys() {
+        check_fixture_keys(&crate::alg::Es256);
+    }
+
+    #[cfg(feature = "rsa")]
+    #[test]
+    fn rsa_fixture_keys() {
+        use crate::alg::Rsa;
+
+        check_fixture_keys(&Rsa::rs256());
+        check_fixture_keys(&Rsa::ps512());
+    }
+
+    #[cfg(any(feature = "es256k", feature = "k256"))]
+    #[test]
+    fn es256k_fixture_keys() {
+        check_fixture_keys(&crate::alg::Es256k::default());
+    }
+
+    #[cfg(any(
+        feature = "exonum-crypto",
+        feature = "ed25519-dalek",
+        feature = "ed25519-compact"
+    ))]
+    #[test]
+    fn ed25519_fixture_keys() {
+        check_fixture_keys(&crate::alg::Ed25519);
+    }
+}
@@ -0,0 +1,605 @@
+//! Random generators of headers, claims and compact tokens for property testing and fuzzing.
+//!
+//! Generators are driven by an [`RngCore`] implementation, so they can be plugged into any
+//! property testing or fuzzing framework (e.g., by seeding a deterministic RNG from the input
+//! provided by the framework). Besides [well-formed tokens](token()), [`mutate_token()`]
+//! produces near-well-formed tokens, which are useful to check that a validator rejects
+//! malformed input gracefully.
+//!
+//! With the `arbitrary` feature, [`Header`], [`Claims`], [`DynamicClaims`], [`Empty`]
+//! and [`UntrustedToken`] implement [`Arbitrary`] on top of these generators, so they can be
+//! used with `cargo fuzz` directly. With the `proptest` feature, [`header_strategy()`],
+//! [`claims_strategy()`], [`token_strategy()`] and [`mutated_token_strategy()`] provide
+//! `proptest` strategies.
+//!
+//! [`Arbitrary`]: https://docs.rs/arbitrary/1/arbitrary/trait.Arbitrary.html
+//!
+//! # Examples
+//!
+//! ```
+//! # use rand::{rngs::StdRng, SeedableRng};
+//! use jwt_compact::{
+//!     prelude::*, testing::{generators, MockAlgorithm, MockKey}, DynamicClaims,
+//! };
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let alg = MockAlgorithm::new();
+//! let key = MockKey::new("test");
+//! let mut rng = StdRng::seed_from_u64(123);
+//! for _ in 0..100 {
+//!     let claims = generators::claims(&mut rng);
+//!     let token_string = generators::token(&mut rng, &alg, &key, &claims)?;
+//!     let token = UntrustedToken::new(&token_string)?;
+//!     let token = alg.validator::<DynamicClaims>(&key).validate(&token)?;
+//!     assert_eq!(*token.claims(), claims);
+//!
+//!     let mutated = generators::mutate_token(&mut rng, &token_string);
+//!     // A validator must not panic on malformed tokens.
+//!     if let Ok(mutated) = UntrustedToken::new(&mutated) {
+//!         alg.validator::<DynamicClaims>(&key).validate(&mutated).ok();
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+#[cfg(feature = "arbitrary")]
+use arbitrary::{Arbitrary, Unstructured};
+use base64ct::{Base64UrlUnpadded, Encoding};
+#[cfg(feature = "proptest")]
+use proptest::{collection::vec, prelude::any, strategy::Strategy};
+use rand_core::RngCore;
+#[cfg(feature = "arbitrary")]
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+use crate::{
+    alloc::{String, ToOwned, Vec},
+    token::Thumbprint,
+    Algorithm, AlgorithmExt, Claims, CreationError, DynamicClaims, Header, Timestamp,
+};
+#[cfg(feature = "arbitrary")]
+use crate::{
+    testing::{MockAlgorithm, MockKey},
+    Empty, UntrustedToken,
+};
+
+/// Maximum nesting depth of generated JSON values.
+const MAX_DEPTH: usize = 2;
+/// Characters used in generated strings. Includes non-ASCII and JSON-escaped chars.
+const CHARS: &[char] = &[
+    'a',
+    'b',
+    'c',
+    'x',
+    'y',
+    'z',
+    'A',
+    'Z',
+    '0',
+    '9',
+    '-',
+    '_',
+    '.',
+    ':',
+    '/',
+    ' ',
+    '"',
+    '\\',
+    '\n',
+    '\u{e9}',
+    '\u{2019}',
+    '\u{1f600}',
+];
+/// Claims generated by [`claims()`] besides time-related ones.
+const REGISTERED_CLAIMS: &[&str] = &["iss", "sub", "aud", "jti"];
+/// Header fields / algorithms used by [`mutate_token()`].
+const ALGORITHMS: &[&str] = &["none", "None", "HS256", "RS256", "ES256", "EdDSA", ""];
+
+fn below(rng: &mut impl RngCore, bound: usize) -> usize {
+    // The modulo bias is irrelevant for testing.
+    usize::try_from(rng.next_u64() % bound as u64).unwrap_or_default()
+}
+
+fn coin(rng: &mut impl RngCore) -> bool {
+    rng.next_u32() % 2 == 0
+}
+
+/// Generates a string with up to 16 chars, including non-ASCII ones.
+pub fn string(rng: &mut impl RngCore) -> String {
+    let len = below(rng, 17);
+    (0..len).map(|_| CHARS[below(rng, CHARS.len())]).collect()
+}
+
+/// Generates a JSON value with bounded nesting depth. Floating-point numbers
+/// are not generated, so that values can be compared after a round trip.
+pub fn json_value(rng: &mut impl RngCore) -> Value {
+    json_value_with_depth(rng, 0)
+}
+
+fn json_value_with_depth(rng: &mut impl RngCore, depth: usize) -> Value {
+    let variants = if depth < MAX_DEPTH { 6 } else { 4 };
+    match below(rng, variants) {
+        0 => Value::Null,
+        1 => Value::Bool(coin(rng)),
+        #[allow(clippy::cast_possible_wrap)] // intentional
+        2 => Value::from(rng.next_u64() as i64),
+        3 => Value::String(string(rng)),
+        4 => {
+            let len = below(rng, 4);
+            (0..len)
+                .map(|_| json_value_with_depth(rng, depth + 1))
+                .collect()
+        }
+        _ => {
+            let len = below(rng, 4);
+            let map = (0..len).map(|_| (string(rng), json_value_with_depth(rng, depth + 1)));
+            Value::Object(map.collect())
+        }
+    }
+}
+
+fn optional_string(rng: &mut impl RngCore) -> Option<String> {
+    coin(rng).then(|| string(rng))
+}
+
+/// Generates a header with random optional fields.
+pub fn header(rng: &mut impl RngCore) -> Header {
+    let mut header = Header::empty();
+    header.key_set_url = optional_string(rng);
+    header.key_id = optional_string(rng);
+    header.certificate_url = optional_string(rng);
+    header.token_type = optional_string(rng);
+    if coin(rng) {
+        let mut bytes = [0_u8; 20];
+        rng.fill_bytes(&mut bytes);
+        header.certificate_sha1_thumbprint = Some(Thumbprint::Bytes(bytes));
+    }
+    if coin(rng) {
+        let mut bytes = [0_u8; 32];
+        rng.fill_bytes(&mut bytes);
+        header.certificate_thumbprint = Some(Thumbprint::Bytes(bytes));
+    }
+    header
+}
+
+/// Generates claims with random time-related claims (between 1970 and 2100),
+/// registered claims (`iss`, `sub`, `aud` and `jti`) and custom claims.
+pub fn claims(rng: &mut impl RngCore) -> Claims<DynamicClaims> {
+    Claims {
+        expiration: timestamp(rng),
+        not_before: timestamp(rng),
+        issued_at: timestamp(rng),
+        custom: custom_claims(rng),
+    }
+}
+
+fn timestamp(rng: &mut impl RngCore) -> Option<Timestamp> {
+    const MAX_TIMESTAMP: u64 = 4_102_444_800; // 2100-01-01
+
+    coin(rng).then(|| {
+        let secs = i64::try_from(rng.next_u64() % MAX_TIMESTAMP).unwrap_or_default();
+        Timestamp::from_secs(secs)
+    })
+}
+
+fn custom_claims(rng: &mut impl RngCore) -> DynamicClaims {
+    let mut custom = Map::new();
+    for &name in REGISTERED_CLAIMS {
+        if coin(rng) {
+            let value = if name == "aud" && coin(rng) {
+                (0..below(rng, 3))
+                    .map(|_| Value::String(string(rng)))
+                    .collect()
+            } else {
+                Value::String(string(rng))
+            };
+            custom.insert(name.to_owned(), value);
+        }
+    }
+    for _ in 0..below(rng, 4) {
+        let name = string(rng);
+        if !matches!(name.as_str(), "exp" | "nbf" | "iat") {
+            custom.insert(name, json_value(rng));
+        }
+    }
+    custom.into()
+}
+
+/// Generates a well-formed token with the specified claims and a [random header](header()),
+/// signed by `algorithm`.
+///
+/// # Errors
+///
+/// Propagates errors creating the token.
+pub fn token<A: Algorithm>(
+    rng: &mut impl RngCore,
+    algorithm: &A,
+    signing_key: &A::SigningKey,
+    claims: &Claims<DynamicClaims>,
+) -> Result<String, CreationError> {
+    let token = algorithm.token(&header(rng), claims, signing_key)?;
+    Ok(token.into_string())
+}
+
+fn encode_segment(rng: &mut impl RngCore) -> String {
+    let value = json_value(rng);
+    Base64UrlUnpadded::encode_string(value.to_string().as_bytes())
+}
+
+/// Produces a near-well-formed token by applying a random mutation to `token`, such as changing
+/// a char, truncating the token, adding or removing a segment, replacing the header
+/// (e.g., with `"alg": "none"`) or the claims with arbitrary JSON.
+///
+/// The mutated token is guaranteed to differ from the original one, but may still be valid
+/// in rare cases (e.g., if the mutation only changes an ignored header field).
+pub fn mutate_token(rng: &mut impl RngCore, token: &str) -> String {
+    let segments: Vec<_> = token.split('.').collect();
+    let mutated = match below(rng, 7) {
+        0 if !token.is_empty() => {
+            let mut chars: Vec<_> = token.chars().collect();
+            let idx = below(rng, chars.len());
+            let replacement = if chars[idx] == 'A' { 'B' } else { 'A' };
+            chars[idx] = replacement;
+            chars.into_iter().collect()
+        }
+        1 if !token.is_empty() => {
+            let len = below(rng, token.len());
+            token.get(..len).unwrap_or_default().to_owned()
+        }
+        2 if segments.len() > 1 => {
+            let mut segments = segments.clone();
+            segments.remove(below(rng, segments.len()));
+            segments.join(".")
+        }
+        3 => {
+            let alg = ALGORITHMS[below(rng, ALGORITHMS.len())];
+            let header = Base64UrlUnpadded::encode_string(
+                serde_json::json!({ "alg": alg }).to_string().as_bytes(),
+            );
+            let mut segments = segments.clone();
+            segments[0] = &header;
+            if alg.eq_ignore_ascii_case("none") {
+                segments.truncate(2);
+                segments.push("");
+            }
+            segments.join(".")
+        }
+        4 if segments.len() > 1 => {
+            let claims = encode_segment(rng);
+            let mut segments = segments.clone();
+            segments[1] = &claims;
+            segments.join(".")
+        }
+        5 => {
+            let mut bytes = Vec::from(token);
+            let idx = below(rng, bytes.len() + 1);
+            bytes.insert(idx, b"=. +/%"[below(rng, 6)]);
+            String::from_utf8(bytes).unwrap_or_default()
+        }
+        _ => {
+            let extra = encode_segment(rng);
+            [token, &extra].join(".")
+        }
+    };
+
+    if mutated == token {
+        // Can happen for empty tokens or no-op mutations.
+        [token, "."].concat()
+    } else {
+        mutated
+    }
+}
+
+/// Adapter feeding bytes from [`Unstructured`] input to generators. Once the input
+/// is exhausted, the generators receive zeros.
+#[cfg(feature = "arbitrary")]
+struct UnstructuredRng<'r, 'a>(&'r mut Unstructured<'a>);
+
+#[cfg(feature = "arbitrary")]
+impl RngCore for UnstructuredRng<'_, '_> {
+    fn next_u32(&mut self) -> u32 {
+        rand_core::impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        rand_core::impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        // `fill_buffer()` pads `dest` with zeros and never fails.
+        self.0.fill_buffer(dest).ok();
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+#[cfg_attr(docsrs, doc(cfg(feature = "arbitrary")))]
+impl<'a> Arbitrary<'a> for Empty {
+    fn arbitrary(_: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Self {})
+    }
+}
+
+/// Produces custom claims in the same way as [`claims()`].
+#[cfg(feature = "arbitrary")]
+#[cfg_attr(docsrs, doc(cfg(feature = "arbitrary")))]
+impl<'a> Arbitrary<'a> for DynamicClaims {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(custom_claims(&mut UnstructuredRng(u)))
+    }
+}
+
+/// Produces a header with fields generated by [`header()`] and arbitrary custom fields.
+#[cfg(feature = "arbitrary")]
+#[cfg_attr(docsrs, doc(cfg(feature = "arbitrary")))]
+impl<'a, T: Arbitrary<'a>> Arbitrary<'a> for Header<T> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let header = header(&mut UnstructuredRng(u));
+        let mut output = Header::new(T::arbitrary(u)?);
+        output.key_set_url = header.key_set_url;
+        output.key_id = header.key_id;
+        output.certificate_url = header.certificate_url;
+        output.certificate_sha1_thumbprint = header.certificate_sha1_thumbprint;
+        output.certificate_thumbprint = header.certificate_thumbprint;
+        output.token_type = header.token_type;
+        Ok(output)
+    }
+}
+
+/// Produces claims with time-related claims generated as in [`claims()`]
+/// and arbitrary custom claims.
+#[cfg(feature = "arbitrary")]
+#[cfg_attr(docsrs, doc(cfg(feature = "arbitrary")))]
+impl<'a, T: Arbitrary<'a>> Arbitrary<'a> for Claims<T> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let mut rng = UnstructuredRng(u);
+        let expiration = timestamp(&mut rng);
+        let not_before = timestamp(&mut rng);
+        let issued_at = timestamp(&mut rng);
+        Ok(Self {
+            expiration,
+            not_before,
+            issued_at,
+            custom: T::arbitrary(u)?,
+        })
+    }
+}
+
+/// Produces a [well-formed token](token()) signed by [`MockAlgorithm::new()`]
+/// with `MockKey::new("arbitrary")`, which is [mutated](mutate_token()) with 50% probability.
+/// Mutated tokens that cannot be parsed are rejected with [`arbitrary::Error::IncorrectFormat`].
+#[cfg(feature = "arbitrary")]
+#[cfg_attr(docsrs, doc(cfg(feature = "arbitrary")))]
+impl<'a, H: DeserializeOwned + 'a> Arbitrary<'a> for UntrustedToken<'static, H> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let mut rng = UnstructuredRng(u);
+        let claims = claims(&mut rng);
+        let key = MockKey::new("arbitrary");
+        let mut token = token(&mut rng, &MockAlgorithm::new(), &key, &claims)
+            .map_err(|_| arbitrary::Error::IncorrectFormat)?;
+        if coin(&mut rng) {
+            token = mutate_token(&mut rng, &token);
+        }
+        let token = UntrustedToken::try_from(token.as_str())
+            .map_err(|_| arbitrary::Error::IncorrectFormat)?;
+        Ok(token.into_owned())
+    }
+}
+
+/// Maximum number of bytes fed to generators by `proptest` strategies.
+#[cfg(feature = "proptest")]
+const MAX_STRATEGY_INPUT_LEN: usize = 1_024;
+
+/// RNG reading bytes from a buffer generated by `proptest`. Once the buffer is exhausted,
+/// the RNG yields zeros. Since `proptest` shrinks the buffer towards shorter and zeroed
+/// byte sequences, generated values shrink towards smaller ones.
+#[cfg(feature = "proptest")]
+struct BufferRng {
+    buffer: Vec<u8>,
+    pos: usize,
+}
+
+#[cfg(feature = "proptest")]
+impl RngCore for BufferRng {
+    fn next_u32(&mut self) -> u32 {
+        rand_core::impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        rand_core::impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        let remaining = self.buffer.get(self.pos..).unwrap_or_default();
+        let len = dest.len().min(remaining.len());
+        dest[..len].copy_from_slice(&remaining[..len]);
+        dest[len..].fill(0);
+        self.pos += len;
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "proptest")]
+fn buffer_strategy<T: core::fmt::Debug>(
+    generate: impl Fn(&mut BufferRng) -> T,
+) -> impl Strategy<Value = T> {
+    vec(any::<u8>(), 0..=MAX_STRATEGY_INPUT_LEN).prop_map(move |buffer| {
+        let mut rng = BufferRng { buffer, pos: 0 };
+        generate(&mut rng)
+    })
+}
+
+/// Returns a `proptest` strategy producing [headers](header()).
+#[cfg(feature = "proptest")]
+#[cfg_attr(docsrs, doc(cfg(feature = "proptest")))]
+pub fn header_strategy() -> impl Strategy<Value = Header> {
+    buffer_strategy(header)
+}
+
+/// Returns a `proptest` strategy producing [claims](claims()).
+#[cfg(feature = "proptest")]
+#[cfg_attr(docsrs, doc(cfg(feature = "proptest")))]
+pub fn claims_strategy() -> impl Strategy<Value = Claims<DynamicClaims>> {
+    buffer_strategy(claims)
+}
+
+/// Returns a `proptest` strategy producing [well-formed tokens](token()) signed by `algorithm`
+/// together with the claims they contain.
+///
+/// # Panics
+///
+/// Panics if the token cannot be created, e.g., because of a signing error.
+#[cfg(feature = "proptest")]
+#[cfg_attr(docsrs, doc(cfg(feature = "proptest")))]
+pub fn token_strategy<A: Algorithm>(
+    algorithm: A,
+    signing_key: A::SigningKey,
+) -> impl Strategy<Value = (Claims<DynamicClaims>, String)> {
+    buffer_strategy(move |rng| {
+        let claims = claims(rng);
+        let token = token(rng, &algorithm, &signing_key, &claims).expect("cannot create token");
+        (claims, token)
+    })
+}
+
+/// Returns a `proptest` strategy producing [near-well-formed tokens](mutate_token())
+/// based on tokens signed by `algorithm`.
+///
+/// # Panics
+///
+/// Panics if the original token cannot be created, e.g., because of a signing error.
+#[cfg(feature = "proptest")]
+#[cfg_attr(docsrs, doc(cfg(feature = "proptest")))]
+pub fn mutated_token_strategy<A: Algorithm>(
+    algorithm: A,
+    signing_key: A::SigningKey,
+) -> impl Strategy<Value = String> {
+    buffer_strategy(move |rng| {
+        let claims = claims(rng);
+        let token = token(rng, &algorithm, &signing_key, &claims).expect("cannot create token");
+        mutate_token(rng, &token)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::{
+        testing::{MockAlgorithm, MockKey},
+        UntrustedToken, ValidationError,
+    };
+
+    #[test]
+    fn generated_tokens_round_trip() {
+        let alg = MockAlgorithm::new();
+        let key = MockKey::new("test");
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..500 {
+            let claims = claims(&mut rng);
+            let token = token(&mut rng, &alg, &key, &claims).unwrap();
+            let token = UntrustedToken::new(&token).unwrap();
+            let token = alg
+                .validator::<DynamicClaims>(&key)
+                .validate(&token)
+                .unwrap();
+            assert_eq!(*token.claims(), claims);
+        }
+    }
+
+    #[test]
+    fn mutated_tokens_are_rejected() {
+        let alg = MockAlgorithm::new();
+        let key = MockKey::new("test");
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut rejected_count = 0;
+        for _ in 0..500 {
+            let claims = claims(&mut rng);
+            let token = token(&mut rng, &alg, &key, &claims).unwrap();
+            let mutated = mutate_token(&mut rng, &token);
+            assert_ne!(mutated, token);
+
+            let Ok(mutated) = UntrustedToken::new(&mutated) else {
+                rejected_count += 1;
+                continue;
+            };
+            match alg.validator::<DynamicClaims>(&key).validate(&mutated) {
+                Ok(mutated) => assert_eq!(*mutated.claims(), claims),
+                Err(err) => {
+                    assert_matches!(
+                        err,
+                        ValidationError::AlgorithmMismatch { .. }
+                            | ValidationError::InvalidSignature
+                            | ValidationError::InvalidSignatureLen { .. }
+                            | ValidationError::MalformedSignature(_)
+                            | ValidationError::MalformedClaims(_)
+                    );
+                    rejected_count += 1;
+                }
+            }
+        }
+        assert!(rejected_count > 450, "{rejected_count}");
+    }
+
+    #[cfg(feature = "arbitrary")]
+    #[test]
+    fn arbitrary_values() {
+        let alg = MockAlgorithm::new();
+        let key = MockKey::new("arbitrary");
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut parsed_count = 0;
+        for _ in 0..500 {
+            let mut bytes = [0_u8; 8_192];
+            rng.fill_bytes(&mut bytes);
+            let mut u = Unstructured::new(&bytes);
+
+            let header = Header::<Empty>::arbitrary(&mut u).unwrap();
+            let claims = Claims::<DynamicClaims>::arbitrary(&mut u).unwrap();
+            let token = alg.token(&header, &claims, &key).unwrap();
+            let token = UntrustedToken::new(&token).unwrap();
+            let token = alg
+                .validator::<DynamicClaims>(&key)
+                .validate(&token)
+                .unwrap();
+            assert_eq!(*token.claims(), claims);
+
+            if let Ok(token) = UntrustedToken::<Empty>::arbitrary(&mut u) {
+                alg.validator::<DynamicClaims>(&key).validate(&token).ok();
+                parsed_count += 1;
+            }
+        }
+        assert!(parsed_count > 200, "{parsed_count}");
+    }
+
+    #[cfg(feature = "proptest")]
+    #[test]
+    fn strategy_tokens_round_trip() {
+        use proptest::test_runner::TestRunner;
+
+        let alg = MockAlgorithm::new();
+        let key = MockKey::new("test");
+        let strategy = token_strategy(alg.clone(), key.clone());
+        TestRunner::deterministic()
+            .run(&strategy, |(claims, token)| {
+                let token = UntrustedToken::new(&token).unwrap();
+                let token = alg
+                    .validator::<DynamicClaims>(&key)
+                    .validate(&token)
+                    .unwrap();
+                assert_eq!(*token.claims(), claims);
+                Ok(())
+            })
+            .unwrap();
+    }
+}
@@ -0,0 +1,330 @@
+//! Backend-agnostic timestamps.
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use serde::{
+    de::{Error as DeError, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use core::{fmt, ops};
+
+/// Numeric date as per the [JWT spec], i.e., the number of seconds since the Unix epoch
+/// (1970-01-01T00:00:00Z), ignoring leap seconds.
+///
+/// This type is used to represent time-related claims (`exp`, `nbf` and `iat`) and
+/// the current time returned by [`TimeOptions`](crate::TimeOptions) clocks independently
+/// of a date / time library. It can be converted to and from [`DateTime<Utc>`] from `chrono`,
+/// [`SystemTime`](std::time::SystemTime) (requires the `std` crate feature) and
+/// `OffsetDateTime` from the `time` crate (requires the `time` crate feature).
+///
+/// When (de)serialized, a timestamp is represented as an integer. Fractional values
+/// are truncated during deserialization.
+///
+/// Timestamps support arithmetic with [`chrono::Duration`]s; sub-second parts
+/// of durations are truncated. As with `DateTime`, `+` and `-` operators panic on overflow;
+/// use [`Self::checked_add_signed()`] and [`Self::checked_sub_signed()`] for values
+/// coming from untrusted sources (e.g., token claims).
+///
+/// [JWT spec]: https://www.rfc-editor.org/rfc/rfc7519#section-2
+///
+/// # Examples
+///
+/// ```
+/// # use chrono::{DateTime, TimeZone, Utc};
+/// # use jwt_compact::Timestamp;
+/// let timestamp = Timestamp::from_secs(1_700_000_000);
+/// let datetime = DateTime::<Utc>::try_from(timestamp)?;
+/// assert_eq!(datetime, Utc.with_ymd_and_hms(2023, 11, 14, 22, 13, 20).unwrap());
+/// assert_eq!(Timestamp::from(datetime), timestamp);
+/// assert_eq!(serde_json::to_string(&timestamp)?, "1700000000");
+/// # Ok::<_, anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Timestamp(i64);
+
+impl Timestamp {
+    /// Unix epoch.
+    pub const UNIX_EPOCH: Self = Self(0);
+    /// Smallest representable timestamp.
+    pub const MIN: Self = Self(i64::MIN);
+    /// Largest representable timestamp.
+    pub const MAX: Self = Self(i64::MAX);
+
+    /// Creates a timestamp from the number of seconds since the Unix epoch.
+    pub const fn from_secs(secs: i64) -> Self {
+        Self(secs)
+    }
+
+    /// Returns the number of seconds since the Unix epoch.
+    pub const fn as_secs(self) -> i64 {
+        self.0
+    }
+
+    /// Adds the specified `duration` to this timestamp, returning `None` on overflow.
+    pub fn checked_add_signed(self, duration: Duration) -> Option<Self> {
+        self.0.checked_add(duration.num_seconds()).map(Self)
+    }
+
+    /// Subtracts the specified `duration` from this timestamp, returning `None` on overflow.
+    pub fn checked_sub_signed(self, duration: Duration) -> Option<Self> {
+        self.0.checked_sub(duration.num_seconds()).map(Self)
+    }
+
+    /// Returns the duration elapsed from `earlier` to this timestamp, which is negative
+    /// if `earlier` is later than this timestamp. The result saturates at the bounds
+    /// of the [`Duration`] range.
+    pub fn signed_duration_since(self, earlier: Self) -> Duration {
+        let secs = self.0.saturating_sub(earlier.0);
+        Duration::try_seconds(secs).unwrap_or(if secs > 0 {
+            Duration::MAX
+        } else {
+            Duration::MIN
+        })
+    }
+}
+
+impl ops::Add<Duration> for Timestamp {
+    type Output = Self;
+
+    fn add(self, rhs: Duration) -> Self::Output {
+        self.checked_add_signed(rhs)
+            .expect("`Timestamp + Duration` overflowed")
+    }
+}
+
+impl ops::Sub<Duration> for Timestamp {
+    type Output = Self;
+
+    fn sub(self, rhs: Duration) -> Self::Output {
+        self.checked_sub_signed(rhs)
+            .expect("`Timestamp - Duration` overflowed")
+    }
+}
+
+/// Equivalent to [`Timestamp::signed_duration_since()`].
+impl ops::Sub for Timestamp {
+    type Output = Duration;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.signed_duration_since(rhs)
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, formatter)
+    }
+}
+
+/// Error converting a [`Timestamp`] to or from a date / time representation that has
+/// a narrower range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct TimestampOverflow;
+
+impl fmt::Display for TimestampOverflow {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("UTC timestamp overflow")
+    }
+}
+
+impl core::error::Error for TimestampOverflow {}
+
+/// Truncates the sub-second part of the `datetime`.
+impl From<DateTime<Utc>> for Timestamp {
+    fn from(datetime: DateTime<Utc>) -> Self {
+        Self(datetime.timestamp())
+    }
+}
+
+impl TryFrom<Timestamp> for DateTime<Utc> {
+    type Error = TimestampOverflow;
+
+    fn try_from(timestamp: Timestamp) -> Result<Self, Self::Error> {
+        Utc.timestamp_opt(timestamp.0, 0)
+            .single()
+            .ok_or(TimestampOverflow)
+    }
+}
+
+/// Truncates the sub-second part of the `time`.
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl TryFrom<std::time::SystemTime> for Timestamp {
+    type Error = TimestampOverflow;
+
+    fn try_from(time: std::time::SystemTime) -> Result<Self, Self::Error> {
+        let secs = match time.duration_since(std::time::UNIX_EPOCH) {
+            Ok(elapsed) => i64::try_from(elapsed.as_secs()).map_err(|_| TimestampOverflow)?,
+            Err(err) => {
+                // Round towards negative infinity, so that truncation is consistent
+                // with positive timestamps.
+                let before_epoch = err.duration();
+                let secs = i64::try_from(before_epoch.as_secs()).map_err(|_| TimestampOverflow)?;
+                let secs = if before_epoch.subsec_nanos() > 0 {
+                    secs.checked_add(1).ok_or(TimestampOverflow)?
+                } else {
+                    secs
+                };
+                -secs
+            }
+        };
+        Ok(Self(secs))
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl TryFrom<Timestamp> for std::time::SystemTime {
+    type Error = TimestampOverflow;
+
+    fn try_from(timestamp: Timestamp) -> Result<Self, Self::Error> {
+        let offset = std::time::Duration::from_secs(timestamp.0.unsigned_abs());
+        if timestamp.0 >= 0 {
+            std::time::UNIX_EPOCH.checked_add(offset)
+        } else {
+            std::time::UNIX_EPOCH.checked_sub(offset)
+        }
+        .ok_or(TimestampOverflow)
+    }
+}
+
+/// Truncates the sub-second part of the `datetime`.
+#[cfg(feature = "time")]
+#[cfg_attr(docsrs, doc(cfg(feature = "time")))]
+impl From<time::OffsetDateTime> for Timestamp {
+    fn from(datetime: time::OffsetDateTime) -> Self {
+        Self(datetime.unix_timestamp())
+    }
+}
+
+#[cfg(feature = "time")]
+#[cfg_attr(docsrs, doc(cfg(feature = "time")))]
+impl TryFrom<Timestamp> for time::OffsetDateTime {
+    type Error = TimestampOverflow;
+
+    fn try_from(timestamp: Timestamp) -> Result<Self, Self::Error> {
+        Self::from_unix_timestamp(timestamp.0).map_err(|_| TimestampOverflow)
+    }
+}
+
+impl Serialize for Timestamp {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i64(self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Timestamp {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct TimestampVisitor;
+
+        impl Visitor<'_> for TimestampVisitor {
+            type Value = Timestamp;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("UTC timestamp")
+            }
+
+            fn visit_i64<E: DeError>(self, value: i64) -> Result<Self::Value, E> {
+                Ok(Timestamp(value))
+            }
+
+            fn visit_u64<E: DeError>(self, value: u64) -> Result<Self::Value, E> {
+                i64::try_from(value)
+                    .map(Timestamp)
+                    .map_err(|_| E::custom(TimestampOverflow))
+            }
+
+            #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+            // ^ The value is checked to be within the `i64` range before the conversion
+            fn visit_f64<E: DeError>(self, value: f64) -> Result<Self::Value, E> {
+                // `i64::MAX as f64` rounds up to 2^63, which is outside the `i64` range.
+                if value >= i64::MIN as f64 && value < i64::MAX as f64 {
+                    Ok(Timestamp(value as i64))
+                } else {
+                    Err(E::custom(TimestampOverflow))
+                }
+            }
+        }
+
+        deserializer.deserialize_i64(TimestampVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alloc::ToString;
+
+    #[test]
+    fn converting_timestamp_to_chrono() {
+        let datetime = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let timestamp = Timestamp::from(datetime);
+        assert_eq!(timestamp.as_secs(), 1_704_067_200);
+        assert_eq!(DateTime::<Utc>::try_from(timestamp).unwrap(), datetime);
+
+        let err = DateTime::<Utc>::try_from(Timestamp::from_secs(i64::MAX)).unwrap_err();
+        assert_eq!(err, TimestampOverflow);
+    }
+
+    #[test]
+    fn timestamp_arithmetic() {
+        let timestamp = Timestamp::from_secs(1_000);
+        let duration = Duration::try_milliseconds(10_500).unwrap();
+        assert_eq!(timestamp + duration, Timestamp::from_secs(1_010));
+        assert_eq!(timestamp - duration, Timestamp::from_secs(990));
+        assert_eq!(
+            Timestamp::from_secs(1_010) - timestamp,
+            Duration::try_seconds(10).unwrap()
+        );
+        assert_eq!(
+            timestamp - Timestamp::from_secs(1_010),
+            Duration::try_seconds(-10).unwrap()
+        );
+
+        assert_eq!(Timestamp::MAX.checked_add_signed(duration), None);
+        assert_eq!(Timestamp::MIN.checked_sub_signed(duration), None);
+        assert_eq!(Timestamp::MAX - Timestamp::MIN, Duration::MAX);
+        assert_eq!(Timestamp::MIN - Timestamp::MAX, Duration::MIN);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn converting_timestamp_to_system_time() {
+        use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+        let time = UNIX_EPOCH + Duration::from_millis(1_704_067_200_500);
+        let timestamp = Timestamp::try_from(time).unwrap();
+        assert_eq!(timestamp, Timestamp::from_secs(1_704_067_200));
+        assert_eq!(
+            SystemTime::try_from(timestamp).unwrap(),
+            UNIX_EPOCH + Duration::from_secs(1_704_067_200)
+        );
+
+        let time = UNIX_EPOCH - Duration::from_millis(1_500);
+        let timestamp = Timestamp::try_from(time).unwrap();
+        assert_eq!(timestamp, Timestamp::from_secs(-2));
+        assert_eq!(
+            SystemTime::try_from(timestamp).unwrap(),
+            UNIX_EPOCH - Duration::from_secs(2)
+        );
+    }
+
+    #[test]
+    fn timestamp_serialization() {
+        let timestamp: Timestamp = serde_json::from_str("1704067200").unwrap();
+        assert_eq!(timestamp, Timestamp::from_secs(1_704_067_200));
+        let timestamp: Timestamp = serde_json::from_str("1704067200.75").unwrap();
+        assert_eq!(timestamp, Timestamp::from_secs(1_704_067_200));
+        let timestamp: Timestamp = serde_json::from_str("-1").unwrap();
+        assert_eq!(timestamp, Timestamp::from_secs(-1));
+        assert_eq!(serde_json::to_string(&timestamp).unwrap(), "-1");
+
+        let err = serde_json::from_str::<Timestamp>("18446744073709551615").unwrap_err();
+        assert!(err.to_string().contains("UTC timestamp overflow"), "{err}");
+        let err = serde_json::from_str::<Timestamp>("1e20").unwrap_err();
+        assert!(err.to_string().contains("UTC timestamp overflow"), "{err}");
+        let err = serde_json::from_str::<Timestamp>("\"1704067200\"").unwrap_err();
+        assert!(err.to_string().contains("UTC timestamp"), "{err}");
+    }
+}
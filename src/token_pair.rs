@@ -0,0 +1,488 @@
+//! Issuance of linked access and refresh tokens.
+//!
+//! [`TokenPairIssuer`] mints a short-lived access token together with a long-lived
+//! refresh token. Both tokens carry the same session ID (the `sid` claim), and the refresh
+//! token references the ID of the access token it was issued with (the `ati` claim, named
+//! after the similar claim in [RFC 8693]). On refresh, [`TokenPairIssuer::validate_refresh()`]
+//! checks this linkage, so that a refresh token cannot be combined with an access token
+//! from another session.
+//!
+//! The access and refresh tokens may use different algorithms; e.g., access tokens
+//! can be signed with an asymmetric algorithm so that they can be verified by resource servers,
+//! while refresh tokens, which are only verified by the issuer, can use a MAC.
+//!
+//! [RFC 8693]: https://www.rfc-editor.org/rfc/rfc8693.html
+
+use base64ct::{Base64UrlUnpadded, Encoding};
+use chrono::Duration;
+use rand_core::{CryptoRng, RngCore};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use core::fmt;
+
+use crate::{
+    alg::SigningKey, alloc::String, Algorithm, AlgorithmExt, Claims, CreationError, Header,
+    TimeOptions, Timestamp, Token, TokenString, UntrustedToken, ValidationError,
+};
+
+/// Default lifetime of access tokens.
+const DEFAULT_ACCESS_LIFETIME: Duration = Duration::minutes(15);
+/// Default lifetime of refresh tokens.
+const DEFAULT_REFRESH_LIFETIME: Duration = Duration::days(30);
+/// Byte length of randomly generated token and session IDs.
+const ID_LEN: usize = 16;
+
+fn random_id<R: CryptoRng + RngCore>(rng: &mut R) -> String {
+    let mut bytes = [0_u8; ID_LEN];
+    rng.fill_bytes(&mut bytes);
+    Base64UrlUnpadded::encode_string(&bytes)
+}
+
+/// Custom claims of an access token issued by [`TokenPairIssuer`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct AccessClaims<T> {
+    /// Token ID (`jti` claim).
+    #[serde(rename = "jti")]
+    pub token_id: String,
+    /// Session ID (`sid` claim) shared with the linked refresh token.
+    #[serde(rename = "sid")]
+    pub session_id: String,
+    /// Application-specific claims.
+    #[serde(flatten)]
+    pub custom: T,
+}
+
+/// Custom claims of a refresh token issued by [`TokenPairIssuer`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct RefreshClaims {
+    /// Token ID (`jti` claim).
+    #[serde(rename = "jti")]
+    pub token_id: String,
+    /// Session ID (`sid` claim) shared with the linked access token.
+    #[serde(rename = "sid")]
+    pub session_id: String,
+    /// ID of the linked access token (`ati` claim).
+    #[serde(rename = "ati")]
+    pub access_token_id: String,
+}
+
+/// Access and refresh tokens produced by [`TokenPairIssuer`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct TokenPair {
+    /// Access token.
+    pub access_token: TokenString,
+    /// Refresh token.
+    pub refresh_token: TokenString,
+    /// Session ID shared by both tokens.
+    pub session_id: String,
+}
+
+/// Errors that can occur when refreshing a [`TokenPair`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum TokenPairError {
+    /// Access token has failed validation (e.g., has an invalid signature). Expiration
+    /// of the access token is not checked.
+    AccessToken(ValidationError),
+    /// Refresh token has failed validation (e.g., has an invalid signature or is expired).
+    RefreshToken(ValidationError),
+    /// Access and refresh tokens belong to different sessions.
+    SessionMismatch,
+    /// Refresh token was not issued together with the access token.
+    AccessTokenMismatch,
+    /// Error creating a new token pair.
+    Creation(CreationError),
+}
+
+impl fmt::Display for TokenPairError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AccessToken(err) => write!(formatter, "access token validation failed: {err}"),
+            Self::RefreshToken(err) => write!(formatter, "refresh token validation failed: {err}"),
+            Self::SessionMismatch => {
+                formatter.write_str("access and refresh tokens belong to different sessions")
+            }
+            Self::AccessTokenMismatch => {
+                formatter.write_str("refresh token is not linked to the access token")
+            }
+            Self::Creation(err) => write!(formatter, "cannot create token pair: {err}"),
+        }
+    }
+}
+
+impl core::error::Error for TokenPairError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::AccessToken(err) | Self::RefreshToken(err) => Some(err),
+            Self::Creation(err) => Some(err),
+            Self::SessionMismatch | Self::AccessTokenMismatch => None,
+        }
+    }
+}
+
+impl From<CreationError> for TokenPairError {
+    fn from(err: CreationError) -> Self {
+        Self::Creation(err)
+    }
+}
+
+/// Issuer of linked access and refresh tokens.
+///
+/// See the [module-level docs](self) for the description of the token linkage.
+///
+/// # Examples
+///
+/// ```
+/// # use chrono::Duration;
+/// # use rand::thread_rng;
+/// use jwt_compact::{
+///     prelude::*, alg::{Hs256, Hs384, Hs256Key, Hs384Key},
+///     token_pair::TokenPairIssuer, DynamicClaims,
+/// };
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let issuer = TokenPairIssuer::new(
+///     Hs256,
+///     Hs256Key::new(b"super_secret_key_donut_steel"),
+///     Hs384,
+///     Hs384Key::new(b"another_secret_key_for_refresh_tokens"),
+/// )
+/// .with_access_lifetime(Duration::try_minutes(5).unwrap());
+///
+/// let time_options = TimeOptions::default();
+/// let custom = DynamicClaims::new().with_claim("sub", "alice");
+/// let pair = issuer.issue(&custom, &time_options, &mut thread_rng())?;
+///
+/// // Later, the client presents both tokens to get a new pair.
+/// let access_token = UntrustedToken::new(&pair.access_token)?;
+/// let refresh_token = UntrustedToken::new(&pair.refresh_token)?;
+/// let new_pair = issuer.refresh::<DynamicClaims, _, _>(
+///     &access_token,
+///     &refresh_token,
+///     &time_options,
+///     &mut thread_rng(),
+/// )?;
+/// assert_eq!(new_pair.session_id, pair.session_id);
+/// # Ok(())
+/// # }
+/// ```
+pub struct TokenPairIssuer<A: Algorithm, R: Algorithm = A> {
+    access_algorithm: A,
+    access_signing_key: A::SigningKey,
+    access_verifying_key: A::VerifyingKey,
+    access_lifetime: Duration,
+    refresh_algorithm: R,
+    refresh_signing_key: R::SigningKey,
+    refresh_verifying_key: R::VerifyingKey,
+    refresh_lifetime: Duration,
+}
+
+impl<A: Algorithm, R: Algorithm> fmt::Debug for TokenPairIssuer<A, R> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("TokenPairIssuer")
+            .field("access_algorithm", &self.access_algorithm.name())
+            .field("access_lifetime", &self.access_lifetime)
+            .field("refresh_algorithm", &self.refresh_algorithm.name())
+            .field("refresh_lifetime", &self.refresh_lifetime)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<A, R> TokenPairIssuer<A, R>
+where
+    A: Algorithm,
+    A::SigningKey: SigningKey<A>,
+    R: Algorithm,
+    R::SigningKey: SigningKey<R>,
+{
+    /// Creates an issuer with the specified algorithms and signing keys for access and refresh
+    /// tokens. By default, access tokens expire in 15 minutes, and refresh tokens in 30 days.
+    pub fn new(
+        access_algorithm: A,
+        access_signing_key: A::SigningKey,
+        refresh_algorithm: R,
+        refresh_signing_key: R::SigningKey,
+    ) -> Self {
+        Self {
+            access_algorithm,
+            access_verifying_key: access_signing_key.to_verifying_key(),
+            access_signing_key,
+            access_lifetime: DEFAULT_ACCESS_LIFETIME,
+            refresh_algorithm,
+            refresh_verifying_key: refresh_signing_key.to_verifying_key(),
+            refresh_signing_key,
+            refresh_lifetime: DEFAULT_REFRESH_LIFETIME,
+        }
+    }
+
+    /// Sets the lifetime of access tokens.
+    #[must_use]
+    pub fn with_access_lifetime(mut self, lifetime: Duration) -> Self {
+        self.access_lifetime = lifetime;
+        self
+    }
+
+    /// Sets the lifetime of refresh tokens.
+    #[must_use]
+    pub fn with_refresh_lifetime(mut self, lifetime: Duration) -> Self {
+        self.refresh_lifetime = lifetime;
+        self
+    }
+
+    /// Issues a token pair for a new session. `custom` claims are included into the access token.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either of the tokens cannot be created.
+    pub fn issue<T, Rng, F>(
+        &self,
+        custom: &T,
+        time_options: &TimeOptions<F>,
+        rng: &mut Rng,
+    ) -> Result<TokenPair, CreationError>
+    where
+        T: Serialize,
+        Rng: CryptoRng + RngCore,
+        F: Fn() -> Timestamp,
+    {
+        let session_id = random_id(rng);
+        self.issue_for_session(session_id, custom, time_options, rng)
+    }
+
+    fn issue_for_session<T, Rng, F>(
+        &self,
+        session_id: String,
+        custom: &T,
+        time_options: &TimeOptions<F>,
+        rng: &mut Rng,
+    ) -> Result<TokenPair, CreationError>
+    where
+        T: Serialize,
+        Rng: CryptoRng + RngCore,
+        F: Fn() -> Timestamp,
+    {
+        let access_claims = AccessClaims {
+            token_id: random_id(rng),
+            session_id: session_id.clone(),
+            custom,
+        };
+        let refresh_claims = RefreshClaims {
+            token_id: random_id(rng),
+            session_id: session_id.clone(),
+            access_token_id: access_claims.token_id.clone(),
+        };
+
+        let access_claims = Claims::new(access_claims)
+            .set_duration_and_issuance(time_options, self.access_lifetime);
+        let access_token = self.access_algorithm.token(
+            &Header::empty(),
+            &access_claims,
+            &self.access_signing_key,
+        )?;
+        let refresh_claims = Claims::new(refresh_claims)
+            .set_duration_and_issuance(time_options, self.refresh_lifetime);
+        let refresh_token = self.refresh_algorithm.token(
+            &Header::empty(),
+            &refresh_claims,
+            &self.refresh_signing_key,
+        )?;
+
+        Ok(TokenPair {
+            access_token,
+            refresh_token,
+            session_id,
+        })
+    }
+
+    /// Validates a refresh token together with the access token it was issued with.
+    /// The refresh token must have a valid signature and must not be expired; the access token
+    /// must have a valid signature, but may be expired. Returns the validated access token.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if validation fails or the tokens are not linked.
+    pub fn validate_refresh<T, F>(
+        &self,
+        access_token: &UntrustedToken<'_>,
+        refresh_token: &UntrustedToken<'_>,
+        time_options: &TimeOptions<F>,
+    ) -> Result<Token<AccessClaims<T>>, TokenPairError>
+    where
+        T: DeserializeOwned,
+        F: Fn() -> Timestamp,
+    {
+        let refresh_token: Token<RefreshClaims> = self
+            .refresh_algorithm
+            .validator(&self.refresh_verifying_key)
+            .validate(refresh_token)
+            .map_err(TokenPairError::RefreshToken)?;
+        refresh_token
+            .claims()
+            .validate_expiration(time_options)
+            .map_err(TokenPairError::RefreshToken)?;
+        let access_token: Token<AccessClaims<T>> = self
+            .access_algorithm
+            .validator(&self.access_verifying_key)
+            .validate(access_token)
+            .map_err(TokenPairError::AccessToken)?;
+
+        let refresh_claims = &refresh_token.claims().custom;
+        let access_claims = &access_token.claims().custom;
+        if refresh_claims.session_id != access_claims.session_id {
+            return Err(TokenPairError::SessionMismatch);
+        }
+        if refresh_claims.access_token_id != access_claims.token_id {
+            return Err(TokenPairError::AccessTokenMismatch);
+        }
+        Ok(access_token)
+    }
+
+    /// Validates the token pair as per [`Self::validate_refresh()`] and issues a new pair
+    /// for the same session with the same custom claims in the access token.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if validation fails or the new tokens cannot be created.
+    pub fn refresh<T, Rng, F>(
+        &self,
+        access_token: &UntrustedToken<'_>,
+        refresh_token: &UntrustedToken<'_>,
+        time_options: &TimeOptions<F>,
+        rng: &mut Rng,
+    ) -> Result<TokenPair, TokenPairError>
+    where
+        T: Serialize + DeserializeOwned,
+        Rng: CryptoRng + RngCore,
+        F: Fn() -> Timestamp,
+    {
+        let access_token =
+            self.validate_refresh::<T, _>(access_token, refresh_token, time_options)?;
+        let AccessClaims {
+            session_id, custom, ..
+        } = access_token.into_parts().1.custom;
+        Ok(self.issue_for_session(session_id, &custom, time_options, rng)?)
+    }
+}
+
+#[cfg(all(test, feature = "clock"))]
+mod tests {
+    use assert_matches::assert_matches;
+    use chrono::{TimeZone, Utc};
+    use rand::thread_rng;
+
+    use super::*;
+    use crate::{
+        alg::{Hs256, Hs256Key, Hs384, Hs384Key},
+        DynamicClaims,
+    };
+
+    fn issuer() -> TokenPairIssuer<Hs256, Hs384> {
+        TokenPairIssuer::new(
+            Hs256,
+            Hs256Key::new(b"super_secret_key_donut_steel"),
+            Hs384,
+            Hs384Key::new(b"another_secret_key_for_refresh_tokens"),
+        )
+        .with_access_lifetime(Duration::try_minutes(5).unwrap())
+        .with_refresh_lifetime(Duration::try_hours(1).unwrap())
+    }
+
+    fn time_options(minutes: i64) -> TimeOptions<impl Fn() -> Timestamp> {
+        let now = Timestamp::from(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap())
+            + Duration::try_minutes(minutes).unwrap();
+        TimeOptions::new(Duration::zero(), move || now)
+    }
+
+    #[test]
+    fn issuing_and_refreshing_token_pair() {
+        let issuer = issuer();
+        let custom = DynamicClaims::new().with_claim("sub", "alice");
+        let pair = issuer
+            .issue(&custom, &time_options(0), &mut thread_rng())
+            .unwrap();
+        let access_token = UntrustedToken::new(&pair.access_token).unwrap();
+        assert_eq!(access_token.algorithm(), "HS256");
+        let refresh_token = UntrustedToken::new(&pair.refresh_token).unwrap();
+        assert_eq!(refresh_token.algorithm(), "HS384");
+
+        // The access token is expired, but the refresh token is not.
+        let later = time_options(10);
+        let validated = issuer
+            .validate_refresh::<DynamicClaims, _>(&access_token, &refresh_token, &later)
+            .unwrap();
+        let claims = &validated.claims().custom;
+        assert_eq!(claims.session_id, pair.session_id);
+        assert_eq!(claims.custom.get_str("sub"), Some("alice"));
+
+        let new_pair = issuer
+            .refresh::<DynamicClaims, _, _>(
+                &access_token,
+                &refresh_token,
+                &later,
+                &mut thread_rng(),
+            )
+            .unwrap();
+        assert_eq!(new_pair.session_id, pair.session_id);
+        let new_access_token = UntrustedToken::new(&new_pair.access_token).unwrap();
+        let new_access_token: Token<AccessClaims<DynamicClaims>> = Hs256
+            .validator(&Hs256Key::new(b"super_secret_key_donut_steel"))
+            .validate(&new_access_token)
+            .unwrap();
+        let new_claims = new_access_token.claims();
+        new_claims.validate_expiration(&later).unwrap();
+        assert_ne!(new_claims.custom.token_id, claims.token_id);
+        assert_eq!(new_claims.custom.custom.get_str("sub"), Some("alice"));
+
+        // The refresh token is expired.
+        let err = issuer
+            .validate_refresh::<DynamicClaims, _>(&access_token, &refresh_token, &time_options(61))
+            .unwrap_err();
+        assert_matches!(err, TokenPairError::RefreshToken(ValidationError::Expired));
+    }
+
+    #[test]
+    fn unlinked_tokens_are_rejected() {
+        let issuer = issuer();
+        let options = time_options(0);
+        let custom = DynamicClaims::new();
+        let pair = issuer.issue(&custom, &options, &mut thread_rng()).unwrap();
+        let other_pair = issuer.issue(&custom, &options, &mut thread_rng()).unwrap();
+
+        let access_token = UntrustedToken::new(&pair.access_token).unwrap();
+        let other_refresh_token = UntrustedToken::new(&other_pair.refresh_token).unwrap();
+        let err = issuer
+            .validate_refresh::<DynamicClaims, _>(&access_token, &other_refresh_token, &options)
+            .unwrap_err();
+        assert_matches!(err, TokenPairError::SessionMismatch);
+
+        // Refreshed pair shares the session, but not the access token.
+        let refresh_token = UntrustedToken::new(&pair.refresh_token).unwrap();
+        let new_pair = issuer
+            .refresh::<DynamicClaims, _, _>(
+                &access_token,
+                &refresh_token,
+                &options,
+                &mut thread_rng(),
+            )
+            .unwrap();
+        let new_access_token = UntrustedToken::new(&new_pair.access_token).unwrap();
+        let err = issuer
+            .validate_refresh::<DynamicClaims, _>(&new_access_token, &refresh_token, &options)
+            .unwrap_err();
+        assert_matches!(err, TokenPairError::AccessTokenMismatch);
+
+        // Tokens in the wrong order fail validation.
+        let err = issuer
+            .validate_refresh::<DynamicClaims, _>(&refresh_token, &access_token, &options)
+            .unwrap_err();
+        assert_matches!(err, TokenPairError::RefreshToken(_));
+        let err = issuer
+            .validate_refresh::<DynamicClaims, _>(&refresh_token, &refresh_token, &options)
+            .unwrap_err();
+        assert_matches!(err, TokenPairError::AccessToken(_));
+    }
+}
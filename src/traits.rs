@@ -1,16 +1,18 @@
 //! Key traits defined by the crate.
 
 use base64ct::{Base64UrlUnpadded, Encoding};
-use serde::{de::DeserializeOwned, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
-use core::{marker::PhantomData, num::NonZeroUsize};
+use core::{fmt, future::Future, marker::PhantomData, num::NonZeroUsize, pin::Pin};
 
-#[cfg(feature = "ciborium")]
+#[cfg(all(feature = "ciborium", feature = "sign"))]
 use crate::error::CborSerError;
 use crate::{
-    alloc::{Cow, String, ToOwned, Vec},
+    alloc::{Box, Cow, String, ToOwned, Vec},
+    key_store::{KeyStore, KeyStoreValidator},
     token::CompleteHeader,
-    Claims, CreationError, Header, SignedToken, Token, UntrustedToken, ValidationError,
+    Claims, CreationError, Header, SignedToken, Token, TokenString, UntrustedToken,
+    ValidationError, VerifiedToken,
 };
 
 /// Signature for a certain JWT signing [`Algorithm`].
@@ -53,6 +55,8 @@ pub trait Algorithm {
     fn name(&self) -> Cow<'static, str>;
 
     /// Signs a `message` with the `signing_key`.
+    #[cfg(feature = "sign")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "sign")))]
     fn sign(&self, signing_key: &Self::SigningKey, message: &[u8]) -> Self::Signature;
 
     /// Verifies the `message` against the `signature` and `verifying_key`.
@@ -62,6 +66,23 @@ pub trait Algorithm {
         verifying_key: &Self::VerifyingKey,
         message: &[u8],
     ) -> bool;
+
+    /// Verifies multiple `(signature, message)` pairs against the same `verifying_key`.
+    /// Returns the verification outcome for each pair, in the same order as `signatures`.
+    ///
+    /// The default implementation calls [`Self::verify_signature()`] for each pair. Algorithms
+    /// may override it to amortize key preparation across the batch or to use batch
+    /// signature verification. Used by [`AlgorithmExt::validate_batch()`].
+    fn verify_signatures(
+        &self,
+        verifying_key: &Self::VerifyingKey,
+        signatures: &[(Self::Signature, &[u8])],
+    ) -> Vec<bool> {
+        signatures
+            .iter()
+            .map(|(signature, message)| self.verify_signature(signature, verifying_key, message))
+            .collect()
+    }
 }
 
 /// Algorithm that uses a custom name when creating and validating tokens.
@@ -112,6 +133,7 @@ impl<A: Algorithm> Algorithm for Renamed<A> {
         Cow::Borrowed(self.name)
     }
 
+    #[cfg(feature = "sign")]
     fn sign(&self, signing_key: &Self::SigningKey, message: &[u8]) -> Self::Signature {
         self.inner.sign(signing_key, message)
     }
@@ -125,29 +147,71 @@ impl<A: Algorithm> Algorithm for Renamed<A> {
         self.inner
             .verify_signature(signature, verifying_key, message)
     }
+
+    fn verify_signatures(
+        &self,
+        verifying_key: &Self::VerifyingKey,
+        signatures: &[(Self::Signature, &[u8])],
+    ) -> Vec<bool> {
+        self.inner.verify_signatures(verifying_key, signatures)
+    }
+}
+
+impl<A: PrepareVerifyingKey> PrepareVerifyingKey for Renamed<A> {
+    type PreparedKey = A::PreparedKey;
+
+    fn prepare_verifying_key(&self, verifying_key: &Self::VerifyingKey) -> Self::PreparedKey {
+        self.inner.prepare_verifying_key(verifying_key)
+    }
+
+    fn verify_prepared(
+        &self,
+        signature: &Self::Signature,
+        prepared_key: &Self::PreparedKey,
+        message: &[u8],
+    ) -> bool {
+        self.inner.verify_prepared(signature, prepared_key, message)
+    }
 }
 
 /// Automatically implemented extensions of the `Algorithm` trait.
 pub trait AlgorithmExt: Algorithm {
     /// Creates a new token and serializes it to string.
+    #[cfg(feature = "sign")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "sign")))]
     fn token<T>(
         &self,
         header: &Header<impl Serialize>,
         claims: &Claims<T>,
         signing_key: &Self::SigningKey,
-    ) -> Result<String, CreationError>
+    ) -> Result<TokenString, CreationError>
     where
         T: Serialize;
 
     /// Creates a new token with CBOR-encoded claims and serializes it to string.
-    #[cfg(feature = "ciborium")]
-    #[cfg_attr(docsrs, doc(cfg(feature = "ciborium")))]
+    #[cfg(all(feature = "ciborium", feature = "sign"))]
+    #[cfg_attr(docsrs, doc(cfg(all(feature = "ciborium", feature = "sign"))))]
     fn compact_token<T>(
         &self,
         header: &Header<impl Serialize>,
         claims: &Claims<T>,
         signing_key: &Self::SigningKey,
-    ) -> Result<String, CreationError>
+    ) -> Result<TokenString, CreationError>
+    where
+        T: Serialize;
+
+    /// Serializes the header and claims of a new token without signing it. This is useful
+    /// if the signature is produced outside this crate, e.g., by a threshold signing protocol
+    /// such as FROST, or via multi-party computation.
+    ///
+    /// The signature must be computed over [`UnsignedToken::signing_input()`] and then attached
+    /// to the token using [`UnsignedToken::attach_signature()`]. The resulting token
+    /// is validated by this algorithm as usual.
+    fn unsigned_token<T>(
+        &self,
+        header: &Header<impl Serialize>,
+        claims: &Claims<T>,
+    ) -> Result<UnsignedToken<Self>, CreationError>
     where
         T: Serialize;
 
@@ -155,6 +219,33 @@ pub trait AlgorithmExt: Algorithm {
     /// The validator can then be used to validate integrity of one or more tokens.
     fn validator<'a, T>(&'a self, verifying_key: &'a Self::VerifyingKey) -> Validator<'a, Self, T>;
 
+    /// Validates integrity of multiple tokens signed with the same `verifying_key`.
+    /// Returns the validation result for each token, in the same order as `tokens`.
+    ///
+    /// This is more efficient than validating tokens one by one if the algorithm
+    /// supports [batch verification](Algorithm::verify_signatures()); e.g., `HS*` algorithms
+    /// prepare the key only once per batch. This is a shortcut for
+    /// [`Validator::validate_batch()`], which should be used directly if critical header
+    /// extensions need to be declared as understood.
+    fn validate_batch<T, H>(
+        &self,
+        tokens: &[UntrustedToken<'_, H>],
+        verifying_key: &Self::VerifyingKey,
+    ) -> Vec<Result<Token<T, H>, ValidationError>>
+    where
+        T: DeserializeOwned,
+        H: Clone;
+
+    /// Creates a JWT validator that selects verifying keys from the specified [`KeyStore`]
+    /// based on the token header (e.g., its `kid` field).
+    fn key_store_validator<'a, T, S>(
+        &'a self,
+        key_store: &'a S,
+    ) -> KeyStoreValidator<'a, Self, S, T>
+    where
+        Self: Sized,
+        S: KeyStore<Self>;
+
     /// Validates the token integrity against the provided `verifying_key`.
     #[deprecated = "Use `.validator().validate()` for added flexibility"]
     fn validate_integrity<T>(
@@ -180,49 +271,35 @@ pub trait AlgorithmExt: Algorithm {
 }
 
 impl<A: Algorithm> AlgorithmExt for A {
+    #[cfg(feature = "sign")]
     fn token<T>(
         &self,
         header: &Header<impl Serialize>,
         claims: &Claims<T>,
         signing_key: &Self::SigningKey,
-    ) -> Result<String, CreationError>
+    ) -> Result<TokenString, CreationError>
     where
         T: Serialize,
     {
-        let complete_header = CompleteHeader {
-            algorithm: self.name(),
-            content_type: None,
-            inner: header,
-        };
-        let header = serde_json::to_string(&complete_header).map_err(CreationError::Header)?;
-        let mut buffer = Vec::new();
-        encode_base64_buf(&header, &mut buffer);
-
-        let claims = serde_json::to_string(claims).map_err(CreationError::Claims)?;
-        buffer.push(b'.');
-        encode_base64_buf(&claims, &mut buffer);
-
+        let buffer = signing_input(self.name(), header, claims)?;
         let signature = self.sign(signing_key, &buffer);
-        buffer.push(b'.');
-        encode_base64_buf(signature.as_bytes(), &mut buffer);
-
-        // SAFETY: safe by construction: base64 alphabet and `.` char are valid UTF-8.
-        Ok(unsafe { String::from_utf8_unchecked(buffer) })
+        Ok(complete_token(buffer, &signature))
     }
 
-    #[cfg(feature = "ciborium")]
+    #[cfg(all(feature = "ciborium", feature = "sign"))]
     fn compact_token<T>(
         &self,
         header: &Header<impl Serialize>,
         claims: &Claims<T>,
         signing_key: &Self::SigningKey,
-    ) -> Result<String, CreationError>
+    ) -> Result<TokenString, CreationError>
     where
         T: Serialize,
     {
         let complete_header = CompleteHeader {
             algorithm: self.name(),
             content_type: Some("CBOR".to_owned()),
+            critical: None,
             inner: header,
         };
         let header = serde_json::to_string(&complete_header).map_err(CreationError::Header)?;
@@ -240,17 +317,55 @@ impl<A: Algorithm> AlgorithmExt for A {
         encode_base64_buf(&serialized_claims, &mut buffer);
 
         let signature = self.sign(signing_key, &buffer);
-        buffer.push(b'.');
-        encode_base64_buf(signature.as_bytes(), &mut buffer);
+        Ok(complete_token(buffer, &signature))
+    }
 
-        // SAFETY: safe by construction: base64 alphabet and `.` char are valid UTF-8.
-        Ok(unsafe { String::from_utf8_unchecked(buffer) })
+    fn unsigned_token<T>(
+        &self,
+        header: &Header<impl Serialize>,
+        claims: &Claims<T>,
+    ) -> Result<UnsignedToken<Self>, CreationError>
+    where
+        T: Serialize,
+    {
+        Ok(UnsignedToken {
+            signing_input: signing_input(self.name(), header, claims)?,
+            _algorithm: PhantomData,
+        })
     }
 
     fn validator<'a, T>(&'a self, verifying_key: &'a Self::VerifyingKey) -> Validator<'a, Self, T> {
         Validator {
             algorithm: self,
             verifying_key,
+            understood_extensions: &[],
+            _claims: PhantomData,
+        }
+    }
+
+    fn validate_batch<T, H>(
+        &self,
+        tokens: &[UntrustedToken<'_, H>],
+        verifying_key: &Self::VerifyingKey,
+    ) -> Vec<Result<Token<T, H>, ValidationError>>
+    where
+        T: DeserializeOwned,
+        H: Clone,
+    {
+        self.validator::<T>(verifying_key).validate_batch(tokens)
+    }
+
+    fn key_store_validator<'a, T, S>(
+        &'a self,
+        key_store: &'a S,
+    ) -> KeyStoreValidator<'a, Self, S, T>
+    where
+        S: KeyStore<Self>,
+    {
+        KeyStoreValidator {
+            algorithm: self,
+            key_store,
+            understood_extensions: &[],
             _claims: PhantomData,
         }
     }
@@ -279,12 +394,70 @@ impl<A: Algorithm> AlgorithmExt for A {
     }
 }
 
+/// Token with the serialized header and claims that awaits a signature produced outside
+/// this crate. Produced by the [`AlgorithmExt::unsigned_token()`] method.
+///
+/// # Examples
+///
+/// ```
+/// # use chrono::Duration;
+/// use jwt_compact::{prelude::*, alg::{Hs256, Hs256Key}, Algorithm};
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let claims = Claims::new(serde_json::json!({ "sub": "alice" }))
+///     .set_duration(&TimeOptions::default(), Duration::try_minutes(10).unwrap());
+/// let unsigned_token = Hs256.unsigned_token(&Header::empty(), &claims)?;
+///
+/// // The signature may be produced by an external party.
+/// let key = Hs256Key::new(b"super_secret_key_donut_steel");
+/// let signature = Hs256.sign(&key, unsigned_token.signing_input());
+/// let token = unsigned_token.attach_signature(&signature);
+///
+/// let token = UntrustedToken::new(&token)?;
+/// Hs256.validator::<serde_json::Value>(&key).validate(&token)?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct UnsignedToken<A: Algorithm + ?Sized> {
+    signing_input: Vec<u8>,
+    _algorithm: PhantomData<fn(&A)>,
+}
+
+impl<A: Algorithm + ?Sized> fmt::Debug for UnsignedToken<A> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("UnsignedToken")
+            .field(
+                "signing_input",
+                &String::from_utf8_lossy(&self.signing_input),
+            )
+            .finish()
+    }
+}
+
+impl<A: Algorithm + ?Sized> UnsignedToken<A> {
+    /// Returns the signing input of the token, i.e., base64url-encoded header and claims
+    /// separated by a `.` char. This is the message that should be signed.
+    pub fn signing_input(&self) -> &[u8] {
+        &self.signing_input
+    }
+
+    /// Attaches a signature over the [signing input](Self::signing_input()) to this token,
+    /// and serializes the token to string.
+    ///
+    /// The signature is not checked; a token with an invalid signature will fail validation.
+    pub fn attach_signature(self, signature: &A::Signature) -> TokenString {
+        complete_token(self.signing_input, signature)
+    }
+}
+
 /// Validator for a certain signing [`Algorithm`] associated with a specific verifying key
 /// and a claims type. Produced by the [`AlgorithmExt::validator()`] method.
 #[derive(Debug)]
 pub struct Validator<'a, A: Algorithm + ?Sized, T> {
     algorithm: &'a A,
     verifying_key: &'a A::VerifyingKey,
+    understood_extensions: &'a [&'a str],
     _claims: PhantomData<fn() -> T>,
 }
 
@@ -296,6 +469,54 @@ impl<A: Algorithm + ?Sized, T> Clone for Validator<'_, A, T> {
 
 impl<A: Algorithm + ?Sized, T> Copy for Validator<'_, A, T> {}
 
+impl<'a, A: Algorithm + ?Sized, T> Validator<'a, A, T> {
+    /// Declares [critical header extensions][crit] understood by the application.
+    /// By default, no extensions are understood, and validation fails with
+    /// [`ValidationError::UnknownCriticalExtension`] for any token listing extensions
+    /// in its `crit` header field.
+    ///
+    /// Declaring an extension understood means that the application processes it after
+    /// validation; the validator itself does not interpret extensions.
+    ///
+    /// [crit]: https://www.rfc-editor.org/rfc/rfc7515#section-4.1.11
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use jwt_compact::{alg::{Hs256, Hs256Key}, prelude::*, Empty, ValidationError};
+    /// # use serde::Serialize;
+    /// #[derive(Debug, Clone, Serialize, serde::Deserialize)]
+    /// struct Extensions {
+    ///     crit: Vec<String>,
+    ///     exp_policy: String,
+    /// }
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// # let key = Hs256Key::new(b"super_secret_key_donut_steel");
+    /// let header = Header::new(Extensions {
+    ///     crit: vec!["exp_policy".to_owned()],
+    ///     exp_policy: "strict".to_owned(),
+    /// });
+    /// let token_string = Hs256.token(&header, &Claims::empty(), &key)?;
+    /// let token = UntrustedToken::new(&token_string)?;
+    /// assert_eq!(token.critical_extensions(), ["exp_policy"]);
+    ///
+    /// let err = Hs256.validator::<Empty>(&key).validate(&token).unwrap_err();
+    /// assert!(matches!(err, ValidationError::UnknownCriticalExtension(_)));
+    /// Hs256
+    ///     .validator::<Empty>(&key)
+    ///     .with_understood_extensions(&["exp_policy"])
+    ///     .validate(&token)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn with_understood_extensions(mut self, extensions: &'a [&'a str]) -> Self {
+        self.understood_extensions = extensions;
+        self
+    }
+}
+
 impl<A: Algorithm + ?Sized, T: DeserializeOwned> Validator<'_, A, T> {
     /// Validates the token integrity against a verifying key enclosed in this validator.
     pub fn validate<H: Clone>(
@@ -312,43 +533,788 @@ impl<A: Algorithm + ?Sized, T: DeserializeOwned> Validator<'_, A, T> {
         self,
         token: &UntrustedToken<'_, H>,
     ) -> Result<SignedToken<A, T, H>, ValidationError> {
-        let expected_alg = self.algorithm.name();
-        if expected_alg != token.algorithm() {
-            return Err(ValidationError::AlgorithmMismatch {
-                expected: expected_alg.into_owned(),
-                actual: token.algorithm().to_owned(),
-            });
+        let understood_extensions = self.understood_extensions;
+        validate_with(
+            &self.algorithm.name(),
+            understood_extensions,
+            token,
+            |signature, message| {
+                self.algorithm
+                    .verify_signature(signature, self.verifying_key, message)
+            },
+        )
+    }
+
+    /// Verifies the token signature against a verifying key enclosed in this validator
+    /// without deserializing the token claims. Claims can be deserialized later
+    /// via [`VerifiedToken::deserialize_claims()`] if necessary.
+    ///
+    /// This is useful if the claims are not always needed, e.g., in gateways that only
+    /// forward tokens with valid signatures.
+    pub fn verify_signature<'t, H>(
+        self,
+        token: &'t UntrustedToken<'_, H>,
+    ) -> Result<VerifiedToken<'t, H>, ValidationError> {
+        let signature = parse_signature::<A::Signature, _>(
+            &self.algorithm.name(),
+            self.understood_extensions,
+            token,
+        )?;
+        if self
+            .algorithm
+            .verify_signature(&signature, self.verifying_key, &token.signed_data)
+        {
+            Ok(VerifiedToken::new(token))
+        } else {
+            Err(ValidationError::InvalidSignature)
         }
+    }
+
+    /// Validates integrity of multiple tokens against a verifying key enclosed
+    /// in this validator. Returns the validation result for each token, in the same order
+    /// as `tokens`. See [`AlgorithmExt::validate_batch()`] for details.
+    pub fn validate_batch<H: Clone>(
+        self,
+        tokens: &[UntrustedToken<'_, H>],
+    ) -> Vec<Result<Token<T, H>, ValidationError>> {
+        let name = self.algorithm.name();
+        let mut signatures = Vec::with_capacity(tokens.len());
+        let parsed: Vec<_> = tokens
+            .iter()
+            .map(|token| {
+                let signature =
+                    parse_signature::<A::Signature, _>(&name, self.understood_extensions, token)?;
+                let claims = token.deserialize_claims_unverified::<T>()?;
+                signatures.push((signature, &*token.signed_data));
+                Ok(claims)
+            })
+            .collect();
+
+        let mut verified = self
+            .algorithm
+            .verify_signatures(self.verifying_key, &signatures)
+            .into_iter();
+        parsed
+            .into_iter()
+            .zip(tokens)
+            .map(|(claims, token)| {
+                let claims = claims?;
+                if verified.next() == Some(true) {
+                    Ok(Token::new(token, claims))
+                } else {
+                    Err(ValidationError::InvalidSignature)
+                }
+            })
+            .collect()
+    }
+}
+
+impl<A: Algorithm + ?Sized, T> Validator<'_, A, T> {
+    /// Validates the token integrity against a verifying key enclosed in this validator.
+    /// Unlike [`Self::validate()`], the claims may borrow from the decoded token payload,
+    /// which is stored in the `token` (e.g., using `&str` or `Cow<'_, str>` fields marked
+    /// with `#[serde(borrow)]`). This avoids allocations for string-heavy claims.
+    ///
+    /// Borrowing is only supported for JSON-encoded claims.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use serde::{Deserialize, Serialize};
+    /// # use std::borrow::Cow;
+    /// # use jwt_compact::{alg::{Hs256, Hs256Key}, prelude::*};
+    /// #[derive(Serialize, Deserialize)]
+    /// struct MyClaims<'a> {
+    ///     #[serde(borrow)]
+    ///     subject: Cow<'a, str>,
+    /// }
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// # let key = Hs256Key::new(b"super_secret_key_donut_steel");
+    /// # let claims = Claims::new(MyClaims { subject: "alice".into() });
+    /// # let token_string = Hs256.token(&Header::empty(), &claims, &key)?;
+    /// let token = UntrustedToken::new(&token_string)?;
+    /// let token = Hs256.validator::<MyClaims>(&key).validate_borrowed(&token)?;
+    /// assert!(matches!(token.claims().custom.subject, Cow::Borrowed("alice")));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn validate_borrowed<'t, H: Clone>(
+        self,
+        token: &'t UntrustedToken<'_, H>,
+    ) -> Result<Token<T, H>, ValidationError>
+    where
+        T: Deserialize<'t>,
+    {
+        let signed = validate_and_deserialize::<A, _, _>(
+            &self.algorithm.name(),
+            self.understood_extensions,
+            token,
+            |signature, message| {
+                self.algorithm
+                    .verify_signature(signature, self.verifying_key, message)
+            },
+            || token.deserialize_borrowed_claims_unverified(),
+        )?;
+        Ok(signed.token)
+    }
+}
+
+/// Validates the `token` integrity using the provided function to verify its signature.
+pub(crate) fn validate_with<A, T, H>(
+    expected_alg: &str,
+    understood_extensions: &[impl AsRef<str>],
+    token: &UntrustedToken<'_, H>,
+    verify: impl FnOnce(&A::Signature, &[u8]) -> bool,
+) -> Result<SignedToken<A, T, H>, ValidationError>
+where
+    A: Algorithm + ?Sized,
+    T: DeserializeOwned,
+    H: Clone,
+{
+    validate_and_deserialize(expected_alg, understood_extensions, token, verify, || {
+        token.deserialize_claims_unverified()
+    })
+}
+
+fn validate_and_deserialize<A, T, H>(
+    expected_alg: &str,
+    understood_extensions: &[impl AsRef<str>],
+    token: &UntrustedToken<'_, H>,
+    verify: impl FnOnce(&A::Signature, &[u8]) -> bool,
+    deserialize_claims: impl FnOnce() -> Result<Claims<T>, ValidationError>,
+) -> Result<SignedToken<A, T, H>, ValidationError>
+where
+    A: Algorithm + ?Sized,
+    H: Clone,
+{
+    let signature = parse_signature::<A::Signature, _>(expected_alg, understood_extensions, token)?;
+    // We assume that parsing claims is less computationally demanding than
+    // validating a signature.
+    let claims = deserialize_claims()?;
+    if !verify(&signature, &token.signed_data) {
+        return Err(ValidationError::InvalidSignature);
+    }
+
+    Ok(SignedToken {
+        signature,
+        token: Token::new(token, claims),
+    })
+}
+
+/// [`Algorithm`] that can pre-compute state derived from a verifying key, so that this
+/// computation is not repeated for each verified token. Used by [`PreparedVerifier`].
+///
+/// For `HS*` algorithms, the prepared key is the HMAC state initialized with the key.
+/// Other algorithms store verifying keys in a form ready for verification (e.g., parsed RSA
+/// public keys or decompressed elliptic curve points), so their prepared keys are
+/// the verifying keys themselves.
+pub trait PrepareVerifyingKey: Algorithm {
+    /// Verifying key together with pre-computed state.
+    type PreparedKey: Send + Sync;
+
+    /// Pre-computes state for the `verifying_key`.
+    fn prepare_verifying_key(&self, verifying_key: &Self::VerifyingKey) -> Self::PreparedKey;
+
+    /// Verifies the `message` against the `signature` and `prepared_key`. The outcome
+    /// must coincide with [`Algorithm::verify_signature()`] for the original verifying key.
+    fn verify_prepared(
+        &self,
+        signature: &Self::Signature,
+        prepared_key: &Self::PreparedKey,
+        message: &[u8],
+    ) -> bool;
+}
 
-        let signature = token.signature_bytes();
-        if let Some(expected_len) = A::Signature::LENGTH {
-            if signature.len() != expected_len.get() {
-                return Err(ValidationError::InvalidSignatureLen {
-                    expected: expected_len.get(),
-                    actual: signature.len(),
-                });
-            }
+/// Owned validator with a verifying key prepared via [`PrepareVerifyingKey`].
+///
+/// Unlike [`Validator`], the verifier owns the algorithm and the key, and it is
+/// `Send + Sync` if the algorithm is. Thus, it can be created once and shared
+/// among threads (e.g., in an `Arc`) to validate tokens on hot paths.
+///
+/// # Examples
+///
+/// ```
+/// # use std::sync::Arc;
+/// use jwt_compact::{prelude::*, alg::{Hs256, Hs256Key}, PreparedVerifier};
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let key = Hs256Key::new(b"super_secret_key_donut_steel");
+/// let token = Hs256.token(&Header::empty(), &Claims::empty(), &key)?;
+///
+/// let verifier = Arc::new(PreparedVerifier::new(Hs256, &key));
+/// let token = UntrustedToken::new(&token)?;
+/// let token = verifier.validate::<jwt_compact::Empty, _>(&token)?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct PreparedVerifier<A: PrepareVerifyingKey> {
+    algorithm: A,
+    prepared_key: A::PreparedKey,
+    understood_extensions: Vec<String>,
+}
+
+impl<A: PrepareVerifyingKey + fmt::Debug> fmt::Debug for PreparedVerifier<A> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("PreparedVerifier")
+            .field("algorithm", &self.algorithm)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<A: PrepareVerifyingKey> PreparedVerifier<A> {
+    /// Creates a verifier with the specified algorithm, preparing the `verifying_key`.
+    pub fn new(algorithm: A, verifying_key: &A::VerifyingKey) -> Self {
+        let prepared_key = algorithm.prepare_verifying_key(verifying_key);
+        Self {
+            algorithm,
+            prepared_key,
+            understood_extensions: Vec::new(),
         }
+    }
+
+    /// Declares [critical header extensions][crit] understood by the application.
+    /// See [`Validator::with_understood_extensions()`] for details.
+    ///
+    /// [crit]: https://www.rfc-editor.org/rfc/rfc7515#section-4.1.11
+    #[must_use]
+    pub fn with_understood_extensions(mut self, extensions: &[&str]) -> Self {
+        self.understood_extensions = extensions.iter().map(|&ext| ext.to_owned()).collect();
+        self
+    }
+
+    /// Returns the algorithm used by this verifier.
+    pub fn algorithm(&self) -> &A {
+        &self.algorithm
+    }
+
+    /// Validates the token integrity against the prepared verifying key.
+    pub fn validate<T, H>(
+        &self,
+        token: &UntrustedToken<'_, H>,
+    ) -> Result<Token<T, H>, ValidationError>
+    where
+        T: DeserializeOwned,
+        H: Clone,
+    {
+        self.validate_for_signed_token(token)
+            .map(|signed| signed.token)
+    }
+
+    /// Validates the token integrity against the prepared verifying key, and returns
+    /// the validated [`Token`] together with its signature.
+    pub fn validate_for_signed_token<T, H>(
+        &self,
+        token: &UntrustedToken<'_, H>,
+    ) -> Result<SignedToken<A, T, H>, ValidationError>
+    where
+        T: DeserializeOwned,
+        H: Clone,
+    {
+        validate_with(
+            &self.algorithm.name(),
+            &self.understood_extensions,
+            token,
+            |signature, message| {
+                self.algorithm
+                    .verify_prepared(signature, &self.prepared_key, message)
+            },
+        )
+    }
+}
+
+/// Dyn-compatible token verifier, i.e., a verifier that can be used as a trait object.
+///
+/// Unlike [`Algorithm`], this trait has no associated types, so verifiers for different
+/// algorithms can be stored together (e.g., in a `Vec` or a map) and selected at runtime,
+/// for example, based on the `alg` header field of a token. The trait is implemented
+/// for [`PreparedVerifier`], so a boxed verifier can be created as
+/// `Box::new(PreparedVerifier::new(algorithm, &key))`.
+///
+/// Tokens are verified via inherent methods on `dyn VerifyToken`, such as
+/// [`validate()`](#method.validate) and [`verify_signature()`](#method.verify_signature).
+///
+/// # Examples
+///
+/// ```
+/// # use std::collections::HashMap;
+/// use jwt_compact::{
+///     prelude::*, alg::{Hs256, Hs256Key, Hs384, Hs384Key}, Empty, PreparedVerifier, VerifyToken,
+/// };
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let hs256_key = Hs256Key::new(b"super_secret_key_donut_steel");
+/// let hs384_key = Hs384Key::new(b"another_super_secret_key_for_hs384");
+/// let verifiers: Vec<Box<dyn VerifyToken>> = vec![
+///     Box::new(PreparedVerifier::new(Hs256, &hs256_key)),
+///     Box::new(PreparedVerifier::new(Hs384, &hs384_key)),
+/// ];
+/// let verifiers: HashMap<_, _> = verifiers
+///     .into_iter()
+///     .map(|verifier| (verifier.algorithm_name(), verifier))
+///     .collect();
+///
+/// let token_string = Hs384.token(&Header::empty(), &Claims::empty(), &hs384_key)?;
+/// let token = UntrustedToken::new(&token_string)?;
+/// let verifier = &verifiers[token.algorithm()];
+/// let token = verifier.validate::<Empty, _>(&token)?;
+/// # Ok(())
+/// # }
+/// ```
+pub trait VerifyToken: Send + Sync {
+    /// Returns the name of the algorithm (i.e., the expected `alg` field in the token header).
+    fn algorithm_name(&self) -> Cow<'static, str>;
+
+    /// Returns [critical header extensions][crit] understood by the application.
+    /// By default, no extensions are understood.
+    ///
+    /// [crit]: https://www.rfc-editor.org/rfc/rfc7515#section-4.1.11
+    fn understood_extensions(&self) -> &[String] {
+        &[]
+    }
+
+    /// Verifies the `signature` bytes for the `message`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the signature is malformed or invalid.
+    fn verify_signature_bytes(
+        &self,
+        signature: &[u8],
+        message: &[u8],
+    ) -> Result<(), ValidationError>;
+}
+
+impl dyn VerifyToken {
+    /// Verifies the token signature without deserializing the token claims.
+    /// See [`Validator::verify_signature()`] for details.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the token algorithm doesn't match the verifier, the token
+    /// has unknown critical extensions, or its signature is malformed or invalid.
+    pub fn verify_signature<'t, H>(
+        &self,
+        token: &'t UntrustedToken<'_, H>,
+    ) -> Result<VerifiedToken<'t, H>, ValidationError> {
+        check_header(&self.algorithm_name(), self.understood_extensions(), token)?;
+        self.verify_signature_bytes(token.signature_bytes(), &token.signed_data)?;
+        Ok(VerifiedToken::new(token))
+    }
+
+    /// Validates the token integrity and deserializes its claims.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the token signature cannot be verified (see
+    /// [`Self::verify_signature()`]), or the claims cannot be deserialized.
+    pub fn validate<T, H>(
+        &self,
+        token: &UntrustedToken<'_, H>,
+    ) -> Result<Token<T, H>, ValidationError>
+    where
+        T: DeserializeOwned,
+        H: Clone,
+    {
+        self.verify_signature(token)?.into_token()
+    }
+}
+
+impl<A> VerifyToken for PreparedVerifier<A>
+where
+    A: PrepareVerifyingKey + Send + Sync,
+{
+    fn algorithm_name(&self) -> Cow<'static, str> {
+        self.algorithm.name()
+    }
+
+    fn understood_extensions(&self) -> &[String] {
+        &self.understood_extensions
+    }
 
-        let signature =
-            A::Signature::try_from_slice(signature).map_err(ValidationError::MalformedSignature)?;
-        // We assume that parsing claims is less computationally demanding than
-        // validating a signature.
-        let claims = token.deserialize_claims_unchecked::<T>()?;
-        if !self
+    fn verify_signature_bytes(
+        &self,
+        signature: &[u8],
+        message: &[u8],
+    ) -> Result<(), ValidationError> {
+        let signature = parse_signature_bytes::<A::Signature>(signature)?;
+        if self
             .algorithm
-            .verify_signature(&signature, self.verifying_key, &token.signed_data)
+            .verify_prepared(&signature, &self.prepared_key, message)
         {
-            return Err(ValidationError::InvalidSignature);
+            Ok(())
+        } else {
+            Err(ValidationError::InvalidSignature)
         }
+    }
+}
+
+/// Checks that the token algorithm matches the `expected_alg` and that all critical extensions
+/// in the token are understood, and parses the token signature.
+fn parse_signature<S: AlgorithmSignature, H>(
+    expected_alg: &str,
+    understood_extensions: &[impl AsRef<str>],
+    token: &UntrustedToken<'_, H>,
+) -> Result<S, ValidationError> {
+    check_header(expected_alg, understood_extensions, token)?;
+    parse_signature_bytes(token.signature_bytes())
+}
+
+/// Checks that the token algorithm matches the `expected_alg` and that all critical extensions
+/// in the token are understood.
+fn check_header<H>(
+    expected_alg: &str,
+    understood_extensions: &[impl AsRef<str>],
+    token: &UntrustedToken<'_, H>,
+) -> Result<(), ValidationError> {
+    if expected_alg != token.algorithm() {
+        return Err(ValidationError::AlgorithmMismatch {
+            expected: expected_alg.to_owned(),
+            actual: token.algorithm().to_owned(),
+        });
+    }
+    let unknown_extension = token.critical_extensions().iter().find(|&ext| {
+        !understood_extensions
+            .iter()
+            .any(|understood| understood.as_ref() == ext.as_str())
+    });
+    if let Some(extension) = unknown_extension {
+        return Err(ValidationError::UnknownCriticalExtension(extension.clone()));
+    }
+    Ok(())
+}
 
-        Ok(SignedToken {
-            signature,
-            token: Token::new(token.header().clone(), claims),
+/// Parses a signature from bytes, checking its length if it is fixed.
+fn parse_signature_bytes<S: AlgorithmSignature>(signature: &[u8]) -> Result<S, ValidationError> {
+    if let Some(expected_len) = S::LENGTH {
+        if signature.len() != expected_len.get() {
+            return Err(ValidationError::InvalidSignatureLen {
+                expected: expected_len.get(),
+                actual: signature.len(),
+            });
+        }
+    }
+    S::try_from_slice(signature).map_err(ValidationError::MalformedSignature)
+}
+
+/// Boxed future returned by [`AsyncAlgorithm::sign_async()`].
+#[cfg(feature = "sign")]
+#[cfg_attr(docsrs, doc(cfg(feature = "sign")))]
+pub type SignFuture<'a, S> = Pin<Box<dyn Future<Output = anyhow::Result<S>> + Send + 'a>>;
+
+/// Boxed future returned by [`AsyncAlgorithmExt::token_async()`].
+#[cfg(feature = "sign")]
+#[cfg_attr(docsrs, doc(cfg(feature = "sign")))]
+pub type TokenFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<TokenString, CreationError>> + Send + 'a>>;
+
+/// JWT signing algorithm with asynchronous, fallible signing.
+///
+/// This trait is intended for algorithms where the signing key is not available locally,
+/// e.g., is held by a key management service (KMS) or a hardware security module (HSM).
+/// Tokens produced by such an algorithm are ordinary JWTs; they are verified
+/// by the corresponding [`Algorithm`] with a verifying (public) key.
+///
+/// Unlike `Algorithm`, this trait uses boxed futures so that it can be implemented
+/// without `async` trait support in the compiler.
+///
+/// To delegate signing for an existing `Algorithm` to a custom backend, it is usually easier
+/// to implement [`RemoteSigner`](crate::alg::RemoteSigner) and use
+/// the [`Remote`](crate::alg::Remote) adapter.
+#[cfg(feature = "sign")]
+#[cfg_attr(docsrs, doc(cfg(feature = "sign")))]
+pub trait AsyncAlgorithm {
+    /// Handle to the signing key used when issuing new tokens.
+    type SigningKey;
+    /// Signature produced by the algorithm.
+    type Signature: AlgorithmSignature;
+
+    /// Returns the name of this algorithm, as mentioned in the `alg` field of the JWT header.
+    fn name(&self) -> Cow<'static, str>;
+
+    /// Signs a `message` with the `signing_key`.
+    fn sign_async<'a>(
+        &'a self,
+        signing_key: &'a Self::SigningKey,
+        message: &'a [u8],
+    ) -> SignFuture<'a, Self::Signature>;
+}
+
+/// Automatically implemented extensions of the [`AsyncAlgorithm`] trait.
+#[cfg(feature = "sign")]
+#[cfg_attr(docsrs, doc(cfg(feature = "sign")))]
+pub trait AsyncAlgorithmExt: AsyncAlgorithm {
+    /// Creates a new token and serializes it to string. Header and claims are serialized
+    /// before the returned future is first polled, so the future only borrows
+    /// the algorithm and the signing key.
+    fn token_async<'a, T>(
+        &'a self,
+        header: &Header<impl Serialize>,
+        claims: &Claims<T>,
+        signing_key: &'a Self::SigningKey,
+    ) -> TokenFuture<'a>
+    where
+        Self: Sync,
+        Self::SigningKey: Sync,
+        T: Serialize;
+}
+
+#[cfg(feature = "sign")]
+impl<A: AsyncAlgorithm + ?Sized> AsyncAlgorithmExt for A {
+    fn token_async<'a, T>(
+        &'a self,
+        header: &Header<impl Serialize>,
+        claims: &Claims<T>,
+        signing_key: &'a Self::SigningKey,
+    ) -> TokenFuture<'a>
+    where
+        Self: Sync,
+        Self::SigningKey: Sync,
+        T: Serialize,
+    {
+        let buffer = signing_input(self.name(), header, claims);
+        Box::pin(async move {
+            let buffer = buffer?;
+            let signature = self
+                .sign_async(signing_key, &buffer)
+                .await
+                .map_err(CreationError::Signing)?;
+            Ok(complete_token(buffer, &signature))
         })
     }
 }
 
+/// Boxed future returned by [`AsyncVerifier::verify_signature_async()`].
+pub type VerifyFuture<'a> = Pin<Box<dyn Future<Output = anyhow::Result<bool>> + Send + 'a>>;
+
+/// JWT algorithm with asynchronous, fallible signature verification.
+///
+/// This is the verification counterpart of [`AsyncAlgorithm`] intended for algorithms
+/// that delegate verification to an external party (e.g., a KMS or the Web Crypto API).
+/// Synchronous [`Algorithm`]s can be used with this trait via the [`AsyncAdapter`].
+pub trait AsyncVerifier {
+    /// Key used when verifying tokens.
+    type VerifyingKey;
+    /// Signature produced by the algorithm.
+    type Signature: AlgorithmSignature;
+
+    /// Returns the name of this algorithm, as mentioned in the `alg` field of the JWT header.
+    fn name(&self) -> Cow<'static, str>;
+
+    /// Verifies the `message` against the `signature` and `verifying_key`.
+    ///
+    /// The returned future resolves to `Ok(false)` if the signature is invalid, and to an error
+    /// if verification cannot be performed (e.g., because a remote verifier is unavailable).
+    fn verify_signature_async<'a>(
+        &'a self,
+        signature: &'a Self::Signature,
+        verifying_key: &'a Self::VerifyingKey,
+        message: &'a [u8],
+    ) -> VerifyFuture<'a>;
+}
+
+/// Automatically implemented extensions of the [`AsyncVerifier`] trait.
+pub trait AsyncVerifierExt: AsyncVerifier {
+    /// Creates a JWT validator for the specified verifying key and the claims type.
+    /// The validator can then be used to validate integrity of one or more tokens.
+    fn async_validator<'a, T>(
+        &'a self,
+        verifying_key: &'a Self::VerifyingKey,
+    ) -> AsyncValidator<'a, Self, T>;
+}
+
+impl<A: AsyncVerifier + ?Sized> AsyncVerifierExt for A {
+    fn async_validator<'a, T>(
+        &'a self,
+        verifying_key: &'a Self::VerifyingKey,
+    ) -> AsyncValidator<'a, Self, T> {
+        AsyncValidator {
+            algorithm: self,
+            verifying_key,
+            understood_extensions: &[],
+            _claims: PhantomData,
+        }
+    }
+}
+
+/// Validator for a certain [`AsyncVerifier`] associated with a specific verifying key
+/// and a claims type. Produced by the [`AsyncVerifierExt::async_validator()`] method.
+#[derive(Debug)]
+pub struct AsyncValidator<'a, A: AsyncVerifier + ?Sized, T> {
+    algorithm: &'a A,
+    verifying_key: &'a A::VerifyingKey,
+    understood_extensions: &'a [&'a str],
+    _claims: PhantomData<fn() -> T>,
+}
+
+impl<A: AsyncVerifier + ?Sized, T> Clone for AsyncValidator<'_, A, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<A: AsyncVerifier + ?Sized, T> Copy for AsyncValidator<'_, A, T> {}
+
+impl<'a, A: AsyncVerifier + ?Sized, T> AsyncValidator<'a, A, T> {
+    /// Declares [critical header extensions][crit] understood by the application.
+    /// See [`Validator::with_understood_extensions()`] for details.
+    ///
+    /// [crit]: https://www.rfc-editor.org/rfc/rfc7515#section-4.1.11
+    #[must_use]
+    pub fn with_understood_extensions(mut self, extensions: &'a [&'a str]) -> Self {
+        self.understood_extensions = extensions;
+        self
+    }
+}
+
+impl<A: AsyncVerifier + ?Sized, T: DeserializeOwned> AsyncValidator<'_, A, T> {
+    /// Validates the token integrity against a verifying key enclosed in this validator.
+    ///
+    /// # Errors
+    ///
+    /// In addition to errors returned by [`Validator::validate()`], returns
+    /// [`ValidationError::Verification`] if the signature cannot be verified.
+    pub async fn validate<H: Clone>(
+        self,
+        token: &UntrustedToken<'_, H>,
+    ) -> Result<Token<T, H>, ValidationError> {
+        let signature = parse_signature::<A::Signature, _>(
+            &self.algorithm.name(),
+            self.understood_extensions,
+            token,
+        )?;
+        let claims = token.deserialize_claims_unverified::<T>()?;
+        let is_valid = self
+            .algorithm
+            .verify_signature_async(&signature, self.verifying_key, &token.signed_data)
+            .await
+            .map_err(ValidationError::Verification)?;
+        if !is_valid {
+            return Err(ValidationError::InvalidSignature);
+        }
+        Ok(Token::new(token, claims))
+    }
+}
+
+/// Adapter implementing [`AsyncAlgorithm`] and [`AsyncVerifier`] for a synchronous
+/// [`Algorithm`]. Signing and verification are performed synchronously, and the returned
+/// futures are immediately ready.
+///
+/// This allows code generic over async traits to work with local keys as well as with keys
+/// held by remote parties.
+///
+/// # Examples
+///
+/// ```
+/// use jwt_compact::{
+///     alg::{Hs256, Hs256Key}, prelude::*, AsyncAdapter, AsyncAlgorithmExt, AsyncVerifierExt,
+/// };
+///
+/// # async fn test_adapter() -> anyhow::Result<()> {
+/// let alg = AsyncAdapter::new(Hs256);
+/// let key = Hs256Key::new(b"super_secret_key_donut_steel");
+/// let claims = Claims::new(serde_json::json!({ "sub": "alice" }));
+/// let token = alg.token_async(&Header::empty(), &claims, &key).await?;
+///
+/// let token = UntrustedToken::new(&token)?;
+/// let token = alg
+///     .async_validator::<serde_json::Value>(&key)
+///     .validate(&token)
+///     .await?;
+/// assert_eq!(token.claims().custom["sub"], "alice");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AsyncAdapter<A>(A);
+
+impl<A: Algorithm> AsyncAdapter<A> {
+    /// Wraps the specified algorithm.
+    pub const fn new(algorithm: A) -> Self {
+        Self(algorithm)
+    }
+
+    /// Returns the wrapped algorithm.
+    pub fn inner(&self) -> &A {
+        &self.0
+    }
+}
+
+#[cfg(feature = "sign")]
+impl<A> AsyncAlgorithm for AsyncAdapter<A>
+where
+    A: Algorithm,
+    A::Signature: Send,
+{
+    type SigningKey = A::SigningKey;
+    type Signature = A::Signature;
+
+    fn name(&self) -> Cow<'static, str> {
+        self.0.name()
+    }
+
+    fn sign_async<'a>(
+        &'a self,
+        signing_key: &'a Self::SigningKey,
+        message: &'a [u8],
+    ) -> SignFuture<'a, Self::Signature> {
+        let signature = self.0.sign(signing_key, message);
+        Box::pin(async move { Ok(signature) })
+    }
+}
+
+impl<A: Algorithm> AsyncVerifier for AsyncAdapter<A> {
+    type VerifyingKey = A::VerifyingKey;
+    type Signature = A::Signature;
+
+    fn name(&self) -> Cow<'static, str> {
+        self.0.name()
+    }
+
+    fn verify_signature_async<'a>(
+        &'a self,
+        signature: &'a Self::Signature,
+        verifying_key: &'a Self::VerifyingKey,
+        message: &'a [u8],
+    ) -> VerifyFuture<'a> {
+        let is_valid = self.0.verify_signature(signature, verifying_key, message);
+        Box::pin(async move { Ok(is_valid) })
+    }
+}
+
+/// Serializes the header (with the `alg` field set to `algorithm`) and JSON claims
+/// into the signing input of a token.
+fn signing_input<T: Serialize>(
+    algorithm: Cow<'static, str>,
+    header: &Header<impl Serialize>,
+    claims: &Claims<T>,
+) -> Result<Vec<u8>, CreationError> {
+    let complete_header = CompleteHeader {
+        algorithm,
+        content_type: None,
+        critical: None,
+        inner: header,
+    };
+    let header = serde_json::to_string(&complete_header).map_err(CreationError::Header)?;
+    let mut buffer = Vec::new();
+    encode_base64_buf(&header, &mut buffer);
+
+    let claims = serde_json::to_string(claims).map_err(CreationError::Claims)?;
+    buffer.push(b'.');
+    encode_base64_buf(&claims, &mut buffer);
+    Ok(buffer)
+}
+
+/// Appends the `signature` to the signing input of a token.
+fn complete_token(mut buffer: Vec<u8>, signature: &impl AlgorithmSignature) -> TokenString {
+    buffer.push(b'.');
+    encode_base64_buf(signature.as_bytes(), &mut buffer);
+    // SAFETY: safe by construction: base64 alphabet and `.` char are valid UTF-8.
+    let token = unsafe { String::from_utf8_unchecked(buffer) };
+    TokenString::new_unchecked(token)
+}
+
 fn encode_base64_buf(source: impl AsRef<[u8]>, buffer: &mut Vec<u8>) {
     let source = source.as_ref();
     let previous_len = buffer.len();
@@ -0,0 +1,1078 @@
+//! Token validation pipelines.
+//!
+//! [`validate()`] performs all steps of validating a token with a known verifying key
+//! in a single call: parsing the token, verifying its signature and checking the claims
+//! against [`Expectations`]. Since the signature and claims checks cannot be separated,
+//! it is impossible to accidentally skip the latter (e.g., forget to check token expiration).
+//!
+//! [`TokenValidator`] performs similar steps asynchronously, resolving the verifying key
+//! with an asynchronous [`KeyResolver`] (e.g., one fetching a JWK set or querying a database).
+//! All failures are reported via [`TokenValidatorError`].
+//!
+//! # Examples
+//!
+//! ```
+//! # use chrono::Duration;
+//! use jwt_compact::{
+//!     prelude::*, alg::{Hs256, Hs256Key}, validation::{TokenValidator, TokenValidatorError},
+//! };
+//!
+//! # async fn test_validator() -> anyhow::Result<()> {
+//! # let key = Hs256Key::new(b"super_secret_key_donut_steel");
+//! # let claims = Claims::new(serde_json::json!({ "sub": "alice" }))
+//! #     .set_duration_and_issuance(&TimeOptions::default(), Duration::try_minutes(5).unwrap());
+//! # let token = Hs256.token(&Header::empty().with_key_id("main"), &claims, &key)?;
+//! let validator = TokenValidator::<_, _, serde_json::Value>::new(
+//!     Hs256,
+//!     |header: &Header| {
+//!         let key_id = header.key_id.clone();
+//!         async move {
+//!             // In real use cases, the key would be fetched from a JWK set, a database etc.
+//!             let key = Hs256Key::new(b"super_secret_key_donut_steel");
+//!             anyhow::Ok((key_id.as_deref() == Some("main")).then_some(key))
+//!         }
+//!     },
+//!     TimeOptions::default(),
+//! );
+//!
+//! let token = validator.validate(&token).await?;
+//! assert_eq!(token.claims().custom["sub"], "alice");
+//!
+//! let err = validator.validate("not a token").await.unwrap_err();
+//! assert!(matches!(err, TokenValidatorError::Parse(_)));
+//! # Ok(())
+//! # }
+//! ```
+
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::Value;
+
+use core::{fmt, future::Future, marker::PhantomData, pin::Pin};
+
+use chrono::Duration;
+
+use crate::{
+    alloc::{Box, String, ToOwned, Vec},
+    traits::validate_with,
+    Algorithm, AlgorithmExt, Audience, Claims, DynamicClaims, Empty, ErrorKind, Header, ParseError,
+    ParseOptions, TimeOptions, Token, UntrustedToken, ValidationError,
+};
+
+/// Boxed future returned by [`KeyResolver::resolve_key()`].
+pub type ResolveFuture<'a, K> =
+    Pin<Box<dyn Future<Output = anyhow::Result<Option<K>>> + Send + 'a>>;
+
+/// Asynchronous resolver of verifying keys for the algorithm `A` based on the token header
+/// with custom fields `H`.
+///
+/// The trait is implemented for functions taking a reference to the header and returning
+/// a future that does not borrow the header (e.g., one capturing a cloned `kid` field).
+pub trait KeyResolver<A: Algorithm + ?Sized, H = Empty> {
+    /// Resolves the verifying key for a token with the specified `header`. The returned future
+    /// resolves to `Ok(None)` if the key is unknown, and to an error if the key cannot
+    /// be resolved (e.g., because a JWK set cannot be fetched).
+    fn resolve_key<'a>(&'a self, header: &'a Header<H>) -> ResolveFuture<'a, A::VerifyingKey>;
+}
+
+impl<A, H, F, Fut> KeyResolver<A, H> for F
+where
+    A: Algorithm + ?Sized,
+    F: Fn(&Header<H>) -> Fut,
+    Fut: Future<Output = anyhow::Result<Option<A::VerifyingKey>>> + Send + 'static,
+{
+    fn resolve_key<'a>(&'a self, header: &'a Header<H>) -> ResolveFuture<'a, A::VerifyingKey> {
+        Box::pin(self(header))
+    }
+}
+
+/// Errors that can occur during token validation with a [`TokenValidator`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum TokenValidatorError {
+    /// The token cannot be parsed.
+    Parse(ParseError),
+    /// The verifying key for the token is unknown to the [`KeyResolver`].
+    UnknownKey,
+    /// The verifying key for the token cannot be resolved.
+    KeyResolution(anyhow::Error),
+    /// The token has failed validation (e.g., has an invalid signature or is expired).
+    Validation(ValidationError),
+}
+
+impl fmt::Display for TokenValidatorError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(err) => write!(formatter, "cannot parse token: {err}"),
+            Self::UnknownKey => formatter.write_str("token is signed with an unknown key"),
+            Self::KeyResolution(err) => write!(formatter, "cannot resolve verifying key: {err}"),
+            Self::Validation(err) => write!(formatter, "token validation failed: {err}"),
+        }
+    }
+}
+
+impl TokenValidatorError {
+    /// Returns the kind of this error.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Parse(err) => err.kind(),
+            Self::UnknownKey => ErrorKind::UnknownKey,
+            Self::KeyResolution(_) => ErrorKind::Internal,
+            Self::Validation(err) => err.kind(),
+        }
+    }
+}
+
+impl core::error::Error for TokenValidatorError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::Parse(err) => Some(err),
+            Self::KeyResolution(err) => Some(err.as_ref()),
+            Self::Validation(err) => Some(err),
+            Self::UnknownKey => None,
+        }
+    }
+}
+
+impl From<ParseError> for TokenValidatorError {
+    fn from(err: ParseError) -> Self {
+        Self::Parse(err)
+    }
+}
+
+impl From<ValidationError> for TokenValidatorError {
+    fn from(err: ValidationError) -> Self {
+        Self::Validation(err)
+    }
+}
+
+/// Validator of tokens with claims of type `T` and custom header fields of type `H`,
+/// which resolves verifying keys with an asynchronous [`KeyResolver`] `R`.
+///
+/// Besides token integrity, the validator checks token expiration (the `exp` claim
+/// is required); if the `nbf` claim is present, token maturity is checked as well.
+/// See the [module docs](self) for an example of usage.
+pub struct TokenValidator<A, R, T, H = Empty> {
+    algorithm: A,
+    resolver: R,
+    time_options: TimeOptions,
+    parse_options: ParseOptions,
+    understood_extensions: Vec<String>,
+    _token: PhantomData<fn() -> (T, H)>,
+}
+
+impl<A: fmt::Debug, R, T, H> fmt::Debug for TokenValidator<A, R, T, H> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("TokenValidator")
+            .field("algorithm", &self.algorithm)
+            .field("time_options", &self.time_options)
+            .field("parse_options", &self.parse_options)
+            .field("understood_extensions", &self.understood_extensions)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<A, R, T, H> TokenValidator<A, R, T, H>
+where
+    A: Algorithm,
+    R: KeyResolver<A, H>,
+    T: DeserializeOwned,
+    H: DeserializeOwned + Clone,
+{
+    /// Creates a validator with the specified algorithm, key resolver and options
+    /// for time-related checks.
+    pub fn new(algorithm: A, resolver: R, time_options: TimeOptions) -> Self {
+        Self {
+            algorithm,
+            resolver,
+            time_options,
+            parse_options: ParseOptions::default(),
+            understood_extensions: Vec::new(),
+            _token: PhantomData,
+        }
+    }
+
+    /// Sets options used when parsing tokens in [`Self::validate()`]. By default,
+    /// [`ParseOptions::default()`] are used.
+    #[must_use]
+    pub fn with_parse_options(mut self, options: ParseOptions) -> Self {
+        self.parse_options = options;
+        self
+    }
+
+    /// Declares critical header extensions understood by the application. See
+    /// [`Validator::with_understood_extensions()`](crate::Validator::with_understood_extensions())
+    /// for details.
+    #[must_use]
+    pub fn with_understood_extensions(mut self, extensions: &[&str]) -> Self {
+        self.understood_extensions = extensions.iter().map(|&ext| ext.to_owned()).collect();
+        self
+    }
+
+    /// Returns the algorithm used by this validator.
+    pub fn algorithm(&self) -> &A {
+        &self.algorithm
+    }
+
+    /// Parses and validates the `token`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the validation steps fails.
+    pub async fn validate(&self, token: &str) -> Result<Token<T, H>, TokenValidatorError> {
+        let token = UntrustedToken::<H>::parse_with_options(token, &self.parse_options)?;
+        self.validate_untrusted(&token).await
+    }
+
+    /// Validates a previously parsed `token`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the validation steps fails.
+    pub async fn validate_untrusted(
+        &self,
+        token: &UntrustedToken<'_, H>,
+    ) -> Result<Token<T, H>, TokenValidatorError> {
+        let key = self
+            .resolver
+            .resolve_key(token.header())
+            .await
+            .map_err(TokenValidatorError::KeyResolution)?
+            .ok_or(TokenValidatorError::UnknownKey)?;
+        let token = validate_with::<A, T, H>(
+            &self.algorithm.name(),
+            &self.understood_extensions,
+            token,
+            |signature, message| self.algorithm.verify_signature(signature, &key, message),
+        )?
+        .token;
+
+        let claims = token.claims();
+        claims.validate_expiration(&self.time_options)?;
+        if claims.not_before.is_some() {
+            claims.validate_maturity(&self.time_options)?;
+        }
+        Ok(token)
+    }
+}
+
+/// Expectations for token claims checked by [`validate()`].
+///
+/// Token expiration is always checked (i.e., the `exp` claim is required); if the `nbf` claim
+/// is present, token maturity is checked as well. Other checks are opt-in.
+///
+/// # Examples
+///
+/// See [`validate()`] for an example of usage.
+#[derive(Debug, Clone)]
+pub struct Expectations {
+    issuer: Option<String>,
+    audiences: Vec<String>,
+    time_options: TimeOptions,
+    parse_options: ParseOptions,
+    required_claims: Vec<String>,
+    allowed_algs: Vec<String>,
+    understood_extensions: Vec<String>,
+}
+
+/// Creates expectations with [default time options](TimeOptions::default()).
+///
+/// This impl is supported on **crate feature `clock`** only.
+#[cfg(feature = "clock")]
+impl Default for Expectations {
+    fn default() -> Self {
+        Self::new(TimeOptions::default())
+    }
+}
+
+impl Expectations {
+    /// Creates expectations with the specified options for time-related checks.
+    pub fn new(time_options: TimeOptions) -> Self {
+        Self {
+            issuer: None,
+            audiences: Vec::new(),
+            time_options,
+            parse_options: ParseOptions::default(),
+            required_claims: Vec::new(),
+            allowed_algs: Vec::new(),
+            understood_extensions: Vec::new(),
+        }
+    }
+
+    /// Sets options used when parsing tokens. By default, [`ParseOptions::default()`] are used.
+    #[must_use]
+    pub fn with_parse_options(mut self, options: ParseOptions) -> Self {
+        self.parse_options = options;
+        self
+    }
+
+    /// Requires the token issuer (the `iss` claim) to be equal to the specified value.
+    #[must_use]
+    pub fn with_issuer(mut self, issuer: impl Into<String>) -> Self {
+        self.issuer = Some(issuer.into());
+        self
+    }
+
+    /// Adds an accepted audience. If at least one audience is specified, the token audience
+    /// (the `aud` claim) must contain at least one of the accepted audiences.
+    #[must_use]
+    pub fn with_audience(mut self, audience: impl Into<String>) -> Self {
+        self.audiences.push(audience.into());
+        self
+    }
+
+    /// Sets the leeway for time-related checks.
+    #[must_use]
+    pub fn with_leeway(mut self, leeway: Duration) -> Self {
+        self.time_options.leeway = leeway;
+        self
+    }
+
+    /// Requires the specified claim to be present in the token. This applies both to claims
+    /// in [`Claims`] (e.g., `iat`) and to custom claims.
+    #[must_use]
+    pub fn with_required_claim(mut self, claim: impl Into<String>) -> Self {
+        self.required_claims.push(claim.into());
+        self
+    }
+
+    /// Adds an allowed token algorithm (the `alg` header field). If at least one algorithm
+    /// is specified, tokens with other algorithms are rejected before verifying the signature.
+    /// Regardless of this setting, the token algorithm must match the verifying algorithm.
+    #[must_use]
+    pub fn with_allowed_algorithm(mut self, alg: impl Into<String>) -> Self {
+        self.allowed_algs.push(alg.into());
+        self
+    }
+
+    /// Declares critical header extensions understood by the application. See
+    /// [`Validator::with_understood_extensions()`](crate::Validator::with_understood_extensions())
+    /// for details.
+    #[must_use]
+    pub fn with_understood_extensions(mut self, extensions: &[&str]) -> Self {
+        self.understood_extensions = extensions.iter().map(|&ext| ext.to_owned()).collect();
+        self
+    }
+
+    fn understood_extensions(&self) -> Vec<&str> {
+        self.understood_extensions
+            .iter()
+            .map(String::as_str)
+            .collect()
+    }
+
+    pub(crate) fn check_algorithm(&self, alg: &str) -> Result<(), ExpectationError> {
+        if self.allowed_algs.is_empty() || self.allowed_algs.iter().any(|allowed| allowed == alg) {
+            Ok(())
+        } else {
+            Err(ExpectationError::DisallowedAlgorithm(alg.to_owned()))
+        }
+    }
+
+    /// Checks claims, stopping at the first failure.
+    fn check_claims(&self, claims: &Claims<DynamicClaims>) -> Result<(), ExpectationError> {
+        let mut fail_fast = Err::<(), ExpectationError>;
+        self.check_time(claims, &mut fail_fast)?;
+        self.check_custom_claims(claims, &mut fail_fast)
+    }
+
+    /// Checks token expiration and maturity, passing failures to `on_failure`.
+    /// Checks stop once `on_failure` returns an error.
+    fn check_time<T>(
+        &self,
+        claims: &Claims<T>,
+        on_failure: &mut impl FnMut(ExpectationError) -> Result<(), ExpectationError>,
+    ) -> Result<(), ExpectationError> {
+        if let Err(err) = claims.validate_expiration(&self.time_options) {
+            on_failure(err.into())?;
+        }
+        if claims.not_before.is_some() {
+            if let Err(err) = claims.validate_maturity(&self.time_options) {
+                on_failure(err.into())?;
+            }
+        }
+        Ok(())
+    }
+
+    fn has_custom_checks(&self) -> bool {
+        !self.required_claims.is_empty() || self.issuer.is_some() || !self.audiences.is_empty()
+    }
+
+    /// Checks required claims, the issuer and the audience, passing failures to `on_failure`.
+    /// Checks stop once `on_failure` returns an error.
+    fn check_custom_claims(
+        &self,
+        claims: &Claims<DynamicClaims>,
+        on_failure: &mut impl FnMut(ExpectationError) -> Result<(), ExpectationError>,
+    ) -> Result<(), ExpectationError> {
+        for claim in &self.required_claims {
+            let is_present = match claim.as_str() {
+                "exp" => claims.expiration.is_some(),
+                "nbf" => claims.not_before.is_some(),
+                "iat" => claims.issued_at.is_some(),
+                _ => claims.custom.contains_key(claim),
+            };
+            if !is_present {
+                on_failure(ExpectationError::MissingClaim(claim.clone()))?;
+            }
+        }
+
+        if let Some(issuer) = &self.issuer {
+            match claims.custom.get("iss") {
+                None => on_failure(ExpectationError::MissingClaim("iss".to_owned()))?,
+                Some(actual) if actual.as_str() != Some(issuer.as_str()) => {
+                    on_failure(ExpectationError::ClaimMismatch("iss"))?;
+                }
+                Some(_) => { /* the issuer matches */ }
+            }
+        }
+
+        if !self.audiences.is_empty() {
+            match claims.custom.get_as::<Audience>("aud") {
+                Err(_) => on_failure(ExpectationError::ClaimMismatch("aud"))?,
+                Ok(None) => on_failure(ExpectationError::MissingClaim("aud".to_owned()))?,
+                Ok(Some(audience)) => {
+                    if !self.audiences.iter().any(|aud| audience.contains(aud)) {
+                        on_failure(ExpectationError::ClaimMismatch("aud"))?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Evaluates all checks for the `token` and returns a report listing every failed check,
+    /// instead of stopping at the first failure as [`validate()`] does. This is useful
+    /// for diagnostics, e.g., to explain why a token was rejected.
+    ///
+    /// If the token signature cannot be verified, claim checks are still evaluated
+    /// for the unverified claims. Thus, the report must not be used to authorize requests
+    /// other than via [`ValidationReport::is_valid()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chrono::Duration;
+    /// use jwt_compact::{
+    ///     prelude::*, alg::{Hs256, Hs256Key}, validation::{Expectations, ValidationFailure},
+    /// };
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let key = Hs256Key::new(b"super_secret_key_donut_steel");
+    /// let claims = Claims::new(serde_json::json!({ "aud": "other" }))
+    ///     .set_duration(&TimeOptions::default(), -Duration::try_minutes(5).unwrap());
+    /// let token = Hs256.token(&Header::empty(), &claims, &key)?;
+    ///
+    /// let expectations = Expectations::default()
+    ///     .with_issuer("https://issuer.example.com")
+    ///     .with_audience("api");
+    /// let report = expectations.validation_report(&Hs256, &token, &key);
+    /// assert!(!report.is_valid());
+    /// assert_eq!(report.failures().len(), 3);
+    /// assert!(matches!(
+    ///     &report.failures()[1],
+    ///     ValidationFailure::MissingClaim(claim) if claim == "iss"
+    /// ));
+    /// assert_eq!(
+    ///     report.to_string(),
+    ///     "token has expired; claim `iss` is missing; claim `aud` has unexpected value"
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn validation_report<A: Algorithm>(
+        &self,
+        algorithm: &A,
+        token: &str,
+        verifying_key: &A::VerifyingKey,
+    ) -> ValidationReport {
+        let mut report = ValidationReport::default();
+        let extensions = self.understood_extensions();
+        self.evaluate::<DynamicClaims>(
+            token,
+            |token| {
+                algorithm
+                    .validator(verifying_key)
+                    .with_understood_extensions(&extensions)
+                    .validate(token)
+            },
+            &mut report,
+        );
+        report
+    }
+
+    /// Evaluates all checks for the `token`, using `verify` to check its integrity, and records
+    /// failures in the `report`. Returns the token claims (unverified if `verify` has failed),
+    /// or `None` if the claims cannot be deserialized.
+    pub(crate) fn evaluate<T: DeserializeOwned>(
+        &self,
+        token: &str,
+        verify: impl FnOnce(&UntrustedToken<'_>) -> Result<Token<T>, ValidationError>,
+        report: &mut ValidationReport,
+    ) -> Option<Claims<T>> {
+        let token = match UntrustedToken::parse_with_options(token, &self.parse_options) {
+            Ok(token) => token,
+            Err(err) => {
+                report.push(ValidationFailure::Parse(err));
+                return None;
+            }
+        };
+        if let Err(err) = self.check_algorithm(token.algorithm()) {
+            report.push(ValidationFailure::from_claim_check(err));
+        }
+
+        let claims = match verify(&token) {
+            Ok(token) => token.into_parts().1,
+            Err(err) => {
+                report.push(ValidationFailure::Integrity(err));
+                token.deserialize_claims_unverified().ok()?
+            }
+        };
+
+        let mut record = |err: ExpectationError| -> Result<(), ExpectationError> {
+            report.push(ValidationFailure::from_claim_check(err));
+            Ok(())
+        };
+        // `record()` never fails, so the checks are never short-circuited.
+        self.check_time(&claims, &mut record).ok();
+        if self.has_custom_checks() {
+            if let Ok(claims) = token.deserialize_claims_unverified::<DynamicClaims>() {
+                self.check_custom_claims(&claims, &mut record).ok();
+            }
+        }
+        Some(claims)
+    }
+}
+
+/// Failed check listed in a [`ValidationReport`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ValidationFailure {
+    /// The token cannot be parsed.
+    Parse(ParseError),
+    /// The token algorithm (the enclosed value) is not allowed by [`Expectations`].
+    DisallowedAlgorithm(String),
+    /// The token signature cannot be verified.
+    Integrity(ValidationError),
+    /// A time-related check (token expiration or maturity) has failed.
+    Time(ValidationError),
+    /// Required claim is missing.
+    MissingClaim(String),
+    /// Claim has an unexpected value.
+    ClaimMismatch(&'static str),
+    /// An authorization policy with the enclosed name is not satisfied. Policies are evaluated
+    /// by [`Authenticator::validation_report()`]; unnamed policies are named by their 0-based
+    /// index (`#0`, `#1`, ...).
+    ///
+    /// [`Authenticator::validation_report()`]: crate::integrations::Authenticator::validation_report()
+    Policy(String),
+}
+
+impl ValidationFailure {
+    /// Converts an error produced by [`Expectations`] checks.
+    fn from_claim_check(err: ExpectationError) -> Self {
+        match err {
+            ExpectationError::Parse(err) => Self::Parse(err),
+            ExpectationError::DisallowedAlgorithm(alg) => Self::DisallowedAlgorithm(alg),
+            ExpectationError::Validation(err) => Self::Time(err),
+            ExpectationError::MissingClaim(claim) => Self::MissingClaim(claim),
+            ExpectationError::ClaimMismatch(claim) => Self::ClaimMismatch(claim),
+        }
+    }
+}
+
+impl fmt::Display for ValidationFailure {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(err) => write!(formatter, "cannot parse token: {err}"),
+            Self::DisallowedAlgorithm(alg) => {
+                write!(formatter, "token algorithm `{alg}` is not allowed")
+            }
+            Self::Integrity(err) | Self::Time(err) => fmt::Display::fmt(err, formatter),
+            Self::MissingClaim(claim) => write!(formatter, "claim `{claim}` is missing"),
+            Self::ClaimMismatch(claim) => write!(formatter, "claim `{claim}` has unexpected value"),
+            Self::Policy(name) => write!(formatter, "policy `{name}` is not satisfied"),
+        }
+    }
+}
+
+/// Diagnostic report listing all checks failed by a token. Produced by
+/// [`Expectations::validation_report()`].
+#[derive(Debug, Default)]
+pub struct ValidationReport {
+    failures: Vec<ValidationFailure>,
+}
+
+impl ValidationReport {
+    /// Checks whether the token has passed all checks.
+    pub fn is_valid(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// Returns failed checks in the order of their evaluation.
+    pub fn failures(&self) -> &[ValidationFailure] {
+        &self.failures
+    }
+
+    pub(crate) fn push(&mut self, failure: ValidationFailure) {
+        self.failures.push(failure);
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.failures.is_empty() {
+            return formatter.write_str("token is valid");
+        }
+        for (i, failure) in self.failures.iter().enumerate() {
+            if i > 0 {
+                formatter.write_str("; ")?;
+            }
+            fmt::Display::fmt(failure, formatter)?;
+        }
+        Ok(())
+    }
+}
+
+/// Errors that can occur during validation with [`validate()`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ExpectationError {
+    /// The token cannot be parsed.
+    Parse(ParseError),
+    /// The token algorithm (the enclosed value) is not allowed by [`Expectations`].
+    DisallowedAlgorithm(String),
+    /// The token has failed validation (e.g., has an invalid signature or is expired).
+    Validation(ValidationError),
+    /// Required claim is missing.
+    MissingClaim(String),
+    /// Claim has an unexpected value.
+    ClaimMismatch(&'static str),
+}
+
+impl fmt::Display for ExpectationError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(err) => write!(formatter, "cannot parse token: {err}"),
+            Self::DisallowedAlgorithm(alg) => {
+                write!(formatter, "token algorithm `{alg}` is not allowed")
+            }
+            Self::Validation(err) => write!(formatter, "token validation failed: {err}"),
+            Self::MissingClaim(claim) => write!(formatter, "claim `{claim}` is missing"),
+            Self::ClaimMismatch(claim) => write!(formatter, "claim `{claim}` has unexpected value"),
+        }
+    }
+}
+
+impl ExpectationError {
+    /// Returns the kind of this error.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Parse(err) => err.kind(),
+            Self::DisallowedAlgorithm(_) => ErrorKind::UnsupportedAlgorithm,
+            Self::Validation(err) => err.kind(),
+            Self::MissingClaim(_) => ErrorKind::MissingClaim,
+            Self::ClaimMismatch(claim) => ErrorKind::claim_mismatch(claim),
+        }
+    }
+}
+
+impl core::error::Error for ExpectationError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::Parse(err) => Some(err),
+            Self::Validation(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<ParseError> for ExpectationError {
+    fn from(err: ParseError) -> Self {
+        Self::Parse(err)
+    }
+}
+
+impl From<ValidationError> for ExpectationError {
+    fn from(err: ValidationError) -> Self {
+        Self::Validation(err)
+    }
+}
+
+/// Parses the `token` with custom header fields `H`, verifies its signature with the `verifying_key`
+/// and checks its claims against the provided `expectations`. Claims are deserialized only once;
+/// custom claims are converted to `T` after all checks have passed.
+///
+/// # Errors
+///
+/// Returns an error if any of the validation steps fails.
+///
+/// # Examples
+///
+/// ```
+/// # use chrono::Duration;
+/// use jwt_compact::{
+///     prelude::*, alg::{Hs256, Hs256Key}, validation::{validate, ExpectationError, Expectations},
+///     Empty,
+/// };
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let key = Hs256Key::new(b"super_secret_key_donut_steel");
+/// let claims = Claims::builder()
+///     .issuer("https://issuer.example.com")
+///     .subject("alice")
+///     .audience(["api"])
+///     .expires_in(Duration::try_minutes(5).unwrap())
+///     .build(&TimeOptions::default());
+/// let token = Hs256.token(&Header::empty(), &claims, &key)?;
+///
+/// let expectations = Expectations::default()
+///     .with_issuer("https://issuer.example.com")
+///     .with_audience("api")
+///     .with_required_claim("sub")
+///     .with_allowed_algorithm("HS256");
+/// let validated: Token<serde_json::Value> = validate(&Hs256, &token, &key, &expectations)?;
+/// assert_eq!(validated.claims().custom["sub"], "alice");
+///
+/// let expectations = expectations.with_required_claim("email");
+/// let err = validate::<_, serde_json::Value, Empty>(&Hs256, &token, &key, &expectations)
+///     .unwrap_err();
+/// assert!(matches!(err, ExpectationError::MissingClaim(claim) if claim == "email"));
+/// # Ok(())
+/// # }
+/// ```
+pub fn validate<A, T, H>(
+    algorithm: &A,
+    token: &str,
+    verifying_key: &A::VerifyingKey,
+    expectations: &Expectations,
+) -> Result<Token<T, H>, ExpectationError>
+where
+    A: Algorithm,
+    T: DeserializeOwned,
+    H: DeserializeOwned + Clone,
+{
+    let token = UntrustedToken::<H>::parse_with_options(token, &expectations.parse_options)?;
+    expectations.check_algorithm(token.algorithm())?;
+    let extensions = expectations.understood_extensions();
+    let verified = algorithm
+        .validator::<T>(verifying_key)
+        .with_understood_extensions(&extensions)
+        .verify_signature(&token)?;
+    let claims = verified.deserialize_claims::<DynamicClaims>()?;
+    expectations.check_claims(&claims)?;
+
+    let custom = T::deserialize(Value::Object(claims.custom.into_inner()))
+        .map_err(ValidationError::MalformedClaims)?;
+    let claims = Claims {
+        expiration: claims.expiration,
+        not_before: claims.not_before,
+        issued_at: claims.issued_at,
+        custom,
+    };
+    Ok(Token::new(&token, claims))
+}
+
+#[cfg(all(test, feature = "clock"))]
+mod tests {
+    use assert_matches::assert_matches;
+    use chrono::Duration;
+    use serde_json::Value;
+
+    use super::*;
+    use crate::{
+        alg::{Hs256, Hs256Key},
+        test_helpers::key,
+        Claims,
+    };
+
+    fn create_token(claims: &Claims<Value>, key_id: &str, key: &Hs256Key) -> String {
+        let header = Header::empty().with_key_id(key_id);
+        Hs256.token(&header, claims, key).unwrap().into()
+    }
+
+    fn resolve_key(header: &Header) -> impl Future<Output = anyhow::Result<Option<Hs256Key>>> {
+        let key_id = header.key_id.clone();
+        async move {
+            match key_id.as_deref() {
+                Some("main") => Ok(Some(key())),
+                Some("offline") => Err(anyhow::anyhow!("JWKS endpoint is unavailable")),
+                _ => Ok(None),
+            }
+        }
+    }
+
+    async fn validate(token: &str) -> Result<Token<Value>, TokenValidatorError> {
+        TokenValidator::new(Hs256, resolve_key, TimeOptions::default())
+            .validate(token)
+            .await
+    }
+
+    #[tokio::test]
+    async fn validating_token() {
+        let claims = Claims::new(serde_json::json!({ "sub": "alice" }))
+            .set_duration_and_issuance(&TimeOptions::default(), Duration::try_minutes(5).unwrap());
+        let token = create_token(&claims, "main", &key());
+        let token = validate(&token).await.unwrap();
+        assert_eq!(token.claims().custom["sub"], "alice");
+    }
+
+    #[tokio::test]
+    async fn validation_errors() {
+        let err = validate("not a token").await.unwrap_err();
+        assert_matches!(err, TokenValidatorError::Parse(_));
+
+        let claims = Claims::new(Value::Null)
+            .set_duration(&TimeOptions::default(), Duration::try_minutes(5).unwrap());
+        let token = create_token(&claims, "other", &key());
+        let err = validate(&token).await.unwrap_err();
+        assert_matches!(err, TokenValidatorError::UnknownKey);
+
+        let token = create_token(&claims, "offline", &key());
+        let err = validate(&token).await.unwrap_err();
+        assert_matches!(
+            &err,
+            TokenValidatorError::KeyResolution(err) if err.to_string().contains("unavailable")
+        );
+
+        let other_key = Hs256Key::new(b"other_secret_key_donut_steel");
+        let token = create_token(&claims, "main", &other_key);
+        let err = validate(&token).await.unwrap_err();
+        assert_matches!(
+            err,
+            TokenValidatorError::Validation(ValidationError::InvalidSignature)
+        );
+
+        let expired_claims = Claims::new(Value::Null)
+            .set_duration(&TimeOptions::default(), -Duration::try_minutes(5).unwrap());
+        let token = create_token(&expired_claims, "main", &key());
+        let err = validate(&token).await.unwrap_err();
+        assert_matches!(
+            err,
+            TokenValidatorError::Validation(ValidationError::Expired)
+        );
+
+        let token = create_token(&Claims::new(Value::Null), "main", &key());
+        let err = validate(&token).await.unwrap_err();
+        assert_matches!(
+            err,
+            TokenValidatorError::Validation(ValidationError::NoClaim(_))
+        );
+    }
+
+    #[tokio::test]
+    async fn error_kinds() {
+        let err = validate("not a token").await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MalformedToken);
+
+        let claims = Claims::new(Value::Null)
+            .set_duration(&TimeOptions::default(), Duration::try_minutes(5).unwrap());
+        let token = create_token(&claims, "other", &key());
+        let err = validate(&token).await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnknownKey);
+
+        let token = create_token(&claims, "offline", &key());
+        let err = validate(&token).await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Internal);
+
+        let other_key = Hs256Key::new(b"other_secret_key_donut_steel");
+        let token = create_token(&claims, "main", &other_key);
+        let err = validate(&token).await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidSignature);
+
+        let expired_claims = Claims::new(Value::Null)
+            .set_duration(&TimeOptions::default(), -Duration::try_minutes(5).unwrap());
+        let token = create_token(&expired_claims, "main", &key());
+        let err = validate(&token).await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Expired);
+        assert_eq!(err.kind().to_string(), "expired");
+
+        let token = create_token(&Claims::new(Value::Null), "main", &key());
+        let err = validate(&token).await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MissingClaim);
+    }
+
+    #[test]
+    fn validating_with_expectations() {
+        let claims = Claims::new(serde_json::json!({
+            "iss": "https://issuer.example.com",
+            "aud": ["api", "admin"],
+            "sub": "alice",
+        }))
+        .set_duration(&TimeOptions::default(), Duration::try_minutes(5).unwrap());
+        let token = create_token(&claims, "main", &key());
+        let check = |expectations: &Expectations| {
+            super::validate::<_, Value, Empty>(&Hs256, &token, &key(), expectations)
+        };
+
+        let expectations = Expectations::default()
+            .with_issuer("https://issuer.example.com")
+            .with_audience("other")
+            .with_audience("admin")
+            .with_required_claim("sub")
+            .with_required_claim("exp")
+            .with_allowed_algorithm("HS256");
+        let validated = check(&expectations).unwrap();
+        assert_eq!(validated.claims().custom["sub"], "alice");
+
+        let err =
+            check(&Expectations::default().with_issuer("https://other.example.com")).unwrap_err();
+        assert_matches!(err, ExpectationError::ClaimMismatch("iss"));
+        assert_eq!(err.kind(), ErrorKind::IssuerMismatch);
+        let err = check(&Expectations::default().with_audience("other")).unwrap_err();
+        assert_matches!(err, ExpectationError::ClaimMismatch("aud"));
+        assert_eq!(err.kind(), ErrorKind::AudienceMismatch);
+        let err = check(&Expectations::default().with_required_claim("iat")).unwrap_err();
+        assert_matches!(err, ExpectationError::MissingClaim(claim) if claim == "iat");
+        let err = check(&Expectations::default().with_allowed_algorithm("ES256")).unwrap_err();
+        assert_matches!(&err, ExpectationError::DisallowedAlgorithm(alg) if alg == "HS256");
+        assert_eq!(err.kind(), ErrorKind::UnsupportedAlgorithm);
+
+        let err = super::validate::<_, Value, Empty>(&Hs256, "not a token", &key(), &expectations)
+            .unwrap_err();
+        assert_matches!(err, ExpectationError::Parse(_));
+        let other_key = Hs256Key::new(b"other_secret_key_donut_steel");
+        let err = super::validate::<_, Value, Empty>(&Hs256, &token, &other_key, &expectations)
+            .unwrap_err();
+        assert_matches!(
+            err,
+            ExpectationError::Validation(ValidationError::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn validating_with_custom_header_and_parse_options() {
+        #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+        struct CustomHeader {
+            custom: String,
+        }
+
+        let claims = Claims::new(serde_json::json!({ "sub": "alice" }))
+            .set_duration(&TimeOptions::default(), Duration::try_minutes(5).unwrap());
+        let header = Header::new(CustomHeader {
+            custom: "custom".to_owned(),
+        });
+        let token = Hs256.token(&header, &claims, &key()).unwrap();
+
+        let expectations = Expectations::default().with_required_claim("sub");
+        let validated =
+            super::validate::<_, Value, CustomHeader>(&Hs256, &token, &key(), &expectations)
+                .unwrap();
+        assert_eq!(validated.header().other_fields.custom, "custom");
+        assert_eq!(validated.claims().custom["sub"], "alice");
+
+        let mut parse_options = ParseOptions::default();
+        parse_options.max_token_len = 16;
+        let expectations = expectations.with_parse_options(parse_options);
+        let err = super::validate::<_, Value, CustomHeader>(&Hs256, &token, &key(), &expectations)
+            .unwrap_err();
+        assert_matches!(err, ExpectationError::Parse(ParseError::TokenTooLong));
+    }
+
+    #[test]
+    fn validating_with_critical_extensions() {
+        #[derive(Debug, Clone, serde::Serialize)]
+        struct Extensions {
+            crit: Vec<&'static str>,
+            exp_policy: &'static str,
+        }
+
+        let claims = Claims::new(Value::Null)
+            .set_duration(&TimeOptions::default(), Duration::try_minutes(5).unwrap());
+        let header = Header::new(Extensions {
+            crit: vec!["exp_policy"],
+            exp_policy: "strict",
+        });
+        let token = Hs256.token(&header, &claims, &key()).unwrap();
+
+        let expectations = Expectations::default();
+        let err =
+            super::validate::<_, Value, Empty>(&Hs256, &token, &key(), &expectations).unwrap_err();
+        assert_matches!(
+            err,
+            ExpectationError::Validation(ValidationError::UnknownCriticalExtension(ext))
+                if ext == "exp_policy"
+        );
+
+        let expectations = expectations.with_understood_extensions(&["exp_policy"]);
+        super::validate::<_, Value, Empty>(&Hs256, &token, &key(), &expectations).unwrap();
+        let report = expectations.validation_report(&Hs256, &token, &key());
+        assert!(report.is_valid(), "{report}");
+    }
+
+    #[test]
+    fn expectations_check_time_claims() {
+        let expectations = Expectations::default();
+        let check = |claims: &Claims<Value>| {
+            let token = create_token(claims, "main", &key());
+            super::validate::<_, Value, Empty>(&Hs256, &token, &key(), &expectations)
+        };
+
+        let err = check(&Claims::new(Value::Null)).unwrap_err();
+        assert_matches!(
+            err,
+            ExpectationError::Validation(ValidationError::NoClaim(_))
+        );
+
+        let expired_claims = Claims::new(Value::Null)
+            .set_duration(&TimeOptions::default(), -Duration::try_minutes(5).unwrap());
+        let err = check(&expired_claims).unwrap_err();
+        assert_matches!(err, ExpectationError::Validation(ValidationError::Expired));
+
+        let lenient = Expectations::default().with_leeway(Duration::try_minutes(10).unwrap());
+        let token = create_token(&expired_claims, "main", &key());
+        super::validate::<_, Value, Empty>(&Hs256, &token, &key(), &lenient).unwrap();
+
+        let immature_claims = Claims::new(Value::Null)
+            .set_duration(&TimeOptions::default(), Duration::try_minutes(5).unwrap())
+            .set_not_before(chrono::Utc::now() + Duration::try_minutes(3).unwrap());
+        let err = check(&immature_claims).unwrap_err();
+        assert_matches!(
+            err,
+            ExpectationError::Validation(ValidationError::NotMature)
+        );
+    }
+
+    #[test]
+    fn validation_report_lists_all_failures() {
+        let claims = Claims::new(serde_json::json!({ "iss": "https://other.example.com" }))
+            .set_duration(&TimeOptions::default(), -Duration::try_minutes(5).unwrap());
+        let token = create_token(&claims, "main", &key());
+        let expectations = Expectations::default()
+            .with_issuer("https://issuer.example.com")
+            .with_required_claim("sub")
+            .with_allowed_algorithm("ES256");
+
+        let report = expectations.validation_report(&Hs256, &token, &key());
+        assert!(!report.is_valid());
+        assert_matches!(
+            report.failures(),
+            [
+                ValidationFailure::DisallowedAlgorithm(alg),
+                ValidationFailure::Time(ValidationError::Expired),
+                ValidationFailure::MissingClaim(claim),
+                ValidationFailure::ClaimMismatch("iss"),
+            ] if alg == "HS256" && claim == "sub"
+        );
+
+        let other_key = Hs256Key::new(b"other_secret_key_donut_steel");
+        let report = Expectations::default().validation_report(&Hs256, &token, &other_key);
+        assert_matches!(
+            report.failures(),
+            [
+                ValidationFailure::Integrity(ValidationError::InvalidSignature),
+                ValidationFailure::Time(ValidationError::Expired),
+            ]
+        );
+
+        let report = Expectations::default().validation_report(&Hs256, "not a token", &key());
+        assert_matches!(report.failures(), [ValidationFailure::Parse(_)]);
+
+        let valid_claims = Claims::new(Value::Null)
+            .set_duration(&TimeOptions::default(), Duration::try_minutes(5).unwrap());
+        let token = create_token(&valid_claims, "main", &key());
+        let report = Expectations::default().validation_report(&Hs256, &token, &key());
+        assert!(report.is_valid());
+        assert_eq!(report.to_string(), "token is valid");
+    }
+}
@@ -2,16 +2,17 @@
 
 use assert_matches::assert_matches;
 use base64ct::{Base64UrlUnpadded, Encoding};
-use chrono::{TimeZone, Utc};
 use rand::thread_rng;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 
 mod shared;
 
-use crate::shared::{create_claims, test_algorithm, Obj, SampleClaims};
+use crate::shared::{create_claims, test_algorithm, CompactClaims, Obj, SampleClaims};
 use jwt_compact::{
-    alg::*, prelude::*, Algorithm, AlgorithmExt, ParseError, Thumbprint, ValidationError,
+    alg::*, prelude::*, Algorithm, AlgorithmExt, AlgorithmSignature, AsyncAdapter,
+    AsyncAlgorithmExt, AsyncVerifier, AsyncVerifierExt, CreationError, ParseError,
+    PrepareVerifyingKey, PreparedVerifier, Thumbprint, Timestamp, ValidationError, VerifyFuture,
 };
 
 #[test]
@@ -35,7 +36,7 @@ fn hs256_reference() {
 
     let validated_token = Hs256.validator::<Obj>(&key).validate(&token).unwrap();
     assert_eq!(
-        validated_token.claims().expiration.unwrap().timestamp(),
+        validated_token.claims().expiration.unwrap().as_secs(),
         1_300_819_380
     );
     assert_eq!(validated_token.claims().custom["iss"], json!("joe"));
@@ -86,7 +87,7 @@ fn hs384_reference() {
         .validator::<SampleClaims>(&key)
         .validate(&token)
         .unwrap();
-    assert_eq!(token.claims().issued_at.unwrap().timestamp(), 1_516_239_022);
+    assert_eq!(token.claims().issued_at.unwrap().as_secs(), 1_516_239_022);
     assert_eq!(
         token.claims().custom,
         SampleClaims {
@@ -116,7 +117,7 @@ fn hs512_reference() {
         .validator::<SampleClaims>(&key)
         .validate(&token)
         .unwrap();
-    assert_eq!(token.claims().issued_at.unwrap().timestamp(), 1_516_239_122);
+    assert_eq!(token.claims().issued_at.unwrap().as_secs(), 1_516_239_122);
     assert_eq!(
         token.claims().custom,
         SampleClaims {
@@ -158,10 +159,7 @@ fn es256_reference() {
         .validator::<Obj>(&public_key)
         .validate(&token)
         .unwrap();
-    assert_eq!(
-        token.claims().expiration.unwrap().timestamp(),
-        1_300_819_380
-    );
+    assert_eq!(token.claims().expiration.unwrap().as_secs(), 1_300_819_380);
     let expected_claims = json!({
         "iss": "joe",
         "http://example.com/is_root": true,
@@ -199,7 +197,7 @@ fn es256k_reference() {
         .validator::<Obj>(&public_key)
         .validate(&token)
         .unwrap();
-    assert_eq!(token.claims().issued_at.unwrap().timestamp(), 1_561_814_788);
+    assert_eq!(token.claims().issued_at.unwrap().as_secs(), 1_561_814_788);
     let expected_claims = json!({
         "bla": "bla",
         "iss": "did:uport:2nQtiQG6Cgm1GYTBaaKAgr76uY7iSexUkqX",
@@ -257,7 +255,7 @@ fn ed25519_reference() {
         .validator::<Obj>(&public_key)
         .validate(&token)
         .unwrap();
-    assert_eq!(token.claims().issued_at.unwrap().timestamp(), 1_561_815_526);
+    assert_eq!(token.claims().issued_at.unwrap().as_secs(), 1_561_815_526);
     let expected_claims = json!({
         "foo": "bar",
         "iss": "did:uport:2nQtiQG6Cgm1GYTBaaKAgr76uY7iSexUkqX",
@@ -289,9 +287,9 @@ fn wso2_reference() {
         token.header().certificate_sha1_thumbprint.as_ref(),
         Some(Thumbprint::String(s)) if s == "6bf8e136eb36d4a56ea05c7ae4b9a45b63bf975d"
     );
-    let claims = token.deserialize_claims_unchecked::<Obj>().unwrap();
+    let claims = token.deserialize_claims_unverified::<Obj>().unwrap();
 
-    let exp = Utc.timestamp_opt(1452594892, 0).unwrap();
+    let exp = Timestamp::from_secs(1452594892);
     assert_eq!(claims.expiration, Some(exp));
     assert_eq!(claims.custom["sub"], "admin@carbon.super");
     assert_eq!(claims.custom["http://wso2.org/claims/organization"], "WSO2");
@@ -315,7 +313,26 @@ fn hs512_algorithm() {
     test_algorithm(&Hs512, &key, &key);
 }
 
-#[cfg(feature = "serde_cbor")]
+#[test]
+fn generating_hmac_keys_with_generic_trait() {
+    let key = <Hs256Key as GenerateKey<_>>::generate(&mut thread_rng());
+    assert_eq!(key.as_ref().len(), 64);
+    assert!(StrongKey::try_from(key).is_ok());
+
+    let (signing_key, verifying_key) = Hs384Key::generate_pair(&mut thread_rng());
+    assert_eq!(signing_key.as_ref(), verifying_key.as_ref());
+    test_algorithm(&Hs384, &signing_key, &verifying_key);
+}
+
+#[test]
+fn hmac_keys_do_not_leak_in_debug_output() {
+    let key = Hs256Key::new(b"super_secret_key_donut_steel");
+    assert_eq!(format!("{key:?}"), "Hs256Key { len: 28 }");
+    let key = Hs512Key::generate(&mut thread_rng()).into_inner();
+    assert_eq!(format!("{key:?}"), "Hs512Key { len: 128 }");
+}
+
+#[cfg(feature = "ciborium")]
 #[test]
 fn compact_token_hs256() {
     let claims = create_claims();
@@ -335,16 +352,15 @@ fn compact_token_hs256() {
     assert_eq!(*token.claims(), claims);
 
     // Check that we can collect unknown / hard to parse claims into `Claims.custom`.
-    // `serde_cbor::Value` is not defined without `std`.
     #[cfg(feature = "std")]
     {
         use std::collections::HashMap;
 
-        let generic_token: Token<HashMap<String, serde_cbor::Value>> =
+        let generic_token: Token<HashMap<String, ciborium::Value>> =
             Hs256.validator(&key).validate(&untrusted_token).unwrap();
         assert_matches::assert_matches!(
             generic_token.claims().custom["sub"],
-            serde_cbor::Value::Bytes(_)
+            ciborium::Value::Bytes(_)
         );
     }
 }
@@ -352,25 +368,16 @@ fn compact_token_hs256() {
 #[cfg(feature = "exonum-crypto")]
 #[test]
 fn ed25519_algorithm() {
-    use exonum_crypto::gen_keypair;
-
-    let (verifying_key, signing_key) = gen_keypair();
+    let (signing_key, verifying_key) = exonum_crypto::SecretKey::generate_pair(&mut thread_rng());
     test_algorithm(&Ed25519, &signing_key, &verifying_key);
 }
 
 #[cfg(feature = "ed25519-dalek")]
 #[test]
 fn ed25519_algorithm() {
-    use ed25519_dalek::{SecretKey, SigningKey};
-    use rand_core::RngCore;
-
-    // Since `ed25519_dalek` works with `rand` v0.7 rather than v0.8, we use this roundabout way
-    // to generate a keypair.
-    let mut secret = SecretKey::default();
-    thread_rng().fill_bytes(&mut secret);
-    let signing_key = SigningKey::from(secret);
-
-    test_algorithm(&Ed25519, &signing_key, signing_key.as_ref());
+    let (signing_key, verifying_key) = ed25519_dalek::SigningKey::generate_pair(&mut thread_rng());
+    assert_eq!(verifying_key, *signing_key.as_ref());
+    test_algorithm(&Ed25519, &signing_key, &verifying_key);
 }
 
 #[cfg(feature = "ed25519-compact")]
@@ -378,24 +385,19 @@ fn ed25519_algorithm() {
 fn ed25519_algorithm() {
     let (signing_key, verifying_key) = Ed25519::generate(&mut thread_rng());
     test_algorithm(&Ed25519, &signing_key, &verifying_key);
+
+    let (signing_key, verifying_key) =
+        <ed25519_compact::SecretKey as GenerateKey<_>>::generate_pair(&mut thread_rng());
+    test_algorithm(&Ed25519, &signing_key, &verifying_key);
 }
 
 #[cfg(any(feature = "es256k", feature = "k256"))]
 #[test]
 fn es256k_algorithm() {
-    use rand::Rng;
-
     type SecretKey = <Es256k as Algorithm>::SigningKey;
     type PublicKey = <Es256k as Algorithm>::VerifyingKey;
 
-    let mut rng = thread_rng();
-    let signing_key = loop {
-        let bytes: [u8; 32] = rng.gen();
-        if let Ok(key) = SecretKey::from_slice(&bytes) {
-            break key;
-        }
-    };
-    let verifying_key = signing_key.to_verifying_key();
+    let (signing_key, verifying_key) = SecretKey::generate_pair(&mut thread_rng());
     let es256k: Es256k = Es256k::default();
     test_algorithm(&es256k, &signing_key, &verifying_key);
 
@@ -411,6 +413,69 @@ fn es256k_algorithm() {
     assert_eq!(verifying_key, verifying_key_copy);
 }
 
+/// Emulates signing by an external party (e.g., via a threshold signing protocol).
+#[cfg(any(
+    feature = "exonum-crypto",
+    feature = "ed25519-dalek",
+    feature = "ed25519-compact",
+    feature = "es256k",
+    feature = "k256"
+))]
+fn test_external_signing<A: Algorithm>(
+    algorithm: &A,
+    signing_key: &A::SigningKey,
+    verifying_key: &A::VerifyingKey,
+) {
+    let claims = create_claims();
+    let header = Header::empty().with_key_id("threshold");
+    let unsigned_token = algorithm.unsigned_token(&header, &claims).unwrap();
+    let signing_input = String::from_utf8(unsigned_token.signing_input().to_vec()).unwrap();
+    assert_eq!(signing_input.split('.').count(), 2);
+
+    let signature = algorithm.sign(signing_key, unsigned_token.signing_input());
+    let token = unsigned_token.attach_signature(&signature);
+    assert!(token.starts_with(&signing_input));
+
+    let token = UntrustedToken::new(&token).unwrap();
+    assert_eq!(token.header().key_id.as_deref(), Some("threshold"));
+    let token = algorithm
+        .validator::<CompactClaims>(verifying_key)
+        .validate(&token)
+        .unwrap();
+    assert_eq!(token.claims().custom, claims.custom);
+
+    // Signature over a different message is rejected.
+    let unsigned_token = algorithm.unsigned_token(&header, &claims).unwrap();
+    let token = unsigned_token.attach_signature(&algorithm.sign(signing_key, b"other"));
+    let token = UntrustedToken::new(&token).unwrap();
+    let err = algorithm
+        .validator::<CompactClaims>(verifying_key)
+        .validate(&token)
+        .unwrap_err();
+    assert_matches!(err, ValidationError::InvalidSignature);
+}
+
+#[cfg(any(
+    feature = "exonum-crypto",
+    feature = "ed25519-dalek",
+    feature = "ed25519-compact"
+))]
+#[test]
+fn ed25519_external_signing() {
+    let (signing_key, verifying_key) =
+        <<Ed25519 as Algorithm>::SigningKey as GenerateKey<_>>::generate_pair(&mut thread_rng());
+    test_external_signing(&Ed25519, &signing_key, &verifying_key);
+}
+
+#[cfg(any(feature = "es256k", feature = "k256"))]
+#[test]
+fn es256k_external_signing() {
+    type SecretKey = <Es256k as Algorithm>::SigningKey;
+
+    let (signing_key, verifying_key) = SecretKey::generate_pair(&mut thread_rng());
+    test_external_signing(&<Es256k>::default(), &signing_key, &verifying_key);
+}
+
 #[cfg(any(feature = "es256k", feature = "k256"))]
 #[test]
 fn high_s_in_signature_is_successfully_validated() {
@@ -442,19 +507,10 @@ fn high_s_in_signature_is_successfully_validated() {
 #[cfg(feature = "p256")]
 #[test]
 fn es256_algorithm() {
-    use rand::Rng;
-
     type SecretKey = <Es256 as Algorithm>::SigningKey;
     type PublicKey = <Es256 as Algorithm>::VerifyingKey;
 
-    let mut rng = thread_rng();
-    let signing_key = loop {
-        let bytes: [u8; 32] = rng.gen();
-        if let Ok(key) = SecretKey::from_slice(&bytes) {
-            break key;
-        }
-    };
-    let verifying_key = signing_key.to_verifying_key();
+    let (signing_key, verifying_key) = SecretKey::generate_pair(&mut thread_rng());
     test_algorithm(&Es256, &signing_key, &verifying_key);
 
     // Test correctness of `SigningKey` / `VerifyingKey` trait implementations.
@@ -493,7 +549,7 @@ fn test_algorithm_with_custom_header<A: Algorithm>(
     let claims = create_claims();
 
     // Successful case with a compact token.
-    #[cfg(feature = "serde_cbor")]
+    #[cfg(feature = "ciborium")]
     {
         let token_string = algorithm
             .compact_token(&header, &claims, signing_key)
@@ -521,7 +577,7 @@ fn test_algorithm_with_custom_header<A: Algorithm>(
     let mangled_header = Base64UrlUnpadded::encode_string(mangled_header.as_bytes());
     let header_end = token_string.find('.').unwrap();
     assert_ne!(mangled_header, &token_string[..header_end]);
-    let mut mangled_str = token_string.clone();
+    let mut mangled_str = token_string.to_string();
     mangled_str.replace_range(..header_end, &mangled_header);
     let token = UntrustedToken::new(&mangled_str).unwrap();
     let err = algorithm
@@ -558,3 +614,330 @@ fn hs512_algorithm_with_custom_header() {
     let key = Hs512Key::generate(&mut thread_rng()).into_inner();
     test_algorithm_with_custom_header(&Hs512, &key, &key);
 }
+
+fn test_batch_validation<A: Algorithm>(
+    algorithm: &A,
+    signing_key: &A::SigningKey,
+    verifying_key: &A::VerifyingKey,
+    other_signing_key: &A::SigningKey,
+) {
+    let claims = create_claims();
+    let mut tokens: Vec<String> = (0..5)
+        .map(|i| {
+            let header = Header::empty().with_key_id(i.to_string());
+            algorithm
+                .token(&header, &claims, signing_key)
+                .unwrap()
+                .into()
+        })
+        .collect();
+    tokens.push(
+        algorithm
+            .token(&Header::empty(), &claims, other_signing_key)
+            .unwrap()
+            .into(),
+    );
+    let (signed_data, _) = tokens[0].rsplit_once('.').unwrap();
+    tokens.push(format!("{signed_data}.AAAA"));
+
+    let tokens: Vec<_> = tokens
+        .iter()
+        .map(|token| UntrustedToken::new(token).unwrap())
+        .collect();
+    let results = algorithm.validate_batch::<CompactClaims, _>(&tokens, verifying_key);
+    assert_eq!(results.len(), tokens.len());
+    for (i, result) in results[..5].iter().enumerate() {
+        let token = result.as_ref().unwrap();
+        assert_eq!(token.header().key_id, Some(i.to_string()));
+        assert_eq!(token.claims().custom, claims.custom);
+    }
+    assert_matches!(results[5], Err(ValidationError::InvalidSignature));
+    assert_matches!(results[6], Err(ValidationError::InvalidSignatureLen { .. }));
+
+    let results = algorithm.validate_batch::<CompactClaims, _>(&tokens[..0], verifying_key);
+    assert!(results.is_empty());
+}
+
+#[test]
+fn hs256_batch_validation() {
+    let key = Hs256Key::generate(&mut thread_rng()).into_inner();
+    let other_key = Hs256Key::generate(&mut thread_rng()).into_inner();
+    test_batch_validation(&Hs256, &key, &key, &other_key);
+}
+
+#[test]
+fn hs512_batch_validation() {
+    let key = Hs512Key::generate(&mut thread_rng()).into_inner();
+    let other_key = Hs512Key::generate(&mut thread_rng()).into_inner();
+    test_batch_validation(&Hs512, &key, &key, &other_key);
+}
+
+#[cfg(any(
+    feature = "exonum-crypto",
+    feature = "ed25519-dalek",
+    feature = "ed25519-compact"
+))]
+#[test]
+fn ed25519_batch_validation() {
+    type SecretKey = <Ed25519 as Algorithm>::SigningKey;
+
+    let (signing_key, verifying_key) = SecretKey::generate_pair(&mut thread_rng());
+    let (other_key, _) = SecretKey::generate_pair(&mut thread_rng());
+    test_batch_validation(&Ed25519, &signing_key, &verifying_key, &other_key);
+}
+
+fn test_prepared_verifier<A>(
+    algorithm: A,
+    signing_key: &A::SigningKey,
+    verifying_key: &A::VerifyingKey,
+    other_signing_key: &A::SigningKey,
+) where
+    A: PrepareVerifyingKey + Send + Sync,
+{
+    fn assert_send_sync<T: Send + Sync>(_: &T) {}
+
+    let claims = create_claims();
+    let header = Header::empty().with_key_id("main");
+    let token = algorithm.token(&header, &claims, signing_key).unwrap();
+    let other_token = algorithm
+        .token(&header, &claims, other_signing_key)
+        .unwrap();
+
+    let verifier = PreparedVerifier::new(algorithm, verifying_key);
+    assert_send_sync(&verifier);
+    for _ in 0..3 {
+        let token = UntrustedToken::new(&token).unwrap();
+        let token = verifier.validate::<CompactClaims, _>(&token).unwrap();
+        assert_eq!(token.header().key_id.as_deref(), Some("main"));
+        assert_eq!(token.claims().custom, claims.custom);
+
+        let other_token = UntrustedToken::new(&other_token).unwrap();
+        let err = verifier
+            .validate::<CompactClaims, _>(&other_token)
+            .unwrap_err();
+        assert_matches!(err, ValidationError::InvalidSignature);
+    }
+
+    let token = UntrustedToken::new(&token).unwrap();
+    let signed = verifier
+        .validate_for_signed_token::<CompactClaims, _>(&token)
+        .unwrap();
+    assert_eq!(
+        AlgorithmSignature::as_bytes(&signed.signature),
+        token.signature_bytes()
+    );
+}
+
+#[test]
+fn hs256_prepared_verifier() {
+    let key = Hs256Key::generate(&mut thread_rng()).into_inner();
+    let other_key = Hs256Key::generate(&mut thread_rng()).into_inner();
+    test_prepared_verifier(Hs256, &key, &key, &other_key);
+}
+
+#[test]
+fn strong_hs384_prepared_verifier() {
+    let key = Hs384Key::generate(&mut thread_rng());
+    let other_key = Hs384Key::generate(&mut thread_rng());
+    test_prepared_verifier(StrongAlg(Hs384), &key, &key, &other_key);
+}
+
+#[cfg(any(
+    feature = "exonum-crypto",
+    feature = "ed25519-dalek",
+    feature = "ed25519-compact"
+))]
+#[test]
+fn ed25519_prepared_verifier() {
+    type SecretKey = <Ed25519 as Algorithm>::SigningKey;
+
+    let (signing_key, verifying_key) = SecretKey::generate_pair(&mut thread_rng());
+    let (other_key, _) = SecretKey::generate_pair(&mut thread_rng());
+    test_prepared_verifier(
+        Ed25519::with_specific_name(),
+        &signing_key,
+        &verifying_key,
+        &other_key,
+    );
+}
+
+#[cfg(any(feature = "es256k", feature = "k256"))]
+#[test]
+fn es256k_prepared_verifier() {
+    type SecretKey = <Es256k as Algorithm>::SigningKey;
+
+    let (signing_key, verifying_key) = SecretKey::generate_pair(&mut thread_rng());
+    let (other_key, _) = SecretKey::generate_pair(&mut thread_rng());
+    test_prepared_verifier(
+        <Es256k>::default(),
+        &signing_key,
+        &verifying_key,
+        &other_key,
+    );
+}
+
+#[cfg(feature = "p256")]
+#[test]
+fn es256_prepared_verifier() {
+    type SecretKey = <Es256 as Algorithm>::SigningKey;
+
+    let (signing_key, verifying_key) = SecretKey::generate_pair(&mut thread_rng());
+    let (other_key, _) = SecretKey::generate_pair(&mut thread_rng());
+    test_prepared_verifier(Es256, &signing_key, &verifying_key, &other_key);
+}
+
+#[test]
+fn signing_keys_are_zeroized_on_drop() {
+    fn assert_zeroize_on_drop<T: zeroize::ZeroizeOnDrop>() {}
+
+    assert_zeroize_on_drop::<Hs256Key>();
+    assert_zeroize_on_drop::<Hs384Key>();
+    assert_zeroize_on_drop::<Hs512Key>();
+    #[cfg(feature = "p256")]
+    assert_zeroize_on_drop::<<Es256 as Algorithm>::SigningKey>();
+    #[cfg(feature = "k256")]
+    assert_zeroize_on_drop::<<Es256k as Algorithm>::SigningKey>();
+    #[cfg(feature = "rsa")]
+    assert_zeroize_on_drop::<<Rsa as Algorithm>::SigningKey>();
+    #[cfg(all(feature = "ed25519-dalek", feature = "zeroize"))]
+    assert_zeroize_on_drop::<<Ed25519 as Algorithm>::SigningKey>();
+}
+
+/// Signer holding the key locally; in real use cases, it would communicate with an HSM etc.
+struct CountingSigner {
+    key: Hs256Key,
+    calls: std::sync::atomic::AtomicUsize,
+}
+
+impl RemoteSigner<Hs256> for CountingSigner {
+    fn sign<'a>(&'a self, message: &'a [u8]) -> jwt_compact::SignFuture<'a, Hs256Signature> {
+        self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        Box::pin(async move { Ok(Hs256.sign(&self.key, message)) })
+    }
+}
+
+#[tokio::test]
+async fn signing_with_remote_signer() {
+    let key = Hs256Key::generate(&mut thread_rng()).into_inner();
+    let signer = CountingSigner {
+        key: key.clone(),
+        calls: std::sync::atomic::AtomicUsize::new(0),
+    };
+    let claims = create_claims();
+    let header = Header::empty().with_key_id("remote");
+    let token = Remote::new(Hs256)
+        .token_async(&header, &claims, &signer)
+        .await
+        .unwrap();
+    assert_eq!(signer.calls.into_inner(), 1);
+    assert_eq!(token, Hs256.token(&header, &claims, &key).unwrap());
+
+    let token = UntrustedToken::new(&token).unwrap();
+    assert_eq!(token.algorithm(), "HS256");
+    assert_eq!(token.header().key_id.as_deref(), Some("remote"));
+    let token = Hs256
+        .validator::<CompactClaims>(&key)
+        .validate(&token)
+        .unwrap();
+    assert_eq!(token.claims().custom, claims.custom);
+}
+
+#[tokio::test]
+async fn validating_remotely_signed_token() {
+    let key = Hs256Key::generate(&mut thread_rng()).into_inner();
+    let signer = |message: Vec<u8>| {
+        let key = key.clone();
+        async move { anyhow::Ok(Hs256.sign(&key, &message)) }
+    };
+    let alg = Remote::new(Hs256);
+    let token = alg
+        .token_async(&Header::empty(), &create_claims(), &signer)
+        .await
+        .unwrap();
+    let token = UntrustedToken::new(&token).unwrap();
+    alg.async_validator::<CompactClaims>(&key)
+        .validate(&token)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn remote_signer_errors() {
+    let signer = |_: Vec<u8>| async { Err::<Hs256Signature, _>(anyhow::anyhow!("HSM is offline")) };
+    let err = Remote::new(Hs256)
+        .token_async(&Header::empty(), &create_claims(), &signer)
+        .await
+        .unwrap_err();
+    assert_matches!(&err, CreationError::Signing(err) if err.to_string() == "HSM is offline");
+}
+
+#[tokio::test]
+async fn async_adapter_for_sync_algorithm() {
+    let alg = AsyncAdapter::new(Hs256);
+    let key = Hs256Key::generate(&mut thread_rng()).into_inner();
+    let claims = create_claims();
+    let token = alg
+        .token_async(&Header::empty(), &claims, &key)
+        .await
+        .unwrap();
+    assert_eq!(token, Hs256.token(&Header::empty(), &claims, &key).unwrap());
+
+    let token = UntrustedToken::new(&token).unwrap();
+    let validated = alg
+        .async_validator::<CompactClaims>(&key)
+        .validate(&token)
+        .await
+        .unwrap();
+    assert_eq!(validated.claims().custom, claims.custom);
+
+    let other_key = Hs256Key::generate(&mut thread_rng()).into_inner();
+    let err = alg
+        .async_validator::<CompactClaims>(&other_key)
+        .validate(&token)
+        .await
+        .unwrap_err();
+    assert_matches!(err, ValidationError::InvalidSignature);
+
+    let err = AsyncAdapter::new(Hs384)
+        .async_validator::<CompactClaims>(&Hs384Key::new(key.as_ref()))
+        .validate(&token)
+        .await
+        .unwrap_err();
+    assert_matches!(err, ValidationError::AlgorithmMismatch { .. });
+}
+
+/// Verifier that is always unavailable.
+struct UnavailableVerifier;
+
+impl AsyncVerifier for UnavailableVerifier {
+    type VerifyingKey = ();
+    type Signature = RawSignature;
+
+    fn name(&self) -> std::borrow::Cow<'static, str> {
+        "HS256".into()
+    }
+
+    fn verify_signature_async<'a>(
+        &'a self,
+        _signature: &'a RawSignature,
+        _verifying_key: &'a (),
+        _message: &'a [u8],
+    ) -> VerifyFuture<'a> {
+        Box::pin(async { Err(anyhow::anyhow!("KMS is unavailable")) })
+    }
+}
+
+#[tokio::test]
+async fn async_verifier_errors() {
+    let key = Hs256Key::generate(&mut thread_rng()).into_inner();
+    let token = Hs256
+        .token(&Header::empty(), &create_claims(), &key)
+        .unwrap();
+    let token = UntrustedToken::new(&token).unwrap();
+    let err = UnavailableVerifier
+        .async_validator::<CompactClaims>(&())
+        .validate(&token)
+        .await
+        .unwrap_err();
+    assert_matches!(&err, ValidationError::Verification(err) if err.to_string() == "KMS is unavailable");
+}
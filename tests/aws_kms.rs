@@ -0,0 +1,146 @@
+//! Tests for the AWS KMS signing backend.
+
+use aws_sdk_kms::{
+    config::{
+        http::{HttpRequest, HttpResponse},
+        AsyncSleep, BehaviorVersion, Credentials, Region, SharedAsyncSleep, Sleep,
+    },
+    Client,
+};
+use aws_smithy_runtime_api::client::http::{
+    http_client_fn, HttpConnector, HttpConnectorFuture, SharedHttpConnector,
+};
+use aws_smithy_types::body::SdkBody;
+use base64ct::{Base64, Encoding};
+use chrono::Duration;
+use p256::{
+    ecdsa::{signature::hazmat::PrehashSigner, Signature, SigningKey},
+    pkcs8::EncodePublicKey,
+};
+use rand::thread_rng;
+use serde_json::{json, Value};
+
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration as StdDuration,
+};
+
+use jwt_compact::{
+    alg::{AwsKms, AwsKmsKey, Es256},
+    prelude::*,
+    AsyncAlgorithmExt,
+};
+
+const KEY_ID: &str = "alias/test";
+
+/// Mock KMS endpoint backed by a local P-256 key.
+#[derive(Debug)]
+struct MockKms {
+    signing_key: SigningKey,
+    requests: Mutex<Vec<Value>>,
+}
+
+impl MockKms {
+    fn respond(&self, request: &HttpRequest) -> HttpResponse {
+        let target = request.headers().get("x-amz-target").unwrap();
+        let body: Value = serde_json::from_slice(request.body().bytes().unwrap()).unwrap();
+        assert_eq!(body["KeyId"], KEY_ID);
+        self.requests.lock().unwrap().push(body.clone());
+
+        let response = match target {
+            "TrentService.Sign" => {
+                let digest = Base64::decode_vec(body["Message"].as_str().unwrap()).unwrap();
+                let signature: Signature = self.signing_key.sign_prehash(&digest).unwrap();
+                let signature = Base64::encode_string(signature.to_der().as_bytes());
+                json!({
+                    "KeyId": KEY_ID,
+                    "Signature": signature,
+                    "SigningAlgorithm": body["SigningAlgorithm"],
+                })
+            }
+            "TrentService.GetPublicKey" => {
+                let public_key = self
+                    .signing_key
+                    .verifying_key()
+                    .to_public_key_der()
+                    .unwrap();
+                json!({
+                    "KeyId": KEY_ID,
+                    "KeySpec": "ECC_NIST_P256",
+                    "KeyUsage": "SIGN_VERIFY",
+                    "PublicKey": Base64::encode_string(public_key.as_bytes()),
+                })
+            }
+            _ => panic!("unexpected KMS operation: {target}"),
+        };
+        HttpResponse::new(200.try_into().unwrap(), SdkBody::from(response.to_string()))
+    }
+}
+
+#[derive(Debug)]
+struct MockConnector(Arc<MockKms>);
+
+impl HttpConnector for MockConnector {
+    fn call(&self, request: HttpRequest) -> HttpConnectorFuture {
+        HttpConnectorFuture::ready(Ok(self.0.respond(&request)))
+    }
+}
+
+#[derive(Debug)]
+struct TokioSleep;
+
+impl AsyncSleep for TokioSleep {
+    fn sleep(&self, duration: StdDuration) -> Sleep {
+        Sleep::new(tokio::time::sleep(duration))
+    }
+}
+
+fn mock_client(kms: &Arc<MockKms>) -> Client {
+    let kms = Arc::clone(kms);
+    let http_client =
+        http_client_fn(move |_, _| SharedHttpConnector::new(MockConnector(Arc::clone(&kms))));
+    let config = aws_sdk_kms::Config::builder()
+        .behavior_version(BehaviorVersion::latest())
+        .region(Region::new("us-east-1"))
+        .credentials_provider(Credentials::new("AKIDTEST", "secret", None, None, "test"))
+        .http_client(http_client)
+        .sleep_impl(SharedAsyncSleep::new(TokioSleep))
+        .build();
+    Client::from_conf(config)
+}
+
+#[tokio::test]
+async fn signing_token_with_kms() {
+    let kms = Arc::new(MockKms {
+        signing_key: SigningKey::random(&mut thread_rng()),
+        requests: Mutex::default(),
+    });
+    let key = AwsKmsKey::new(mock_client(&kms), KEY_ID);
+    let claims = Claims::new(json!({ "sub": "alice" }))
+        .set_duration_and_issuance(&TimeOptions::default(), Duration::try_minutes(5).unwrap());
+    let header = Header::empty().with_key_id("test");
+    let token = AwsKms::es256()
+        .token_async(&header, &claims, &key)
+        .await
+        .unwrap();
+
+    {
+        let requests = kms.requests.lock().unwrap();
+        let [request] = requests.as_slice() else {
+            panic!("unexpected requests: {requests:?}");
+        };
+        assert_eq!(request["MessageType"], "DIGEST");
+        assert_eq!(request["SigningAlgorithm"], "ECDSA_SHA_256");
+    }
+
+    let verifying_key = key.es256_public_key().await.unwrap();
+    assert_eq!(verifying_key, *kms.signing_key.verifying_key());
+    let token = UntrustedToken::new(&token).unwrap();
+    assert_eq!(token.algorithm(), "ES256");
+    let token = Es256
+        .validator::<Value>(&verifying_key)
+        .validate(&token)
+        .unwrap();
+    assert_eq!(token.claims().custom["sub"], "alice");
+    assert_eq!(token.header().key_id.as_deref(), Some("test"));
+}
@@ -0,0 +1,145 @@
+//! Tests for the Azure Key Vault signing backend.
+
+use assert_matches::assert_matches;
+use base64ct::{Base64UrlUnpadded, Encoding};
+use chrono::Duration;
+use p256::ecdsa::{signature::hazmat::PrehashSigner, Signature, SigningKey, VerifyingKey};
+use rand::thread_rng;
+use serde_json::{json, Value};
+
+mod http_stub;
+
+use jwt_compact::{
+    alg::{AzureKeyVault, AzureKeyVaultError, AzureKeyVaultKey, Es256, ManagedIdentity},
+    jwk::JsonWebKey,
+    prelude::*,
+    AsyncAlgorithmExt, CreationError,
+};
+
+const KEY_PATH: &str = "/keys/signing/0123456789abcdef";
+
+fn key(vault_url: &str) -> AzureKeyVaultKey {
+    let key_url = format!("{}{}", vault_url.trim_end_matches('/'), KEY_PATH);
+    AzureKeyVaultKey::new(reqwest::Client::new(), key_url, || async {
+        Ok("test-token".to_owned())
+    })
+}
+
+fn vault_jwk(verifying_key: &VerifyingKey) -> Value {
+    let mut jwk = serde_json::to_value(JsonWebKey::from(verifying_key)).unwrap();
+    jwk["kty"] = "EC-HSM".into();
+    jwk["kid"] = "https://vault.example/keys/signing/0123456789abcdef".into();
+    jwk["key_ops"] = json!(["sign", "verify"]);
+    json!({ "key": jwk, "attributes": { "enabled": true } })
+}
+
+#[tokio::test]
+async fn signing_token_with_key_vault() {
+    let (listener, vault_url) = http_stub::bind().await;
+    let signing_key = SigningKey::random(&mut thread_rng());
+    let key = key(&vault_url);
+    let claims = Claims::new(json!({ "sub": "service" }))
+        .set_duration_and_issuance(&TimeOptions::default(), Duration::try_minutes(5).unwrap());
+
+    let sign = |request: &http_stub::Request| {
+        let body: Value = serde_json::from_str(&request.body).unwrap();
+        assert_eq!(body["alg"], "ES256");
+        let digest = Base64UrlUnpadded::decode_vec(body["value"].as_str().unwrap()).unwrap();
+        let signature: Signature = signing_key.sign_prehash(&digest).unwrap();
+        let signature = Base64UrlUnpadded::encode_string(&signature.to_bytes());
+        (
+            200,
+            json!({ "kid": "test", "value": signature }).to_string(),
+        )
+    };
+    let (alg, header) = (AzureKeyVault::es256(), key.header());
+    let (request, token) = tokio::join!(
+        http_stub::serve(&listener, sign),
+        alg.token_async(&header, &claims, &key)
+    );
+    let token = token.unwrap();
+    assert_eq!(request.method, "POST");
+    assert_eq!(request.path, format!("{KEY_PATH}/sign?api-version=7.4"));
+    assert_eq!(request.header("authorization"), Some("Bearer test-token"));
+
+    let get_key = |request: &http_stub::Request| {
+        assert_eq!(request.method, "GET");
+        assert_eq!(request.path, format!("{KEY_PATH}?api-version=7.4"));
+        (200, vault_jwk(signing_key.verifying_key()).to_string())
+    };
+    let (_, verifying_key) = tokio::join!(
+        http_stub::serve(&listener, get_key),
+        key.verifying_key::<VerifyingKey>()
+    );
+    let verifying_key = verifying_key.unwrap();
+    assert_eq!(verifying_key, *signing_key.verifying_key());
+    // The public key is cached, so no further requests are made.
+    let jwk = key.public_jwk().await.unwrap();
+    assert_eq!(jwk, JsonWebKey::from(signing_key.verifying_key()));
+
+    let token = UntrustedToken::new(&token).unwrap();
+    assert_eq!(token.algorithm(), "ES256");
+    assert_eq!(token.header().key_id.as_deref(), Some(key.key_id()));
+    let token = Es256
+        .validator::<Value>(&verifying_key)
+        .validate(&token)
+        .unwrap();
+    assert_eq!(token.claims().custom["sub"], "service");
+}
+
+#[tokio::test]
+async fn key_vault_error_response() {
+    let (listener, vault_url) = http_stub::bind().await;
+    let key = key(&vault_url);
+    let error = json!({
+        "error": { "code": "Forbidden", "message": "Caller is not authorized" },
+    });
+    let (alg, header) = (AzureKeyVault::rs256(), Header::empty());
+    let (_, result) = tokio::join!(
+        http_stub::serve(&listener, |_| (403, error.to_string())),
+        alg.token_async(&header, &Claims::empty(), &key)
+    );
+    let err = result.unwrap_err();
+    let CreationError::Signing(err) = err else {
+        panic!("unexpected error: {err:?}");
+    };
+    let err = err.downcast_ref::<AzureKeyVaultError>().unwrap();
+    assert_matches!(
+        err,
+        AzureKeyVaultError::Api { status: 403, message } if message == "Caller is not authorized"
+    );
+}
+
+#[tokio::test]
+async fn managed_identity_tokens_are_cached() {
+    let (listener, endpoint) = http_stub::bind().await;
+    let identity = ManagedIdentity::new(reqwest::Client::new())
+        .with_client_id("client")
+        .with_endpoint(format!("{endpoint}token"));
+
+    let expires_on = chrono::Utc::now().timestamp() + 3_600;
+    let issue_token = |request: &http_stub::Request| {
+        assert_eq!(request.method, "GET");
+        assert!(request.path.starts_with("/token?api-version=2018-02-01&"));
+        assert!(request
+            .path
+            .contains("resource=https%3A%2F%2Fvault.azure.net"));
+        assert!(request.path.ends_with("&client_id=client"));
+        assert_eq!(request.header("metadata"), Some("true"));
+        let response = json!({
+            "access_token": "managed-token",
+            "expires_on": expires_on.to_string(),
+            "token_type": "Bearer",
+        });
+        (200, response.to_string())
+    };
+    let (_, token) = tokio::join!(
+        http_stub::serve(&listener, issue_token),
+        identity.access_token()
+    );
+    assert_eq!(token.unwrap(), "managed-token");
+
+    // The second call is served from the cache by a cloned provider.
+    let token = identity.clone().access_token().await.unwrap();
+    assert_eq!(token, "managed-token");
+}
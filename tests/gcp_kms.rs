@@ -0,0 +1,116 @@
+//! Tests for the Google Cloud KMS signing backend.
+
+use assert_matches::assert_matches;
+use base64ct::{Base64, Encoding};
+use chrono::Duration;
+use p256::{
+    ecdsa::{signature::hazmat::PrehashSigner, Signature, SigningKey},
+    pkcs8::{EncodePublicKey, LineEnding},
+};
+use rand::thread_rng;
+use serde_json::{json, Value};
+
+mod http_stub;
+
+use jwt_compact::{
+    alg::{Es256, GcpKms, GcpKmsError, GcpKmsKey},
+    prelude::*,
+    AsyncAlgorithmExt, CreationError,
+};
+
+const KEY_NAME: &str =
+    "projects/test/locations/global/keyRings/tokens/cryptoKeys/signing/cryptoKeyVersions/3";
+
+fn key(endpoint: &str) -> GcpKmsKey {
+    GcpKmsKey::new(reqwest::Client::new(), KEY_NAME, || async {
+        Ok("test-token".to_owned())
+    })
+    .with_endpoint(endpoint)
+}
+
+#[test]
+fn key_id_is_derived_from_resource_name() {
+    let key = key("http://localhost/");
+    assert_eq!(key.key_id(), "signing/3");
+    assert_eq!(key.header().key_id.as_deref(), Some("signing/3"));
+}
+
+#[tokio::test]
+async fn signing_token_with_kms() {
+    let (listener, endpoint) = http_stub::bind().await;
+    let signing_key = SigningKey::random(&mut thread_rng());
+    let key = key(&endpoint);
+    let claims = Claims::new(json!({ "sub": "service" }))
+        .set_duration_and_issuance(&TimeOptions::default(), Duration::try_minutes(5).unwrap());
+
+    let sign = |request: &http_stub::Request| {
+        let body: Value = serde_json::from_str(&request.body).unwrap();
+        let digest = Base64::decode_vec(body["digest"]["sha256"].as_str().unwrap()).unwrap();
+        let signature: Signature = signing_key.sign_prehash(&digest).unwrap();
+        let signature = Base64::encode_string(signature.to_der().as_bytes());
+        (
+            200,
+            json!({ "signature": signature, "name": KEY_NAME }).to_string(),
+        )
+    };
+    let (alg, header) = (GcpKms::es256(), key.header());
+    let (request, token) = tokio::join!(
+        http_stub::serve(&listener, sign),
+        alg.token_async(&header, &claims, &key)
+    );
+    let token = token.unwrap();
+    assert_eq!(request.method, "POST");
+    assert_eq!(request.path, format!("/{KEY_NAME}:asymmetricSign"));
+    assert_eq!(request.header("authorization"), Some("Bearer test-token"));
+
+    let get_public_key = |request: &http_stub::Request| {
+        assert_eq!(request.method, "GET");
+        assert_eq!(request.path, format!("/{KEY_NAME}/publicKey"));
+        let pem = signing_key
+            .verifying_key()
+            .to_public_key_pem(LineEnding::LF)
+            .unwrap();
+        (
+            200,
+            json!({ "pem": pem, "algorithm": "EC_SIGN_P256_SHA256" }).to_string(),
+        )
+    };
+    let (_, verifying_key) = tokio::join!(
+        http_stub::serve(&listener, get_public_key),
+        key.es256_public_key()
+    );
+    let verifying_key = verifying_key.unwrap();
+    assert_eq!(verifying_key, *signing_key.verifying_key());
+
+    let token = UntrustedToken::new(&token).unwrap();
+    assert_eq!(token.algorithm(), "ES256");
+    assert_eq!(token.header().key_id.as_deref(), Some("signing/3"));
+    let token = Es256
+        .validator::<Value>(&verifying_key)
+        .validate(&token)
+        .unwrap();
+    assert_eq!(token.claims().custom["sub"], "service");
+}
+
+#[tokio::test]
+async fn kms_error_response() {
+    let (listener, endpoint) = http_stub::bind().await;
+    let key = key(&endpoint);
+    let error = json!({
+        "error": { "code": 403, "message": "Permission denied", "status": "PERMISSION_DENIED" },
+    });
+    let (alg, header) = (GcpKms::rs256(), Header::empty());
+    let (_, result) = tokio::join!(
+        http_stub::serve(&listener, |_| (403, error.to_string())),
+        alg.token_async(&header, &Claims::empty(), &key)
+    );
+    let err = result.unwrap_err();
+    let CreationError::Signing(err) = err else {
+        panic!("unexpected error: {err:?}");
+    };
+    let err = err.downcast_ref::<GcpKmsError>().unwrap();
+    assert_matches!(
+        err,
+        GcpKmsError::Api { status: 403, message } if message == "Permission denied"
+    );
+}
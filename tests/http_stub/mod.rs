@@ -0,0 +1,82 @@
+//! Minimal HTTP server stub shared by tests of HTTP-based signing backends.
+
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+};
+
+/// HTTP request received by the stub.
+#[derive(Debug)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl Request {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find_map(|(header_name, value)| {
+            header_name
+                .eq_ignore_ascii_case(name)
+                .then_some(value.as_str())
+        })
+    }
+}
+
+/// Binds a listener on a random local port and returns it together with its base URL.
+pub async fn bind() -> (TcpListener, String) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/", listener.local_addr().unwrap());
+    (listener, url)
+}
+
+/// Accepts a single HTTP request and responds to it with the status and body computed
+/// by the `respond` closure. Returns the received request.
+pub async fn serve(
+    listener: &TcpListener,
+    respond: impl FnOnce(&Request) -> (u16, String),
+) -> Request {
+    let (mut stream, _) = listener.accept().await.unwrap();
+    let mut raw = Vec::new();
+    let mut buffer = [0_u8; 1_024];
+    let (head, body) = loop {
+        let len = stream.read(&mut buffer).await.unwrap();
+        raw.extend_from_slice(&buffer[..len]);
+        let text = String::from_utf8_lossy(&raw);
+        if let Some((head, body)) = text.split_once("\r\n\r\n") {
+            let content_len = head.lines().find_map(|line| {
+                let (name, value) = line.split_once(':')?;
+                let is_len = name.eq_ignore_ascii_case("content-length");
+                is_len.then(|| value.trim().parse::<usize>().unwrap())
+            });
+            if body.len() >= content_len.unwrap_or(0) || len == 0 {
+                break (head.to_owned(), body.to_owned());
+            }
+        }
+        assert!(len > 0, "connection closed before receiving request");
+    };
+
+    let mut lines = head.lines();
+    let mut request_line = lines.next().unwrap().split(' ');
+    let request = Request {
+        method: request_line.next().unwrap().to_owned(),
+        path: request_line.next().unwrap().to_owned(),
+        headers: lines
+            .filter_map(|line| {
+                let (name, value) = line.split_once(':')?;
+                Some((name.to_owned(), value.trim().to_owned()))
+            })
+            .collect(),
+        body,
+    };
+
+    let (status, body) = respond(&request);
+    let response = format!(
+        "HTTP/1.1 {status} Stub\r\ncontent-type: application/json\r\n\
+         content-length: {}\r\nconnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).await.unwrap();
+    request
+}
@@ -0,0 +1,245 @@
+//! Round-trip tests with [`jsonwebtoken`] and [`josekit`]: tokens issued by these libraries
+//! are validated by this crate, and vice versa.
+//!
+//! [`jsonwebtoken`]: https://docs.rs/jsonwebtoken/
+//! [`josekit`]: https://docs.rs/josekit/
+
+use josekit::{
+    jwk::Jwk,
+    jws::{EdDSA, JwsHeader, JwsSigner, JwsVerifier, ES256, HS256, RS256},
+    jwt::{self as josekit_jwt, JwtPayload},
+};
+use jsonwebtoken::{DecodingKey, EncodingKey};
+use serde_json::{Map, Value};
+
+use jwt_compact::{
+    alg::{Ed25519, Es256, Hs256, Hs256Key, Rsa},
+    interop::{claims_from_payload, claims_to_payload, JsonwebtokenValidation, KeyComponents},
+    jwk::JsonWebKey,
+    pem::{PemFormat, PemKey},
+    prelude::*,
+    testing::FixtureKeys,
+    validation::validate,
+    DynamicClaims, Timestamp,
+};
+
+const SECRET: &[u8] = b"super_secret_key_donut_steel";
+const ISSUER: &str = "https://issuer.example.com";
+const AUDIENCE: &str = "api";
+/// 2100-01-01T00:00:00Z
+const EXPIRATION: i64 = 4_102_444_800;
+
+fn sample_payload() -> Map<String, Value> {
+    let payload = serde_json::json!({
+        "sub": "alice",
+        "iss": ISSUER,
+        "aud": AUDIENCE,
+        "exp": EXPIRATION,
+        "admin": true,
+    });
+    match payload {
+        Value::Object(map) => map,
+        _ => unreachable!(),
+    }
+}
+
+fn assert_sample_claims(claims: &Claims<DynamicClaims>) {
+    assert_eq!(claims.expiration, Some(Timestamp::from_secs(EXPIRATION)));
+    assert_eq!(claims.custom.get_str("sub"), Some("alice"));
+    assert_eq!(claims_to_payload(claims).unwrap(), sample_payload());
+}
+
+fn jwt_algorithm<A: Algorithm>(algorithm: &A) -> jsonwebtoken::Algorithm {
+    algorithm.name().parse().unwrap()
+}
+
+/// Converts a verifying key into a `jsonwebtoken` key using [`KeyComponents`].
+fn decoding_key(jwk: &JsonWebKey<'_>) -> DecodingKey {
+    match KeyComponents::from_jwk(jwk).unwrap() {
+        KeyComponents::Rsa { modulus, exponent } => {
+            DecodingKey::from_rsa_components(&modulus, &exponent).unwrap()
+        }
+        KeyComponents::EllipticCurve { x, y, .. } => {
+            DecodingKey::from_ec_components(&x, &y).unwrap()
+        }
+        KeyComponents::KeyPair { x, .. } => DecodingKey::from_ed_components(&x).unwrap(),
+        _ => unreachable!("unexpected key components"),
+    }
+}
+
+/// Converts a verifying key into a `josekit` JWK.
+fn josekit_jwk(jwk: &JsonWebKey<'_>) -> Jwk {
+    match serde_json::to_value(jwk).unwrap() {
+        Value::Object(map) => Jwk::from_map(map).unwrap(),
+        _ => unreachable!(),
+    }
+}
+
+fn jsonwebtoken_to_jwt_compact<A: Algorithm>(
+    algorithm: &A,
+    encoding_key: &EncodingKey,
+    verifying_key: &A::VerifyingKey,
+) {
+    let header = jsonwebtoken::Header::new(jwt_algorithm(algorithm));
+    let token = jsonwebtoken::encode(&header, &sample_payload(), encoding_key).unwrap();
+
+    let mut validation = JsonwebtokenValidation::new(&algorithm.name());
+    validation.iss = Some(vec![ISSUER.to_owned()]);
+    validation.aud = Some(vec![AUDIENCE.to_owned()]);
+    let expectations = validation.to_expectations(TimeOptions::default()).unwrap();
+    let token: Token<DynamicClaims> =
+        validate(algorithm, &token, verifying_key, &expectations).unwrap();
+    assert_eq!(token.header().token_type.as_deref(), Some("JWT"));
+    assert_sample_claims(token.claims());
+}
+
+fn jwt_compact_to_jsonwebtoken<A: Algorithm>(
+    algorithm: &A,
+    signing_key: &A::SigningKey,
+    decoding_key: &DecodingKey,
+) {
+    let claims = claims_from_payload(sample_payload()).unwrap();
+    let header = Header::empty().with_token_type("JWT");
+    let token = algorithm.token(&header, &claims, signing_key).unwrap();
+
+    let mut validation = jsonwebtoken::Validation::new(jwt_algorithm(algorithm));
+    validation.set_issuer(&[ISSUER]);
+    validation.set_audience(&[AUDIENCE]);
+    let decoded =
+        jsonwebtoken::decode::<Map<String, Value>>(token.as_str(), decoding_key, &validation)
+            .unwrap();
+    assert_eq!(decoded.header.alg, jwt_algorithm(algorithm));
+    assert_eq!(decoded.claims, sample_payload());
+
+    // Check that `jsonwebtoken` actually validates claims.
+    validation.set_audience(&["other"]);
+    jsonwebtoken::decode::<Map<String, Value>>(token.as_str(), decoding_key, &validation)
+        .unwrap_err();
+}
+
+fn josekit_to_jwt_compact<A: Algorithm>(
+    algorithm: &A,
+    signer: &dyn JwsSigner,
+    verifying_key: &A::VerifyingKey,
+) {
+    let payload = JwtPayload::from_map(sample_payload()).unwrap();
+    let mut header = JwsHeader::new();
+    header.set_token_type("JWT");
+    let token = josekit_jwt::encode_with_signer(&payload, &header, signer).unwrap();
+
+    let token = UntrustedToken::new(&token).unwrap();
+    assert_eq!(token.algorithm(), algorithm.name());
+    let token = algorithm
+        .validator::<DynamicClaims>(verifying_key)
+        .validate(&token)
+        .unwrap();
+    token
+        .claims()
+        .validate_expiration(&TimeOptions::default())
+        .unwrap();
+    assert_sample_claims(token.claims());
+}
+
+fn jwt_compact_to_josekit<A: Algorithm>(
+    algorithm: &A,
+    signing_key: &A::SigningKey,
+    verifier: &dyn JwsVerifier,
+) {
+    let claims = claims_from_payload(sample_payload()).unwrap();
+    let header = Header::empty().with_token_type("JWT");
+    let token = algorithm.token(&header, &claims, signing_key).unwrap();
+
+    let (payload, header) = josekit_jwt::decode_with_verifier(token.as_str(), verifier).unwrap();
+    assert_eq!(header.algorithm(), Some(&*algorithm.name()));
+    assert_eq!(header.token_type(), Some("JWT"));
+    assert_eq!(*payload.claims_set(), sample_payload());
+    let claims = claims_from_payload(payload.claims_set().clone()).unwrap();
+    assert_sample_claims(&claims);
+}
+
+#[test]
+fn hs256_roundtrip_with_jsonwebtoken() {
+    let key = Hs256Key::new(SECRET);
+    jsonwebtoken_to_jwt_compact(&Hs256, &EncodingKey::from_secret(SECRET), &key);
+    jwt_compact_to_jsonwebtoken(&Hs256, &key, &DecodingKey::from_secret(SECRET));
+}
+
+#[test]
+fn hs256_roundtrip_with_josekit() {
+    let key = Hs256Key::new(SECRET);
+    let signer = HS256.signer_from_bytes(SECRET).unwrap();
+    josekit_to_jwt_compact(&Hs256, &signer, &key);
+    let verifier = HS256.verifier_from_bytes(SECRET).unwrap();
+    jwt_compact_to_josekit(&Hs256, &key, &verifier);
+}
+
+#[test]
+fn rs256_roundtrip_with_jsonwebtoken() {
+    let (signing_key, verifying_key) = Rsa::fixture_keys();
+    let rsa = Rsa::rs256();
+    let pem = signing_key.to_pem_in(PemFormat::Pkcs1PrivateKey).unwrap();
+    let encoding_key = EncodingKey::from_rsa_pem(pem.as_bytes()).unwrap();
+    jsonwebtoken_to_jwt_compact(&rsa, &encoding_key, &verifying_key);
+
+    let decoding_key = decoding_key(&JsonWebKey::from(&verifying_key));
+    jwt_compact_to_jsonwebtoken(&rsa, &signing_key, &decoding_key);
+}
+
+#[test]
+fn rs256_roundtrip_with_josekit() {
+    let (signing_key, verifying_key) = Rsa::fixture_keys();
+    let rsa = Rsa::rs256();
+    let pem = signing_key.to_pem_in(PemFormat::Pkcs1PrivateKey).unwrap();
+    let signer = RS256.signer_from_pem(pem.as_bytes()).unwrap();
+    josekit_to_jwt_compact(&rsa, &signer, &verifying_key);
+
+    let jwk = josekit_jwk(&JsonWebKey::from(&verifying_key));
+    let verifier = RS256.verifier_from_jwk(&jwk).unwrap();
+    jwt_compact_to_josekit(&rsa, &signing_key, &verifier);
+}
+
+#[test]
+fn es256_roundtrip_with_jsonwebtoken() {
+    let (signing_key, verifying_key) = Es256::fixture_keys();
+    let pem = signing_key.to_pem().unwrap();
+    let encoding_key = EncodingKey::from_ec_pem(pem.as_bytes()).unwrap();
+    jsonwebtoken_to_jwt_compact(&Es256, &encoding_key, &verifying_key);
+
+    let decoding_key = decoding_key(&JsonWebKey::from(&verifying_key));
+    jwt_compact_to_jsonwebtoken(&Es256, &signing_key, &decoding_key);
+}
+
+#[test]
+fn es256_roundtrip_with_josekit() {
+    let (signing_key, verifying_key) = Es256::fixture_keys();
+    let pem = signing_key.to_pem().unwrap();
+    let signer = ES256.signer_from_pem(pem.as_bytes()).unwrap();
+    josekit_to_jwt_compact(&Es256, &signer, &verifying_key);
+
+    let jwk = josekit_jwk(&JsonWebKey::from(&verifying_key));
+    let verifier = ES256.verifier_from_jwk(&jwk).unwrap();
+    jwt_compact_to_josekit(&Es256, &signing_key, &verifier);
+}
+
+#[test]
+fn eddsa_roundtrip_with_jsonwebtoken() {
+    let (signing_key, verifying_key) = Ed25519::fixture_keys();
+    let pem = signing_key.to_pem().unwrap();
+    let encoding_key = EncodingKey::from_ed_pem(pem.as_bytes()).unwrap();
+    jsonwebtoken_to_jwt_compact(&Ed25519, &encoding_key, &verifying_key);
+
+    let decoding_key = decoding_key(&JsonWebKey::from(&verifying_key));
+    jwt_compact_to_jsonwebtoken(&Ed25519, &signing_key, &decoding_key);
+}
+
+#[test]
+fn eddsa_roundtrip_with_josekit() {
+    let (signing_key, verifying_key) = Ed25519::fixture_keys();
+    let pem = signing_key.to_pem().unwrap();
+    let signer = EdDSA.signer_from_pem(pem.as_bytes()).unwrap();
+    josekit_to_jwt_compact(&Ed25519, &signer, &verifying_key);
+
+    let jwk = josekit_jwk(&JsonWebKey::from(&verifying_key));
+    let verifier = EdDSA.verifier_from_jwk(&jwk).unwrap();
+    jwt_compact_to_josekit(&Ed25519, &signing_key, &verifier);
+}
@@ -8,7 +8,7 @@ use sha2::{digest::Digest, Sha256, Sha384, Sha512};
 
 use jwt_compact::{
     alg::Hs256Key,
-    jwk::{JsonWebKey, JwkError, KeyType},
+    jwk::{JsonWebKey, JsonWebKeySet, JwkError, KeyType},
 };
 
 fn key_thumbprint<'a, D, K>(key: &'a K) -> String
@@ -28,10 +28,11 @@ fn assert_jwk_roundtrip(jwk: &JsonWebKey<'_>) {
     let restored_from_json: JsonWebKey<'_> = serde_json::from_value(json).unwrap();
     assert_eq!(restored_from_json, *jwk);
 
-    #[cfg(feature = "serde_cbor")]
+    #[cfg(feature = "ciborium")]
     {
-        let bytes = serde_cbor::to_vec(jwk).unwrap();
-        let restored_from_cbor: JsonWebKey<'_> = serde_cbor::from_slice(&bytes).unwrap();
+        let mut bytes = vec![];
+        ciborium::into_writer(jwk, &mut bytes).unwrap();
+        let restored_from_cbor: JsonWebKey<'_> = ciborium::from_reader(&bytes[..]).unwrap();
         assert_eq!(restored_from_cbor, *jwk);
     }
 }
@@ -68,6 +69,13 @@ fn hs256_jwk() {
     );
 }
 
+#[test]
+fn symmetric_keys_are_not_included_into_jwks() {
+    let key = Hs256Key::new(b"super_secret_key_donut_steel");
+    let jwks = JsonWebKeySet::from_keys(&jwt_compact::alg::Hs256, [&key]);
+    assert!(jwks.keys.is_empty());
+}
+
 #[test]
 fn hs256_incorrect_key_type() {
     let jwk = serde_json::json!({
@@ -686,7 +694,7 @@ mod es256 {
 mod ed25519 {
     use super::*;
     use jwt_compact::{
-        alg::{Ed25519, SigningKey, VerifyingKey},
+        alg::{Ed25519, GenerateKey, SigningKey, VerifyingKey},
         Algorithm,
     };
 
@@ -727,6 +735,43 @@ mod ed25519 {
         );
     }
 
+    #[test]
+    fn jwks_from_keys() {
+        const KEY_BYTES: [u8; 32] =
+            Hex.decode(b"b7e6ddbf8d4c2571315e7a6ab8706e0e7ee7d581b25fb80b41c8551c0a0dbb9d");
+        let public_key = <PublicKey as VerifyingKey<Ed25519>>::from_slice(&KEY_BYTES).unwrap();
+
+        let jwks = JsonWebKeySet::from_keys(&Ed25519, [&public_key]);
+        assert_eq!(
+            serde_json::to_value(&jwks).unwrap(),
+            serde_json::json!({
+                "keys": [{
+                    "kid": "TZ72OrmiQl5Bz5Zm3NIM_0ksFtfP36SFdPZrwCj_2ZE",
+                    "alg": "EdDSA",
+                    "use": "sig",
+                    "kty": "OKP",
+                    "crv": "Ed25519",
+                    "x": "t-bdv41MJXExXnpquHBuDn7n1YGyX7gLQchVHAoNu50",
+                }],
+            })
+        );
+
+        // Signing keys are published without secret parts.
+        let secret_keys: Vec<_> = (0..2)
+            .map(|_| SecretKey::generate(&mut rand::thread_rng()))
+            .collect();
+        let jwks = JsonWebKeySet::from_keys(&Ed25519, &secret_keys);
+        assert_eq!(jwks.keys.len(), 2);
+        for (entry, secret_key) in jwks.keys.iter().zip(&secret_keys) {
+            assert!(!entry.key.is_signing_key());
+            let public_key = PublicKey::try_from(&entry.key).unwrap();
+            assert_eq!(public_key, secret_key.to_verifying_key());
+            let key_id = entry.key_id.as_deref().unwrap();
+            assert_eq!(key_id, key_thumbprint::<Sha256, _>(&public_key));
+            assert_eq!(jwks.find(key_id), Some(entry));
+        }
+    }
+
     #[test]
     fn signing_jwk() {
         // Randomly generated
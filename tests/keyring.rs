@@ -0,0 +1,140 @@
+//! Tests for storing HMAC secrets in the OS credential store. The tests use an in-memory
+//! credential store so that they don't depend on the platform keyring.
+
+use assert_matches::assert_matches;
+use chrono::Duration;
+use rand::thread_rng;
+
+use std::{
+    any::Any,
+    collections::HashMap,
+    sync::{Arc, Mutex, Once},
+};
+
+use jwt_compact::{
+    alg::{Hs256, Hs256Key, Hs512Key, KeyringError, KeyringStore},
+    prelude::*,
+};
+use keyring::credential::{Credential, CredentialApi, CredentialBuilderApi};
+
+type Secrets = Arc<Mutex<HashMap<(String, String), Vec<u8>>>>;
+
+#[derive(Debug)]
+struct MemoryCredential {
+    secrets: Secrets,
+    key: (String, String),
+}
+
+impl CredentialApi for MemoryCredential {
+    fn set_secret(&self, secret: &[u8]) -> keyring::Result<()> {
+        let mut secrets = self.secrets.lock().unwrap();
+        secrets.insert(self.key.clone(), secret.to_vec());
+        Ok(())
+    }
+
+    fn get_secret(&self) -> keyring::Result<Vec<u8>> {
+        let secrets = self.secrets.lock().unwrap();
+        secrets
+            .get(&self.key)
+            .cloned()
+            .ok_or(keyring::Error::NoEntry)
+    }
+
+    fn delete_credential(&self) -> keyring::Result<()> {
+        let mut secrets = self.secrets.lock().unwrap();
+        secrets
+            .remove(&self.key)
+            .map(drop)
+            .ok_or(keyring::Error::NoEntry)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[derive(Debug, Default)]
+struct MemoryCredentialBuilder {
+    secrets: Secrets,
+}
+
+impl CredentialBuilderApi for MemoryCredentialBuilder {
+    fn build(
+        &self,
+        _target: Option<&str>,
+        service: &str,
+        user: &str,
+    ) -> keyring::Result<Box<Credential>> {
+        Ok(Box::new(MemoryCredential {
+            secrets: Arc::clone(&self.secrets),
+            key: (service.to_owned(), user.to_owned()),
+        }))
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+fn store(service: &str) -> KeyringStore {
+    static INIT: Once = Once::new();
+
+    INIT.call_once(|| {
+        keyring::set_default_credential_builder(Box::<MemoryCredentialBuilder>::default());
+    });
+    KeyringStore::new(service)
+}
+
+#[test]
+fn storing_and_loading_key() {
+    let store = store("com.example.storing");
+    let key = Hs256Key::generate(&mut thread_rng()).into_inner();
+    store.store("token", &key).unwrap();
+
+    let loaded: Hs256Key = store.load("token").unwrap();
+    assert_eq!(loaded.as_ref(), key.as_ref());
+
+    let claims =
+        Claims::empty().set_duration(&TimeOptions::default(), Duration::try_minutes(5).unwrap());
+    let token = Hs256.token(&Header::empty(), &claims, &key).unwrap();
+    let token = UntrustedToken::new(&token).unwrap();
+    Hs256.validator::<()>(&loaded).validate(&token).unwrap();
+}
+
+#[test]
+fn loading_missing_key() {
+    let store = store("com.example.missing");
+    let err = store.load::<Hs256Key>("token").unwrap_err();
+    assert_matches!(&err, KeyringError::NotFound(name) if name == "token");
+    assert!(err.to_string().contains("`token`"), "{err}");
+}
+
+#[test]
+fn loading_or_generating_key() {
+    let store = store("com.example.generating");
+    let key: Hs512Key = store.load_or_generate("token", &mut thread_rng()).unwrap();
+    assert_eq!(key.as_ref().len(), 128);
+    let same_key: Hs512Key = store.load_or_generate("token", &mut thread_rng()).unwrap();
+    assert_eq!(same_key.as_ref(), key.as_ref());
+
+    // Secrets are scoped by the service.
+    let other_store = self::store("com.example.generating.other");
+    let other_key: Hs512Key = other_store
+        .load_or_generate("token", &mut thread_rng())
+        .unwrap();
+    assert_ne!(other_key.as_ref(), key.as_ref());
+}
+
+#[test]
+fn deleting_key() {
+    let store = store("com.example.deleting");
+    assert!(!store.delete("token").unwrap());
+
+    let key = Hs256Key::generate(&mut thread_rng()).into_inner();
+    store.store("token", &key).unwrap();
+    assert!(store.delete("token").unwrap());
+    assert_matches!(
+        store.load::<Hs256Key>("token").unwrap_err(),
+        KeyringError::NotFound(_)
+    );
+}
@@ -7,7 +7,7 @@ use hex_buffer_serde::{Hex as _, HexForm};
 use rand::{seq::index::sample as sample_indexes, thread_rng};
 use serde::{Deserialize, Serialize};
 
-use jwt_compact::{prelude::*, Algorithm, ValidationError};
+use jwt_compact::{prelude::*, Algorithm, Timestamp, ValidationError};
 
 pub type Obj = serde_json::Map<String, serde_json::Value>;
 
@@ -30,8 +30,7 @@ pub struct CompactClaims {
 }
 
 pub fn create_claims() -> Claims<CompactClaims> {
-    let now = Utc.with_ymd_and_hms(2020, 9, 1, 10, 0, 0).single().unwrap();
-    let now = now - Duration::nanoseconds(i64::from(now.timestamp_subsec_nanos()));
+    let now = Timestamp::from(Utc.with_ymd_and_hms(2020, 9, 1, 10, 0, 0).unwrap());
 
     let mut claims = Claims::new(CompactClaims { subject: [1; 32] });
     claims.issued_at = Some(now);
@@ -50,7 +49,7 @@ pub fn test_algorithm<A: Algorithm>(
     let claims = create_claims();
 
     // Successful case with a compact token.
-    #[cfg(feature = "serde_cbor")]
+    #[cfg(feature = "ciborium")]
     {
         let token_string = algorithm
             .compact_token(&Header::empty(), &claims, signing_key)
@@ -86,7 +85,7 @@ pub fn test_algorithm<A: Algorithm>(
         mangled_signature[i / 8] ^= 1 << (i % 8) as u8;
         let mangled_signature = Base64UrlUnpadded::encode_string(&mangled_signature);
 
-        let mut mangled_str = token_string.clone();
+        let mut mangled_str = token_string.to_string();
         mangled_str.replace_range(signature_start.., &mangled_signature);
         let token = UntrustedToken::new(&mangled_str).unwrap();
         let err = algorithm
@@ -104,7 +103,7 @@ pub fn test_algorithm<A: Algorithm>(
     let mangled_header = Base64UrlUnpadded::encode_string(mangled_header.as_bytes());
     let header_end = token_string.find('.').unwrap();
     assert_ne!(mangled_header, &token_string[..header_end]);
-    let mut mangled_str = token_string.clone();
+    let mut mangled_str = token_string.to_string();
     mangled_str.replace_range(..header_end, &mangled_header);
     let token = UntrustedToken::new(&mangled_str).unwrap();
     let err = algorithm
@@ -118,7 +117,7 @@ pub fn test_algorithm<A: Algorithm>(
         &serde_json::to_vec(&{
             let mut mangled_claims = claims;
             let issued_at = mangled_claims.issued_at.as_mut().unwrap();
-            *issued_at += Duration::try_seconds(1).unwrap();
+            *issued_at = *issued_at + Duration::try_seconds(1).unwrap();
             mangled_claims
         })
         .unwrap(),
@@ -127,7 +126,7 @@ pub fn test_algorithm<A: Algorithm>(
         claims_string,
         token_string[(header_end + 1)..(signature_start - 1)]
     );
-    let mut mangled_str = token_string.clone();
+    let mut mangled_str = token_string.to_string();
     mangled_str.replace_range((header_end + 1)..(signature_start - 1), &claims_string);
     let token = UntrustedToken::new(&mangled_str).unwrap();
     let err = algorithm
@@ -0,0 +1,202 @@
+//! Tests for the SSH agent signing backend.
+
+#![cfg(unix)]
+
+use assert_matches::assert_matches;
+use chrono::Duration;
+use p256::ecdsa::{signature::Signer, Signature, SigningKey};
+use rand::thread_rng;
+use serde_json::{json, Value};
+
+use std::{
+    io::{Read, Write},
+    os::unix::net::UnixStream,
+    sync::{Arc, Mutex},
+    thread,
+};
+
+use jwt_compact::{
+    alg::{Es256, SshAgent, SshAgentClient, SshAgentError},
+    prelude::*,
+    AsyncAlgorithmExt, CreationError,
+};
+
+fn write_string(buffer: &mut Vec<u8>, bytes: &[u8]) {
+    buffer.extend_from_slice(&u32::try_from(bytes.len()).unwrap().to_be_bytes());
+    buffer.extend_from_slice(bytes);
+}
+
+fn write_mpint(buffer: &mut Vec<u8>, bytes: &[u8]) {
+    let bytes = &bytes[bytes.iter().take_while(|&&byte| byte == 0).count()..];
+    if bytes.first().is_some_and(|&byte| byte >= 0x80) {
+        let mut padded = vec![0];
+        padded.extend_from_slice(bytes);
+        write_string(buffer, &padded);
+    } else {
+        write_string(buffer, bytes);
+    }
+}
+
+fn read_string(bytes: &mut &[u8]) -> Vec<u8> {
+    let len = u32::from_be_bytes(bytes[..4].try_into().unwrap()) as usize;
+    let string = bytes[4..4 + len].to_vec();
+    *bytes = &bytes[4 + len..];
+    string
+}
+
+fn p256_key_blob(signing_key: &SigningKey) -> Vec<u8> {
+    let point = signing_key.verifying_key().to_encoded_point(false);
+    let mut blob = vec![];
+    write_string(&mut blob, b"ecdsa-sha2-nistp256");
+    write_string(&mut blob, b"nistp256");
+    write_string(&mut blob, point.as_bytes());
+    blob
+}
+
+/// Minimal SSH agent holding a P-256 key and an RSA key (which is unsupported
+/// by the backend).
+#[derive(Debug)]
+struct StubAgent {
+    signing_key: SigningKey,
+    refuse_signing: bool,
+    requests: Mutex<Vec<u8>>,
+}
+
+impl StubAgent {
+    fn new(signing_key: SigningKey) -> Arc<Self> {
+        Arc::new(Self {
+            signing_key,
+            refuse_signing: false,
+            requests: Mutex::default(),
+        })
+    }
+
+    fn client(self: &Arc<Self>) -> SshAgentClient {
+        let this = Arc::clone(self);
+        SshAgentClient::with_connector(move || {
+            let (client, server) = UnixStream::pair()?;
+            let this = Arc::clone(&this);
+            thread::spawn(move || this.serve(server));
+            Ok(client)
+        })
+    }
+
+    fn serve(&self, mut stream: UnixStream) {
+        let mut len = [0_u8; 4];
+        stream.read_exact(&mut len).unwrap();
+        let mut request = vec![0_u8; u32::from_be_bytes(len) as usize];
+        stream.read_exact(&mut request).unwrap();
+        self.requests.lock().unwrap().push(request[0]);
+
+        let response = match request[0] {
+            11 => {
+                let mut response = vec![12];
+                response.extend_from_slice(&2_u32.to_be_bytes());
+                let mut rsa_blob = vec![];
+                write_string(&mut rsa_blob, b"ssh-rsa");
+                write_string(&mut response, &rsa_blob);
+                write_string(&mut response, b"rsa@host");
+                write_string(&mut response, &p256_key_blob(&self.signing_key));
+                write_string(&mut response, b"alice@host");
+                response
+            }
+            13 if self.refuse_signing => vec![5],
+            13 => {
+                let mut body = &request[1..];
+                let blob = read_string(&mut body);
+                assert_eq!(blob, p256_key_blob(&self.signing_key));
+                let data = read_string(&mut body);
+                let signature: Signature = self.signing_key.sign(&data);
+                let (r, s) = signature.split_bytes();
+
+                let mut signature_blob = vec![];
+                write_mpint(&mut signature_blob, &r);
+                write_mpint(&mut signature_blob, &s);
+                let mut signature = vec![];
+                write_string(&mut signature, b"ecdsa-sha2-nistp256");
+                write_string(&mut signature, &signature_blob);
+                let mut response = vec![14];
+                write_string(&mut response, &signature);
+                response
+            }
+            _ => vec![5],
+        };
+        let mut message = vec![];
+        write_string(&mut message, &response);
+        stream.write_all(&message).unwrap();
+    }
+}
+
+#[test]
+fn listing_keys() {
+    let signing_key = SigningKey::random(&mut thread_rng());
+    let agent = StubAgent::new(signing_key.clone());
+    let client = agent.client();
+
+    let keys = client.keys().unwrap();
+    assert_eq!(keys.len(), 1);
+    let key = &keys[0];
+    assert_eq!(key.key_type(), "ecdsa-sha2-nistp256");
+    assert_eq!(key.comment(), "alice@host");
+    assert!(key.key_id().starts_with("SHA256:"));
+    assert_eq!(
+        key.public_key(),
+        signing_key
+            .verifying_key()
+            .to_encoded_point(false)
+            .as_bytes()
+    );
+
+    let err = client.find_key("rsa@host").unwrap_err();
+    assert_matches!(err, SshAgentError::KeyNotFound(comment) if comment == "rsa@host");
+}
+
+#[tokio::test]
+async fn signing_token_with_agent() {
+    let signing_key = SigningKey::random(&mut thread_rng());
+    let agent = StubAgent::new(signing_key.clone());
+    let key = agent.client().find_key("alice@host").unwrap();
+
+    let claims = Claims::new(json!({ "sub": "alice" }))
+        .set_duration_and_issuance(&TimeOptions::default(), Duration::try_minutes(5).unwrap());
+    let token = SshAgent::es256()
+        .token_async(&key.header(), &claims, &key)
+        .await
+        .unwrap();
+    assert_eq!(*agent.requests.lock().unwrap(), [11, 13]);
+
+    let token = UntrustedToken::new(&token).unwrap();
+    assert_eq!(token.algorithm(), "ES256");
+    assert_eq!(token.header().key_id.as_deref(), Some(key.key_id()));
+    let token = Es256
+        .validator::<Value>(signing_key.verifying_key())
+        .validate(&token)
+        .unwrap();
+    assert_eq!(token.claims().custom["sub"], "alice");
+
+    let err = SshAgent::eddsa().sign_blocking(&key, b"test").unwrap_err();
+    assert_matches!(
+        err,
+        SshAgentError::AlgorithmMismatch { key, signing: "EdDSA" } if key == "ecdsa-sha2-nistp256"
+    );
+}
+
+#[tokio::test]
+async fn agent_refusing_to_sign() {
+    let agent = Arc::new(StubAgent {
+        signing_key: SigningKey::random(&mut thread_rng()),
+        refuse_signing: true,
+        requests: Mutex::default(),
+    });
+    let key = agent.client().find_key("alice@host").unwrap();
+
+    let result = SshAgent::es256()
+        .token_async(&Header::empty(), &Claims::empty(), &key)
+        .await;
+    let err = result.unwrap_err();
+    let CreationError::Signing(err) = err else {
+        panic!("unexpected error: {err:?}");
+    };
+    let err = err.downcast_ref::<SshAgentError>().unwrap();
+    assert_matches!(err, SshAgentError::Refused);
+}
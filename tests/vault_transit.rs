@@ -0,0 +1,172 @@
+//! Tests for the HashiCorp Vault transit engine signing backend.
+
+use assert_matches::assert_matches;
+use base64ct::{Base64, Encoding};
+use chrono::Duration;
+use p256::{
+    ecdsa::{signature::hazmat::PrehashSigner, Signature, SigningKey},
+    pkcs8::{EncodePublicKey, LineEnding},
+};
+use rand::thread_rng;
+use serde_json::{json, Value};
+
+mod http_stub;
+
+use jwt_compact::{
+    alg::{Es256, VaultTransit, VaultTransitError, VaultTransitKey},
+    prelude::*,
+    AsyncAlgorithmExt, CreationError,
+};
+
+fn key(address: &str) -> VaultTransitKey {
+    VaultTransitKey::new(reqwest::Client::new(), address, "signing", || async {
+        Ok("test-token".to_owned())
+    })
+}
+
+fn sign_with(
+    signing_key: &SigningKey,
+    version: u32,
+) -> impl FnOnce(&http_stub::Request) -> (u16, String) + '_ {
+    move |request| {
+        let body: Value = serde_json::from_str(&request.body).unwrap();
+        assert_eq!(body["prehashed"], true);
+        let digest = Base64::decode_vec(body["input"].as_str().unwrap()).unwrap();
+        let signature: Signature = signing_key.sign_prehash(&digest).unwrap();
+        let signature = Base64::encode_string(signature.to_der().as_bytes());
+        let signature = format!("vault:v{version}:{signature}");
+        (
+            200,
+            json!({ "data": { "signature": signature } }).to_string(),
+        )
+    }
+}
+
+#[test]
+fn key_id_includes_version() {
+    let key = key("http://localhost/");
+    assert_eq!(key.key_id(), "signing");
+    let key = key.with_version(2);
+    assert_eq!(key.key_id(), "signing:v2");
+    assert_eq!(key.header().key_id.as_deref(), Some("signing:v2"));
+}
+
+#[tokio::test]
+async fn signing_token_with_vault() {
+    let (listener, address) = http_stub::bind().await;
+    let signing_key = SigningKey::random(&mut thread_rng());
+    let pem = signing_key
+        .verifying_key()
+        .to_public_key_pem(LineEnding::LF)
+        .unwrap();
+    let key_info = json!({
+        "data": {
+            "type": "ecdsa-p256",
+            "latest_version": 2,
+            "keys": {
+                "1": { "public_key": "-----BEGIN PUBLIC KEY-----\nAAEC\n-----END PUBLIC KEY-----" },
+                "2": { "public_key": pem },
+            },
+        },
+    });
+
+    let get_key_info = |request: &http_stub::Request| {
+        assert_eq!(request.method, "GET");
+        assert_eq!(request.path, "/v1/transit/keys/signing");
+        assert_eq!(request.header("x-vault-token"), Some("test-token"));
+        (200, key_info.to_string())
+    };
+    let (_, key) = tokio::join!(
+        http_stub::serve(&listener, get_key_info),
+        key(&address).pin_latest_version()
+    );
+    let key = key.unwrap();
+    assert_eq!(key.version(), Some(2));
+
+    let claims = Claims::new(json!({ "sub": "service" }))
+        .set_duration_and_issuance(&TimeOptions::default(), Duration::try_minutes(5).unwrap());
+    let (alg, header) = (VaultTransit::es256(), key.header());
+    let (request, token) = tokio::join!(
+        http_stub::serve(&listener, sign_with(&signing_key, 2)),
+        alg.token_async(&header, &claims, &key)
+    );
+    let token = token.unwrap();
+    assert_eq!(request.method, "POST");
+    assert_eq!(request.path, "/v1/transit/sign/signing");
+    let body: Value = serde_json::from_str(&request.body).unwrap();
+    assert_eq!(body["key_version"], 2);
+
+    let (_, verifying_key) = tokio::join!(
+        http_stub::serve(&listener, |_| (200, key_info.to_string())),
+        key.es256_public_key(2)
+    );
+    let verifying_key = verifying_key.unwrap();
+    assert_eq!(verifying_key, *signing_key.verifying_key());
+
+    let token = UntrustedToken::new(&token).unwrap();
+    assert_eq!(token.algorithm(), "ES256");
+    assert_eq!(token.header().key_id.as_deref(), Some("signing:v2"));
+    let token = Es256
+        .validator::<Value>(&verifying_key)
+        .validate(&token)
+        .unwrap();
+    assert_eq!(token.claims().custom["sub"], "service");
+
+    let (_, err) = tokio::join!(
+        http_stub::serve(&listener, |_| (200, key_info.to_string())),
+        key.public_key(3)
+    );
+    assert_matches!(err.unwrap_err(), VaultTransitError::UnknownVersion(3));
+}
+
+#[tokio::test]
+async fn version_mismatch() {
+    let (listener, address) = http_stub::bind().await;
+    let signing_key = SigningKey::random(&mut thread_rng());
+    let key = key(&address).with_version(1);
+
+    let (alg, header) = (VaultTransit::es256(), key.header());
+    let (_, result) = tokio::join!(
+        http_stub::serve(&listener, sign_with(&signing_key, 2)),
+        alg.token_async(&header, &Claims::empty(), &key)
+    );
+    let err = result.unwrap_err();
+    let CreationError::Signing(err) = err else {
+        panic!("unexpected error: {err:?}");
+    };
+    let err = err.downcast_ref::<VaultTransitError>().unwrap();
+    assert_matches!(
+        err,
+        VaultTransitError::VersionMismatch {
+            expected: 1,
+            actual: 2
+        }
+    );
+}
+
+#[tokio::test]
+async fn vault_error_response() {
+    let (listener, address) = http_stub::bind().await;
+    let key = key(&address)
+        .with_mount("pki-transit")
+        .with_namespace("team");
+    let error = json!({ "errors": ["permission denied"] });
+
+    let (alg, header) = (VaultTransit::eddsa(), Header::empty());
+    let (request, result) = tokio::join!(
+        http_stub::serve(&listener, |_| (403, error.to_string())),
+        alg.token_async(&header, &Claims::empty(), &key)
+    );
+    assert_eq!(request.path, "/v1/pki-transit/sign/signing");
+    assert_eq!(request.header("x-vault-namespace"), Some("team"));
+
+    let err = result.unwrap_err();
+    let CreationError::Signing(err) = err else {
+        panic!("unexpected error: {err:?}");
+    };
+    let err = err.downcast_ref::<VaultTransitError>().unwrap();
+    assert_matches!(
+        err,
+        VaultTransitError::Api { status: 403, message } if message == "permission denied"
+    );
+}